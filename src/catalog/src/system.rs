@@ -22,7 +22,7 @@ use common_catalog::consts::{
 };
 use common_query::logical_plan::Expr;
 use common_query::physical_plan::{PhysicalPlanRef, SessionContext};
-use common_recordbatch::SendableRecordBatchStream;
+use common_recordbatch::{RecordBatches, SendableRecordBatchStream};
 use common_telemetry::debug;
 use common_time::util;
 use datatypes::prelude::{ConcreteDataType, ScalarVector};
@@ -132,10 +132,21 @@ impl SystemCatalogTable {
             .scan(full_projection, &[], None)
             .await
             .context(error::SystemCatalogTableScanSnafu)?;
-        let stream = scan
-            .execute(0, ctx.task_ctx())
-            .context(error::SystemCatalogTableScanExecSnafu)?;
-        Ok(stream)
+        // The scan may have multiple partitions, so we read all of them.
+        let num_partitions = scan.output_partitioning().partition_count();
+        let mut batches = Vec::new();
+        for partition in 0..num_partitions {
+            let stream = scan
+                .execute(partition, ctx.task_ctx())
+                .context(error::SystemCatalogTableScanExecSnafu)?;
+            let records = RecordBatches::try_collect(stream)
+                .await
+                .context(error::ReadSystemCatalogSnafu)?;
+            batches.extend(records.take());
+        }
+        let records = RecordBatches::try_new(scan.schema(), batches)
+            .context(error::ReadSystemCatalogSnafu)?;
+        Ok(records.as_stream())
     }
 }
 
@@ -18,8 +18,8 @@ use api::v1::ddl_request::Expr as DdlExpr;
 use api::v1::greptime_request::Request;
 use api::v1::query_request::Query;
 use api::v1::{
-    AlterExpr, CreateTableExpr, DdlRequest, DropTableExpr, GreptimeRequest, InsertRequest,
-    QueryRequest,
+    AlterExpr, CreateTableExpr, DdlRequest, DeleteRequest, DropTableExpr, GreptimeRequest,
+    InsertRequest, QueryRequest,
 };
 use arrow_flight::{FlightData, Ticket};
 use common_error::prelude::*;
@@ -57,6 +57,13 @@ impl Database {
         .await
     }
 
+    pub async fn delete(&self, request: DeleteRequest) -> Result<Output> {
+        self.do_get(GreptimeRequest {
+            request: Some(Request::Delete(request)),
+        })
+        .await
+    }
+
     pub async fn sql(&self, sql: &str) -> Result<Output> {
         self.do_get(GreptimeRequest {
             request: Some(Request::Query(QueryRequest {
@@ -141,10 +141,13 @@ impl TryFrom<StartCommand> for DatanodeOptions {
 #[cfg(test)]
 mod tests {
     use std::assert_matches::assert_matches;
+    use std::fs::File;
+    use std::io::Write;
     use std::time::Duration;
 
     use datanode::datanode::ObjectStoreConfig;
     use servers::Mode;
+    use tempdir::TempDir;
 
     use super::*;
 
@@ -178,10 +181,83 @@ mod tests {
             ObjectStoreConfig::File { data_dir } => {
                 assert_eq!("/tmp/greptimedb/data/".to_string(), data_dir)
             }
-            ObjectStoreConfig::S3 { .. } => unreachable!(),
+            _ => unreachable!(),
         };
     }
 
+    #[test]
+    fn test_read_object_store_config() {
+        let dir = TempDir::new("test_read_object_store_config").unwrap();
+        let config_file = format!("{}/datanode.toml", dir.path().to_str().unwrap());
+        let mut file = File::create(&config_file).unwrap();
+        file.write_all(
+            br#"
+[storage]
+type = 'Azblob'
+container = 'greptimedb'
+root = 'data'
+account_name = 'devstoreaccount1'
+account_key = 'key'
+endpoint = 'http://127.0.0.1:10000/devstoreaccount1'
+cache_path = '/tmp/greptimedb/cache'
+cache_capacity = '256MB'
+"#,
+        )
+        .unwrap();
+
+        let options: DatanodeOptions = StartCommand {
+            config_file: Some(config_file.clone()),
+            ..Default::default()
+        }
+        .try_into()
+        .unwrap();
+        match &options.storage {
+            ObjectStoreConfig::Azblob {
+                container,
+                root,
+                account_name,
+                account_key,
+                endpoint,
+                ..
+            } => {
+                assert_eq!("greptimedb", container);
+                assert_eq!("data", root);
+                assert_eq!("devstoreaccount1", account_name);
+                assert_eq!("key", account_key);
+                assert_eq!(
+                    Some("http://127.0.0.1:10000/devstoreaccount1"),
+                    endpoint.as_deref()
+                );
+            }
+            _ => unreachable!(),
+        }
+        let (cache_path, cache_capacity) = options.storage.cache_config().unwrap();
+        assert_eq!("/tmp/greptimedb/cache", cache_path);
+        assert_eq!(Some(256 * 1024 * 1024), cache_capacity.map(|s| s.0));
+
+        let mut file = File::create(&config_file).unwrap();
+        file.write_all(
+            br#"
+[storage]
+type = 'Oss'
+bucket = 'greptimedb'
+root = 'data'
+access_key_id = 'id'
+access_key_secret = 'secret'
+endpoint = 'https://oss-cn-hangzhou.aliyuncs.com'
+"#,
+        )
+        .unwrap();
+        let options: DatanodeOptions = StartCommand {
+            config_file: Some(config_file),
+            ..Default::default()
+        }
+        .try_into()
+        .unwrap();
+        assert!(matches!(options.storage, ObjectStoreConfig::Oss { .. }));
+        assert!(options.storage.cache_config().is_none());
+    }
+
     #[test]
     fn test_try_from_cmd() {
         assert_eq!(
@@ -229,6 +305,23 @@ mod tests {
         assert_eq!(1024 * 1024 * 1024, dn_opts.wal.file_size.0);
         assert_eq!(1024 * 1024 * 1024 * 50, dn_opts.wal.purge_threshold.0);
         assert!(!dn_opts.wal.sync_write);
+        assert_eq!(
+            32 * 1024 * 1024,
+            dn_opts.storage_engine.max_write_buffer_size.0
+        );
+        assert_eq!(
+            Some(1024 * 1024 * 1024),
+            dn_opts.storage_engine.global_write_buffer_size.map(|s| s.0)
+        );
+        assert_eq!(8, dn_opts.storage_engine.max_flush_tasks);
+        assert_eq!(
+            Some(Duration::from_secs(3600)),
+            dn_opts.storage_engine.auto_flush_interval
+        );
+        assert_eq!(
+            Some(Duration::from_secs(600)),
+            dn_opts.storage_engine.compaction_check_interval
+        );
         assert_eq!(Some(42), dn_opts.node_id);
         let MetaClientOpts {
             metasrv_addrs: metasrv_addr,
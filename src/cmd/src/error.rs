@@ -68,6 +68,37 @@ pub enum Error {
         #[snafu(backtrace)]
         source: meta_srv::error::Error,
     },
+
+    #[snafu(display("Failed to open log store, source: {}", source))]
+    OpenLogStore {
+        #[snafu(backtrace)]
+        source: log_store::error::Error,
+    },
+
+    #[snafu(display("Failed to init backend in dir: {}, source: {}", dir, source))]
+    InitBackend {
+        dir: String,
+        source: object_store::Error,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Failed to inspect region, source: {}", source))]
+    InspectRegion {
+        #[snafu(backtrace)]
+        source: storage::error::Error,
+    },
+
+    #[snafu(display("Metadata of region {} not found in its manifest", region))]
+    RegionMetadataNotFound {
+        region: String,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Failed to pretty print rows, source: {}", source))]
+    PrettyPrint {
+        #[snafu(backtrace)]
+        source: common_recordbatch::error::Error,
+    },
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -84,6 +115,11 @@ impl ErrorExt for Error {
             }
             Error::IllegalConfig { .. } => StatusCode::InvalidArguments,
             Error::IllegalAuthConfig { .. } => StatusCode::InvalidArguments,
+            Error::OpenLogStore { source } => source.status_code(),
+            Error::InitBackend { .. } => StatusCode::StorageUnavailable,
+            Error::InspectRegion { source } => source.status_code(),
+            Error::RegionMetadataNotFound { .. } => StatusCode::InvalidArguments,
+            Error::PrettyPrint { source } => source.status_code(),
         }
     }
 
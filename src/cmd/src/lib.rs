@@ -20,3 +20,4 @@ pub mod frontend;
 pub mod metasrv;
 pub mod standalone;
 mod toml_loader;
+pub mod tools;
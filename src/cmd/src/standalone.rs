@@ -16,7 +16,9 @@ use std::sync::Arc;
 
 use clap::Parser;
 use common_telemetry::info;
-use datanode::datanode::{Datanode, DatanodeOptions, ObjectStoreConfig, WalConfig};
+use datanode::datanode::{
+    Datanode, DatanodeOptions, ObjectStoreConfig, StorageEngineConfig, WalConfig,
+};
 use datanode::instance::InstanceRef;
 use frontend::frontend::{Frontend, FrontendOptions};
 use frontend::grpc::GrpcOptions;
@@ -75,6 +77,8 @@ pub struct StandaloneOptions {
     pub mode: Mode,
     pub wal: WalConfig,
     pub storage: ObjectStoreConfig,
+    pub cold_storage: Option<ObjectStoreConfig>,
+    pub storage_engine: StorageEngineConfig,
     pub enable_memory_catalog: bool,
 }
 
@@ -91,6 +95,8 @@ impl Default for StandaloneOptions {
             mode: Mode::Standalone,
             wal: WalConfig::default(),
             storage: ObjectStoreConfig::default(),
+            cold_storage: None,
+            storage_engine: StorageEngineConfig::default(),
             enable_memory_catalog: false,
         }
     }
@@ -115,6 +121,8 @@ impl StandaloneOptions {
         DatanodeOptions {
             wal: self.wal,
             storage: self.storage,
+            cold_storage: self.cold_storage,
+            storage_engine: self.storage_engine,
             enable_memory_catalog: self.enable_memory_catalog,
             ..Default::default()
         }
@@ -0,0 +1,249 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Tools to inspect files of a datanode offline.
+
+use std::sync::Arc;
+
+use clap::Parser;
+use common_recordbatch::{RecordBatch, RecordBatches};
+use log_store::raft_engine::log_store::RaftEngineLogStore;
+use log_store::LogConfig;
+use object_store::services::fs::Builder as FsBuilder;
+use object_store::ObjectStore;
+use snafu::{OptionExt, ResultExt};
+use storage::inspect::{self, RegionInspector};
+
+use crate::error::{self, Result};
+
+#[derive(Parser)]
+pub struct Command {
+    #[clap(subcommand)]
+    subcmd: SubCommand,
+}
+
+impl Command {
+    pub async fn run(self) -> Result<()> {
+        self.subcmd.run().await
+    }
+}
+
+#[derive(Parser)]
+enum SubCommand {
+    /// Dumps WAL entries of a region.
+    Wal(WalCommand),
+    /// Lists actions in the manifest of a region.
+    Manifest(ManifestCommand),
+    /// Prints metadata and rows of an SST.
+    Sst(SstCommand),
+}
+
+impl SubCommand {
+    async fn run(self) -> Result<()> {
+        match self {
+            SubCommand::Wal(cmd) => cmd.run().await,
+            SubCommand::Manifest(cmd) => cmd.run().await,
+            SubCommand::Sst(cmd) => cmd.run().await,
+        }
+    }
+}
+
+#[derive(Debug, Parser)]
+struct WalCommand {
+    /// Directory of the WAL.
+    #[clap(long)]
+    wal_dir: String,
+    /// Id of the region, entries of the region are stored in the namespace with the same id.
+    #[clap(long)]
+    region_id: u64,
+    /// Sequence of the first entry to dump.
+    #[clap(long, default_value = "0")]
+    start_seq: u64,
+    /// Also prints rows in entries.
+    #[clap(long)]
+    rows: bool,
+}
+
+impl WalCommand {
+    async fn run(self) -> Result<()> {
+        let config = LogConfig {
+            log_file_dir: self.wal_dir.clone(),
+            ..Default::default()
+        };
+        let log_store =
+            RaftEngineLogStore::open_read_only(config).context(error::OpenLogStoreSnafu)?;
+        let entries = inspect::read_wal(Arc::new(log_store), self.region_id, self.start_seq)
+            .await
+            .context(error::InspectRegionSnafu)?;
+
+        for (sequence, header, payload) in entries {
+            println!("sequence: {sequence}, header: {header:?}");
+            let Some(payload) = payload else {
+                continue;
+            };
+            for mutation in payload.mutations {
+                let num_rows = mutation.record_batch.num_rows();
+                println!("  {:?}, rows: {}", mutation.op_type, num_rows);
+                if self.rows {
+                    println!("{}", pretty_print(vec![mutation.record_batch])?);
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Location of a region in the data directory.
+#[derive(Debug, Parser)]
+struct RegionArgs {
+    /// Data directory of the datanode.
+    #[clap(long)]
+    data_dir: String,
+    /// Parent directory of the region, relative to the data directory.
+    #[clap(long, default_value = "")]
+    parent_dir: String,
+    /// Name of the region.
+    #[clap(long)]
+    region: String,
+}
+
+impl RegionArgs {
+    fn inspector(&self) -> Result<RegionInspector> {
+        let accessor =
+            FsBuilder::default()
+                .root(&self.data_dir)
+                .build()
+                .context(error::InitBackendSnafu {
+                    dir: &self.data_dir,
+                })?;
+        let object_store = ObjectStore::new(accessor);
+
+        Ok(RegionInspector::new(
+            &self.parent_dir,
+            &self.region,
+            object_store,
+        ))
+    }
+}
+
+#[derive(Debug, Parser)]
+struct ManifestCommand {
+    #[clap(flatten)]
+    region: RegionArgs,
+}
+
+impl ManifestCommand {
+    async fn run(self) -> Result<()> {
+        let inspector = self.region.inspector()?;
+        let actions = inspector
+            .manifest_actions()
+            .await
+            .context(error::InspectRegionSnafu)?;
+
+        for (version, action_list) in actions {
+            println!(
+                "version: {version}, prev_version: {}",
+                action_list.prev_version
+            );
+            for action in action_list.actions {
+                println!("  {action:?}");
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Parser)]
+struct SstCommand {
+    #[clap(flatten)]
+    region: RegionArgs,
+    /// Name of the SST file.
+    #[clap(long)]
+    file: String,
+    /// Also prints rows in the file, using the schema in the manifest of the region.
+    #[clap(long)]
+    rows: bool,
+}
+
+impl SstCommand {
+    async fn run(self) -> Result<()> {
+        let inspector = self.region.inspector()?;
+        let meta = inspector
+            .sst_meta(&self.file)
+            .await
+            .context(error::InspectRegionSnafu)?;
+        println!("file: {}", self.file);
+        println!("num rows: {}", meta.num_rows);
+        println!("rows of row groups: {:?}", meta.row_group_rows);
+        println!("created by: {:?}", meta.created_by);
+        println!("metadata keys: {:?}", meta.metadata_keys);
+        println!("has bloom filters: {}", meta.has_bloom_filters);
+        println!("schema: {:?}", meta.store_schema.schema());
+
+        if !self.rows {
+            return Ok(());
+        }
+        let metadata = inspector
+            .metadata()
+            .await
+            .context(error::InspectRegionSnafu)?
+            .context(error::RegionMetadataNotFoundSnafu {
+                region: &self.region.region,
+            })?;
+        let batches = inspector
+            .read_sst(&self.file, &metadata)
+            .await
+            .context(error::InspectRegionSnafu)?;
+        if !batches.is_empty() {
+            println!("{}", pretty_print(batches)?);
+        }
+
+        Ok(())
+    }
+}
+
+fn pretty_print(batches: Vec<RecordBatch>) -> Result<String> {
+    let schema = batches[0].schema.clone();
+    RecordBatches::try_new(schema, batches)
+        .and_then(|batches| batches.pretty_print())
+        .context(error::PrettyPrintSnafu)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_sst_command() {
+        let cmd = SstCommand::parse_from([
+            "sst",
+            "--data-dir",
+            "/tmp/greptimedb/data",
+            "--parent-dir",
+            "greptime/public/1024",
+            "--region",
+            "1024_0000000000",
+            "--file",
+            "test.parquet",
+            "--rows",
+        ]);
+        assert_eq!("/tmp/greptimedb/data", cmd.region.data_dir);
+        assert_eq!("greptime/public/1024", cmd.region.parent_dir);
+        assert_eq!("1024_0000000000", cmd.region.region);
+        assert_eq!("test.parquet", cmd.file);
+        assert!(cmd.rows);
+    }
+}
@@ -0,0 +1,131 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+
+use api::helper::ColumnDataTypeWrapper;
+use api::v1::{Column, DeleteRequest as GrpcDeleteRequest};
+use datatypes::data_type::{ConcreteDataType, DataType};
+use snafu::{ensure, ResultExt};
+use table::requests::DeleteRequest;
+
+use crate::error::{ColumnDataTypeSnafu, IllegalDeleteRequestSnafu, Result};
+use crate::insert::add_values_to_builder;
+
+pub fn to_table_delete_request(request: GrpcDeleteRequest) -> Result<DeleteRequest> {
+    let row_count = request.row_count as usize;
+
+    let mut key_column_values = HashMap::with_capacity(request.key_columns.len());
+    for Column {
+        column_name,
+        values,
+        null_mask,
+        datatype,
+        ..
+    } in request.key_columns
+    {
+        let Some(values) = values else { continue };
+
+        let datatype: ConcreteDataType = ColumnDataTypeWrapper::try_new(datatype)
+            .context(ColumnDataTypeSnafu)?
+            .into();
+
+        let vector_builder = &mut datatype.create_mutable_vector(row_count);
+
+        add_values_to_builder(vector_builder, values, row_count, null_mask)?;
+
+        ensure!(
+            key_column_values
+                .insert(column_name.clone(), vector_builder.to_vector())
+                .is_none(),
+            IllegalDeleteRequestSnafu {
+                reason: format!("Duplicated key column: {column_name}"),
+            }
+        );
+    }
+
+    Ok(DeleteRequest { key_column_values })
+}
+
+#[cfg(test)]
+mod tests {
+    use api::v1::column::{SemanticType, Values};
+    use api::v1::ColumnDataType;
+    use common_time::timestamp::Timestamp;
+    use datatypes::value::Value;
+
+    use super::*;
+
+    fn mock_key_columns() -> Vec<Column> {
+        let host_column = Column {
+            column_name: "host".to_string(),
+            semantic_type: SemanticType::Tag as i32,
+            values: Some(Values {
+                string_values: vec!["host1".to_string(), "host2".to_string()],
+                ..Default::default()
+            }),
+            null_mask: vec![0],
+            datatype: ColumnDataType::String as i32,
+        };
+
+        let ts_column = Column {
+            column_name: "ts".to_string(),
+            semantic_type: SemanticType::Timestamp as i32,
+            values: Some(Values {
+                ts_millisecond_values: vec![100, 101],
+                ..Default::default()
+            }),
+            null_mask: vec![0],
+            datatype: ColumnDataType::TimestampMillisecond as i32,
+        };
+
+        vec![host_column, ts_column]
+    }
+
+    #[test]
+    fn test_to_table_delete_request() {
+        let request = GrpcDeleteRequest {
+            schema_name: "public".to_string(),
+            table_name: "demo".to_string(),
+            key_columns: mock_key_columns(),
+            row_count: 2,
+            region_number: 0,
+        };
+        let delete_req = to_table_delete_request(request).unwrap();
+
+        assert_eq!(2, delete_req.key_column_values.len());
+
+        let host = delete_req.key_column_values.get("host").unwrap();
+        assert_eq!(Value::String("host1".into()), host.get(0));
+        assert_eq!(Value::String("host2".into()), host.get(1));
+
+        let ts = delete_req.key_column_values.get("ts").unwrap();
+        assert_eq!(Value::Timestamp(Timestamp::new_millisecond(100)), ts.get(0));
+        assert_eq!(Value::Timestamp(Timestamp::new_millisecond(101)), ts.get(1));
+    }
+
+    #[test]
+    fn test_to_table_delete_request_duplicated_column() {
+        let mut key_columns = mock_key_columns();
+        key_columns.push(key_columns[0].clone());
+        let request = GrpcDeleteRequest {
+            schema_name: "public".to_string(),
+            table_name: "demo".to_string(),
+            key_columns,
+            row_count: 2,
+            region_number: 0,
+        };
+        assert!(to_table_delete_request(request).is_err());
+    }
+}
@@ -34,6 +34,12 @@ pub enum Error {
     #[snafu(display("Illegal insert data"))]
     IllegalInsertData,
 
+    #[snafu(display("Illegal delete request, reason: {}", reason))]
+    IllegalDeleteRequest {
+        reason: String,
+        backtrace: Backtrace,
+    },
+
     #[snafu(display("Column datatype error, source: {}", source))]
     ColumnDataType {
         #[snafu(backtrace)]
@@ -98,9 +104,9 @@ impl ErrorExt for Error {
     fn status_code(&self) -> StatusCode {
         match self {
             Error::ColumnNotFound { .. } => StatusCode::TableColumnNotFound,
-            Error::DecodeInsert { .. } | Error::IllegalInsertData { .. } => {
-                StatusCode::InvalidArguments
-            }
+            Error::DecodeInsert { .. }
+            | Error::IllegalInsertData { .. }
+            | Error::IllegalDeleteRequest { .. } => StatusCode::InvalidArguments,
             Error::ColumnDataType { .. } => StatusCode::Internal,
             Error::CreateSchema { .. }
             | Error::DuplicatedTimestampColumn { .. }
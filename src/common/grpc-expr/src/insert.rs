@@ -322,7 +322,7 @@ pub fn to_table_insert_request(request: GrpcInsertRequest) -> Result<InsertReque
     })
 }
 
-fn add_values_to_builder(
+pub(crate) fn add_values_to_builder(
     builder: &mut Box<dyn MutableVector>,
     values: Values,
     row_count: usize,
@@ -111,6 +111,20 @@ pub enum Error {
     #[snafu(display("Not expected to run ExecutionPlan more than once"))]
     ExecuteRepeatedly { backtrace: Backtrace },
 
+    #[snafu(display(
+        "Partition {} of ExecutionPlan is out of range, num partitions: {}",
+        partition,
+        num_partitions
+    ))]
+    PartitionOutOfRange {
+        partition: usize,
+        num_partitions: usize,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("ExecutionPlan requires at least one partition"))]
+    EmptyPartitions { backtrace: Backtrace },
+
     #[snafu(display("General DataFusion error, source: {}", source))]
     GeneralDataFusion {
         source: DataFusionError,
@@ -204,6 +218,8 @@ impl ErrorExt for Error {
             | Error::FromArrowArray { source } => source.status_code(),
 
             Error::ExecuteRepeatedly { .. }
+            | Error::PartitionOutOfRange { .. }
+            | Error::EmptyPartitions { .. }
             | Error::GeneralDataFusion { .. }
             | Error::DataFusionExecutionPlan { .. } => StatusCode::Unexpected,
 
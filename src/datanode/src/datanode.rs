@@ -20,11 +20,17 @@ use common_telemetry::info;
 use meta_client::MetaClientOpts;
 use serde::{Deserialize, Serialize};
 use servers::Mode;
+pub use storage::config::EngineConfig as StorageEngineConfig;
 
 use crate::error::Result;
 use crate::instance::{Instance, InstanceRef};
 use crate::server::Services;
 
+/// Default max size of the local cache of the remote object store (1G).
+pub const DEFAULT_OBJECT_STORE_CACHE_SIZE: ReadableSize = ReadableSize::gb(1);
+/// Default OAuth scope to access GCS.
+pub const DEFAULT_GCS_SCOPE: &str = "https://www.googleapis.com/auth/devstorage.read_write";
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type")]
 pub enum ObjectStoreConfig {
@@ -38,9 +44,74 @@ pub enum ObjectStoreConfig {
         secret_access_key: String,
         endpoint: Option<String>,
         region: Option<String>,
+        /// Local directory to cache data read from S3, no cache if not set. Cached
+        /// files are kept in its `object_cache` subdirectory, which is cleared on startup.
+        cache_path: Option<String>,
+        /// Max size of the cache, defaults to [DEFAULT_OBJECT_STORE_CACHE_SIZE].
+        cache_capacity: Option<ReadableSize>,
+    },
+    Azblob {
+        container: String,
+        root: String,
+        account_name: String,
+        account_key: String,
+        /// Defaults to `https://{account_name}.blob.core.windows.net`.
+        endpoint: Option<String>,
+        cache_path: Option<String>,
+        cache_capacity: Option<ReadableSize>,
+    },
+    Gcs {
+        bucket: String,
+        root: String,
+        /// Base64 encoded content of the service account credential.
+        credential: String,
+        /// Defaults to [DEFAULT_GCS_SCOPE].
+        scope: Option<String>,
+        endpoint: Option<String>,
+        cache_path: Option<String>,
+        cache_capacity: Option<ReadableSize>,
+    },
+    Oss {
+        bucket: String,
+        root: String,
+        access_key_id: String,
+        access_key_secret: String,
+        endpoint: String,
+        cache_path: Option<String>,
+        cache_capacity: Option<ReadableSize>,
     },
 }
 
+impl ObjectStoreConfig {
+    /// Returns the local cache path and capacity of the remote object store, `None`
+    /// if the cache is not enabled.
+    pub fn cache_config(&self) -> Option<(&str, Option<ReadableSize>)> {
+        match self {
+            ObjectStoreConfig::File { .. } => None,
+            ObjectStoreConfig::S3 {
+                cache_path,
+                cache_capacity,
+                ..
+            }
+            | ObjectStoreConfig::Azblob {
+                cache_path,
+                cache_capacity,
+                ..
+            }
+            | ObjectStoreConfig::Gcs {
+                cache_path,
+                cache_capacity,
+                ..
+            }
+            | ObjectStoreConfig::Oss {
+                cache_path,
+                cache_capacity,
+                ..
+            } => cache_path.as_deref().map(|path| (path, *cache_capacity)),
+        }
+    }
+}
+
 impl Default for ObjectStoreConfig {
     fn default() -> Self {
         ObjectStoreConfig::File {
@@ -49,8 +120,21 @@ impl Default for ObjectStoreConfig {
     }
 }
 
+/// Where the WAL of regions is stored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WalProvider {
+    /// Local raft-engine log files under `dir`.
+    RaftEngine,
+    /// Topics of a Kafka cluster, so the WAL outlives the datanode.
+    Kafka,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct WalConfig {
+    // where to store the wal
+    pub provider: WalProvider,
     // wal directory
     pub dir: String,
     // wal file size in bytes
@@ -64,17 +148,24 @@ pub struct WalConfig {
     pub read_batch_size: usize,
     // whether to sync log file after every write
     pub sync_write: bool,
+    // bootstrap brokers of the kafka cluster, only for the kafka provider
+    pub broker_endpoints: Vec<String>,
+    // prefix of the kafka topics, only for the kafka provider
+    pub topic_prefix: String,
 }
 
 impl Default for WalConfig {
     fn default() -> Self {
         Self {
+            provider: WalProvider::RaftEngine,
             dir: "/tmp/greptimedb/wal".to_string(),
             file_size: ReadableSize::gb(1),        // log file size 1G
             purge_threshold: ReadableSize::gb(50), // purge threshold 50G
             purge_interval: Duration::from_secs(600),
             read_batch_size: 128,
             sync_write: false,
+            broker_endpoints: Vec::new(),
+            topic_prefix: "greptimedb_wal_".to_string(),
         }
     }
 }
@@ -91,6 +182,9 @@ pub struct DatanodeOptions {
     pub meta_client_opts: Option<MetaClientOpts>,
     pub wal: WalConfig,
     pub storage: ObjectStoreConfig,
+    /// Remote storage that SSTs older than `storage_engine.cold_sst_age` are moved to.
+    pub cold_storage: Option<ObjectStoreConfig>,
+    pub storage_engine: StorageEngineConfig,
     pub enable_memory_catalog: bool,
     pub mode: Mode,
 }
@@ -107,6 +201,8 @@ impl Default for DatanodeOptions {
             meta_client_opts: None,
             wal: WalConfig::default(),
             storage: ObjectStoreConfig::default(),
+            cold_storage: None,
+            storage_engine: StorageEngineConfig::default(),
             enable_memory_catalog: false,
             mode: Mode::Standalone,
         }
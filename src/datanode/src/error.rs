@@ -79,6 +79,30 @@ pub enum Error {
         source: BoxedError,
     },
 
+    #[snafu(display("Failed to flush table {}, source: {}", table_name, source))]
+    FlushTable {
+        table_name: String,
+        #[snafu(backtrace)]
+        source: TableError,
+    },
+
+    #[snafu(display("Failed to export table {}, source: {}", table_name, source))]
+    ExportTable {
+        table_name: String,
+        #[snafu(backtrace)]
+        source: mito::error::Error,
+    },
+
+    #[snafu(display("Failed to restore table {}, source: {}", table_name, source))]
+    RestoreTable {
+        table_name: String,
+        #[snafu(backtrace)]
+        source: mito::error::Error,
+    },
+
+    #[snafu(display("Table engine does not support exporting and restoring tables"))]
+    TableBackupNotSupported { backtrace: Backtrace },
+
     #[snafu(display("Table not found: {}", table_name))]
     TableNotFound { table_name: String },
 
@@ -111,6 +135,17 @@ pub enum Error {
         source: TableError,
     },
 
+    #[snafu(display(
+        "Failed to delete value from table: {}, source: {}",
+        table_name,
+        source
+    ))]
+    Delete {
+        table_name: String,
+        #[snafu(backtrace)]
+        source: TableError,
+    },
+
     #[snafu(display("Failed to start server, source: {}", source))]
     StartServer {
         #[snafu(backtrace)]
@@ -135,6 +170,9 @@ pub enum Error {
     #[snafu(display("Failed to create directory {}, source: {}", dir, source))]
     CreateDir { dir: String, source: std::io::Error },
 
+    #[snafu(display("Failed to remove directory {}, source: {}", dir, source))]
+    RemoveDir { dir: String, source: std::io::Error },
+
     #[snafu(display("Failed to open log store, source: {}", source))]
     OpenLogStore {
         #[snafu(backtrace)]
@@ -267,6 +305,12 @@ pub enum Error {
         source: common_grpc_expr::error::Error,
     },
 
+    #[snafu(display("Failed to delete data, source: {}", source))]
+    DeleteData {
+        #[snafu(backtrace)]
+        source: common_grpc_expr::error::Error,
+    },
+
     #[snafu(display(
         "Table id provider not found, cannot execute SQL directly on datanode in distributed mode"
     ))]
@@ -290,6 +334,9 @@ pub enum Error {
     #[snafu(display("Missing node id option in distributed mode"))]
     MissingMetasrvOpts { backtrace: Backtrace },
 
+    #[snafu(display("Missing broker endpoints option for the kafka WAL"))]
+    MissingWalBrokerEndpoints { backtrace: Backtrace },
+
     #[snafu(display("Missing required field: {}", name))]
     MissingRequiredField { name: String, backtrace: Backtrace },
 
@@ -330,8 +377,13 @@ impl ErrorExt for Error {
             | Error::GetTable { source, .. }
             | Error::AlterTable { source, .. } => source.status_code(),
             Error::DropTable { source, .. } => source.status_code(),
+            Error::FlushTable { source, .. } => source.status_code(),
+            Error::ExportTable { source, .. } | Error::RestoreTable { source, .. } => {
+                source.status_code()
+            }
+            Error::TableBackupNotSupported { .. } => StatusCode::Unsupported,
 
-            Error::Insert { source, .. } => source.status_code(),
+            Error::Insert { source, .. } | Error::Delete { source, .. } => source.status_code(),
 
             Error::TableNotFound { .. } => StatusCode::TableNotFound,
             Error::ColumnNotFound { .. } => StatusCode::TableColumnNotFound,
@@ -342,7 +394,8 @@ impl ErrorExt for Error {
 
             Error::AlterExprToRequest { source, .. }
             | Error::CreateExprToRequest { source }
-            | Error::InsertData { source } => source.status_code(),
+            | Error::InsertData { source }
+            | Error::DeleteData { source } => source.status_code(),
 
             Error::CreateSchema { source, .. }
             | Error::ConvertSchema { source, .. }
@@ -366,6 +419,7 @@ impl ErrorExt for Error {
             | Error::TcpBind { .. }
             | Error::StartGrpc { .. }
             | Error::CreateDir { .. }
+            | Error::RemoveDir { .. }
             | Error::InsertSystemCatalog { .. }
             | Error::RenameTable { .. }
             | Error::RegisterSchema { .. }
@@ -383,6 +437,7 @@ impl ErrorExt for Error {
             Error::BumpTableId { source, .. } => source.status_code(),
             Error::MissingNodeId { .. } => StatusCode::InvalidArguments,
             Error::MissingMetasrvOpts { .. } => StatusCode::InvalidArguments,
+            Error::MissingWalBrokerEndpoints { .. } => StatusCode::InvalidArguments,
             Error::ColumnDefaultValue { source, .. } => source.status_code(),
             Error::ColumnNoneDefaultValue { .. } => StatusCode::InvalidArguments,
         }
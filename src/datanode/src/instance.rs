@@ -19,32 +19,42 @@ use std::{fs, path};
 use backon::ExponentialBackoff;
 use catalog::remote::MetaKvBackend;
 use catalog::{CatalogManager, CatalogManagerRef, RegisterTableRequest};
+use common_base::readable_size::ReadableSize;
 use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME, MIN_USER_TABLE_ID};
 use common_grpc::channel_manager::{ChannelConfig, ChannelManager};
 use common_telemetry::logging::info;
 use log_store::raft_engine::log_store::RaftEngineLogStore;
+use log_store::remote::{KafkaLogClient, RemoteLogConfig, RemoteLogStore};
 use log_store::LogConfig;
 use meta_client::client::{MetaClient, MetaClientBuilder};
 use meta_client::MetaClientOpts;
 use mito::config::EngineConfig as TableEngineConfig;
-use mito::engine::MitoEngine;
+use mito::engine::{MitoEngine, TableBackupRef};
+use object_store::cache::LruCacheLayer;
 use object_store::layers::{LoggingLayer, MetricsLayer, RetryLayer, TracingLayer};
+use object_store::services::azblob::Builder as AzblobBuilder;
 use object_store::services::fs::Builder as FsBuilder;
+use object_store::services::gcs::Builder as GcsBuilder;
+use object_store::services::oss::Builder as OssBuilder;
 use object_store::services::s3::Builder as S3Builder;
 use object_store::{util, ObjectStore};
 use query::query_engine::{QueryEngineFactory, QueryEngineRef};
 use servers::Mode;
 use snafu::prelude::*;
-use storage::config::EngineConfig as StorageEngineConfig;
 use storage::EngineImpl;
+use store_api::logstore::LogStore;
+use table::engine::TableEngineRef;
 use table::table::numbers::NumbersTable;
 use table::table::TableIdProviderRef;
 use table::Table;
 
-use crate::datanode::{DatanodeOptions, ObjectStoreConfig, WalConfig};
+use crate::datanode::{
+    DatanodeOptions, ObjectStoreConfig, StorageEngineConfig, WalConfig, WalProvider,
+    DEFAULT_GCS_SCOPE, DEFAULT_OBJECT_STORE_CACHE_SIZE,
+};
 use crate::error::{
     self, CatalogSnafu, MetaClientInitSnafu, MissingMetasrvOptsSnafu, MissingNodeIdSnafu,
-    NewCatalogSnafu, OpenLogStoreSnafu, Result,
+    MissingWalBrokerEndpointsSnafu, NewCatalogSnafu, OpenLogStoreSnafu, Result,
 };
 use crate::heartbeat::HeartbeatTask;
 use crate::script::ScriptExecutor;
@@ -52,9 +62,7 @@ use crate::sql::SqlHandler;
 
 mod grpc;
 mod script;
-mod sql;
-
-pub(crate) type DefaultEngine = MitoEngine<EngineImpl<RaftEngineLogStore>>;
+pub(crate) mod sql;
 
 // An abstraction to read/write services.
 pub struct Instance {
@@ -71,7 +79,10 @@ pub type InstanceRef = Arc<Instance>;
 impl Instance {
     pub async fn new(opts: &DatanodeOptions) -> Result<Self> {
         let object_store = new_object_store(&opts.storage).await?;
-        let logstore = Arc::new(create_log_store(&opts.wal).await?);
+        let cold_store = match &opts.cold_storage {
+            Some(store_config) => Some(new_object_store(store_config).await?),
+            None => None,
+        };
 
         let meta_client = match opts.mode {
             Mode::Standalone => None,
@@ -87,15 +98,13 @@ impl Instance {
             }
         };
 
-        let table_engine = Arc::new(DefaultEngine::new(
-            TableEngineConfig::default(),
-            EngineImpl::new(
-                StorageEngineConfig::default(),
-                logstore.clone(),
-                object_store.clone(),
-            ),
+        let (table_engine, table_backup) = create_table_engine(
+            &opts.wal,
+            opts.storage_engine.clone(),
             object_store,
-        ));
+            cold_store,
+        )
+        .await?;
 
         // create remote catalog manager
         let (catalog_manager, factory, table_id_provider) = match opts.mode {
@@ -171,6 +180,7 @@ impl Instance {
                 table_engine,
                 catalog_manager.clone(),
                 query_engine.clone(),
+                Some(table_backup),
             ),
             catalog_manager,
             script_executor,
@@ -203,15 +213,49 @@ pub(crate) async fn new_object_store(store_config: &ObjectStoreConfig) -> Result
     let object_store = match store_config {
         ObjectStoreConfig::File { data_dir } => new_fs_object_store(data_dir).await,
         ObjectStoreConfig::S3 { .. } => new_s3_object_store(store_config).await,
-    };
+        ObjectStoreConfig::Azblob { .. } => new_azblob_object_store(store_config).await,
+        ObjectStoreConfig::Gcs { .. } => new_gcs_object_store(store_config).await,
+        ObjectStoreConfig::Oss { .. } => new_oss_object_store(store_config).await,
+    }?;
+
+    let object_store = object_store
+        .layer(RetryLayer::new(ExponentialBackoff::default().with_jitter()))
+        .layer(MetricsLayer)
+        .layer(LoggingLayer::default())
+        .layer(TracingLayer);
+
+    match store_config.cache_config() {
+        Some((cache_path, cache_capacity)) => {
+            let capacity = cache_capacity.unwrap_or(DEFAULT_OBJECT_STORE_CACHE_SIZE);
+            let cache_layer = new_lru_cache_layer(cache_path, capacity).await?;
+            Ok(object_store.layer(cache_layer))
+        }
+        None => Ok(object_store),
+    }
+}
 
-    object_store.map(|object_store| {
-        object_store
-            .layer(RetryLayer::new(ExponentialBackoff::default().with_jitter()))
-            .layer(MetricsLayer)
-            .layer(LoggingLayer::default())
-            .layer(TracingLayer)
-    })
+/// Name of the subdirectory under the configured cache path that holds the cached
+/// files, the cache only touches files in this subdirectory.
+const OBJECT_CACHE_DIR: &str = "object_cache";
+
+/// Creates a layer that caches data of the object store under the [OBJECT_CACHE_DIR]
+/// of `cache_path`, files left there by previous runs are removed.
+async fn new_lru_cache_layer(cache_path: &str, capacity: ReadableSize) -> Result<LruCacheLayer> {
+    let cache_path = util::normalize_dir(&format!(
+        "{}{}",
+        util::normalize_dir(cache_path),
+        OBJECT_CACHE_DIR
+    ));
+    if path::Path::new(&cache_path).exists() {
+        fs::remove_dir_all(&cache_path).context(error::RemoveDirSnafu { dir: &cache_path })?;
+    }
+    info!(
+        "The object store cache directory is: {}, capacity: {}",
+        cache_path, capacity
+    );
+
+    let cache_store = new_fs_object_store(&cache_path).await?;
+    Ok(LruCacheLayer::new(cache_store, capacity.0))
 }
 
 pub(crate) async fn new_s3_object_store(store_config: &ObjectStoreConfig) -> Result<ObjectStore> {
@@ -223,6 +267,7 @@ pub(crate) async fn new_s3_object_store(store_config: &ObjectStoreConfig) -> Res
             secret_access_key,
             endpoint,
             region,
+            ..
         } => (
             root,
             secret_access_key,
@@ -258,6 +303,108 @@ pub(crate) async fn new_s3_object_store(store_config: &ObjectStoreConfig) -> Res
     Ok(ObjectStore::new(accessor))
 }
 
+pub(crate) async fn new_azblob_object_store(
+    store_config: &ObjectStoreConfig,
+) -> Result<ObjectStore> {
+    let (container, root, account_name, account_key, endpoint) = match store_config {
+        ObjectStoreConfig::Azblob {
+            container,
+            root,
+            account_name,
+            account_key,
+            endpoint,
+            ..
+        } => (container, root, account_name, account_key, endpoint),
+        _ => unreachable!(),
+    };
+
+    let root = util::normalize_dir(root);
+    info!(
+        "The azblob storage container is: {}, root is: {}",
+        container, &root
+    );
+
+    let endpoint = endpoint
+        .clone()
+        .unwrap_or_else(|| format!("https://{account_name}.blob.core.windows.net"));
+    let accessor = AzblobBuilder::default()
+        .root(&root)
+        .container(container)
+        .endpoint(&endpoint)
+        .account_name(account_name)
+        .account_key(account_key)
+        .build()
+        .with_context(|_| error::InitBackendSnafu {
+            config: store_config.clone(),
+        })?;
+
+    Ok(ObjectStore::new(accessor))
+}
+
+pub(crate) async fn new_gcs_object_store(store_config: &ObjectStoreConfig) -> Result<ObjectStore> {
+    let (bucket, root, credential, scope, endpoint) = match store_config {
+        ObjectStoreConfig::Gcs {
+            bucket,
+            root,
+            credential,
+            scope,
+            endpoint,
+            ..
+        } => (bucket, root, credential, scope, endpoint),
+        _ => unreachable!(),
+    };
+
+    let root = util::normalize_dir(root);
+    info!("The gcs storage bucket is: {}, root is: {}", bucket, &root);
+
+    let mut builder = GcsBuilder::default();
+    let mut builder = builder
+        .root(&root)
+        .bucket(bucket)
+        .credential(credential)
+        .scope(scope.as_deref().unwrap_or(DEFAULT_GCS_SCOPE));
+
+    if let Some(endpoint) = endpoint {
+        builder = builder.endpoint(endpoint);
+    }
+
+    let accessor = builder.build().with_context(|_| error::InitBackendSnafu {
+        config: store_config.clone(),
+    })?;
+
+    Ok(ObjectStore::new(accessor))
+}
+
+pub(crate) async fn new_oss_object_store(store_config: &ObjectStoreConfig) -> Result<ObjectStore> {
+    let (bucket, root, access_key_id, access_key_secret, endpoint) = match store_config {
+        ObjectStoreConfig::Oss {
+            bucket,
+            root,
+            access_key_id,
+            access_key_secret,
+            endpoint,
+            ..
+        } => (bucket, root, access_key_id, access_key_secret, endpoint),
+        _ => unreachable!(),
+    };
+
+    let root = util::normalize_dir(root);
+    info!("The oss storage bucket is: {}, root is: {}", bucket, &root);
+
+    let accessor = OssBuilder::default()
+        .root(&root)
+        .bucket(bucket)
+        .endpoint(endpoint)
+        .access_key_id(access_key_id)
+        .access_key_secret(access_key_secret)
+        .build()
+        .with_context(|_| error::InitBackendSnafu {
+            config: store_config.clone(),
+        })?;
+
+    Ok(ObjectStore::new(accessor))
+}
+
 pub(crate) async fn new_fs_object_store(data_dir: &str) -> Result<ObjectStore> {
     let data_dir = util::normalize_dir(data_dir);
     fs::create_dir_all(path::Path::new(&data_dir))
@@ -306,6 +453,57 @@ async fn new_metasrv_client(node_id: u64, meta_config: &MetaClientOpts) -> Resul
     Ok(meta_client)
 }
 
+/// Creates the table engine whose regions write their WAL to the log store selected
+/// by `wal_config.provider`, and the backup of the engine.
+pub(crate) async fn create_table_engine(
+    wal_config: &WalConfig,
+    storage_config: StorageEngineConfig,
+    object_store: ObjectStore,
+    cold_store: Option<ObjectStore>,
+) -> Result<(TableEngineRef, TableBackupRef)> {
+    let table_engine = match wal_config.provider {
+        WalProvider::RaftEngine => {
+            let log_store = Arc::new(create_log_store(wal_config).await?);
+            new_table_engine(log_store, storage_config, object_store, cold_store)
+        }
+        WalProvider::Kafka => {
+            let log_store = Arc::new(create_remote_log_store(wal_config).await?);
+            new_table_engine(log_store, storage_config, object_store, cold_store)
+        }
+    };
+    Ok(table_engine)
+}
+
+fn new_table_engine<S: LogStore>(
+    log_store: Arc<S>,
+    storage_config: StorageEngineConfig,
+    object_store: ObjectStore,
+    cold_store: Option<ObjectStore>,
+) -> (TableEngineRef, TableBackupRef) {
+    let engine = Arc::new(MitoEngine::new(
+        TableEngineConfig::default(),
+        EngineImpl::with_cold_store(storage_config, log_store, object_store.clone(), cold_store),
+        object_store,
+    ));
+    (engine.clone(), engine)
+}
+
+async fn create_remote_log_store(wal_config: &WalConfig) -> Result<RemoteLogStore> {
+    ensure!(
+        !wal_config.broker_endpoints.is_empty(),
+        MissingWalBrokerEndpointsSnafu
+    );
+    info!("Creating remote logstore with config: {:?}", wal_config);
+    let client = KafkaLogClient::connect(wal_config.broker_endpoints.clone())
+        .await
+        .context(OpenLogStoreSnafu)?;
+    let config = RemoteLogConfig {
+        topic_prefix: wal_config.topic_prefix.clone(),
+        read_batch_size: wal_config.read_batch_size,
+    };
+    Ok(RemoteLogStore::new(config, Arc::new(client)))
+}
+
 pub(crate) async fn create_log_store(wal_config: &WalConfig) -> Result<RaftEngineLogStore> {
     // create WAL directory
     fs::create_dir_all(path::Path::new(&wal_config.dir)).context(error::CreateDirSnafu {
@@ -326,3 +524,28 @@ pub(crate) async fn create_log_store(wal_config: &WalConfig) -> Result<RaftEngin
         .context(OpenLogStoreSnafu)?;
     Ok(logstore)
 }
+
+#[cfg(test)]
+mod tests {
+    use tempdir::TempDir;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_lru_cache_layer_keeps_other_files() {
+        let dir = TempDir::new("test_lru_cache_layer_keeps_other_files").unwrap();
+        let user_file = dir.path().join("user_data");
+        fs::write(&user_file, b"hello").unwrap();
+        let stale_file = dir.path().join(OBJECT_CACHE_DIR).join("stale.cache-0_4");
+        fs::create_dir_all(stale_file.parent().unwrap()).unwrap();
+        fs::write(&stale_file, b"stale").unwrap();
+
+        let _layer = new_lru_cache_layer(dir.path().to_str().unwrap(), ReadableSize::mb(1))
+            .await
+            .unwrap();
+
+        // Only files in the cache subdirectory are removed.
+        assert!(user_file.exists());
+        assert!(!stale_file.exists());
+    }
+}
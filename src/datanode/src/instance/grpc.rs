@@ -15,7 +15,7 @@
 use api::v1::ddl_request::Expr as DdlExpr;
 use api::v1::greptime_request::Request as GrpcRequest;
 use api::v1::query_request::Query;
-use api::v1::{CreateDatabaseExpr, DdlRequest, GreptimeRequest, InsertRequest};
+use api::v1::{CreateDatabaseExpr, DdlRequest, DeleteRequest, GreptimeRequest, InsertRequest};
 use async_trait::async_trait;
 use common_catalog::consts::DEFAULT_CATALOG_NAME;
 use common_query::Output;
@@ -79,6 +79,24 @@ impl Instance {
         Ok(Output::AffectedRows(affected_rows))
     }
 
+    pub async fn handle_delete(&self, request: DeleteRequest) -> Result<Output> {
+        let table_name = &request.table_name.clone();
+        let table = self
+            .catalog_manager
+            .table(DEFAULT_CATALOG_NAME, &request.schema_name, table_name)
+            .context(error::CatalogSnafu)?
+            .context(error::TableNotFoundSnafu { table_name })?;
+
+        let request = common_grpc_expr::delete::to_table_delete_request(request)
+            .context(error::DeleteDataSnafu)?;
+
+        let affected_rows = table
+            .delete(request)
+            .await
+            .context(error::DeleteSnafu { table_name })?;
+        Ok(Output::AffectedRows(affected_rows))
+    }
+
     async fn handle_ddl(&self, request: DdlRequest) -> Result<Output> {
         let expr = request.expr.context(error::MissingRequiredFieldSnafu {
             name: "DdlRequest.expr",
@@ -111,6 +129,7 @@ impl GrpcQueryHandler for Instance {
                 self.handle_query(query).await
             }
             GrpcRequest::Ddl(request) => self.handle_ddl(request).await,
+            GrpcRequest::Delete(request) => self.handle_delete(request).await,
         }
     }
 }
@@ -297,6 +316,81 @@ mod test {
         assert_eq!(recordbatches.pretty_print().unwrap(), expected);
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_handle_delete() {
+        let instance = MockInstance::new("test_handle_delete").await;
+        let instance = instance.inner();
+        test_util::create_test_table(instance, ConcreteDataType::timestamp_millisecond_datatype())
+            .await
+            .unwrap();
+
+        let output = instance
+            .execute_sql(
+                "INSERT INTO demo(host, cpu, memory, ts) VALUES \
+                    ('host1', 66.6, 1024, 1672201025000),\
+                    ('host2', 88.8, 333.3, 1672201026000),\
+                    ('host3', 88.8, 333.3, 1672201026000)",
+                QueryContext::arc(),
+            )
+            .await
+            .unwrap();
+        assert!(matches!(output, Output::AffectedRows(3)));
+
+        let delete = DeleteRequest {
+            schema_name: "public".to_string(),
+            table_name: "demo".to_string(),
+            key_columns: vec![
+                Column {
+                    column_name: "host".to_string(),
+                    values: Some(Values {
+                        string_values: vec!["host2".to_string(), "host3".to_string()],
+                        ..Default::default()
+                    }),
+                    semantic_type: SemanticType::Tag as i32,
+                    datatype: ColumnDataType::String as i32,
+                    ..Default::default()
+                },
+                Column {
+                    column_name: "ts".to_string(),
+                    values: Some(Values {
+                        ts_millisecond_values: vec![1672201026000, 1672201027000],
+                        ..Default::default()
+                    }),
+                    semantic_type: SemanticType::Timestamp as i32,
+                    datatype: ColumnDataType::TimestampMillisecond as i32,
+                    ..Default::default()
+                },
+            ],
+            row_count: 2,
+            ..Default::default()
+        };
+
+        let query = GreptimeRequest {
+            request: Some(GrpcRequest::Delete(delete)),
+        };
+        // Only the row of (host2, 1672201026000) exists and is deleted.
+        let output = instance.do_query(query).await.unwrap();
+        assert!(matches!(output, Output::AffectedRows(1)));
+
+        let output = instance
+            .execute_sql(
+                "SELECT ts, host FROM demo ORDER BY host",
+                QueryContext::arc(),
+            )
+            .await
+            .unwrap();
+        let Output::Stream(stream) = output else { unreachable!() };
+        let recordbatches = RecordBatches::try_collect(stream).await.unwrap();
+        let expected = "\
++---------------------+-------+
+| ts                  | host  |
++---------------------+-------+
+| 2022-12-28T04:17:05 | host1 |
+| 2022-12-28T04:17:06 | host3 |
++---------------------+-------+";
+        assert_eq!(recordbatches.pretty_print().unwrap(), expected);
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn test_handle_query() {
         let instance = MockInstance::new("test_handle_query").await;
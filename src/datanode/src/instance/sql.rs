@@ -18,7 +18,7 @@ use common_query::Output;
 use common_recordbatch::RecordBatches;
 use common_telemetry::logging::info;
 use common_telemetry::timer;
-use query::parser::{QueryLanguageParser, QueryStatement};
+use query::parser::{PromQuery, QueryLanguageParser, QueryStatement};
 use servers::query_handler::sql::SqlQueryHandler;
 use session::context::QueryContextRef;
 use snafu::prelude::*;
@@ -61,6 +61,11 @@ impl Instance {
                 )?;
                 self.sql_handler.execute(request, query_ctx).await
             }
+            QueryStatement::Sql(Statement::Delete(d)) => {
+                self.sql_handler
+                    .execute(SqlRequest::Delete(*d), query_ctx)
+                    .await
+            }
 
             QueryStatement::Sql(Statement::CreateDatabase(c)) => {
                 let request = CreateDatabaseRequest {
@@ -115,6 +120,28 @@ impl Instance {
                     .execute(SqlRequest::DropTable(req), query_ctx)
                     .await
             }
+            QueryStatement::Sql(Statement::FlushTable(stmt)) => {
+                self.sql_handler
+                    .execute(SqlRequest::FlushTable(stmt), query_ctx)
+                    .await
+            }
+            QueryStatement::Sql(Statement::ExportTable(stmt)) => {
+                self.sql_handler
+                    .execute(SqlRequest::ExportTable(stmt), query_ctx)
+                    .await
+            }
+            QueryStatement::Sql(Statement::RestoreTable(stmt)) => {
+                let table_id = self
+                    .table_id_provider
+                    .as_ref()
+                    .context(TableIdProviderNotFoundSnafu)?
+                    .next_table_id()
+                    .await
+                    .context(BumpTableIdSnafu)?;
+                self.sql_handler
+                    .execute(SqlRequest::RestoreTable { table_id, stmt }, query_ctx)
+                    .await
+            }
             QueryStatement::Sql(Statement::ShowDatabases(stmt)) => {
                 self.sql_handler
                     .execute(SqlRequest::ShowDatabases(stmt), query_ctx)
@@ -159,8 +186,12 @@ impl Instance {
         self.execute_stmt(stmt, query_ctx).await
     }
 
-    pub async fn execute_promql(&self, sql: &str, query_ctx: QueryContextRef) -> Result<Output> {
-        let stmt = QueryLanguageParser::parse_promql(sql).context(ExecuteSqlSnafu)?;
+    pub async fn execute_promql(
+        &self,
+        query: &PromQuery,
+        query_ctx: QueryContextRef,
+    ) -> Result<Output> {
+        let stmt = QueryLanguageParser::parse_promql(query).context(ExecuteSqlSnafu)?;
         self.execute_stmt(stmt, query_ctx).await
     }
 }
@@ -168,7 +199,7 @@ impl Instance {
 // TODO(LFC): Refactor consideration: move this function to some helper mod,
 // could be done together or after `TableReference`'s refactoring, when issue #559 is resolved.
 /// Converts maybe fully-qualified table name (`<catalog>.<schema>.<table>`) to tuple.
-fn table_idents_to_full_name(
+pub(crate) fn table_idents_to_full_name(
     obj_name: &ObjectName,
     query_ctx: QueryContextRef,
 ) -> Result<(String, String, String)> {
@@ -217,6 +248,15 @@ impl SqlQueryHandler for Instance {
             .await
     }
 
+    async fn do_promql_query(
+        &self,
+        query: &PromQuery,
+        query_ctx: QueryContextRef,
+    ) -> Result<Output> {
+        let _timer = timer!(metric::METRIC_HANDLE_PROMQL_ELAPSED);
+        self.execute_promql(query, query_ctx).await
+    }
+
     fn is_valid_schema(&self, catalog: &str, schema: &str) -> Result<bool> {
         self.catalog_manager
             .schema(catalog, schema)
@@ -20,19 +20,17 @@ use catalog::CatalogManagerRef;
 use common_catalog::consts::MIN_USER_TABLE_ID;
 use meta_client::client::{MetaClient, MetaClientBuilder};
 use meta_srv::mocks::MockInfo;
-use mito::config::EngineConfig as TableEngineConfig;
 use query::QueryEngineFactory;
 use servers::Mode;
 use snafu::ResultExt;
 use storage::config::EngineConfig as StorageEngineConfig;
-use storage::EngineImpl;
 use table::metadata::TableId;
 use table::table::TableIdProvider;
 
 use crate::datanode::DatanodeOptions;
 use crate::error::{CatalogSnafu, Result};
 use crate::heartbeat::HeartbeatTask;
-use crate::instance::{create_log_store, new_object_store, DefaultEngine, Instance};
+use crate::instance::{create_table_engine, new_object_store, Instance};
 use crate::script::ScriptExecutor;
 use crate::sql::SqlHandler;
 
@@ -44,17 +42,14 @@ impl Instance {
 
     pub async fn with_mock_meta_server(opts: &DatanodeOptions, meta_srv: MockInfo) -> Result<Self> {
         let object_store = new_object_store(&opts.storage).await?;
-        let logstore = Arc::new(create_log_store(&opts.wal).await?);
         let meta_client = Arc::new(mock_meta_client(meta_srv, opts.node_id.unwrap_or(42)).await);
-        let table_engine = Arc::new(DefaultEngine::new(
-            TableEngineConfig::default(),
-            EngineImpl::new(
-                StorageEngineConfig::default(),
-                logstore.clone(),
-                object_store.clone(),
-            ),
+        let (table_engine, table_backup) = create_table_engine(
+            &opts.wal,
+            StorageEngineConfig::default(),
             object_store,
-        ));
+            None,
+        )
+        .await?;
 
         // By default, catalog manager and factory are created in standalone mode
         let (catalog_manager, factory) = match opts.mode {
@@ -96,6 +91,7 @@ impl Instance {
                 table_engine,
                 catalog_manager.clone(),
                 query_engine.clone(),
+                Some(table_backup),
             ),
             catalog_manager,
             script_executor,
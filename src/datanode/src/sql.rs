@@ -15,31 +15,46 @@
 use catalog::CatalogManagerRef;
 use common_query::Output;
 use common_telemetry::error;
+use mito::engine::TableBackupRef;
 use query::query_engine::QueryEngineRef;
 use query::sql::{describe_table, explain, show_databases, show_tables};
 use session::context::QueryContextRef;
 use snafu::{OptionExt, ResultExt};
+use sql::statements::backup::{ExportTable, RestoreTable};
+use sql::statements::delete::Delete;
 use sql::statements::describe::DescribeTable;
 use sql::statements::explain::Explain;
+use sql::statements::flush::FlushTable;
 use sql::statements::show::{ShowDatabases, ShowTables};
 use table::engine::{EngineContext, TableEngineRef, TableReference};
+use table::metadata::TableId;
 use table::requests::*;
 use table::TableRef;
 
 use crate::error::{ExecuteSqlSnafu, GetTableSnafu, Result, TableNotFoundSnafu};
 
 mod alter;
+mod backup;
 mod create;
+mod delete;
 mod drop_table;
+mod flush_table;
 mod insert;
 
 #[derive(Debug)]
 pub enum SqlRequest {
     Insert(InsertRequest),
+    Delete(Delete),
     CreateTable(CreateTableRequest),
     CreateDatabase(CreateDatabaseRequest),
     Alter(AlterTableRequest),
     DropTable(DropTableRequest),
+    FlushTable(FlushTable),
+    ExportTable(ExportTable),
+    RestoreTable {
+        table_id: TableId,
+        stmt: RestoreTable,
+    },
     ShowDatabases(ShowDatabases),
     ShowTables(ShowTables),
     DescribeTable(DescribeTable),
@@ -51,6 +66,8 @@ pub struct SqlHandler {
     table_engine: TableEngineRef,
     catalog_manager: CatalogManagerRef,
     query_engine: QueryEngineRef,
+    /// Backs up tables of the `table_engine`, `None` if the engine doesn't support it.
+    table_backup: Option<TableBackupRef>,
 }
 
 impl SqlHandler {
@@ -58,11 +75,13 @@ impl SqlHandler {
         table_engine: TableEngineRef,
         catalog_manager: CatalogManagerRef,
         query_engine: QueryEngineRef,
+        table_backup: Option<TableBackupRef>,
     ) -> Self {
         Self {
             table_engine,
             catalog_manager,
             query_engine,
+            table_backup,
         }
     }
 
@@ -73,10 +92,16 @@ impl SqlHandler {
     pub async fn execute(&self, request: SqlRequest, query_ctx: QueryContextRef) -> Result<Output> {
         let result = match request {
             SqlRequest::Insert(req) => self.insert(req).await,
+            SqlRequest::Delete(stmt) => self.delete(stmt, query_ctx).await,
             SqlRequest::CreateTable(req) => self.create_table(req).await,
             SqlRequest::CreateDatabase(req) => self.create_database(req).await,
             SqlRequest::Alter(req) => self.alter(req).await,
             SqlRequest::DropTable(req) => self.drop_table(req).await,
+            SqlRequest::FlushTable(stmt) => self.flush_table(stmt, query_ctx).await,
+            SqlRequest::ExportTable(stmt) => self.export_table(stmt, query_ctx).await,
+            SqlRequest::RestoreTable { table_id, stmt } => {
+                self.restore_table(table_id, stmt, query_ctx).await
+            }
             SqlRequest::ShowDatabases(stmt) => {
                 show_databases(stmt, self.catalog_manager.clone()).context(ExecuteSqlSnafu)
             }
@@ -259,7 +284,12 @@ mod tests {
 
         let factory = QueryEngineFactory::new(catalog_list.clone());
         let query_engine = factory.query_engine();
-        let sql_handler = SqlHandler::new(table_engine, catalog_list.clone(), query_engine.clone());
+        let sql_handler = SqlHandler::new(
+            table_engine,
+            catalog_list.clone(),
+            query_engine.clone(),
+            None,
+        );
 
         let stmt = match QueryLanguageParser::parse_sql(sql).unwrap() {
             QueryStatement::Sql(Statement::Insert(i)) => i,
@@ -0,0 +1,97 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use catalog::RegisterTableRequest;
+use common_query::Output;
+use common_telemetry::info;
+use mito::engine::TableBackupRef;
+use session::context::QueryContextRef;
+use snafu::{OptionExt, ResultExt};
+use sql::statements::backup::{ExportTable, RestoreTable};
+use table::engine::TableReference;
+use table::metadata::TableId;
+
+use crate::error::{self, CatalogSnafu, InsertSystemCatalogSnafu, Result, SchemaNotFoundSnafu};
+use crate::instance::sql::table_idents_to_full_name;
+use crate::sql::SqlHandler;
+
+impl SqlHandler {
+    pub(crate) async fn export_table(
+        &self,
+        stmt: ExportTable,
+        query_ctx: QueryContextRef,
+    ) -> Result<Output> {
+        let (catalog, schema, table) = table_idents_to_full_name(stmt.table_name(), query_ctx)?;
+        let table_ref = TableReference::full(&catalog, &schema, &table);
+        // Ensures the table exists.
+        let _ = self.get_table(&table_ref)?;
+
+        self.table_backup()?
+            .export(&table_ref, stmt.dir())
+            .await
+            .context(error::ExportTableSnafu {
+                table_name: table_ref.to_string(),
+            })?;
+        info!("Exported table {} to {}", table_ref, stmt.dir());
+
+        Ok(Output::AffectedRows(0))
+    }
+
+    /// Restores the table exported to the directory in `stmt` as a new table with
+    /// `table_id`, and registers the table to the catalog.
+    pub(crate) async fn restore_table(
+        &self,
+        table_id: TableId,
+        stmt: RestoreTable,
+        query_ctx: QueryContextRef,
+    ) -> Result<Output> {
+        let (catalog, schema, table) = table_idents_to_full_name(stmt.table_name(), query_ctx)?;
+        let table_ref = TableReference::full(&catalog, &schema, &table);
+        let _ = self
+            .catalog_manager
+            .schema(&catalog, &schema)
+            .context(CatalogSnafu)?
+            .context(SchemaNotFoundSnafu { name: &schema })?;
+
+        let restored = self
+            .table_backup()?
+            .restore(stmt.dir(), table_id, &table_ref)
+            .await
+            .context(error::RestoreTableSnafu {
+                table_name: table_ref.to_string(),
+            })?;
+
+        info!("Restored table {} from {}", table_ref, stmt.dir());
+
+        let register_req = RegisterTableRequest {
+            catalog,
+            schema,
+            table_name: table,
+            table_id,
+            table: restored,
+        };
+        self.catalog_manager
+            .register_table(register_req)
+            .await
+            .context(InsertSystemCatalogSnafu)?;
+
+        Ok(Output::AffectedRows(0))
+    }
+
+    fn table_backup(&self) -> Result<&TableBackupRef> {
+        self.table_backup
+            .as_ref()
+            .context(error::TableBackupNotSupportedSnafu)
+    }
+}
@@ -126,6 +126,7 @@ impl SqlHandler {
     ) -> Result<CreateTableRequest> {
         let mut ts_index = usize::MAX;
         let mut primary_keys = vec![];
+        let table_options = stmt.table_options();
 
         let col_map = stmt
             .columns
@@ -216,7 +217,7 @@ impl SqlHandler {
             region_numbers: vec![0],
             primary_key_indices: primary_keys,
             create_if_not_exists: stmt.if_not_exists,
-            table_options: HashMap::new(),
+            table_options,
         };
         Ok(request)
     }
@@ -256,12 +257,13 @@ mod tests {
                        cpu double default 0,
                        memory double,
                        TIME INDEX (ts),
-                       PRIMARY KEY(host)) engine=mito with(regions=1);"#,
+                       PRIMARY KEY(host)) engine=mito with(regions=1, ttl='7d');"#,
         );
         let c = handler
             .create_to_request(42, parsed_stmt, TableReference::bare("demo_table"))
             .unwrap();
         assert_eq!("demo_table", c.table_name);
+        assert_eq!(Some("7d"), c.table_options.get(TTL_KEY).map(String::as_str));
         assert_eq!(42, c.id);
         assert!(!c.create_if_not_exists);
         assert_eq!(vec![0], c.primary_key_indices);
@@ -0,0 +1,37 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common_query::Output;
+use query::sql::delete;
+use session::context::QueryContextRef;
+use snafu::ResultExt;
+use sql::statements::delete::Delete;
+use table::engine::TableReference;
+
+use crate::error::{ExecuteSqlSnafu, Result};
+use crate::instance::sql::table_idents_to_full_name;
+use crate::sql::SqlHandler;
+
+impl SqlHandler {
+    pub(crate) async fn delete(&self, stmt: Delete, query_ctx: QueryContextRef) -> Result<Output> {
+        let (catalog, schema, table) =
+            table_idents_to_full_name(stmt.table_name(), query_ctx.clone())?;
+        let table_ref = TableReference::full(&catalog, &schema, &table);
+        let table = self.get_table(&table_ref)?;
+
+        delete(stmt, table, self.query_engine.clone(), query_ctx)
+            .await
+            .context(ExecuteSqlSnafu)
+    }
+}
@@ -579,6 +579,316 @@ async fn test_insert_with_default_value() {
     test_insert_with_default_value_for_type("bigint").await;
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_execute_delete() {
+    let instance = MockInstance::new("execute_delete").await;
+
+    let output = execute_sql(
+        &instance,
+        r#"create table test_table(
+            host string,
+            ts timestamp,
+            cpu double default 0,
+            TIME INDEX (ts),
+            PRIMARY KEY(host)
+        ) engine=mito with(regions=1);"#,
+    )
+    .await;
+    assert!(matches!(output, Output::AffectedRows(0)));
+
+    let output = execute_sql(
+        &instance,
+        r#"insert into test_table(host, cpu, ts) values
+                           ('host1', 1.1, 1000),
+                           ('host1', 1.2, 2000),
+                           ('host2', 2.1, 1000),
+                           ('host2', 2.2, 2000)
+                           "#,
+    )
+    .await;
+    assert!(matches!(output, Output::AffectedRows(4)));
+
+    let output = execute_sql(
+        &instance,
+        "delete from test_table where host = 'host1' or cpu > 2.15",
+    )
+    .await;
+    assert!(matches!(output, Output::AffectedRows(3)));
+
+    let output = execute_sql(&instance, "select host, cpu from test_table").await;
+    let expected = "\
++-------+-----+
+| host  | cpu |
++-------+-----+
+| host2 | 2.1 |
++-------+-----+\
+    "
+    .to_string();
+    check_output_stream(output, expected).await;
+
+    // Nothing matches the selection.
+    let output = execute_sql(&instance, "delete from test_table where host = 'host3'").await;
+    assert!(matches!(output, Output::AffectedRows(0)));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_execute_delete_nothing_matched() {
+    let instance = MockInstance::new("execute_delete_nothing_matched").await;
+
+    let output = execute_sql(
+        &instance,
+        r#"create table test_table(
+            host string,
+            ts timestamp,
+            cpu double default 0,
+            TIME INDEX (ts),
+            PRIMARY KEY(host)
+        ) engine=mito with(regions=1);"#,
+    )
+    .await;
+    assert!(matches!(output, Output::AffectedRows(0)));
+
+    // The table is empty.
+    let output = execute_sql(&instance, "delete from test_table").await;
+    assert!(matches!(output, Output::AffectedRows(0)));
+
+    // The key is written twice, but it is only one row.
+    let output = execute_sql(
+        &instance,
+        r#"insert into test_table(host, cpu, ts) values
+                           ('host1', 1.1, 1000),
+                           ('host1', 1.2, 1000),
+                           ('host2', 2.1, 1000)
+                           "#,
+    )
+    .await;
+    assert!(matches!(output, Output::AffectedRows(3)));
+
+    let output = execute_sql(&instance, "delete from test_table where cpu > 3").await;
+    assert!(matches!(output, Output::AffectedRows(0)));
+
+    let output = execute_sql(&instance, "delete from test_table where host = 'host1'").await;
+    assert!(matches!(output, Output::AffectedRows(1)));
+
+    // Rows already deleted are not counted again.
+    let output = execute_sql(&instance, "delete from test_table where host = 'host1'").await;
+    assert!(matches!(output, Output::AffectedRows(0)));
+
+    let output = execute_sql(&instance, "select host, cpu from test_table").await;
+    let expected = "\
++-------+-----+
+| host  | cpu |
++-------+-----+
+| host2 | 2.1 |
++-------+-----+\
+    "
+    .to_string();
+    check_output_stream(output, expected).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_execute_order_by_ts_limit() {
+    let instance = MockInstance::new("execute_order_by_ts_limit").await;
+
+    let output = execute_sql(
+        &instance,
+        r#"create table test_table(
+            host string,
+            ts timestamp,
+            cpu double default 0,
+            TIME INDEX (ts),
+            PRIMARY KEY(host)
+        ) engine=mito with(regions=1);"#,
+    )
+    .await;
+    assert!(matches!(output, Output::AffectedRows(0)));
+
+    let output = execute_sql(
+        &instance,
+        r#"insert into test_table(host, cpu, ts) values
+                           ('host1', 1.1, 1000),
+                           ('host1', 1.3, 3000),
+                           ('host2', 2.2, 2000),
+                           ('host2', 2.4, 4000)
+                           "#,
+    )
+    .await;
+    assert!(matches!(output, Output::AffectedRows(4)));
+
+    let output = execute_sql(
+        &instance,
+        "select host, cpu from test_table order by ts desc limit 3",
+    )
+    .await;
+    let expected = "\
++-------+-----+
+| host  | cpu |
++-------+-----+
+| host2 | 2.4 |
+| host1 | 1.3 |
+| host2 | 2.2 |
++-------+-----+\
+    "
+    .to_string();
+    check_output_stream(output, expected).await;
+
+    let output = execute_sql(
+        &instance,
+        "select host, cpu from test_table order by ts limit 2 offset 1",
+    )
+    .await;
+    let expected = "\
++-------+-----+
+| host  | cpu |
++-------+-----+
+| host2 | 2.2 |
+| host1 | 1.3 |
++-------+-----+\
+    "
+    .to_string();
+    check_output_stream(output, expected).await;
+
+    // The filter is applied after the scan, so the scan is not limited.
+    let output = execute_sql(
+        &instance,
+        "select host, cpu from test_table where host = 'host1' order by ts desc limit 1",
+    )
+    .await;
+    let expected = "\
++-------+-----+
+| host  | cpu |
++-------+-----+
+| host1 | 1.3 |
++-------+-----+\
+    "
+    .to_string();
+    check_output_stream(output, expected).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_execute_flush_table() {
+    let instance = MockInstance::new("execute_flush_table").await;
+
+    let output = execute_sql(
+        &instance,
+        r#"create table test_table(
+            host string,
+            ts timestamp,
+            cpu double default 0,
+            TIME INDEX (ts),
+            PRIMARY KEY(host)
+        ) engine=mito with(regions=1);"#,
+    )
+    .await;
+    assert!(matches!(output, Output::AffectedRows(0)));
+
+    let output = execute_sql(
+        &instance,
+        r#"insert into test_table(host, cpu, ts) values
+                           ('host1', 1.1, 1000),
+                           ('host2', 2.1, 1000)
+                           "#,
+    )
+    .await;
+    assert!(matches!(output, Output::AffectedRows(2)));
+
+    let output = execute_sql(&instance, "flush table test_table").await;
+    assert!(matches!(output, Output::AffectedRows(0)));
+
+    let output = execute_sql(&instance, "select host, cpu from test_table").await;
+    let expected = "\
++-------+-----+
+| host  | cpu |
++-------+-----+
+| host1 | 1.1 |
+| host2 | 2.1 |
++-------+-----+\
+    "
+    .to_string();
+    check_output_stream(output, expected).await;
+
+    // Flush a table that doesn't exist.
+    let result =
+        try_execute_sql_in_db(&instance, "flush table not_exist", DEFAULT_SCHEMA_NAME).await;
+    assert!(result.is_err());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_export_and_restore_table() {
+    let instance = MockInstance::new("export_and_restore_table").await;
+
+    let output = execute_sql(
+        &instance,
+        r#"create table test_table(
+            host string,
+            ts timestamp,
+            cpu double default 0,
+            TIME INDEX (ts),
+            PRIMARY KEY(host)
+        ) engine=mito with(regions=1);"#,
+    )
+    .await;
+    assert!(matches!(output, Output::AffectedRows(0)));
+
+    let output = execute_sql(
+        &instance,
+        r#"insert into test_table(host, cpu, ts) values
+                           ('host1', 1.1, 1000),
+                           ('host2', 2.1, 1000)
+                           "#,
+    )
+    .await;
+    assert!(matches!(output, Output::AffectedRows(2)));
+
+    let output = execute_sql(&instance, "export table test_table to 'backup/test_table'").await;
+    assert!(matches!(output, Output::AffectedRows(0)));
+
+    let output = execute_sql(
+        &instance,
+        "restore table restored_table from 'backup/test_table'",
+    )
+    .await;
+    assert!(matches!(output, Output::AffectedRows(0)));
+
+    // The restored table is registered in the catalog.
+    assert!(instance
+        .inner()
+        .catalog_manager
+        .table(DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME, "restored_table")
+        .unwrap()
+        .is_some());
+
+    let output = execute_sql(&instance, "select host, cpu from restored_table").await;
+    let expected = "\
++-------+-----+
+| host  | cpu |
++-------+-----+
+| host1 | 1.1 |
+| host2 | 2.1 |
++-------+-----+\
+    "
+    .to_string();
+    check_output_stream(output, expected).await;
+
+    // Restore into a table that already exists.
+    let result = try_execute_sql_in_db(
+        &instance,
+        "restore table test_table from 'backup/test_table'",
+        DEFAULT_SCHEMA_NAME,
+    )
+    .await;
+    assert!(result.is_err());
+
+    // Export a table that doesn't exist.
+    let result = try_execute_sql_in_db(
+        &instance,
+        "export table not_exist to 'backup/not_exist'",
+        DEFAULT_SCHEMA_NAME,
+    )
+    .await;
+    assert!(result.is_err());
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn test_use_database() {
     let instance = MockInstance::new("test_use_database").await;
@@ -16,9 +16,15 @@ use std::sync::Arc;
 
 use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
 use common_query::Output;
-use session::context::QueryContext;
+use common_recordbatch::util;
+use common_time::Timestamp;
+use datatypes::value::Value;
+use query::parser::PromQuery;
+use session::context::{QueryContext, QueryContextRef};
 
-use crate::tests::test_util::{check_output_stream, setup_test_instance};
+use crate::error::Result;
+use crate::instance::Instance;
+use crate::tests::test_util::{check_output_stream, setup_test_instance, MockInstance};
 
 #[tokio::test(flavor = "multi_thread")]
 async fn sql_insert_promql_query_ceil() {
@@ -50,10 +56,15 @@ async fn sql_insert_promql_query_ceil() {
         .unwrap();
     assert!(matches!(put_output, Output::AffectedRows(12)));
 
-    let promql = "ceil(demo{host=\"host1\"})";
+    let query = PromQuery {
+        query: "ceil(demo{host=\"host1\"})".to_string(),
+        start: "0".to_string(),
+        end: "100".to_string(),
+        step: "5s".to_string(),
+    };
     let query_output = instance
         .inner()
-        .execute_promql(promql, query_ctx)
+        .execute_promql(&query, query_ctx)
         .await
         .unwrap();
     let expected = String::from(
@@ -64,10 +75,586 @@ async fn sql_insert_promql_query_ceil() {
 | 1970-01-01T00:00:00 | 67             | 1024              |
 | 1970-01-01T00:00:05 | 67             | 4096              |
 | 1970-01-01T00:00:10 | 100            | 20480             |
+| 1970-01-01T00:00:15 | 100            | 20480             |
+| 1970-01-01T00:00:20 | 100            | 20480             |
+| 1970-01-01T00:00:25 | 32             | 8192              |
+| 1970-01-01T00:00:30 | 32             | 8192              |
+| 1970-01-01T00:00:35 | 96             | 334               |
+| 1970-01-01T00:00:40 | 96             | 334               |
+| 1970-01-01T00:00:45 | 96             | 334               |
 | 1970-01-01T00:00:50 | 12424          | 1334              |
+| 1970-01-01T00:00:55 | 12424          | 1334              |
+| 1970-01-01T00:01:00 | 12424          | 1334              |
+| 1970-01-01T00:01:05 | 12424          | 1334              |
+| 1970-01-01T00:01:10 | 12424          | 1334              |
+| 1970-01-01T00:01:15 | 12424          | 1334              |
 | 1970-01-01T00:01:20 | 0              | 2334              |
+| 1970-01-01T00:01:25 | 0              | 2334              |
+| 1970-01-01T00:01:30 | 0              | 2334              |
+| 1970-01-01T00:01:35 | 0              | 2334              |
 | 1970-01-01T00:01:40 | 49             | 3334              |
 +---------------------+----------------+-------------------+",
     );
     check_output_stream(query_output, expected).await;
 }
+
+fn new_query_ctx() -> QueryContextRef {
+    Arc::new(QueryContext::with(
+        DEFAULT_CATALOG_NAME.to_owned(),
+        DEFAULT_SCHEMA_NAME.to_owned(),
+    ))
+}
+
+/// Sets up an instance whose `demo` table has one sample of each host at timestamp 0.
+async fn setup_hosts_instance(test_name: &str) -> MockInstance {
+    let instance = setup_test_instance(test_name).await;
+    let put_output = instance
+        .inner()
+        .execute_sql(
+            r#"insert into demo(host, cpu, memory, ts) values
+            ('host1', 10.5, 1024, 0),
+            ('host2', 20.5, 2048, 0),
+            ('host3', 30.5, 4096, 0),
+            ('host4', 30.5, 8192, 0);
+            "#,
+            new_query_ctx(),
+        )
+        .await
+        .unwrap();
+    assert!(matches!(put_output, Output::AffectedRows(4)));
+    instance
+}
+
+/// Evaluates `query` at timestamp 0.
+async fn execute_instant_promql(instance: &Instance, query: &str) -> Result<Output> {
+    let query = PromQuery {
+        query: query.to_string(),
+        start: "0".to_string(),
+        end: "0".to_string(),
+        step: "5s".to_string(),
+    };
+    instance.execute_promql(&query, new_query_ctx()).await
+}
+
+/// Collects rows of `output` in sorted order, as the output order of some plans is
+/// not deterministic.
+async fn collect_sorted_rows(output: Output) -> Vec<Vec<Value>> {
+    let recordbatches = match output {
+        Output::Stream(stream) => util::collect_batches(stream).await.unwrap(),
+        Output::RecordBatches(recordbatches) => recordbatches,
+        _ => unreachable!(),
+    };
+    let mut rows: Vec<_> = recordbatches
+        .iter()
+        .flat_map(|batch| batch.rows())
+        .collect();
+    rows.sort();
+    rows
+}
+
+fn ts_value(millis: i64) -> Value {
+    Value::Timestamp(Timestamp::new_millisecond(millis))
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn promql_query_topk() {
+    let instance = setup_hosts_instance("promql_query_topk").await;
+
+    let output = execute_instant_promql(instance.inner(), "topk(2, demo)")
+        .await
+        .unwrap();
+    assert_eq!(
+        vec![
+            vec![
+                Value::from("host3"),
+                Value::from(30.5),
+                Value::from(4096.0),
+                ts_value(0)
+            ],
+            vec![
+                Value::from("host4"),
+                Value::from(30.5),
+                Value::from(8192.0),
+                ts_value(0)
+            ],
+        ],
+        collect_sorted_rows(output).await
+    );
+
+    let output = execute_instant_promql(instance.inner(), "bottomk(1.9, demo)")
+        .await
+        .unwrap();
+    assert_eq!(
+        vec![vec![
+            Value::from("host1"),
+            Value::from(10.5),
+            Value::from(1024.0),
+            ts_value(0)
+        ]],
+        collect_sorted_rows(output).await
+    );
+
+    // Each host is a group of its own.
+    let output = execute_instant_promql(instance.inner(), "topk by (host) (1, demo)")
+        .await
+        .unwrap();
+    assert_eq!(4, collect_sorted_rows(output).await.len());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn promql_query_topk_invalid_param() {
+    let instance = setup_hosts_instance("promql_query_topk_invalid_param").await;
+
+    // Like Prometheus, k less than 1 selects nothing.
+    for query in ["topk(0, demo)", "topk(-1, demo)", "bottomk(0.5, demo)"] {
+        let output = execute_instant_promql(instance.inner(), query)
+            .await
+            .unwrap();
+        assert!(collect_sorted_rows(output).await.is_empty(), "{query}");
+    }
+
+    assert!(execute_instant_promql(instance.inner(), "topk(NaN, demo)")
+        .await
+        .is_err());
+    // k must be a literal.
+    assert!(execute_instant_promql(instance.inner(), "topk(demo, demo)")
+        .await
+        .is_err());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn promql_query_count_values() {
+    let instance = setup_hosts_instance("promql_query_count_values").await;
+
+    let output = execute_instant_promql(instance.inner(), r#"count_values("value", demo)"#)
+        .await
+        .unwrap();
+    assert_eq!(
+        vec![
+            vec![Value::from("10.5"), ts_value(0), Value::from(1.0)],
+            vec![Value::from("20.5"), ts_value(0), Value::from(1.0)],
+            vec![Value::from("30.5"), ts_value(0), Value::from(2.0)],
+        ],
+        collect_sorted_rows(output).await
+    );
+
+    // The label name must be a string literal.
+    assert!(
+        execute_instant_promql(instance.inner(), "count_values(1, demo)")
+            .await
+            .is_err()
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn promql_query_quantile() {
+    let instance = setup_hosts_instance("promql_query_quantile").await;
+
+    // The quantile of a single sample is the sample itself.
+    let output = execute_instant_promql(instance.inner(), "quantile by (host) (0.5, demo)")
+        .await
+        .unwrap();
+    assert_eq!(
+        vec![
+            vec![
+                Value::from("host1"),
+                ts_value(0),
+                Value::from(10.5),
+                Value::from(1024.0)
+            ],
+            vec![
+                Value::from("host2"),
+                ts_value(0),
+                Value::from(20.5),
+                Value::from(2048.0)
+            ],
+            vec![
+                Value::from("host3"),
+                ts_value(0),
+                Value::from(30.5),
+                Value::from(4096.0)
+            ],
+            vec![
+                Value::from("host4"),
+                ts_value(0),
+                Value::from(30.5),
+                Value::from(8192.0)
+            ],
+        ],
+        collect_sorted_rows(output).await
+    );
+
+    // The 1-quantile is the max value.
+    let output = execute_instant_promql(instance.inner(), "quantile(1, demo)")
+        .await
+        .unwrap();
+    assert_eq!(
+        vec![vec![ts_value(0), Value::from(30.5), Value::from(8192.0)]],
+        collect_sorted_rows(output).await
+    );
+
+    // The quantile is interpolated between the two nearest samples.
+    let output = execute_instant_promql(instance.inner(), "quantile(0.5, demo)")
+        .await
+        .unwrap();
+    assert_eq!(
+        vec![vec![ts_value(0), Value::from(25.5), Value::from(3072.0)]],
+        collect_sorted_rows(output).await
+    );
+    let output = execute_instant_promql(instance.inner(), "quantile(0.25, demo)")
+        .await
+        .unwrap();
+    assert_eq!(
+        vec![vec![ts_value(0), Value::from(18.0), Value::from(1792.0)]],
+        collect_sorted_rows(output).await
+    );
+
+    // Like Prometheus, φ out of [0, 1] results in -Inf or +Inf, and NaN results in NaN.
+    for (query, expect) in [
+        ("quantile(-0.1, demo)", f64::NEG_INFINITY),
+        ("quantile(1.5, demo)", f64::INFINITY),
+        ("quantile(NaN, demo)", f64::NAN),
+    ] {
+        let output = execute_instant_promql(instance.inner(), query)
+            .await
+            .unwrap();
+        assert_eq!(
+            vec![vec![ts_value(0), Value::from(expect), Value::from(expect)]],
+            collect_sorted_rows(output).await,
+            "{query}"
+        );
+    }
+
+    // φ must be a literal.
+    assert!(
+        execute_instant_promql(instance.inner(), "quantile(demo, demo)")
+            .await
+            .is_err()
+    );
+}
+
+/// Sets up an instance with table `requests` of labels `host` and `dc`, and table
+/// `limits` of labels `dc` and `tier`. Both have one sample of each series at timestamp 0.
+async fn setup_requests_instance(test_name: &str) -> MockInstance {
+    let instance = setup_test_instance(test_name).await;
+    for sql in [
+        r#"create table requests(
+            host string,
+            dc string,
+            ts timestamp,
+            val double,
+            TIME INDEX (ts),
+            PRIMARY KEY(host, dc)
+        ) engine=mito with(regions=1);"#,
+        r#"create table limits(
+            dc string,
+            tier string,
+            ts timestamp,
+            val double,
+            TIME INDEX (ts),
+            PRIMARY KEY(dc, tier)
+        ) engine=mito with(regions=1);"#,
+    ] {
+        let output = instance
+            .inner()
+            .execute_sql(sql, new_query_ctx())
+            .await
+            .unwrap();
+        assert!(matches!(output, Output::AffectedRows(0)));
+    }
+
+    let output = instance
+        .inner()
+        .execute_sql(
+            r#"insert into requests(host, dc, ts, val) values
+            ('host1', 'dc1', 0, 10),
+            ('host2', 'dc1', 0, 20),
+            ('host3', 'dc2', 0, 30);
+            "#,
+            new_query_ctx(),
+        )
+        .await
+        .unwrap();
+    assert!(matches!(output, Output::AffectedRows(3)));
+    let output = instance
+        .inner()
+        .execute_sql(
+            r#"insert into limits(dc, tier, ts, val) values
+            ('dc1', 'gold', 0, 100),
+            ('dc2', 'silver', 0, 200);
+            "#,
+            new_query_ctx(),
+        )
+        .await
+        .unwrap();
+    assert!(matches!(output, Output::AffectedRows(2)));
+    instance
+}
+
+/// Returns true if `query` fails to plan or to execute.
+async fn instant_promql_fails(instance: &Instance, query: &str) -> bool {
+    match execute_instant_promql(instance, query).await {
+        Ok(Output::Stream(stream)) => util::collect_batches(stream).await.is_err(),
+        Ok(_) => false,
+        Err(_) => true,
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn promql_query_binary_missing_labels() {
+    let instance = setup_requests_instance("promql_query_binary_missing_labels").await;
+
+    // `host` is absent in `limits` and `tier` is absent in `requests`, so no series
+    // have the same labels.
+    let output = execute_instant_promql(instance.inner(), "requests + limits")
+        .await
+        .unwrap();
+    assert!(collect_sorted_rows(output).await.is_empty());
+    let output = execute_instant_promql(instance.inner(), "sum by (dc) (requests) / limits")
+        .await
+        .unwrap();
+    assert!(collect_sorted_rows(output).await.is_empty());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn promql_query_binary_on_and_ignoring() {
+    let instance = setup_requests_instance("promql_query_binary_on_and_ignoring").await;
+
+    let output = execute_instant_promql(
+        instance.inner(),
+        "sum by (dc) (requests) / ignoring(tier) limits",
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        vec![
+            vec![Value::from("dc1"), ts_value(0), Value::from(30.0 / 100.0)],
+            vec![Value::from("dc2"), ts_value(0), Value::from(30.0 / 200.0)],
+        ],
+        collect_sorted_rows(output).await
+    );
+
+    let output = execute_instant_promql(instance.inner(), "sum by (dc) (requests) - on(dc) limits")
+        .await
+        .unwrap();
+    assert_eq!(
+        vec![
+            vec![Value::from("dc1"), ts_value(0), Value::from(-70.0)],
+            vec![Value::from("dc2"), ts_value(0), Value::from(-170.0)],
+        ],
+        collect_sorted_rows(output).await
+    );
+
+    // Both `host1` and `host2` are in `dc1`, one-to-one matching requires one series on
+    // each side.
+    assert!(instant_promql_fails(instance.inner(), "requests + on(dc) limits").await);
+    assert!(instant_promql_fails(instance.inner(), "limits + ignoring(host, tier) requests").await);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn promql_query_binary_group_modifiers() {
+    let instance = setup_requests_instance("promql_query_binary_group_modifiers").await;
+
+    let output = execute_instant_promql(
+        instance.inner(),
+        "requests * on(dc) group_left(tier) limits",
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        vec![
+            vec![
+                Value::from("host1"),
+                Value::from("dc1"),
+                Value::from("gold"),
+                ts_value(0),
+                Value::from(1000.0)
+            ],
+            vec![
+                Value::from("host2"),
+                Value::from("dc1"),
+                Value::from("gold"),
+                ts_value(0),
+                Value::from(2000.0)
+            ],
+            vec![
+                Value::from("host3"),
+                Value::from("dc2"),
+                Value::from("silver"),
+                ts_value(0),
+                Value::from(6000.0)
+            ],
+        ],
+        collect_sorted_rows(output).await
+    );
+
+    let output = execute_instant_promql(
+        instance.inner(),
+        "limits / ignoring(host, tier) group_right requests",
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        vec![
+            vec![
+                Value::from("host1"),
+                Value::from("dc1"),
+                ts_value(0),
+                Value::from(100.0 / 10.0)
+            ],
+            vec![
+                Value::from("host2"),
+                Value::from("dc1"),
+                ts_value(0),
+                Value::from(100.0 / 20.0)
+            ],
+            vec![
+                Value::from("host3"),
+                Value::from("dc2"),
+                ts_value(0),
+                Value::from(200.0 / 30.0)
+            ],
+        ],
+        collect_sorted_rows(output).await
+    );
+
+    // The "one" side has more than one series in `dc1`.
+    assert!(instant_promql_fails(instance.inner(), "limits * on(dc) group_left requests").await);
+    assert!(instant_promql_fails(instance.inner(), "requests * on(dc) group_right limits").await);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn promql_query_binary_bool() {
+    let instance = setup_requests_instance("promql_query_binary_bool").await;
+
+    let output = execute_instant_promql(
+        instance.inner(),
+        "limits > bool ignoring(tier) sum by (dc) (requests) * 4",
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        vec![
+            vec![Value::from("dc1"), ts_value(0), Value::from(0.0)],
+            vec![Value::from("dc2"), ts_value(0), Value::from(1.0)],
+        ],
+        collect_sorted_rows(output).await
+    );
+
+    // Without `bool`, the comparison filters the samples of the left side.
+    let output = execute_instant_promql(
+        instance.inner(),
+        "limits > ignoring(tier) sum by (dc) (requests) * 4",
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        vec![vec![Value::from("dc2"), ts_value(0), Value::from(200.0)]],
+        collect_sorted_rows(output).await
+    );
+}
+
+/// Evaluates `query` from 0 to 300s every 60s on `demo`, whose `host1` counts up one per
+/// second in `cpu` and two per second in `memory`, with a sample every 60s.
+async fn execute_counter_promql(test_name: &str, query: &str) -> Vec<Vec<Value>> {
+    let instance = setup_test_instance(test_name).await;
+    let output = instance
+        .inner()
+        .execute_sql(
+            r#"insert into demo(host, cpu, memory, ts) values
+            ('host1', 0, 0, 0),
+            ('host1', 60, 120, 60000),
+            ('host1', 120, 240, 120000),
+            ('host1', 180, 360, 180000),
+            ('host1', 240, 480, 240000),
+            ('host1', 300, 600, 300000);
+            "#,
+            new_query_ctx(),
+        )
+        .await
+        .unwrap();
+    assert!(matches!(output, Output::AffectedRows(6)));
+
+    let query = PromQuery {
+        query: query.to_string(),
+        start: "0".to_string(),
+        end: "300".to_string(),
+        step: "60s".to_string(),
+    };
+    let output = instance
+        .inner()
+        .execute_promql(&query, new_query_ctx())
+        .await
+        .unwrap();
+    collect_sorted_rows(output).await
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn promql_query_matrix_selector_at_modifier() {
+    // Every step evaluates the rate over the same range [0, 300s].
+    let rows = execute_counter_promql(
+        "promql_query_matrix_selector_at_modifier",
+        "rate(demo[5m] @ end())",
+    )
+    .await;
+    let expected = (0..=300_000)
+        .step_by(60_000)
+        .map(|ts| {
+            vec![
+                Value::from("host1"),
+                ts_value(ts),
+                Value::from(1.0),
+                Value::from(2.0),
+            ]
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(expected, rows);
+
+    // The range ends at 0 and holds only one sample, so there is no rate.
+    let rows = execute_counter_promql(
+        "promql_query_matrix_selector_at_start",
+        "rate(demo[5m] @ start())",
+    )
+    .await;
+    assert!(rows.is_empty(), "{rows:?}");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn promql_query_subquery_at_modifier() {
+    // The inner query is evaluated at 180s, 240s and 300s for every step.
+    let rows = execute_counter_promql(
+        "promql_query_subquery_at_modifier",
+        "max_over_time(demo[2m:1m] @ end())",
+    )
+    .await;
+    let expected = (0..=300_000)
+        .step_by(60_000)
+        .map(|ts| {
+            vec![
+                Value::from("host1"),
+                ts_value(ts),
+                Value::from(300.0),
+                Value::from(600.0),
+            ]
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(expected, rows);
+
+    let rows = execute_counter_promql(
+        "promql_query_subquery_at_modifier_min",
+        "min_over_time(demo[2m:1m] @ end())",
+    )
+    .await;
+    let expected = (0..=300_000)
+        .step_by(60_000)
+        .map(|ts| {
+            vec![
+                Value::from("host1"),
+                ts_value(ts),
+                Value::from(180.0),
+                Value::from(360.0),
+            ]
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(expected, rows);
+}
@@ -146,7 +146,7 @@ pub async fn create_mock_sql_handler() -> SqlHandler {
     let catalog_list = catalog::local::new_memory_catalog_list().unwrap();
     let factory = QueryEngineFactory::new(catalog_list);
 
-    SqlHandler::new(mock_engine, catalog_manager, factory.query_engine())
+    SqlHandler::new(mock_engine, catalog_manager, factory.query_engine(), None)
 }
 
 pub(crate) async fn setup_test_instance(test_name: &str) -> MockInstance {
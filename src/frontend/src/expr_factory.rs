@@ -12,7 +12,6 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::collections::HashMap;
 use std::sync::Arc;
 
 use api::helper::ColumnDataTypeWrapper;
@@ -83,6 +82,8 @@ fn create_to_expr(
         table_idents_to_full_name(&create.name).context(ParseSqlSnafu)?;
 
     let time_index = find_time_index(&create.constraints)?;
+    let mut table_options = create.table_options();
+    table_options.insert("engine".to_string(), create.engine.clone());
     let expr = CreateTableExpr {
         catalog_name,
         schema_name,
@@ -92,8 +93,7 @@ fn create_to_expr(
         time_index,
         primary_keys: find_primary_keys(&create.constraints)?,
         create_if_not_exists: create.if_not_exists,
-        // TODO(LFC): Fill in other table options.
-        table_options: HashMap::from([("engine".to_string(), create.engine.clone())]),
+        table_options,
         table_id: table_id.map(|id| api::v1::TableId { id }),
         region_ids,
     };
@@ -40,6 +40,7 @@ use datanode::instance::InstanceRef as DnInstanceRef;
 use distributed::DistInstance;
 use meta_client::client::{MetaClient, MetaClientBuilder};
 use meta_client::MetaClientOpts;
+use query::parser::PromQuery;
 use servers::error as server_error;
 use servers::interceptor::{SqlQueryInterceptor, SqlQueryInterceptorRef};
 use servers::query_handler::grpc::{GrpcQueryHandler, GrpcQueryHandlerRef};
@@ -377,7 +378,11 @@ impl Instance {
             | Statement::DescribeTable(_)
             | Statement::Explain(_)
             | Statement::Query(_)
-            | Statement::Insert(_) => {
+            | Statement::Insert(_)
+            | Statement::Delete(_)
+            | Statement::FlushTable(_)
+            | Statement::ExportTable(_)
+            | Statement::RestoreTable(_) => {
                 return self.sql_handler.do_statement_query(stmt, query_ctx).await;
             }
             Statement::Alter(alter_stmt) => {
@@ -473,6 +478,18 @@ impl SqlQueryHandler for Instance {
             .and_then(|output| query_interceptor.post_execute(output, query_ctx.clone()))
     }
 
+    async fn do_promql_query(
+        &self,
+        query: &PromQuery,
+        query_ctx: QueryContextRef,
+    ) -> Result<Output> {
+        let query_interceptor = self.plugins.get::<SqlQueryInterceptorRef<Error>>();
+        self.sql_handler
+            .do_promql_query(query, query_ctx.clone())
+            .await
+            .and_then(|output| query_interceptor.post_execute(output, query_ctx))
+    }
+
     fn is_valid_schema(&self, catalog: &str, schema: &str) -> Result<bool> {
         self.catalog_manager
             .schema(catalog, schema)
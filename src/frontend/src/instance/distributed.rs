@@ -18,7 +18,9 @@ use std::collections::HashMap;
 use std::sync::Arc;
 
 use api::helper::ColumnDataTypeWrapper;
-use api::v1::{AlterExpr, CreateDatabaseExpr, CreateTableExpr, InsertRequest, TableId};
+use api::v1::{
+    AlterExpr, CreateDatabaseExpr, CreateTableExpr, DeleteRequest, InsertRequest, TableId,
+};
 use async_trait::async_trait;
 use catalog::helper::{SchemaKey, SchemaValue, TableGlobalKey, TableGlobalValue};
 use catalog::{CatalogList, CatalogManager};
@@ -34,8 +36,8 @@ use meta_client::rpc::{
     CreateRequest as MetaCreateRequest, Partition as MetaPartition, PutRequest, RouteResponse,
     TableName, TableRoute,
 };
-use query::parser::QueryStatement;
-use query::sql::{describe_table, explain, show_databases, show_tables};
+use query::parser::{PromQuery, QueryLanguageParser, QueryStatement};
+use query::sql::{delete, describe_table, explain, show_databases, show_tables};
 use query::{QueryEngineFactory, QueryEngineRef};
 use servers::query_handler::sql::SqlQueryHandler;
 use session::context::QueryContextRef;
@@ -53,7 +55,7 @@ use crate::error::{
     self, AlterExprToRequestSnafu, CatalogEntrySerdeSnafu, CatalogNotFoundSnafu, CatalogSnafu,
     ColumnDataTypeSnafu, ParseSqlSnafu, PrimaryKeyNotFoundSnafu, RequestDatanodeSnafu,
     RequestMetaSnafu, Result, SchemaNotFoundSnafu, StartMetaClientSnafu, TableNotFoundSnafu,
-    TableSnafu, ToTableInsertRequestSnafu,
+    TableSnafu, ToTableDeleteRequestSnafu, ToTableInsertRequestSnafu,
 };
 use crate::expr_factory::{CreateExprFactory, DefaultCreateExprFactory};
 use crate::instance::parse_stmt;
@@ -187,11 +189,54 @@ impl DistInstance {
                     table.insert(insert_request).await.context(TableSnafu)?,
                 ));
             }
+            Statement::Delete(stmt) => {
+                let (catalog, schema, table) = stmt.full_table_name().context(ParseSqlSnafu)?;
+
+                let table = self
+                    .catalog_manager
+                    .table(&catalog, &schema, &table)
+                    .context(CatalogSnafu)?
+                    .context(TableNotFoundSnafu { table_name: table })?;
+
+                delete(*stmt, table, self.query_engine.clone(), query_ctx).await
+            }
+            Statement::FlushTable(stmt) => {
+                let (catalog, schema, table) = stmt.full_table_name().context(ParseSqlSnafu)?;
+
+                let table = self
+                    .catalog_manager
+                    .table(&catalog, &schema, &table)
+                    .context(CatalogSnafu)?
+                    .context(TableNotFoundSnafu { table_name: table })?;
+
+                table.flush().await.context(TableSnafu)?;
+
+                return Ok(Output::AffectedRows(0));
+            }
+            Statement::ExportTable(_) | Statement::RestoreTable(_) => {
+                return error::NotSupportedSnafu {
+                    feat: "EXPORT TABLE and RESTORE TABLE in distributed mode",
+                }
+                .fail();
+            }
             _ => unreachable!(),
         }
         .context(error::ExecuteStatementSnafu)
     }
 
+    async fn handle_promql(&self, query: &PromQuery, query_ctx: QueryContextRef) -> Result<Output> {
+        let stmt =
+            QueryLanguageParser::parse_promql(query).context(error::ExecuteStatementSnafu)?;
+        let plan = self
+            .query_engine
+            .statement_to_plan(stmt, query_ctx)
+            .context(error::ExecuteStatementSnafu)?;
+        self.query_engine
+            .execute(&plan)
+            .await
+            .context(error::ExecuteStatementSnafu)
+    }
+
     async fn handle_sql(&self, sql: &str, query_ctx: QueryContextRef) -> Vec<Result<Output>> {
         let stmts = parse_stmt(sql);
         match stmts {
@@ -362,6 +407,21 @@ impl DistInstance {
         Ok(Output::AffectedRows(affected_rows))
     }
 
+    async fn handle_dist_delete(&self, request: DeleteRequest) -> Result<Output> {
+        let table_name = &request.table_name;
+        let table = self
+            .catalog_manager
+            .table(DEFAULT_CATALOG_NAME, &request.schema_name, table_name)
+            .context(CatalogSnafu)?
+            .context(TableNotFoundSnafu { table_name })?;
+
+        let request = common_grpc_expr::delete::to_table_delete_request(request)
+            .context(ToTableDeleteRequestSnafu)?;
+
+        let affected_rows = table.delete(request).await.context(TableSnafu)?;
+        Ok(Output::AffectedRows(affected_rows))
+    }
+
     #[cfg(test)]
     pub(crate) fn catalog_manager(&self) -> Arc<FrontendCatalogManager> {
         self.catalog_manager.clone()
@@ -384,6 +444,14 @@ impl SqlQueryHandler for DistInstance {
         self.handle_statement(stmt, query_ctx).await
     }
 
+    async fn do_promql_query(
+        &self,
+        query: &PromQuery,
+        query_ctx: QueryContextRef,
+    ) -> Result<Output> {
+        self.handle_promql(query, query_ctx).await
+    }
+
     fn is_valid_schema(&self, catalog: &str, schema: &str) -> Result<bool> {
         self.catalog_manager
             .schema(catalog, schema)
@@ -468,7 +536,7 @@ fn create_table_global_value(
         next_column_id: column_schemas.len() as u32,
         region_numbers: vec![],
         engine_options: HashMap::new(),
-        options: HashMap::new(),
+        options: create_table.table_options.clone(),
         created_on: DateTime::default(),
     };
 
@@ -581,6 +649,8 @@ fn find_partition_columns(
 
 #[cfg(test)]
 mod test {
+    use std::path::Path;
+
     use itertools::Itertools;
     use servers::query_handler::sql::SqlQueryHandlerRef;
     use session::context::QueryContext;
@@ -746,4 +816,73 @@ ENGINE=mito",
             assert_show_tables(StandaloneSqlQueryHandler::arc(x.clone())).await
         }
     }
+
+    fn count_sst_files(dir: &Path) -> usize {
+        std::fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| {
+                let path = entry.unwrap().path();
+                if path.is_dir() {
+                    count_sst_files(&path)
+                } else {
+                    usize::from(path.extension().map_or(false, |ext| ext == "parquet"))
+                }
+            })
+            .sum()
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_flush_table() {
+        let instance = crate::tests::create_distributed_instance("test_flush_table").await;
+        let dist_instance = &instance.dist_instance;
+
+        let sql = "
+            CREATE TABLE dist_flush (
+                ts BIGINT,
+                n INT,
+                TIME INDEX (ts),
+            )
+            PARTITION BY RANGE COLUMNS (n) (
+                PARTITION r0 VALUES LESS THAN (10),
+                PARTITION r1 VALUES LESS THAN (20),
+                PARTITION r2 VALUES LESS THAN (50),
+                PARTITION r3 VALUES LESS THAN (MAXVALUE),
+            )
+            ENGINE=mito";
+        dist_instance
+            .handle_sql(sql, QueryContext::arc())
+            .await
+            .remove(0)
+            .unwrap();
+
+        // One row for each region.
+        let sql = "INSERT INTO dist_flush(ts, n) VALUES (1, 1), (2, 11), (3, 21), (4, 51)";
+        let output = dist_instance
+            .handle_sql(sql, QueryContext::arc())
+            .await
+            .remove(0)
+            .unwrap();
+        assert!(matches!(output, Output::AffectedRows(4)));
+
+        let data_dirs = instance
+            .guards
+            .iter()
+            .map(|guard| guard.data_tmp_dir.path())
+            .collect::<Vec<_>>();
+        for dir in &data_dirs {
+            assert_eq!(0, count_sst_files(dir));
+        }
+
+        let output = dist_instance
+            .handle_sql("FLUSH TABLE dist_flush", QueryContext::arc())
+            .await
+            .remove(0)
+            .unwrap();
+        assert!(matches!(output, Output::AffectedRows(0)));
+
+        // Each datanode leads one region of the table, and flushes it to one SST.
+        for dir in &data_dirs {
+            assert_eq!(1, count_sst_files(dir));
+        }
+    }
 }
@@ -33,6 +33,7 @@ impl GrpcQueryHandler for DistInstance {
         })?;
         match request {
             Request::Insert(request) => self.handle_dist_insert(request).await,
+            Request::Delete(request) => self.handle_dist_delete(request).await,
             Request::Query(_) => {
                 unreachable!("Query should have been handled directly in Frontend Instance!")
             }
@@ -61,9 +61,9 @@ impl GrpcQueryHandler for Instance {
                     }
                 }
             }
-            Request::Ddl(request) => {
+            Request::Ddl(_) | Request::Delete(_) => {
                 let query = GreptimeRequest {
-                    request: Some(Request::Ddl(request)),
+                    request: Some(request),
                 };
                 GrpcQueryHandler::do_query(&*self.grpc_query_handler, query).await?
             }
@@ -18,6 +18,7 @@ use api::v1::GreptimeRequest;
 use async_trait::async_trait;
 use common_query::Output;
 use datanode::error::Error as DatanodeError;
+use query::parser::PromQuery;
 use servers::query_handler::grpc::{GrpcQueryHandler, GrpcQueryHandlerRef};
 use servers::query_handler::sql::{SqlQueryHandler, SqlQueryHandlerRef};
 use session::context::QueryContextRef;
@@ -58,6 +59,17 @@ impl SqlQueryHandler for StandaloneSqlQueryHandler {
             .context(error::InvokeDatanodeSnafu)
     }
 
+    async fn do_promql_query(
+        &self,
+        query: &PromQuery,
+        query_ctx: QueryContextRef,
+    ) -> Result<Output> {
+        self.0
+            .do_promql_query(query, query_ctx)
+            .await
+            .context(error::InvokeDatanodeSnafu)
+    }
+
     fn is_valid_schema(&self, catalog: &str, schema: &str) -> Result<bool> {
         self.0
             .is_valid_schema(catalog, schema)
@@ -20,7 +20,7 @@ use datatypes::value::Value;
 use datatypes::vectors::VectorRef;
 use snafu::{ensure, OptionExt};
 use store_api::storage::RegionNumber;
-use table::requests::InsertRequest;
+use table::requests::{DeleteRequest, InsertRequest};
 
 use crate::error::{
     Error, FindPartitionColumnSnafu, FindRegionSnafu, InvalidInsertRequestSnafu, Result,
@@ -28,6 +28,7 @@ use crate::error::{
 use crate::partitioning::PartitionRuleRef;
 
 pub type DistInsertRequest = HashMap<RegionNumber, InsertRequest>;
+pub type DistDeleteRequest = HashMap<RegionNumber, DeleteRequest>;
 
 pub struct WriteSpliter {
     partition_rule: PartitionRuleRef<Error>,
@@ -44,12 +45,29 @@ impl WriteSpliter {
         check_req(&insert)?;
 
         let column_names = self.partition_rule.partition_columns();
-        let partition_columns = find_partitioning_values(&insert, &column_names)?;
+        let partition_columns = find_partitioning_values(&insert.columns_values, &column_names)?;
         let region_map = self.split_partitioning_values(&partition_columns)?;
 
         Ok(partition_insert_request(&insert, region_map))
     }
 
+    /// Splits the delete request by regions, so the partition columns must be
+    /// in the key columns of the request.
+    pub fn split_delete(&self, delete: DeleteRequest) -> Result<DistDeleteRequest> {
+        let column_names = self.partition_rule.partition_columns();
+        let partition_columns = find_partitioning_values(&delete.key_column_values, &column_names)?;
+        let region_map = self.split_partitioning_values(&partition_columns)?;
+
+        Ok(
+            partition_columns_values(&delete.key_column_values, region_map)
+                .into_iter()
+                .map(|(region_id, key_column_values)| {
+                    (region_id, DeleteRequest { key_column_values })
+                })
+                .collect(),
+        )
+    }
+
     fn split_partitioning_values(
         &self,
         values: &[VectorRef],
@@ -100,11 +118,9 @@ fn check_vectors_len(insert: &InsertRequest) -> Result<()> {
 }
 
 fn find_partitioning_values(
-    insert: &InsertRequest,
+    values: &HashMap<String, VectorRef>,
     partition_columns: &[String],
 ) -> Result<Vec<VectorRef>> {
-    let values = &insert.columns_values;
-
     partition_columns
         .iter()
         .map(|column_name| {
@@ -127,23 +143,41 @@ fn partition_insert_request(
     insert: &InsertRequest,
     region_map: HashMap<RegionNumber, Vec<usize>>,
 ) -> DistInsertRequest {
-    let mut dist_insert: HashMap<RegionNumber, HashMap<&str, Box<dyn MutableVector>>> =
+    let catalog_name = &insert.catalog_name;
+    let schema_name = &insert.schema_name;
+    let table_name = &insert.table_name;
+    partition_columns_values(&insert.columns_values, region_map)
+        .into_iter()
+        .map(|(region_id, columns_values)| {
+            (
+                region_id,
+                InsertRequest {
+                    catalog_name: catalog_name.to_string(),
+                    schema_name: schema_name.to_string(),
+                    table_name: table_name.to_string(),
+                    columns_values,
+                },
+            )
+        })
+        .collect()
+}
+
+fn partition_columns_values(
+    columns_values: &HashMap<String, VectorRef>,
+    region_map: HashMap<RegionNumber, Vec<usize>>,
+) -> HashMap<RegionNumber, HashMap<String, VectorRef>> {
+    let mut dist_columns: HashMap<RegionNumber, HashMap<&str, Box<dyn MutableVector>>> =
         HashMap::with_capacity(region_map.len());
 
-    let row_num = insert
-        .columns_values
-        .values()
-        .next()
-        .map(|v| v.len())
-        .unwrap_or(0);
+    let row_num = columns_values.values().next().map(|v| v.len()).unwrap_or(0);
 
-    let column_count = insert.columns_values.len();
-    for (column_name, vector) in &insert.columns_values {
+    let column_count = columns_values.len();
+    for (column_name, vector) in columns_values {
         for (region_id, val_idxs) in &region_map {
-            let region_insert = dist_insert
+            let region_columns = dist_columns
                 .entry(*region_id)
                 .or_insert_with(|| HashMap::with_capacity(column_count));
-            let builder = region_insert
+            let builder = region_columns
                 .entry(column_name)
                 .or_insert_with(|| vector.data_type().create_mutable_vector(row_num));
             val_idxs.iter().for_each(|idx| {
@@ -155,25 +189,14 @@ fn partition_insert_request(
         }
     }
 
-    let catalog_name = &insert.catalog_name;
-    let schema_name = &insert.schema_name;
-    let table_name = &insert.table_name;
-    dist_insert
+    dist_columns
         .into_iter()
         .map(|(region_id, vector_map)| {
             let columns_values = vector_map
                 .into_iter()
                 .map(|(column_name, mut builder)| (column_name.to_string(), builder.to_vector()))
                 .collect();
-            (
-                region_id,
-                InsertRequest {
-                    catalog_name: catalog_name.to_string(),
-                    schema_name: schema_name.to_string(),
-                    table_name: table_name.to_string(),
-                    columns_values,
-                },
-            )
+            (region_id, columns_values)
         })
         .collect()
 }
@@ -195,7 +218,7 @@ mod tests {
     };
     use serde::{Deserialize, Serialize};
     use store_api::storage::RegionNumber;
-    use table::requests::InsertRequest;
+    use table::requests::{DeleteRequest, InsertRequest};
 
     use super::{
         check_req, find_partitioning_values, partition_insert_request, partition_values,
@@ -270,6 +293,40 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_delete_spliter() {
+        let insert = mock_insert_request();
+        let mut key_column_values = insert.columns_values;
+        key_column_values.remove("enable_reboot");
+        let delete = DeleteRequest { key_column_values };
+
+        let rule = Arc::new(MockPartitionRule) as PartitionRuleRef<Error>;
+        let spliter = WriteSpliter::with_partition_rule(rule);
+        let ret = spliter.split_delete(delete).unwrap();
+
+        assert_eq!(2, ret.len());
+
+        let r1_columns = &ret.get(&0).unwrap().key_column_values;
+        assert_eq!(2, r1_columns.len());
+        assert_eq!(1, r1_columns.get("id").unwrap().len());
+        assert_eq!(Value::from(1_i16), r1_columns.get("id").unwrap().get(0));
+        assert_eq!(Value::from("host1"), r1_columns.get("host").unwrap().get(0));
+
+        let r2_columns = &ret.get(&1).unwrap().key_column_values;
+        assert_eq!(2, r2_columns.len());
+        assert_eq!(2, r2_columns.get("id").unwrap().len());
+        assert_eq!(Value::from(2_i16), r2_columns.get("id").unwrap().get(0));
+        assert_eq!(Value::from(3_i16), r2_columns.get("id").unwrap().get(1));
+        assert_eq!(Value::Null, r2_columns.get("host").unwrap().get(0));
+        assert_eq!(Value::from("host3"), r2_columns.get("host").unwrap().get(1));
+
+        // Missing the partition column.
+        let mut key_column_values = mock_insert_request().columns_values;
+        key_column_values.remove("id");
+        let delete = DeleteRequest { key_column_values };
+        assert!(spliter.split_delete(delete).is_err());
+    }
+
     #[test]
     fn test_partition_insert_request() {
         let insert = mock_insert_request();
@@ -338,7 +395,8 @@ mod tests {
         let insert = mock_insert_request();
 
         let partition_column_names = vec!["host".to_string(), "id".to_string()];
-        let columns = find_partitioning_values(&insert, &partition_column_names).unwrap();
+        let columns =
+            find_partitioning_values(&insert.columns_values, &partition_column_names).unwrap();
 
         let host_column = columns[0].clone();
         assert_eq!(
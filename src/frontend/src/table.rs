@@ -46,7 +46,7 @@ use snafu::prelude::*;
 use store_api::storage::RegionNumber;
 use table::error::TableOperationSnafu;
 use table::metadata::{FilterPushDownType, TableInfo, TableInfoRef};
-use table::requests::{AlterTableRequest, InsertRequest};
+use table::requests::{AlterTableRequest, DeleteRequest, InsertRequest};
 use table::table::AlterContext;
 use table::Table;
 use tokio::sync::RwLock;
@@ -65,6 +65,7 @@ use crate::spliter::WriteSpliter;
 use crate::table::route::TableRoutes;
 use crate::table::scan::{DatanodeInstance, TableScanPlan};
 
+pub mod delete;
 pub mod insert;
 pub(crate) mod scan;
 
@@ -169,6 +170,35 @@ impl Table for DistTable {
             .map_err(BoxedError::new)
             .context(TableOperationSnafu)
     }
+
+    async fn delete(&self, request: DeleteRequest) -> table::Result<usize> {
+        let partition_rule = self
+            .find_partition_rule()
+            .await
+            .map_err(BoxedError::new)
+            .context(TableOperationSnafu)?;
+
+        let spliter = WriteSpliter::with_partition_rule(partition_rule);
+        let deletes = spliter
+            .split_delete(request)
+            .map_err(BoxedError::new)
+            .context(TableOperationSnafu)?;
+
+        let output = self
+            .dist_delete(deletes)
+            .await
+            .map_err(BoxedError::new)
+            .context(TableOperationSnafu)?;
+        let Output::AffectedRows(rows) = output else { unreachable!() };
+        Ok(rows)
+    }
+
+    async fn flush(&self) -> table::Result<()> {
+        self.flush_datanodes()
+            .await
+            .map_err(BoxedError::new)
+            .context(TableOperationSnafu)
+    }
 }
 
 impl DistTable {
@@ -479,6 +509,47 @@ impl DistTable {
         }
         Ok(())
     }
+
+    /// Flushes the regions of this table on every datanode that leads them.
+    async fn flush_datanodes(&self) -> Result<()> {
+        let table_routes = self.table_routes.get_route(&self.table_name).await?;
+        let leaders = table_routes.find_leaders();
+        ensure!(
+            !leaders.is_empty(),
+            LeaderNotFoundSnafu {
+                table: self.table_name.to_string()
+            }
+        );
+        let sql = flush_table_sql(&self.table_name);
+        let mut joins = Vec::with_capacity(leaders.len());
+        for datanode in leaders {
+            let db = Database::new(
+                &self.table_name.schema_name,
+                self.datanode_clients.get_client(&datanode).await,
+            );
+            let sql = sql.clone();
+            debug!("Sending {} to {:?}", sql, db);
+            joins.push(tokio::spawn(async move {
+                db.sql(&sql).await.context(RequestDatanodeSnafu)
+            }));
+        }
+        for join in joins {
+            let _ = join.await.context(error::JoinTaskSnafu)??;
+        }
+        Ok(())
+    }
+}
+
+/// Builds the `FLUSH TABLE` statement sent to datanodes. Identifiers are quoted, so
+/// names with special characters or keywords are flushed as they are.
+fn flush_table_sql(table_name: &TableName) -> String {
+    let quote = |ident: &str| format!("\"{}\"", ident.replace('"', "\"\""));
+    format!(
+        "FLUSH TABLE {}.{}.{}",
+        quote(&table_name.catalog_name),
+        quote(&table_name.schema_name),
+        quote(&table_name.table_name)
+    )
 }
 
 fn project_schema(table_schema: SchemaRef, projection: Option<&Vec<usize>>) -> SchemaRef {
@@ -664,6 +735,27 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_flush_table_sql() {
+        let table_name = TableName::new("greptime", "my-schema", "select \"a\".b");
+        let sql = flush_table_sql(&table_name);
+        let stmt = ParserContext::create_with_dialect(&sql, &sqlparser::dialect::GenericDialect {})
+            .unwrap()
+            .pop()
+            .unwrap();
+        let Statement::FlushTable(flush) = stmt else {
+            unreachable!()
+        };
+        assert_eq!(
+            (
+                "greptime".to_string(),
+                "my-schema".to_string(),
+                "select \"a\".b".to_string()
+            ),
+            flush.full_table_name().unwrap()
+        );
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn test_find_partition_rule() {
         let table_name = TableName::new("greptime", "public", "foo");
@@ -0,0 +1,130 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+
+use api::v1::DeleteRequest as GrpcDeleteRequest;
+use common_query::Output;
+use snafu::ResultExt;
+use store_api::storage::RegionNumber;
+use table::requests::DeleteRequest;
+
+use super::DistTable;
+use crate::error;
+use crate::error::Result;
+use crate::table::insert::columns_values_to_columns;
+
+impl DistTable {
+    pub async fn dist_delete(
+        &self,
+        deletes: HashMap<RegionNumber, DeleteRequest>,
+    ) -> Result<Output> {
+        let route = self.table_routes.get_route(&self.table_name).await?;
+
+        let mut joins = Vec::with_capacity(deletes.len());
+        for (region_id, delete) in deletes {
+            let instance = self.region_instance(&route, region_id).await?;
+            let request = to_grpc_delete_request(
+                &self.table_name.schema_name,
+                &self.table_name.table_name,
+                region_id,
+                delete,
+            )?;
+
+            // TODO(fys): a separate runtime should be used here.
+            let join = tokio::spawn(async move {
+                instance
+                    .grpc_delete(request)
+                    .await
+                    .context(error::RequestDatanodeSnafu)
+            });
+
+            joins.push(join);
+        }
+
+        let mut success = 0;
+        for join in joins {
+            let object_result = join.await.context(error::JoinTaskSnafu)??;
+            let Output::AffectedRows(rows) = object_result else { unreachable!() };
+            success += rows;
+        }
+        Ok(Output::AffectedRows(success))
+    }
+}
+
+fn to_grpc_delete_request(
+    schema_name: &str,
+    table_name: &str,
+    region_number: RegionNumber,
+    delete: DeleteRequest,
+) -> Result<GrpcDeleteRequest> {
+    let (key_columns, row_count) = columns_values_to_columns(&delete.key_column_values)?;
+    Ok(GrpcDeleteRequest {
+        schema_name: schema_name.to_string(),
+        table_name: table_name.to_string(),
+        key_columns,
+        row_count,
+        region_number,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use api::v1::ColumnDataType;
+    use datatypes::prelude::ScalarVectorBuilder;
+    use datatypes::vectors::{
+        MutableVector, StringVectorBuilder, TimestampMillisecondVectorBuilder,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_to_grpc_delete_request() {
+        let mut key_column_values = HashMap::with_capacity(2);
+
+        let mut builder = StringVectorBuilder::with_capacity(2);
+        builder.push(Some("host1"));
+        builder.push(Some("host2"));
+        key_column_values.insert("host".to_string(), builder.to_vector());
+
+        let mut builder = TimestampMillisecondVectorBuilder::with_capacity(2);
+        builder.push(Some(1000.into()));
+        builder.push(Some(2000.into()));
+        key_column_values.insert("ts".to_string(), builder.to_vector());
+
+        let request =
+            to_grpc_delete_request("public", "demo", 12, DeleteRequest { key_column_values })
+                .unwrap();
+
+        assert_eq!("public", request.schema_name);
+        assert_eq!("demo", request.table_name);
+        assert_eq!(12, request.region_number);
+        assert_eq!(2, request.row_count);
+        assert_eq!(2, request.key_columns.len());
+        for column in request.key_columns {
+            let values = column.values.unwrap();
+            match column.column_name.as_str() {
+                "host" => {
+                    assert_eq!(ColumnDataType::String as i32, column.datatype);
+                    assert_eq!(vec!["host1", "host2"], values.string_values);
+                }
+                "ts" => {
+                    assert_eq!(ColumnDataType::TimestampMillisecond as i32, column.datatype);
+                    assert_eq!(vec![1000, 2000], values.ts_millisecond_values);
+                }
+                _ => unreachable!(),
+            }
+        }
+    }
+}
@@ -21,6 +21,8 @@ use api::v1::{Column, InsertRequest as GrpcInsertRequest};
 use client::Database;
 use common_query::Output;
 use datatypes::prelude::ConcreteDataType;
+use datatypes::vectors::VectorRef;
+use meta_client::rpc::TableRoute;
 use snafu::{ensure, OptionExt, ResultExt};
 use store_api::storage::RegionNumber;
 use table::requests::InsertRequest;
@@ -39,21 +41,7 @@ impl DistTable {
 
         let mut joins = Vec::with_capacity(inserts.len());
         for (region_id, insert) in inserts {
-            let datanode = route
-                .region_routes
-                .iter()
-                .find_map(|x| {
-                    if x.region.id == region_id as u64 {
-                        x.leader_peer.clone()
-                    } else {
-                        None
-                    }
-                })
-                .context(error::FindDatanodeSnafu { region: region_id })?;
-
-            let client = self.datanode_clients.get_client(&datanode).await;
-            let db = Database::new(&self.table_name.schema_name, client);
-            let instance = DatanodeInstance::new(Arc::new(self.clone()) as _, db);
+            let instance = self.region_instance(&route, region_id).await?;
 
             // TODO(fys): a separate runtime should be used here.
             let join = tokio::spawn(async move {
@@ -74,13 +62,42 @@ impl DistTable {
         }
         Ok(Output::AffectedRows(success))
     }
+
+    /// Returns the instance of the datanode where the leader of the region is.
+    pub(crate) async fn region_instance(
+        &self,
+        route: &TableRoute,
+        region_id: RegionNumber,
+    ) -> Result<DatanodeInstance> {
+        let datanode = route
+            .region_routes
+            .iter()
+            .find_map(|x| {
+                if x.region.id == region_id as u64 {
+                    x.leader_peer.clone()
+                } else {
+                    None
+                }
+            })
+            .context(error::FindDatanodeSnafu { region: region_id })?;
+
+        let client = self.datanode_clients.get_client(&datanode).await;
+        let db = Database::new(&self.table_name.schema_name, client);
+        Ok(DatanodeInstance::new(Arc::new(self.clone()) as _, db))
+    }
 }
 
 pub fn insert_request_to_insert_batch(insert: &InsertRequest) -> Result<(Vec<Column>, u32)> {
+    columns_values_to_columns(&insert.columns_values)
+}
+
+/// Converts vectors of columns to gRPC columns, returns the columns and the row count.
+pub(crate) fn columns_values_to_columns(
+    columns_values: &HashMap<String, VectorRef>,
+) -> Result<(Vec<Column>, u32)> {
     let mut row_count = None;
 
-    let columns = insert
-        .columns_values
+    let columns = columns_values
         .iter()
         .map(|(column_name, vector)| {
             match row_count {
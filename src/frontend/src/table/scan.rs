@@ -15,7 +15,7 @@
 use std::fmt::Formatter;
 use std::sync::Arc;
 
-use api::v1::InsertRequest;
+use api::v1::{DeleteRequest, InsertRequest};
 use client::Database;
 use common_query::prelude::Expr;
 use common_query::Output;
@@ -51,6 +51,10 @@ impl DatanodeInstance {
         self.db.insert(request).await
     }
 
+    pub(crate) async fn grpc_delete(&self, request: DeleteRequest) -> client::Result<Output> {
+        self.db.delete(request).await
+    }
+
     pub(crate) async fn grpc_table_scan(&self, plan: TableScanPlan) -> Result<RecordBatches> {
         let logical_plan = self.build_logical_plan(&plan)?;
 
@@ -45,14 +45,14 @@ use crate::table::route::TableRoutes;
 /// (The `TempDir` will be deleted once it goes out of scope.)
 pub struct TestGuard {
     _wal_tmp_dir: TempDir,
-    _data_tmp_dir: TempDir,
+    pub(crate) data_tmp_dir: TempDir,
 }
 
 pub(crate) struct MockDistributedInstance {
     pub(crate) frontend: Arc<Instance>,
     pub(crate) dist_instance: Arc<DistInstance>,
     pub(crate) datanodes: HashMap<u64, Arc<DatanodeInstance>>,
-    _guards: Vec<TestGuard>,
+    pub(crate) guards: Vec<TestGuard>,
 }
 
 pub(crate) struct MockStandaloneInstance {
@@ -91,7 +91,7 @@ fn create_tmp_dir_and_datanode_opts(name: &str) -> (DatanodeOptions, TestGuard)
         opts,
         TestGuard {
             _wal_tmp_dir: wal_tmp_dir,
-            _data_tmp_dir: data_tmp_dir,
+            data_tmp_dir,
         },
     )
 }
@@ -185,7 +185,7 @@ async fn create_distributed_datanode(
         instance,
         TestGuard {
             _wal_tmp_dir: wal_tmp_dir,
-            _data_tmp_dir: data_tmp_dir,
+            data_tmp_dir,
         },
     )
 }
@@ -262,6 +262,6 @@ pub(crate) async fn create_distributed_instance(test_name: &str) -> MockDistribu
         frontend: Arc::new(frontend),
         dist_instance,
         datanodes: datanode_instances,
-        _guards: test_guards,
+        guards: test_guards,
     }
 }
@@ -42,6 +42,12 @@ pub enum Error {
     #[snafu(display("Log store not started yet"))]
     IllegalState { backtrace: Backtrace },
 
+    #[snafu(display("Log store is opened read-only"))]
+    ReadOnly { backtrace: Backtrace },
+
+    #[snafu(display("Log directory not found: {}", dir))]
+    LogDirNotFound { dir: String, backtrace: Backtrace },
+
     #[snafu(display("Namespace is illegal: {}", ns))]
     IllegalNamespace { ns: u64, backtrace: Backtrace },
 
@@ -61,6 +67,16 @@ pub enum Error {
         source: raft_engine::Error,
         backtrace: Backtrace,
     },
+
+    #[snafu(display("Failed to {} in Kafka, source: {}", operation, source))]
+    Kafka {
+        operation: String,
+        source: rskafka::client::error::Error,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Kafka record does not have a valid entry id as its key"))]
+    InvalidKafkaRecord { backtrace: Backtrace },
 }
 
 impl ErrorExt for Error {
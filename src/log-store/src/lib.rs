@@ -16,6 +16,7 @@ mod config;
 pub mod error;
 mod noop;
 pub mod raft_engine;
+pub mod remote;
 pub mod test_util;
 
 pub use config::LogConfig;
@@ -65,7 +65,7 @@ impl LogStore for NoopLogStore {
         Ok(AppendResponse { entry_id: 0 })
     }
 
-    async fn append_batch(&self, _ns: &Self::Namespace, _e: Vec<Self::Entry>) -> Result<Vec<Id>> {
+    async fn append_batch(&self, _e: Vec<Self::Entry>) -> Result<Vec<Id>> {
         Ok(vec![])
     }
 
@@ -129,10 +129,7 @@ mod tests {
         let mut store = NoopLogStore::default();
         let e = store.entry("".as_bytes(), 1, NamespaceImpl::default());
         store.append(e.clone()).await.unwrap();
-        store
-            .append_batch(&NamespaceImpl::default(), vec![e])
-            .await
-            .unwrap();
+        store.append_batch(vec![e]).await.unwrap();
         store
             .create_namespace(&NamespaceImpl::default())
             .await
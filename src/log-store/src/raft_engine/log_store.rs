@@ -12,7 +12,9 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::BTreeMap;
 use std::fmt::{Debug, Formatter};
+use std::path::Path;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 
@@ -31,7 +33,7 @@ use tokio_util::sync::CancellationToken;
 use crate::config::LogConfig;
 use crate::error::{
     AddEntryLogBatchSnafu, Error, FetchEntrySnafu, IllegalNamespaceSnafu, IllegalStateSnafu,
-    RaftEngineSnafu, WaitGcTaskStopSnafu,
+    LogDirNotFoundSnafu, RaftEngineSnafu, ReadOnlySnafu, WaitGcTaskStopSnafu,
 };
 use crate::raft_engine::protos::logstore::{EntryImpl as Entry, NamespaceImpl as Namespace};
 
@@ -44,6 +46,7 @@ pub struct RaftEngineLogStore {
     cancel_token: Mutex<Option<CancellationToken>>,
     gc_task_handle: Mutex<Option<JoinHandle<()>>>,
     started: AtomicBool,
+    read_only: bool,
 }
 
 impl RaftEngineLogStore {
@@ -64,15 +67,56 @@ impl RaftEngineLogStore {
             cancel_token: Mutex::new(None),
             gc_task_handle: Mutex::new(None),
             started: AtomicBool::new(false),
+            read_only: false,
         };
         log_store.start().await?;
         Ok(log_store)
     }
 
+    /// Opens an existing log store for reading only, e.g. to inspect it offline.
+    ///
+    /// Unlike [`RaftEngineLogStore::try_new`], this never creates the log directory, fails
+    /// instead of truncating a corrupted tail during recovery and doesn't start the purge task.
+    /// Writes to the returned store are rejected.
+    pub fn open_read_only(config: LogConfig) -> Result<Self, Error> {
+        ensure!(
+            Path::new(&config.log_file_dir).is_dir(),
+            LogDirNotFoundSnafu {
+                dir: &config.log_file_dir,
+            }
+        );
+        let raft_engine_config = Config {
+            dir: config.log_file_dir.clone(),
+            purge_threshold: ReadableSize(u64::MAX),
+            recovery_mode: RecoveryMode::AbsoluteConsistency,
+            target_file_size: ReadableSize(config.file_size),
+            ..Default::default()
+        };
+        let engine = Arc::new(Engine::open(raft_engine_config).context(RaftEngineSnafu)?);
+        info!(
+            "RaftEngineLogStore opened read-only with config: {:?}",
+            config
+        );
+        Ok(Self {
+            config,
+            engine,
+            cancel_token: Mutex::new(None),
+            gc_task_handle: Mutex::new(None),
+            started: AtomicBool::new(true),
+            read_only: true,
+        })
+    }
+
     pub fn started(&self) -> bool {
         self.started.load(Ordering::Relaxed)
     }
 
+    fn ensure_writable(&self) -> Result<(), Error> {
+        ensure!(self.started(), IllegalStateSnafu);
+        ensure!(!self.read_only, ReadOnlySnafu);
+        Ok(())
+    }
+
     async fn start(&self) -> Result<(), Error> {
         let engine_clone = self.engine.clone();
         let interval = self.config.purge_interval;
@@ -133,6 +177,10 @@ impl LogStore for RaftEngineLogStore {
                 .is_ok(),
             IllegalStateSnafu
         );
+        if self.read_only {
+            info!("RaftEngineLogStore stopped");
+            return Ok(());
+        }
         let handle = self
             .gc_task_handle
             .lock()
@@ -153,7 +201,7 @@ impl LogStore for RaftEngineLogStore {
 
     /// Append an entry to logstore. Currently of existence of entry's namespace is not checked.
     async fn append(&self, e: Self::Entry) -> Result<AppendResponse, Self::Error> {
-        ensure!(self.started(), IllegalStateSnafu);
+        self.ensure_writable()?;
         let entry_id = e.id;
         let mut batch = LogBatch::with_capacity(1);
         batch
@@ -167,18 +215,21 @@ impl LogStore for RaftEngineLogStore {
     }
 
     /// Append a batch of entries to logstore. `RaftEngineLogStore` assures the atomicity of
-    /// batch append.
-    async fn append_batch(
-        &self,
-        ns: &Self::Namespace,
-        entries: Vec<Self::Entry>,
-    ) -> Result<Vec<Id>, Self::Error> {
-        ensure!(self.started(), IllegalStateSnafu);
+    /// batch append, entries of all namespaces are written in one write.
+    async fn append_batch(&self, entries: Vec<Self::Entry>) -> Result<Vec<Id>, Self::Error> {
+        self.ensure_writable()?;
         let entry_ids = entries.iter().map(Entry::get_id).collect::<Vec<_>>();
-        let mut batch = LogBatch::with_capacity(entries.len());
-        batch
-            .add_entries::<MessageType>(ns.id, &entries)
-            .context(AddEntryLogBatchSnafu)?;
+        // Entries of the same namespace keep their order.
+        let mut entries_by_ns: BTreeMap<u64, Vec<Entry>> = BTreeMap::new();
+        for e in entries {
+            entries_by_ns.entry(e.namespace_id).or_default().push(e);
+        }
+        let mut batch = LogBatch::with_capacity(entry_ids.len());
+        for (ns, entries) in entries_by_ns {
+            batch
+                .add_entries::<MessageType>(ns, &entries)
+                .context(AddEntryLogBatchSnafu)?;
+        }
         self.engine
             .write(&mut batch, self.config.sync_write)
             .context(RaftEngineSnafu)?;
@@ -248,7 +299,7 @@ impl LogStore for RaftEngineLogStore {
             ns.id != SYSTEM_NAMESPACE,
             IllegalNamespaceSnafu { ns: ns.id }
         );
-        ensure!(self.started(), IllegalStateSnafu);
+        self.ensure_writable()?;
         let key = format!("{}{}", NAMESPACE_PREFIX, ns.id).as_bytes().to_vec();
         let mut batch = LogBatch::with_capacity(1);
         batch
@@ -265,7 +316,7 @@ impl LogStore for RaftEngineLogStore {
             ns.id != SYSTEM_NAMESPACE,
             IllegalNamespaceSnafu { ns: ns.id }
         );
-        ensure!(self.started(), IllegalStateSnafu);
+        self.ensure_writable()?;
         let key = format!("{}{}", NAMESPACE_PREFIX, ns.id).as_bytes().to_vec();
         let mut batch = LogBatch::with_capacity(1);
         batch.delete(SYSTEM_NAMESPACE, key);
@@ -310,7 +361,7 @@ impl LogStore for RaftEngineLogStore {
     }
 
     async fn obsolete(&self, namespace: Self::Namespace, id: Id) -> Result<(), Self::Error> {
-        ensure!(self.started(), IllegalStateSnafu);
+        self.ensure_writable()?;
         let obsoleted = self.engine.compact_to(namespace.id(), id + 1);
         info!(
             "Namespace {} obsoleted {} entries",
@@ -424,6 +475,31 @@ mod tests {
         assert_eq!((0..cnt).into_iter().collect::<HashSet<_>>(), entries);
     }
 
+    #[tokio::test]
+    async fn test_append_batch_of_namespaces() {
+        let dir = TempDir::new("raft-engine-logstore-test").unwrap();
+        let logstore = RaftEngineLogStore::try_new(LogConfig {
+            log_file_dir: dir.path().to_str().unwrap().to_string(),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+        let entries = (0..10)
+            .map(|i| Entry::create(i / 2, i % 2, i.to_string().as_bytes().to_vec()))
+            .collect();
+        let ids = logstore.append_batch(entries).await.unwrap();
+        assert_eq!(vec![0, 0, 1, 1, 2, 2, 3, 3, 4, 4], ids);
+
+        for ns in 0..2 {
+            let entries =
+                collect_entries(logstore.read(&Namespace::with_id(ns), 0).await.unwrap()).await;
+            let ids: Vec<_> = entries.iter().map(|e| e.id).collect();
+            assert_eq!(vec![0, 1, 2, 3, 4], ids);
+            assert!(entries.iter().all(|e| e.namespace_id == ns));
+        }
+    }
+
     async fn collect_entries(mut s: SendableEntryStream<'_, Entry, Error>) -> Vec<Entry> {
         let mut res = vec![];
         while let Some(r) = s.next().await {
@@ -471,6 +547,50 @@ mod tests {
         assert_eq!(1, entries[0].namespace_id);
     }
 
+    #[tokio::test]
+    async fn test_open_read_only() {
+        let dir = TempDir::new("raft-engine-logstore-read-only-test").unwrap();
+        let missing = dir.path().join("missing").to_str().unwrap().to_string();
+        let err = RaftEngineLogStore::open_read_only(LogConfig {
+            log_file_dir: missing.clone(),
+            ..Default::default()
+        })
+        .unwrap_err();
+        assert!(matches!(err, Error::LogDirNotFound { .. }), "{err:?}");
+        assert!(!std::path::Path::new(&missing).exists());
+
+        {
+            let logstore = RaftEngineLogStore::try_new(LogConfig {
+                log_file_dir: dir.path().to_str().unwrap().to_string(),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+            logstore
+                .append(Entry::create(1, 1, "1".as_bytes().to_vec()))
+                .await
+                .unwrap();
+            logstore.stop().await.unwrap();
+        }
+
+        let logstore = RaftEngineLogStore::open_read_only(LogConfig {
+            log_file_dir: dir.path().to_str().unwrap().to_string(),
+            ..Default::default()
+        })
+        .unwrap();
+        let entries =
+            collect_entries(logstore.read(&Namespace::with_id(1), 1).await.unwrap()).await;
+        assert_eq!(1, entries.len());
+        assert_eq!(1, entries[0].id);
+
+        let err = logstore
+            .append(Entry::create(2, 1, "2".as_bytes().to_vec()))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::ReadOnly { .. }), "{err:?}");
+        logstore.stop().await.unwrap();
+    }
+
     async fn wal_dir_usage(path: impl AsRef<str>) -> usize {
         let mut size: usize = 0;
         let mut read_dir = tokio::fs::read_dir(path.as_ref()).await.unwrap();
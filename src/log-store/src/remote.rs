@@ -0,0 +1,90 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A log store backed by a shared remote log service, so the WAL of a region outlives
+//! the datanode that writes it.
+
+use std::hash::{Hash, Hasher};
+
+use store_api::logstore::entry::{Entry, Id};
+use store_api::logstore::namespace::{Id as NamespaceId, Namespace};
+
+use crate::error::Error;
+
+mod client;
+mod kafka;
+pub mod log_store;
+mod memory;
+
+pub use client::{LogClient, LogClientRef, Record};
+pub use kafka::KafkaLogClient;
+pub use log_store::{RemoteLogConfig, RemoteLogStore};
+pub use memory::MemoryLogClient;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EntryImpl {
+    pub id: Id,
+    pub namespace_id: NamespaceId,
+    pub data: Vec<u8>,
+}
+
+impl EntryImpl {
+    pub fn create(id: Id, ns: NamespaceId, data: Vec<u8>) -> Self {
+        Self {
+            id,
+            namespace_id: ns,
+            data,
+        }
+    }
+}
+
+impl Entry for EntryImpl {
+    type Error = Error;
+    type Namespace = NamespaceImpl;
+
+    fn data(&self) -> &[u8] {
+        self.data.as_slice()
+    }
+
+    fn id(&self) -> Id {
+        self.id
+    }
+
+    fn namespace(&self) -> Self::Namespace {
+        NamespaceImpl::with_id(self.namespace_id)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NamespaceImpl {
+    pub id: NamespaceId,
+}
+
+impl NamespaceImpl {
+    pub fn with_id(id: NamespaceId) -> Self {
+        Self { id }
+    }
+}
+
+impl Hash for NamespaceImpl {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+    }
+}
+
+impl Namespace for NamespaceImpl {
+    fn id(&self) -> NamespaceId {
+        self.id
+    }
+}
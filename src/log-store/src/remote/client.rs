@@ -0,0 +1,57 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt::Debug;
+use std::sync::Arc;
+
+use store_api::logstore::entry::Id;
+
+use crate::error::Result;
+
+/// A record in a topic of the remote log service.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Record {
+    /// Id of the log entry.
+    pub id: Id,
+    pub data: Vec<u8>,
+}
+
+/// Client of a remote log service that stores records in named topics, such as a
+/// Kafka-compatible service.
+///
+/// Records of a topic must be durable and visible to all clients of the service once
+/// they are appended.
+#[async_trait::async_trait]
+pub trait LogClient: Send + Sync + Debug {
+    /// Appends `records` to the `topic`, creates the topic if it does not exist.
+    async fn append(&self, topic: &str, records: Vec<Record>) -> Result<()>;
+
+    /// Reads at most `limit` records whose ids are not less than `start` from the
+    /// `topic`, ordered by their ids. Returns an empty vector if the topic does not exist.
+    async fn read(&self, topic: &str, start: Id, limit: usize) -> Result<Vec<Record>>;
+
+    /// Deletes records whose ids are not greater than `id` from the `topic`.
+    async fn truncate(&self, topic: &str, id: Id) -> Result<()>;
+
+    /// Creates the `topic` if it does not exist.
+    async fn create_topic(&self, topic: &str) -> Result<()>;
+
+    /// Deletes the `topic` and all its records.
+    async fn delete_topic(&self, topic: &str) -> Result<()>;
+
+    /// Lists all topics.
+    async fn list_topics(&self) -> Result<Vec<String>>;
+}
+
+pub type LogClientRef = Arc<dyn LogClient>;
@@ -0,0 +1,253 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::{Debug, Formatter};
+use std::sync::Arc;
+
+use chrono::Utc;
+use common_telemetry::info;
+use rskafka::client::error::{Error as KafkaError, ProtocolError};
+use rskafka::client::partition::{Compression, OffsetAt, PartitionClient};
+use rskafka::client::{Client, ClientBuilder};
+use rskafka::record::Record as KafkaRecord;
+use snafu::{OptionExt, ResultExt};
+use store_api::logstore::entry::Id;
+use tokio::sync::Mutex;
+
+use crate::error::{InvalidKafkaRecordSnafu, KafkaSnafu, Result};
+use crate::remote::client::{LogClient, Record};
+
+/// Each topic only has one partition, so records of a topic are totally ordered.
+const PARTITION: i32 = 0;
+const REPLICATION_FACTOR: i16 = 1;
+const TIMEOUT_MS: i32 = 5_000;
+const FETCH_MAX_WAIT_MS: i32 = 100;
+const FETCH_MAX_BYTES: i32 = 4 * 1024 * 1024;
+
+/// A [LogClient] that stores each topic in a single-partition topic of a Kafka cluster.
+///
+/// The id of a record is stored in the key of the Kafka record as a big-endian u64.
+pub struct KafkaLogClient {
+    endpoints: Vec<String>,
+    client: Client,
+    partitions: Mutex<HashMap<String, Arc<PartitionClient>>>,
+}
+
+impl Debug for KafkaLogClient {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("KafkaLogClient")
+            .field("endpoints", &self.endpoints)
+            .finish()
+    }
+}
+
+impl KafkaLogClient {
+    /// Connects to the Kafka cluster through the bootstrap broker `endpoints`.
+    pub async fn connect(endpoints: Vec<String>) -> Result<Self> {
+        let client = ClientBuilder::new(endpoints.clone())
+            .build()
+            .await
+            .context(KafkaSnafu {
+                operation: "connect",
+            })?;
+        info!("Connected to Kafka brokers: {:?}", endpoints);
+
+        Ok(Self {
+            endpoints,
+            client,
+            partitions: Mutex::new(HashMap::new()),
+        })
+    }
+
+    async fn partition_client(&self, topic: &str) -> Result<Arc<PartitionClient>> {
+        let mut partitions = self.partitions.lock().await;
+        if let Some(client) = partitions.get(topic) {
+            return Ok(client.clone());
+        }
+        let client = Arc::new(self.client.partition_client(topic, PARTITION).context(
+            KafkaSnafu {
+                operation: "create partition client",
+            },
+        )?);
+        partitions.insert(topic.to_string(), client.clone());
+        Ok(client)
+    }
+
+    async fn topic_exists(&self, topic: &str) -> Result<bool> {
+        Ok(self.list_topics().await?.iter().any(|name| name == topic))
+    }
+
+    /// Scans records in `topic` from the earliest offset, calls `f` with the id and
+    /// the offset of each record until `f` returns false.
+    async fn scan<F>(&self, topic: &str, mut f: F) -> Result<()>
+    where
+        F: FnMut(Id, i64, Vec<u8>) -> bool,
+    {
+        let partition = self.partition_client(topic).await?;
+        let mut offset = partition
+            .get_offset(OffsetAt::Earliest)
+            .await
+            .context(KafkaSnafu {
+                operation: "get earliest offset",
+            })?;
+        loop {
+            let (records, high_watermark) = partition
+                .fetch_records(offset, 1..FETCH_MAX_BYTES, FETCH_MAX_WAIT_MS)
+                .await
+                .context(KafkaSnafu {
+                    operation: "fetch records",
+                })?;
+            for record in records {
+                offset = record.offset + 1;
+                let id = decode_id(&record.record)?;
+                if !f(id, record.offset, record.record.value.unwrap_or_default()) {
+                    return Ok(());
+                }
+            }
+            if offset >= high_watermark {
+                return Ok(());
+            }
+        }
+    }
+}
+
+fn encode_id(id: Id) -> Vec<u8> {
+    id.to_be_bytes().to_vec()
+}
+
+fn decode_id(record: &KafkaRecord) -> Result<Id> {
+    let key = record
+        .key
+        .as_ref()
+        .and_then(|key| <[u8; 8]>::try_from(key.as_slice()).ok())
+        .context(InvalidKafkaRecordSnafu)?;
+    Ok(Id::from_be_bytes(key))
+}
+
+#[async_trait::async_trait]
+impl LogClient for KafkaLogClient {
+    async fn append(&self, topic: &str, records: Vec<Record>) -> Result<()> {
+        self.create_topic(topic).await?;
+        let partition = self.partition_client(topic).await?;
+        let now = Utc::now();
+        let records = records
+            .into_iter()
+            .map(|record| KafkaRecord {
+                key: Some(encode_id(record.id)),
+                value: Some(record.data),
+                headers: BTreeMap::new(),
+                timestamp: now,
+            })
+            .collect();
+        partition
+            .produce(records, Compression::NoCompression)
+            .await
+            .context(KafkaSnafu {
+                operation: "produce records",
+            })?;
+        Ok(())
+    }
+
+    async fn read(&self, topic: &str, start: Id, limit: usize) -> Result<Vec<Record>> {
+        if limit == 0 || !self.topic_exists(topic).await? {
+            return Ok(Vec::new());
+        }
+        let mut res = Vec::new();
+        self.scan(topic, |id, _, data| {
+            if id >= start {
+                res.push(Record { id, data });
+            }
+            res.len() < limit
+        })
+        .await?;
+        Ok(res)
+    }
+
+    async fn truncate(&self, topic: &str, id: Id) -> Result<()> {
+        if !self.topic_exists(topic).await? {
+            return Ok(());
+        }
+        // Records before the first record whose id is greater than `id` are deleted.
+        let mut delete_before = None;
+        self.scan(topic, |record_id, offset, _| {
+            if record_id > id {
+                delete_before = Some(offset);
+                false
+            } else {
+                true
+            }
+        })
+        .await?;
+
+        let partition = self.partition_client(topic).await?;
+        let offset = match delete_before {
+            Some(offset) => offset,
+            None => partition
+                .get_offset(OffsetAt::Latest)
+                .await
+                .context(KafkaSnafu {
+                    operation: "get latest offset",
+                })?,
+        };
+        partition
+            .delete_records(offset, TIMEOUT_MS)
+            .await
+            .context(KafkaSnafu {
+                operation: "delete records",
+            })
+    }
+
+    async fn create_topic(&self, topic: &str) -> Result<()> {
+        if self.partitions.lock().await.contains_key(topic) {
+            return Ok(());
+        }
+        let controller = self.client.controller_client().context(KafkaSnafu {
+            operation: "create controller client",
+        })?;
+        match controller
+            .create_topic(topic, 1, REPLICATION_FACTOR, TIMEOUT_MS)
+            .await
+        {
+            Ok(()) => info!("Created Kafka topic {}", topic),
+            Err(KafkaError::ServerError {
+                protocol_error: ProtocolError::TopicAlreadyExists,
+                ..
+            }) => {}
+            Err(e) => {
+                return Err(e).context(KafkaSnafu {
+                    operation: "create topic",
+                })
+            }
+        }
+        self.partition_client(topic).await?;
+        Ok(())
+    }
+
+    /// Deletes all records of the `topic`. The topic itself is kept since the client
+    /// does not support deleting topics, and it would be recreated by the next append
+    /// anyway.
+    async fn delete_topic(&self, topic: &str) -> Result<()> {
+        self.truncate(topic, Id::MAX).await?;
+        self.partitions.lock().await.remove(topic);
+        Ok(())
+    }
+
+    async fn list_topics(&self) -> Result<Vec<String>> {
+        let topics = self.client.list_topics().await.context(KafkaSnafu {
+            operation: "list topics",
+        })?;
+        Ok(topics.into_iter().map(|topic| topic.name).collect())
+    }
+}
@@ -0,0 +1,300 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::BTreeMap;
+
+use async_stream::stream;
+use common_telemetry::info;
+use store_api::logstore::entry::Id;
+use store_api::logstore::entry_stream::SendableEntryStream;
+use store_api::logstore::namespace::Id as NamespaceId;
+use store_api::logstore::{AppendResponse, LogStore};
+
+use crate::error::{Error, Result};
+use crate::remote::client::{LogClientRef, Record};
+use crate::remote::{EntryImpl as Entry, NamespaceImpl as Namespace};
+
+#[derive(Debug, Clone)]
+pub struct RemoteLogConfig {
+    /// Prefix of topics, each namespace is stored in the topic `{topic_prefix}{namespace id}`.
+    pub topic_prefix: String,
+    /// Max number of entries to fetch from the remote log service in one request.
+    pub read_batch_size: usize,
+}
+
+impl Default for RemoteLogConfig {
+    fn default() -> Self {
+        Self {
+            topic_prefix: "greptimedb_wal_".to_string(),
+            read_batch_size: 128,
+        }
+    }
+}
+
+/// A [LogStore] that stores entries in a remote log service.
+///
+/// Entries are not bound to the local node, so a region could be reopened on another
+/// datanode connected to the same service and replay its WAL there.
+#[derive(Debug)]
+pub struct RemoteLogStore {
+    config: RemoteLogConfig,
+    client: LogClientRef,
+}
+
+impl RemoteLogStore {
+    pub fn new(config: RemoteLogConfig, client: LogClientRef) -> Self {
+        info!("RemoteLogStore started with config: {:?}", config);
+        Self { config, client }
+    }
+
+    fn topic(&self, ns: NamespaceId) -> String {
+        format!("{}{}", self.config.topic_prefix, ns)
+    }
+}
+
+#[async_trait::async_trait]
+impl LogStore for RemoteLogStore {
+    type Error = Error;
+    type Namespace = Namespace;
+    type Entry = Entry;
+
+    async fn stop(&self) -> Result<()> {
+        info!("RemoteLogStore stopped");
+        Ok(())
+    }
+
+    /// Append an entry to the topic of its namespace, the topic is created if it does
+    /// not exist.
+    async fn append(&self, e: Self::Entry) -> Result<AppendResponse> {
+        let entry_id = e.id;
+        let topic = self.topic(e.namespace_id);
+        self.client
+            .append(
+                &topic,
+                vec![Record {
+                    id: e.id,
+                    data: e.data,
+                }],
+            )
+            .await?;
+        Ok(AppendResponse { entry_id })
+    }
+
+    /// Append a batch of entries to the topics of their namespaces, one request per
+    /// topic. The atomicity of each request depends on the remote log service.
+    async fn append_batch(&self, entries: Vec<Self::Entry>) -> Result<Vec<Id>> {
+        let entry_ids = entries.iter().map(|e| e.id).collect();
+        let mut records_by_ns: BTreeMap<NamespaceId, Vec<Record>> = BTreeMap::new();
+        for e in entries {
+            records_by_ns
+                .entry(e.namespace_id)
+                .or_default()
+                .push(Record {
+                    id: e.id,
+                    data: e.data,
+                });
+        }
+        for (ns, records) in records_by_ns {
+            self.client.append(&self.topic(ns), records).await?;
+        }
+        Ok(entry_ids)
+    }
+
+    /// Create a stream of entries from the topic of `ns`. The stream ends once it reads
+    /// all entries in the topic.
+    async fn read(
+        &self,
+        ns: &Self::Namespace,
+        id: Id,
+    ) -> Result<SendableEntryStream<'_, Self::Entry, Self::Error>> {
+        let client = self.client.clone();
+        let topic = self.topic(ns.id);
+        let namespace_id = ns.id;
+        let max_batch_size = self.config.read_batch_size;
+
+        let s = stream!({
+            let mut start_id = id;
+            loop {
+                let records = match client.read(&topic, start_id, max_batch_size).await {
+                    Ok(records) => records,
+                    Err(e) => {
+                        yield Err(e);
+                        break;
+                    }
+                };
+                let Some(last_record) = records.last() else {
+                    break;
+                };
+                start_id = last_record.id + 1;
+                let is_last_batch = records.len() < max_batch_size;
+                yield Ok(records
+                    .into_iter()
+                    .map(|record| Entry::create(record.id, namespace_id, record.data))
+                    .collect());
+                if is_last_batch {
+                    break;
+                }
+            }
+        });
+        Ok(Box::pin(s))
+    }
+
+    async fn create_namespace(&mut self, ns: &Self::Namespace) -> Result<()> {
+        self.client.create_topic(&self.topic(ns.id)).await
+    }
+
+    async fn delete_namespace(&mut self, ns: &Self::Namespace) -> Result<()> {
+        self.client.delete_topic(&self.topic(ns.id)).await
+    }
+
+    async fn list_namespaces(&self) -> Result<Vec<Self::Namespace>> {
+        let topics = self.client.list_topics().await?;
+        let namespaces = topics
+            .iter()
+            .filter_map(|topic| topic.strip_prefix(&self.config.topic_prefix))
+            .filter_map(|id| id.parse().ok())
+            .map(Namespace::with_id)
+            .collect();
+        Ok(namespaces)
+    }
+
+    fn entry<D: AsRef<[u8]>>(&self, data: D, id: Id, ns: Self::Namespace) -> Self::Entry {
+        Entry::create(id, ns.id, data.as_ref().to_vec())
+    }
+
+    fn namespace(&self, id: NamespaceId) -> Self::Namespace {
+        Namespace::with_id(id)
+    }
+
+    async fn obsolete(&self, namespace: Self::Namespace, id: Id) -> Result<()> {
+        self.client.truncate(&self.topic(namespace.id), id).await?;
+        info!("Namespace {} obsoleted entries to {}", namespace.id, id);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use futures_util::StreamExt;
+
+    use super::*;
+    use crate::remote::MemoryLogClient;
+
+    fn new_log_store(client: &MemoryLogClient) -> RemoteLogStore {
+        let config = RemoteLogConfig {
+            read_batch_size: 16,
+            ..Default::default()
+        };
+        RemoteLogStore::new(config, Arc::new(client.clone()))
+    }
+
+    async fn collect_entries(mut s: SendableEntryStream<'_, Entry, Error>) -> Vec<Entry> {
+        let mut res = vec![];
+        while let Some(r) = s.next().await {
+            res.extend(r.unwrap());
+        }
+        res
+    }
+
+    #[tokio::test]
+    async fn test_manage_namespace() {
+        let client = MemoryLogClient::default();
+        let mut logstore = new_log_store(&client);
+        assert!(logstore.list_namespaces().await.unwrap().is_empty());
+
+        logstore
+            .create_namespace(&Namespace::with_id(42))
+            .await
+            .unwrap();
+        let namespaces = logstore.list_namespaces().await.unwrap();
+        assert_eq!(vec![Namespace::with_id(42)], namespaces);
+
+        logstore
+            .delete_namespace(&Namespace::with_id(42))
+            .await
+            .unwrap();
+        assert!(logstore.list_namespaces().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_append_and_read() {
+        let client = MemoryLogClient::default();
+        let logstore = new_log_store(&client);
+
+        let namespace = Namespace::with_id(1);
+        for i in 0..100 {
+            let response = logstore
+                .append(Entry::create(i, namespace.id, i.to_string().into_bytes()))
+                .await
+                .unwrap();
+            assert_eq!(i, response.entry_id);
+        }
+        let entries = (100..150)
+            .map(|i| Entry::create(i, namespace.id, i.to_string().into_bytes()))
+            .collect();
+        logstore.append_batch(entries).await.unwrap();
+
+        let entries = collect_entries(logstore.read(&namespace, 10).await.unwrap()).await;
+        let ids: Vec<_> = entries.iter().map(|e| e.id).collect();
+        assert_eq!((10..150).collect::<Vec<_>>(), ids);
+        assert_eq!(b"10", entries[0].data.as_slice());
+
+        // Other namespaces are empty.
+        let entries =
+            collect_entries(logstore.read(&Namespace::with_id(2), 0).await.unwrap()).await;
+        assert!(entries.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_read_from_another_node() {
+        let client = MemoryLogClient::default();
+        let namespace = Namespace::with_id(1);
+        {
+            let logstore = new_log_store(&client);
+            for i in 0..10 {
+                logstore
+                    .append(Entry::create(i, namespace.id, vec![b'x'; 8]))
+                    .await
+                    .unwrap();
+            }
+            logstore.stop().await.unwrap();
+        }
+
+        // A log store on another node connected to the same service.
+        let logstore = new_log_store(&client);
+        let entries = collect_entries(logstore.read(&namespace, 0).await.unwrap()).await;
+        assert_eq!(10, entries.len());
+        assert_eq!(9, entries.last().unwrap().id);
+    }
+
+    #[tokio::test]
+    async fn test_obsolete() {
+        let client = MemoryLogClient::default();
+        let logstore = new_log_store(&client);
+        let namespace = Namespace::with_id(42);
+        for id in 0..64 {
+            logstore
+                .append(Entry::create(id, namespace.id, vec![b'x'; 8]))
+                .await
+                .unwrap();
+        }
+
+        logstore.obsolete(namespace.clone(), 20).await.unwrap();
+        let entries = collect_entries(logstore.read(&namespace, 0).await.unwrap()).await;
+        assert_eq!(21, entries.first().unwrap().id);
+        assert_eq!(43, entries.len());
+    }
+}
@@ -0,0 +1,117 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, RwLock};
+
+use store_api::logstore::entry::Id;
+
+use crate::error::Result;
+use crate::remote::client::{LogClient, Record};
+
+type Topics = HashMap<String, BTreeMap<Id, Vec<u8>>>;
+
+/// An in-process stand-in of the remote log service, mainly for tests.
+///
+/// Cloned clients share the same topics, like clients of a real service.
+#[derive(Debug, Clone, Default)]
+pub struct MemoryLogClient {
+    topics: Arc<RwLock<Topics>>,
+}
+
+#[async_trait::async_trait]
+impl LogClient for MemoryLogClient {
+    async fn append(&self, topic: &str, records: Vec<Record>) -> Result<()> {
+        let mut topics = self.topics.write().unwrap();
+        let topic = topics.entry(topic.to_string()).or_default();
+        topic.extend(records.into_iter().map(|record| (record.id, record.data)));
+        Ok(())
+    }
+
+    async fn read(&self, topic: &str, start: Id, limit: usize) -> Result<Vec<Record>> {
+        let topics = self.topics.read().unwrap();
+        let Some(topic) = topics.get(topic) else {
+            return Ok(Vec::new());
+        };
+        let records = topic
+            .range(start..)
+            .take(limit)
+            .map(|(id, data)| Record {
+                id: *id,
+                data: data.clone(),
+            })
+            .collect();
+        Ok(records)
+    }
+
+    async fn truncate(&self, topic: &str, id: Id) -> Result<()> {
+        let mut topics = self.topics.write().unwrap();
+        if let Some(topic) = topics.get_mut(topic) {
+            *topic = topic.split_off(&id.saturating_add(1));
+        }
+        Ok(())
+    }
+
+    async fn create_topic(&self, topic: &str) -> Result<()> {
+        let mut topics = self.topics.write().unwrap();
+        topics.entry(topic.to_string()).or_default();
+        Ok(())
+    }
+
+    async fn delete_topic(&self, topic: &str) -> Result<()> {
+        let mut topics = self.topics.write().unwrap();
+        topics.remove(topic);
+        Ok(())
+    }
+
+    async fn list_topics(&self) -> Result<Vec<String>> {
+        let topics = self.topics.read().unwrap();
+        Ok(topics.keys().cloned().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new_records(ids: &[Id]) -> Vec<Record> {
+        ids.iter()
+            .map(|id| Record {
+                id: *id,
+                data: id.to_string().into_bytes(),
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_memory_log_client() {
+        let client = MemoryLogClient::default();
+        assert!(client.read("t", 0, 10).await.unwrap().is_empty());
+
+        client
+            .append("t", new_records(&[1, 2, 3, 4]))
+            .await
+            .unwrap();
+        // Clients share topics.
+        let other = client.clone();
+        assert_eq!(new_records(&[2, 3]), other.read("t", 2, 2).await.unwrap());
+        assert_eq!(vec!["t".to_string()], other.list_topics().await.unwrap());
+
+        client.truncate("t", 2).await.unwrap();
+        assert_eq!(new_records(&[3, 4]), other.read("t", 0, 10).await.unwrap());
+
+        client.delete_topic("t").await.unwrap();
+        assert!(other.list_topics().await.unwrap().is_empty());
+    }
+}
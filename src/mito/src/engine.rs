@@ -20,17 +20,24 @@ use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
 use common_error::ext::BoxedError;
 use common_telemetry::logging;
 use datatypes::schema::SchemaRef;
-use object_store::ObjectStore;
+use object_store::{util, ObjectStore};
 use snafu::{ensure, OptionExt, ResultExt};
+use storage::backup::BackupLocation;
+use storage::region::RegionImpl;
+use storage::EngineImpl;
+use store_api::logstore::LogStore;
+use store_api::manifest::Manifest;
 use store_api::storage::{
     ColumnDescriptorBuilder, ColumnFamilyDescriptor, ColumnFamilyDescriptorBuilder, ColumnId,
-    CreateOptions, EngineContext as StorageEngineContext, OpenOptions, RegionDescriptorBuilder,
-    RegionId, RowKeyDescriptor, RowKeyDescriptorBuilder, StorageEngine,
+    CreateOptions, EngineContext as StorageEngineContext, MemtableType, OpenOptions,
+    RegionDescriptorBuilder, RegionId, RowKeyDescriptor, RowKeyDescriptorBuilder, StorageEngine,
 };
 use table::engine::{EngineContext, TableEngine, TableReference};
-use table::metadata::{TableId, TableInfoBuilder, TableMetaBuilder, TableType, TableVersion};
+use table::metadata::{
+    RawTableInfo, TableId, TableInfoBuilder, TableMetaBuilder, TableType, TableVersion,
+};
 use table::requests::{
-    AlterKind, AlterTableRequest, CreateTableRequest, DropTableRequest, OpenTableRequest,
+    self, AlterKind, AlterTableRequest, CreateTableRequest, DropTableRequest, OpenTableRequest,
 };
 use table::table::{AlterContext, TableRef};
 use table::{error as table_error, Result as TableResult, Table};
@@ -42,6 +49,7 @@ use crate::error::{
     BuildRowKeyDescriptorSnafu, InvalidPrimaryKeySnafu, MissingTimestampIndexSnafu, Result,
     TableExistsSnafu,
 };
+use crate::manifest::action::{TableChange, TableMetaAction, TableMetaActionList};
 use crate::table::MitoTable;
 
 pub const MITO_ENGINE: &str = "mito";
@@ -81,6 +89,83 @@ impl<S: StorageEngine> MitoEngine<S> {
     }
 }
 
+/// Request to restore a table exported by [MitoEngine::export_table].
+#[derive(Debug, Clone)]
+pub struct RestoreTableRequest {
+    /// Object store that holds the exported table.
+    pub object_store: ObjectStore,
+    /// Directory of the exported table in the object store.
+    pub dir: String,
+    /// Id of the restored table.
+    pub id: TableId,
+    pub catalog_name: String,
+    pub schema_name: String,
+    pub table_name: String,
+}
+
+impl<L: LogStore> MitoEngine<EngineImpl<L>> {
+    /// Flushes regions of the table and exports them with the table info to `dir`
+    /// of `object_store`.
+    pub async fn export_table(
+        &self,
+        table_ref: &TableReference<'_>,
+        object_store: ObjectStore,
+        dir: &str,
+    ) -> Result<()> {
+        self.inner.export_table(table_ref, object_store, dir).await
+    }
+
+    /// Restores the table exported by [MitoEngine::export_table] as a new table, the
+    /// regions of the new table are named by the new table id.
+    pub async fn restore_table(&self, request: RestoreTableRequest) -> Result<TableRef> {
+        self.inner.restore_table(request).await
+    }
+}
+
+/// Exports and restores tables of an engine in the object store of the engine, so
+/// callers only holding a [TableEngine] could back up its tables.
+#[async_trait]
+pub trait TableBackup: Send + Sync {
+    /// Exports the table to `dir` of the object store.
+    async fn export(&self, table_ref: &TableReference<'_>, dir: &str) -> Result<()>;
+
+    /// Restores the table exported to `dir` of the object store as a new table with
+    /// `table_id` and the name in `table_ref`.
+    async fn restore(
+        &self,
+        dir: &str,
+        table_id: TableId,
+        table_ref: &TableReference<'_>,
+    ) -> Result<TableRef>;
+}
+
+pub type TableBackupRef = Arc<dyn TableBackup>;
+
+#[async_trait]
+impl<L: LogStore> TableBackup for MitoEngine<EngineImpl<L>> {
+    async fn export(&self, table_ref: &TableReference<'_>, dir: &str) -> Result<()> {
+        self.export_table(table_ref, self.inner.object_store.clone(), dir)
+            .await
+    }
+
+    async fn restore(
+        &self,
+        dir: &str,
+        table_id: TableId,
+        table_ref: &TableReference<'_>,
+    ) -> Result<TableRef> {
+        self.restore_table(RestoreTableRequest {
+            object_store: self.inner.object_store.clone(),
+            dir: dir.to_string(),
+            id: table_id,
+            catalog_name: table_ref.catalog.to_string(),
+            schema_name: table_ref.schema.to_string(),
+            table_name: table_ref.table.to_string(),
+        })
+        .await
+    }
+}
+
 #[async_trait]
 impl<S: StorageEngine> TableEngine for MitoEngine<S> {
     fn name(&self) -> &str {
@@ -304,6 +389,10 @@ impl<S: StorageEngine> MitoEngineInner<S> {
         };
 
         validate_create_table_request(&request)?;
+        let ttl = requests::parse_ttl(&request.table_options)
+            .context(error::InvalidTableOptionsSnafu { table_name })?;
+        let memtable_type = requests::parse_memtable_type(&request.table_options)
+            .context(error::InvalidTableOptionsSnafu { table_name })?;
 
         if let Some(table) = self.get_table(&table_ref) {
             if request.create_if_not_exists {
@@ -362,6 +451,8 @@ impl<S: StorageEngine> MitoEngineInner<S> {
         let table_dir = table_dir(schema_name, table_id);
         let opts = CreateOptions {
             parent_dir: table_dir.clone(),
+            ttl,
+            memtable_type,
         };
 
         let region = self
@@ -377,6 +468,7 @@ impl<S: StorageEngine> MitoEngineInner<S> {
             .next_column_id(next_column_id)
             .primary_key_indices(request.primary_key_indices.clone())
             .region_numbers(vec![region_number])
+            .options(request.table_options)
             .build()
             .context(error::BuildTableMetaSnafu { table_name })?;
 
@@ -441,8 +533,24 @@ impl<S: StorageEngine> MitoEngineInner<S> {
             let table_id = request.table_id;
             let engine_ctx = StorageEngineContext::default();
             let table_dir = table_dir(schema_name, table_id);
+            // Recovers the table info first as the region is opened with the table options.
+            let manifest =
+                MitoTable::<S::Region>::build_manifest(&table_dir, self.object_store.clone());
+            let table_info = MitoTable::<S::Region>::recover_table_info(table_name, &manifest)
+                .await
+                .map_err(BoxedError::new)
+                .context(table_error::TableOperationSnafu)?;
+            let (ttl, memtable_type) = match &table_info {
+                Some(table_info) => (
+                    requests::parse_ttl(&table_info.meta.options)?,
+                    requests::parse_memtable_type(&table_info.meta.options)?,
+                ),
+                None => (None, MemtableType::default()),
+            };
             let opts = OpenOptions {
                 parent_dir: table_dir.to_string(),
+                ttl,
+                memtable_type,
             };
 
             // TODO(dennis): supports multi regions;
@@ -462,8 +570,7 @@ impl<S: StorageEngine> MitoEngineInner<S> {
             };
 
             let table = Arc::new(
-                MitoTable::open(table_name, &table_dir, region, self.object_store.clone())
-                    .await
+                MitoTable::open(table_name, table_info, region, manifest)
                     .map_err(BoxedError::new)
                     .context(table_error::TableOperationSnafu)?,
             );
@@ -549,6 +656,153 @@ impl<S: StorageEngine> MitoEngineInner<S> {
     }
 }
 
+impl<L: LogStore> MitoEngineInner<EngineImpl<L>> {
+    async fn export_table(
+        &self,
+        table_ref: &TableReference<'_>,
+        object_store: ObjectStore,
+        dir: &str,
+    ) -> Result<()> {
+        let table_name = table_ref.to_string();
+        let table = self
+            .get_table(table_ref)
+            .context(error::TableNotFoundSnafu {
+                table_name: &table_name,
+            })?;
+        // All tables of the engine are mito tables.
+        let table = table
+            .as_any()
+            .downcast_ref::<MitoTable<RegionImpl<L>>>()
+            .context(error::TableNotFoundSnafu {
+                table_name: &table_name,
+            })?;
+        let dir = util::normalize_dir(dir);
+
+        logging::info!("Mito engine exporting table {} to {}", table_name, dir);
+
+        let flushed_sequence = table
+            .region()
+            .export(object_store.clone(), &dir)
+            .await
+            .context(error::ExportTableSnafu {
+                table_name: &table_name,
+            })?;
+        // Writes the table info after the regions, so the table can't be restored
+        // until all its regions are exported.
+        let manifest = MitoTable::<RegionImpl<L>>::build_manifest(&dir, object_store);
+        manifest
+            .update(TableMetaActionList::with_action(TableMetaAction::Change(
+                Box::new(TableChange {
+                    table_info: RawTableInfo::from(table.table_info().as_ref().clone()),
+                }),
+            )))
+            .await
+            .context(error::UpdateTableManifestSnafu {
+                table_name: &table_name,
+            })?;
+
+        logging::info!(
+            "Mito engine exported table {} to {}, flushed sequence: {}",
+            table_name,
+            dir,
+            flushed_sequence
+        );
+
+        Ok(())
+    }
+
+    async fn restore_table(&self, request: RestoreTableRequest) -> Result<TableRef> {
+        let table_name = &request.table_name;
+        let table_ref = TableReference {
+            catalog: &request.catalog_name,
+            schema: &request.schema_name,
+            table: table_name,
+        };
+
+        let _lock = self.table_mutex.lock().await;
+        ensure!(
+            self.get_table(&table_ref).is_none(),
+            TableExistsSnafu {
+                table_name: table_ref.to_string(),
+            }
+        );
+
+        let source_dir = util::normalize_dir(&request.dir);
+        let manifest =
+            MitoTable::<RegionImpl<L>>::build_manifest(&source_dir, request.object_store.clone());
+        let mut table_info = MitoTable::<RegionImpl<L>>::recover_table_info(table_name, &manifest)
+            .await?
+            .context(error::TableInfoNotFoundSnafu { table_name })?;
+        let ttl = requests::parse_ttl(&table_info.meta.options)
+            .context(error::InvalidTableOptionsSnafu { table_name })?;
+        let memtable_type = requests::parse_memtable_type(&table_info.meta.options)
+            .context(error::InvalidTableOptionsSnafu { table_name })?;
+
+        // TODO(dennis): supports multi regions;
+        ensure!(
+            table_info.meta.region_numbers.len() == 1,
+            error::UnsupportedBackupSnafu {
+                table_name,
+                num_regions: table_info.meta.region_numbers.len(),
+            }
+        );
+        let region_number = table_info.meta.region_numbers[0];
+        let source = BackupLocation::new(
+            request.object_store.clone(),
+            &source_dir,
+            &region_name(table_info.ident.table_id, region_number),
+        );
+
+        let table_id = request.id;
+        let table_dir = table_dir(&request.schema_name, table_id);
+        let opts = OpenOptions {
+            parent_dir: table_dir.clone(),
+            ttl,
+            memtable_type,
+        };
+        let region = self
+            .storage_engine
+            .restore_region(
+                &StorageEngineContext::default(),
+                &source,
+                region_id(table_id, region_number),
+                &region_name(table_id, region_number),
+                &opts,
+            )
+            .await
+            .context(error::RestoreTableSnafu { table_name })?;
+
+        table_info.ident.table_id = table_id;
+        table_info.name = table_name.clone();
+        table_info.catalog_name = request.catalog_name.clone();
+        table_info.schema_name = request.schema_name.clone();
+        let table = Arc::new(
+            MitoTable::create(
+                table_name,
+                &table_dir,
+                table_info,
+                region,
+                self.object_store.clone(),
+            )
+            .await?,
+        );
+
+        logging::info!(
+            "Mito engine restored table {} from {}, table: {:?}",
+            table_ref,
+            source_dir,
+            table.table_info()
+        );
+
+        self.tables
+            .write()
+            .unwrap()
+            .insert(table_ref.to_string(), table.clone());
+
+        Ok(table)
+    }
+}
+
 impl<S: StorageEngine> MitoEngineInner<S> {
     fn new(_config: EngineConfig, storage_engine: S, object_store: ObjectStore) -> Self {
         Self {
@@ -562,8 +816,11 @@ impl<S: StorageEngine> MitoEngineInner<S> {
 
 #[cfg(test)]
 mod tests {
+    use common_query::logical_plan::Expr;
     use common_query::physical_plan::SessionContext;
     use common_recordbatch::util;
+    use datafusion::logical_expr::{col, lit};
+    use datafusion_common::ScalarValue;
     use datatypes::prelude::ConcreteDataType;
     use datatypes::schema::{ColumnDefaultConstraint, ColumnSchema, SchemaBuilder};
     use datatypes::value::Value;
@@ -574,7 +831,7 @@ mod tests {
     use storage::config::EngineConfig as StorageEngineConfig;
     use storage::EngineImpl;
     use store_api::manifest::Manifest;
-    use store_api::storage::ReadContext;
+    use store_api::storage::{ReadContext, TimeOrder};
     use table::requests::{AddColumnRequest, AlterKind, DeleteRequest};
     use tempdir::TempDir;
 
@@ -970,6 +1227,142 @@ mod tests {
         assert_eq!(reopened.manifest().last_version(), 1);
     }
 
+    fn new_create_request_with_options(options: HashMap<String, String>) -> CreateTableRequest {
+        CreateTableRequest {
+            id: 1,
+            catalog_name: DEFAULT_CATALOG_NAME.to_string(),
+            schema_name: DEFAULT_SCHEMA_NAME.to_string(),
+            table_name: TABLE_NAME.to_string(),
+            desc: None,
+            schema: Arc::new(schema_for_test()),
+            region_numbers: vec![0],
+            create_if_not_exists: true,
+            primary_key_indices: vec![0],
+            table_options: options,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_create_table_with_ttl() {
+        common_telemetry::init_default_ut_logging();
+
+        let ctx = EngineContext::default();
+        let (_dir, object_store) =
+            test_util::new_test_object_store("test_create_table_with_ttl").await;
+        let storage_engine = EngineImpl::new(
+            StorageEngineConfig::default(),
+            Arc::new(NoopLogStore::default()),
+            object_store.clone(),
+        );
+        let table_engine = MitoEngine::new(
+            EngineConfig::default(),
+            storage_engine.clone(),
+            object_store.clone(),
+        );
+
+        let options = HashMap::from([(requests::TTL_KEY.to_string(), "ttl".to_string())]);
+        let result = table_engine
+            .create_table(&ctx, new_create_request_with_options(options))
+            .await;
+        assert!(matches!(result, Err(e) if format!("{e:?}").contains("Invalid ttl option")));
+
+        let options = HashMap::from([(requests::TTL_KEY.to_string(), "1h".to_string())]);
+        let table = table_engine
+            .create_table(&ctx, new_create_request_with_options(options.clone()))
+            .await
+            .unwrap();
+        assert_eq!(options, table.table_info().meta.options);
+
+        // Rows older than the ttl are invisible.
+        let now = common_time::util::current_time_millis();
+        let mut columns_values: HashMap<String, VectorRef> = HashMap::with_capacity(4);
+        let hosts: VectorRef = Arc::new(StringVector::from(vec!["host1", "host2"]));
+        let cpus: VectorRef = Arc::new(Float64Vector::from_vec(vec![55.5, 66.6]));
+        let memories: VectorRef = Arc::new(Float64Vector::from_vec(vec![1024f64, 4096f64]));
+        let tss: VectorRef = Arc::new(TimestampMillisecondVector::from_vec(vec![1, now]));
+        columns_values.insert("host".to_string(), hosts);
+        columns_values.insert("cpu".to_string(), cpus);
+        columns_values.insert("memory".to_string(), memories);
+        columns_values.insert("ts".to_string(), tss);
+        let insert_req = new_insert_request(TABLE_NAME.to_string(), columns_values);
+        assert_eq!(2, table.insert(insert_req).await.unwrap());
+
+        let session_ctx = SessionContext::new();
+        let stream = table.scan(None, &[], None).await.unwrap();
+        let stream = stream.execute(0, session_ctx.task_ctx()).unwrap();
+        let batches = util::collect(stream).await.unwrap();
+        assert_eq!(1, batches.len());
+        let expect: VectorRef = Arc::new(StringVector::from(vec!["host2"]));
+        assert_eq!(expect, *batches[0].column(0));
+
+        // The ttl is recovered after reopening the table.
+        let table_engine = MitoEngine::new(EngineConfig::default(), storage_engine, object_store);
+        let open_req = OpenTableRequest {
+            catalog_name: DEFAULT_CATALOG_NAME.to_string(),
+            schema_name: DEFAULT_SCHEMA_NAME.to_string(),
+            table_name: TABLE_NAME.to_string(),
+            table_id: 1,
+            region_numbers: vec![0],
+        };
+        let reopened = table_engine
+            .open_table(&ctx, open_req)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(options, reopened.table_info().meta.options);
+    }
+
+    #[tokio::test]
+    async fn test_create_table_with_series_memtable() {
+        let ctx = EngineContext::default();
+        let (_dir, object_store) =
+            test_util::new_test_object_store("test_create_table_with_series_memtable").await;
+        let table_engine = MitoEngine::new(
+            EngineConfig::default(),
+            EngineImpl::new(
+                StorageEngineConfig::default(),
+                Arc::new(NoopLogStore::default()),
+                object_store.clone(),
+            ),
+            object_store,
+        );
+
+        let options = HashMap::from([(requests::MEMTABLE_KEY.to_string(), "hash".to_string())]);
+        let result = table_engine
+            .create_table(&ctx, new_create_request_with_options(options))
+            .await;
+        assert!(matches!(result, Err(e) if format!("{e:?}").contains("Invalid memtable option")));
+
+        let options = HashMap::from([(requests::MEMTABLE_KEY.to_string(), "series".to_string())]);
+        let table = table_engine
+            .create_table(&ctx, new_create_request_with_options(options))
+            .await
+            .unwrap();
+
+        // Rows are sorted by (host, ts) though they are written out of order.
+        let mut columns_values: HashMap<String, VectorRef> = HashMap::with_capacity(4);
+        let hosts: VectorRef = Arc::new(StringVector::from(vec!["host2", "host1", "host1"]));
+        let cpus: VectorRef = Arc::new(Float64Vector::from_vec(vec![1.0, 2.0, 3.0]));
+        let memories: VectorRef = Arc::new(Float64Vector::from_vec(vec![1.0, 2.0, 3.0]));
+        let tss: VectorRef = Arc::new(TimestampMillisecondVector::from_vec(vec![1, 3, 2]));
+        columns_values.insert("host".to_string(), hosts);
+        columns_values.insert("cpu".to_string(), cpus);
+        columns_values.insert("memory".to_string(), memories);
+        columns_values.insert("ts".to_string(), tss);
+        let insert_req = new_insert_request(TABLE_NAME.to_string(), columns_values);
+        assert_eq!(3, table.insert(insert_req).await.unwrap());
+
+        let session_ctx = SessionContext::new();
+        let stream = table.scan(None, &[], None).await.unwrap();
+        let stream = stream.execute(0, session_ctx.task_ctx()).unwrap();
+        let batches = util::collect(stream).await.unwrap();
+        assert_eq!(1, batches.len());
+        let expect: VectorRef = Arc::new(StringVector::from(vec!["host1", "host1", "host2"]));
+        assert_eq!(expect, *batches[0].column(0));
+        let expect: VectorRef = Arc::new(TimestampMillisecondVector::from_vec(vec![2, 3, 1]));
+        assert_eq!(expect, *batches[0].column(3));
+    }
+
     #[test]
     fn test_region_id() {
         assert_eq!(1, region_id(0, 1));
@@ -1256,8 +1649,22 @@ mod tests {
         let mut key_column_values = HashMap::with_capacity(2);
         key_column_values.insert("host".to_string(), del_hosts);
         key_column_values.insert("ts".to_string(), del_tss);
+        let del_req = DeleteRequest {
+            key_column_values: key_column_values.clone(),
+        };
+        assert_eq!(2, table.delete(del_req).await.unwrap());
+        // Rows are already deleted.
         let del_req = DeleteRequest { key_column_values };
-        table.delete(del_req).await.unwrap();
+        assert_eq!(0, table.delete(del_req).await.unwrap());
+
+        // Only host2 has a row, and duplicate keys are deleted once.
+        let del_hosts: VectorRef = Arc::new(StringVector::from(vec!["host2", "host2", "host5"]));
+        let del_tss: VectorRef = Arc::new(TimestampMillisecondVector::from_vec(vec![2, 2, 1]));
+        let mut key_column_values = HashMap::with_capacity(2);
+        key_column_values.insert("host".to_string(), del_hosts);
+        key_column_values.insert("ts".to_string(), del_tss);
+        let del_req = DeleteRequest { key_column_values };
+        assert_eq!(1, table.delete(del_req).await.unwrap());
 
         let session_ctx = SessionContext::new();
         let stream = table.scan(None, &[], None).await.unwrap();
@@ -1270,9 +1677,195 @@ mod tests {
 +-------+-----+--------+-------------------------+
 | host  | cpu | memory | ts                      |
 +-------+-----+--------+-------------------------+
-| host2 | 2   | 2      | 1970-01-01T00:00:00.002 |
 | host4 | 4   | 4      | 1970-01-01T00:00:00.001 |
 +-------+-----+--------+-------------------------+"
         );
     }
+
+    #[tokio::test]
+    async fn test_table_scan_ordered() {
+        let (_engine, table, _schema, _dir) = test_util::setup_test_engine_and_table().await;
+
+        let mut columns_values: HashMap<String, VectorRef> = HashMap::with_capacity(4);
+        let hosts: VectorRef =
+            Arc::new(StringVector::from(vec!["host1", "host1", "host2", "host2"]));
+        let cpus: VectorRef = Arc::new(Float64Vector::from_vec(vec![1.0, 3.0, 2.0, 4.0]));
+        let memories: VectorRef = Arc::new(Float64Vector::from_vec(vec![1.0, 3.0, 2.0, 4.0]));
+        let tss: VectorRef = Arc::new(TimestampMillisecondVector::from_vec(vec![1, 3, 2, 4]));
+
+        columns_values.insert("host".to_string(), hosts);
+        columns_values.insert("cpu".to_string(), cpus);
+        columns_values.insert("memory".to_string(), memories);
+        columns_values.insert("ts".to_string(), tss);
+
+        let insert_req = new_insert_request("demo".to_string(), columns_values);
+        assert_eq!(4, table.insert(insert_req).await.unwrap());
+
+        // Rows of different hosts are merged by timestamp, and the scan stops at the limit.
+        let session_ctx = SessionContext::new();
+        let stream = table
+            .scan_ordered(None, &[], TimeOrder::Desc, Some(3))
+            .await
+            .unwrap();
+        let stream = stream.execute(0, session_ctx.task_ctx()).unwrap();
+        let batches = util::collect_batches(stream).await.unwrap();
+
+        assert_eq!(
+            batches.pretty_print().unwrap(),
+            "\
++-------+-----+--------+-------------------------+
+| host  | cpu | memory | ts                      |
++-------+-----+--------+-------------------------+
+| host2 | 4   | 4      | 1970-01-01T00:00:00.004 |
+| host1 | 3   | 3      | 1970-01-01T00:00:00.003 |
+| host2 | 2   | 2      | 1970-01-01T00:00:00.002 |
++-------+-----+--------+-------------------------+"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_table_point_lookup() {
+        let (_engine, table, _schema, _dir) = test_util::setup_test_engine_and_table().await;
+
+        let mut columns_values: HashMap<String, VectorRef> = HashMap::with_capacity(4);
+        let hosts: VectorRef = Arc::new(StringVector::from(vec!["host1", "host1", "host2"]));
+        let cpus: VectorRef = Arc::new(Float64Vector::from_vec(vec![1.0, 2.0, 3.0]));
+        let memories: VectorRef = Arc::new(Float64Vector::from_vec(vec![1.0, 2.0, 3.0]));
+        let tss: VectorRef = Arc::new(TimestampMillisecondVector::from_vec(vec![1, 2, 1]));
+
+        columns_values.insert("host".to_string(), hosts);
+        columns_values.insert("cpu".to_string(), cpus);
+        columns_values.insert("memory".to_string(), memories);
+        columns_values.insert("ts".to_string(), tss);
+
+        let insert_req = new_insert_request("demo".to_string(), columns_values);
+        assert_eq!(3, table.insert(insert_req).await.unwrap());
+
+        let session_ctx = SessionContext::new();
+        let filter = |host: &str, ts: i64| {
+            Expr::from(
+                col("host")
+                    .eq(lit(host))
+                    .and(col("ts").eq(lit(ScalarValue::TimestampMillisecond(Some(ts), None)))),
+            )
+        };
+        let stream = table
+            .scan(Some(&vec![0, 1, 3]), &[filter("host1", 2)], None)
+            .await
+            .unwrap();
+        let stream = stream.execute(0, session_ctx.task_ctx()).unwrap();
+        let batches = util::collect_batches(stream).await.unwrap();
+        assert_eq!(
+            batches.pretty_print().unwrap(),
+            "\
++-------+-----+-------------------------+
+| host  | cpu | ts                      |
++-------+-----+-------------------------+
+| host1 | 2   | 1970-01-01T00:00:00.002 |
++-------+-----+-------------------------+"
+        );
+
+        let stream = table.scan(None, &[filter("host2", 2)], None).await.unwrap();
+        let stream = stream.execute(0, session_ctx.task_ctx()).unwrap();
+        let batches = util::collect_batches(stream).await.unwrap();
+        assert_eq!(0, batches.iter().map(|b| b.num_rows()).sum::<usize>());
+    }
+
+    #[tokio::test]
+    async fn test_table_flush() {
+        let (_engine, table, _schema, dir) = test_util::setup_test_engine_and_table().await;
+
+        let mut columns_values: HashMap<String, VectorRef> = HashMap::with_capacity(4);
+        let hosts: VectorRef = Arc::new(StringVector::from(vec!["host1", "host2"]));
+        let cpus: VectorRef = Arc::new(Float64Vector::from_vec(vec![1.0, 2.0]));
+        let memories: VectorRef = Arc::new(Float64Vector::from_vec(vec![1.0, 2.0]));
+        let tss: VectorRef = Arc::new(TimestampMillisecondVector::from_vec(vec![1, 2]));
+
+        columns_values.insert("host".to_string(), hosts);
+        columns_values.insert("cpu".to_string(), cpus);
+        columns_values.insert("memory".to_string(), memories);
+        columns_values.insert("ts".to_string(), tss);
+
+        let insert_req = new_insert_request("demo".to_string(), columns_values);
+        assert_eq!(2, table.insert(insert_req).await.unwrap());
+
+        table.flush().await.unwrap();
+
+        let region_name = region_name(table.table_info().ident.table_id, 0);
+        let sst_dir = format!(
+            "{}/{}{}/",
+            dir.path().to_str().unwrap(),
+            table_dir("public", table.table_info().ident.table_id),
+            region_name
+        );
+        let has_sst = std::fs::read_dir(sst_dir)
+            .unwrap()
+            .any(|entry| entry.unwrap().path().extension() == Some("parquet".as_ref()));
+        assert!(has_sst);
+
+        let session_ctx = SessionContext::new();
+        let stream = table.scan(None, &[], None).await.unwrap();
+        let stream = stream.execute(0, session_ctx.task_ctx()).unwrap();
+        let batches = util::collect_batches(stream).await.unwrap();
+        assert_eq!(2, batches.iter().map(|b| b.num_rows()).sum::<usize>());
+    }
+
+    #[tokio::test]
+    async fn test_export_and_restore_table() {
+        let (engine, table, _schema, _dir) = test_util::setup_test_engine_and_table().await;
+
+        let mut columns_values: HashMap<String, VectorRef> = HashMap::with_capacity(4);
+        let hosts: VectorRef = Arc::new(StringVector::from(vec!["host1", "host2"]));
+        let cpus: VectorRef = Arc::new(Float64Vector::from_vec(vec![1.0, 2.0]));
+        let memories: VectorRef = Arc::new(Float64Vector::from_vec(vec![1.0, 2.0]));
+        let tss: VectorRef = Arc::new(TimestampMillisecondVector::from_vec(vec![1, 2]));
+
+        columns_values.insert("host".to_string(), hosts.clone());
+        columns_values.insert("cpu".to_string(), cpus);
+        columns_values.insert("memory".to_string(), memories);
+        columns_values.insert("ts".to_string(), tss);
+
+        let insert_req = new_insert_request("demo".to_string(), columns_values);
+        assert_eq!(2, table.insert(insert_req).await.unwrap());
+
+        let (_backup_dir, backup_store) =
+            test_util::new_test_object_store("test_export_and_restore_table").await;
+        engine
+            .export_table(
+                &TableReference::bare("demo"),
+                backup_store.clone(),
+                "backup",
+            )
+            .await
+            .unwrap();
+
+        let request = RestoreTableRequest {
+            object_store: backup_store,
+            dir: "backup".to_string(),
+            id: 2,
+            catalog_name: "greptime".to_string(),
+            schema_name: "public".to_string(),
+            table_name: "demo_restored".to_string(),
+        };
+        let restored = engine.restore_table(request.clone()).await.unwrap();
+        let table_info = restored.table_info();
+        assert_eq!(2, table_info.ident.table_id);
+        assert_eq!("demo_restored", table_info.name);
+        assert_eq!(table.schema(), restored.schema());
+        assert!(engine.table_exists(
+            &EngineContext::default(),
+            &TableReference::bare("demo_restored")
+        ));
+
+        let session_ctx = SessionContext::new();
+        let stream = restored.scan(None, &[], None).await.unwrap();
+        let stream = stream.execute(0, session_ctx.task_ctx()).unwrap();
+        let batches = util::collect(stream).await.unwrap();
+        assert_eq!(1, batches.len());
+        assert_eq!(hosts, *batches[0].column(0));
+
+        // Restores to an existing table.
+        let err = engine.restore_table(request).await.unwrap_err();
+        assert!(matches!(err, error::Error::TableExists { .. }));
+    }
 }
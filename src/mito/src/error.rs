@@ -170,6 +170,38 @@ pub enum Error {
         #[snafu(backtrace)]
         source: table::metadata::ConvertError,
     },
+
+    #[snafu(display("Invalid options of table {}, source: {}", table_name, source))]
+    InvalidTableOptions {
+        table_name: String,
+        #[snafu(backtrace)]
+        source: table::error::Error,
+    },
+
+    #[snafu(display("Failed to export table {}, source: {}", table_name, source))]
+    ExportTable {
+        table_name: String,
+        #[snafu(backtrace)]
+        source: storage::error::Error,
+    },
+
+    #[snafu(display(
+        "Backup of table {} has {} regions, only tables with one region are supported",
+        table_name,
+        num_regions
+    ))]
+    UnsupportedBackup {
+        table_name: String,
+        num_regions: usize,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Failed to restore table {}, source: {}", table_name, source))]
+    RestoreTable {
+        table_name: String,
+        #[snafu(backtrace)]
+        source: storage::error::Error,
+    },
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -181,7 +213,9 @@ impl ErrorExt for Error {
         match self {
             CreateRegion { source, .. } => source.status_code(),
 
-            AlterTable { source, .. } => source.status_code(),
+            AlterTable { source, .. } | InvalidTableOptions { source, .. } => source.status_code(),
+
+            ExportTable { source, .. } | RestoreTable { source, .. } => source.status_code(),
 
             BuildRowKeyDescriptor { .. }
             | BuildColumnDescriptor { .. }
@@ -197,6 +231,8 @@ impl ErrorExt for Error {
 
             TableInfoNotFound { .. } | ConvertRaw { .. } => StatusCode::Unexpected,
 
+            UnsupportedBackup { .. } => StatusCode::Unsupported,
+
             ScanTableManifest { .. } | UpdateTableManifest { .. } => StatusCode::StorageUnavailable,
         }
     }
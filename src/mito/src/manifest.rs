@@ -17,9 +17,9 @@ pub mod action;
 
 use storage::manifest::ManifestImpl;
 
-use crate::manifest::action::TableMetaActionList;
+use crate::manifest::action::{TableCheckpoint, TableMetaActionList};
 
-pub type TableManifest = ManifestImpl<TableMetaActionList>;
+pub type TableManifest = ManifestImpl<TableCheckpoint, TableMetaActionList>;
 
 #[cfg(test)]
 mod tests {
@@ -94,4 +94,48 @@ mod tests {
             _ => unreachable!(),
         }
     }
+
+    #[tokio::test]
+    async fn test_table_manifest_checkpoint() {
+        let (_dir, object_store) =
+            test_util::new_test_object_store("test_table_manifest_checkpoint").await;
+
+        // Disable background checkpoint.
+        let manifest = TableManifest::with_checkpoint_margin("manifest/", object_store, 0);
+        assert!(manifest.checkpoint().await.unwrap().is_none());
+
+        let table_info = test_util::build_test_table_info();
+        for _ in 0..3 {
+            let action_list =
+                TableMetaActionList::new(vec![TableMetaAction::Change(Box::new(TableChange {
+                    table_info: RawTableInfo::from(table_info.clone()),
+                }))]);
+            manifest.update(action_list).await.unwrap();
+        }
+
+        let checkpoint = manifest.checkpoint().await.unwrap().unwrap();
+        assert_eq!(2, checkpoint.last_version);
+        assert_eq!(ProtocolAction::new(), checkpoint.protocol);
+        assert_eq!(
+            table_info,
+            TableInfo::try_from(checkpoint.table_info.clone().unwrap()).unwrap()
+        );
+        assert_eq!(
+            checkpoint,
+            manifest.last_checkpoint().await.unwrap().unwrap()
+        );
+
+        // Compacted actions are deleted.
+        let mut iter = manifest.scan(0, 100).await.unwrap();
+        assert!(iter.next_action().await.unwrap().is_none());
+
+        let action_list = TableMetaActionList::new(vec![TableMetaAction::Remove(TableRemove {
+            table_name: table_info.name.clone(),
+            table_ident: table_info.ident.clone(),
+        })]);
+        assert_eq!(3, manifest.update(action_list).await.unwrap());
+        let checkpoint = manifest.checkpoint().await.unwrap().unwrap();
+        assert_eq!(3, checkpoint.last_version);
+        assert!(checkpoint.table_info.is_none());
+    }
 }
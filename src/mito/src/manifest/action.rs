@@ -18,12 +18,12 @@ use serde::{Deserialize, Serialize};
 use serde_json as json;
 use snafu::{ensure, OptionExt, ResultExt};
 use storage::error::{
-    DecodeJsonSnafu, DecodeMetaActionListSnafu, Error as StorageError,
+    DecodeJsonSnafu, DecodeMetaActionListSnafu, EncodeJsonSnafu, Error as StorageError,
     ManifestProtocolForbidReadSnafu, ReadlineSnafu,
 };
 use storage::manifest::helper;
 use store_api::manifest::action::{ProtocolAction, ProtocolVersion, VersionHeader};
-use store_api::manifest::{ManifestVersion, MetaAction};
+use store_api::manifest::{Checkpoint, ManifestVersion, MetaAction};
 use table::metadata::{RawTableInfo, TableIdent};
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
@@ -137,6 +137,64 @@ impl MetaAction for TableMetaActionList {
     }
 }
 
+/// Checkpoint of the table manifest.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct TableCheckpoint {
+    /// Version of the last action compacted into this checkpoint.
+    pub last_version: ManifestVersion,
+    /// The last protocol action.
+    pub protocol: ProtocolAction,
+    /// The latest table info, `None` if the table is removed.
+    pub table_info: Option<RawTableInfo>,
+}
+
+impl Checkpoint for TableCheckpoint {
+    type Error = StorageError;
+    type MetaAction = TableMetaActionList;
+
+    fn last_version(&self) -> ManifestVersion {
+        self.last_version
+    }
+
+    fn protocol(&self) -> &ProtocolAction {
+        &self.protocol
+    }
+
+    fn apply(
+        &mut self,
+        version: ManifestVersion,
+        action_list: TableMetaActionList,
+    ) -> Result<(), Self::Error> {
+        for action in action_list.actions {
+            match action {
+                TableMetaAction::Protocol(p) => self.protocol = p,
+                TableMetaAction::Change(c) => self.table_info = Some(c.table_info),
+                TableMetaAction::Remove(_) => self.table_info = None,
+            }
+        }
+        self.last_version = version;
+
+        Ok(())
+    }
+
+    fn encode(&self) -> Result<Vec<u8>, Self::Error> {
+        json::to_vec(self).context(EncodeJsonSnafu)
+    }
+
+    fn decode(bs: &[u8], reader_version: ProtocolVersion) -> Result<Self, Self::Error> {
+        let checkpoint: TableCheckpoint = json::from_slice(bs).context(DecodeJsonSnafu)?;
+        ensure!(
+            checkpoint.protocol.is_readable(reader_version),
+            ManifestProtocolForbidReadSnafu {
+                min_version: checkpoint.protocol.min_reader_version,
+                supported_version: reader_version,
+            }
+        );
+
+        Ok(checkpoint)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use common_telemetry::logging;
@@ -16,25 +16,32 @@
 pub mod test_util;
 
 use std::any::Any;
+use std::collections::{BTreeSet, HashMap};
 use std::pin::Pin;
 use std::sync::Arc;
 
 use arc_swap::ArcSwap;
 use async_trait::async_trait;
 use common_error::ext::BoxedError;
-use common_query::logical_plan::Expr;
+use common_query::logical_plan::{DfExpr, Expr};
 use common_query::physical_plan::PhysicalPlanRef;
 use common_recordbatch::error::{ExternalSnafu, Result as RecordBatchResult};
-use common_recordbatch::{RecordBatch, RecordBatchStream};
+use common_recordbatch::{RecordBatch, RecordBatchStream, RecordBatches};
 use common_telemetry::logging;
+use datafusion::logical_expr::{BinaryExpr, Operator};
+use datafusion_common::ScalarValue;
+use datatypes::prelude::DataType;
+use datatypes::schema::Schema;
+use datatypes::value::{scalar_value_to_timestamp, Value};
+use datatypes::vectors::VectorRef;
 use futures::task::{Context, Poll};
 use futures::Stream;
 use object_store::ObjectStore;
 use snafu::{OptionExt, ResultExt};
 use store_api::manifest::{self, Manifest, ManifestVersion, MetaActionIterator};
 use store_api::storage::{
-    AddColumn, AlterOperation, AlterRequest, ChunkReader, ReadContext, Region, RegionMeta,
-    ScanRequest, SchemaRef, Snapshot, WriteContext, WriteRequest,
+    AddColumn, AlterOperation, AlterRequest, ChunkReader, FlushContext, GetRequest, ReadContext,
+    Region, RegionMeta, ScanRequest, SchemaRef, Snapshot, TimeOrder, WriteContext, WriteRequest,
 };
 use table::error as table_error;
 use table::error::Result as TableResult;
@@ -124,43 +131,20 @@ impl<R: Region> Table for MitoTable<R> {
         &self,
         projection: Option<&Vec<usize>>,
         filters: &[Expr],
-        _limit: Option<usize>,
+        limit: Option<usize>,
     ) -> TableResult<PhysicalPlanRef> {
-        let read_ctx = ReadContext::default();
-        let snapshot = self
-            .region
-            .snapshot(&read_ctx)
-            .map_err(BoxedError::new)
-            .context(table_error::TableOperationSnafu)?;
+        self.scan_region(projection, filters, None, limit).await
+    }
 
-        let projection = self
-            .transform_projection(&self.region, projection.cloned())
-            .map_err(BoxedError::new)
-            .context(table_error::TableOperationSnafu)?;
-        let filters = filters.into();
-        let scan_request = ScanRequest {
-            projection,
-            filters,
-            ..Default::default()
-        };
-        let mut reader = snapshot
-            .scan(&read_ctx, scan_request)
+    async fn scan_ordered(
+        &self,
+        projection: Option<&Vec<usize>>,
+        filters: &[Expr],
+        order: TimeOrder,
+        limit: Option<usize>,
+    ) -> TableResult<PhysicalPlanRef> {
+        self.scan_region(projection, filters, Some(order), limit)
             .await
-            .map_err(BoxedError::new)
-            .context(table_error::TableOperationSnafu)?
-            .reader;
-
-        let schema = reader.schema().clone();
-        let stream_schema = schema.clone();
-
-        let stream = Box::pin(async_stream::try_stream! {
-            while let Some(chunk) = reader.next_chunk().await.map_err(BoxedError::new).context(ExternalSnafu)? {
-                yield RecordBatch::new(stream_schema.clone(), chunk.columns)?
-            }
-        });
-
-        let stream = Box::pin(ChunkStream { schema, stream });
-        Ok(Arc::new(SimpleTableScan::new(stream)))
     }
 
     fn supports_filter_pushdown(&self, _filter: &Expr) -> table::error::Result<FilterPushDownType> {
@@ -248,11 +232,14 @@ impl<R: Region> Table for MitoTable<R> {
             return Ok(0);
         }
 
-        let mut write_request = self.region.write_request();
-
-        let key_column_values = request.key_column_values;
+        let key_column_values = self.retain_existing_keys(request.key_column_values).await?;
         // Safety: key_column_values isn't empty.
         let rows_num = key_column_values.values().next().unwrap().len();
+        if rows_num == 0 {
+            return Ok(0);
+        }
+
+        let mut write_request = self.region.write_request();
 
         logging::trace!(
             "Delete from table {} where key_columns are: {:?}",
@@ -272,6 +259,16 @@ impl<R: Region> Table for MitoTable<R> {
 
         Ok(rows_num)
     }
+
+    async fn flush(&self) -> TableResult<()> {
+        logging::info!("Flush table {}", self.table_info().name);
+
+        self.region
+            .flush(&FlushContext::default())
+            .await
+            .map_err(BoxedError::new)
+            .context(table_error::TableOperationSnafu)
+    }
 }
 
 struct ChunkStream {
@@ -293,6 +290,14 @@ impl Stream for ChunkStream {
     }
 }
 
+/// Returns the number of partitions to scan a region, which is the same as the default
+/// number of partitions the query engine executes in parallel.
+fn scan_parallelism() -> usize {
+    std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1)
+}
+
 #[inline]
 fn column_qualified_name(table_name: &str, region_name: &str, column_name: &str) -> String {
     format!("{table_name}.{region_name}.{column_name}")
@@ -308,6 +313,67 @@ impl<R: Region> MitoTable<R> {
         }
     }
 
+    /// Scans the region, rows of each partition are ordered by timestamp in `order` if it
+    /// is not `None`.
+    async fn scan_region(
+        &self,
+        projection: Option<&Vec<usize>>,
+        filters: &[Expr],
+        order: Option<TimeOrder>,
+        limit: Option<usize>,
+    ) -> TableResult<PhysicalPlanRef> {
+        let read_ctx = ReadContext::default();
+        let snapshot = self
+            .region
+            .snapshot(&read_ctx)
+            .map_err(BoxedError::new)
+            .context(table_error::TableOperationSnafu)?;
+
+        let projection = self
+            .transform_projection(&self.region, projection.cloned())
+            .map_err(BoxedError::new)
+            .context(table_error::TableOperationSnafu)?;
+        if let Some(mut request) = point_lookup_request(&self.table_info(), filters) {
+            request.projection = projection;
+            return self.point_lookup(&read_ctx, &snapshot, request).await;
+        }
+
+        let filters = filters.into();
+        let scan_request = ScanRequest {
+            projection,
+            filters,
+            order,
+            limit,
+            ..Default::default()
+        };
+        let responses = snapshot
+            .scan_partitions(&read_ctx, scan_request, scan_parallelism())
+            .await
+            .map_err(BoxedError::new)
+            .context(table_error::TableOperationSnafu)?;
+
+        let streams = responses
+            .into_iter()
+            .map(|response| {
+                let mut reader = response.reader;
+                let schema = reader.schema().clone();
+                let stream_schema = schema.clone();
+
+                let stream = Box::pin(async_stream::try_stream! {
+                    while let Some(chunk) = reader.next_chunk().await.map_err(BoxedError::new).context(ExternalSnafu)? {
+                        yield RecordBatch::new(stream_schema.clone(), chunk.columns)?
+                    }
+                });
+
+                Box::pin(ChunkStream { schema, stream }) as _
+            })
+            .collect();
+        let scan = SimpleTableScan::with_partitions(streams)
+            .map_err(BoxedError::new)
+            .context(table_error::TableOperationSnafu)?;
+        Ok(Arc::new(scan))
+    }
+
     /// Transform projection which is based on table schema
     /// into projection based on region schema.
     fn transform_projection(
@@ -364,6 +430,57 @@ impl<R: Region> MitoTable<R> {
             .transpose()
     }
 
+    /// Reads the row requested by `request` from the `snapshot` and returns a scan
+    /// over it, the scan is empty if the row doesn't exist.
+    async fn point_lookup(
+        &self,
+        read_ctx: &ReadContext,
+        snapshot: &R::Snapshot,
+        request: GetRequest,
+    ) -> TableResult<PhysicalPlanRef> {
+        let region_meta = self.region.in_memory_metadata();
+        let region_schema = region_meta.schema();
+        let column_schemas = match &request.projection {
+            Some(projection) => projection
+                .iter()
+                .map(|idx| region_schema.column_schemas()[*idx].clone())
+                .collect(),
+            None => region_schema.column_schemas().to_vec(),
+        };
+        let schema = Arc::new(Schema::new(column_schemas));
+
+        let row = snapshot
+            .get(read_ctx, request)
+            .await
+            .map_err(BoxedError::new)
+            .context(table_error::TableOperationSnafu)?
+            .row;
+        let mut batches = Vec::new();
+        if let Some(row) = row {
+            let columns = schema
+                .column_schemas()
+                .iter()
+                .zip(row)
+                .map(|(column_schema, value)| {
+                    let mut builder = column_schema.data_type.create_mutable_vector(1);
+                    builder.push_value_ref(value.as_value_ref())?;
+                    Ok(builder.to_vector())
+                })
+                .collect::<datatypes::error::Result<Vec<_>>>()
+                .map_err(BoxedError::new)
+                .context(table_error::TableOperationSnafu)?;
+            let batch = RecordBatch::new(schema.clone(), columns)
+                .map_err(BoxedError::new)
+                .context(table_error::TableOperationSnafu)?;
+            batches.push(batch);
+        }
+
+        let batches = RecordBatches::try_new(schema, batches)
+            .map_err(BoxedError::new)
+            .context(table_error::TableOperationSnafu)?;
+        Ok(Arc::new(SimpleTableScan::new(batches.as_stream())))
+    }
+
     pub async fn create(
         table_name: &str,
         table_dir: &str,
@@ -386,33 +503,50 @@ impl<R: Region> MitoTable<R> {
         Ok(MitoTable::new(table_info, region, manifest))
     }
 
-    pub async fn open(
+    /// Opens the table with `table_info` recovered by [MitoTable::recover_table_info].
+    pub fn open(
         table_name: &str,
-        table_dir: &str,
+        table_info: Option<TableInfo>,
         region: R,
-        object_store: ObjectStore,
+        manifest: TableManifest,
     ) -> Result<MitoTable<R>> {
-        let manifest = TableManifest::new(&table_manifest_dir(table_dir), object_store);
-
-        let mut table_info = Self::recover_table_info(table_name, &manifest)
-            .await?
-            .context(TableInfoNotFoundSnafu { table_name })?;
+        let mut table_info = table_info.context(TableInfoNotFoundSnafu { table_name })?;
         table_info.meta.region_numbers = vec![(region.id() & 0xFFFFFFFF) as u32];
         Ok(MitoTable::new(table_info, region, manifest))
     }
 
-    async fn recover_table_info(
+    /// Builds the manifest of the table under `table_dir`.
+    pub fn build_manifest(table_dir: &str, object_store: ObjectStore) -> TableManifest {
+        TableManifest::new(&table_manifest_dir(table_dir), object_store)
+    }
+
+    /// Recovers the table info from `manifest`, returns `None` if the table info
+    /// is absent.
+    pub async fn recover_table_info(
         table_name: &str,
         manifest: &TableManifest,
     ) -> Result<Option<TableInfo>> {
-        let (start, end) = Self::manifest_scan_range();
+        let (mut start, end) = Self::manifest_scan_range();
+        let mut last_manifest_version = manifest::MIN_VERSION;
+        let mut protocol = None;
+        let mut raw_table_info = None;
+        // Recover from the latest checkpoint first.
+        if let Some(checkpoint) = manifest
+            .last_checkpoint()
+            .await
+            .context(ScanTableManifestSnafu { table_name })?
+        {
+            start = checkpoint.last_version + 1;
+            last_manifest_version = checkpoint.last_version;
+            protocol = Some(checkpoint.protocol);
+            raw_table_info = checkpoint.table_info;
+        }
+
         let mut iter = manifest
             .scan(start, end)
             .await
             .context(ScanTableManifestSnafu { table_name })?;
 
-        let mut last_manifest_version = manifest::MIN_VERSION;
-        let mut table_info = None;
         while let Some((manifest_version, action_list)) = iter
             .next_action()
             .await
@@ -423,9 +557,7 @@ impl<R: Region> MitoTable<R> {
             for action in action_list.actions {
                 match action {
                     TableMetaAction::Change(c) => {
-                        table_info = Some(
-                            TableInfo::try_from(c.table_info).context(error::ConvertRawSnafu)?,
-                        );
+                        raw_table_info = Some(c.table_info);
                     }
                     TableMetaAction::Protocol(_) => {}
                     TableMetaAction::Remove(_) => unimplemented!("Drop table is unimplemented"),
@@ -433,10 +565,17 @@ impl<R: Region> MitoTable<R> {
             }
         }
 
+        let table_info = raw_table_info
+            .map(TableInfo::try_from)
+            .transpose()
+            .context(error::ConvertRawSnafu)?;
+
         if table_info.is_some() {
             // update manifest state after recovering
-            let protocol = iter.last_protocol();
-            manifest.update_state(last_manifest_version + 1, protocol.clone());
+            if iter.last_protocol().is_some() {
+                protocol = iter.last_protocol().clone();
+            }
+            manifest.update_state(last_manifest_version + 1, protocol);
         }
 
         logging::debug!(
@@ -453,6 +592,133 @@ impl<R: Region> MitoTable<R> {
         &self.region
     }
 
+    /// Returns the distinct keys in `key_column_values` that have rows in the table, so
+    /// the delete only counts rows actually deleted.
+    async fn retain_existing_keys(
+        &self,
+        key_column_values: HashMap<String, VectorRef>,
+    ) -> TableResult<HashMap<String, VectorRef>> {
+        let table_info = self.table_info();
+        let schema = &table_info.meta.schema;
+        let mut key_indices = key_column_values
+            .keys()
+            .map(|name| {
+                schema.column_index_by_name(name).with_context(|| {
+                    table_error::ColumnNotExistsSnafu {
+                        column_name: name,
+                        table_name: &table_info.name,
+                    }
+                })
+            })
+            .collect::<TableResult<Vec<_>>>()?;
+        key_indices.sort_unstable();
+        let key_names: Vec<_> = key_indices
+            .iter()
+            .map(|idx| schema.column_schemas()[*idx].name.clone())
+            .collect();
+        let key_vectors: Vec<_> = key_names
+            .iter()
+            .map(|name| key_column_values[name].clone())
+            .collect();
+        // Safety: key_column_values isn't empty.
+        let num_rows = key_vectors[0].len();
+
+        // Only reads rows in the time range of the keys.
+        let mut filters = Vec::new();
+        if let Some(ts_column) = schema.timestamp_column() {
+            if let Some(ts_vector) = key_column_values.get(&ts_column.name) {
+                let timestamps = (0..num_rows).map(|i| ts_vector.get(i));
+                if let (Some(min), Some(max)) = (timestamps.clone().min(), timestamps.max()) {
+                    let to_scalar = |value: Value| {
+                        value
+                            .try_to_scalar_value(&ts_column.data_type)
+                            .map_err(BoxedError::new)
+                            .context(table_error::TableOperationSnafu)
+                    };
+                    let ts = DfExpr::Column(ts_column.name.as_str().into());
+                    filters.push(
+                        ts.clone()
+                            .gt_eq(DfExpr::Literal(to_scalar(min)?))
+                            .and(ts.lt_eq(DfExpr::Literal(to_scalar(max)?)))
+                            .into(),
+                    );
+                }
+            }
+        }
+
+        let read_ctx = ReadContext::default();
+        let snapshot = self
+            .region
+            .snapshot(&read_ctx)
+            .map_err(BoxedError::new)
+            .context(table_error::TableOperationSnafu)?;
+        let projection = self
+            .transform_projection(&self.region, Some(key_indices))
+            .map_err(BoxedError::new)
+            .context(table_error::TableOperationSnafu)?;
+        let scan_request = ScanRequest {
+            projection,
+            filters,
+            ..Default::default()
+        };
+        let mut reader = snapshot
+            .scan(&read_ctx, scan_request)
+            .await
+            .map_err(BoxedError::new)
+            .context(table_error::TableOperationSnafu)?
+            .reader;
+        let reader_indices = key_names
+            .iter()
+            .map(|name| {
+                reader.schema().column_index_by_name(name).with_context(|| {
+                    table_error::ColumnNotExistsSnafu {
+                        column_name: name,
+                        table_name: &table_info.name,
+                    }
+                })
+            })
+            .collect::<TableResult<Vec<_>>>()?;
+
+        let mut existing_keys = BTreeSet::new();
+        while let Some(chunk) = reader
+            .next_chunk()
+            .await
+            .map_err(BoxedError::new)
+            .context(table_error::TableOperationSnafu)?
+        {
+            let num_chunk_rows = chunk.columns.first().map(|c| c.len()).unwrap_or(0);
+            for row in 0..num_chunk_rows {
+                let key: Vec<_> = reader_indices
+                    .iter()
+                    .map(|idx| chunk.columns[*idx].get(row))
+                    .collect();
+                existing_keys.insert(key);
+            }
+        }
+
+        let mut builders: Vec<_> = key_vectors
+            .iter()
+            .map(|vector| vector.data_type().create_mutable_vector(num_rows))
+            .collect();
+        for row in 0..num_rows {
+            let key: Vec<_> = key_vectors.iter().map(|vector| vector.get(row)).collect();
+            // Removes the key so duplicate keys in the request are only counted once.
+            if existing_keys.remove(&key) {
+                for (builder, value) in builders.iter_mut().zip(key) {
+                    builder
+                        .push_value_ref(value.as_value_ref())
+                        .map_err(BoxedError::new)
+                        .context(table_error::TableOperationSnafu)?;
+                }
+            }
+        }
+
+        Ok(key_names
+            .into_iter()
+            .zip(builders.iter_mut().map(|builder| builder.to_vector()))
+            .collect())
+    }
+
     pub fn set_table_info(&self, table_info: TableInfo) {
         self.table_info.swap(Arc::new(table_info));
     }
@@ -468,6 +734,58 @@ impl<R: Region> MitoTable<R> {
     }
 }
 
+/// Builds a [GetRequest] if `filters` specify values of all primary key columns and
+/// the time index by equality conditions, so the row could be read by [Snapshot::get]
+/// instead of a scan. The projection of the request is left empty.
+fn point_lookup_request(table_info: &TableInfo, filters: &[Expr]) -> Option<GetRequest> {
+    let mut equalities = HashMap::new();
+    for filter in filters {
+        collect_equalities(filter.df_expr(), &mut equalities);
+    }
+
+    let schema = &table_info.meta.schema;
+    let ts_column = schema.timestamp_column()?;
+    let timestamp = scalar_value_to_timestamp(equalities.get(&ts_column.name)?)?;
+
+    let primary_key = table_info
+        .meta
+        .primary_key_indices
+        .iter()
+        .map(|idx| {
+            let column = &schema.column_schemas()[*idx];
+            let value = Value::try_from(equalities.get(&column.name)?.clone()).ok()?;
+            // Falls back to scan if the literal needs a cast.
+            (value.data_type() == column.data_type).then(|| (column.name.clone(), value))
+        })
+        .collect::<Option<HashMap<_, _>>>()?;
+
+    Some(GetRequest {
+        primary_key,
+        timestamp,
+        sequence: None,
+        projection: None,
+    })
+}
+
+/// Collects `column = literal` conditions joined by `AND` in `expr`.
+fn collect_equalities(expr: &DfExpr, equalities: &mut HashMap<String, ScalarValue>) {
+    let DfExpr::BinaryExpr(BinaryExpr { left, op, right }) = expr else { return; };
+    match op {
+        Operator::And => {
+            collect_equalities(left, equalities);
+            collect_equalities(right, equalities);
+        }
+        Operator::Eq => match (left.as_ref(), right.as_ref()) {
+            (DfExpr::Column(column), DfExpr::Literal(scalar))
+            | (DfExpr::Literal(scalar), DfExpr::Column(column)) => {
+                equalities.insert(column.name.clone(), scalar.clone());
+            }
+            _ => (),
+        },
+        _ => (),
+    }
+}
+
 /// Create [`AlterOperation`] according to given `alter_kind`.
 fn create_alter_operation(
     table_name: &str,
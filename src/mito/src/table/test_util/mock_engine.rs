@@ -26,9 +26,9 @@ use datatypes::schema::{ColumnSchema, Schema};
 use storage::metadata::{RegionMetaImpl, RegionMetadata};
 use storage::write_batch::WriteBatch;
 use store_api::storage::{
-    AlterRequest, Chunk, ChunkReader, CreateOptions, EngineContext, GetRequest, GetResponse,
-    OpenOptions, ReadContext, Region, RegionDescriptor, RegionId, ScanRequest, ScanResponse,
-    SchemaRef, Snapshot, StorageEngine, WriteContext, WriteResponse,
+    AlterRequest, Chunk, ChunkReader, CreateOptions, EngineContext, FlushContext, GetRequest,
+    GetResponse, OpenOptions, ReadContext, Region, RegionDescriptor, RegionId, ScanRequest,
+    ScanResponse, SchemaRef, Snapshot, StorageEngine, WriteContext, WriteResponse,
 };
 
 pub type Result<T> = std::result::Result<T, MockError>;
@@ -119,8 +119,17 @@ impl Snapshot for MockSnapshot {
         Ok(ScanResponse { reader })
     }
 
+    async fn scan_partitions(
+        &self,
+        ctx: &ReadContext,
+        request: ScanRequest,
+        _num_partitions: usize,
+    ) -> Result<Vec<ScanResponse<MockChunkReader>>> {
+        Ok(vec![self.scan(ctx, request).await?])
+    }
+
     async fn get(&self, _ctx: &ReadContext, _request: GetRequest) -> Result<GetResponse> {
-        Ok(GetResponse {})
+        Ok(GetResponse { row: None })
     }
 }
 
@@ -188,6 +197,10 @@ impl Region for MockRegion {
 
         Ok(())
     }
+
+    async fn flush(&self, _ctx: &FlushContext) -> Result<()> {
+        Ok(())
+    }
 }
 
 impl MockRegionInner {
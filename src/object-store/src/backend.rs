@@ -14,5 +14,7 @@
 
 pub mod azblob;
 pub mod fs;
+pub mod gcs;
 pub mod memory;
+pub mod oss;
 pub mod s3;
@@ -0,0 +1,282 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Cache objects of remote storage in a local object store.
+
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use lru::LruCache;
+use metrics::{gauge, increment_counter};
+use opendal::ops::{OpDelete, OpRead, OpWrite};
+use opendal::raw::{Accessor, AccessorMetadata, BytesReader, RpDelete, RpRead, RpWrite};
+use opendal::{Layer, Result};
+
+use crate::metric::{METRIC_LRU_CACHE_BYTES, METRIC_LRU_CACHE_HIT, METRIC_LRU_CACHE_MISS};
+use crate::ObjectStore;
+
+/// A [Layer] that caches data read from the underlying storage in another object store,
+/// usually a local file system, and evicts the least recently used entries once the size
+/// of all cached entries exceeds the capacity.
+///
+/// Each range read from an object is cached as an individual entry, so this layer is
+/// designed for immutable objects like SST files. Writing or deleting an object
+/// invalidates all its cached entries.
+#[derive(Debug, Clone)]
+pub struct LruCacheLayer {
+    cache: ObjectStore,
+    index: Arc<Mutex<CacheIndex>>,
+}
+
+impl LruCacheLayer {
+    /// Returns a layer that caches at most `capacity` bytes in `cache`.
+    ///
+    /// Entries already in `cache` are not tracked by the layer, so the `cache` should
+    /// be empty.
+    pub fn new(cache: ObjectStore, capacity: u64) -> LruCacheLayer {
+        LruCacheLayer {
+            cache,
+            index: Arc::new(Mutex::new(CacheIndex::new(capacity))),
+        }
+    }
+
+    /// Returns the size in bytes of all cached entries.
+    pub fn cached_bytes(&self) -> u64 {
+        self.index.lock().unwrap().size
+    }
+}
+
+impl Layer for LruCacheLayer {
+    fn layer(&self, inner: Arc<dyn Accessor>) -> Arc<dyn Accessor> {
+        Arc::new(LruCacheAccessor {
+            inner,
+            cache: self.cache.clone(),
+            index: self.index.clone(),
+        })
+    }
+}
+
+/// Sizes of entries in the cache, in LRU order.
+#[derive(Debug)]
+struct CacheIndex {
+    entries: LruCache<String, u64>,
+    /// Max bytes of all entries.
+    capacity: u64,
+    /// Bytes of all entries.
+    size: u64,
+}
+
+impl CacheIndex {
+    fn new(capacity: u64) -> CacheIndex {
+        CacheIndex {
+            entries: LruCache::unbounded(),
+            capacity,
+            size: 0,
+        }
+    }
+
+    /// Returns the size of the entry if it is cached and marks it as most recently used.
+    fn touch(&mut self, key: &str) -> Option<u64> {
+        self.entries.get(key).copied()
+    }
+
+    /// Adds an entry of `size` bytes, returns keys of entries evicted.
+    fn insert(&mut self, key: String, size: u64) -> Vec<String> {
+        if let Some(old_size) = self.entries.put(key, size) {
+            self.size -= old_size;
+        }
+        self.size += size;
+
+        let mut evicted = Vec::new();
+        while self.size > self.capacity {
+            let Some((key, size)) = self.entries.pop_lru() else { break };
+            self.size -= size;
+            evicted.push(key);
+        }
+        self.update_metrics();
+
+        evicted
+    }
+
+    /// Removes the entry with `key`.
+    fn remove(&mut self, key: &str) {
+        if let Some(size) = self.entries.pop(key) {
+            self.size -= size;
+        }
+        self.update_metrics();
+    }
+
+    /// Removes all entries of the object under `path`, returns keys of removed entries.
+    fn remove_object(&mut self, path: &str) -> Vec<String> {
+        let prefix = cache_key_prefix(path);
+        let keys: Vec<_> = self
+            .entries
+            .iter()
+            .filter(|(key, _)| key.starts_with(&prefix))
+            .map(|(key, _)| key.clone())
+            .collect();
+        for key in &keys {
+            self.remove(key);
+        }
+
+        keys
+    }
+
+    fn update_metrics(&self) {
+        gauge!(METRIC_LRU_CACHE_BYTES, self.size as f64);
+    }
+}
+
+#[derive(Debug)]
+struct LruCacheAccessor {
+    inner: Arc<dyn Accessor>,
+    cache: ObjectStore,
+    index: Arc<Mutex<CacheIndex>>,
+}
+
+impl LruCacheAccessor {
+    /// Returns a reader of the entry with `key` in the cache, `None` if it is not cached.
+    async fn read_cache(&self, key: &str) -> Option<(RpRead, BytesReader)> {
+        let size = self.index.lock().unwrap().touch(key)?;
+
+        match self.cache.object(key).reader().await {
+            Ok(reader) => Some((RpRead::new(size), Box::new(reader))),
+            Err(_) => {
+                // The entry is unavailable, e.g. removed by others, just forget it.
+                self.index.lock().unwrap().remove(key);
+                None
+            }
+        }
+    }
+
+    /// Streams `size` bytes from `reader` into the cache entry with `key`. Returns
+    /// false if the entry is not cached, failures are ignored since the cache is optional.
+    async fn write_cache(&self, key: &str, size: u64, reader: BytesReader) -> bool {
+        if self
+            .cache
+            .object(key)
+            .write_from(size, reader)
+            .await
+            .is_err()
+        {
+            // Removes the partially written entry.
+            let _ = self.cache.object(key).delete().await;
+            return false;
+        }
+
+        let evicted = self.index.lock().unwrap().insert(key.to_string(), size);
+        for key in evicted {
+            let _ = self.cache.object(&key).delete().await;
+        }
+        true
+    }
+
+    /// Removes all cached entries of the object under `path`.
+    async fn invalidate(&self, path: &str) {
+        let removed = self.index.lock().unwrap().remove_object(path);
+        for key in removed {
+            let _ = self.cache.object(&key).delete().await;
+        }
+    }
+}
+
+#[async_trait]
+impl Accessor for LruCacheAccessor {
+    fn inner(&self) -> Option<Arc<dyn Accessor>> {
+        Some(self.inner.clone())
+    }
+
+    fn metadata(&self) -> AccessorMetadata {
+        self.inner.metadata()
+    }
+
+    async fn read(&self, path: &str, args: OpRead) -> Result<(RpRead, BytesReader)> {
+        let key = cache_key(path, &args);
+        if let Some(cached) = self.read_cache(&key).await {
+            increment_counter!(METRIC_LRU_CACHE_HIT);
+            return Ok(cached);
+        }
+
+        increment_counter!(METRIC_LRU_CACHE_MISS);
+        let (rp, reader) = self.inner.read(path, args.clone()).await?;
+        let size = rp.into_metadata().content_length();
+        if size > self.index.lock().unwrap().capacity {
+            return Ok((RpRead::new(size), reader));
+        }
+
+        // Streams the object into the cache instead of buffering it in memory, then
+        // serves the read from the cached file.
+        if self.write_cache(&key, size, reader).await {
+            if let Some(cached) = self.read_cache(&key).await {
+                return Ok(cached);
+            }
+        }
+        // The reader is consumed, so reads the object again.
+        self.inner.read(path, args).await
+    }
+
+    async fn write(&self, path: &str, args: OpWrite, r: BytesReader) -> Result<RpWrite> {
+        self.invalidate(path).await;
+        self.inner.write(path, args, r).await
+    }
+
+    async fn delete(&self, path: &str, args: OpDelete) -> Result<RpDelete> {
+        self.invalidate(path).await;
+        self.inner.delete(path, args).await
+    }
+}
+
+fn cache_key_prefix(path: &str) -> String {
+    format!("{}.cache-", path.trim_start_matches('/'))
+}
+
+/// Returns the key of the range to read in the cache, e.g. `dir/file.cache-0_100`.
+/// Bounds of the range that are not specified are left empty.
+fn cache_key(path: &str, args: &OpRead) -> String {
+    let range = args.range();
+    let bound_to_string = |bound: Option<u64>| bound.map(|v| v.to_string()).unwrap_or_default();
+    format!(
+        "{}{}_{}",
+        cache_key_prefix(path),
+        bound_to_string(range.offset()),
+        bound_to_string(range.size())
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cache_index() {
+        let mut index = CacheIndex::new(10);
+        assert!(index.insert("a.cache-0_4".to_string(), 4).is_empty());
+        assert!(index.insert("b.cache-0_4".to_string(), 4).is_empty());
+        assert_eq!(Some(4), index.touch("a.cache-0_4"));
+        assert_eq!(8, index.size);
+
+        // b is the least recently used entry.
+        assert_eq!(
+            vec!["b.cache-0_4".to_string()],
+            index.insert("a.cache-4_4".to_string(), 4)
+        );
+        assert_eq!(8, index.size);
+        assert_eq!(None, index.touch("b.cache-0_4"));
+
+        let mut removed = index.remove_object("/a");
+        removed.sort();
+        assert_eq!(vec!["a.cache-0_4", "a.cache-4_4"], removed);
+        assert_eq!(0, index.size);
+    }
+}
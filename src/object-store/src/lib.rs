@@ -17,5 +17,7 @@ pub use opendal::{
     Operator as ObjectStore, Result,
 };
 pub mod backend;
+pub mod cache;
+mod metric;
 pub mod test_util;
 pub mod util;
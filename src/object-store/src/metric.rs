@@ -0,0 +1,22 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! object store metrics
+
+/// Counter of reads served by the local cache.
+pub const METRIC_LRU_CACHE_HIT: &str = "object_store.lru_cache.hit";
+/// Counter of reads that miss the local cache and go to the underlying storage.
+pub const METRIC_LRU_CACHE_MISS: &str = "object_store.lru_cache.miss";
+/// Bytes of objects kept in the local cache.
+pub const METRIC_LRU_CACHE_BYTES: &str = "object_store.lru_cache.bytes";
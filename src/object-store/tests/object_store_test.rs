@@ -16,7 +16,8 @@ use std::env;
 
 use anyhow::Result;
 use common_telemetry::logging;
-use object_store::backend::{fs, s3};
+use object_store::backend::{azblob, fs, s3};
+use object_store::cache::LruCacheLayer;
 use object_store::test_util::TempFolder;
 use object_store::{util, Object, ObjectLister, ObjectMode, ObjectStore};
 use tempdir::TempDir;
@@ -104,6 +105,67 @@ async fn test_fs_backend() -> Result<()> {
     Ok(())
 }
 
+fn new_fs_store(dir: &TempDir) -> Result<ObjectStore> {
+    let root = dir.path().to_string_lossy();
+    let atomic_write_dir = format!("{root}/.tmp/");
+    let accessor = fs::Builder::default()
+        .root(&root)
+        .atomic_write_dir(&atomic_write_dir)
+        .build()?;
+    Ok(ObjectStore::new(accessor))
+}
+
+async fn num_cached_files(cache_store: &ObjectStore) -> Result<usize> {
+    let objects = util::collect(cache_store.object("/").list().await?).await?;
+    Ok(objects
+        .iter()
+        .filter(|object| object.path().contains(".cache-"))
+        .count())
+}
+
+#[tokio::test]
+async fn test_lru_cache_layer() -> Result<()> {
+    let data_dir = TempDir::new("test_lru_cache_layer_data")?;
+    let cache_dir = TempDir::new("test_lru_cache_layer_cache")?;
+    let cache_store = new_fs_store(&cache_dir)?;
+    let cache_layer = LruCacheLayer::new(cache_store.clone(), 32);
+    let store = new_fs_store(&data_dir)?.layer(cache_layer.clone());
+
+    test_object_crud(&store).await?;
+    test_object_list(&store).await?;
+    assert_eq!(0, num_cached_files(&cache_store).await?);
+    assert_eq!(0, cache_layer.cached_bytes());
+
+    let o1 = store.object("test_file1");
+    o1.write("Hello, object1!").await?;
+    // Read twice, the second read hits the cache.
+    for _ in 0..2 {
+        assert_eq!("Hello, object1!", String::from_utf8(o1.read().await?)?);
+        assert_eq!("object1", String::from_utf8(o1.range_read(7..14).await?)?);
+    }
+    assert_eq!(2, num_cached_files(&cache_store).await?);
+    assert_eq!(22, cache_layer.cached_bytes());
+
+    // Evicts the least recently used entry, which is the whole `test_file1`.
+    let o2 = store.object("test_file2");
+    o2.write("Hello, object2!").await?;
+    assert_eq!("Hello, object2!", String::from_utf8(o2.read().await?)?);
+    assert_eq!(2, num_cached_files(&cache_store).await?);
+    assert_eq!(22, cache_layer.cached_bytes());
+
+    // Overwriting an object invalidates its cache.
+    o2.write("Hello, greptime!").await?;
+    assert_eq!("Hello, greptime!", String::from_utf8(o2.read().await?)?);
+
+    o1.delete().await?;
+    o2.delete().await?;
+    assert!(o1.read().await.is_err());
+    assert_eq!(0, num_cached_files(&cache_store).await?);
+    assert_eq!(0, cache_layer.cached_bytes());
+
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_s3_backend() -> Result<()> {
     logging::init_default_ut_logging();
@@ -131,3 +193,34 @@ async fn test_s3_backend() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn test_azblob_backend() -> Result<()> {
+    logging::init_default_ut_logging();
+    // Could run against a local emulator like Azurite, whose endpoint of the default
+    // account is `http://127.0.0.1:10000/devstoreaccount1`.
+    if let Ok(container) = env::var("GT_AZBLOB_CONTAINER") {
+        if !container.is_empty() {
+            logging::info!("Running azblob test.");
+
+            let root = uuid::Uuid::new_v4().to_string();
+
+            let accessor = azblob::Builder::default()
+                .root(&root)
+                .container(&container)
+                .endpoint(&env::var("GT_AZBLOB_ENDPOINT")?)
+                .account_name(&env::var("GT_AZBLOB_ACCOUNT_NAME")?)
+                .account_key(&env::var("GT_AZBLOB_ACCOUNT_KEY")?)
+                .build()?;
+
+            let store = ObjectStore::new(accessor);
+
+            let mut guard = TempFolder::new(&store, "/");
+            test_object_crud(&store).await?;
+            test_object_list(&store).await?;
+            guard.remove_all().await?;
+        }
+    }
+
+    Ok(())
+}
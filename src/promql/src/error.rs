@@ -73,6 +73,36 @@ pub enum Error {
         expr: PromExpr,
         backtrace: Backtrace,
     },
+
+    #[snafu(display("Expect a range selector as the input of function {}", name))]
+    ExpectRangeSelector { name: String, backtrace: Backtrace },
+
+    #[snafu(display("Expect a literal parameter for {}, but found {:?}", op, param))]
+    ExpectLiteralParam {
+        op: TokenType,
+        param: PromExpr,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Invalid parameter {} for {}, reason: {}", param, op, reason))]
+    InvalidParam {
+        op: TokenType,
+        param: f64,
+        reason: String,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display(
+        "Cannot match value columns of binary operands, left: {:?}, right: {:?}",
+        left,
+        right
+    ))]
+    ValueColumnMismatch {
+        left: Vec<String>,
+        right: Vec<String>,
+        backtrace: Backtrace,
+    },
+
     #[snafu(display(
         "Illegal range: offset {}, length {}, array len {}",
         offset,
@@ -105,7 +135,11 @@ impl ErrorExt for Error {
             | UnexpectedToken { .. }
             | MultipleVector { .. }
             | LabelNotFound { .. }
-            | ExpectExpr { .. } => StatusCode::InvalidArguments,
+            | ExpectExpr { .. }
+            | ExpectRangeSelector { .. }
+            | ExpectLiteralParam { .. }
+            | InvalidParam { .. }
+            | ValueColumnMismatch { .. } => StatusCode::InvalidArguments,
             UnknownTable { .. }
             | TableNotFound { .. }
             | DataFusionPlanning { .. }
@@ -43,12 +43,15 @@ use crate::extension_plan::Millisecond;
 /// `start` and `end` with step `interval`. Find in the `lookback` range if data
 /// is missing at the given timestamp. If data is absent in some timestamp, all columns
 /// except the time index will left blank.
+///
+/// If `at` is set (the `@` modifier), the sample at `at` is taken for every timestamp.
 #[derive(Debug)]
 pub struct InstantManipulate {
     start: Millisecond,
     end: Millisecond,
     lookback_delta: Millisecond,
     interval: Millisecond,
+    at: Option<Millisecond>,
     time_index_column: String,
     input: LogicalPlan,
 }
@@ -75,7 +78,11 @@ impl UserDefinedLogicalNode for InstantManipulate {
             f,
             "PromInstantManipulate: range=[{}..{}], lookback=[{}], interval=[{}], time index=[{}]",
             self.start, self.end, self.lookback_delta, self.interval, self.time_index_column
-        )
+        )?;
+        if let Some(at) = self.at {
+            write!(f, ", at=[{at}]")?;
+        }
+        Ok(())
     }
 
     fn from_template(
@@ -90,6 +97,7 @@ impl UserDefinedLogicalNode for InstantManipulate {
             end: self.end,
             lookback_delta: self.lookback_delta,
             interval: self.interval,
+            at: self.at,
             time_index_column: self.time_index_column.clone(),
             input: inputs[0].clone(),
         })
@@ -102,6 +110,7 @@ impl InstantManipulate {
         end: Millisecond,
         lookback_delta: Millisecond,
         interval: Millisecond,
+        at: Option<Millisecond>,
         time_index_column: String,
         input: LogicalPlan,
     ) -> Self {
@@ -110,6 +119,7 @@ impl InstantManipulate {
             end,
             lookback_delta,
             interval,
+            at,
             time_index_column,
             input,
         }
@@ -121,6 +131,7 @@ impl InstantManipulate {
             end: self.end,
             lookback_delta: self.lookback_delta,
             interval: self.interval,
+            at: self.at,
             time_index_column: self.time_index_column.clone(),
             input: exec_input,
             metric: ExecutionPlanMetricsSet::new(),
@@ -134,6 +145,7 @@ pub struct InstantManipulateExec {
     end: Millisecond,
     lookback_delta: Millisecond,
     interval: Millisecond,
+    at: Option<Millisecond>,
     time_index_column: String,
 
     input: Arc<dyn ExecutionPlan>,
@@ -175,6 +187,7 @@ impl ExecutionPlan for InstantManipulateExec {
             end: self.end,
             lookback_delta: self.lookback_delta,
             interval: self.interval,
+            at: self.at,
             time_index_column: self.time_index_column.clone(),
             input: children[0].clone(),
             metric: self.metric.clone(),
@@ -199,6 +212,7 @@ impl ExecutionPlan for InstantManipulateExec {
             end: self.end,
             lookback_delta: self.lookback_delta,
             interval: self.interval,
+            at: self.at,
             time_index,
             schema,
             input,
@@ -213,7 +227,11 @@ impl ExecutionPlan for InstantManipulateExec {
                     f,
                     "PromInstantManipulateExec: range=[{}..{}], lookback=[{}], interval=[{}], time index=[{}]",
                    self.start,self.end, self.lookback_delta, self.interval, self.time_index_column
-                )
+                )?;
+                if let Some(at) = self.at {
+                    write!(f, ", at=[{at}]")?;
+                }
+                Ok(())
             }
         }
     }
@@ -247,6 +265,7 @@ pub struct InstantManipulateStream {
     end: Millisecond,
     lookback_delta: Millisecond,
     interval: Millisecond,
+    at: Option<Millisecond>,
     // Column index of TIME INDEX column's position in schema
     time_index: usize,
 
@@ -292,8 +311,8 @@ impl InstantManipulateStream {
             .step_by(self.interval as usize)
             .collect::<Vec<_>>();
 
-        // calculate the offsets to take
-        'next: for expected_ts in aligned_ts.iter().copied() {
+        // calculate the offsets to take, the `@` modifier fixes the timestamp to look up
+        'next: for expected_ts in aligned_ts.iter().map(|ts| self.at.unwrap_or(*ts)) {
             // first, search toward end to see if there is matched timestamp
             while cursor < ts_column.len() {
                 let curr = ts_column.value(cursor);
@@ -397,6 +416,17 @@ mod test {
         lookback_delta: Millisecond,
         interval: Millisecond,
         expected: String,
+    ) {
+        do_normalize_test_at(start, end, lookback_delta, interval, None, expected).await;
+    }
+
+    async fn do_normalize_test_at(
+        start: Millisecond,
+        end: Millisecond,
+        lookback_delta: Millisecond,
+        interval: Millisecond,
+        at: Option<Millisecond>,
+        expected: String,
     ) {
         let memory_exec = Arc::new(prepare_test_data());
         let normalize_exec = Arc::new(InstantManipulateExec {
@@ -404,6 +434,7 @@ mod test {
             end,
             lookback_delta,
             interval,
+            at,
             time_index_column: TIME_INDEX_COLUMN.to_string(),
             input: memory_exec,
             metric: ExecutionPlanMetricsSet::new(),
@@ -717,4 +748,32 @@ mod test {
         );
         do_normalize_test(190_000, 300_000, 30_000, 10_000, expected).await;
     }
+
+    #[tokio::test]
+    async fn at_modifier_found() {
+        let expected = String::from(
+            "+---------------------+-------+------+\
+            \n| timestamp           | value | path |\
+            \n+---------------------+-------+------+\
+            \n| 1970-01-01T00:00:00 | 1     | foo  |\
+            \n| 1970-01-01T00:00:30 | 1     | foo  |\
+            \n| 1970-01-01T00:01:00 | 1     | foo  |\
+            \n+---------------------+-------+------+",
+        );
+        do_normalize_test_at(0, 60_000, 10_000, 30_000, Some(245_000), expected).await;
+    }
+
+    #[tokio::test]
+    async fn at_modifier_not_found() {
+        let expected = String::from(
+            "+---------------------+-------+------+\
+            \n| timestamp           | value | path |\
+            \n+---------------------+-------+------+\
+            \n| 1970-01-01T00:00:00 |       |      |\
+            \n| 1970-01-01T00:00:30 |       |      |\
+            \n| 1970-01-01T00:01:00 |       |      |\
+            \n+---------------------+-------+------+",
+        );
+        do_normalize_test_at(0, 60_000, 10_000, 30_000, Some(150_000), expected).await;
+    }
 }
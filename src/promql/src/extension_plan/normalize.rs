@@ -39,7 +39,7 @@ use crate::extension_plan::Millisecond;
 /// the input batch only contains sample points from one time series.
 ///
 /// Roughly speaking, this method does these things:
-/// - bias sample's timestamp by offset
+/// - bias sample's timestamp by offset, i.e. a sample at `t` is moved to `t + offset`
 /// - sort the record batch based on timestamp column
 #[derive(Debug)]
 pub struct SeriesNormalize {
@@ -226,7 +226,7 @@ impl SeriesNormalizeStream {
             ts_column.clone()
         } else {
             TimestampMillisecondArray::from_iter(
-                ts_column.iter().map(|ts| ts.map(|ts| ts - self.offset)),
+                ts_column.iter().map(|ts| ts.map(|ts| ts + self.offset)),
             )
         };
         let mut columns = input.columns().to_vec();
@@ -353,11 +353,11 @@ mod test {
             "+---------------------+-------+------+\
             \n| timestamp           | value | path |\
             \n+---------------------+-------+------+\
-            \n| 1969-12-31T23:59:59 | 10    | foo  |\
-            \n| 1970-01-01T00:00:29 | 100   | foo  |\
-            \n| 1970-01-01T00:00:59 | 0     | foo  |\
-            \n| 1970-01-01T00:01:29 | 1000  | foo  |\
-            \n| 1970-01-01T00:01:59 | 1     | foo  |\
+            \n| 1970-01-01T00:00:01 | 10    | foo  |\
+            \n| 1970-01-01T00:00:31 | 100   | foo  |\
+            \n| 1970-01-01T00:01:01 | 0     | foo  |\
+            \n| 1970-01-01T00:01:31 | 1000  | foo  |\
+            \n| 1970-01-01T00:02:01 | 1     | foo  |\
             \n+---------------------+-------+------+",
         );
 
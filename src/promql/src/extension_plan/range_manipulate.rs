@@ -41,14 +41,20 @@ use crate::range_array::RangeArray;
 
 /// Time series manipulator for range function.
 ///
-/// This plan will "fold" time index and value columns into [RangeArray]s, and truncate
-/// other columns to the same length with the "folded" [RangeArray] column.
+/// This plan will "fold" value columns into [RangeArray]s, and truncate other columns
+/// to the same length with the "folded" [RangeArray] column. The time index column is
+/// replaced by the aligned evaluation timestamps, and the original timestamps are also
+/// folded into a [RangeArray] column named by [`RangeManipulate::build_timestamp_range_name`]
+/// which is appended to the end of schema.
+///
+/// If `at` is set (the `@` modifier), the range ending at `at` is taken for every timestamp.
 #[derive(Debug)]
 pub struct RangeManipulate {
     start: Millisecond,
     end: Millisecond,
     interval: Millisecond,
     range: Millisecond,
+    at: Option<Millisecond>,
 
     time_index: String,
     value_columns: Vec<String>,
@@ -62,6 +68,7 @@ impl RangeManipulate {
         end: Millisecond,
         interval: Millisecond,
         range: Millisecond,
+        at: Option<Millisecond>,
         time_index: String,
         value_columns: Vec<String>,
         input: LogicalPlan,
@@ -73,6 +80,7 @@ impl RangeManipulate {
             end,
             interval,
             range,
+            at,
             time_index,
             value_columns,
             input,
@@ -80,6 +88,11 @@ impl RangeManipulate {
         })
     }
 
+    /// Name of the column that holds the [RangeArray] of original timestamps.
+    pub fn build_timestamp_range_name(time_index: &str) -> String {
+        format!("{time_index}_range")
+    }
+
     fn calculate_output_schema(
         input_schema: &DFSchemaRef,
         time_index: &str,
@@ -87,28 +100,44 @@ impl RangeManipulate {
     ) -> DataFusionResult<DFSchemaRef> {
         let mut columns = input_schema.fields().clone();
 
-        // process time index column
+        // process time index column, the original timestamps are moved to a new column
         let index = input_schema.index_of_column_by_name(None, time_index)?;
-        columns[index] = DFField::from(RangeArray::convert_field(columns[index].field()));
+        let ts_range_field = Self::convert_range_field(
+            &columns[index],
+            &Self::build_timestamp_range_name(time_index),
+        );
 
         // process value columns
         for name in value_columns {
             let index = input_schema.index_of_column_by_name(None, name)?;
-            columns[index] = DFField::from(RangeArray::convert_field(columns[index].field()));
+            columns[index] = Self::convert_range_field(&columns[index], name);
         }
 
+        columns.push(ts_range_field);
+
         Ok(Arc::new(DFSchema::new_with_metadata(
             columns,
             HashMap::new(),
         )?))
     }
 
+    /// Like [RangeArray::convert_field] but keeps the qualifier of field.
+    fn convert_range_field(field: &DFField, name: &str) -> DFField {
+        DFField::new(
+            field.qualifier().map(|qualifier| qualifier.as_str()),
+            name,
+            RangeArray::convert_data_type(field.data_type().clone()),
+            field.is_nullable(),
+        )
+    }
+
     pub fn to_execution_plan(&self, exec_input: Arc<dyn ExecutionPlan>) -> Arc<dyn ExecutionPlan> {
         Arc::new(RangeManipulateExec {
             start: self.start,
             end: self.end,
             interval: self.interval,
             range: self.range,
+            at: self.at,
             time_index_column: self.time_index.clone(),
             value_columns: self.value_columns.clone(),
             input: exec_input,
@@ -140,7 +169,11 @@ impl UserDefinedLogicalNode for RangeManipulate {
             f,
             "PromRangeManipulate: req range=[{}..{}], interval=[{}], eval range=[{}], time index=[{}], values={:?}",
             self.start, self.end, self.interval, self.range, self.time_index, self.value_columns
-        )
+        )?;
+        if let Some(at) = self.at {
+            write!(f, ", at=[{at}]")?;
+        }
+        Ok(())
     }
 
     fn from_template(
@@ -155,6 +188,7 @@ impl UserDefinedLogicalNode for RangeManipulate {
             end: self.end,
             interval: self.interval,
             range: self.range,
+            at: self.at,
             time_index: self.time_index.clone(),
             value_columns: self.value_columns.clone(),
             input: inputs[0].clone(),
@@ -169,6 +203,7 @@ pub struct RangeManipulateExec {
     end: Millisecond,
     interval: Millisecond,
     range: Millisecond,
+    at: Option<Millisecond>,
     time_index_column: String,
     value_columns: Vec<String>,
 
@@ -212,6 +247,7 @@ impl ExecutionPlan for RangeManipulateExec {
             end: self.end,
             interval: self.interval,
             range: self.range,
+            at: self.at,
             time_index_column: self.time_index_column.clone(),
             value_columns: self.value_columns.clone(),
             output_schema: self.output_schema.clone(),
@@ -248,6 +284,7 @@ impl ExecutionPlan for RangeManipulateExec {
             end: self.end,
             interval: self.interval,
             range: self.range,
+            at: self.at,
             time_index,
             value_columns,
             output_schema: self.output_schema.clone(),
@@ -261,9 +298,13 @@ impl ExecutionPlan for RangeManipulateExec {
             DisplayFormatType::Default => {
                 write!(
                     f,
-                    "PromInstantManipulateExec: req range=[{}..{}], interval=[{}], eval range=[{}], time index=[{}]",
+                    "PromRangeManipulateExec: req range=[{}..{}], interval=[{}], eval range=[{}], time index=[{}]",
                    self.start, self.end, self.interval, self.range, self.time_index_column
-                )
+                )?;
+                if let Some(at) = self.at {
+                    write!(f, ", at=[{at}]")?;
+                }
+                Ok(())
             }
         }
     }
@@ -297,6 +338,7 @@ pub struct RangeManipulateStream {
     end: Millisecond,
     interval: Millisecond,
     range: Millisecond,
+    at: Option<Millisecond>,
     time_index: usize,
     value_columns: Vec<usize>,
 
@@ -329,14 +371,20 @@ impl Stream for RangeManipulateStream {
 impl RangeManipulateStream {
     // Prometheus: https://github.com/prometheus/prometheus/blob/e934d0f01158a1d55fa0ebb035346b195fcc1260/promql/engine.go#L1113-L1198
     // But they are not exactly the same, because we don't eager-evaluate on the data in this plan.
-    // And the generated timestamp is not aligned to the step. It's expected to do later.
+    // The time index column is aligned to the step and the original timestamps are kept
+    // in the range column, so range functions can evaluate windows on them.
     pub fn manipulate(&self, input: RecordBatch) -> ArrowResult<RecordBatch> {
+        // there is nothing to fold for an empty batch
+        if input.num_rows() == 0 {
+            return Ok(RecordBatch::new_empty(self.output_schema.clone()));
+        }
+
         let mut other_columns = (0..input.columns().len()).collect::<HashSet<_>>();
         // calculate the range
-        let ranges = self.calculate_range(&input);
+        let (aligned_ts, ranges) = self.calculate_range(&input);
         // transform columns
         let mut new_columns = input.columns().to_vec();
-        for index in self.value_columns.iter().chain([self.time_index].iter()) {
+        for index in self.value_columns.iter() {
             other_columns.remove(index);
             let column = input.column(*index);
             let new_column = Arc::new(
@@ -347,6 +395,16 @@ impl RangeManipulateStream {
             new_columns[*index] = new_column;
         }
 
+        // replace time index column and append the timestamp range column
+        other_columns.remove(&self.time_index);
+        let ts_range_column = Arc::new(
+            RangeArray::from_ranges(input.column(self.time_index).clone(), ranges.clone())
+                .map_err(|e| ArrowError::InvalidArgumentError(e.to_string()))?
+                .into_dict(),
+        );
+        new_columns[self.time_index] = Arc::new(TimestampMillisecondArray::from(aligned_ts));
+        new_columns.push(ts_range_column);
+
         // truncate other columns
         let take_indices = Int64Array::from(vec![0; ranges.len()]);
         for index in other_columns.into_iter() {
@@ -356,37 +414,43 @@ impl RangeManipulateStream {
         RecordBatch::try_new(self.output_schema.clone(), new_columns)
     }
 
-    fn calculate_range(&self, input: &RecordBatch) -> Vec<(u32, u32)> {
+    /// Returns the aligned timestamps and the range of input rows for each of them.
+    fn calculate_range(&self, input: &RecordBatch) -> (Vec<Millisecond>, Vec<(u32, u32)>) {
         let ts_column = input
             .column(self.time_index)
             .as_any()
             .downcast_ref::<TimestampMillisecondArray>()
             .unwrap();
 
+        let mut aligned_ts = vec![];
         let mut result = vec![];
 
         // calculate for every aligned timestamp (`curr_ts`), assume the ts column is ordered.
+        // The `@` modifier fixes the end of the range.
         for curr_ts in (self.start..=self.end).step_by(self.interval as _) {
+            aligned_ts.push(curr_ts);
+            let curr_ts = self.at.unwrap_or(curr_ts);
             let mut range_start = ts_column.len();
+            // exclusive
             let mut range_end = 0;
             for (index, ts) in ts_column.values().iter().enumerate() {
                 if ts + self.range >= curr_ts {
                     range_start = range_start.min(index);
                 }
                 if *ts <= curr_ts {
-                    range_end = range_end.max(index);
+                    range_end = index + 1;
                 } else {
                     break;
                 }
             }
-            if range_start > range_end {
+            if range_start >= range_end {
                 result.push((0, 0));
             } else {
-                result.push((range_start as _, (range_end + 1 - range_start) as _));
+                result.push((range_start as _, (range_end - range_start) as _));
             }
         }
 
-        result
+        (aligned_ts, result)
     }
 }
 
@@ -440,6 +504,17 @@ mod test {
         interval: Millisecond,
         range: Millisecond,
         expected: String,
+    ) {
+        do_normalize_test_at(start, end, interval, range, None, expected).await;
+    }
+
+    async fn do_normalize_test_at(
+        start: Millisecond,
+        end: Millisecond,
+        interval: Millisecond,
+        range: Millisecond,
+        at: Option<Millisecond>,
+        expected: String,
     ) {
         let memory_exec = Arc::new(prepare_test_data());
         let time_index = TIME_INDEX_COLUMN.to_string();
@@ -459,6 +534,7 @@ mod test {
             end,
             interval,
             range,
+            at,
             value_columns,
             output_schema: manipulate_output_schema,
             time_index_column: time_index,
@@ -499,32 +575,54 @@ mod test {
     #[tokio::test]
     async fn interval_30s_range_90s() {
         let expected = String::from(
-            "RangeArray { \
-                base array: PrimitiveArray<Timestamp(Millisecond, None)>\n[\n  1970-01-01T00:00:00,\n  1970-01-01T00:00:30,\n  1970-01-01T00:01:00,\n  1970-01-01T00:01:30,\n  1970-01-01T00:02:00,\n  1970-01-01T00:03:00,\n  1970-01-01T00:04:00,\n  1970-01-01T00:04:01,\n  1970-01-01T00:04:31,\n  1970-01-01T00:04:51,\n], \
-                ranges: [Some(0..1), Some(0..2), Some(0..3), Some(0..4), Some(1..5), Some(2..5), Some(3..6), Some(4..6), Some(5..7), Some(5..8), Some(6..10)] \
-            }\nRangeArray { \
+            "PrimitiveArray<Timestamp(Millisecond, None)>\n[\n  1970-01-01T00:00:00,\n  1970-01-01T00:00:30,\n  1970-01-01T00:01:00,\n  1970-01-01T00:01:30,\n  1970-01-01T00:02:00,\n  1970-01-01T00:02:30,\n  1970-01-01T00:03:00,\n  1970-01-01T00:03:30,\n  1970-01-01T00:04:00,\n  1970-01-01T00:04:30,\n  1970-01-01T00:05:00,\n]\
+            \nRangeArray { \
                 base array: PrimitiveArray<Float64>\n[\n  1.0,\n  1.0,\n  1.0,\n  1.0,\n  1.0,\n  1.0,\n  1.0,\n  1.0,\n  1.0,\n  1.0,\n], \
                 ranges: [Some(0..1), Some(0..2), Some(0..3), Some(0..4), Some(1..5), Some(2..5), Some(3..6), Some(4..6), Some(5..7), Some(5..8), Some(6..10)] \
             }\nRangeArray { \
                 base array: PrimitiveArray<Float64>\n[\n  1.0,\n  1.0,\n  1.0,\n  1.0,\n  1.0,\n  1.0,\n  1.0,\n  1.0,\n  1.0,\n  1.0,\n], \
                 ranges: [Some(0..1), Some(0..2), Some(0..3), Some(0..4), Some(1..5), Some(2..5), Some(3..6), Some(4..6), Some(5..7), Some(5..8), Some(6..10)] \
-            }\nStringArray\n[\n  \"foo\",\n  \"foo\",\n  \"foo\",\n  \"foo\",\n  \"foo\",\n  \"foo\",\n  \"foo\",\n  \"foo\",\n  \"foo\",\n  \"foo\",\n  \"foo\",\n]");
+            }\nStringArray\n[\n  \"foo\",\n  \"foo\",\n  \"foo\",\n  \"foo\",\n  \"foo\",\n  \"foo\",\n  \"foo\",\n  \"foo\",\n  \"foo\",\n  \"foo\",\n  \"foo\",\n]\
+            \nRangeArray { \
+                base array: PrimitiveArray<Timestamp(Millisecond, None)>\n[\n  1970-01-01T00:00:00,\n  1970-01-01T00:00:30,\n  1970-01-01T00:01:00,\n  1970-01-01T00:01:30,\n  1970-01-01T00:02:00,\n  1970-01-01T00:03:00,\n  1970-01-01T00:04:00,\n  1970-01-01T00:04:01,\n  1970-01-01T00:04:31,\n  1970-01-01T00:04:51,\n], \
+                ranges: [Some(0..1), Some(0..2), Some(0..3), Some(0..4), Some(1..5), Some(2..5), Some(3..6), Some(4..6), Some(5..7), Some(5..8), Some(6..10)] \
+            }");
         do_normalize_test(0, 310_000, 30_000, 90_000, expected).await;
     }
 
     #[tokio::test]
     async fn small_empty_range() {
         let expected = String::from(
-        "RangeArray { \
-            base array: PrimitiveArray<Timestamp(Millisecond, None)>\n[\n  1970-01-01T00:00:00,\n  1970-01-01T00:00:30,\n  1970-01-01T00:01:00,\n  1970-01-01T00:01:30,\n  1970-01-01T00:02:00,\n  1970-01-01T00:03:00,\n  1970-01-01T00:04:00,\n  1970-01-01T00:04:01,\n  1970-01-01T00:04:31,\n  1970-01-01T00:04:51,\n], \
-            ranges: [Some(0..1), Some(0..0), Some(0..0), Some(0..0)] \
-        }\nRangeArray { \
+        "PrimitiveArray<Timestamp(Millisecond, None)>\n[\n  1970-01-01T00:00:00.001,\n  1970-01-01T00:00:03.001,\n  1970-01-01T00:00:06.001,\n  1970-01-01T00:00:09.001,\n]\
+        \nRangeArray { \
             base array: PrimitiveArray<Float64>\n[\n  1.0,\n  1.0,\n  1.0,\n  1.0,\n  1.0,\n  1.0,\n  1.0,\n  1.0,\n  1.0,\n  1.0,\n], \
             ranges: [Some(0..1), Some(0..0), Some(0..0), Some(0..0)] \
         }\nRangeArray { \
             base array: PrimitiveArray<Float64>\n[\n  1.0,\n  1.0,\n  1.0,\n  1.0,\n  1.0,\n  1.0,\n  1.0,\n  1.0,\n  1.0,\n  1.0,\n], \
             ranges: [Some(0..1), Some(0..0), Some(0..0), Some(0..0)] \
-        }\nStringArray\n[\n  \"foo\",\n  \"foo\",\n  \"foo\",\n  \"foo\",\n]");
+        }\nStringArray\n[\n  \"foo\",\n  \"foo\",\n  \"foo\",\n  \"foo\",\n]\
+        \nRangeArray { \
+            base array: PrimitiveArray<Timestamp(Millisecond, None)>\n[\n  1970-01-01T00:00:00,\n  1970-01-01T00:00:30,\n  1970-01-01T00:01:00,\n  1970-01-01T00:01:30,\n  1970-01-01T00:02:00,\n  1970-01-01T00:03:00,\n  1970-01-01T00:04:00,\n  1970-01-01T00:04:01,\n  1970-01-01T00:04:31,\n  1970-01-01T00:04:51,\n], \
+            ranges: [Some(0..1), Some(0..0), Some(0..0), Some(0..0)] \
+        }");
         do_normalize_test(1, 10_001, 3_000, 1_000, expected).await;
     }
+
+    #[tokio::test]
+    async fn range_at_fixed_timestamp() {
+        let expected = String::from(
+        "PrimitiveArray<Timestamp(Millisecond, None)>\n[\n  1970-01-01T00:00:00,\n  1970-01-01T00:00:30,\n  1970-01-01T00:01:00,\n]\
+        \nRangeArray { \
+            base array: PrimitiveArray<Float64>\n[\n  1.0,\n  1.0,\n  1.0,\n  1.0,\n  1.0,\n  1.0,\n  1.0,\n  1.0,\n  1.0,\n  1.0,\n], \
+            ranges: [Some(5..8), Some(5..8), Some(5..8)] \
+        }\nRangeArray { \
+            base array: PrimitiveArray<Float64>\n[\n  1.0,\n  1.0,\n  1.0,\n  1.0,\n  1.0,\n  1.0,\n  1.0,\n  1.0,\n  1.0,\n  1.0,\n], \
+            ranges: [Some(5..8), Some(5..8), Some(5..8)] \
+        }\nStringArray\n[\n  \"foo\",\n  \"foo\",\n  \"foo\",\n]\
+        \nRangeArray { \
+            base array: PrimitiveArray<Timestamp(Millisecond, None)>\n[\n  1970-01-01T00:00:00,\n  1970-01-01T00:00:30,\n  1970-01-01T00:01:00,\n  1970-01-01T00:01:30,\n  1970-01-01T00:02:00,\n  1970-01-01T00:03:00,\n  1970-01-01T00:04:00,\n  1970-01-01T00:04:01,\n  1970-01-01T00:04:31,\n  1970-01-01T00:04:51,\n], \
+            ranges: [Some(5..8), Some(5..8), Some(5..8)] \
+        }");
+        do_normalize_test_at(0, 60_000, 30_000, 90_000, Some(245_000), expected).await;
+    }
 }
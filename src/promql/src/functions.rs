@@ -0,0 +1,195 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! PromQL functions that consume range vectors. They are implemented as DataFusion's
+//! [ScalarUDF] over [RangeArray]s generated by [RangeManipulate].
+//!
+//! [RangeManipulate]: crate::extension_plan::RangeManipulate
+
+/// Define a unit struct for the range function `$fn_name`, which evaluates every
+/// window by `$window_fn`. See [window_function_udf] for details.
+macro_rules! define_window_function {
+    ($(#[$meta:meta])* $name:ident, $fn_name:literal, $window_fn:path) => {
+        $(#[$meta])*
+        #[derive(Debug)]
+        pub struct $name;
+
+        impl $name {
+            pub const fn name() -> &'static str {
+                $fn_name
+            }
+
+            pub fn scalar_udf() -> datafusion::physical_plan::udf::ScalarUDF {
+                crate::functions::window_function_udf(Self::name(), $window_fn)
+            }
+        }
+    };
+}
+
+mod aggr_over_time;
+mod changes;
+mod extrapolate_rate;
+mod idelta;
+mod quantile;
+mod resets;
+#[cfg(test)]
+mod test_util;
+
+use std::sync::Arc;
+
+pub use aggr_over_time::{
+    AbsentOverTime, AvgOverTime, CountOverTime, LastOverTime, MaxOverTime, MinOverTime,
+    PresentOverTime, QuantileOverTime, StddevOverTime, StdvarOverTime, SumOverTime,
+};
+pub use changes::Changes;
+use datafusion::arrow::array::{Array, ArrayRef, DictionaryArray, Float64Array};
+use datafusion::arrow::compute;
+use datafusion::arrow::datatypes::{DataType, Int64Type, TimeUnit};
+use datafusion::error::DataFusionError;
+use datafusion::logical_expr::{
+    ColumnarValue, ReturnTypeFunction, ScalarFunctionImplementation, Signature, TypeSignature,
+    Volatility,
+};
+use datafusion::physical_plan::udf::ScalarUDF;
+use datatypes::arrow::array::TimestampMillisecondArray;
+pub use extrapolate_rate::{Delta, ExtrapolatedRate, Increase, Rate};
+pub use idelta::IDelta;
+pub use quantile::Quantile;
+pub use resets::Resets;
+
+use crate::range_array::RangeArray;
+
+/// Signature of a function that evaluates one window of a series. Returns `None`
+/// if nothing should be output for this window.
+pub(crate) type WindowFunction = fn(&TimestampMillisecondArray, &Float64Array) -> Option<f64>;
+
+/// Build a [ScalarUDF] of `name` that applies `window_fn` on every window of its
+/// two inputs, the timestamp [RangeArray] and the value [RangeArray].
+pub(crate) fn window_function_udf(name: &str, window_fn: WindowFunction) -> ScalarUDF {
+    let fun: ScalarFunctionImplementation = Arc::new(move |input: &[ColumnarValue]| {
+        if input.len() != 2 {
+            return Err(DataFusionError::Plan(format!(
+                "expect 2 inputs for range function, but found {}",
+                input.len()
+            )));
+        }
+        let ts_range = extract_range_array(&input[0])?;
+        let value_range = extract_range_array(&input[1])?;
+        eval_windows(&ts_range, &value_range, |_, ts, values| {
+            window_fn(ts, values)
+        })
+    });
+
+    ScalarUDF::new(
+        name,
+        &Signature::new(
+            TypeSignature::Exact(vec![timestamp_range_type(), value_range_type()]),
+            Volatility::Immutable,
+        ),
+        &float64_return_type(),
+        &fun,
+    )
+}
+
+/// Apply `f` on every window of the given [RangeArray]s. The first argument of `f`
+/// is the index of window.
+pub(crate) fn eval_windows<F>(
+    ts_range: &RangeArray,
+    value_range: &RangeArray,
+    mut f: F,
+) -> Result<ColumnarValue, DataFusionError>
+where
+    F: FnMut(usize, &TimestampMillisecondArray, &Float64Array) -> Option<f64>,
+{
+    if ts_range.len() != value_range.len() {
+        return Err(DataFusionError::Execution(format!(
+            "timestamp range length {} doesn't match value range length {}",
+            ts_range.len(),
+            value_range.len()
+        )));
+    }
+
+    let mut result = Vec::with_capacity(ts_range.len());
+    for index in 0..ts_range.len() {
+        // safety: index is checked above
+        let mut ts = ts_range.get(index).unwrap();
+        let mut values = value_range.get(index).unwrap();
+        // samples with null value are skipped, as if they are not scraped
+        if values.null_count() > 0 {
+            let mask = compute::is_not_null(values.as_ref())?;
+            ts = compute::filter(ts.as_ref(), &mask)?;
+            values = compute::filter(values.as_ref(), &mask)?;
+        }
+        let ts = ts
+            .as_any()
+            .downcast_ref::<TimestampMillisecondArray>()
+            .ok_or_else(|| {
+                DataFusionError::Execution(format!(
+                    "expect TimestampMillisecond range, found {:?}",
+                    ts.data_type()
+                ))
+            })?;
+        let values = values
+            .as_any()
+            .downcast_ref::<Float64Array>()
+            .ok_or_else(|| {
+                DataFusionError::Execution(format!(
+                    "expect Float64 range, found {:?}",
+                    values.data_type()
+                ))
+            })?;
+        result.push(f(index, ts, values));
+    }
+
+    Ok(ColumnarValue::Array(Arc::new(Float64Array::from(result))))
+}
+
+pub(crate) fn extract_array(columnar_value: &ColumnarValue) -> Result<ArrayRef, DataFusionError> {
+    if let ColumnarValue::Array(array) = columnar_value {
+        Ok(array.clone())
+    } else {
+        Err(DataFusionError::Execution(String::from(
+            "expect array as input, found scalar value",
+        )))
+    }
+}
+
+pub(crate) fn extract_range_array(
+    columnar_value: &ColumnarValue,
+) -> Result<RangeArray, DataFusionError> {
+    let array = extract_array(columnar_value)?;
+    let dict = array
+        .as_any()
+        .downcast_ref::<DictionaryArray<Int64Type>>()
+        .ok_or_else(|| {
+            DataFusionError::Execution(format!(
+                "expect range array as input, found {:?}",
+                array.data_type()
+            ))
+        })?
+        .clone();
+    RangeArray::try_new(dict).map_err(|e| DataFusionError::Execution(e.to_string()))
+}
+
+pub(crate) fn timestamp_range_type() -> DataType {
+    RangeArray::convert_data_type(DataType::Timestamp(TimeUnit::Millisecond, None))
+}
+
+pub(crate) fn value_range_type() -> DataType {
+    RangeArray::convert_data_type(DataType::Float64)
+}
+
+pub(crate) fn float64_return_type() -> ReturnTypeFunction {
+    Arc::new(|_| Ok(Arc::new(DataType::Float64)))
+}
@@ -0,0 +1,514 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Implementations of [`<aggregation>_over_time`](https://prometheus.io/docs/prometheus/latest/querying/functions/#aggregation_over_time)
+//! functions in PromQL. They aggregate all values of each window.
+
+use std::cmp::Ordering;
+use std::sync::Arc;
+
+use datafusion::arrow::array::{Array, Float64Array};
+use datafusion::arrow::datatypes::DataType;
+use datafusion::error::DataFusionError;
+use datafusion::logical_expr::{
+    ColumnarValue, ScalarFunctionImplementation, Signature, TypeSignature, Volatility,
+};
+use datafusion::physical_plan::udf::ScalarUDF;
+use datafusion::scalar::ScalarValue;
+use datatypes::arrow::array::TimestampMillisecondArray;
+
+use crate::functions::{
+    eval_windows, extract_range_array, float64_return_type, timestamp_range_type, value_range_type,
+};
+
+define_window_function!(
+    /// The average value of all points in the specified interval.
+    AvgOverTime,
+    "prom_avg_over_time",
+    avg_over_time
+);
+
+define_window_function!(
+    /// The minimum value of all points in the specified interval.
+    MinOverTime,
+    "prom_min_over_time",
+    min_over_time
+);
+
+define_window_function!(
+    /// The maximum value of all points in the specified interval.
+    MaxOverTime,
+    "prom_max_over_time",
+    max_over_time
+);
+
+define_window_function!(
+    /// The sum of all values in the specified interval.
+    SumOverTime,
+    "prom_sum_over_time",
+    sum_over_time
+);
+
+define_window_function!(
+    /// The count of all values in the specified interval.
+    CountOverTime,
+    "prom_count_over_time",
+    count_over_time
+);
+
+define_window_function!(
+    /// The most recent point value in the specified interval.
+    LastOverTime,
+    "prom_last_over_time",
+    last_over_time
+);
+
+define_window_function!(
+    /// The population standard deviation of the values in the specified interval.
+    StddevOverTime,
+    "prom_stddev_over_time",
+    stddev_over_time
+);
+
+define_window_function!(
+    /// The population standard variance of the values in the specified interval.
+    StdvarOverTime,
+    "prom_stdvar_over_time",
+    stdvar_over_time
+);
+
+define_window_function!(
+    /// The value 1 for any series in the specified interval.
+    PresentOverTime,
+    "prom_present_over_time",
+    present_over_time
+);
+
+define_window_function!(
+    /// The value 1 if the specified interval has no elements. Notice that the "series"
+    /// here is the input series, so steps without any point in the range of an existing
+    /// series get 1, while a metric without any series outputs nothing.
+    AbsentOverTime,
+    "prom_absent_over_time",
+    absent_over_time
+);
+
+fn avg_over_time(_: &TimestampMillisecondArray, values: &Float64Array) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+
+    // Calculate the mean incrementally to avoid overflow of the sum.
+    let mut mean = 0.0;
+    for (count, value) in values.values().iter().enumerate() {
+        if mean.is_infinite() {
+            // The mean is infinite and cannot be changed by another infinite value of the
+            // same sign or any finite value. Continue to avoid producing NaN.
+            if value.is_infinite() && (mean > 0.0) == (*value > 0.0) {
+                continue;
+            }
+            if !value.is_infinite() && !value.is_nan() {
+                continue;
+            }
+        }
+        let count = (count + 1) as f64;
+        mean += value / count - mean / count;
+    }
+
+    Some(mean)
+}
+
+fn min_over_time(_: &TimestampMillisecondArray, values: &Float64Array) -> Option<f64> {
+    let values = values.values();
+    let mut min = *values.first()?;
+    for value in values {
+        // NaN is only kept if all values are NaN
+        if *value < min || min.is_nan() {
+            min = *value;
+        }
+    }
+    Some(min)
+}
+
+fn max_over_time(_: &TimestampMillisecondArray, values: &Float64Array) -> Option<f64> {
+    let values = values.values();
+    let mut max = *values.first()?;
+    for value in values {
+        // NaN is only kept if all values are NaN
+        if *value > max || max.is_nan() {
+            max = *value;
+        }
+    }
+    Some(max)
+}
+
+fn sum_over_time(_: &TimestampMillisecondArray, values: &Float64Array) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    Some(values.values().iter().sum())
+}
+
+fn count_over_time(_: &TimestampMillisecondArray, values: &Float64Array) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    Some(values.len() as f64)
+}
+
+fn last_over_time(_: &TimestampMillisecondArray, values: &Float64Array) -> Option<f64> {
+    values.values().last().copied()
+}
+
+fn stddev_over_time(ts: &TimestampMillisecondArray, values: &Float64Array) -> Option<f64> {
+    stdvar_over_time(ts, values).map(f64::sqrt)
+}
+
+fn stdvar_over_time(_: &TimestampMillisecondArray, values: &Float64Array) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+
+    // Welford's online algorithm
+    let mut mean = 0.0;
+    let mut aux = 0.0;
+    for (count, value) in values.values().iter().enumerate() {
+        let delta = value - mean;
+        mean += delta / (count + 1) as f64;
+        aux += delta * (value - mean);
+    }
+
+    Some(aux / values.len() as f64)
+}
+
+fn present_over_time(_: &TimestampMillisecondArray, values: &Float64Array) -> Option<f64> {
+    if values.is_empty() {
+        None
+    } else {
+        Some(1.0)
+    }
+}
+
+fn absent_over_time(_: &TimestampMillisecondArray, values: &Float64Array) -> Option<f64> {
+    if values.is_empty() {
+        Some(1.0)
+    } else {
+        None
+    }
+}
+
+/// The φ-quantile (0 ≤ φ ≤ 1) of the values in the specified interval. φ is given
+/// as the third input in a Float64 literal.
+#[derive(Debug)]
+pub struct QuantileOverTime;
+
+impl QuantileOverTime {
+    pub const fn name() -> &'static str {
+        "prom_quantile_over_time"
+    }
+
+    pub fn scalar_udf() -> ScalarUDF {
+        let fun: ScalarFunctionImplementation = Arc::new(Self::calc);
+        ScalarUDF::new(
+            Self::name(),
+            &Signature::new(
+                TypeSignature::Exact(vec![
+                    timestamp_range_type(),
+                    value_range_type(),
+                    DataType::Float64,
+                ]),
+                Volatility::Immutable,
+            ),
+            &float64_return_type(),
+            &fun,
+        )
+    }
+
+    fn calc(input: &[ColumnarValue]) -> Result<ColumnarValue, DataFusionError> {
+        if input.len() != 3 {
+            return Err(DataFusionError::Plan(format!(
+                "expect 3 inputs for {}, but found {}",
+                Self::name(),
+                input.len()
+            )));
+        }
+        let ts_range = extract_range_array(&input[0])?;
+        let value_range = extract_range_array(&input[1])?;
+        let ColumnarValue::Scalar(ScalarValue::Float64(Some(quantile))) = &input[2] else {
+            return Err(DataFusionError::Execution(format!(
+                "expect quantile in Float64 literal for {}",
+                Self::name()
+            )));
+        };
+
+        eval_windows(&ts_range, &value_range, |_, _, values| {
+            calc_quantile(*quantile, values)
+        })
+    }
+}
+
+/// The φ-quantile of `values`, interpolated linearly between the two nearest values.
+/// Returns -Inf if φ < 0 and +Inf if φ > 1.
+// Prometheus: `quantile()` in `promql/quantile.go`
+pub(crate) fn calc_quantile(quantile: f64, values: &Float64Array) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    if quantile.is_nan() {
+        return Some(f64::NAN);
+    }
+    if quantile < 0.0 {
+        return Some(f64::NEG_INFINITY);
+    }
+    if quantile > 1.0 {
+        return Some(f64::INFINITY);
+    }
+
+    // NaN is placed in front of all other values.
+    let mut values = values.values().to_vec();
+    values.sort_unstable_by(|a, b| match (a.is_nan(), b.is_nan()) {
+        (true, true) => Ordering::Equal,
+        (true, false) => Ordering::Less,
+        (false, true) => Ordering::Greater,
+        (false, false) => a.partial_cmp(b).unwrap(),
+    });
+
+    let n = values.len() as f64;
+    let rank = quantile * (n - 1.0);
+    let lower_index = rank.floor().max(0.0);
+    let upper_index = (lower_index + 1.0).min(n - 1.0);
+    let weight = rank - rank.floor();
+
+    Some(values[lower_index as usize] * (1.0 - weight) + values[upper_index as usize] * weight)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::functions::test_util::{range_inputs, run_and_check};
+
+    const TIMESTAMPS: [i64; 5] = [0, 10_000, 20_000, 30_000, 40_000];
+
+    fn check(udf: ScalarUDF, values: &[f64], ranges: &[(u32, u32)], expected: Vec<Option<f64>>) {
+        let (ts, values) = range_inputs(&TIMESTAMPS[..values.len()], values, ranges);
+        run_and_check(udf, &[ts, values], expected);
+    }
+
+    // From prometheus `promql/testdata/functions.test`.
+    //
+    // ```text
+    // load 10s
+    //     data{type="numbers"} 2 0 3
+    //     data{type="some_nan"} 2 0 NaN
+    //     data{type="some_nan2"} 2 NaN 1
+    //     data{type="some_nan3"} NaN 0 1
+    //     data{type="only_nan"} NaN NaN NaN
+    //
+    // eval instant at 1m min_over_time(data[1m])
+    // eval instant at 1m max_over_time(data[1m])
+    // eval instant at 1m last_over_time(data[1m])
+    // ```
+    #[test]
+    fn min_max_last_over_time() {
+        let nan = f64::NAN;
+        let cases = [
+            ([2., 0., 3.], [0., 3., 3.]),
+            ([2., 0., nan], [0., 2., nan]),
+            ([2., nan, 1.], [1., 2., 1.]),
+            ([nan, 0., 1.], [0., 1., 1.]),
+            ([nan, nan, nan], [nan, nan, nan]),
+        ];
+        for (values, [min, max, last]) in cases {
+            let ranges = [(0, 3), (0, 0)];
+            check(
+                MinOverTime::scalar_udf(),
+                &values,
+                &ranges,
+                vec![Some(min), None],
+            );
+            check(
+                MaxOverTime::scalar_udf(),
+                &values,
+                &ranges,
+                vec![Some(max), None],
+            );
+            check(
+                LastOverTime::scalar_udf(),
+                &values,
+                &ranges,
+                vec![Some(last), None],
+            );
+        }
+    }
+
+    // From prometheus `promql/testdata/functions.test`.
+    //
+    // ```text
+    // load 10s
+    //     metric 1 2 3 4 5
+    //     metric2 1 2 3 4 Inf
+    //     metric3 1.7976931348623157e+308 1.7976931348623157e+308 1.7976931348623157e+308
+    //
+    // eval instant at 1m avg_over_time(metric[1m])
+    //     {} 3
+    //
+    // eval instant at 1m sum_over_time(metric[1m])/count_over_time(metric[1m])
+    //     {} 3
+    //
+    // eval instant at 1m avg_over_time(metric2[1m])
+    //     {} Inf
+    //
+    // eval instant at 1m avg_over_time(metric3[1m])
+    //     {} 1.7976931348623157e+308
+    // ```
+    #[test]
+    fn avg_sum_count_over_time() {
+        let ranges = [(0, 5), (1, 2), (0, 0)];
+        let metric = [1., 2., 3., 4., 5.];
+        check(
+            AvgOverTime::scalar_udf(),
+            &metric,
+            &ranges,
+            vec![Some(3.0), Some(2.5), None],
+        );
+        check(
+            SumOverTime::scalar_udf(),
+            &metric,
+            &ranges,
+            vec![Some(15.0), Some(5.0), None],
+        );
+        check(
+            CountOverTime::scalar_udf(),
+            &metric,
+            &ranges,
+            vec![Some(5.0), Some(2.0), None],
+        );
+
+        check(
+            AvgOverTime::scalar_udf(),
+            &[1., 2., 3., 4., f64::INFINITY],
+            &ranges,
+            vec![Some(f64::INFINITY), Some(2.5), None],
+        );
+        check(
+            AvgOverTime::scalar_udf(),
+            &[f64::MAX; 3],
+            &[(0, 3)],
+            vec![Some(f64::MAX)],
+        );
+    }
+
+    // From prometheus `promql/testdata/functions.test`.
+    //
+    // ```text
+    // load 10s
+    //     metric 0 8 8 2 3
+    //
+    // eval instant at 1m stdvar_over_time(metric[1m])
+    //     {} 10.56
+    //
+    // eval instant at 1m stddev_over_time(metric[1m])
+    //     {} 3.249615
+    //
+    // load 10s
+    //     metric 1.5990505637277868 1.5990505637277868 1.5990505637277868
+    //
+    // eval instant at 1m stdvar_over_time(metric[1m])
+    //     {} 0
+    // ```
+    #[test]
+    fn stddev_stdvar_over_time() {
+        let metric = [0., 8., 8., 2., 3.];
+        check(
+            StdvarOverTime::scalar_udf(),
+            &metric,
+            &[(0, 5), (0, 0)],
+            vec![Some(10.56), None],
+        );
+        check(
+            StddevOverTime::scalar_udf(),
+            &metric,
+            &[(0, 5), (0, 0)],
+            vec![Some(10.56f64.sqrt()), None],
+        );
+        check(
+            StdvarOverTime::scalar_udf(),
+            &[1.5990505637277868; 3],
+            &[(0, 3)],
+            vec![Some(0.0)],
+        );
+    }
+
+    #[test]
+    fn present_absent_over_time() {
+        let ranges = [(0, 3), (2, 1), (0, 0)];
+        check(
+            PresentOverTime::scalar_udf(),
+            &[1., 2., 3.],
+            &ranges,
+            vec![Some(1.0), Some(1.0), None],
+        );
+        check(
+            AbsentOverTime::scalar_udf(),
+            &[1., 2., 3.],
+            &ranges,
+            vec![None, None, Some(1.0)],
+        );
+    }
+
+    // From prometheus `promql/testdata/functions.test`.
+    //
+    // ```text
+    // load 10s
+    //     data{test="two samples"} 0 1
+    //     data{test="three samples"} 0 1 2
+    //     data{test="uneven samples"} 0 1 4
+    //
+    // eval instant at 1m quantile_over_time(<quantile>, data[1m])
+    // ```
+    #[test]
+    fn quantile_over_time() {
+        let cases = [
+            (0.0, [0.0, 0.0, 0.0]),
+            (0.5, [0.5, 1.0, 1.0]),
+            (0.75, [0.75, 1.5, 2.5]),
+            (0.8, [0.8, 1.6, 2.8]),
+            (1.0, [1.0, 2.0, 4.0]),
+            (-1.0, [f64::NEG_INFINITY; 3]),
+            (2.0, [f64::INFINITY; 3]),
+        ];
+        for (quantile, [two, three, uneven]) in cases {
+            for (values, expected) in [
+                (&[0., 1.][..], two),
+                (&[0., 1., 2.][..], three),
+                (&[0., 1., 4.][..], uneven),
+            ] {
+                let (ts, values) = range_inputs(
+                    &TIMESTAMPS[..values.len()],
+                    values,
+                    &[(0, values.len() as u32), (0, 0)],
+                );
+                run_and_check(
+                    QuantileOverTime::scalar_udf(),
+                    &[
+                        ts,
+                        values,
+                        ColumnarValue::Scalar(ScalarValue::Float64(Some(quantile))),
+                    ],
+                    vec![Some(expected), None],
+                );
+            }
+        }
+    }
+}
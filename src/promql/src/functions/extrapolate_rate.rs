@@ -0,0 +1,366 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Implementations of [`delta`](https://prometheus.io/docs/prometheus/latest/querying/functions/#delta),
+//! [`increase`](https://prometheus.io/docs/prometheus/latest/querying/functions/#increase) and
+//! [`rate`](https://prometheus.io/docs/prometheus/latest/querying/functions/#rate) in PromQL.
+//! They share the same extrapolation algorithm of Prometheus.
+
+use std::sync::Arc;
+
+use datafusion::arrow::array::{Array, Float64Array};
+use datafusion::arrow::datatypes::{DataType, TimeUnit};
+use datafusion::error::DataFusionError;
+use datafusion::logical_expr::{
+    ColumnarValue, ScalarFunctionImplementation, Signature, TypeSignature, Volatility,
+};
+use datafusion::physical_plan::udf::ScalarUDF;
+use datafusion::scalar::ScalarValue;
+use datatypes::arrow::array::TimestampMillisecondArray;
+
+use crate::extension_plan::Millisecond;
+use crate::functions::{
+    eval_windows, extract_range_array, float64_return_type, timestamp_range_type, value_range_type,
+};
+
+pub type Delta = ExtrapolatedRate<false, false>;
+pub type Increase = ExtrapolatedRate<true, false>;
+pub type Rate = ExtrapolatedRate<true, true>;
+
+/// Calculate the difference between the first and last samples of the range, and
+/// extrapolate it to the whole range. `IS_COUNTER` adjusts the difference by counter
+/// resets, and `IS_RATE` converts the result to per-second.
+///
+/// Besides the timestamp and value ranges, it takes two more inputs: the evaluation
+/// timestamp of each range, and the length of range in millisecond as a scalar.
+#[derive(Debug)]
+pub struct ExtrapolatedRate<const IS_COUNTER: bool, const IS_RATE: bool>;
+
+impl<const IS_COUNTER: bool, const IS_RATE: bool> ExtrapolatedRate<IS_COUNTER, IS_RATE> {
+    pub const fn name() -> &'static str {
+        match (IS_COUNTER, IS_RATE) {
+            (true, true) => "prom_rate",
+            (true, false) => "prom_increase",
+            (false, _) => "prom_delta",
+        }
+    }
+
+    pub fn scalar_udf() -> ScalarUDF {
+        let fun: ScalarFunctionImplementation = Arc::new(Self::calc);
+        ScalarUDF::new(
+            Self::name(),
+            &Signature::new(
+                TypeSignature::Exact(vec![
+                    timestamp_range_type(),
+                    value_range_type(),
+                    DataType::Timestamp(TimeUnit::Millisecond, None),
+                    DataType::Int64,
+                ]),
+                Volatility::Immutable,
+            ),
+            &float64_return_type(),
+            &fun,
+        )
+    }
+
+    fn calc(input: &[ColumnarValue]) -> Result<ColumnarValue, DataFusionError> {
+        if input.len() != 4 {
+            return Err(DataFusionError::Plan(format!(
+                "expect 4 inputs for {}, but found {}",
+                Self::name(),
+                input.len()
+            )));
+        }
+        let ts_range = extract_range_array(&input[0])?;
+        let value_range = extract_range_array(&input[1])?;
+        // the evaluation timestamp is a literal if it's fixed by the `@` modifier
+        let eval_ts = input[2].clone().into_array(ts_range.len());
+        let eval_ts = eval_ts
+            .as_any()
+            .downcast_ref::<TimestampMillisecondArray>()
+            .ok_or_else(|| {
+                DataFusionError::Execution(format!(
+                    "expect TimestampMillisecond as evaluation timestamp, found {:?}",
+                    eval_ts.data_type()
+                ))
+            })?;
+        let ColumnarValue::Scalar(ScalarValue::Int64(Some(range_length))) = &input[3] else {
+            return Err(DataFusionError::Execution(format!(
+                "expect range length in Int64 literal for {}",
+                Self::name()
+            )));
+        };
+        if eval_ts.len() != ts_range.len() {
+            return Err(DataFusionError::Execution(format!(
+                "evaluation timestamp length {} doesn't match range length {}",
+                eval_ts.len(),
+                ts_range.len()
+            )));
+        }
+
+        eval_windows(&ts_range, &value_range, |index, ts, values| {
+            Self::extrapolate(eval_ts.value(index), *range_length, ts, values)
+        })
+    }
+
+    // Prometheus: `extrapolatedRate()` in `promql/functions.go`
+    fn extrapolate(
+        eval_ts: Millisecond,
+        range_length: Millisecond,
+        ts: &TimestampMillisecondArray,
+        values: &Float64Array,
+    ) -> Option<f64> {
+        let len = values.len();
+        if len < 2 {
+            return None;
+        }
+        let range_start = eval_ts - range_length;
+        let range_end = eval_ts;
+        let (first_ts, last_ts) = (ts.value(0), ts.value(len - 1));
+        let (first_value, last_value) = (values.value(0), values.value(len - 1));
+
+        let mut result = last_value - first_value;
+        if IS_COUNTER {
+            // compensate counter resets
+            let mut prev = 0.0;
+            for curr in values.values() {
+                if *curr < prev {
+                    result += prev;
+                }
+                prev = *curr;
+            }
+        }
+
+        // Duration between first/last samples and boundary of range.
+        let mut duration_to_start = (first_ts - range_start) as f64 / 1000.0;
+        let duration_to_end = (range_end - last_ts) as f64 / 1000.0;
+        let sampled_interval = (last_ts - first_ts) as f64 / 1000.0;
+        let average_duration_between_samples = sampled_interval / (len - 1) as f64;
+
+        if IS_COUNTER && result > 0.0 && first_value >= 0.0 {
+            // Counters cannot be negative. If we have any slope at all (i.e. result
+            // went up), we can extrapolate the zero point of the counter. If the
+            // duration to the zero point is shorter than the duration to start, we
+            // take the zero point as the start of the series, thereby avoiding
+            // extrapolation to negative counter values.
+            let duration_to_zero = sampled_interval * (first_value / result);
+            if duration_to_zero < duration_to_start {
+                duration_to_start = duration_to_zero;
+            }
+        }
+
+        // If the first/last samples are close to the boundaries of the range,
+        // extrapolate the result. This is as we expect that another sample will
+        // exist given the spacing between samples we've seen thus far, with an
+        // allowance for noise.
+        let extrapolation_threshold = average_duration_between_samples * 1.1;
+        let mut extrapolate_to_interval = sampled_interval;
+        if duration_to_start < extrapolation_threshold {
+            extrapolate_to_interval += duration_to_start;
+        } else {
+            extrapolate_to_interval += average_duration_between_samples / 2.0;
+        }
+        if duration_to_end < extrapolation_threshold {
+            extrapolate_to_interval += duration_to_end;
+        } else {
+            extrapolate_to_interval += average_duration_between_samples / 2.0;
+        }
+
+        result *= extrapolate_to_interval / sampled_interval;
+        if IS_RATE {
+            result /= range_length as f64 / 1000.0;
+        }
+
+        Some(result)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::functions::test_util::{range_inputs, run_and_check};
+
+    fn extra_inputs(eval_ts: &[i64], range_length: i64) -> [ColumnarValue; 2] {
+        [
+            ColumnarValue::Array(Arc::new(TimestampMillisecondArray::from(eval_ts.to_vec()))),
+            ColumnarValue::Scalar(ScalarValue::Int64(Some(range_length))),
+        ]
+    }
+
+    fn check<const IS_COUNTER: bool, const IS_RATE: bool>(
+        timestamps: &[i64],
+        values: &[f64],
+        ranges: &[(u32, u32)],
+        eval_ts: &[i64],
+        range_length: i64,
+        expected: Vec<Option<f64>>,
+    ) {
+        let (ts, values) = range_inputs(timestamps, values, ranges);
+        let [eval_ts, range_length] = extra_inputs(eval_ts, range_length);
+        run_and_check(
+            ExtrapolatedRate::<IS_COUNTER, IS_RATE>::scalar_udf(),
+            &[ts, values, eval_ts, range_length],
+            expected,
+        );
+    }
+
+    // From prometheus `promql/testdata/functions.test`.
+    //
+    // ```text
+    // load 5m
+    //     http_requests{path="/foo"}	0+10x10
+    //     http_requests{path="/bar"}	0+10x5 0+10x5
+    //
+    // eval instant at 50m increase(http_requests[50m])
+    //     {path="/foo"} 100
+    //     {path="/bar"}  90
+    //
+    // eval instant at 50m increase(http_requests[100m])
+    //     {path="/foo"} 100
+    //     {path="/bar"}  90
+    // ```
+    #[test]
+    fn increase() {
+        let timestamps = (0..12).map(|i| i * 300_000).collect::<Vec<_>>();
+        let foo = (0..12).map(|i| i as f64 * 10.0).collect::<Vec<_>>();
+        let bar = (0..12).map(|i| (i % 6) as f64 * 10.0).collect::<Vec<_>>();
+
+        for range_length in [3_000_000, 6_000_000] {
+            check::<true, false>(
+                &timestamps,
+                &foo,
+                &[(0, 11)],
+                &[3_000_000],
+                range_length,
+                vec![Some(100.0)],
+            );
+            check::<true, false>(
+                &timestamps,
+                &bar,
+                &[(0, 11)],
+                &[3_000_000],
+                range_length,
+                vec![Some(90.0)],
+            );
+        }
+    }
+
+    // From prometheus `promql/testdata/functions.test`.
+    //
+    // ```text
+    // load 5m
+    //     testcounter_reset_middle	0+10x4 0+10x5
+    //     testcounter_reset_end    	0+10x9 0 10
+    //
+    // # Counter resets at in the middle of range are handled correctly by rate().
+    // eval instant at 50m rate(testcounter_reset_middle[50m])
+    //     {} 0.03
+    //
+    // # Counter resets at end of range are ignored by rate().
+    // eval instant at 50m rate(testcounter_reset_end[5m])
+    //     {} 0
+    // ```
+    #[test]
+    fn rate_counter_reset() {
+        let timestamps = (0..12).map(|i| i * 300_000).collect::<Vec<_>>();
+
+        let reset_middle = [0., 10., 20., 30., 40., 0., 10., 20., 30., 40., 50.];
+        check::<true, true>(
+            &timestamps[..11],
+            &reset_middle,
+            &[(0, 11)],
+            &[3_000_000],
+            3_000_000,
+            vec![Some(0.03)],
+        );
+
+        let reset_end = [0., 10., 20., 30., 40., 50., 60., 70., 80., 90., 0., 10.];
+        check::<true, true>(
+            &timestamps,
+            &reset_end,
+            &[(9, 2)],
+            &[3_000_000],
+            300_000,
+            vec![Some(0.0)],
+        );
+    }
+
+    // From prometheus `promql/testdata/functions.test`.
+    //
+    // ```text
+    // load 5m
+    //     http_requests{path="/foo"}	0 50 100 150 200
+    //     http_requests{path="/bar"}	200 150 100 50 0
+    //
+    // eval instant at 20m delta(http_requests[20m])
+    //     {path="/foo"} 200
+    //     {path="/bar"} -200
+    // ```
+    #[test]
+    fn delta() {
+        let timestamps = [0, 300_000, 600_000, 900_000, 1_200_000];
+        check::<false, false>(
+            &timestamps,
+            &[0., 50., 100., 150., 200.],
+            &[(0, 5)],
+            &[1_200_000],
+            1_200_000,
+            vec![Some(200.0)],
+        );
+        check::<false, false>(
+            &timestamps,
+            &[200., 150., 100., 50., 0.],
+            &[(0, 5)],
+            &[1_200_000],
+            1_200_000,
+            vec![Some(-200.0)],
+        );
+    }
+
+    #[test]
+    fn extrapolation() {
+        // samples at 10s, 20s and 30s, evaluated at 60s with range 60s. The result 2 is
+        // extrapolated to the start of range (10s) and half of the average interval
+        // after the last sample (5s), i.e. `2 * (20 + 10 + 5) / 20`.
+        let timestamps = [10_000, 20_000, 30_000];
+        let values = [1., 2., 3.];
+        let ranges = [(0, 3), (0, 1), (0, 0)];
+        let eval_ts = [60_000, 60_000, 60_000];
+
+        check::<false, false>(
+            &timestamps,
+            &values,
+            &ranges,
+            &eval_ts,
+            60_000,
+            vec![Some(3.5), None, None],
+        );
+        check::<true, false>(
+            &timestamps,
+            &values,
+            &ranges,
+            &eval_ts,
+            60_000,
+            vec![Some(3.5), None, None],
+        );
+        check::<true, true>(
+            &timestamps,
+            &values,
+            &ranges,
+            &eval_ts,
+            60_000,
+            vec![Some(3.5 / 60.0), None, None],
+        );
+    }
+}
@@ -0,0 +1,146 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Implementation of [`idelta`](https://prometheus.io/docs/prometheus/latest/querying/functions/#idelta)
+//! and [`irate`](https://prometheus.io/docs/prometheus/latest/querying/functions/#irate) in PromQL.
+
+use datafusion::arrow::array::Float64Array;
+use datafusion::physical_plan::udf::ScalarUDF;
+use datatypes::arrow::array::TimestampMillisecondArray;
+
+use crate::functions::window_function_udf;
+
+/// Calculate the difference (`idelta`) or the per-second rate (`irate`) between the
+/// last two samples of the range.
+#[derive(Debug)]
+pub struct IDelta<const IS_RATE: bool>;
+
+impl<const IS_RATE: bool> IDelta<IS_RATE> {
+    pub const fn name() -> &'static str {
+        if IS_RATE {
+            "prom_irate"
+        } else {
+            "prom_idelta"
+        }
+    }
+
+    pub fn scalar_udf() -> ScalarUDF {
+        window_function_udf(Self::name(), Self::instant_value)
+    }
+
+    // Prometheus: `instantValue()` in `promql/functions.go`
+    fn instant_value(ts: &TimestampMillisecondArray, values: &Float64Array) -> Option<f64> {
+        let len = values.len();
+        if len < 2 {
+            return None;
+        }
+
+        let (prev_ts, last_ts) = (ts.value(len - 2), ts.value(len - 1));
+        let (prev_value, last_value) = (values.value(len - 2), values.value(len - 1));
+
+        let mut result = if IS_RATE && last_value < prev_value {
+            // counter reset
+            last_value
+        } else {
+            last_value - prev_value
+        };
+
+        let sampled_interval = last_ts - prev_ts;
+        if sampled_interval == 0 {
+            return None;
+        }
+        if IS_RATE {
+            // convert to per-second
+            result /= sampled_interval as f64 / 1000.0;
+        }
+
+        Some(result)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::functions::test_util::{range_inputs, run_and_check};
+
+    // From prometheus `promql/testdata/functions.test`.
+    //
+    // ```text
+    // load 5m
+    //     http_requests{path="/foo"}	0 50 100 150
+    //     http_requests{path="/bar"}	0 50 100 50
+    //
+    // eval instant at 20m idelta(http_requests[20m])
+    //     {path="/foo"} 50
+    //     {path="/bar"} -50
+    // ```
+    #[test]
+    fn idelta() {
+        let timestamps = [0, 300_000, 600_000, 900_000];
+        let ranges = [(0, 4), (3, 1), (0, 0)];
+
+        let (ts, values) = range_inputs(&timestamps, &[0., 50., 100., 150.], &ranges);
+        run_and_check(
+            IDelta::<false>::scalar_udf(),
+            &[ts, values],
+            vec![Some(50.0), None, None],
+        );
+
+        let (ts, values) = range_inputs(&timestamps, &[0., 50., 100., 50.], &ranges);
+        run_and_check(
+            IDelta::<false>::scalar_udf(),
+            &[ts, values],
+            vec![Some(-50.0), None, None],
+        );
+    }
+
+    // From prometheus `promql/testdata/functions.test`.
+    //
+    // ```text
+    // load 5m
+    //     http_requests{path="/foo"}	0+10x10
+    //     http_requests{path="/bar"}	0+10x5 0+10x5
+    //
+    // eval instant at 50m irate(http_requests[50m])
+    //     {path="/foo"} .03333333333333333333
+    //     {path="/bar"} .03333333333333333333
+    //
+    // # Counter reset.
+    // eval instant at 30m irate(http_requests[50m])
+    //     {path="/foo"} .03333333333333333333
+    //     {path="/bar"} 0
+    // ```
+    #[test]
+    fn irate() {
+        let timestamps = (0..12).map(|i| i * 300_000).collect::<Vec<_>>();
+        // ranges of evaluating at 50m and 30m
+        let ranges = [(0, 11), (0, 7)];
+
+        let foo = (0..12).map(|i| i as f64 * 10.0).collect::<Vec<_>>();
+        let (ts, values) = range_inputs(&timestamps, &foo, &ranges);
+        run_and_check(
+            IDelta::<true>::scalar_udf(),
+            &[ts, values],
+            vec![Some(1.0 / 30.0), Some(1.0 / 30.0)],
+        );
+
+        let bar = (0..12).map(|i| (i % 6) as f64 * 10.0).collect::<Vec<_>>();
+        let (ts, values) = range_inputs(&timestamps, &bar, &ranges);
+        run_and_check(
+            IDelta::<true>::scalar_udf(),
+            &[ts, values],
+            vec![Some(1.0 / 30.0), Some(0.0)],
+        );
+    }
+}
@@ -0,0 +1,143 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Implementation of the [`quantile`](https://prometheus.io/docs/prometheus/latest/querying/operators/#aggregation-operators)
+//! aggregation in PromQL.
+
+use std::sync::Arc;
+
+use datafusion::arrow::array::{Array, Float64Array, ListArray};
+use datafusion::error::DataFusionError;
+use datafusion::logical_expr::{
+    ColumnarValue, ScalarFunctionImplementation, Signature, Volatility,
+};
+use datafusion::physical_plan::udf::ScalarUDF;
+use datafusion::scalar::ScalarValue;
+
+use crate::functions::aggr_over_time::calc_quantile;
+use crate::functions::float64_return_type;
+
+/// The φ-quantile of the values in each list, φ is given as the second input in a
+/// Float64 literal. Values of each group are collected into a list by `ARRAY_AGG` first.
+#[derive(Debug)]
+pub struct Quantile;
+
+impl Quantile {
+    pub const fn name() -> &'static str {
+        "prom_quantile"
+    }
+
+    pub fn scalar_udf() -> ScalarUDF {
+        let fun: ScalarFunctionImplementation = Arc::new(Self::calc);
+        ScalarUDF::new(
+            Self::name(),
+            // the field of the list type is decided by `ARRAY_AGG`, so the input types are
+            // checked on execution
+            &Signature::any(2, Volatility::Immutable),
+            &float64_return_type(),
+            &fun,
+        )
+    }
+
+    fn calc(input: &[ColumnarValue]) -> Result<ColumnarValue, DataFusionError> {
+        if input.len() != 2 {
+            return Err(DataFusionError::Plan(format!(
+                "expect 2 inputs for {}, but found {}",
+                Self::name(),
+                input.len()
+            )));
+        }
+        let lists = match &input[0] {
+            ColumnarValue::Array(array) => array.clone(),
+            ColumnarValue::Scalar(scalar) => scalar.to_array(),
+        };
+        let lists = lists.as_any().downcast_ref::<ListArray>().ok_or_else(|| {
+            DataFusionError::Execution(format!(
+                "expect list as input of {}, found {:?}",
+                Self::name(),
+                lists.data_type()
+            ))
+        })?;
+        let ColumnarValue::Scalar(ScalarValue::Float64(Some(quantile))) = &input[1] else {
+            return Err(DataFusionError::Execution(format!(
+                "expect quantile in Float64 literal for {}",
+                Self::name()
+            )));
+        };
+
+        let mut result = Vec::with_capacity(lists.len());
+        for list in lists.iter() {
+            let Some(list) = list else {
+                result.push(None);
+                continue;
+            };
+            let values = list
+                .as_any()
+                .downcast_ref::<Float64Array>()
+                .ok_or_else(|| {
+                    DataFusionError::Execution(format!(
+                        "expect Float64 list, found {:?}",
+                        list.data_type()
+                    ))
+                })?;
+            // samples with null value are skipped, as if they are not scraped
+            let values = values.iter().flatten().collect::<Float64Array>();
+            result.push(calc_quantile(*quantile, &values));
+        }
+
+        Ok(ColumnarValue::Array(Arc::new(Float64Array::from(result))))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use datafusion::arrow::array::ArrayRef;
+    use datafusion::arrow::datatypes::Float64Type;
+
+    use super::*;
+
+    fn quantile_of_lists(lists: Vec<Option<Vec<Option<f64>>>>, quantile: f64) -> Vec<f64> {
+        let lists: ArrayRef = Arc::new(ListArray::from_iter_primitive::<Float64Type, _, _>(lists));
+        let result = Quantile::calc(&[
+            ColumnarValue::Array(lists),
+            ColumnarValue::Scalar(ScalarValue::Float64(Some(quantile))),
+        ])
+        .unwrap();
+        let ColumnarValue::Array(result) = result else { unreachable!() };
+        let result = result.as_any().downcast_ref::<Float64Array>().unwrap();
+        result.iter().map(|v| v.unwrap_or(-1.0)).collect()
+    }
+
+    #[test]
+    fn quantile_interpolates() {
+        let lists = || {
+            vec![
+                Some(vec![Some(4.0), Some(1.0), Some(2.0), Some(3.0)]),
+                Some(vec![Some(1.0), None, Some(3.0)]),
+            ]
+        };
+        assert_eq!(vec![1.0, 1.0], quantile_of_lists(lists(), 0.0));
+        assert_eq!(vec![2.5, 2.0], quantile_of_lists(lists(), 0.5));
+        assert_eq!(vec![3.25, 2.5], quantile_of_lists(lists(), 0.75));
+        assert_eq!(vec![4.0, 3.0], quantile_of_lists(lists(), 1.0));
+    }
+
+    #[test]
+    fn quantile_out_of_range() {
+        let lists = || vec![Some(vec![Some(1.0), Some(2.0)])];
+        assert_eq!(vec![f64::NEG_INFINITY], quantile_of_lists(lists(), -0.1));
+        assert_eq!(vec![f64::INFINITY], quantile_of_lists(lists(), 1.5));
+        assert!(quantile_of_lists(lists(), f64::NAN)[0].is_nan());
+    }
+}
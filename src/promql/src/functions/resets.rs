@@ -0,0 +1,105 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Implementation of [`resets`](https://prometheus.io/docs/prometheus/latest/querying/functions/#resets) in PromQL.
+
+use datafusion::arrow::array::Float64Array;
+use datatypes::arrow::array::TimestampMillisecondArray;
+
+define_window_function!(
+    /// The number of counter resets within the provided time range. Any decrease in the
+    /// value between two consecutive samples is interpreted as a counter reset.
+    Resets,
+    "prom_resets",
+    resets
+);
+
+fn resets(_: &TimestampMillisecondArray, values: &Float64Array) -> Option<f64> {
+    let values = values.values();
+    let (first, rest) = values.split_first()?;
+
+    let mut resets = 0;
+    let mut prev = *first;
+    for curr in rest {
+        if *curr < prev {
+            resets += 1;
+        }
+        prev = *curr;
+    }
+
+    Some(resets as f64)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::functions::test_util::{range_inputs, run_and_check};
+
+    // From prometheus `promql/testdata/functions.test`, evaluated at 50m.
+    //
+    // ```text
+    // load 5m
+    //     http_requests{path="/foo"}	1 2 3 0 1 0 0 1 2 0
+    //     http_requests{path="/bar"}	1 2 3 4 5 1 2 3 4 5
+    //     http_requests{path="/biz"}	0 0 0 0 0 1 1 1 1 1
+    // ```
+    const TIMESTAMPS: [i64; 10] = [
+        0, 300_000, 600_000, 900_000, 1_200_000, 1_500_000, 1_800_000, 2_100_000, 2_400_000,
+        2_700_000,
+    ];
+    // ranges of `[5m]`, `[20m]`, `[30m]`, `[50m]` and a window without any sample
+    const RANGES: [(u32, u32); 5] = [(9, 1), (6, 4), (4, 6), (0, 10), (0, 0)];
+
+    #[test]
+    fn resets_foo() {
+        let (ts, values) = range_inputs(
+            &TIMESTAMPS,
+            &[1., 2., 3., 0., 1., 0., 0., 1., 2., 0.],
+            &RANGES,
+        );
+        run_and_check(
+            Resets::scalar_udf(),
+            &[ts, values],
+            vec![Some(0.0), Some(1.0), Some(2.0), Some(3.0), None],
+        );
+    }
+
+    #[test]
+    fn resets_bar() {
+        let (ts, values) = range_inputs(
+            &TIMESTAMPS,
+            &[1., 2., 3., 4., 5., 1., 2., 3., 4., 5.],
+            &RANGES,
+        );
+        run_and_check(
+            Resets::scalar_udf(),
+            &[ts, values],
+            vec![Some(0.0), Some(0.0), Some(1.0), Some(1.0), None],
+        );
+    }
+
+    #[test]
+    fn resets_biz() {
+        let (ts, values) = range_inputs(
+            &TIMESTAMPS,
+            &[0., 0., 0., 0., 0., 1., 1., 1., 1., 1.],
+            &RANGES,
+        );
+        run_and_check(
+            Resets::scalar_udf(),
+            &[ts, values],
+            vec![Some(0.0), Some(0.0), Some(0.0), Some(0.0), None],
+        );
+    }
+}
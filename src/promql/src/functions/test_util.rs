@@ -0,0 +1,73 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use datafusion::arrow::array::Float64Array;
+use datafusion::logical_expr::ColumnarValue;
+use datafusion::physical_plan::udf::ScalarUDF;
+use datatypes::arrow::array::TimestampMillisecondArray;
+
+use crate::range_array::{RangeArray, RangeTuple};
+
+/// Build the timestamp and value range inputs of a range function.
+pub fn range_inputs(
+    ts: &[i64],
+    values: &[f64],
+    ranges: &[RangeTuple],
+) -> (ColumnarValue, ColumnarValue) {
+    let ts_range = RangeArray::from_ranges(
+        Arc::new(TimestampMillisecondArray::from(ts.to_vec())),
+        ranges.iter().copied(),
+    )
+    .unwrap();
+    let value_range = RangeArray::from_ranges(
+        Arc::new(Float64Array::from(values.to_vec())),
+        ranges.iter().copied(),
+    )
+    .unwrap();
+
+    (
+        ColumnarValue::Array(Arc::new(ts_range.into_dict())),
+        ColumnarValue::Array(Arc::new(value_range.into_dict())),
+    )
+}
+
+/// Evaluate `udf` on `input` and compare the result with `expected`. Float values are
+/// compared with a tolerance, and `NaN` equals to `NaN`.
+pub fn run_and_check(udf: ScalarUDF, input: &[ColumnarValue], expected: Vec<Option<f64>>) {
+    let ColumnarValue::Array(result) = (udf.fun)(input).unwrap() else {
+        panic!("expect array output from {}", udf.name)
+    };
+    let result = result
+        .as_any()
+        .downcast_ref::<Float64Array>()
+        .unwrap()
+        .iter()
+        .collect::<Vec<_>>();
+
+    assert_eq!(result.len(), expected.len(), "result: {result:?}");
+    for (actual, expected) in result.iter().zip(expected.iter()) {
+        let matched = match (actual, expected) {
+            (Some(actual), Some(expected)) => {
+                (actual.is_nan() && expected.is_nan())
+                    || actual == expected
+                    || (actual - expected).abs() < 1e-9
+            }
+            (None, None) => true,
+            _ => false,
+        };
+        assert!(matched, "result: {result:?}, expected: {expected:?}");
+    }
+}
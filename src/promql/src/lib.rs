@@ -15,5 +15,6 @@
 pub mod engine;
 pub mod error;
 pub mod extension_plan;
+pub mod functions;
 pub mod planner;
 pub mod range_array;
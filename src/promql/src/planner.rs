@@ -12,18 +12,24 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::collections::HashSet;
+use std::collections::{BTreeSet, HashSet};
 use std::str::FromStr;
 use std::sync::Arc;
 use std::time::{Duration, UNIX_EPOCH};
 
+use datafusion::arrow::array::{Array, BooleanArray, Int64Array};
+use datafusion::arrow::datatypes::DataType;
 use datafusion::datasource::DefaultTableSource;
+use datafusion::error::DataFusionError;
 use datafusion::logical_expr::expr::AggregateFunction;
 use datafusion::logical_expr::{
-    AggregateFunction as AggregateFunctionEnum, BinaryExpr, BuiltinScalarFunction, Extension,
-    Filter, LogicalPlan, LogicalPlanBuilder, Operator,
+    AggregateFunction as AggregateFunctionEnum, BinaryExpr, BuiltInWindowFunction,
+    BuiltinScalarFunction, Cast, ColumnarValue, Extension, Filter, JoinType, LogicalPlan,
+    LogicalPlanBuilder, Operator, ReturnTypeFunction, ScalarFunctionImplementation, Signature,
+    Volatility, WindowFunction,
 };
 use datafusion::optimizer::utils;
+use datafusion::physical_plan::udf::ScalarUDF;
 use datafusion::prelude::{Column, Expr as DfExpr};
 use datafusion::scalar::ScalarValue;
 use datafusion::sql::planner::ContextProvider;
@@ -31,18 +37,24 @@ use datafusion::sql::TableReference;
 use promql_parser::label::{MatchOp, Matchers, METRIC_NAME};
 use promql_parser::parser::{
     token, AggregateExpr, BinaryExpr as PromBinaryExpr, Call, EvalStmt, Expr as PromExpr, Function,
-    MatrixSelector, NumberLiteral, ParenExpr, StringLiteral, SubqueryExpr, TokenType, UnaryExpr,
-    VectorSelector,
+    LabelModifier, MatrixSelector, NumberLiteral, ParenExpr, StringLiteral, SubqueryExpr,
+    TokenType, UnaryExpr, VectorMatchCardinality, VectorSelector,
 };
-use snafu::{OptionExt, ResultExt};
+use snafu::{ensure, OptionExt, ResultExt};
 use table::table::adapter::DfTableProviderAdapter;
 
 use crate::error::{
-    DataFusionPlanningSnafu, ExpectExprSnafu, LabelNotFoundSnafu, MultipleVectorSnafu, Result,
-    TableNameNotFoundSnafu, TableNotFoundSnafu, TimeIndexNotFoundSnafu, UnexpectedPlanExprSnafu,
-    UnexpectedTokenSnafu, UnknownTableSnafu, UnsupportedExprSnafu, ValueNotFoundSnafu,
+    DataFusionPlanningSnafu, ExpectExprSnafu, ExpectLiteralParamSnafu, ExpectRangeSelectorSnafu,
+    InvalidParamSnafu, LabelNotFoundSnafu, MultipleVectorSnafu, Result, TableNameNotFoundSnafu,
+    TableNotFoundSnafu, TimeIndexNotFoundSnafu, UnexpectedPlanExprSnafu, UnexpectedTokenSnafu,
+    UnknownTableSnafu, UnsupportedExprSnafu, ValueColumnMismatchSnafu, ValueNotFoundSnafu,
 };
 use crate::extension_plan::{InstantManipulate, Millisecond, RangeManipulate, SeriesNormalize};
+use crate::functions::{
+    AbsentOverTime, AvgOverTime, Changes, CountOverTime, Delta, IDelta, Increase, LastOverTime,
+    MaxOverTime, MinOverTime, PresentOverTime, Quantile, QuantileOverTime, Rate, Resets,
+    StddevOverTime, StdvarOverTime, SumOverTime,
+};
 
 #[derive(Default, Debug, Clone)]
 struct PromPlannerContext {
@@ -55,7 +67,13 @@ struct PromPlannerContext {
     // planner states
     table_name: Option<String>,
     time_index_column: Option<String>,
+    /// Label columns of the current plan, which identify series.
+    tag_columns: Vec<String>,
     value_columns: Vec<String>,
+    /// The range of the matrix selector in the input, for range functions.
+    range: Option<Millisecond>,
+    /// The `@` timestamp of the matrix selector in the input, for range functions.
+    at: Option<Millisecond>,
 }
 
 impl PromPlannerContext {
@@ -89,95 +107,130 @@ impl<S: ContextProvider> PromPlanner<S> {
             PromExpr::Aggregate(AggregateExpr {
                 op,
                 expr,
-                // TODO(ruihang): support param
-                param: _param,
+                param,
                 grouping,
                 without,
             }) => {
                 let input = self.prom_expr_to_plan(*expr.clone())?;
 
-                // calculate columns to group by
+                // calculate labels to group by, `without` removes labels from all tags
                 let schema = input.schema();
-                let group_columns_indices = grouping
-                    .iter()
-                    .map(|label| {
-                        schema
-                            .index_of_column_by_name(None, label)
-                            .with_context(|_| LabelNotFoundSnafu {
-                                table: self.ctx.table_name.clone().unwrap(),
-                            })
-                    })
-                    .collect::<Result<HashSet<_>>>()?;
-                let value_names = self.ctx.value_columns.iter().collect::<HashSet<_>>();
-                let group_exprs = schema
-                    .fields()
-                    .iter()
-                    .enumerate()
-                    .filter_map(|(i, field)| {
-                        if *without != group_columns_indices.contains(&i)
-                            && Some(field.name()) != self.ctx.time_index_column.as_ref()
-                            && !value_names.contains(&field.name())
-                        {
-                            Some(DfExpr::Column(Column::from(field.name())))
-                        } else {
-                            None
-                        }
-                    })
-                    .collect::<Vec<_>>();
-
-                // convert op and value columns to aggregate exprs
-                let aggr_exprs = self.create_aggregate_exprs(*op)?;
-
-                // create plan
-                LogicalPlanBuilder::from(input)
-                    .aggregate(group_exprs, aggr_exprs)
-                    .context(DataFusionPlanningSnafu)?
-                    .build()
-                    .context(DataFusionPlanningSnafu)?
-            }
-            PromExpr::Unary(UnaryExpr { .. }) => UnsupportedExprSnafu {
-                name: "Prom Unary Expr",
-            }
-            .fail()?,
-            PromExpr::Binary(PromBinaryExpr { lhs, rhs, .. }) => {
-                let _left_input = self.prom_expr_to_plan(*lhs.clone())?;
-                let _right_input = self.prom_expr_to_plan(*rhs.clone())?;
+                for label in grouping {
+                    schema
+                        .index_of_column_by_name(None, label)
+                        .with_context(|_| LabelNotFoundSnafu {
+                            table: self.ctx.table_name.clone().unwrap_or_default(),
+                        })?;
+                }
+                let group_labels = if *without {
+                    self.ctx
+                        .tag_columns
+                        .iter()
+                        .filter(|tag| !grouping.contains(tag))
+                        .cloned()
+                        .collect::<Vec<_>>()
+                } else {
+                    grouping.clone()
+                };
 
-                UnsupportedExprSnafu {
-                    name: "Prom Binary Expr",
+                match *op {
+                    token::T_TOPK | token::T_BOTTOMK => {
+                        self.topk_to_plan(input, *op, param, &group_labels)?
+                    }
+                    token::T_COUNT_VALUES => {
+                        self.count_values_to_plan(input, param, group_labels)?
+                    }
+                    token::T_QUANTILE => self.quantile_to_plan(input, param, group_labels)?,
+                    _ => {
+                        let mut group_exprs = group_labels
+                            .iter()
+                            .map(|label| DfExpr::Column(Column::from_name(label)))
+                            .collect::<Vec<_>>();
+                        group_exprs.push(self.create_time_index_column_expr()?);
+                        let aggr_exprs = self.create_aggregate_exprs(*op)?;
+                        let num_values = aggr_exprs.len();
+                        let aggregate = LogicalPlanBuilder::from(input)
+                            .aggregate(group_exprs, aggr_exprs)
+                            .context(DataFusionPlanningSnafu)?;
+
+                        self.ctx.tag_columns = group_labels;
+                        self.ctx.value_columns = last_column_names(&aggregate, num_values);
+
+                        aggregate.build().context(DataFusionPlanningSnafu)?
+                    }
                 }
-                .fail()?
-            }
-            PromExpr::Paren(ParenExpr { .. }) => UnsupportedExprSnafu {
-                name: "Prom Paren Expr",
             }
-            .fail()?,
-            PromExpr::Subquery(SubqueryExpr { .. }) => UnsupportedExprSnafu {
-                name: "Prom Subquery",
+            PromExpr::Unary(UnaryExpr { expr, .. }) => {
+                let input = self.prom_expr_to_plan(*expr.clone())?;
+                self.projection_on_values(input, |value| Ok(DfExpr::Negative(Box::new(value))))?
             }
-            .fail()?,
-            PromExpr::NumberLiteral(NumberLiteral { .. }) => UnsupportedExprSnafu {
-                name: "Prom Number Literal",
+            PromExpr::Binary(binary_expr) => self.binary_expr_to_plan(binary_expr)?,
+            PromExpr::Paren(ParenExpr { expr, .. }) => self.prom_expr_to_plan(*expr.clone())?,
+            PromExpr::Subquery(SubqueryExpr {
+                expr,
+                range,
+                offset,
+                start_or_end,
+                step,
+                ..
+            }) => {
+                let at = self.create_at_timestamp(start_or_end)?;
+                let range = range.as_millis() as Millisecond;
+                let offset = offset.unwrap_or_default();
+                let input = self.subquery_inner_to_plan(expr, range, offset, *step, at)?;
+
+                // the inner results are folded into ranges like a matrix selector
+                let time_index =
+                    self.ctx
+                        .time_index_column
+                        .clone()
+                        .with_context(|| TimeIndexNotFoundSnafu {
+                            table: self.ctx.table_name.clone().unwrap_or_default(),
+                        })?;
+                let normalize = LogicalPlan::Extension(Extension {
+                    node: Arc::new(SeriesNormalize::new(offset, &time_index, input)),
+                });
+                self.ctx.range = Some(range);
+                self.ctx.at = at;
+                let manipulate = RangeManipulate::new(
+                    self.ctx.start,
+                    self.ctx.end,
+                    self.ctx.interval,
+                    range,
+                    at,
+                    time_index,
+                    self.ctx.value_columns.clone(),
+                    normalize,
+                )
+                .context(DataFusionPlanningSnafu)?;
+
+                LogicalPlan::Extension(Extension {
+                    node: Arc::new(manipulate),
+                })
             }
-            .fail()?,
-            PromExpr::StringLiteral(StringLiteral { .. }) => UnsupportedExprSnafu {
-                name: "Prom String Literal",
+            PromExpr::NumberLiteral(_) | PromExpr::StringLiteral(_) => {
+                // safety: literals are always convertible
+                let literal = Self::try_build_literal_expr(&prom_expr).unwrap();
+                self.literal_to_plan(literal)?
             }
-            .fail()?,
             PromExpr::VectorSelector(VectorSelector {
                 name: _,
                 offset,
-                start_or_end: _,
+                start_or_end,
                 label_matchers,
             }) => {
                 let matchers = self.preprocess_label_matchers(label_matchers)?;
                 self.setup_context()?;
-                let normalize = self.selector_to_series_normalize_plan(*offset, matchers)?;
+                self.ctx.range = None;
+                self.ctx.at = None;
+                let at = self.create_at_timestamp(start_or_end)?;
+                let normalize = self.selector_to_series_normalize_plan(*offset, at, matchers)?;
                 let manipulate = InstantManipulate::new(
                     self.ctx.start,
                     self.ctx.end,
                     self.ctx.lookback_delta,
                     self.ctx.interval,
+                    at,
                     self.ctx
                         .time_index_column
                         .clone()
@@ -192,16 +245,20 @@ impl<S: ContextProvider> PromPlanner<S> {
                 vector_selector,
                 range,
             }) => {
+                // TODO(ruihang): convert via Timestamp datatypes to support different time units
+                let range = range.as_millis() as Millisecond;
                 let normalize = match &**vector_selector {
                     PromExpr::VectorSelector(VectorSelector {
                         name: _,
                         offset,
-                        start_or_end: _,
+                        start_or_end,
                         label_matchers,
                     })=> {
                         let matchers = self.preprocess_label_matchers(label_matchers)?;
                         self.setup_context()?;
-                        self.selector_to_series_normalize_plan(*offset, matchers)?
+                        self.ctx.range = Some(range);
+                        self.ctx.at = self.create_at_timestamp(start_or_end)?;
+                        self.selector_to_series_normalize_plan(*offset, self.ctx.at, matchers)?
                     }
                     _ => UnexpectedPlanExprSnafu {
                         desc: format!(
@@ -214,8 +271,8 @@ impl<S: ContextProvider> PromPlanner<S> {
                     self.ctx.start,
                     self.ctx.end,
                     self.ctx.interval,
-                    // TODO(ruihang): convert via Timestamp datatypes to support different time units
-                    range.as_millis() as _,
+                    range,
+                    self.ctx.at,
                     self.ctx
                         .time_index_column
                         .clone()
@@ -235,11 +292,21 @@ impl<S: ContextProvider> PromPlanner<S> {
                     self.prom_expr_to_plan(args.input.with_context(|| ExpectExprSnafu {
                         expr: prom_expr.clone(),
                     })?)?;
-                let mut func_exprs = self.create_function_expr(func, args.literals)?;
-                func_exprs.insert(0, self.create_time_index_column_expr()?);
-                LogicalPlanBuilder::from(input)
-                    .project(func_exprs)
-                    .context(DataFusionPlanningSnafu)?
+                let func_exprs = self.create_function_expr(func, args.literals)?;
+                let num_values = func_exprs.len();
+                let mut exprs = self.create_tag_column_exprs();
+                exprs.push(self.create_time_index_column_expr()?);
+                exprs.extend(func_exprs);
+                let projection = LogicalPlanBuilder::from(input)
+                    .project(exprs)
+                    .context(DataFusionPlanningSnafu)?;
+
+                // values are replaced by the function results, and the range is consumed
+                self.ctx.value_columns = last_column_names(&projection, num_values);
+                self.ctx.range = None;
+                self.ctx.at = None;
+
+                projection
                     .filter(self.create_empty_values_filter_expr()?)
                     .context(DataFusionPlanningSnafu)?
                     .build()
@@ -268,17 +335,24 @@ impl<S: ContextProvider> PromPlanner<S> {
     fn selector_to_series_normalize_plan(
         &self,
         offset: Option<Duration>,
+        at: Option<Millisecond>,
         label_matchers: Matchers,
     ) -> Result<LogicalPlan> {
         let table_name = self.ctx.table_name.clone().unwrap();
 
-        // make filter exprs
+        // make filter exprs. Instant selector needs the data in lookback window before
+        // start, and range selector needs the data in its range. Samples are read from
+        // `offset` before, and only around `at` if the `@` modifier is present.
         let mut filters = self.matchers_to_expr(label_matchers)?;
+        let offset_millis = offset.unwrap_or_default().as_millis() as Millisecond;
+        let (start, end) = at.map_or((self.ctx.start, self.ctx.end), |at| (at, at));
+        let start = start - offset_millis - self.ctx.range.unwrap_or(self.ctx.lookback_delta);
+        let end = end - offset_millis;
         filters.push(self.create_time_index_column_expr()?.gt_eq(DfExpr::Literal(
-            ScalarValue::TimestampMillisecond(Some(self.ctx.start), None),
+            ScalarValue::TimestampMillisecond(Some(start), None),
         )));
         filters.push(self.create_time_index_column_expr()?.lt_eq(DfExpr::Literal(
-            ScalarValue::TimestampMillisecond(Some(self.ctx.end), None),
+            ScalarValue::TimestampMillisecond(Some(end), None),
         )));
 
         // make table scan with filter exprs
@@ -310,6 +384,53 @@ impl<S: ContextProvider> PromPlanner<S> {
         Ok(logical_plan)
     }
 
+    /// Convert the `@ start()` or `@ end()` modifier to timestamp.
+    fn create_at_timestamp(&self, start_or_end: &Option<TokenType>) -> Result<Option<Millisecond>> {
+        match start_or_end {
+            Some(token::T_START) => Ok(Some(self.ctx.start)),
+            Some(token::T_END) => Ok(Some(self.ctx.end)),
+            Some(token) => UnexpectedTokenSnafu { token: *token }.fail(),
+            None => Ok(None),
+        }
+    }
+
+    /// Plan the inner expr of a subquery. It is evaluated at timestamps that are multiples
+    /// of `step` within the subquery range of all evaluation timestamps, or of `at` only if
+    /// the `@` modifier is present. Like Prometheus, `step` defaults to the evaluation interval.
+    fn subquery_inner_to_plan(
+        &mut self,
+        expr: &PromExpr,
+        range: Millisecond,
+        offset: Duration,
+        step: Duration,
+        at: Option<Millisecond>,
+    ) -> Result<LogicalPlan> {
+        let (start, end, interval) = (self.ctx.start, self.ctx.end, self.ctx.interval);
+        let step = if step.is_zero() {
+            interval
+        } else {
+            step.as_millis() as Millisecond
+        };
+        let offset = offset.as_millis() as Millisecond;
+        let (outer_start, outer_end) = at.map_or((start, end), |at| (at, at));
+
+        let first_ts = outer_start - offset - range;
+        let mut inner_start = first_ts.div_euclid(step) * step;
+        if inner_start < first_ts {
+            inner_start += step;
+        }
+        self.ctx.start = inner_start;
+        self.ctx.end = outer_end - offset;
+        self.ctx.interval = step;
+
+        let plan = self.prom_expr_to_plan(expr.clone());
+
+        self.ctx.start = start;
+        self.ctx.end = end;
+        self.ctx.interval = interval;
+        plan
+    }
+
     // TODO(ruihang): ignore `MetricNameLabel` (`__name__`) matcher
     fn matchers_to_expr(&self, label_matchers: Matchers) -> Result<Vec<DfExpr>> {
         let mut exprs = Vec::with_capacity(label_matchers.matchers.len());
@@ -378,6 +499,14 @@ impl<S: ContextProvider> PromPlanner<S> {
             .clone();
         self.ctx.time_index_column = Some(time_index);
 
+        // set tag columns
+        self.ctx.tag_columns = table
+            .table_info()
+            .meta
+            .row_key_column_names()
+            .cloned()
+            .collect();
+
         // set values column
         let values = table
             .table_info()
@@ -396,28 +525,11 @@ impl<S: ContextProvider> PromPlanner<S> {
         let mut result = FunctionArgs::default();
 
         for arg in args {
-            match *arg.clone() {
-                PromExpr::Aggregate(_)
-                | PromExpr::Unary(_)
-                | PromExpr::Binary(_)
-                | PromExpr::Paren(_)
-                | PromExpr::Subquery(_)
-                | PromExpr::VectorSelector(_)
-                | PromExpr::MatrixSelector(_)
-                | PromExpr::Call(_) => {
-                    if result.input.replace(*arg.clone()).is_some() {
-                        MultipleVectorSnafu { expr: *arg.clone() }.fail()?;
-                    }
-                }
-
-                PromExpr::NumberLiteral(NumberLiteral { val, .. }) => {
-                    let scalar_value = ScalarValue::Float64(Some(val));
-                    result.literals.push(DfExpr::Literal(scalar_value));
-                }
-                PromExpr::StringLiteral(StringLiteral { val, .. }) => {
-                    let scalar_value = ScalarValue::Utf8(Some(val));
-                    result.literals.push(DfExpr::Literal(scalar_value));
-                }
+            // arguments like `-1` or `(2 * 3)` are also literals
+            if let Some(literal) = Self::try_build_literal_expr(arg) {
+                result.literals.push(literal);
+            } else if result.input.replace(*arg.clone()).is_some() {
+                MultipleVectorSnafu { expr: *arg.clone() }.fail()?;
             }
         }
 
@@ -433,24 +545,80 @@ impl<S: ContextProvider> PromPlanner<S> {
 
         // TODO(ruihang): set this according to in-param list
         let value_column_pos = 0;
-        let scalar_func = BuiltinScalarFunction::from_str(func.name).map_err(|_| {
-            UnsupportedExprSnafu {
-                name: func.name.to_string(),
-            }
-            .build()
-        })?;
+        let scalar_func = match func.name {
+            "increase" => ScalarFunc::ExtrapolateUdf(Increase::scalar_udf()),
+            "rate" => ScalarFunc::ExtrapolateUdf(Rate::scalar_udf()),
+            "delta" => ScalarFunc::ExtrapolateUdf(Delta::scalar_udf()),
+            "idelta" => ScalarFunc::Udf(IDelta::<false>::scalar_udf()),
+            "irate" => ScalarFunc::Udf(IDelta::<true>::scalar_udf()),
+            "resets" => ScalarFunc::Udf(Resets::scalar_udf()),
+            "changes" => ScalarFunc::Udf(Changes::scalar_udf()),
+            "avg_over_time" => ScalarFunc::Udf(AvgOverTime::scalar_udf()),
+            "min_over_time" => ScalarFunc::Udf(MinOverTime::scalar_udf()),
+            "max_over_time" => ScalarFunc::Udf(MaxOverTime::scalar_udf()),
+            "sum_over_time" => ScalarFunc::Udf(SumOverTime::scalar_udf()),
+            "count_over_time" => ScalarFunc::Udf(CountOverTime::scalar_udf()),
+            "last_over_time" => ScalarFunc::Udf(LastOverTime::scalar_udf()),
+            "stddev_over_time" => ScalarFunc::Udf(StddevOverTime::scalar_udf()),
+            "stdvar_over_time" => ScalarFunc::Udf(StdvarOverTime::scalar_udf()),
+            "quantile_over_time" => ScalarFunc::Udf(QuantileOverTime::scalar_udf()),
+            "present_over_time" => ScalarFunc::Udf(PresentOverTime::scalar_udf()),
+            "absent_over_time" => ScalarFunc::Udf(AbsentOverTime::scalar_udf()),
+            _ => ScalarFunc::DataFusionBuiltin(
+                BuiltinScalarFunction::from_str(func.name).map_err(|_| {
+                    UnsupportedExprSnafu {
+                        name: func.name.to_string(),
+                    }
+                    .build()
+                })?,
+            ),
+        };
 
         // TODO(ruihang): handle those functions doesn't require input
         let mut exprs = Vec::with_capacity(self.ctx.value_columns.len());
         for value in &self.ctx.value_columns {
             let col_expr = DfExpr::Column(Column::from_name(value));
-            other_input_exprs.insert(value_column_pos, col_expr);
-            let fn_expr = DfExpr::ScalarFunction {
-                fun: scalar_func.clone(),
-                args: other_input_exprs.clone(),
+            let fn_expr = match &scalar_func {
+                ScalarFunc::DataFusionBuiltin(fun) => {
+                    other_input_exprs.insert(value_column_pos, col_expr);
+                    let fn_expr = DfExpr::ScalarFunction {
+                        fun: fun.clone(),
+                        args: other_input_exprs.clone(),
+                    };
+                    other_input_exprs.remove(value_column_pos);
+                    fn_expr
+                }
+                ScalarFunc::Udf(fun) => {
+                    let mut args = vec![self.create_timestamp_range_column_expr(func)?, col_expr];
+                    args.extend(other_input_exprs.iter().cloned());
+                    DfExpr::ScalarUDF {
+                        fun: Arc::new(fun.clone()),
+                        args,
+                    }
+                }
+                ScalarFunc::ExtrapolateUdf(fun) => {
+                    let range = self.ctx.range.with_context(|| ExpectRangeSelectorSnafu {
+                        name: func.name.to_string(),
+                    })?;
+                    // ranges end at the `@` timestamp instead of the evaluation timestamp
+                    let eval_ts = match self.ctx.at {
+                        Some(at) => {
+                            DfExpr::Literal(ScalarValue::TimestampMillisecond(Some(at), None))
+                        }
+                        None => self.create_time_index_column_expr()?,
+                    };
+                    DfExpr::ScalarUDF {
+                        fun: Arc::new(fun.clone()),
+                        args: vec![
+                            self.create_timestamp_range_column_expr(func)?,
+                            col_expr,
+                            eval_ts,
+                            DfExpr::Literal(ScalarValue::Int64(Some(range))),
+                        ],
+                    }
+                }
             };
             exprs.push(fn_expr);
-            other_input_exprs.remove(value_column_pos);
         }
 
         Ok(exprs)
@@ -465,6 +633,25 @@ impl<S: ContextProvider> PromPlanner<S> {
         )))
     }
 
+    /// Create the expr of column generated by [RangeManipulate] that holds the timestamp
+    /// ranges. The input of `func` is required to be a matrix selector.
+    fn create_timestamp_range_column_expr(&self, func: &Function) -> Result<DfExpr> {
+        ensure!(
+            self.ctx.range.is_some(),
+            ExpectRangeSelectorSnafu {
+                name: func.name.to_string(),
+            }
+        );
+        let time_index = self
+            .ctx
+            .time_index_column
+            .as_ref()
+            .with_context(|| TimeIndexNotFoundSnafu { table: "unknown" })?;
+        Ok(DfExpr::Column(Column::from_name(
+            RangeManipulate::build_timestamp_range_name(time_index),
+        )))
+    }
+
     fn create_empty_values_filter_expr(&self) -> Result<DfExpr> {
         let mut exprs = Vec::with_capacity(self.ctx.value_columns.len());
         for value in &self.ctx.value_columns {
@@ -487,12 +674,6 @@ impl<S: ContextProvider> PromPlanner<S> {
             token::T_GROUP => AggregateFunctionEnum::Grouping,
             token::T_STDDEV => AggregateFunctionEnum::Stddev,
             token::T_STDVAR => AggregateFunctionEnum::Variance,
-            token::T_TOPK | token::T_BOTTOMK | token::T_COUNT_VALUES | token::T_QUANTILE => {
-                UnsupportedExprSnafu {
-                    name: op.to_string(),
-                }
-                .fail()?
-            }
             _ => UnexpectedTokenSnafu { token: op }.fail()?,
         };
 
@@ -511,357 +692,1979 @@ impl<S: ContextProvider> PromPlanner<S> {
             .collect();
         Ok(exprs)
     }
-}
-
-#[derive(Default, Debug)]
-struct FunctionArgs {
-    input: Option<PromExpr>,
-    literals: Vec<DfExpr>,
-}
-
-#[cfg(test)]
-mod test {
-    use std::time::UNIX_EPOCH;
-
-    use catalog::local::MemoryCatalogManager;
-    use catalog::{CatalogManager, RegisterTableRequest};
-    use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
-    use datatypes::prelude::ConcreteDataType;
-    use datatypes::schema::{ColumnSchema, Schema};
-    use promql_parser::label::Matcher;
-    use promql_parser::parser::ValueType;
-    use query::query_engine::QueryEngineState;
-    use query::DfContextProviderAdapter;
-    use session::context::QueryContext;
-    use table::metadata::{TableInfoBuilder, TableMetaBuilder};
-    use table::test_util::EmptyTable;
 
-    use super::*;
+    /// Evaluate the number parameter of aggregation `op`, which must only consist of
+    /// number literals.
+    fn create_number_param(op: TokenType, param: &PromExpr) -> Result<f64> {
+        Self::try_eval_number_literal(param).with_context(|| ExpectLiteralParamSnafu {
+            op,
+            param: param.clone(),
+        })
+    }
 
-    async fn build_test_context_provider(
-        table_name: String,
-        num_tag: usize,
-        num_field: usize,
-    ) -> DfContextProviderAdapter {
-        let mut columns = vec![];
-        for i in 0..num_tag {
-            columns.push(ColumnSchema::new(
-                format!("tag_{i}"),
-                ConcreteDataType::string_datatype(),
-                false,
-            ));
+    /// Try to evaluate `expr` to a number if it only consists of number literals and
+    /// arithmetic operators.
+    fn try_eval_number_literal(expr: &PromExpr) -> Option<f64> {
+        match expr {
+            PromExpr::NumberLiteral(NumberLiteral { val, .. }) => Some(*val),
+            PromExpr::Paren(ParenExpr { expr, .. }) => Self::try_eval_number_literal(expr),
+            PromExpr::Unary(UnaryExpr { expr, .. }) => {
+                Self::try_eval_number_literal(expr).map(|val| -val)
+            }
+            PromExpr::Binary(PromBinaryExpr { lhs, rhs, op, .. }) => {
+                let lhs = Self::try_eval_number_literal(lhs)?;
+                let rhs = Self::try_eval_number_literal(rhs)?;
+                match *op {
+                    token::T_ADD => Some(lhs + rhs),
+                    token::T_SUB => Some(lhs - rhs),
+                    token::T_MUL => Some(lhs * rhs),
+                    token::T_DIV => Some(lhs / rhs),
+                    token::T_MOD => Some(lhs % rhs),
+                    token::T_POW => Some(lhs.powf(rhs)),
+                    _ => None,
+                }
+            }
+            _ => None,
         }
-        columns.push(
-            ColumnSchema::new(
-                "timestamp".to_string(),
-                ConcreteDataType::timestamp_millisecond_datatype(),
-                false,
-            )
-            .with_time_index(true),
+    }
+
+    /// Plan `topk` and `bottomk`. Samples are ranked by the first value column in each
+    /// group at every timestamp, and those with rank greater than `k` are filtered out.
+    /// Like Prometheus, `k` is truncated to an integer and a `k` less than 1 selects
+    /// nothing.
+    fn topk_to_plan(
+        &self,
+        input: LogicalPlan,
+        op: TokenType,
+        param: &PromExpr,
+        group_labels: &[String],
+    ) -> Result<LogicalPlan> {
+        let k = Self::create_number_param(op, param)?;
+        ensure!(
+            !k.is_nan(),
+            InvalidParamSnafu {
+                op,
+                param: k,
+                reason: "k must not be NaN",
+            }
         );
-        for i in 0..num_field {
-            columns.push(ColumnSchema::new(
-                format!("field_{i}"),
-                ConcreteDataType::float64_datatype(),
-                true,
-            ));
+        let k = DfExpr::Literal(ScalarValue::Float64(Some(k.trunc())));
+        let value = self
+            .ctx
+            .value_columns
+            .first()
+            .with_context(|| ValueNotFoundSnafu {
+                table: self.ctx.table_name.clone().unwrap_or_default(),
+            })?;
+
+        let mut partition_by = group_labels
+            .iter()
+            .map(|label| DfExpr::Column(Column::from_name(label)))
+            .collect::<Vec<_>>();
+        partition_by.push(self.create_time_index_column_expr()?);
+        let rank = DfExpr::WindowFunction {
+            fun: WindowFunction::BuiltInWindowFunction(BuiltInWindowFunction::RowNumber),
+            args: vec![],
+            partition_by,
+            order_by: vec![
+                DfExpr::Column(Column::from_name(value)).sort(op == token::T_BOTTOMK, false)
+            ],
+            window_frame: None,
         }
-        let schema = Arc::new(Schema::new(columns));
-        let table_meta = TableMetaBuilder::default()
-            .schema(schema)
-            .primary_key_indices((0..num_tag).collect())
-            .value_indices((num_tag + 1..num_tag + 1 + num_field).collect())
-            .next_column_id(1024)
-            .build()
-            .unwrap();
-        let table_info = TableInfoBuilder::default()
-            .name(&table_name)
-            .meta(table_meta)
-            .build()
-            .unwrap();
-        let table = Arc::new(EmptyTable::from_table_info(&table_info));
-        let catalog_list = Arc::new(MemoryCatalogManager::default());
-        catalog_list
-            .register_table(RegisterTableRequest {
-                catalog: DEFAULT_CATALOG_NAME.to_string(),
-                schema: DEFAULT_SCHEMA_NAME.to_string(),
-                table_name,
-                table_id: 1024,
-                table,
-            })
-            .await
-            .unwrap();
+        .alias(RANK_COLUMN);
 
-        let query_engine_state = QueryEngineState::new(catalog_list);
-        let query_context = QueryContext::new();
-        DfContextProviderAdapter::new(query_engine_state, query_context.into())
+        // project the rank column away
+        let exprs = input
+            .schema()
+            .fields()
+            .iter()
+            .map(|field| DfExpr::Column(field.qualified_column()))
+            .collect::<Vec<_>>();
+        LogicalPlanBuilder::from(input)
+            .window(vec![rank])
+            .context(DataFusionPlanningSnafu)?
+            .filter(DfExpr::Column(Column::from_name(RANK_COLUMN)).lt_eq(k))
+            .context(DataFusionPlanningSnafu)?
+            .project(exprs)
+            .context(DataFusionPlanningSnafu)?
+            .build()
+            .context(DataFusionPlanningSnafu)
     }
 
-    // {
-    //     input: `abs(some_metric{foo!="bar"})`,
-    //     expected: &Call{
-    //         Func: MustGetFunction("abs"),
-    //         Args: Expressions{
-    //             &VectorSelector{
-    //                 Name: "some_metric",
-    //                 LabelMatchers: []*labels.Matcher{
-    //                     MustLabelMatcher(labels.MatchNotEqual, "foo", "bar"),
-    //                     MustLabelMatcher(labels.MatchEqual, model.MetricNameLabel, "some_metric"),
-    //                 },
-    //             },
-    //         },
-    //     },
-    // },
-    async fn do_single_instant_function_call(fn_name: &'static str, plan_name: &str) {
-        let prom_expr = PromExpr::Call(Call {
-            func: Function {
-                name: fn_name,
-                arg_types: vec![ValueType::Vector],
-                variadic: false,
-                return_type: ValueType::Vector,
-            },
-            args: vec![Box::new(PromExpr::VectorSelector(VectorSelector {
-                name: Some("some_metric".to_owned()),
-                offset: None,
-                start_or_end: None,
-                label_matchers: Matchers {
-                    matchers: vec![
-                        Matcher {
-                            op: MatchOp::NotEqual,
-                            name: "tag_0".to_string(),
-                            value: "bar".to_string(),
-                        },
-                        Matcher {
-                            op: MatchOp::Equal,
-                            name: METRIC_NAME.to_string(),
-                            value: "some_metric".to_string(),
-                        },
-                    ],
-                },
-            }))],
-        });
-        let eval_stmt = EvalStmt {
-            expr: prom_expr,
-            start: UNIX_EPOCH,
-            end: UNIX_EPOCH
-                .checked_add(Duration::from_secs(100_000))
-                .unwrap(),
-            interval: Duration::from_secs(5),
-            lookback_delta: Duration::from_secs(1),
+    /// Plan `count_values`, which counts samples of the same value in each group. The
+    /// value is output as a new label named by the parameter.
+    fn count_values_to_plan(
+        &mut self,
+        input: LogicalPlan,
+        param: &PromExpr,
+        mut group_labels: Vec<String>,
+    ) -> Result<LogicalPlan> {
+        let PromExpr::StringLiteral(StringLiteral { val: label, .. }) = param else {
+            return ExpectLiteralParamSnafu {
+                op: token::T_COUNT_VALUES,
+                param: param.clone(),
+            }
+            .fail();
         };
+        let value = self
+            .ctx
+            .value_columns
+            .first()
+            .with_context(|| ValueNotFoundSnafu {
+                table: self.ctx.table_name.clone().unwrap_or_default(),
+            })?
+            .clone();
+        let value_expr = DfExpr::Column(Column::from_name(&value));
 
-        let context_provider = build_test_context_provider("some_metric".to_string(), 1, 1).await;
-        let plan = PromPlanner::stmt_to_plan(eval_stmt, context_provider).unwrap();
+        let mut group_exprs = group_labels
+            .iter()
+            .map(|label| DfExpr::Column(Column::from_name(label)))
+            .collect::<Vec<_>>();
+        group_exprs.push(self.create_time_index_column_expr()?);
+        group_exprs.push(value_expr.clone());
+        let count = DfExpr::AggregateFunction(AggregateFunction {
+            fun: AggregateFunctionEnum::Count,
+            args: vec![value_expr.clone()],
+            distinct: false,
+            filter: None,
+        });
+        let aggregate = LogicalPlanBuilder::from(input)
+            .aggregate(group_exprs, vec![count])
+            .context(DataFusionPlanningSnafu)?;
+        // safety: the aggregate plan has at least the count column
+        let count_name = last_column_names(&aggregate, 1).pop().unwrap();
 
-        let  expected = String::from(
-            "Filter: some_metric.field_0 IS NOT NULL [timestamp:Timestamp(Millisecond, None), TEMPLATE(some_metric.field_0):Float64;N]\
-            \n  Projection: some_metric.timestamp, TEMPLATE(some_metric.field_0) [timestamp:Timestamp(Millisecond, None), TEMPLATE(some_metric.field_0):Float64;N]\
-            \n    PromInstantManipulate: range=[0..100000000], lookback=[1000], interval=[5000], time index=[timestamp] [tag_0:Utf8, timestamp:Timestamp(Millisecond, None), field_0:Float64;N]\
-            \n      PromSeriesNormalize: offset=[0], time index=[timestamp] [tag_0:Utf8, timestamp:Timestamp(Millisecond, None), field_0:Float64;N]\
-            \n        Filter: tag_0 != Utf8(\"bar\") AND timestamp >= TimestampMillisecond(0, None) AND timestamp <= TimestampMillisecond(100000000, None) [tag_0:Utf8, timestamp:Timestamp(Millisecond, None), field_0:Float64;N]\
-            \n          TableScan: some_metric, unsupported_filters=[tag_0 != Utf8(\"bar\"), timestamp >= TimestampMillisecond(0, None), timestamp <= TimestampMillisecond(100000000, None)] [tag_0:Utf8, timestamp:Timestamp(Millisecond, None), field_0:Float64;N]",
-        ).replace("TEMPLATE", plan_name);
+        let mut exprs = group_labels
+            .iter()
+            .map(|label| DfExpr::Column(Column::from_name(label)))
+            .collect::<Vec<_>>();
+        exprs.push(DfExpr::Cast(Cast::new(Box::new(value_expr), DataType::Utf8)).alias(label));
+        exprs.push(self.create_time_index_column_expr()?);
+        exprs.push(
+            DfExpr::Cast(Cast::new(
+                Box::new(DfExpr::Column(Column::from_name(count_name))),
+                DataType::Float64,
+            ))
+            .alias(&value),
+        );
 
-        assert_eq!(plan.display_indent_schema().to_string(), expected);
+        group_labels.push(label.clone());
+        self.ctx.tag_columns = group_labels;
+        self.ctx.value_columns = vec![value];
+
+        aggregate
+            .project(exprs)
+            .context(DataFusionPlanningSnafu)?
+            .build()
+            .context(DataFusionPlanningSnafu)
     }
 
-    #[tokio::test]
-    async fn single_abs() {
+    /// Plan `quantile`, which calculates the φ-quantile of samples in each group like
+    /// Prometheus. Samples of each group are collected by `ARRAY_AGG` and the quantile
+    /// is interpolated between the two nearest samples. φ out of [0, 1] results in -Inf
+    /// or +Inf.
+    fn quantile_to_plan(
+        &mut self,
+        input: LogicalPlan,
+        param: &PromExpr,
+        group_labels: Vec<String>,
+    ) -> Result<LogicalPlan> {
+        let quantile = Self::create_number_param(token::T_QUANTILE, param)?;
+
+        let mut group_exprs = group_labels
+            .iter()
+            .map(|label| DfExpr::Column(Column::from_name(label)))
+            .collect::<Vec<_>>();
+        group_exprs.push(self.create_time_index_column_expr()?);
+        let aggr_exprs = self
+            .ctx
+            .value_columns
+            .iter()
+            .map(|value| {
+                DfExpr::AggregateFunction(AggregateFunction {
+                    fun: AggregateFunctionEnum::ArrayAgg,
+                    args: vec![DfExpr::Column(Column::from_name(value))],
+                    distinct: false,
+                    filter: None,
+                })
+            })
+            .collect::<Vec<_>>();
+        let num_values = aggr_exprs.len();
+        let aggregate = LogicalPlanBuilder::from(input)
+            .aggregate(group_exprs.clone(), aggr_exprs)
+            .context(DataFusionPlanningSnafu)?;
+
+        let mut exprs = group_exprs;
+        for (list, value) in last_column_names(&aggregate, num_values)
+            .into_iter()
+            .zip(self.ctx.value_columns.iter())
+        {
+            let quantile = DfExpr::ScalarUDF {
+                fun: Arc::new(Quantile::scalar_udf()),
+                args: vec![
+                    DfExpr::Column(Column::from_name(list)),
+                    DfExpr::Literal(ScalarValue::Float64(Some(quantile))),
+                ],
+            };
+            exprs.push(quantile.alias(value));
+        }
+
+        self.ctx.tag_columns = group_labels;
+        aggregate
+            .project(exprs)
+            .context(DataFusionPlanningSnafu)?
+            .build()
+            .context(DataFusionPlanningSnafu)
+    }
+
+    fn create_tag_column_exprs(&self) -> Vec<DfExpr> {
+        self.ctx
+            .tag_columns
+            .iter()
+            .map(|tag| DfExpr::Column(Column::from_name(tag)))
+            .collect()
+    }
+
+    fn binary_expr_to_plan(&mut self, binary_expr: &PromBinaryExpr) -> Result<LogicalPlan> {
+        let PromBinaryExpr { lhs, rhs, op, .. } = binary_expr;
+        let matching = VectorMatching::from_binary_expr(binary_expr);
+
+        match (
+            Self::try_build_literal_expr(lhs),
+            Self::try_build_literal_expr(rhs),
+        ) {
+            (Some(lhs), Some(rhs)) => {
+                let expr = Self::build_binary_expr(lhs, rhs, *op, matching.return_bool)?;
+                self.literal_to_plan(expr)
+            }
+            (Some(literal), None) => {
+                let input = self.prom_expr_to_plan(*rhs.clone())?;
+                self.binary_with_literal(input, literal, true, *op, matching.return_bool)
+            }
+            (None, Some(literal)) => {
+                let input = self.prom_expr_to_plan(*lhs.clone())?;
+                self.binary_with_literal(input, literal, false, *op, matching.return_bool)
+            }
+            (None, None) => {
+                let left_input = self.prom_expr_to_plan(*lhs.clone())?;
+                let left_ctx = self.ctx.clone();
+                let right_input = self.prom_expr_to_plan(*rhs.clone())?;
+                let right_ctx = self.ctx.clone();
+
+                let left = BinaryOperand::new(left_input, left_ctx, LEFT_OPERAND_ALIAS)?;
+                let right = BinaryOperand::new(right_input, right_ctx, RIGHT_OPERAND_ALIAS)?;
+                if Self::is_set_op(*op) {
+                    self.set_op_to_plan(left, right, *op, &matching)
+                } else {
+                    self.vectors_binary_to_plan(left, right, *op, &matching)
+                }
+            }
+        }
+    }
+
+    /// Try to build a DataFusion expr from `expr` if it only consists of literals.
+    /// Returns `None` if there is any selector in it.
+    fn try_build_literal_expr(expr: &PromExpr) -> Option<DfExpr> {
+        match expr {
+            PromExpr::NumberLiteral(NumberLiteral { val, .. }) => {
+                Some(DfExpr::Literal(ScalarValue::Float64(Some(*val))))
+            }
+            PromExpr::StringLiteral(StringLiteral { val, .. }) => {
+                Some(DfExpr::Literal(ScalarValue::Utf8(Some(val.clone()))))
+            }
+            PromExpr::Paren(ParenExpr { expr, .. }) => Self::try_build_literal_expr(expr),
+            PromExpr::Unary(UnaryExpr { expr, .. }) => {
+                Self::try_build_literal_expr(expr).map(|expr| DfExpr::Negative(Box::new(expr)))
+            }
+            PromExpr::Binary(binary_expr) => {
+                let PromBinaryExpr { lhs, rhs, op, .. } = binary_expr;
+                let lhs = Self::try_build_literal_expr(lhs)?;
+                let rhs = Self::try_build_literal_expr(rhs)?;
+                let return_bool = VectorMatching::from_binary_expr(binary_expr).return_bool;
+                Self::build_binary_expr(lhs, rhs, *op, return_bool).ok()
+            }
+            _ => None,
+        }
+    }
+
+    /// Build the expr of binary operator `op` on `lhs` and `rhs`. Comparisons return
+    /// 0 or 1 in Float64 if `return_bool` is set, or a boolean otherwise.
+    fn build_binary_expr(
+        lhs: DfExpr,
+        rhs: DfExpr,
+        op: TokenType,
+        return_bool: bool,
+    ) -> Result<DfExpr> {
+        let df_op = match op {
+            token::T_ADD => Operator::Plus,
+            token::T_SUB => Operator::Minus,
+            token::T_MUL => Operator::Multiply,
+            token::T_DIV => Operator::Divide,
+            token::T_MOD => Operator::Modulo,
+            token::T_EQLC => Operator::Eq,
+            token::T_NEQ => Operator::NotEq,
+            token::T_GTR => Operator::Gt,
+            token::T_LSS => Operator::Lt,
+            token::T_GTE => Operator::GtEq,
+            token::T_LTE => Operator::LtEq,
+            token::T_POW => {
+                return Ok(DfExpr::ScalarFunction {
+                    fun: BuiltinScalarFunction::Power,
+                    args: vec![lhs, rhs],
+                })
+            }
+            token::T_ATAN2 => {
+                return Ok(DfExpr::ScalarFunction {
+                    fun: BuiltinScalarFunction::Atan2,
+                    args: vec![lhs, rhs],
+                })
+            }
+            _ => UnexpectedTokenSnafu { token: op }.fail()?,
+        };
+
+        let expr = DfExpr::BinaryExpr(BinaryExpr {
+            left: Box::new(lhs),
+            op: df_op,
+            right: Box::new(rhs),
+        });
+        if return_bool && Self::is_comparison_op(op) {
+            Ok(DfExpr::Cast(Cast::new(Box::new(expr), DataType::Float64)))
+        } else {
+            Ok(expr)
+        }
+    }
+
+    fn is_comparison_op(op: TokenType) -> bool {
+        matches!(
+            op,
+            token::T_EQLC
+                | token::T_NEQ
+                | token::T_GTR
+                | token::T_LSS
+                | token::T_GTE
+                | token::T_LTE
+        )
+    }
+
+    fn is_set_op(op: TokenType) -> bool {
+        matches!(op, token::T_LAND | token::T_LOR | token::T_LUNLESS)
+    }
+
+    /// Plan an expr that only contains literals to a series that has one sample at each
+    /// evaluation timestamp.
+    fn literal_to_plan(&mut self, literal: DfExpr) -> Result<LogicalPlan> {
+        let timestamps = (self.ctx.start..=self.ctx.end)
+            .step_by(self.ctx.interval.max(1) as usize)
+            .map(|ts| {
+                vec![DfExpr::Literal(ScalarValue::TimestampMillisecond(
+                    Some(ts),
+                    None,
+                ))]
+            })
+            .collect();
+
+        self.ctx.table_name = None;
+        self.ctx.time_index_column = Some(LITERAL_TIME_INDEX_COLUMN.to_string());
+        self.ctx.tag_columns = vec![];
+        self.ctx.value_columns = vec![LITERAL_VALUE_COLUMN.to_string()];
+        self.ctx.range = None;
+        self.ctx.at = None;
+
+        LogicalPlanBuilder::values(timestamps)
+            .context(DataFusionPlanningSnafu)?
+            .project(vec![
+                DfExpr::Column(Column::from_name("column1")).alias(LITERAL_TIME_INDEX_COLUMN),
+                literal.alias(LITERAL_VALUE_COLUMN),
+            ])
+            .context(DataFusionPlanningSnafu)?
+            .build()
+            .context(DataFusionPlanningSnafu)
+    }
+
+    /// Apply `op` between every value column of `input` and `literal`. Comparisons
+    /// without `bool` filter out the samples that don't satisfy them.
+    fn binary_with_literal(
+        &self,
+        input: LogicalPlan,
+        literal: DfExpr,
+        literal_on_left: bool,
+        op: TokenType,
+        return_bool: bool,
+    ) -> Result<LogicalPlan> {
+        ensure!(!Self::is_set_op(op), UnexpectedTokenSnafu { token: op });
+        let build_expr = |value: DfExpr| {
+            if literal_on_left {
+                Self::build_binary_expr(literal.clone(), value, op, return_bool)
+            } else {
+                Self::build_binary_expr(value, literal.clone(), op, return_bool)
+            }
+        };
+
+        if Self::is_comparison_op(op) && !return_bool {
+            let predicates = self
+                .ctx
+                .value_columns
+                .iter()
+                .map(|value| build_expr(DfExpr::Column(Column::from_name(value))))
+                .collect::<Result<Vec<_>>>()?;
+            let predicate =
+                utils::conjunction(predicates.into_iter()).with_context(|| ValueNotFoundSnafu {
+                    table: self.ctx.table_name.clone().unwrap_or_default(),
+                })?;
+            LogicalPlanBuilder::from(input)
+                .filter(predicate)
+                .context(DataFusionPlanningSnafu)?
+                .build()
+                .context(DataFusionPlanningSnafu)
+        } else {
+            self.projection_on_values(input, build_expr)
+        }
+    }
+
+    /// Project `input` with every value column replaced by the result of `f` on it.
+    /// Other columns are kept as they are.
+    fn projection_on_values<F>(&self, input: LogicalPlan, f: F) -> Result<LogicalPlan>
+    where
+        F: Fn(DfExpr) -> Result<DfExpr>,
+    {
+        let value_columns = self.ctx.value_columns.iter().collect::<HashSet<_>>();
+        let exprs = input
+            .schema()
+            .fields()
+            .iter()
+            .map(|field| {
+                let column = DfExpr::Column(field.qualified_column());
+                if value_columns.contains(field.name()) {
+                    Ok(f(column)?.alias(field.name()))
+                } else {
+                    Ok(column)
+                }
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        LogicalPlanBuilder::from(input)
+            .project(exprs)
+            .context(DataFusionPlanningSnafu)?
+            .build()
+            .context(DataFusionPlanningSnafu)
+    }
+
+    /// Labels the two operands are matched on, excluding the time index.
+    fn match_labels(
+        left: &BinaryOperand,
+        right: &BinaryOperand,
+        matching: &VectorMatching,
+    ) -> Vec<String> {
+        match &matching.on {
+            Some(on) => on.clone(),
+            // Prometheus requires all labels to be equal. A label that only exists in one
+            // side is still compared, as it is empty in the other side.
+            None => left
+                .labels
+                .iter()
+                .chain(right.labels.iter())
+                .filter(|label| !matching.ignoring.contains(label))
+                .collect::<BTreeSet<_>>()
+                .into_iter()
+                .cloned()
+                .collect(),
+        }
+    }
+
+    /// Join `left` and `right` on the time index and `labels`. Sides in `unique_sides`
+    /// fail on execution if more than one of their series match the same labels.
+    fn join_operands(
+        left: &BinaryOperand,
+        right: &BinaryOperand,
+        labels: &[String],
+        join_type: JoinType,
+        unique_sides: UniqueSides,
+    ) -> Result<LogicalPlanBuilder> {
+        let keys = |operand: &BinaryOperand| {
+            labels
+                .iter()
+                .map(|label| Column::new(Some(operand.alias), match_key_column(label)))
+                .chain(std::iter::once(operand.column(&operand.time_index)))
+                .collect::<Vec<_>>()
+        };
+        let left_plan = Self::match_key_plan(left, labels, unique_sides.left)?;
+        let right_plan = Self::match_key_plan(right, labels, unique_sides.right)?;
+
+        LogicalPlanBuilder::from(left_plan)
+            .alias(left.alias)
+            .context(DataFusionPlanningSnafu)?
+            .join(
+                LogicalPlanBuilder::from(right_plan)
+                    .alias(right.alias)
+                    .context(DataFusionPlanningSnafu)?
+                    .build()
+                    .context(DataFusionPlanningSnafu)?,
+                join_type,
+                (keys(left), keys(right)),
+                None,
+            )
+            .context(DataFusionPlanningSnafu)
+    }
+
+    /// Add a key column of each label in `labels` to the plan of `operand`, to join on.
+    ///
+    /// If `unique` is set, the plan fails on execution if more than one series of the
+    /// operand have the same keys at a timestamp.
+    fn match_key_plan(
+        operand: &BinaryOperand,
+        labels: &[String],
+        unique: bool,
+    ) -> Result<LogicalPlan> {
+        let columns = operand
+            .plan
+            .schema()
+            .fields()
+            .iter()
+            .map(|field| DfExpr::Column(field.qualified_column()))
+            .collect::<Vec<_>>();
+        let mut exprs = columns.clone();
+        exprs.extend(
+            labels
+                .iter()
+                .map(|label| operand.match_key_expr(label).alias(match_key_column(label))),
+        );
+        let builder = LogicalPlanBuilder::from(operand.plan.clone())
+            .project(exprs)
+            .context(DataFusionPlanningSnafu)?;
+        if !unique {
+            return builder.build().context(DataFusionPlanningSnafu);
+        }
+
+        let mut partition_by = labels
+            .iter()
+            .map(|label| DfExpr::Column(Column::from_name(match_key_column(label))))
+            .collect::<Vec<_>>();
+        partition_by.push(DfExpr::Column(Column::from_name(&operand.time_index)));
+        let count = DfExpr::WindowFunction {
+            fun: WindowFunction::AggregateFunction(AggregateFunctionEnum::Count),
+            args: vec![DfExpr::Column(Column::from_name(&operand.time_index))],
+            partition_by,
+            order_by: vec![],
+            window_frame: None,
+        }
+        .alias(MATCH_COUNT_COLUMN);
+        let check = DfExpr::ScalarUDF {
+            fun: Arc::new(unique_match_udf(operand.side())),
+            args: vec![DfExpr::Column(Column::from_name(MATCH_COUNT_COLUMN))],
+        };
+
+        // project the count column away
+        let mut exprs = columns;
+        exprs.extend(
+            labels
+                .iter()
+                .map(|label| DfExpr::Column(Column::from_name(match_key_column(label)))),
+        );
+        builder
+            .window(vec![count])
+            .context(DataFusionPlanningSnafu)?
+            .filter(check)
+            .context(DataFusionPlanningSnafu)?
+            .project(exprs)
+            .context(DataFusionPlanningSnafu)?
+            .build()
+            .context(DataFusionPlanningSnafu)
+    }
+
+    /// Plan arithmetic and comparison operators between two vectors by joining them on
+    /// the matching labels and the time index.
+    fn vectors_binary_to_plan(
+        &mut self,
+        left: BinaryOperand,
+        right: BinaryOperand,
+        op: TokenType,
+        matching: &VectorMatching,
+    ) -> Result<LogicalPlan> {
+        let match_labels = Self::match_labels(&left, &right, matching);
+        // the "one" side of group modifiers, and both sides of one-to-one matching,
+        // must not have more than one series matched by the same labels
+        let unique_sides = match matching.group {
+            Some(Group::Left) => UniqueSides {
+                left: false,
+                right: true,
+            },
+            Some(Group::Right) => UniqueSides {
+                left: true,
+                right: false,
+            },
+            None => UniqueSides {
+                left: true,
+                right: true,
+            },
+        };
+        let joined =
+            Self::join_operands(&left, &right, &match_labels, JoinType::Inner, unique_sides)?;
+
+        // labels and time index of the output come from the "many" side, and
+        // `group_left` / `group_right` take the included labels from the "one" side
+        let (many, one) = match matching.group {
+            Some(Group::Right) => (&right, &left),
+            Some(Group::Left) | None => (&left, &right),
+        };
+        let output_labels = match matching.group {
+            Some(_) => many
+                .labels
+                .iter()
+                .filter(|label| !matching.include.contains(label))
+                .map(|label| (many, label))
+                .chain(
+                    matching
+                        .include
+                        .iter()
+                        .filter(|label| one.labels.contains(label))
+                        .map(|label| (one, label)),
+                )
+                .collect::<Vec<_>>(),
+            None => many
+                .labels
+                .iter()
+                .filter(|label| match &matching.on {
+                    Some(on) => on.contains(label),
+                    None => !matching.ignoring.contains(label),
+                })
+                .map(|label| (many, label))
+                .collect(),
+        };
+        let mut exprs = output_labels
+            .iter()
+            .map(|(operand, label)| operand.column_expr(label))
+            .collect::<Vec<_>>();
+        exprs.push(many.column_expr(&many.time_index));
+
+        let value_pairs = Self::pair_value_columns(&left, &right)?;
+        let is_filter = Self::is_comparison_op(op) && !matching.return_bool;
+        let mut predicates = Vec::with_capacity(value_pairs.len());
+        let mut value_columns = Vec::with_capacity(value_pairs.len());
+        for (left_value, right_value, name) in value_pairs {
+            let expr = Self::build_binary_expr(
+                left.column_expr(&left_value),
+                right.column_expr(&right_value),
+                op,
+                matching.return_bool,
+            )?;
+            if is_filter {
+                // comparisons keep the sample of the left side
+                predicates.push(expr);
+                exprs.push(left.column_expr(&left_value).alias(&name));
+            } else {
+                exprs.push(expr.alias(&name));
+            }
+            value_columns.push(name);
+        }
+
+        let joined = match utils::conjunction(predicates.into_iter()) {
+            Some(predicate) => joined.filter(predicate).context(DataFusionPlanningSnafu)?,
+            None => joined,
+        };
+        let plan = joined
+            .project(exprs)
+            .context(DataFusionPlanningSnafu)?
+            .build()
+            .context(DataFusionPlanningSnafu)?;
+
+        self.ctx = many.ctx.clone();
+        self.ctx.tag_columns = output_labels
+            .into_iter()
+            .map(|(_, label)| label.clone())
+            .collect();
+        self.ctx.value_columns = value_columns;
+        Ok(plan)
+    }
+
+    /// Plan set operators `and`, `or` and `unless`. The first two are planned as
+    /// semi and anti joins. `or` is the union of `left` and the part of `right` that
+    /// doesn't match `left`.
+    fn set_op_to_plan(
+        &mut self,
+        left: BinaryOperand,
+        right: BinaryOperand,
+        op: TokenType,
+        matching: &VectorMatching,
+    ) -> Result<LogicalPlan> {
+        let match_labels = Self::match_labels(&left, &right, matching);
+        // set operators allow many-to-many matching
+        let unique_sides = UniqueSides::default();
+        // project the match keys away
+        let left_columns = left
+            .plan
+            .schema()
+            .fields()
+            .iter()
+            .map(|field| left.column_expr(field.name()))
+            .collect::<Vec<_>>();
+        let plan = match op {
+            token::T_LAND | token::T_LUNLESS => {
+                let join_type = if op == token::T_LAND {
+                    JoinType::LeftSemi
+                } else {
+                    JoinType::LeftAnti
+                };
+                Self::join_operands(&left, &right, &match_labels, join_type, unique_sides)?
+                    .project(left_columns)
+                    .context(DataFusionPlanningSnafu)?
+                    .build()
+                    .context(DataFusionPlanningSnafu)?
+            }
+            token::T_LOR => {
+                let right_only = Self::join_operands(
+                    &right,
+                    &left,
+                    &match_labels,
+                    JoinType::LeftAnti,
+                    unique_sides,
+                )?;
+
+                // both sides are projected to the same columns to be unioned
+                let labels = left
+                    .labels
+                    .iter()
+                    .chain(right.labels.iter())
+                    .collect::<BTreeSet<_>>();
+                let value_pairs = Self::pair_value_columns(&left, &right)?;
+                let union_exprs = |operand: &BinaryOperand, is_left: bool| {
+                    let mut exprs = labels
+                        .iter()
+                        .map(|label| {
+                            if operand.labels.contains(label) {
+                                operand.column_expr(label).alias(label.as_str())
+                            } else {
+                                DfExpr::Literal(ScalarValue::Utf8(None)).alias(label.as_str())
+                            }
+                        })
+                        .collect::<Vec<_>>();
+                    exprs.push(
+                        operand
+                            .column_expr(&operand.time_index)
+                            .alias(&left.time_index),
+                    );
+                    for (left_value, right_value, _) in &value_pairs {
+                        let value = if is_left { left_value } else { right_value };
+                        exprs.push(operand.column_expr(value).alias(left_value));
+                    }
+                    exprs
+                };
+
+                LogicalPlanBuilder::from(left.plan.clone())
+                    .alias(left.alias)
+                    .context(DataFusionPlanningSnafu)?
+                    .project(union_exprs(&left, true))
+                    .context(DataFusionPlanningSnafu)?
+                    .union(
+                        right_only
+                            .project(union_exprs(&right, false))
+                            .context(DataFusionPlanningSnafu)?
+                            .build()
+                            .context(DataFusionPlanningSnafu)?,
+                    )
+                    .context(DataFusionPlanningSnafu)?
+                    .build()
+                    .context(DataFusionPlanningSnafu)?
+            }
+            _ => UnexpectedTokenSnafu { token: op }.fail()?,
+        };
+
+        self.ctx = left.ctx.clone();
+        if op == token::T_LOR {
+            self.ctx.tag_columns = left
+                .labels
+                .iter()
+                .chain(right.labels.iter())
+                .collect::<BTreeSet<_>>()
+                .into_iter()
+                .cloned()
+                .collect();
+            self.ctx.value_columns = Self::pair_value_columns(&left, &right)?
+                .into_iter()
+                .map(|(left_value, _, _)| left_value)
+                .collect();
+        }
+        Ok(plan)
+    }
+
+    /// Pair up value columns of both operands. Returns tuples of the left column, the
+    /// right column and the output name. A single value column is paired with every
+    /// value column of the other side.
+    fn pair_value_columns(
+        left: &BinaryOperand,
+        right: &BinaryOperand,
+    ) -> Result<Vec<(String, String, String)>> {
+        let left_values = &left.ctx.value_columns;
+        let right_values = &right.ctx.value_columns;
+        let pairs = if left_values.len() == right_values.len() {
+            left_values
+                .iter()
+                .zip(right_values.iter())
+                .map(|(l, r)| (l.clone(), r.clone(), l.clone()))
+                .collect()
+        } else if right_values.len() == 1 {
+            left_values
+                .iter()
+                .map(|l| (l.clone(), right_values[0].clone(), l.clone()))
+                .collect()
+        } else if left_values.len() == 1 {
+            right_values
+                .iter()
+                .map(|r| (left_values[0].clone(), r.clone(), r.clone()))
+                .collect()
+        } else {
+            return ValueColumnMismatchSnafu {
+                left: left_values.clone(),
+                right: right_values.clone(),
+            }
+            .fail();
+        };
+        Ok(pairs)
+    }
+}
+
+#[derive(Default, Debug)]
+struct FunctionArgs {
+    input: Option<PromExpr>,
+    literals: Vec<DfExpr>,
+}
+
+/// Alias of the operands of binary exprs, to distinguish columns of the same name
+/// from both sides after they are joined.
+const LEFT_OPERAND_ALIAS: &str = "lhs";
+const RIGHT_OPERAND_ALIAS: &str = "rhs";
+
+/// Prefix of the columns binary operands are joined on, one for each matched label.
+const MATCH_KEY_COLUMN_PREFIX: &str = "prom_match_key_";
+/// Column of the number of series in an operand matched by the same labels.
+const MATCH_COUNT_COLUMN: &str = "prom_match_count";
+
+fn match_key_column(label: &str) -> String {
+    format!("{MATCH_KEY_COLUMN_PREFIX}{label}")
+}
+
+/// Sides of a binary expr that must not have more than one series matched by the same
+/// labels.
+#[derive(Debug, Default, Clone, Copy)]
+struct UniqueSides {
+    left: bool,
+    right: bool,
+}
+
+/// Build a [ScalarUDF] that returns true for every match count, or fails if any count is
+/// greater than one, i.e. more than one series of the `side` operand are matched by the
+/// same labels.
+fn unique_match_udf(side: &'static str) -> ScalarUDF {
+    let fun: ScalarFunctionImplementation = Arc::new(move |input: &[ColumnarValue]| {
+        let counts = match &input[0] {
+            ColumnarValue::Array(array) => array.clone(),
+            ColumnarValue::Scalar(scalar) => scalar.to_array(),
+        };
+        let counts = counts
+            .as_any()
+            .downcast_ref::<Int64Array>()
+            .ok_or_else(|| {
+                DataFusionError::Execution(format!(
+                    "expect Int64 match counts, found {}",
+                    counts.data_type()
+                ))
+            })?;
+        if counts.iter().flatten().any(|count| count > 1) {
+            return Err(DataFusionError::Execution(format!(
+                "found duplicate series for the match group on the {side} hand-side of the \
+                 operation, many-to-many matching not allowed: matching labels must be \
+                 unique on one side"
+            )));
+        }
+
+        Ok(ColumnarValue::Array(Arc::new(BooleanArray::from(vec![
+            true;
+            counts.len()
+        ]))))
+    });
+    let return_type: ReturnTypeFunction = Arc::new(|_| Ok(Arc::new(DataType::Boolean)));
+
+    ScalarUDF::new(
+        &format!("prom_unique_match_{side}"),
+        &Signature::exact(vec![DataType::Int64], Volatility::Immutable),
+        &return_type,
+        &fun,
+    )
+}
+
+/// Names of the last `n` columns in the output of `plan`.
+fn last_column_names(plan: &LogicalPlanBuilder, n: usize) -> Vec<String> {
+    let fields = plan.schema().fields();
+    fields[fields.len().saturating_sub(n)..]
+        .iter()
+        .map(|field| field.name().clone())
+        .collect()
+}
+
+/// Column of the rank of each sample in `topk` and `bottomk`.
+const RANK_COLUMN: &str = "prom_rank";
+
+/// Columns of the plan built from an expr that only contains literals.
+const LITERAL_TIME_INDEX_COLUMN: &str = "time";
+const LITERAL_VALUE_COLUMN: &str = "value";
+
+/// One side of a binary expr between two vectors.
+struct BinaryOperand {
+    plan: LogicalPlan,
+    ctx: PromPlannerContext,
+    alias: &'static str,
+    time_index: String,
+    /// Columns other than the time index and values.
+    labels: Vec<String>,
+}
+
+impl BinaryOperand {
+    fn new(plan: LogicalPlan, ctx: PromPlannerContext, alias: &'static str) -> Result<Self> {
+        let time_index = ctx
+            .time_index_column
+            .clone()
+            .with_context(|| TimeIndexNotFoundSnafu {
+                table: ctx.table_name.clone().unwrap_or_default(),
+            })?;
+        let labels = plan
+            .schema()
+            .fields()
+            .iter()
+            .map(|field| field.name())
+            .filter(|name| **name != time_index && !ctx.value_columns.contains(name))
+            .cloned()
+            .collect();
+
+        Ok(Self {
+            plan,
+            ctx,
+            alias,
+            time_index,
+            labels,
+        })
+    }
+
+    /// The column of `name` in the aliased plan.
+    fn column(&self, name: &str) -> Column {
+        Column::new(Some(self.alias), name)
+    }
+
+    fn column_expr(&self, name: &str) -> DfExpr {
+        DfExpr::Column(self.column(name))
+    }
+
+    /// Value of `label` in the plan to match with the other operand. Labels absent in the
+    /// operand are empty, as Prometheus doesn't distinguish empty and absent labels.
+    fn match_key_expr(&self, label: &str) -> DfExpr {
+        let empty = DfExpr::Literal(ScalarValue::Utf8(Some(String::new())));
+        if !self.labels.iter().any(|l| l == label) {
+            return empty;
+        }
+
+        let value = DfExpr::Cast(Cast::new(
+            Box::new(DfExpr::Column(Column::from_name(label))),
+            DataType::Utf8,
+        ));
+        DfExpr::ScalarFunction {
+            fun: BuiltinScalarFunction::Coalesce,
+            args: vec![value, empty],
+        }
+    }
+
+    /// Name of the side in errors.
+    fn side(&self) -> &'static str {
+        if self.alias == LEFT_OPERAND_ALIAS {
+            "left"
+        } else {
+            "right"
+        }
+    }
+}
+
+/// Which side is the "many" side in many-to-one or one-to-many matching.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Group {
+    /// `group_left`, the left side is the "many" side.
+    Left,
+    /// `group_right`, the right side is the "many" side.
+    Right,
+}
+
+/// How the elements of two vectors are matched in a binary expr.
+#[derive(Debug, Default)]
+struct VectorMatching {
+    /// Labels in `on(...)`, `None` if `on` is absent.
+    on: Option<Vec<String>>,
+    /// Labels in `ignoring(...)`.
+    ignoring: Vec<String>,
+    /// `group_left` or `group_right`, `None` for one-to-one or set operators.
+    group: Option<Group>,
+    /// Labels in `group_left(...)` or `group_right(...)` to include from the "one" side.
+    include: Vec<String>,
+    /// Whether the `bool` modifier is set.
+    return_bool: bool,
+}
+
+impl VectorMatching {
+    fn from_binary_expr(expr: &PromBinaryExpr) -> Self {
+        let Some(modifier) = &expr.modifier else { return Self::default() };
+
+        let mut matching = Self {
+            return_bool: modifier.return_bool,
+            ..Default::default()
+        };
+        match &modifier.matching {
+            Some(LabelModifier::Include(labels)) => matching.on = Some(Self::sorted_labels(labels)),
+            Some(LabelModifier::Exclude(labels)) => matching.ignoring = Self::sorted_labels(labels),
+            None => {}
+        }
+        match &modifier.card {
+            VectorMatchCardinality::ManyToOne(labels) => {
+                matching.group = Some(Group::Left);
+                matching.include = Self::sorted_labels(labels);
+            }
+            VectorMatchCardinality::OneToMany(labels) => {
+                matching.group = Some(Group::Right);
+                matching.include = Self::sorted_labels(labels);
+            }
+            VectorMatchCardinality::OneToOne | VectorMatchCardinality::ManyToMany => {}
+        }
+        matching
+    }
+
+    fn sorted_labels<'a>(labels: impl IntoIterator<Item = &'a String>) -> Vec<String> {
+        let mut labels = labels.into_iter().cloned().collect::<Vec<_>>();
+        labels.sort_unstable();
+        labels
+    }
+}
+
+#[derive(Debug)]
+enum ScalarFunc {
+    DataFusionBuiltin(BuiltinScalarFunction),
+    /// Range function that takes the timestamp and value ranges, followed by literal
+    /// arguments.
+    Udf(ScalarUDF),
+    /// Range function that takes the timestamp and value ranges, the evaluation
+    /// timestamp and the range length.
+    ExtrapolateUdf(ScalarUDF),
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::UNIX_EPOCH;
+
+    use catalog::local::MemoryCatalogManager;
+    use catalog::{CatalogManager, RegisterTableRequest};
+    use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
+    use datatypes::prelude::ConcreteDataType;
+    use datatypes::schema::{ColumnSchema, Schema};
+    use promql_parser::label::Matcher;
+    use promql_parser::parser::ValueType;
+    use query::query_engine::QueryEngineState;
+    use query::DfContextProviderAdapter;
+    use session::context::QueryContext;
+    use table::metadata::{TableInfoBuilder, TableMetaBuilder};
+    use table::test_util::EmptyTable;
+
+    use super::*;
+    use crate::error::Error;
+
+    async fn build_test_context_provider(
+        table_name: String,
+        num_tag: usize,
+        num_field: usize,
+    ) -> DfContextProviderAdapter {
+        let mut columns = vec![];
+        for i in 0..num_tag {
+            columns.push(ColumnSchema::new(
+                format!("tag_{i}"),
+                ConcreteDataType::string_datatype(),
+                false,
+            ));
+        }
+        columns.push(
+            ColumnSchema::new(
+                "timestamp".to_string(),
+                ConcreteDataType::timestamp_millisecond_datatype(),
+                false,
+            )
+            .with_time_index(true),
+        );
+        for i in 0..num_field {
+            columns.push(ColumnSchema::new(
+                format!("field_{i}"),
+                ConcreteDataType::float64_datatype(),
+                true,
+            ));
+        }
+        let schema = Arc::new(Schema::new(columns));
+        let table_meta = TableMetaBuilder::default()
+            .schema(schema)
+            .primary_key_indices((0..num_tag).collect())
+            .value_indices((num_tag + 1..num_tag + 1 + num_field).collect())
+            .next_column_id(1024)
+            .build()
+            .unwrap();
+        let table_info = TableInfoBuilder::default()
+            .name(&table_name)
+            .meta(table_meta)
+            .build()
+            .unwrap();
+        let table = Arc::new(EmptyTable::from_table_info(&table_info));
+        let catalog_list = Arc::new(MemoryCatalogManager::default());
+        catalog_list
+            .register_table(RegisterTableRequest {
+                catalog: DEFAULT_CATALOG_NAME.to_string(),
+                schema: DEFAULT_SCHEMA_NAME.to_string(),
+                table_name,
+                table_id: 1024,
+                table,
+            })
+            .await
+            .unwrap();
+
+        let query_engine_state = QueryEngineState::new(catalog_list);
+        let query_context = QueryContext::new();
+        DfContextProviderAdapter::new(query_engine_state, query_context.into())
+    }
+
+    // {
+    //     input: `abs(some_metric{foo!="bar"})`,
+    //     expected: &Call{
+    //         Func: MustGetFunction("abs"),
+    //         Args: Expressions{
+    //             &VectorSelector{
+    //                 Name: "some_metric",
+    //                 LabelMatchers: []*labels.Matcher{
+    //                     MustLabelMatcher(labels.MatchNotEqual, "foo", "bar"),
+    //                     MustLabelMatcher(labels.MatchEqual, model.MetricNameLabel, "some_metric"),
+    //                 },
+    //             },
+    //         },
+    //     },
+    // },
+    async fn do_single_instant_function_call(fn_name: &'static str, plan_name: &str) {
+        let prom_expr = PromExpr::Call(Call {
+            func: Function {
+                name: fn_name,
+                arg_types: vec![ValueType::Vector],
+                variadic: false,
+                return_type: ValueType::Vector,
+            },
+            args: vec![Box::new(PromExpr::VectorSelector(VectorSelector {
+                name: Some("some_metric".to_owned()),
+                offset: None,
+                start_or_end: None,
+                label_matchers: Matchers {
+                    matchers: vec![
+                        Matcher {
+                            op: MatchOp::NotEqual,
+                            name: "tag_0".to_string(),
+                            value: "bar".to_string(),
+                        },
+                        Matcher {
+                            op: MatchOp::Equal,
+                            name: METRIC_NAME.to_string(),
+                            value: "some_metric".to_string(),
+                        },
+                    ],
+                },
+            }))],
+        });
+        let eval_stmt = EvalStmt {
+            expr: prom_expr,
+            start: UNIX_EPOCH,
+            end: UNIX_EPOCH
+                .checked_add(Duration::from_secs(100_000))
+                .unwrap(),
+            interval: Duration::from_secs(5),
+            lookback_delta: Duration::from_secs(1),
+        };
+
+        let context_provider = build_test_context_provider("some_metric".to_string(), 1, 1).await;
+        let plan = PromPlanner::stmt_to_plan(eval_stmt, context_provider).unwrap();
+
+        let  expected = String::from(
+            "Filter: TEMPLATE(some_metric.field_0) IS NOT NULL [tag_0:Utf8, timestamp:Timestamp(Millisecond, None), TEMPLATE(some_metric.field_0):Float64;N]\
+            \n  Projection: some_metric.tag_0, some_metric.timestamp, TEMPLATE(some_metric.field_0) [tag_0:Utf8, timestamp:Timestamp(Millisecond, None), TEMPLATE(some_metric.field_0):Float64;N]\
+            \n    PromInstantManipulate: range=[0..100000000], lookback=[1000], interval=[5000], time index=[timestamp] [tag_0:Utf8, timestamp:Timestamp(Millisecond, None), field_0:Float64;N]\
+            \n      PromSeriesNormalize: offset=[0], time index=[timestamp] [tag_0:Utf8, timestamp:Timestamp(Millisecond, None), field_0:Float64;N]\
+            \n        Filter: tag_0 != Utf8(\"bar\") AND timestamp >= TimestampMillisecond(-1000, None) AND timestamp <= TimestampMillisecond(100000000, None) [tag_0:Utf8, timestamp:Timestamp(Millisecond, None), field_0:Float64;N]\
+            \n          TableScan: some_metric, unsupported_filters=[tag_0 != Utf8(\"bar\"), timestamp >= TimestampMillisecond(-1000, None), timestamp <= TimestampMillisecond(100000000, None)] [tag_0:Utf8, timestamp:Timestamp(Millisecond, None), field_0:Float64;N]",
+        ).replace("TEMPLATE", plan_name);
+
+        assert_eq!(plan.display_indent_schema().to_string(), expected);
+    }
+
+    #[tokio::test]
+    async fn single_abs() {
         do_single_instant_function_call("abs", "abs").await;
     }
 
     #[tokio::test]
     #[should_panic]
-    async fn single_absent() {
-        do_single_instant_function_call("absent", "").await;
+    async fn single_absent() {
+        do_single_instant_function_call("absent", "").await;
+    }
+
+    #[tokio::test]
+    async fn single_ceil() {
+        do_single_instant_function_call("ceil", "ceil").await;
+    }
+
+    #[tokio::test]
+    async fn single_exp() {
+        do_single_instant_function_call("exp", "exp").await;
+    }
+
+    #[tokio::test]
+    async fn single_ln() {
+        do_single_instant_function_call("ln", "ln").await;
+    }
+
+    #[tokio::test]
+    async fn single_log2() {
+        do_single_instant_function_call("log2", "log2").await;
+    }
+
+    #[tokio::test]
+    async fn single_log10() {
+        do_single_instant_function_call("log10", "log10").await;
+    }
+
+    #[tokio::test]
+    #[should_panic]
+    async fn single_scalar() {
+        do_single_instant_function_call("scalar", "").await;
+    }
+
+    #[tokio::test]
+    #[should_panic]
+    async fn single_sgn() {
+        do_single_instant_function_call("sgn", "").await;
+    }
+
+    #[tokio::test]
+    #[should_panic]
+    async fn single_sort() {
+        do_single_instant_function_call("sort", "").await;
+    }
+
+    #[tokio::test]
+    #[should_panic]
+    async fn single_sort_desc() {
+        do_single_instant_function_call("sort_desc", "").await;
+    }
+
+    #[tokio::test]
+    async fn single_sqrt() {
+        do_single_instant_function_call("sqrt", "sqrt").await;
+    }
+
+    #[tokio::test]
+    #[should_panic]
+    async fn single_timestamp() {
+        do_single_instant_function_call("timestamp", "").await;
+    }
+
+    #[tokio::test]
+    async fn single_acos() {
+        do_single_instant_function_call("acos", "acos").await;
+    }
+
+    #[tokio::test]
+    #[should_panic]
+    async fn single_acosh() {
+        do_single_instant_function_call("acosh", "").await;
+    }
+
+    #[tokio::test]
+    async fn single_asin() {
+        do_single_instant_function_call("asin", "asin").await;
+    }
+
+    #[tokio::test]
+    #[should_panic]
+    async fn single_asinh() {
+        do_single_instant_function_call("asinh", "").await;
+    }
+
+    #[tokio::test]
+    async fn single_atan() {
+        do_single_instant_function_call("atan", "atan").await;
+    }
+
+    #[tokio::test]
+    #[should_panic]
+    async fn single_atanh() {
+        do_single_instant_function_call("atanh", "").await;
+    }
+
+    #[tokio::test]
+    async fn single_cos() {
+        do_single_instant_function_call("cos", "cos").await;
+    }
+
+    #[tokio::test]
+    #[should_panic]
+    async fn single_cosh() {
+        do_single_instant_function_call("cosh", "").await;
+    }
+
+    #[tokio::test]
+    async fn single_sin() {
+        do_single_instant_function_call("sin", "sin").await;
+    }
+
+    #[tokio::test]
+    #[should_panic]
+    async fn single_sinh() {
+        do_single_instant_function_call("sinh", "").await;
     }
 
     #[tokio::test]
-    async fn single_ceil() {
-        do_single_instant_function_call("ceil", "ceil").await;
+    async fn single_tan() {
+        do_single_instant_function_call("tan", "tan").await;
     }
 
     #[tokio::test]
-    async fn single_exp() {
-        do_single_instant_function_call("exp", "exp").await;
+    #[should_panic]
+    async fn single_tanh() {
+        do_single_instant_function_call("tanh", "").await;
     }
 
     #[tokio::test]
-    async fn single_ln() {
-        do_single_instant_function_call("ln", "ln").await;
+    #[should_panic]
+    async fn single_deg() {
+        do_single_instant_function_call("deg", "").await;
     }
 
     #[tokio::test]
-    async fn single_log2() {
-        do_single_instant_function_call("log2", "log2").await;
+    #[should_panic]
+    async fn single_rad() {
+        do_single_instant_function_call("rad", "").await;
+    }
+
+    // {
+    //     input: "avg by (foo)(some_metric)",
+    //     expected: &AggregateExpr{
+    //         Op: AVG,
+    //         Expr: &VectorSelector{
+    //             Name: "some_metric",
+    //             LabelMatchers: []*labels.Matcher{
+    //                 MustLabelMatcher(labels.MatchEqual, model.MetricNameLabel, "some_metric"),
+    //             },
+    //             PosRange: PositionRange{
+    //                 Start: 13,
+    //                 End:   24,
+    //             },
+    //         },
+    //         Grouping: []string{"foo"},
+    //         PosRange: PositionRange{
+    //             Start: 0,
+    //             End:   25,
+    //         },
+    //     },
+    // },
+    async fn do_aggregate_expr_plan(op: TokenType, name: &str) {
+        let prom_expr = PromExpr::Aggregate(AggregateExpr {
+            op,
+            expr: Box::new(PromExpr::VectorSelector(VectorSelector {
+                name: Some("some_metric".to_owned()),
+                offset: None,
+                start_or_end: None,
+                label_matchers: Matchers {
+                    matchers: vec![
+                        Matcher {
+                            op: MatchOp::NotEqual,
+                            name: "tag_0".to_string(),
+                            value: "bar".to_string(),
+                        },
+                        Matcher {
+                            op: MatchOp::Equal,
+                            name: METRIC_NAME.to_string(),
+                            value: "some_metric".to_string(),
+                        },
+                    ],
+                },
+            })),
+            param: Box::new(PromExpr::empty_vector_selector()),
+            grouping: vec![String::from("tag_1")],
+            without: false,
+        });
+        let mut eval_stmt = EvalStmt {
+            expr: prom_expr,
+            start: UNIX_EPOCH,
+            end: UNIX_EPOCH
+                .checked_add(Duration::from_secs(100_000))
+                .unwrap(),
+            interval: Duration::from_secs(5),
+            lookback_delta: Duration::from_secs(1),
+        };
+
+        // test group by
+        let context_provider = build_test_context_provider("some_metric".to_string(), 2, 2).await;
+        let plan = PromPlanner::stmt_to_plan(eval_stmt.clone(), context_provider).unwrap();
+        let  expected_no_without = String::from(
+            "Aggregate: groupBy=[[some_metric.tag_1, some_metric.timestamp]], aggr=[[TEMPLATE(some_metric.field_0), TEMPLATE(some_metric.field_1)]] [tag_1:Utf8, timestamp:Timestamp(Millisecond, None), TEMPLATE(some_metric.field_0):Float64;N, TEMPLATE(some_metric.field_1):Float64;N]\
+            \n  PromInstantManipulate: range=[0..100000000], lookback=[1000], interval=[5000], time index=[timestamp] [tag_0:Utf8, tag_1:Utf8, timestamp:Timestamp(Millisecond, None), field_0:Float64;N, field_1:Float64;N]\
+            \n    PromSeriesNormalize: offset=[0], time index=[timestamp] [tag_0:Utf8, tag_1:Utf8, timestamp:Timestamp(Millisecond, None), field_0:Float64;N, field_1:Float64;N]\
+            \n      Filter: tag_0 != Utf8(\"bar\") AND timestamp >= TimestampMillisecond(-1000, None) AND timestamp <= TimestampMillisecond(100000000, None) [tag_0:Utf8, tag_1:Utf8, timestamp:Timestamp(Millisecond, None), field_0:Float64;N, field_1:Float64;N]\
+            \n        TableScan: some_metric, unsupported_filters=[tag_0 != Utf8(\"bar\"), timestamp >= TimestampMillisecond(-1000, None), timestamp <= TimestampMillisecond(100000000, None)] [tag_0:Utf8, tag_1:Utf8, timestamp:Timestamp(Millisecond, None), field_0:Float64;N, field_1:Float64;N]")
+            .replace("TEMPLATE", name);
+        assert_eq!(
+            plan.display_indent_schema().to_string(),
+            expected_no_without
+        );
+
+        // test group without
+        if let PromExpr::Aggregate(AggregateExpr { without, .. }) = &mut eval_stmt.expr {
+            *without = true;
+        }
+        let context_provider = build_test_context_provider("some_metric".to_string(), 2, 2).await;
+        let plan = PromPlanner::stmt_to_plan(eval_stmt, context_provider).unwrap();
+        let  expected_without = String::from(
+            "Aggregate: groupBy=[[some_metric.tag_0, some_metric.timestamp]], aggr=[[TEMPLATE(some_metric.field_0), TEMPLATE(some_metric.field_1)]] [tag_0:Utf8, timestamp:Timestamp(Millisecond, None), TEMPLATE(some_metric.field_0):Float64;N, TEMPLATE(some_metric.field_1):Float64;N]\
+            \n  PromInstantManipulate: range=[0..100000000], lookback=[1000], interval=[5000], time index=[timestamp] [tag_0:Utf8, tag_1:Utf8, timestamp:Timestamp(Millisecond, None), field_0:Float64;N, field_1:Float64;N]\
+            \n    PromSeriesNormalize: offset=[0], time index=[timestamp] [tag_0:Utf8, tag_1:Utf8, timestamp:Timestamp(Millisecond, None), field_0:Float64;N, field_1:Float64;N]\
+            \n      Filter: tag_0 != Utf8(\"bar\") AND timestamp >= TimestampMillisecond(-1000, None) AND timestamp <= TimestampMillisecond(100000000, None) [tag_0:Utf8, tag_1:Utf8, timestamp:Timestamp(Millisecond, None), field_0:Float64;N, field_1:Float64;N]\
+            \n        TableScan: some_metric, unsupported_filters=[tag_0 != Utf8(\"bar\"), timestamp >= TimestampMillisecond(-1000, None), timestamp <= TimestampMillisecond(100000000, None)] [tag_0:Utf8, tag_1:Utf8, timestamp:Timestamp(Millisecond, None), field_0:Float64;N, field_1:Float64;N]")
+            .replace("TEMPLATE", name);
+        assert_eq!(plan.display_indent_schema().to_string(), expected_without);
     }
 
     #[tokio::test]
-    async fn single_log10() {
-        do_single_instant_function_call("log10", "log10").await;
+    async fn aggregate_sum() {
+        do_aggregate_expr_plan(token::T_SUM, "SUM").await;
     }
 
     #[tokio::test]
-    #[should_panic]
-    async fn single_scalar() {
-        do_single_instant_function_call("scalar", "").await;
+    async fn aggregate_avg() {
+        do_aggregate_expr_plan(token::T_AVG, "AVG").await;
+    }
+
+    #[tokio::test]
+    #[should_panic] // output type doesn't match
+    async fn aggregate_count() {
+        do_aggregate_expr_plan(token::T_COUNT, "COUNT").await;
+    }
+
+    #[tokio::test]
+    async fn aggregate_min() {
+        do_aggregate_expr_plan(token::T_MIN, "MIN").await;
+    }
+
+    #[tokio::test]
+    async fn aggregate_max() {
+        do_aggregate_expr_plan(token::T_MAX, "MAX").await;
+    }
+
+    #[tokio::test]
+    #[should_panic] // output type doesn't match
+    async fn aggregate_group() {
+        do_aggregate_expr_plan(token::T_GROUP, "GROUPING").await;
+    }
+
+    #[tokio::test]
+    async fn aggregate_stddev() {
+        do_aggregate_expr_plan(token::T_STDDEV, "STDDEV").await;
+    }
+
+    #[tokio::test]
+    #[should_panic] // schema doesn't match
+    async fn aggregate_stdvar() {
+        do_aggregate_expr_plan(token::T_STDVAR, "STDVAR").await;
+    }
+
+    /// Plan `op by (tag_1) (param, some_metric)`.
+    async fn param_aggregate_expr_plan(op: TokenType, param: PromExpr) -> Result<LogicalPlan> {
+        let prom_expr = PromExpr::Aggregate(AggregateExpr {
+            op,
+            expr: Box::new(PromExpr::VectorSelector(VectorSelector {
+                name: Some("some_metric".to_owned()),
+                offset: None,
+                start_or_end: None,
+                label_matchers: Matchers {
+                    matchers: vec![Matcher {
+                        op: MatchOp::Equal,
+                        name: METRIC_NAME.to_string(),
+                        value: "some_metric".to_string(),
+                    }],
+                },
+            })),
+            param: Box::new(param),
+            grouping: vec![String::from("tag_1")],
+            without: false,
+        });
+        let eval_stmt = EvalStmt {
+            expr: prom_expr,
+            start: UNIX_EPOCH,
+            end: UNIX_EPOCH
+                .checked_add(Duration::from_secs(100_000))
+                .unwrap(),
+            interval: Duration::from_secs(5),
+            lookback_delta: Duration::from_secs(1),
+        };
+
+        let context_provider = build_test_context_provider("some_metric".to_string(), 2, 1).await;
+        PromPlanner::stmt_to_plan(eval_stmt, context_provider)
+    }
+
+    /// Plan `op by (tag_1) (param, some_metric)` and returns the plan and its output
+    /// column names.
+    async fn do_param_aggregate_expr_plan(op: TokenType, param: PromExpr) -> (String, Vec<String>) {
+        let plan = param_aggregate_expr_plan(op, param).await.unwrap();
+        let columns = plan
+            .schema()
+            .fields()
+            .iter()
+            .map(|field| field.name().clone())
+            .collect();
+        (plan.display_indent().to_string(), columns)
+    }
+
+    #[tokio::test]
+    async fn aggregate_top_k() {
+        let (plan, columns) = do_param_aggregate_expr_plan(
+            token::T_TOPK,
+            PromExpr::NumberLiteral(NumberLiteral { val: 5.0 }),
+        )
+        .await;
+        assert!(
+            plan.starts_with(
+                "Projection: some_metric.tag_0, some_metric.tag_1, some_metric.timestamp, some_metric.field_0\
+                \n  Filter: prom_rank <= Float64(5)\
+                \n    WindowAggr: windowExpr=[[ROW_NUMBER() PARTITION BY [some_metric.tag_1, some_metric.timestamp] ORDER BY [some_metric.field_0 DESC NULLS LAST] AS prom_rank]]"
+            ),
+            "{plan}"
+        );
+        assert_eq!(columns, vec!["tag_0", "tag_1", "timestamp", "field_0"]);
+    }
+
+    #[tokio::test]
+    async fn aggregate_bottom_k() {
+        let (plan, columns) = do_param_aggregate_expr_plan(
+            token::T_BOTTOMK,
+            PromExpr::NumberLiteral(NumberLiteral { val: 5.0 }),
+        )
+        .await;
+        assert!(
+            plan.contains("ORDER BY [some_metric.field_0 ASC NULLS LAST] AS prom_rank"),
+            "{plan}"
+        );
+        assert_eq!(columns, vec!["tag_0", "tag_1", "timestamp", "field_0"]);
+    }
+
+    #[tokio::test]
+    async fn aggregate_count_values() {
+        let (plan, columns) = do_param_aggregate_expr_plan(
+            token::T_COUNT_VALUES,
+            PromExpr::StringLiteral(StringLiteral {
+                val: "value".to_string(),
+            }),
+        )
+        .await;
+        assert!(
+            plan.contains("Aggregate: groupBy=[[some_metric.tag_1, some_metric.timestamp, some_metric.field_0]], aggr=[[COUNT(some_metric.field_0)]]"),
+            "{plan}"
+        );
+        assert_eq!(columns, vec!["tag_1", "value", "timestamp", "field_0"]);
+    }
+
+    #[tokio::test]
+    async fn aggregate_quantile() {
+        let (plan, columns) = do_param_aggregate_expr_plan(
+            token::T_QUANTILE,
+            PromExpr::NumberLiteral(NumberLiteral { val: 0.9 }),
+        )
+        .await;
+        assert!(
+            plan.starts_with(
+                "Projection: some_metric.tag_1, some_metric.timestamp, prom_quantile(ARRAYAGG(some_metric.field_0), Float64(0.9)) AS field_0\
+                \n  Aggregate: groupBy=[[some_metric.tag_1, some_metric.timestamp]], aggr=[[ARRAYAGG(some_metric.field_0)]]"
+            ),
+            "{plan}"
+        );
+        assert_eq!(columns, vec!["tag_1", "timestamp", "field_0"]);
+    }
+
+    #[tokio::test]
+    async fn aggregate_top_k_expr_param() {
+        // topk by (tag_1) (2 + 3.5, some_metric)
+        let param = PromExpr::Binary(PromBinaryExpr {
+            op: token::T_ADD,
+            lhs: number_literal(2.0),
+            rhs: number_literal(3.5),
+            modifier: None,
+        });
+        let (plan, _) = do_param_aggregate_expr_plan(token::T_TOPK, param).await;
+        assert!(plan.contains("Filter: prom_rank <= Float64(5)"), "{plan}");
+    }
+
+    #[tokio::test]
+    async fn aggregate_top_k_vector_param() {
+        let result = param_aggregate_expr_plan(token::T_TOPK, *some_metric_selector()).await;
+        assert!(
+            matches!(result, Err(Error::ExpectLiteralParam { .. })),
+            "{result:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn aggregate_top_k_nan_param() {
+        let result = param_aggregate_expr_plan(token::T_TOPK, *number_literal(f64::NAN)).await;
+        assert!(
+            matches!(result, Err(Error::InvalidParam { .. })),
+            "{result:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn aggregate_count_values_number_param() {
+        let result = param_aggregate_expr_plan(token::T_COUNT_VALUES, *number_literal(1.0)).await;
+        assert!(
+            matches!(result, Err(Error::ExpectLiteralParam { .. })),
+            "{result:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn aggregate_quantile_out_of_range_param() {
+        // Like Prometheus, φ out of [0, 1] results in -Inf or +Inf instead of an error.
+        for quantile in [f64::NAN, -0.1, 1.5] {
+            let result =
+                param_aggregate_expr_plan(token::T_QUANTILE, *number_literal(quantile)).await;
+            assert!(result.is_ok(), "{result:?}");
+        }
     }
 
-    #[tokio::test]
-    #[should_panic]
-    async fn single_sgn() {
-        do_single_instant_function_call("sgn", "").await;
+    // {
+    //     input: `rate(some_metric[5m])`,
+    //     expected: &Call{
+    //         Func: MustGetFunction("rate"),
+    //         Args: Expressions{
+    //             &MatrixSelector{
+    //                 VectorSelector: &VectorSelector{
+    //                     Name: "some_metric",
+    //                     LabelMatchers: []*labels.Matcher{
+    //                         MustLabelMatcher(labels.MatchEqual, model.MetricNameLabel, "some_metric"),
+    //                     },
+    //                 },
+    //                 Range: 5 * time.Minute,
+    //             },
+    //         },
+    //     },
+    // },
+    async fn do_single_range_function_call(fn_name: &'static str, expr_display: &str) {
+        let prom_expr = PromExpr::Call(Call {
+            func: Function {
+                name: fn_name,
+                arg_types: vec![ValueType::Matrix],
+                variadic: false,
+                return_type: ValueType::Vector,
+            },
+            args: vec![Box::new(PromExpr::MatrixSelector(MatrixSelector {
+                vector_selector: Box::new(PromExpr::VectorSelector(VectorSelector {
+                    name: Some("some_metric".to_owned()),
+                    offset: None,
+                    start_or_end: None,
+                    label_matchers: Matchers {
+                        matchers: vec![Matcher {
+                            op: MatchOp::Equal,
+                            name: METRIC_NAME.to_string(),
+                            value: "some_metric".to_string(),
+                        }],
+                    },
+                })),
+                range: Duration::from_secs(300),
+            }))],
+        });
+        let eval_stmt = EvalStmt {
+            expr: prom_expr,
+            start: UNIX_EPOCH,
+            end: UNIX_EPOCH
+                .checked_add(Duration::from_secs(100_000))
+                .unwrap(),
+            interval: Duration::from_secs(5),
+            lookback_delta: Duration::from_secs(1),
+        };
+
+        let context_provider = build_test_context_provider("some_metric".to_string(), 1, 1).await;
+        let plan = PromPlanner::stmt_to_plan(eval_stmt, context_provider).unwrap();
+
+        // column name doesn't contain the space after comma
+        let expr_name = expr_display.replace(", ", ",");
+        let expected = format!(
+            "Filter: {expr_name} IS NOT NULL [tag_0:Utf8, timestamp:Timestamp(Millisecond, None), {expr_name}:Float64;N]\
+            \n  Projection: some_metric.tag_0, some_metric.timestamp, {expr_display} [tag_0:Utf8, timestamp:Timestamp(Millisecond, None), {expr_name}:Float64;N]\
+            \n    PromRangeManipulate: req range=[0..100000000], interval=[5000], eval range=[300000], time index=[timestamp], values=[\"field_0\"] [tag_0:Utf8, timestamp:Timestamp(Millisecond, None), field_0:Dictionary(Int64, Float64);N, timestamp_range:Dictionary(Int64, Timestamp(Millisecond, None))]\
+            \n      PromSeriesNormalize: offset=[0], time index=[timestamp] [tag_0:Utf8, timestamp:Timestamp(Millisecond, None), field_0:Float64;N]\
+            \n        Filter: timestamp >= TimestampMillisecond(-300000, None) AND timestamp <= TimestampMillisecond(100000000, None) [tag_0:Utf8, timestamp:Timestamp(Millisecond, None), field_0:Float64;N]\
+            \n          TableScan: some_metric, unsupported_filters=[timestamp >= TimestampMillisecond(-300000, None), timestamp <= TimestampMillisecond(100000000, None)] [tag_0:Utf8, timestamp:Timestamp(Millisecond, None), field_0:Float64;N]"
+        );
+
+        assert_eq!(plan.display_indent_schema().to_string(), expected);
     }
 
     #[tokio::test]
-    #[should_panic]
-    async fn single_sort() {
-        do_single_instant_function_call("sort", "").await;
+    async fn single_rate() {
+        do_single_range_function_call(
+            "rate",
+            "prom_rate(some_metric.timestamp_range, some_metric.field_0, some_metric.timestamp, Int64(300000))",
+        )
+        .await;
     }
 
     #[tokio::test]
-    #[should_panic]
-    async fn single_sort_desc() {
-        do_single_instant_function_call("sort_desc", "").await;
+    async fn single_increase() {
+        do_single_range_function_call(
+            "increase",
+            "prom_increase(some_metric.timestamp_range, some_metric.field_0, some_metric.timestamp, Int64(300000))",
+        )
+        .await;
     }
 
     #[tokio::test]
-    async fn single_sqrt() {
-        do_single_instant_function_call("sqrt", "sqrt").await;
+    async fn single_delta() {
+        do_single_range_function_call(
+            "delta",
+            "prom_delta(some_metric.timestamp_range, some_metric.field_0, some_metric.timestamp, Int64(300000))",
+        )
+        .await;
     }
 
     #[tokio::test]
-    #[should_panic]
-    async fn single_timestamp() {
-        do_single_instant_function_call("timestamp", "").await;
+    async fn single_irate() {
+        do_single_range_function_call(
+            "irate",
+            "prom_irate(some_metric.timestamp_range, some_metric.field_0)",
+        )
+        .await;
     }
 
     #[tokio::test]
-    async fn single_acos() {
-        do_single_instant_function_call("acos", "acos").await;
+    async fn single_idelta() {
+        do_single_range_function_call(
+            "idelta",
+            "prom_idelta(some_metric.timestamp_range, some_metric.field_0)",
+        )
+        .await;
     }
 
     #[tokio::test]
-    #[should_panic]
-    async fn single_acosh() {
-        do_single_instant_function_call("acosh", "").await;
+    async fn single_changes() {
+        do_single_range_function_call(
+            "changes",
+            "prom_changes(some_metric.timestamp_range, some_metric.field_0)",
+        )
+        .await;
     }
 
     #[tokio::test]
-    async fn single_asin() {
-        do_single_instant_function_call("asin", "asin").await;
+    async fn single_resets() {
+        do_single_range_function_call(
+            "resets",
+            "prom_resets(some_metric.timestamp_range, some_metric.field_0)",
+        )
+        .await;
     }
 
     #[tokio::test]
-    #[should_panic]
-    async fn single_asinh() {
-        do_single_instant_function_call("asinh", "").await;
+    async fn single_avg_over_time() {
+        do_single_range_function_call(
+            "avg_over_time",
+            "prom_avg_over_time(some_metric.timestamp_range, some_metric.field_0)",
+        )
+        .await;
     }
 
     #[tokio::test]
-    async fn single_atan() {
-        do_single_instant_function_call("atan", "atan").await;
+    async fn single_last_over_time() {
+        do_single_range_function_call(
+            "last_over_time",
+            "prom_last_over_time(some_metric.timestamp_range, some_metric.field_0)",
+        )
+        .await;
     }
 
-    #[tokio::test]
-    #[should_panic]
-    async fn single_atanh() {
-        do_single_instant_function_call("atanh", "").await;
+    fn some_metric_selector() -> Box<PromExpr> {
+        Box::new(PromExpr::VectorSelector(VectorSelector {
+            name: Some("some_metric".to_owned()),
+            offset: None,
+            start_or_end: None,
+            label_matchers: Matchers {
+                matchers: vec![Matcher {
+                    op: MatchOp::Equal,
+                    name: METRIC_NAME.to_string(),
+                    value: "some_metric".to_string(),
+                }],
+            },
+        }))
     }
 
-    #[tokio::test]
-    async fn single_cos() {
-        do_single_instant_function_call("cos", "cos").await;
+    fn number_literal(val: f64) -> Box<PromExpr> {
+        Box::new(PromExpr::NumberLiteral(NumberLiteral { val }))
     }
 
-    #[tokio::test]
-    #[should_panic]
-    async fn single_cosh() {
-        do_single_instant_function_call("cosh", "").await;
+    /// Plan `lhs op rhs` and returns the plan and its output column names.
+    async fn do_binary_expr_plan(
+        op: TokenType,
+        lhs: Box<PromExpr>,
+        rhs: Box<PromExpr>,
+    ) -> (String, Vec<String>) {
+        let eval_stmt = EvalStmt {
+            expr: PromExpr::Binary(PromBinaryExpr {
+                op,
+                lhs,
+                rhs,
+                modifier: None,
+            }),
+            start: UNIX_EPOCH,
+            end: UNIX_EPOCH
+                .checked_add(Duration::from_secs(100_000))
+                .unwrap(),
+            interval: Duration::from_secs(5),
+            lookback_delta: Duration::from_secs(1),
+        };
+
+        let context_provider = build_test_context_provider("some_metric".to_string(), 1, 1).await;
+        let plan = PromPlanner::stmt_to_plan(eval_stmt, context_provider).unwrap();
+        let columns = plan
+            .schema()
+            .fields()
+            .iter()
+            .map(|field| field.name().clone())
+            .collect();
+        (plan.display_indent().to_string(), columns)
     }
 
     #[tokio::test]
-    async fn single_sin() {
-        do_single_instant_function_call("sin", "sin").await;
+    async fn binary_vector_literal_arithmetic() {
+        let (plan, columns) =
+            do_binary_expr_plan(token::T_MUL, some_metric_selector(), number_literal(2.0)).await;
+        assert!(plan.starts_with(
+            "Projection: some_metric.tag_0, some_metric.timestamp, some_metric.field_0 * Float64(2) AS field_0"
+        ), "{plan}");
+        assert_eq!(columns, vec!["tag_0", "timestamp", "field_0"]);
+
+        let (plan, _) =
+            do_binary_expr_plan(token::T_SUB, number_literal(2.0), some_metric_selector()).await;
+        assert!(
+            plan.contains("Float64(2) - some_metric.field_0 AS field_0"),
+            "{plan}"
+        );
     }
 
     #[tokio::test]
-    #[should_panic]
-    async fn single_sinh() {
-        do_single_instant_function_call("sinh", "").await;
+    async fn binary_vector_literal_comparison() {
+        let (plan, columns) =
+            do_binary_expr_plan(token::T_GTR, some_metric_selector(), number_literal(2.0)).await;
+        assert!(
+            plan.starts_with("Filter: some_metric.field_0 > Float64(2)"),
+            "{plan}"
+        );
+        assert_eq!(columns, vec!["tag_0", "timestamp", "field_0"]);
     }
 
     #[tokio::test]
-    async fn single_tan() {
-        do_single_instant_function_call("tan", "tan").await;
+    async fn binary_literals() {
+        let (plan, columns) =
+            do_binary_expr_plan(token::T_ADD, number_literal(1.0), number_literal(2.0)).await;
+        assert!(
+            plan.starts_with("Projection: column1 AS time, Float64(1) + Float64(2) AS value"),
+            "{plan}"
+        );
+        assert_eq!(columns, vec!["time", "value"]);
     }
 
     #[tokio::test]
-    #[should_panic]
-    async fn single_tanh() {
-        do_single_instant_function_call("tanh", "").await;
+    async fn binary_vectors_arithmetic() {
+        let (plan, columns) =
+            do_binary_expr_plan(token::T_DIV, some_metric_selector(), some_metric_selector()).await;
+        assert!(
+            plan.starts_with(
+                "Projection: lhs.tag_0, lhs.timestamp, lhs.field_0 / rhs.field_0 AS field_0\
+            \n  Inner Join: lhs.prom_match_key_tag_0 = rhs.prom_match_key_tag_0, lhs.timestamp = rhs.timestamp\
+            \n    SubqueryAlias: lhs"
+            ),
+            "{plan}"
+        );
+        assert_eq!(columns, vec!["tag_0", "timestamp", "field_0"]);
     }
 
     #[tokio::test]
-    #[should_panic]
-    async fn single_deg() {
-        do_single_instant_function_call("deg", "").await;
+    async fn binary_vectors_comparison() {
+        let (plan, columns) =
+            do_binary_expr_plan(token::T_LTE, some_metric_selector(), some_metric_selector()).await;
+        assert!(
+            plan.starts_with(
+                "Projection: lhs.tag_0, lhs.timestamp, lhs.field_0 AS field_0\
+            \n  Filter: lhs.field_0 <= rhs.field_0\
+            \n    Inner Join: lhs.prom_match_key_tag_0 = rhs.prom_match_key_tag_0, lhs.timestamp = rhs.timestamp"
+            ),
+            "{plan}"
+        );
+        assert_eq!(columns, vec!["tag_0", "timestamp", "field_0"]);
     }
 
     #[tokio::test]
-    #[should_panic]
-    async fn single_rad() {
-        do_single_instant_function_call("rad", "").await;
+    async fn binary_vectors_set_operators() {
+        let (plan, columns) = do_binary_expr_plan(
+            token::T_LAND,
+            some_metric_selector(),
+            some_metric_selector(),
+        )
+        .await;
+        assert!(
+            plan.starts_with(
+                "Projection: lhs.tag_0, lhs.timestamp, lhs.field_0\
+            \n  LeftSemi Join: lhs.prom_match_key_tag_0 = rhs.prom_match_key_tag_0, lhs.timestamp = rhs.timestamp"
+            ),
+            "{plan}"
+        );
+        assert_eq!(columns, vec!["tag_0", "timestamp", "field_0"]);
+
+        let (plan, _) = do_binary_expr_plan(
+            token::T_LUNLESS,
+            some_metric_selector(),
+            some_metric_selector(),
+        )
+        .await;
+        assert!(
+            plan.starts_with(
+                "Projection: lhs.tag_0, lhs.timestamp, lhs.field_0\
+            \n  LeftAnti Join: lhs.prom_match_key_tag_0 = rhs.prom_match_key_tag_0, lhs.timestamp = rhs.timestamp"
+            ),
+            "{plan}"
+        );
+
+        let (plan, columns) =
+            do_binary_expr_plan(token::T_LOR, some_metric_selector(), some_metric_selector()).await;
+        assert!(plan.starts_with("Union"), "{plan}");
+        assert!(
+            plan.contains(
+                "LeftAnti Join: rhs.prom_match_key_tag_0 = lhs.prom_match_key_tag_0, rhs.timestamp = lhs.timestamp"
+            ),
+            "{plan}"
+        );
+        assert_eq!(columns, vec!["tag_0", "timestamp", "field_0"]);
     }
 
-    // {
-    //     input: "avg by (foo)(some_metric)",
-    //     expected: &AggregateExpr{
-    //         Op: AVG,
-    //         Expr: &VectorSelector{
-    //             Name: "some_metric",
-    //             LabelMatchers: []*labels.Matcher{
-    //                 MustLabelMatcher(labels.MatchEqual, model.MetricNameLabel, "some_metric"),
-    //             },
-    //             PosRange: PositionRange{
-    //                 Start: 13,
-    //                 End:   24,
-    //             },
-    //         },
-    //         Grouping: []string{"foo"},
-    //         PosRange: PositionRange{
-    //             Start: 0,
-    //             End:   25,
-    //         },
-    //     },
-    // },
-    async fn do_aggregate_expr_plan(op: TokenType, name: &str) {
-        let prom_expr = PromExpr::Aggregate(AggregateExpr {
-            op,
-            expr: Box::new(PromExpr::VectorSelector(VectorSelector {
-                name: Some("some_metric".to_owned()),
-                offset: None,
-                start_or_end: None,
-                label_matchers: Matchers {
-                    matchers: vec![
-                        Matcher {
-                            op: MatchOp::NotEqual,
-                            name: "tag_0".to_string(),
-                            value: "bar".to_string(),
-                        },
-                        Matcher {
-                            op: MatchOp::Equal,
-                            name: METRIC_NAME.to_string(),
-                            value: "some_metric".to_string(),
-                        },
-                    ],
-                },
-            })),
-            param: Box::new(PromExpr::empty_vector_selector()),
-            grouping: vec![String::from("tag_1")],
-            without: false,
+    #[tokio::test]
+    async fn at_modifier_end() {
+        let prom_expr = PromExpr::VectorSelector(VectorSelector {
+            name: Some("some_metric".to_owned()),
+            offset: None,
+            start_or_end: Some(token::T_END),
+            label_matchers: Matchers {
+                matchers: vec![Matcher {
+                    op: MatchOp::Equal,
+                    name: METRIC_NAME.to_string(),
+                    value: "some_metric".to_string(),
+                }],
+            },
         });
-        let mut eval_stmt = EvalStmt {
+        let eval_stmt = EvalStmt {
             expr: prom_expr,
             start: UNIX_EPOCH,
             end: UNIX_EPOCH
@@ -871,103 +2674,154 @@ mod test {
             lookback_delta: Duration::from_secs(1),
         };
 
-        // test group by
-        let context_provider = build_test_context_provider("some_metric".to_string(), 2, 2).await;
-        let plan = PromPlanner::stmt_to_plan(eval_stmt.clone(), context_provider).unwrap();
-        let  expected_no_without = String::from(
-            "Aggregate: groupBy=[[some_metric.tag_1]], aggr=[[TEMPLATE(some_metric.field_0), TEMPLATE(some_metric.field_1)]] [tag_1:Utf8, TEMPLATE(some_metric.field_0):Float64;N, TEMPLATE(some_metric.field_1):Float64;N]\
-            \n  PromInstantManipulate: range=[0..100000000], lookback=[1000], interval=[5000], time index=[timestamp] [tag_0:Utf8, tag_1:Utf8, timestamp:Timestamp(Millisecond, None), field_0:Float64;N, field_1:Float64;N]\
-            \n    PromSeriesNormalize: offset=[0], time index=[timestamp] [tag_0:Utf8, tag_1:Utf8, timestamp:Timestamp(Millisecond, None), field_0:Float64;N, field_1:Float64;N]\
-            \n      Filter: tag_0 != Utf8(\"bar\") AND timestamp >= TimestampMillisecond(0, None) AND timestamp <= TimestampMillisecond(100000000, None) [tag_0:Utf8, tag_1:Utf8, timestamp:Timestamp(Millisecond, None), field_0:Float64;N, field_1:Float64;N]\
-            \n        TableScan: some_metric, unsupported_filters=[tag_0 != Utf8(\"bar\"), timestamp >= TimestampMillisecond(0, None), timestamp <= TimestampMillisecond(100000000, None)] [tag_0:Utf8, tag_1:Utf8, timestamp:Timestamp(Millisecond, None), field_0:Float64;N, field_1:Float64;N]")
-            .replace("TEMPLATE", name);
-        assert_eq!(
-            plan.display_indent_schema().to_string(),
-            expected_no_without
-        );
-
-        // test group without
-        if let PromExpr::Aggregate(AggregateExpr { without, .. }) = &mut eval_stmt.expr {
-            *without = true;
-        }
-        let context_provider = build_test_context_provider("some_metric".to_string(), 2, 2).await;
+        let context_provider = build_test_context_provider("some_metric".to_string(), 1, 1).await;
         let plan = PromPlanner::stmt_to_plan(eval_stmt, context_provider).unwrap();
-        let  expected_without = String::from(
-            "Aggregate: groupBy=[[some_metric.tag_0]], aggr=[[TEMPLATE(some_metric.field_0), TEMPLATE(some_metric.field_1)]] [tag_0:Utf8, TEMPLATE(some_metric.field_0):Float64;N, TEMPLATE(some_metric.field_1):Float64;N]\
-            \n  PromInstantManipulate: range=[0..100000000], lookback=[1000], interval=[5000], time index=[timestamp] [tag_0:Utf8, tag_1:Utf8, timestamp:Timestamp(Millisecond, None), field_0:Float64;N, field_1:Float64;N]\
-            \n    PromSeriesNormalize: offset=[0], time index=[timestamp] [tag_0:Utf8, tag_1:Utf8, timestamp:Timestamp(Millisecond, None), field_0:Float64;N, field_1:Float64;N]\
-            \n      Filter: tag_0 != Utf8(\"bar\") AND timestamp >= TimestampMillisecond(0, None) AND timestamp <= TimestampMillisecond(100000000, None) [tag_0:Utf8, tag_1:Utf8, timestamp:Timestamp(Millisecond, None), field_0:Float64;N, field_1:Float64;N]\
-            \n        TableScan: some_metric, unsupported_filters=[tag_0 != Utf8(\"bar\"), timestamp >= TimestampMillisecond(0, None), timestamp <= TimestampMillisecond(100000000, None)] [tag_0:Utf8, tag_1:Utf8, timestamp:Timestamp(Millisecond, None), field_0:Float64;N, field_1:Float64;N]")
-            .replace("TEMPLATE", name);
-        assert_eq!(plan.display_indent_schema().to_string(), expected_without);
-    }
-
-    #[tokio::test]
-    async fn aggregate_sum() {
-        do_aggregate_expr_plan(token::T_SUM, "SUM").await;
-    }
-
-    #[tokio::test]
-    async fn aggregate_avg() {
-        do_aggregate_expr_plan(token::T_AVG, "AVG").await;
-    }
-
-    #[tokio::test]
-    #[should_panic] // output type doesn't match
-    async fn aggregate_count() {
-        do_aggregate_expr_plan(token::T_COUNT, "COUNT").await;
-    }
 
-    #[tokio::test]
-    async fn aggregate_min() {
-        do_aggregate_expr_plan(token::T_MIN, "MIN").await;
-    }
+        let expected = String::from(
+            "PromInstantManipulate: range=[0..100000000], lookback=[1000], interval=[5000], time index=[timestamp], at=[100000000] [tag_0:Utf8, timestamp:Timestamp(Millisecond, None), field_0:Float64;N]\
+            \n  PromSeriesNormalize: offset=[0], time index=[timestamp] [tag_0:Utf8, timestamp:Timestamp(Millisecond, None), field_0:Float64;N]\
+            \n    Filter: timestamp >= TimestampMillisecond(99999000, None) AND timestamp <= TimestampMillisecond(100000000, None) [tag_0:Utf8, timestamp:Timestamp(Millisecond, None), field_0:Float64;N]\
+            \n      TableScan: some_metric, unsupported_filters=[timestamp >= TimestampMillisecond(99999000, None), timestamp <= TimestampMillisecond(100000000, None)] [tag_0:Utf8, timestamp:Timestamp(Millisecond, None), field_0:Float64;N]",
+        );
 
-    #[tokio::test]
-    async fn aggregate_max() {
-        do_aggregate_expr_plan(token::T_MAX, "MAX").await;
+        assert_eq!(plan.display_indent_schema().to_string(), expected);
     }
 
+    // max_over_time(rate(some_metric[5m])[1h:1m])
     #[tokio::test]
-    #[should_panic] // output type doesn't match
-    async fn aggregate_group() {
-        do_aggregate_expr_plan(token::T_GROUP, "GROUPING").await;
-    }
+    async fn subquery() {
+        let rate = PromExpr::Call(Call {
+            func: Function {
+                name: "rate",
+                arg_types: vec![ValueType::Matrix],
+                variadic: false,
+                return_type: ValueType::Vector,
+            },
+            args: vec![Box::new(PromExpr::MatrixSelector(MatrixSelector {
+                vector_selector: some_metric_selector(),
+                range: Duration::from_secs(300),
+            }))],
+        });
+        let prom_expr = PromExpr::Call(Call {
+            func: Function {
+                name: "max_over_time",
+                arg_types: vec![ValueType::Matrix],
+                variadic: false,
+                return_type: ValueType::Vector,
+            },
+            args: vec![Box::new(PromExpr::Subquery(SubqueryExpr {
+                expr: Box::new(rate),
+                range: Duration::from_secs(3600),
+                offset: None,
+                start_or_end: None,
+                step: Duration::from_secs(60),
+            }))],
+        });
+        let eval_stmt = EvalStmt {
+            expr: prom_expr,
+            start: UNIX_EPOCH,
+            end: UNIX_EPOCH
+                .checked_add(Duration::from_secs(100_000))
+                .unwrap(),
+            interval: Duration::from_secs(5),
+            lookback_delta: Duration::from_secs(1),
+        };
 
-    #[tokio::test]
-    async fn aggregate_stddev() {
-        do_aggregate_expr_plan(token::T_STDDEV, "STDDEV").await;
+        let context_provider = build_test_context_provider("some_metric".to_string(), 1, 1).await;
+        let plan = PromPlanner::stmt_to_plan(eval_stmt, context_provider)
+            .unwrap()
+            .display_indent()
+            .to_string();
+
+        let expected = "PromRangeManipulate: req range=[0..100000000], interval=[5000], eval range=[3600000], time index=[timestamp], values=[\"prom_rate(some_metric.timestamp_range,some_metric.field_0,some_metric.timestamp,Int64(300000))\"]\
+            \n      PromSeriesNormalize: offset=[0], time index=[timestamp]\
+            \n        Filter: prom_rate(some_metric.timestamp_range,some_metric.field_0,some_metric.timestamp,Int64(300000)) IS NOT NULL\
+            \n          Projection: some_metric.tag_0, some_metric.timestamp, prom_rate(some_metric.timestamp_range, some_metric.field_0, some_metric.timestamp, Int64(300000))\
+            \n            PromRangeManipulate: req range=[-3600000..100000000], interval=[60000], eval range=[300000], time index=[timestamp], values=[\"field_0\"]\
+            \n              PromSeriesNormalize: offset=[0], time index=[timestamp]\
+            \n                Filter: timestamp >= TimestampMillisecond(-3900000, None) AND timestamp <= TimestampMillisecond(100000000, None)";
+        assert!(plan.contains(expected), "{plan}");
+        assert!(plan.starts_with("Filter: prom_max_over_time("), "{plan}");
     }
 
-    #[tokio::test]
-    #[should_panic] // schema doesn't match
-    async fn aggregate_stdvar() {
-        do_aggregate_expr_plan(token::T_STDVAR, "STDVAR").await;
+    fn rate_call(vector_selector: Box<PromExpr>, range: Duration) -> PromExpr {
+        PromExpr::Call(Call {
+            func: Function {
+                name: "rate",
+                arg_types: vec![ValueType::Matrix],
+                variadic: false,
+                return_type: ValueType::Vector,
+            },
+            args: vec![Box::new(PromExpr::MatrixSelector(MatrixSelector {
+                vector_selector,
+                range,
+            }))],
+        })
     }
 
-    #[tokio::test]
-    #[should_panic]
-    async fn aggregate_top_k() {
-        do_aggregate_expr_plan(token::T_TOPK, "").await;
-    }
+    async fn plan_from_0_to_100000s(prom_expr: PromExpr) -> String {
+        let eval_stmt = EvalStmt {
+            expr: prom_expr,
+            start: UNIX_EPOCH,
+            end: UNIX_EPOCH
+                .checked_add(Duration::from_secs(100_000))
+                .unwrap(),
+            interval: Duration::from_secs(5),
+            lookback_delta: Duration::from_secs(1),
+        };
 
-    #[tokio::test]
-    #[should_panic]
-    async fn aggregate_bottom_k() {
-        do_aggregate_expr_plan(token::T_BOTTOMK, "").await;
+        let context_provider = build_test_context_provider("some_metric".to_string(), 1, 1).await;
+        PromPlanner::stmt_to_plan(eval_stmt, context_provider)
+            .unwrap()
+            .display_indent()
+            .to_string()
     }
 
+    // rate(some_metric[5m] @ end())
     #[tokio::test]
-    #[should_panic]
-    async fn aggregate_count_values() {
-        do_aggregate_expr_plan(token::T_COUNT_VALUES, "").await;
+    async fn matrix_selector_at_modifier() {
+        let mut selector = some_metric_selector();
+        if let PromExpr::VectorSelector(selector) = selector.as_mut() {
+            selector.start_or_end = Some(token::T_END);
+        }
+        let plan = plan_from_0_to_100000s(rate_call(selector, Duration::from_secs(300))).await;
+
+        // the range of every step ends at `end`, so does the extrapolation of rate
+        let expected = "Projection: some_metric.tag_0, some_metric.timestamp, prom_rate(some_metric.timestamp_range, some_metric.field_0, TimestampMillisecond(100000000, None), Int64(300000))\
+            \n    PromRangeManipulate: req range=[0..100000000], interval=[5000], eval range=[300000], time index=[timestamp], values=[\"field_0\"], at=[100000000]\
+            \n      PromSeriesNormalize: offset=[0], time index=[timestamp]\
+            \n        Filter: timestamp >= TimestampMillisecond(99700000, None) AND timestamp <= TimestampMillisecond(100000000, None)";
+        assert!(plan.contains(expected), "{plan}");
     }
 
+    // max_over_time(rate(some_metric[5m])[1h:1m] @ end())
     #[tokio::test]
-    #[should_panic]
-    async fn aggregate_quantile() {
-        do_aggregate_expr_plan(token::T_QUANTILE, "").await;
+    async fn subquery_at_modifier() {
+        let prom_expr = PromExpr::Call(Call {
+            func: Function {
+                name: "max_over_time",
+                arg_types: vec![ValueType::Matrix],
+                variadic: false,
+                return_type: ValueType::Vector,
+            },
+            args: vec![Box::new(PromExpr::Subquery(SubqueryExpr {
+                expr: Box::new(rate_call(some_metric_selector(), Duration::from_secs(300))),
+                range: Duration::from_secs(3600),
+                offset: None,
+                start_or_end: Some(token::T_END),
+                step: Duration::from_secs(60),
+            }))],
+        });
+        let plan = plan_from_0_to_100000s(prom_expr).await;
+
+        // the inner query is only evaluated in the hour before `end`
+        let expected = "PromRangeManipulate: req range=[0..100000000], interval=[5000], eval range=[3600000], time index=[timestamp], values=[\"prom_rate(some_metric.timestamp_range,some_metric.field_0,some_metric.timestamp,Int64(300000))\"], at=[100000000]\
+            \n      PromSeriesNormalize: offset=[0], time index=[timestamp]\
+            \n        Filter: prom_rate(some_metric.timestamp_range,some_metric.field_0,some_metric.timestamp,Int64(300000)) IS NOT NULL\
+            \n          Projection: some_metric.tag_0, some_metric.timestamp, prom_rate(some_metric.timestamp_range, some_metric.field_0, some_metric.timestamp, Int64(300000))\
+            \n            PromRangeManipulate: req range=[96420000..100000000], interval=[60000], eval range=[300000], time index=[timestamp], values=[\"field_0\"]\
+            \n              PromSeriesNormalize: offset=[0], time index=[timestamp]\
+            \n                Filter: timestamp >= TimestampMillisecond(96120000, None) AND timestamp <= TimestampMillisecond(100000000, None)";
+        assert!(plan.contains(expected), "{plan}");
     }
-
-    // TODO(ruihang): add range fn tests once exprs are ready.
 }
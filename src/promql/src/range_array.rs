@@ -182,14 +182,18 @@ impl RangeArray {
     /// Change the field's datatype to the type after processed by [RangeArray].
     /// Like `Utf8` will become `Dictionary<Int64, Utf8>`.
     pub fn convert_field(field: &Field) -> Field {
-        let value_type = Box::new(field.data_type().clone());
         Field::new(
             field.name(),
-            DataType::Dictionary(Box::new(Self::key_type()), value_type),
+            Self::convert_data_type(field.data_type().clone()),
             field.is_nullable(),
         )
     }
 
+    /// Build datatype of wrapped [RangeArray] on given value type.
+    pub fn convert_data_type(value_type: DataType) -> DataType {
+        DataType::Dictionary(Box::new(Self::key_type()), Box::new(value_type))
+    }
+
     pub fn values(&self) -> &ArrayRef {
         self.array.values()
     }
@@ -93,7 +93,11 @@ where
             | Statement::CreateDatabase(_)
             | Statement::Alter(_)
             | Statement::Insert(_)
+            | Statement::Delete(_)
             | Statement::DropTable(_)
+            | Statement::FlushTable(_)
+            | Statement::ExportTable(_)
+            | Statement::RestoreTable(_)
             | Statement::Use(_) => unreachable!(),
         }
     }
@@ -57,6 +57,22 @@ pub enum Error {
         source: common_recordbatch::error::Error,
     },
 
+    #[snafu(display("Failed to collect RecordBatches, source: {}", source))]
+    CollectRecordBatches {
+        #[snafu(backtrace)]
+        source: common_recordbatch::error::Error,
+    },
+
+    #[snafu(display("Missing timestamp column in table: {}", table))]
+    MissingTimestampColumn { table: String, backtrace: Backtrace },
+
+    #[snafu(display("Failed to delete rows from table: {}, source: {}", table, source))]
+    DeleteTable {
+        table: String,
+        #[snafu(backtrace)]
+        source: table::error::Error,
+    },
+
     #[snafu(display("Failure during query execution, source: {}", source))]
     QueryExecution { source: BoxedError },
 
@@ -68,6 +84,19 @@ pub enum Error {
 
     #[snafu(display("The SQL string has multiple statements, query: {}", query))]
     MultipleStatements { query: String, backtrace: Backtrace },
+
+    #[snafu(display("Failed to parse PromQL: {}, reason: {}", query, reason))]
+    ParsePromQL {
+        query: String,
+        reason: String,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Invalid timestamp: {}", raw))]
+    InvalidTimestamp { raw: String, backtrace: Backtrace },
+
+    #[snafu(display("Invalid duration: {}", raw))]
+    InvalidDuration { raw: String, backtrace: Backtrace },
 }
 
 impl ErrorExt for Error {
@@ -75,14 +104,20 @@ impl ErrorExt for Error {
         use Error::*;
 
         match self {
-            QueryParse { .. } | MultipleStatements { .. } => StatusCode::InvalidSyntax,
+            QueryParse { .. } | MultipleStatements { .. } | ParsePromQL { .. } => {
+                StatusCode::InvalidSyntax
+            }
             UnsupportedExpr { .. }
+            | InvalidTimestamp { .. }
+            | InvalidDuration { .. }
             | CatalogNotFound { .. }
             | SchemaNotFound { .. }
-            | TableNotFound { .. } => StatusCode::InvalidArguments,
+            | TableNotFound { .. }
+            | MissingTimestampColumn { .. } => StatusCode::InvalidArguments,
             Catalog { source } => source.status_code(),
             VectorComputation { source } => source.status_code(),
-            CreateRecordBatch { source } => source.status_code(),
+            CreateRecordBatch { source } | CollectRecordBatches { source } => source.status_code(),
+            DeleteTable { source, .. } => source.status_code(),
             QueryExecution { source } | QueryPlan { source } => source.status_code(),
         }
     }
@@ -16,15 +16,19 @@ use std::str::FromStr;
 use std::sync::Arc;
 
 use common_time::timestamp::{TimeUnit, Timestamp};
+use datafusion::datasource::DefaultTableSource;
 use datafusion::optimizer::optimizer::OptimizerRule;
 use datafusion::optimizer::OptimizerConfig;
 use datafusion_common::{DFSchemaRef, DataFusionError, Result, ScalarValue};
+use datafusion_expr::expr::Sort as SortExpr;
 use datafusion_expr::expr_rewriter::{ExprRewritable, ExprRewriter};
 use datafusion_expr::{
-    Between, BinaryExpr, Expr, ExprSchemable, Filter, LogicalPlan, Operator, TableScan,
+    Between, BinaryExpr, Expr, ExprSchemable, Filter, Limit, LogicalPlan, Operator, Sort, TableScan,
 };
 use datatypes::arrow::compute;
 use datatypes::arrow::datatypes::DataType;
+use store_api::storage::TimeOrder;
+use table::table::adapter::DfTableProviderAdapter;
 
 /// TypeConversionRule converts some literal values in logical plan to other types according
 /// to data type of corresponding columns.
@@ -289,6 +293,151 @@ fn string_to_timestamp_ms(string: &str) -> Result<ScalarValue> {
     ))
 }
 
+/// OrderHintRule asks the table to scan rows ordered by its timestamp column if the plan
+/// sorts rows by the timestamp column and then limits them, e.g.
+/// `SELECT * FROM t ORDER BY ts DESC LIMIT 10`, so the table could stop scanning once it
+/// returns enough rows. The sort and limit are still kept in the plan.
+pub struct OrderHintRule;
+
+impl OptimizerRule for OrderHintRule {
+    fn try_optimize(
+        &self,
+        plan: &LogicalPlan,
+        config: &dyn OptimizerConfig,
+    ) -> Result<Option<LogicalPlan>> {
+        let sort_and_limit = match plan {
+            LogicalPlan::Limit(Limit {
+                skip,
+                fetch: Some(fetch),
+                input,
+            }) => match input.as_ref() {
+                LogicalPlan::Sort(sort) => Some((sort, skip.saturating_add(*fetch))),
+                _ => None,
+            },
+            LogicalPlan::Sort(sort) => sort.fetch.map(|fetch| (sort, fetch)),
+            _ => None,
+        };
+        if let Some((sort, limit)) = sort_and_limit {
+            if let Some(input) = order_table_scan(&sort.input, &sort.expr, limit)? {
+                let sort = LogicalPlan::Sort(Sort {
+                    expr: sort.expr.clone(),
+                    input: Arc::new(input),
+                    fetch: sort.fetch,
+                });
+                let plan = match plan {
+                    LogicalPlan::Limit(limit) => LogicalPlan::Limit(Limit {
+                        skip: limit.skip,
+                        fetch: limit.fetch,
+                        input: Arc::new(sort),
+                    }),
+                    _ => sort,
+                };
+                return Ok(Some(plan));
+            }
+        }
+
+        let inputs = plan.inputs();
+        let mut new_inputs = Vec::with_capacity(inputs.len());
+        let mut optimized = false;
+        for input in inputs {
+            match self.try_optimize(input, config)? {
+                Some(new_input) => {
+                    optimized = true;
+                    new_inputs.push(new_input);
+                }
+                None => new_inputs.push(input.clone()),
+            }
+        }
+        if !optimized {
+            return Ok(None);
+        }
+
+        datafusion_expr::utils::from_plan(plan, &plan.expressions(), &new_inputs).map(Some)
+    }
+
+    fn name(&self) -> &str {
+        "OrderHintRule"
+    }
+}
+
+/// Returns the new `plan` whose table scan returns at most `limit` rows ordered by
+/// `sort_exprs`, or `None` if the order can't be pushed down to the table scan.
+fn order_table_scan(
+    plan: &LogicalPlan,
+    sort_exprs: &[Expr],
+    limit: usize,
+) -> Result<Option<LogicalPlan>> {
+    let [Expr::Sort(SortExpr { expr, asc, .. })] = sort_exprs else { return Ok(None) };
+    let Expr::Column(column) = expr.as_ref() else { return Ok(None) };
+    let order = if *asc {
+        TimeOrder::Asc
+    } else {
+        TimeOrder::Desc
+    };
+
+    order_table_scan_by(plan, &column.name, order, limit)
+}
+
+fn order_table_scan_by(
+    plan: &LogicalPlan,
+    column: &str,
+    order: TimeOrder,
+    limit: usize,
+) -> Result<Option<LogicalPlan>> {
+    match plan {
+        // Only projections are allowed between the sort and the scan, filters or other
+        // plans may drop or change rows the scan returns.
+        LogicalPlan::Projection(projection) => {
+            // The sort column must be a column of the scan, not a computed one.
+            let from_input = projection
+                .expr
+                .iter()
+                .any(|expr| matches!(expr, Expr::Column(c) if c.name == column));
+            if !from_input {
+                return Ok(None);
+            }
+            let Some(input) = order_table_scan_by(&projection.input, column, order, limit)? else { return Ok(None) };
+
+            datafusion_expr::utils::from_plan(plan, &plan.expressions(), &[input]).map(Some)
+        }
+        LogicalPlan::TableScan(scan) => {
+            let Some(adapter) = scan
+                .source
+                .as_any()
+                .downcast_ref::<DefaultTableSource>()
+                .and_then(|source| {
+                    source
+                        .table_provider
+                        .as_any()
+                        .downcast_ref::<DfTableProviderAdapter>()
+                }) else { return Ok(None) };
+            if adapter.order().is_some() {
+                // Already optimized.
+                return Ok(None);
+            }
+            let table = adapter.table();
+            let is_timestamp = table
+                .schema()
+                .timestamp_column()
+                .map_or(false, |c| c.name == column);
+            if !is_timestamp {
+                return Ok(None);
+            }
+
+            let adapter = DfTableProviderAdapter::new(table).with_order(Some(order));
+            Ok(Some(LogicalPlan::TableScan(TableScan {
+                table_name: scan.table_name.clone(),
+                source: Arc::new(DefaultTableSource::new(Arc::new(adapter))),
+                projection: scan.projection.clone(),
+                projected_schema: scan.projected_schema.clone(),
+                filters: scan.filters.clone(),
+                fetch: Some(scan.fetch.map_or(limit, |fetch| fetch.min(limit))),
+            })))
+        }
+        _ => Ok(None),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::collections::HashMap;
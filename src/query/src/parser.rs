@@ -12,26 +12,46 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::time::Duration;
+use std::str::FromStr;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use common_error::prelude::BoxedError;
 use common_telemetry::timer;
-use promql_parser::label::{MatchOp, Matcher, Matchers};
-use promql_parser::parser::{EvalStmt, Expr as PromExpr, Function, ValueType};
-use snafu::ResultExt;
+use common_time::timestamp::TimeUnit;
+use common_time::Timestamp;
+use promql_parser::parser::EvalStmt;
+use snafu::{ensure, OptionExt, ResultExt};
 use sql::dialect::GenericDialect;
 use sql::parser::ParserContext;
 use sql::statements::statement::Statement;
 
-use crate::error::{MultipleStatementsSnafu, QueryParseSnafu, Result};
+use crate::error::{
+    InvalidDurationSnafu, InvalidTimestampSnafu, MultipleStatementsSnafu, ParsePromQLSnafu,
+    QueryParseSnafu, Result,
+};
 use crate::metric::{METRIC_PARSE_PROMQL_ELAPSED, METRIC_PARSE_SQL_ELAPSED};
 
+/// Default lookback delta of PromQL queries, same as Prometheus.
+pub const DEFAULT_LOOKBACK_DELTA: Duration = Duration::from_secs(5 * 60);
+
 #[derive(Debug, Clone)]
 pub enum QueryStatement {
     Sql(Statement),
     Promql(EvalStmt),
 }
 
+/// A PromQL query and its evaluation parameters, in the formats of the Prometheus HTTP API.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PromQuery {
+    pub query: String,
+    /// Start of the evaluation, either a RFC3339 string or a unix timestamp in seconds.
+    pub start: String,
+    /// End of the evaluation, in the same format as `start`.
+    pub end: String,
+    /// Evaluation step, either a duration string like `1m30s` or a float number of seconds.
+    pub step: String,
+}
+
 pub struct QueryLanguageParser {}
 
 impl QueryLanguageParser {
@@ -52,53 +72,99 @@ impl QueryLanguageParser {
         }
     }
 
-    // TODO(ruihang): implement this method when parser is ready.
-    pub fn parse_promql(_promql: &str) -> Result<QueryStatement> {
+    pub fn parse_promql(query: &PromQuery) -> Result<QueryStatement> {
         let _timer = timer!(METRIC_PARSE_PROMQL_ELAPSED);
 
-        let prom_expr = PromExpr::Call(promql_parser::parser::Call {
-            func: Function {
-                name: "ceil",
-                arg_types: vec![ValueType::Vector],
-                variadic: false,
-                return_type: ValueType::Vector,
-            },
-            args: vec![Box::new(PromExpr::VectorSelector(
-                promql_parser::parser::VectorSelector {
-                    name: Some("demo".to_owned()),
-                    offset: None,
-                    start_or_end: None,
-                    label_matchers: Matchers {
-                        matchers: vec![
-                            Matcher {
-                                op: MatchOp::Equal,
-                                name: "host".to_string(),
-                                value: "host1".to_string(),
-                            },
-                            Matcher {
-                                op: MatchOp::Equal,
-                                name: promql_parser::label::METRIC_NAME.to_string(),
-                                value: "demo".to_string(),
-                            },
-                        ],
-                    },
-                },
-            ))],
-        });
+        let expr = promql_parser::parser::parse(&query.query).map_err(|e| {
+            ParsePromQLSnafu {
+                query: &query.query,
+                reason: e.to_string(),
+            }
+            .build()
+        })?;
+
         let eval_stmt = EvalStmt {
-            expr: prom_expr,
-            start: std::time::UNIX_EPOCH,
-            end: std::time::UNIX_EPOCH
-                .checked_add(Duration::from_secs(100))
-                .unwrap(),
-            interval: Duration::from_secs(5),
-            lookback_delta: Duration::from_secs(1),
+            expr,
+            start: parse_promql_timestamp(&query.start)?,
+            end: parse_promql_timestamp(&query.end)?,
+            interval: parse_promql_duration(&query.step)?,
+            lookback_delta: DEFAULT_LOOKBACK_DELTA,
         };
 
         Ok(QueryStatement::Promql(eval_stmt))
     }
 }
 
+/// Parses a timestamp of the Prometheus HTTP API, which is either a RFC3339 string or a
+/// unix timestamp in seconds with optional decimal places.
+pub fn parse_promql_timestamp(raw: &str) -> Result<SystemTime> {
+    let since_epoch = if let Ok(secs) = raw.parse::<f64>() {
+        ensure!(
+            secs.is_finite() && secs >= 0.0,
+            InvalidTimestampSnafu { raw }
+        );
+        Duration::from_secs_f64(secs)
+    } else {
+        let millis = Timestamp::from_str(raw)
+            .ok()
+            .and_then(|ts| ts.convert_to(TimeUnit::Millisecond))
+            .map(|ts| ts.value())
+            .context(InvalidTimestampSnafu { raw })?;
+        // The planner doesn't accept time before the epoch.
+        ensure!(millis >= 0, InvalidTimestampSnafu { raw });
+        Duration::from_millis(millis as u64)
+    };
+
+    UNIX_EPOCH
+        .checked_add(since_epoch)
+        .context(InvalidTimestampSnafu { raw })
+}
+
+/// Parses a duration of the Prometheus HTTP API, which is either a float number of seconds
+/// or a duration string like `1h30m` with units `y`, `w`, `d`, `h`, `m`, `s` and `ms`.
+fn parse_promql_duration(raw: &str) -> Result<Duration> {
+    if let Ok(secs) = raw.parse::<f64>() {
+        ensure!(secs.is_finite() && secs > 0.0, InvalidDurationSnafu { raw });
+        return Ok(Duration::from_secs_f64(secs));
+    }
+
+    let mut millis: u64 = 0;
+    let mut rest = raw;
+    while !rest.is_empty() {
+        let digits_end = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        let unit_end = rest[digits_end..]
+            .find(|c: char| c.is_ascii_digit())
+            .map(|i| digits_end + i)
+            .unwrap_or(rest.len());
+
+        let value: u64 = rest[..digits_end]
+            .parse()
+            .ok()
+            .context(InvalidDurationSnafu { raw })?;
+        let unit_millis: u64 = match &rest[digits_end..unit_end] {
+            "ms" => 1,
+            "s" => 1000,
+            "m" => 60 * 1000,
+            "h" => 60 * 60 * 1000,
+            "d" => 24 * 60 * 60 * 1000,
+            "w" => 7 * 24 * 60 * 60 * 1000,
+            "y" => 365 * 24 * 60 * 60 * 1000,
+            _ => return InvalidDurationSnafu { raw }.fail(),
+        };
+        millis = value
+            .checked_mul(unit_millis)
+            .and_then(|v| v.checked_add(millis))
+            .context(InvalidDurationSnafu { raw })?;
+
+        rest = &rest[unit_end..];
+    }
+    ensure!(millis > 0, InvalidDurationSnafu { raw });
+
+    Ok(Duration::from_millis(millis))
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -135,4 +201,65 @@ mod test {
 
         assert_eq!(format!("{stmt:?}"), expected);
     }
+
+    #[test]
+    fn parse_promql_simple() {
+        let query = PromQuery {
+            query: "ceil(demo{host=\"host1\"})".to_string(),
+            start: "0".to_string(),
+            end: "1970-01-01T00:01:40Z".to_string(),
+            step: "5s".to_string(),
+        };
+        let QueryStatement::Promql(stmt) = QueryLanguageParser::parse_promql(&query).unwrap() else {
+            unreachable!()
+        };
+        assert_eq!(UNIX_EPOCH, stmt.start);
+        assert_eq!(UNIX_EPOCH + Duration::from_secs(100), stmt.end);
+        assert_eq!(Duration::from_secs(5), stmt.interval);
+        assert_eq!(DEFAULT_LOOKBACK_DELTA, stmt.lookback_delta);
+
+        let query = PromQuery {
+            query: "ceil(demo{".to_string(),
+            ..query
+        };
+        assert!(QueryLanguageParser::parse_promql(&query).is_err());
+    }
+
+    #[test]
+    fn test_parse_promql_timestamp() {
+        assert_eq!(UNIX_EPOCH, parse_promql_timestamp("0").unwrap());
+        assert_eq!(
+            UNIX_EPOCH + Duration::from_millis(1500),
+            parse_promql_timestamp("1.5").unwrap()
+        );
+        assert_eq!(
+            UNIX_EPOCH + Duration::from_secs(60),
+            parse_promql_timestamp("1970-01-01T00:01:00Z").unwrap()
+        );
+        assert!(parse_promql_timestamp("-1").is_err());
+        assert!(parse_promql_timestamp("now").is_err());
+    }
+
+    #[test]
+    fn test_parse_promql_duration() {
+        assert_eq!(
+            Duration::from_millis(1500),
+            parse_promql_duration("1.5").unwrap()
+        );
+        assert_eq!(
+            Duration::from_secs(5400),
+            parse_promql_duration("1h30m").unwrap()
+        );
+        assert_eq!(
+            Duration::from_millis(1010),
+            parse_promql_duration("1s10ms").unwrap()
+        );
+        assert_eq!(
+            Duration::from_secs(7 * 24 * 3600),
+            parse_promql_duration("1w").unwrap()
+        );
+        for raw in ["", "0", "-1", "0s", "s", "5x", "1h30"] {
+            assert!(parse_promql_duration(raw).is_err(), "{raw}");
+        }
+    }
 }
@@ -37,7 +37,7 @@ use datatypes::arrow::datatypes::DataType;
 use promql::extension_plan::PromExtensionPlanner;
 
 use crate::datafusion::DfCatalogListAdapter;
-use crate::optimizer::TypeConversionRule;
+use crate::optimizer::{OrderHintRule, TypeConversionRule};
 
 /// Query engine global state
 // TODO(yingwen): This QueryEngineState still relies on datafusion, maybe we can define a trait for it,
@@ -65,6 +65,7 @@ impl QueryEngineState {
         let mut optimizer = Optimizer::new();
         // Apply the type conversion rule first.
         optimizer.rules.insert(0, Arc::new(TypeConversionRule {}));
+        optimizer.rules.push(Arc::new(OrderHintRule));
 
         let mut session_state = SessionState::with_config_rt(session_config, runtime_env);
         session_state.optimizer = optimizer;
@@ -17,20 +17,24 @@ use std::sync::Arc;
 use catalog::CatalogManagerRef;
 use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
 use common_query::Output;
-use common_recordbatch::RecordBatches;
+use common_recordbatch::{util, RecordBatches};
 use datatypes::prelude::*;
 use datatypes::schema::{ColumnSchema, Schema};
 use datatypes::vectors::{Helper, StringVector};
 use once_cell::sync::Lazy;
 use session::context::QueryContextRef;
 use snafu::{ensure, OptionExt, ResultExt};
+use sql::ast::Ident;
+use sql::statements::delete::Delete;
 use sql::statements::describe::DescribeTable;
 use sql::statements::explain::Explain;
 use sql::statements::show::{ShowDatabases, ShowKind, ShowTables};
 use sql::statements::statement::Statement;
+use table::requests::DeleteRequest;
+use table::TableRef;
 
 use crate::error::{self, Result};
-use crate::parser::QueryStatement;
+use crate::parser::{QueryLanguageParser, QueryStatement};
 use crate::QueryEngineRef;
 
 const SCHEMAS_COLUMN: &str = "Schemas";
@@ -170,6 +174,71 @@ pub async fn explain(
     query_engine.execute(&plan).await
 }
 
+/// Deletes rows matching the selection of the `DELETE` statement from the `table`.
+///
+/// Keys (primary key and time index) of the rows to delete are resolved by a query
+/// on the table, then the table writes tombstones for these keys.
+pub async fn delete(
+    stmt: Delete,
+    table: TableRef,
+    query_engine: QueryEngineRef,
+    query_ctx: QueryContextRef,
+) -> Result<Output> {
+    let table_info = table.table_info();
+    let mut key_columns: Vec<_> = table_info.meta.row_key_column_names().cloned().collect();
+    let timestamp_column =
+        table_info
+            .meta
+            .schema
+            .timestamp_column()
+            .context(error::MissingTimestampColumnSnafu {
+                table: &table_info.name,
+            })?;
+    key_columns.push(timestamp_column.name.clone());
+
+    let projection = key_columns
+        .iter()
+        .map(|name| Ident::with_quote('"', name).to_string())
+        .collect::<Vec<_>>()
+        .join(", ");
+    let mut sql = format!("SELECT {} FROM {}", projection, stmt.table_name());
+    if let Some(selection) = stmt.selection() {
+        sql.push_str(&format!(" WHERE {selection}"));
+    }
+
+    let plan = query_engine.statement_to_plan(QueryLanguageParser::parse_sql(&sql)?, query_ctx)?;
+    let batches = match query_engine.execute(&plan).await? {
+        Output::Stream(stream) => util::collect(stream)
+            .await
+            .context(error::CollectRecordBatchesSnafu)?,
+        Output::RecordBatches(batches) => batches.take(),
+        Output::AffectedRows(_) => unreachable!(),
+    };
+
+    // The table only counts rows actually deleted, so keys returned more than once
+    // are not counted repeatedly.
+    let mut affected_rows = 0;
+    for batch in batches {
+        if batch.num_rows() == 0 {
+            continue;
+        }
+
+        let key_column_values = key_columns
+            .iter()
+            .cloned()
+            .zip(batch.columns().iter().cloned())
+            .collect();
+        affected_rows += table
+            .delete(DeleteRequest { key_column_values })
+            .await
+            .context(error::DeleteTableSnafu {
+                table: &table_info.name,
+            })?;
+    }
+
+    Ok(Output::AffectedRows(affected_rows))
+}
+
 pub fn describe_table(stmt: DescribeTable, catalog_manager: CatalogManagerRef) -> Result<Output> {
     let catalog = stmt.catalog_name.as_str();
     let schema = stmt.schema_name.as_str();
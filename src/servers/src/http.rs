@@ -17,6 +17,7 @@ pub mod handler;
 pub mod influxdb;
 pub mod opentsdb;
 pub mod prometheus;
+pub mod promql;
 pub mod script;
 
 use std::net::SocketAddr;
@@ -449,6 +450,11 @@ impl HttpServer {
             );
         }
 
+        router = router.nest(
+            &format!("/{HTTP_API_VERSION}/prometheus/api/v1"),
+            self.route_promql(self.sql_handler.clone()),
+        );
+
         router = router.route("/metrics", routing::get(handler::metrics));
 
         router = router.route(
@@ -491,6 +497,31 @@ impl HttpServer {
             .with_state(prom_handler)
     }
 
+    fn route_promql<S>(&self, sql_handler: ServerSqlQueryHandlerRef) -> Router<S> {
+        Router::new()
+            .route(
+                "/query",
+                routing::get(promql::instant_query).post(promql::instant_query),
+            )
+            .route(
+                "/query_range",
+                routing::get(promql::range_query).post(promql::range_query),
+            )
+            .route(
+                "/series",
+                routing::get(promql::series_query).post(promql::series_query),
+            )
+            .route(
+                "/labels",
+                routing::get(promql::labels_query).post(promql::labels_query),
+            )
+            .route(
+                "/label/:label_name/values",
+                routing::get(promql::label_values_query),
+            )
+            .with_state(sql_handler)
+    }
+
     fn route_influxdb<S>(&self, influxdb_handler: InfluxdbLineProtocolHandlerRef) -> Router<S> {
         Router::new()
             .route("/write", routing::post(influxdb_write))
@@ -592,6 +623,14 @@ mod test {
             unimplemented!()
         }
 
+        async fn do_promql_query(
+            &self,
+            _query: &query::parser::PromQuery,
+            _query_ctx: QueryContextRef,
+        ) -> Result<Output> {
+            unimplemented!()
+        }
+
         fn is_valid_schema(&self, _catalog: &str, _schema: &str) -> Result<bool> {
             Ok(true)
         }
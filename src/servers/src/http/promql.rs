@@ -0,0 +1,716 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Prometheus compatible query APIs, see
+//! <https://prometheus.io/docs/prometheus/latest/querying/api/>.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use axum::extract::{Path, State};
+use axum::response::{IntoResponse, Response};
+use axum::{http, Form, Json};
+use common_error::prelude::ErrorExt;
+use common_error::status_code::StatusCode;
+use common_query::Output;
+use common_recordbatch::{util, RecordBatch};
+use common_time::timestamp::TimeUnit;
+use datatypes::prelude::ConcreteDataType;
+use datatypes::value::ValueRef;
+use promql_parser::label::{MatchOp, METRIC_NAME};
+use promql_parser::parser::{Expr as PromExpr, MatrixSelector};
+use query::parser::{parse_promql_timestamp, PromQuery, DEFAULT_LOOKBACK_DELTA};
+use serde::{Deserialize, Serialize};
+use session::context::QueryContextRef;
+
+use crate::http::query_context_from_db;
+use crate::query_handler::sql::ServerSqlQueryHandlerRef;
+
+/// Label to distinguish series of different fields when the output has several value
+/// columns, as a table may have more than one field while a series has only one value.
+pub const FIELD_LABEL: &str = "__field__";
+
+/// Step of instant queries, which are evaluated at a single point so the step is never used.
+const INSTANT_QUERY_STEP: &str = "1s";
+/// Time range to look up series when the request doesn't specify the start time.
+const DEFAULT_LOOKUP_RANGE_SECS: u64 = 60 * 60;
+
+type PromResult<T> = std::result::Result<T, PromJsonResponse>;
+
+/// Series of labels, which are ordered by name.
+pub type Labels = BTreeMap<String, String>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PromErrorType {
+    BadData,
+    Execution,
+    Internal,
+}
+
+impl PromErrorType {
+    fn from_status_code(code: StatusCode) -> Self {
+        match code {
+            StatusCode::InvalidArguments
+            | StatusCode::InvalidSyntax
+            | StatusCode::Unsupported
+            | StatusCode::TableNotFound
+            | StatusCode::TableColumnNotFound
+            | StatusCode::DatabaseNotFound => PromErrorType::BadData,
+            StatusCode::Unknown | StatusCode::Unexpected | StatusCode::Internal => {
+                PromErrorType::Internal
+            }
+            _ => PromErrorType::Execution,
+        }
+    }
+
+    fn http_status(&self) -> http::StatusCode {
+        match self {
+            PromErrorType::BadData => http::StatusCode::BAD_REQUEST,
+            PromErrorType::Execution => http::StatusCode::UNPROCESSABLE_ENTITY,
+            PromErrorType::Internal => http::StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PromSeries {
+    pub metric: Labels,
+    /// Samples of a range query.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub values: Vec<(f64, String)>,
+    /// Sample of an instant query.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<(f64, String)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PromData {
+    #[serde(rename = "resultType")]
+    pub result_type: String,
+    pub result: Vec<PromSeries>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum PromResponse {
+    PromData(PromData),
+    Labels(Vec<String>),
+    Series(Vec<Labels>),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PromJsonResponse {
+    pub status: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<PromResponse>,
+    #[serde(rename = "errorType", skip_serializing_if = "Option::is_none")]
+    pub error_type: Option<PromErrorType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl PromJsonResponse {
+    pub fn success(data: PromResponse) -> Self {
+        PromJsonResponse {
+            status: "success".to_string(),
+            data: Some(data),
+            error_type: None,
+            error: None,
+        }
+    }
+
+    pub fn error(error_type: PromErrorType, reason: impl Into<String>) -> Self {
+        PromJsonResponse {
+            status: "error".to_string(),
+            data: None,
+            error_type: Some(error_type),
+            error: Some(reason.into()),
+        }
+    }
+
+    fn from_error(e: &impl ErrorExt) -> Self {
+        Self::error(
+            PromErrorType::from_status_code(e.status_code()),
+            e.to_string(),
+        )
+    }
+}
+
+impl IntoResponse for PromJsonResponse {
+    fn into_response(self) -> Response {
+        let status = self
+            .error_type
+            .map(|t| t.http_status())
+            .unwrap_or(http::StatusCode::OK);
+        (status, Json(self)).into_response()
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct InstantQuery {
+    pub query: Option<String>,
+    pub time: Option<String>,
+    pub db: Option<String>,
+}
+
+#[axum_macros::debug_handler]
+pub async fn instant_query(
+    State(handler): State<ServerSqlQueryHandlerRef>,
+    Form(params): Form<InstantQuery>,
+) -> PromResult<PromJsonResponse> {
+    let query_ctx = query_context(&handler, params.db)?;
+    let query = params.query.ok_or_else(|| {
+        PromJsonResponse::error(PromErrorType::BadData, "missing query parameter")
+    })?;
+    let time = params.time.unwrap_or_else(current_time);
+
+    let prom_query = PromQuery {
+        query,
+        start: time.clone(),
+        end: time,
+        step: INSTANT_QUERY_STEP.to_string(),
+    };
+    let result = evaluate(&handler, &prom_query, query_ctx)
+        .await?
+        .into_iter()
+        .map(|mut series| {
+            series.value = series.values.pop();
+            series.values.clear();
+            series
+        })
+        .collect();
+
+    Ok(PromJsonResponse::success(PromResponse::PromData(
+        PromData {
+            result_type: "vector".to_string(),
+            result,
+        },
+    )))
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct RangeQuery {
+    pub query: Option<String>,
+    pub start: Option<String>,
+    pub end: Option<String>,
+    pub step: Option<String>,
+    pub db: Option<String>,
+}
+
+#[axum_macros::debug_handler]
+pub async fn range_query(
+    State(handler): State<ServerSqlQueryHandlerRef>,
+    Form(params): Form<RangeQuery>,
+) -> PromResult<PromJsonResponse> {
+    let query_ctx = query_context(&handler, params.db)?;
+    let (Some(query), Some(start), Some(end), Some(step)) =
+        (params.query, params.start, params.end, params.step) else {
+        return Err(PromJsonResponse::error(
+            PromErrorType::BadData,
+            "query, start, end and step parameters are required",
+        ));
+    };
+
+    let prom_query = PromQuery {
+        query,
+        start,
+        end,
+        step,
+    };
+    let result = evaluate(&handler, &prom_query, query_ctx).await?;
+
+    Ok(PromJsonResponse::success(PromResponse::PromData(
+        PromData {
+            result_type: "matrix".to_string(),
+            result,
+        },
+    )))
+}
+
+#[axum_macros::debug_handler]
+pub async fn series_query(
+    State(handler): State<ServerSqlQueryHandlerRef>,
+    Form(params): Form<Vec<(String, String)>>,
+) -> PromResult<PromJsonResponse> {
+    let params = LookupParams::from(params);
+    if params.matches.is_empty() {
+        return Err(PromJsonResponse::error(
+            PromErrorType::BadData,
+            "no match[] parameter provided",
+        ));
+    }
+
+    let series = lookup_series(&handler, params).await?;
+
+    Ok(PromJsonResponse::success(PromResponse::Series(
+        series.into_iter().collect(),
+    )))
+}
+
+#[axum_macros::debug_handler]
+pub async fn labels_query(
+    State(handler): State<ServerSqlQueryHandlerRef>,
+    Form(params): Form<Vec<(String, String)>>,
+) -> PromResult<PromJsonResponse> {
+    let series = lookup_series(&handler, LookupParams::from(params)).await?;
+    let names: BTreeSet<_> = series.into_iter().flat_map(|s| s.into_keys()).collect();
+
+    Ok(PromJsonResponse::success(PromResponse::Labels(
+        names.into_iter().collect(),
+    )))
+}
+
+#[axum_macros::debug_handler]
+pub async fn label_values_query(
+    State(handler): State<ServerSqlQueryHandlerRef>,
+    Path(label_name): Path<String>,
+    Form(params): Form<Vec<(String, String)>>,
+) -> PromResult<PromJsonResponse> {
+    let series = lookup_series(&handler, LookupParams::from(params)).await?;
+    let values: BTreeSet<_> = series
+        .into_iter()
+        .filter_map(|mut s| s.remove(&label_name))
+        .collect();
+
+    Ok(PromJsonResponse::success(PromResponse::Labels(
+        values.into_iter().collect(),
+    )))
+}
+
+/// Parameters of the series, labels and label values APIs, which may contain repeated
+/// `match[]` parameters.
+#[derive(Debug, Default)]
+struct LookupParams {
+    matches: Vec<String>,
+    start: Option<String>,
+    end: Option<String>,
+    db: Option<String>,
+}
+
+impl From<Vec<(String, String)>> for LookupParams {
+    fn from(pairs: Vec<(String, String)>) -> Self {
+        let mut params = LookupParams::default();
+        for (key, value) in pairs {
+            match key.as_str() {
+                "match[]" => params.matches.push(value),
+                "start" => params.start = Some(value),
+                "end" => params.end = Some(value),
+                "db" => params.db = Some(value),
+                _ => {}
+            }
+        }
+        params
+    }
+}
+
+impl LookupParams {
+    /// Returns the time range to look up in whole seconds, which covers the requested range.
+    fn time_range_secs(&self) -> PromResult<(u64, u64)> {
+        let parse =
+            |raw: &str| parse_promql_timestamp(raw).map_err(|e| PromJsonResponse::from_error(&e));
+        let end = match &self.end {
+            Some(end) => parse(end)?,
+            None => SystemTime::now(),
+        };
+        let end = secs_since_epoch(end, true);
+        let start = match &self.start {
+            Some(start) => secs_since_epoch(parse(start)?, false),
+            None => end.saturating_sub(DEFAULT_LOOKUP_RANGE_SECS),
+        };
+        Ok((start, end.max(start)))
+    }
+}
+
+fn secs_since_epoch(time: SystemTime, round_up: bool) -> u64 {
+    let since_epoch = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    if round_up && since_epoch.subsec_nanos() > 0 {
+        since_epoch.as_secs() + 1
+    } else {
+        since_epoch.as_secs()
+    }
+}
+
+/// Looks up the series selected by `match[]` in the time range, or series of all metrics
+/// in the database if there is no selector.
+async fn lookup_series(
+    handler: &ServerSqlQueryHandlerRef,
+    params: LookupParams,
+) -> PromResult<BTreeSet<Labels>> {
+    let (start, end) = params.time_range_secs()?;
+    let query_ctx = query_context(handler, params.db)?;
+
+    let mut series = BTreeSet::new();
+    if params.matches.is_empty() {
+        for table in show_tables(handler, query_ctx.clone()).await? {
+            let selector = format!(
+                "{{{METRIC_NAME}=\"{}\"}}",
+                table.replace('\\', "\\\\").replace('"', "\\\"")
+            );
+            // Tables that are not metrics, like those without a time index, can't be
+            // selected and are skipped.
+            if let Ok(selected) =
+                select_series(handler, &selector, start, end, query_ctx.clone()).await
+            {
+                series.extend(selected);
+            }
+        }
+    } else {
+        for selector in &params.matches {
+            series.extend(select_series(handler, selector, start, end, query_ctx.clone()).await?);
+        }
+    }
+
+    Ok(series)
+}
+
+/// Returns labels of series selected by `selector` that have samples in `[start, end]`.
+async fn select_series(
+    handler: &ServerSqlQueryHandlerRef,
+    selector: &str,
+    start: u64,
+    end: u64,
+    query_ctx: QueryContextRef,
+) -> PromResult<Vec<Labels>> {
+    // Evaluates the selector at points no farther apart than the lookback delta, so every
+    // sample in the range is visible to one of them. The last point may be slightly after
+    // `end` to keep the points evenly spaced.
+    let lookback = DEFAULT_LOOKBACK_DELTA.as_secs();
+    let range = end - start;
+    let points = ((range + lookback - 1) / lookback).max(1);
+    let step = ((range + points - 1) / points).max(1);
+
+    let prom_query = PromQuery {
+        query: selector.to_string(),
+        start: start.to_string(),
+        end: (start + points * step).to_string(),
+        step: step.to_string(),
+    };
+    let series = evaluate(handler, &prom_query, query_ctx).await?;
+
+    Ok(series.into_iter().map(|s| s.metric).collect())
+}
+
+/// Returns names of tables in the database of `query_ctx`.
+async fn show_tables(
+    handler: &ServerSqlQueryHandlerRef,
+    query_ctx: QueryContextRef,
+) -> PromResult<Vec<String>> {
+    let mut names = Vec::new();
+    for output in handler.do_query("SHOW TABLES", query_ctx).await {
+        let output = output.map_err(|e| PromJsonResponse::from_error(&e))?;
+        for batch in collect_record_batches(output).await? {
+            if batch.num_columns() == 0 {
+                continue;
+            }
+            let column = batch.column(0);
+            names.extend((0..column.len()).filter_map(|i| {
+                column
+                    .get_ref(i)
+                    .as_string()
+                    .ok()
+                    .flatten()
+                    .map(|s| s.to_string())
+            }));
+        }
+    }
+    Ok(names)
+}
+
+/// Evaluates the query and converts its output into series.
+async fn evaluate(
+    handler: &ServerSqlQueryHandlerRef,
+    query: &PromQuery,
+    query_ctx: QueryContextRef,
+) -> PromResult<Vec<PromSeries>> {
+    let output = handler
+        .do_promql_query(query, query_ctx)
+        .await
+        .map_err(|e| PromJsonResponse::from_error(&e))?;
+    let batches = collect_record_batches(output).await?;
+    let metric_name = retrieve_metric_name(&query.query);
+
+    Ok(record_batches_to_series(metric_name.as_deref(), &batches))
+}
+
+async fn collect_record_batches(output: Output) -> PromResult<Vec<RecordBatch>> {
+    match output {
+        Output::Stream(stream) => util::collect(stream)
+            .await
+            .map_err(|e| PromJsonResponse::from_error(&e)),
+        Output::RecordBatches(batches) => Ok(batches.take()),
+        Output::AffectedRows(_) => Err(PromJsonResponse::error(
+            PromErrorType::Internal,
+            "unexpected affected rows output",
+        )),
+    }
+}
+
+/// Returns the metric name if the query is a plain selector, whose output series keep
+/// the `__name__` label in Prometheus.
+fn retrieve_metric_name(query: &str) -> Option<String> {
+    let expr = promql_parser::parser::parse(query).ok()?;
+    let selector = match &expr {
+        PromExpr::VectorSelector(selector) => selector,
+        PromExpr::MatrixSelector(MatrixSelector {
+            vector_selector, ..
+        }) => match &**vector_selector {
+            PromExpr::VectorSelector(selector) => selector,
+            _ => return None,
+        },
+        _ => return None,
+    };
+
+    selector
+        .label_matchers
+        .matchers
+        .iter()
+        .find(|m| m.name == METRIC_NAME && matches!(m.op, MatchOp::Equal))
+        .map(|m| m.value.clone())
+        .or_else(|| selector.name.clone())
+}
+
+/// Converts the output of a PromQL query into series. String columns are labels, the first
+/// timestamp column holds the sample time and each numeric column is a series of samples.
+fn record_batches_to_series(metric_name: Option<&str>, batches: &[RecordBatch]) -> Vec<PromSeries> {
+    let mut series: Vec<PromSeries> = Vec::new();
+    let mut series_index: HashMap<Labels, usize> = HashMap::new();
+
+    for batch in batches {
+        let column_schemas = batch.schema.column_schemas();
+        let mut time_index = None;
+        let mut label_indices = Vec::new();
+        let mut value_indices = Vec::new();
+        for (i, column_schema) in column_schemas.iter().enumerate() {
+            match &column_schema.data_type {
+                ConcreteDataType::Timestamp(_) if time_index.is_none() => time_index = Some(i),
+                ConcreteDataType::String(_) => label_indices.push(i),
+                data_type if is_sample_type(data_type) => value_indices.push(i),
+                _ => {}
+            }
+        }
+        let Some(time_index) = time_index else { continue };
+
+        for row in 0..batch.num_rows() {
+            let Some(time) = batch
+                .column(time_index)
+                .get_ref(row)
+                .as_timestamp()
+                .ok()
+                .flatten()
+                .and_then(|ts| ts.convert_to(TimeUnit::Millisecond)) else {
+                continue;
+            };
+            let time = time.value() as f64 / 1000.0;
+
+            let mut labels = Labels::new();
+            if let Some(name) = metric_name {
+                labels.insert(METRIC_NAME.to_string(), name.to_string());
+            }
+            for &i in &label_indices {
+                if let Ok(Some(value)) = batch.column(i).get_ref(row).as_string() {
+                    // Prometheus treats labels with empty values as absent.
+                    if !value.is_empty() {
+                        labels.insert(column_schemas[i].name.clone(), value.to_string());
+                    }
+                }
+            }
+
+            for &i in &value_indices {
+                let Some(value) = sample_value(batch.column(i).get_ref(row)) else { continue };
+                let mut labels = labels.clone();
+                if value_indices.len() > 1 {
+                    labels.insert(FIELD_LABEL.to_string(), column_schemas[i].name.clone());
+                }
+                let index = *series_index.entry(labels.clone()).or_insert_with(|| {
+                    series.push(PromSeries {
+                        metric: labels,
+                        ..Default::default()
+                    });
+                    series.len() - 1
+                });
+                series[index]
+                    .values
+                    .push((time, format_sample_value(value)));
+            }
+        }
+    }
+
+    series
+}
+
+fn is_sample_type(data_type: &ConcreteDataType) -> bool {
+    matches!(
+        data_type,
+        ConcreteDataType::Int8(_)
+            | ConcreteDataType::Int16(_)
+            | ConcreteDataType::Int32(_)
+            | ConcreteDataType::Int64(_)
+            | ConcreteDataType::UInt8(_)
+            | ConcreteDataType::UInt16(_)
+            | ConcreteDataType::UInt32(_)
+            | ConcreteDataType::UInt64(_)
+            | ConcreteDataType::Float32(_)
+            | ConcreteDataType::Float64(_)
+    )
+}
+
+fn sample_value(value: ValueRef) -> Option<f64> {
+    let value = match value {
+        ValueRef::Int8(v) => v as f64,
+        ValueRef::Int16(v) => v as f64,
+        ValueRef::Int32(v) => v as f64,
+        ValueRef::Int64(v) => v as f64,
+        ValueRef::UInt8(v) => v as f64,
+        ValueRef::UInt16(v) => v as f64,
+        ValueRef::UInt32(v) => v as f64,
+        ValueRef::UInt64(v) => v as f64,
+        ValueRef::Float32(v) => v.0 as f64,
+        ValueRef::Float64(v) => v.0,
+        _ => return None,
+    };
+    Some(value)
+}
+
+/// Formats the sample value in the way Prometheus does.
+fn format_sample_value(value: f64) -> String {
+    if value.is_nan() {
+        "NaN".to_string()
+    } else if value == f64::INFINITY {
+        "+Inf".to_string()
+    } else if value == f64::NEG_INFINITY {
+        "-Inf".to_string()
+    } else {
+        value.to_string()
+    }
+}
+
+fn current_time() -> String {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+    format!("{}.{:03}", now.as_secs(), now.subsec_millis())
+}
+
+fn query_context(
+    handler: &ServerSqlQueryHandlerRef,
+    db: Option<String>,
+) -> PromResult<QueryContextRef> {
+    query_context_from_db(handler.clone(), db).map_err(|resp| {
+        PromJsonResponse::error(PromErrorType::BadData, resp.error.unwrap_or_default())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use datatypes::schema::{ColumnSchema, Schema};
+    use datatypes::vectors::{Float64Vector, StringVector, TimestampMillisecondVector, VectorRef};
+
+    use super::*;
+
+    #[test]
+    fn test_record_batches_to_series() {
+        let schema = Arc::new(Schema::new(vec![
+            ColumnSchema::new("host", ConcreteDataType::string_datatype(), true),
+            ColumnSchema::new(
+                "ts",
+                ConcreteDataType::timestamp_millisecond_datatype(),
+                false,
+            ),
+            ColumnSchema::new("cpu", ConcreteDataType::float64_datatype(), true),
+            ColumnSchema::new("memory", ConcreteDataType::float64_datatype(), true),
+        ]));
+        let columns: Vec<VectorRef> = vec![
+            Arc::new(StringVector::from(vec!["host1", "host2", "host1"])),
+            Arc::new(TimestampMillisecondVector::from_slice(&[1000, 1000, 2500])),
+            Arc::new(Float64Vector::from(vec![Some(0.5), Some(1.0), None])),
+            Arc::new(Float64Vector::from(vec![
+                Some(1024.0),
+                Some(f64::NAN),
+                Some(2048.0),
+            ])),
+        ];
+        let batch = RecordBatch::new(schema, columns).unwrap();
+
+        let series = record_batches_to_series(Some("demo"), &[batch]);
+        let labels = |host: &str, field: &str| -> Labels {
+            [(METRIC_NAME, "demo"), ("host", host), (FIELD_LABEL, field)]
+                .into_iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect()
+        };
+        let expect = vec![
+            PromSeries {
+                metric: labels("host1", "cpu"),
+                values: vec![(1.0, "0.5".to_string())],
+                value: None,
+            },
+            PromSeries {
+                metric: labels("host1", "memory"),
+                values: vec![(1.0, "1024".to_string()), (2.5, "2048".to_string())],
+                value: None,
+            },
+            PromSeries {
+                metric: labels("host2", "cpu"),
+                values: vec![(1.0, "1".to_string())],
+                value: None,
+            },
+            PromSeries {
+                metric: labels("host2", "memory"),
+                values: vec![(1.0, "NaN".to_string())],
+                value: None,
+            },
+        ];
+        assert_eq!(expect, series);
+    }
+
+    #[test]
+    fn test_json_response_format() {
+        let resp = PromJsonResponse::success(PromResponse::PromData(PromData {
+            result_type: "vector".to_string(),
+            result: vec![PromSeries {
+                metric: [("host".to_string(), "host1".to_string())].into(),
+                values: vec![],
+                value: Some((1.5, "2".to_string())),
+            }],
+        }));
+        assert_eq!(
+            r#"{"status":"success","data":{"resultType":"vector","result":[{"metric":{"host":"host1"},"value":[1.5,"2"]}]}}"#,
+            serde_json::to_string(&resp).unwrap()
+        );
+
+        let resp = PromJsonResponse::error(PromErrorType::BadData, "invalid query");
+        assert_eq!(
+            r#"{"status":"error","errorType":"bad_data","error":"invalid query"}"#,
+            serde_json::to_string(&resp).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_lookup_params() {
+        let params = LookupParams::from(vec![
+            ("match[]".to_string(), "up".to_string()),
+            ("match[]".to_string(), "demo{host=\"h1\"}".to_string()),
+            ("start".to_string(), "10.5".to_string()),
+            ("end".to_string(), "20.5".to_string()),
+        ]);
+        assert_eq!(vec!["up", "demo{host=\"h1\"}"], params.matches);
+        assert_eq!((10, 21), params.time_range_secs().unwrap());
+
+        let params = LookupParams::from(vec![("end".to_string(), "7200".to_string())]);
+        assert_eq!((3600, 7200), params.time_range_secs().unwrap());
+    }
+}
@@ -17,6 +17,7 @@ use std::sync::Arc;
 use async_trait::async_trait;
 use common_error::prelude::*;
 use common_query::Output;
+use query::parser::PromQuery;
 use session::context::QueryContextRef;
 use sql::statements::statement::Statement;
 
@@ -41,6 +42,12 @@ pub trait SqlQueryHandler {
         query_ctx: QueryContextRef,
     ) -> std::result::Result<Output, Self::Error>;
 
+    async fn do_promql_query(
+        &self,
+        query: &PromQuery,
+        query_ctx: QueryContextRef,
+    ) -> std::result::Result<Output, Self::Error>;
+
     fn is_valid_schema(
         &self,
         catalog: &str,
@@ -87,6 +94,20 @@ where
             .context(error::ExecuteStatementSnafu)
     }
 
+    async fn do_promql_query(
+        &self,
+        query: &PromQuery,
+        query_ctx: QueryContextRef,
+    ) -> Result<Output> {
+        self.0
+            .do_promql_query(query, query_ctx)
+            .await
+            .map_err(BoxedError::new)
+            .context(error::ExecuteQuerySnafu {
+                query: &query.query,
+            })
+    }
+
     fn is_valid_schema(&self, catalog: &str, schema: &str) -> Result<bool> {
         self.0
             .is_valid_schema(catalog, schema)
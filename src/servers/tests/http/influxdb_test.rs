@@ -62,6 +62,14 @@ impl SqlQueryHandler for DummyInstance {
         unimplemented!()
     }
 
+    async fn do_promql_query(
+        &self,
+        _query: &query::parser::PromQuery,
+        _query_ctx: QueryContextRef,
+    ) -> Result<Output> {
+        unimplemented!()
+    }
+
     fn is_valid_schema(&self, _catalog: &str, _schema: &str) -> Result<bool> {
         Ok(true)
     }
@@ -21,7 +21,13 @@ use async_trait::async_trait;
 use axum::Router;
 use axum_test_helper::TestClient;
 use common_query::Output;
+use common_recordbatch::{RecordBatch, RecordBatches};
+use datatypes::prelude::ConcreteDataType;
+use datatypes::schema::{ColumnSchema, Schema};
+use datatypes::vectors::{Float64Vector, StringVector, TimestampMillisecondVector};
 use prost::Message;
+use query::parser::PromQuery;
+use serde_json::json;
 use servers::error::{Error, Result};
 use servers::http::{HttpOptions, HttpServer};
 use servers::prometheus;
@@ -73,8 +79,21 @@ impl PrometheusProtocolHandler for DummyInstance {
 impl SqlQueryHandler for DummyInstance {
     type Error = Error;
 
-    async fn do_query(&self, _: &str, _: QueryContextRef) -> Vec<Result<Output>> {
-        unimplemented!()
+    async fn do_query(&self, query: &str, _: QueryContextRef) -> Vec<Result<Output>> {
+        assert_eq!("SHOW TABLES", query);
+        let schema = Arc::new(Schema::new(vec![ColumnSchema::new(
+            "Tables",
+            ConcreteDataType::string_datatype(),
+            false,
+        )]));
+        let batch = RecordBatch::new(
+            schema.clone(),
+            vec![Arc::new(StringVector::from(vec!["demo"])) as _],
+        )
+        .unwrap();
+        vec![Ok(Output::RecordBatches(
+            RecordBatches::try_new(schema, vec![batch]).unwrap(),
+        ))]
     }
 
     async fn do_statement_query(
@@ -85,6 +104,36 @@ impl SqlQueryHandler for DummyInstance {
         unimplemented!()
     }
 
+    async fn do_promql_query(
+        &self,
+        query: &PromQuery,
+        _query_ctx: QueryContextRef,
+    ) -> Result<Output> {
+        let _ = self.tx.send((query.query.clone(), vec![])).await;
+
+        let schema = Arc::new(Schema::new(vec![
+            ColumnSchema::new("host", ConcreteDataType::string_datatype(), true),
+            ColumnSchema::new(
+                "ts",
+                ConcreteDataType::timestamp_millisecond_datatype(),
+                false,
+            ),
+            ColumnSchema::new("cpu", ConcreteDataType::float64_datatype(), true),
+        ]));
+        let batch = RecordBatch::new(
+            schema.clone(),
+            vec![
+                Arc::new(StringVector::from(vec!["host1", "host1"])) as _,
+                Arc::new(TimestampMillisecondVector::from_slice(&[1000, 2000])) as _,
+                Arc::new(Float64Vector::from_slice(&[0.5, 1.0])) as _,
+            ],
+        )
+        .unwrap();
+        Ok(Output::RecordBatches(
+            RecordBatches::try_new(schema, vec![batch]).unwrap(),
+        ))
+    }
+
     fn is_valid_schema(&self, _catalog: &str, _schema: &str) -> Result<bool> {
         Ok(true)
     }
@@ -202,3 +251,108 @@ async fn test_prometheus_remote_write_read() {
         ReadRequest::decode(&(requests[3].1)[..]).unwrap()
     );
 }
+
+#[tokio::test]
+async fn test_prometheus_query_api() {
+    let (tx, mut rx) = mpsc::channel(100);
+
+    let app = make_test_app(tx);
+    let client = TestClient::new(app);
+
+    let result = client
+        .get("/v1/prometheus/api/v1/query?query=demo&time=2")
+        .send()
+        .await;
+    assert_eq!(result.status(), 200);
+    let body: serde_json::Value = serde_json::from_str(&result.text().await).unwrap();
+    assert_eq!(
+        json!({
+            "status": "success",
+            "data": {
+                "resultType": "vector",
+                "result": [{
+                    "metric": {"__name__": "demo", "host": "host1"},
+                    "value": [2.0, "1"],
+                }],
+            },
+        }),
+        body
+    );
+
+    let result = client
+        .post("/v1/prometheus/api/v1/query_range")
+        .header("content-type", "application/x-www-form-urlencoded")
+        .body("query=ceil(demo)&start=0&end=10&step=1s")
+        .send()
+        .await;
+    assert_eq!(result.status(), 200);
+    let body: serde_json::Value = serde_json::from_str(&result.text().await).unwrap();
+    assert_eq!(
+        json!({
+            "status": "success",
+            "data": {
+                "resultType": "matrix",
+                "result": [{
+                    "metric": {"host": "host1"},
+                    "values": [[1.0, "0.5"], [2.0, "1"]],
+                }],
+            },
+        }),
+        body
+    );
+
+    let result = client
+        .get("/v1/prometheus/api/v1/query_range?query=demo&start=0&end=10")
+        .send()
+        .await;
+    assert_eq!(result.status(), 400);
+    let body: serde_json::Value = serde_json::from_str(&result.text().await).unwrap();
+    assert_eq!("error", body["status"]);
+    assert_eq!("bad_data", body["errorType"]);
+
+    let result = client
+        .get("/v1/prometheus/api/v1/series?match[]=demo&start=0&end=10")
+        .send()
+        .await;
+    assert_eq!(result.status(), 200);
+    let body: serde_json::Value = serde_json::from_str(&result.text().await).unwrap();
+    assert_eq!(
+        json!({
+            "status": "success",
+            "data": [{"__name__": "demo", "host": "host1"}],
+        }),
+        body
+    );
+
+    let result = client.get("/v1/prometheus/api/v1/series").send().await;
+    assert_eq!(result.status(), 400);
+
+    // Looks up all metrics without match[].
+    let result = client
+        .get("/v1/prometheus/api/v1/labels?start=0&end=10")
+        .send()
+        .await;
+    assert_eq!(result.status(), 200);
+    let body: serde_json::Value = serde_json::from_str(&result.text().await).unwrap();
+    assert_eq!(
+        json!({"status": "success", "data": ["__name__", "host"]}),
+        body
+    );
+
+    let result = client
+        .get("/v1/prometheus/api/v1/label/host/values?match[]=demo&start=0&end=10")
+        .send()
+        .await;
+    assert_eq!(result.status(), 200);
+    let body: serde_json::Value = serde_json::from_str(&result.text().await).unwrap();
+    assert_eq!(json!({"status": "success", "data": ["host1"]}), body);
+
+    let mut queries = vec![];
+    while let Ok((query, _)) = rx.try_recv() {
+        queries.push(query);
+    }
+    assert_eq!(
+        vec!["demo", "ceil(demo)", "demo", "{__name__=\"demo\"}", "demo"],
+        queries
+    );
+}
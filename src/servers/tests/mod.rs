@@ -76,6 +76,14 @@ impl SqlQueryHandler for DummyInstance {
         unimplemented!()
     }
 
+    async fn do_promql_query(
+        &self,
+        _query: &query::parser::PromQuery,
+        _query_ctx: QueryContextRef,
+    ) -> Result<Output> {
+        unimplemented!()
+    }
+
     fn is_valid_schema(&self, catalog: &str, schema: &str) -> Result<bool> {
         Ok(catalog == DEFAULT_CATALOG_NAME && schema == DEFAULT_SCHEMA_NAME)
     }
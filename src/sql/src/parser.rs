@@ -13,6 +13,7 @@
 // limitations under the License.
 
 use snafu::{ensure, ResultExt};
+use sqlparser::ast::ObjectName;
 use sqlparser::dialect::Dialect;
 use sqlparser::keywords::Keyword;
 use sqlparser::parser::{Parser, ParserError};
@@ -21,9 +22,11 @@ use sqlparser::tokenizer::{Token, Tokenizer};
 use crate::error::{
     self, InvalidDatabaseNameSnafu, InvalidTableNameSnafu, Result, SyntaxSnafu, TokenizerSnafu,
 };
+use crate::statements::backup::{ExportTable, RestoreTable};
 use crate::statements::describe::DescribeTable;
 use crate::statements::drop::DropTable;
 use crate::statements::explain::Explain;
+use crate::statements::flush::FlushTable;
 use crate::statements::show::{ShowCreateTable, ShowDatabases, ShowKind, ShowTables};
 use crate::statements::statement::Statement;
 use crate::statements::table_idents_to_full_name;
@@ -96,6 +99,8 @@ impl<'a> ParserContext<'a> {
 
                     Keyword::INSERT => self.parse_insert(),
 
+                    Keyword::DELETE => self.parse_delete(),
+
                     Keyword::SELECT | Keyword::WITH | Keyword::VALUES => self.parse_query(),
 
                     Keyword::ALTER => self.parse_alter(),
@@ -116,6 +121,12 @@ impl<'a> ParserContext<'a> {
                         Ok(Statement::Use(database_name.value))
                     }
 
+                    _ if w.value.eq_ignore_ascii_case("FLUSH") => self.parse_flush(),
+
+                    _ if w.value.eq_ignore_ascii_case("EXPORT") => self.parse_export(),
+
+                    _ if w.value.eq_ignore_ascii_case("RESTORE") => self.parse_restore(),
+
                     // todo(hl) support more statements.
                     _ => self.unsupported(self.peek_token_as_string()),
                 }
@@ -318,6 +329,83 @@ impl<'a> ParserContext<'a> {
         }))
     }
 
+    /// Parses `FLUSH TABLE <table_name>`.
+    fn parse_flush(&mut self) -> Result<Statement> {
+        self.parser.next_token();
+        if !self.matches_keyword(Keyword::TABLE) {
+            return self.unsupported(self.peek_token_as_string());
+        }
+        self.parser.next_token();
+
+        let table_ident =
+            self.parser
+                .parse_object_name()
+                .with_context(|_| error::UnexpectedSnafu {
+                    sql: self.sql,
+                    expected: "a table name",
+                    actual: self.peek_token_as_string(),
+                })?;
+        ensure!(
+            !table_ident.0.is_empty(),
+            InvalidTableNameSnafu {
+                name: table_ident.to_string()
+            }
+        );
+
+        Ok(Statement::FlushTable(FlushTable::new(table_ident)))
+    }
+
+    /// Parses `EXPORT TABLE <table_name> TO '<dir>'`.
+    fn parse_export(&mut self) -> Result<Statement> {
+        let (table_ident, dir) = self.parse_table_and_dir(Keyword::TO)?;
+        Ok(Statement::ExportTable(ExportTable::new(table_ident, dir)))
+    }
+
+    /// Parses `RESTORE TABLE <table_name> FROM '<dir>'`.
+    fn parse_restore(&mut self) -> Result<Statement> {
+        let (table_ident, dir) = self.parse_table_and_dir(Keyword::FROM)?;
+        Ok(Statement::RestoreTable(RestoreTable::new(table_ident, dir)))
+    }
+
+    /// Parses `<statement> TABLE <table_name> <keyword> '<dir>'`, the first word of the
+    /// statement is already peeked.
+    fn parse_table_and_dir(&mut self, keyword: Keyword) -> Result<(ObjectName, String)> {
+        self.parser.next_token();
+        if !self.matches_keyword(Keyword::TABLE) {
+            return self.unsupported(self.peek_token_as_string());
+        }
+        self.parser.next_token();
+
+        let table_ident =
+            self.parser
+                .parse_object_name()
+                .with_context(|_| error::UnexpectedSnafu {
+                    sql: self.sql,
+                    expected: "a table name",
+                    actual: self.peek_token_as_string(),
+                })?;
+        ensure!(
+            !table_ident.0.is_empty(),
+            InvalidTableNameSnafu {
+                name: table_ident.to_string()
+            }
+        );
+
+        self.parser
+            .expect_keyword(keyword)
+            .context(SyntaxSnafu { sql: self.sql })?;
+        let dir = self
+            .parser
+            .parse_literal_string()
+            .with_context(|_| error::UnexpectedSnafu {
+                sql: self.sql,
+                expected: "a directory",
+                actual: self.peek_token_as_string(),
+            })?;
+
+        Ok((table_ident, dir))
+    }
+
     // Report unexpected token
     pub(crate) fn expected<T>(&self, expected: &str, found: Token) -> Result<T> {
         Err(ParserError::ParserError(format!(
@@ -385,7 +473,9 @@ mod tests {
     use std::assert_matches::assert_matches;
 
     use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
-    use sqlparser::ast::{Query as SpQuery, Statement as SpStatement, WildcardAdditionalOptions};
+    use sqlparser::ast::{
+        Ident, ObjectName, Query as SpQuery, Statement as SpStatement, WildcardAdditionalOptions,
+    };
     use sqlparser::dialect::GenericDialect;
 
     use super::*;
@@ -615,4 +705,63 @@ mod tests {
             })
         )
     }
+
+    #[test]
+    pub fn test_flush_table() {
+        let sql = "FLUSH TABLE foo";
+        let result = ParserContext::create_with_dialect(sql, &GenericDialect {});
+        let mut stmts = result.unwrap();
+        assert_eq!(
+            stmts.pop().unwrap(),
+            Statement::FlushTable(FlushTable::new(ObjectName(vec![Ident::new("foo")])))
+        );
+
+        let sql = "flush table my_schema.foo";
+        let result = ParserContext::create_with_dialect(sql, &GenericDialect {});
+        let mut stmts = result.unwrap();
+        assert_eq!(
+            stmts.pop().unwrap(),
+            Statement::FlushTable(FlushTable::new(ObjectName(vec![
+                Ident::new("my_schema"),
+                Ident::new("foo")
+            ])))
+        );
+
+        let sql = "FLUSH foo";
+        let result = ParserContext::create_with_dialect(sql, &GenericDialect {});
+        assert!(result.is_err());
+    }
+
+    #[test]
+    pub fn test_export_and_restore_table() {
+        let sql = "EXPORT TABLE my_schema.foo TO 'backup/foo'";
+        let result = ParserContext::create_with_dialect(sql, &GenericDialect {});
+        let mut stmts = result.unwrap();
+        assert_eq!(
+            stmts.pop().unwrap(),
+            Statement::ExportTable(ExportTable::new(
+                ObjectName(vec![Ident::new("my_schema"), Ident::new("foo")]),
+                "backup/foo".to_string()
+            ))
+        );
+
+        let sql = "restore table bar from 'backup/foo'";
+        let result = ParserContext::create_with_dialect(sql, &GenericDialect {});
+        let mut stmts = result.unwrap();
+        assert_eq!(
+            stmts.pop().unwrap(),
+            Statement::RestoreTable(RestoreTable::new(
+                ObjectName(vec![Ident::new("bar")]),
+                "backup/foo".to_string()
+            ))
+        );
+
+        let sql = "EXPORT TABLE foo 'backup/foo'";
+        let result = ParserContext::create_with_dialect(sql, &GenericDialect {});
+        assert!(result.is_err());
+
+        let sql = "RESTORE TABLE foo FROM backup";
+        let result = ParserContext::create_with_dialect(sql, &GenericDialect {});
+        assert!(result.is_err());
+    }
 }
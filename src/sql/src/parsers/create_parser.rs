@@ -714,6 +714,7 @@ fn ensure_partition_names_no_duplicate(partitions: &Partitions) -> Result<()> {
 #[cfg(test)]
 mod tests {
     use std::assert_matches::assert_matches;
+    use std::collections::HashMap;
 
     use sqlparser::ast::ColumnOption::NotNull;
     use sqlparser::dialect::GenericDialect;
@@ -1262,6 +1263,25 @@ ENGINE=mito";
         }
     }
 
+    #[test]
+    fn test_parse_create_table_options() {
+        let sql = r"create table demo(
+                             host string,
+                             ts timestamp,
+                             TIME INDEX (ts)) engine=mito
+                             with(regions=1, TTL='30d');
+         ";
+        let result = ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap();
+        match &result[0] {
+            Statement::CreateTable(c) => {
+                // Options not recognised by the table engine are skipped.
+                let expect = HashMap::from([("ttl".to_string(), "30d".to_string())]);
+                assert_eq!(expect, c.table_options());
+            }
+            _ => unreachable!(),
+        }
+    }
+
     #[test]
     fn test_invalid_index_keys() {
         let sql = r"create table demo(
@@ -0,0 +1,89 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snafu::ResultExt;
+use sqlparser::ast::{Statement as SpStatement, TableFactor};
+
+use crate::error::{self, Result};
+use crate::parser::ParserContext;
+use crate::statements::delete::Delete;
+use crate::statements::statement::Statement;
+
+/// DELETE statement parser implementation
+impl<'a> ParserContext<'a> {
+    pub(crate) fn parse_delete(&mut self) -> Result<Statement> {
+        self.parser.next_token();
+        let spstatement = self
+            .parser
+            .parse_delete()
+            .context(error::SyntaxSnafu { sql: self.sql })?;
+
+        match spstatement {
+            SpStatement::Delete {
+                table_name: TableFactor::Table { .. },
+                ..
+            } => Ok(Statement::Delete(Box::new(Delete { inner: spstatement }))),
+            unexp => error::UnsupportedSnafu {
+                sql: self.sql.to_string(),
+                keyword: unexp.to_string(),
+            }
+            .fail(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::assert_matches::assert_matches;
+
+    use sqlparser::dialect::GenericDialect;
+
+    use super::*;
+
+    #[test]
+    pub fn test_parse_delete() {
+        let sql = r"DELETE FROM my_schema.table_1 WHERE host = 'host1' AND ts < 1000";
+        let mut result = ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap();
+        assert_eq!(1, result.len());
+        assert_matches!(result[0], Statement::Delete { .. });
+
+        match result.remove(0) {
+            Statement::Delete(delete) => {
+                assert_eq!("my_schema.table_1", delete.table_name().to_string());
+                assert_eq!(
+                    "host = 'host1' AND ts < 1000",
+                    delete.selection().as_ref().unwrap().to_string()
+                );
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    pub fn test_parse_delete_without_selection() {
+        let sql = r"DELETE FROM table_1";
+        let mut result = ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap();
+        match result.remove(0) {
+            Statement::Delete(delete) => assert!(delete.selection().is_none()),
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    pub fn test_parse_invalid_delete() {
+        let sql = r"DELETE table_1 WHERE"; // intentionally a bad sql
+        let result = ParserContext::create_with_dialect(sql, &GenericDialect {});
+        assert!(result.is_err(), "result is: {result:?}");
+    }
+}
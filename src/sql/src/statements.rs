@@ -13,10 +13,13 @@
 // limitations under the License.
 
 pub mod alter;
+pub mod backup;
 pub mod create;
+pub mod delete;
 pub mod describe;
 pub mod drop;
 pub mod explain;
+pub mod flush;
 pub mod insert;
 pub mod query;
 pub mod show;
@@ -0,0 +1,59 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use sqlparser::ast::ObjectName;
+
+/// EXPORT TABLE statement, exports the table to a directory of the storage.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExportTable {
+    table_name: ObjectName,
+    dir: String,
+}
+
+impl ExportTable {
+    /// Creates a statement for `EXPORT TABLE <table_name> TO '<dir>'`
+    pub fn new(table_name: ObjectName, dir: String) -> Self {
+        ExportTable { table_name, dir }
+    }
+
+    pub fn table_name(&self) -> &ObjectName {
+        &self.table_name
+    }
+
+    pub fn dir(&self) -> &str {
+        &self.dir
+    }
+}
+
+/// RESTORE TABLE statement, restores a table exported by [ExportTable] as a new table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RestoreTable {
+    table_name: ObjectName,
+    dir: String,
+}
+
+impl RestoreTable {
+    /// Creates a statement for `RESTORE TABLE <table_name> FROM '<dir>'`
+    pub fn new(table_name: ObjectName, dir: String) -> Self {
+        RestoreTable { table_name, dir }
+    }
+
+    pub fn table_name(&self) -> &ObjectName {
+        &self.table_name
+    }
+
+    pub fn dir(&self) -> &str {
+        &self.dir
+    }
+}
@@ -12,6 +12,10 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
+
+use table::requests::valid_table_option;
+
 use crate::ast::{ColumnDef, Ident, ObjectName, SqlOption, TableConstraint, Value as SqlValue};
 
 /// Time index name, used in table constraints.
@@ -32,6 +36,27 @@ pub struct CreateTable {
     pub partitions: Option<Partitions>,
 }
 
+impl CreateTable {
+    /// Returns table options in `WITH` recognised by the table engine, as a map from
+    /// lowercase option name to value. Other options like `regions` are skipped.
+    pub fn table_options(&self) -> HashMap<String, String> {
+        self.options
+            .iter()
+            .filter_map(|option| {
+                let name = option.name.value.to_lowercase();
+                if !valid_table_option(&name) {
+                    return None;
+                }
+                let value = match &option.value {
+                    SqlValue::SingleQuotedString(s) | SqlValue::DoubleQuotedString(s) => s.clone(),
+                    value => value.to_string(),
+                };
+                Some((name, value))
+            })
+            .collect()
+    }
+}
+
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub struct Partitions {
     pub column_list: Vec<Ident>,
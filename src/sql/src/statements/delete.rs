@@ -0,0 +1,49 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use sqlparser::ast::{Expr, ObjectName, Statement, TableFactor};
+
+use crate::error::Result;
+use crate::statements::table_idents_to_full_name;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Delete {
+    // Can only be sqlparser::ast::Statement::Delete variant, whose table factor
+    // must be TableFactor::Table.
+    pub inner: Statement,
+}
+
+impl Delete {
+    pub fn full_table_name(&self) -> Result<(String, String, String)> {
+        table_idents_to_full_name(self.table_name())
+    }
+
+    pub fn table_name(&self) -> &ObjectName {
+        match &self.inner {
+            Statement::Delete {
+                table_name: TableFactor::Table { name, .. },
+                ..
+            } => name,
+            _ => unreachable!(),
+        }
+    }
+
+    /// Returns the `WHERE` clause of the statement.
+    pub fn selection(&self) -> &Option<Expr> {
+        match &self.inner {
+            Statement::Delete { selection, .. } => selection,
+            _ => unreachable!(),
+        }
+    }
+}
@@ -0,0 +1,39 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use sqlparser::ast::ObjectName;
+
+use crate::error::Result;
+use crate::statements::table_idents_to_full_name;
+
+/// FLUSH TABLE statement.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FlushTable {
+    table_name: ObjectName,
+}
+
+impl FlushTable {
+    /// Creates a statement for `FLUSH TABLE`
+    pub fn new(table_name: ObjectName) -> Self {
+        FlushTable { table_name }
+    }
+
+    pub fn full_table_name(&self) -> Result<(String, String, String)> {
+        table_idents_to_full_name(&self.table_name)
+    }
+
+    pub fn table_name(&self) -> &ObjectName {
+        &self.table_name
+    }
+}
@@ -13,10 +13,13 @@
 // limitations under the License.
 
 use crate::statements::alter::AlterTable;
+use crate::statements::backup::{ExportTable, RestoreTable};
 use crate::statements::create::{CreateDatabase, CreateTable};
+use crate::statements::delete::Delete;
 use crate::statements::describe::DescribeTable;
 use crate::statements::drop::DropTable;
 use crate::statements::explain::Explain;
+use crate::statements::flush::FlushTable;
 use crate::statements::insert::Insert;
 use crate::statements::query::Query;
 use crate::statements::show::{ShowCreateTable, ShowDatabases, ShowTables};
@@ -29,6 +32,8 @@ pub enum Statement {
     Query(Box<Query>),
     // Insert
     Insert(Box<Insert>),
+    // Delete
+    Delete(Box<Delete>),
     /// CREATE TABLE
     CreateTable(CreateTable),
     // DROP TABLE
@@ -47,6 +52,12 @@ pub enum Statement {
     DescribeTable(DescribeTable),
     // EXPLAIN QUERY
     Explain(Explain),
+    // FLUSH TABLE
+    FlushTable(FlushTable),
+    // EXPORT TABLE
+    ExportTable(ExportTable),
+    // RESTORE TABLE
+    RestoreTable(RestoreTable),
     Use(String),
 }
 
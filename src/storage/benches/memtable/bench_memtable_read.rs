@@ -22,9 +22,12 @@ fn bench_memtable_read(c: &mut Criterion) {
     let kvs = generate_kvs(10, 10000, 20);
     let ctx = BenchContext::new();
     kvs.iter().for_each(|kv| ctx.write(kv));
+    let series_ctx = BenchContext::new_series();
+    kvs.iter().for_each(|kv| series_ctx.write(kv));
     let mut group = c.benchmark_group("memtable_read");
     group.throughput(Throughput::Elements(10 * 10000));
     group.bench_function("read", |b| b.iter(|| ctx.read(100)));
+    group.bench_function("read_series", |b| b.iter(|| series_ctx.read(100)));
     group.finish();
 }
 
@@ -39,7 +39,7 @@ impl Context {
     /// Job accessing this context should check `is_cancelled()` and exit if it
     /// returns true.
     pub fn cancel(&self) {
-        self.inner.cancelled.store(false, Ordering::Relaxed);
+        self.inner.cancelled.store(true, Ordering::Relaxed);
     }
 
     /// Returns true if this context is cancelled.
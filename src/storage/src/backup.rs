@@ -0,0 +1,177 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Backup of regions.
+//!
+//! A backup of a region is a copy of its SSTs and a manifest that only contains the
+//! metadata and files of the region, using the same layout as the region in the engine,
+//! so it can be restored by copying the files back.
+
+use object_store::{util, ObjectStore};
+use snafu::ensure;
+use store_api::manifest::Manifest;
+use store_api::storage::{RegionId, SequenceNumber};
+
+use crate::engine;
+use crate::error::{self, Result};
+use crate::inspect::RegionInspector;
+use crate::manifest::action::{
+    RawRegionMetadata, RegionChange, RegionEdit, RegionMetaAction, RegionMetaActionList,
+};
+use crate::manifest::region::RegionManifest;
+use crate::sst::{self, FileMeta, FileTier};
+
+/// Location of a region backup.
+#[derive(Debug, Clone)]
+pub struct BackupLocation {
+    /// Object store that holds the backup.
+    pub object_store: ObjectStore,
+    /// Parent directory of the region in the backup.
+    pub parent_dir: String,
+    /// Name of the region in the backup.
+    pub region_name: String,
+}
+
+impl BackupLocation {
+    pub fn new(object_store: ObjectStore, parent_dir: &str, region_name: &str) -> Self {
+        Self {
+            object_store,
+            parent_dir: util::normalize_dir(parent_dir),
+            region_name: region_name.to_string(),
+        }
+    }
+
+    #[inline]
+    pub(crate) fn sst_dir(&self) -> String {
+        engine::region_sst_dir(&self.parent_dir, &self.region_name)
+    }
+
+    #[inline]
+    fn manifest_dir(&self) -> String {
+        engine::region_manifest_dir(&self.parent_dir, &self.region_name)
+    }
+
+    /// Returns error if there is already a region in this location.
+    pub(crate) async fn ensure_absent(&self) -> Result<()> {
+        ensure!(
+            RegionBackup::load(self).await?.is_none(),
+            error::RegionExistsInDirSnafu {
+                dir: self.manifest_dir(),
+            }
+        );
+
+        Ok(())
+    }
+}
+
+/// Metadata and files of a region at a flushed sequence.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct RegionBackup {
+    pub metadata: RawRegionMetadata,
+    /// Rows with sequence less than or equal to this sequence are in the `files`.
+    pub flushed_sequence: SequenceNumber,
+    pub files: Vec<FileMeta>,
+}
+
+impl RegionBackup {
+    /// Loads the backup from the manifest in `location`, returns `None` if the manifest
+    /// doesn't have the metadata of the region.
+    pub(crate) async fn load(location: &BackupLocation) -> Result<Option<RegionBackup>> {
+        let inspector = RegionInspector::new(
+            &location.parent_dir,
+            &location.region_name,
+            location.object_store.clone(),
+        );
+
+        let mut metadata = None;
+        let mut flushed_sequence = 0;
+        let mut files: Vec<FileMeta> = Vec::new();
+        for (_, action_list) in inspector.manifest_actions().await? {
+            for action in action_list.actions {
+                match action {
+                    RegionMetaAction::Change(change) => metadata = Some(change.metadata),
+                    RegionMetaAction::Edit(edit) => {
+                        files.retain(|file| {
+                            edit.files_to_remove
+                                .iter()
+                                .all(|removed| removed.file_name != file.file_name)
+                        });
+                        files.extend(edit.files_to_add);
+                        if let Some(sequence) = edit.flushed_sequence {
+                            flushed_sequence = flushed_sequence.max(sequence);
+                        }
+                    }
+                    RegionMetaAction::Protocol(_) | RegionMetaAction::Remove(_) => (),
+                }
+            }
+        }
+
+        Ok(metadata.map(|metadata| RegionBackup {
+            metadata,
+            flushed_sequence,
+            files,
+        }))
+    }
+
+    /// Copies the files of the backup in `from` to `to`, all copied files are in
+    /// the hot tier.
+    pub(crate) async fn copy_files(
+        &mut self,
+        from: &BackupLocation,
+        to: &BackupLocation,
+    ) -> Result<()> {
+        let (from_dir, to_dir) = (from.sst_dir(), to.sst_dir());
+        for file in &mut self.files {
+            sst::copy_object(
+                &from.object_store,
+                &format!("{}{}", from_dir, file.file_name),
+                &to.object_store,
+                &format!("{}{}", to_dir, file.file_name),
+            )
+            .await?;
+            file.tier = FileTier::Hot;
+        }
+
+        Ok(())
+    }
+
+    /// Renames the region in the backup.
+    pub(crate) fn rename(&mut self, id: RegionId, name: &str) {
+        self.metadata.id = id;
+        self.metadata.name = name.to_string();
+    }
+
+    /// Writes the metadata and files of the backup to a new manifest in `location`.
+    ///
+    /// The caller should ensure there is no region in `location` by
+    /// [BackupLocation::ensure_absent].
+    pub(crate) async fn write_manifest(&self, location: &BackupLocation) -> Result<()> {
+        let manifest = RegionManifest::new(&location.manifest_dir(), location.object_store.clone());
+        let action_list = RegionMetaActionList::new(vec![
+            RegionMetaAction::Change(RegionChange {
+                committed_sequence: self.flushed_sequence,
+                metadata: self.metadata.clone(),
+            }),
+            RegionMetaAction::Edit(RegionEdit {
+                region_version: self.metadata.version,
+                flushed_sequence: Some(self.flushed_sequence),
+                files_to_add: self.files.clone(),
+                files_to_remove: Vec::new(),
+            }),
+        ]);
+        manifest.update(action_list).await?;
+
+        Ok(())
+    }
+}
@@ -12,21 +12,32 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::cmp;
 use std::sync::Arc;
+use std::time::Duration;
 
 use async_trait::async_trait;
 use common_query::logical_plan::Expr;
 use common_telemetry::debug;
 use common_time::range::TimestampRange;
+use common_time::Timestamp;
+use futures::FutureExt;
+use metrics::counter;
 use snafu::ResultExt;
-use store_api::storage::{Chunk, ChunkReader, SchemaRef, SequenceNumber};
+use store_api::storage::{Chunk, ChunkReader, SchemaRef, SequenceNumber, TimeOrder};
 use table::predicate::{Predicate, TimeRangePredicateBuilder};
 
 use crate::error::{self, Error, Result};
 use crate::memtable::{IterContext, MemtableRef};
-use crate::read::{BoxedBatchReader, DedupReader, MergeReaderBuilder};
+use crate::metric::{
+    METRIC_SCAN_MEMTABLES_PRUNED, METRIC_SCAN_SST_FILES_PRUNED, METRIC_SCAN_SST_FILES_READ,
+};
+use crate::read::{
+    Batch, BatchReader, BoxedBatchReader, DedupReader, LimitReader, MergeReaderBuilder,
+    WindowedReader,
+};
 use crate::schema::{ProjectedSchema, ProjectedSchemaRef, RegionSchemaRef};
-use crate::sst::{AccessLayerRef, FileHandle, LevelMetas, ReadOptions, Visitor};
+use crate::sst::{self, AccessLayerRef, FileHandle, LevelMetas, ReadOptions, Visitor};
 
 /// Chunk reader implementation.
 // Now we use async-trait to implement the chunk reader, which is easier to implement than
@@ -35,6 +46,8 @@ use crate::sst::{AccessLayerRef, FileHandle, LevelMetas, ReadOptions, Visitor};
 pub struct ChunkReaderImpl {
     schema: ProjectedSchemaRef,
     batch_reader: BoxedBatchReader,
+    /// Holds the SST files to read, so they won't be purged before the reader is dropped.
+    _sst_files: Vec<FileHandle>,
 }
 
 #[async_trait]
@@ -46,7 +59,7 @@ impl ChunkReader for ChunkReaderImpl {
     }
 
     async fn next_chunk(&mut self) -> Result<Option<Chunk>> {
-        let batch = match self.batch_reader.next_batch().await? {
+        let batch = match self.next_batch().await? {
             Some(b) => b,
             None => return Ok(None),
         };
@@ -58,12 +71,28 @@ impl ChunkReader for ChunkReaderImpl {
 }
 
 impl ChunkReaderImpl {
-    pub fn new(schema: ProjectedSchemaRef, batch_reader: BoxedBatchReader) -> ChunkReaderImpl {
+    pub fn new(
+        schema: ProjectedSchemaRef,
+        batch_reader: BoxedBatchReader,
+        sst_files: Vec<FileHandle>,
+    ) -> ChunkReaderImpl {
         ChunkReaderImpl {
             schema,
             batch_reader,
+            _sst_files: sst_files,
         }
     }
+
+    /// Fetch next [Batch] from the underlying reader.
+    #[inline]
+    pub async fn next_batch(&mut self) -> Result<Option<Batch>> {
+        self.batch_reader.next_batch().await
+    }
+
+    #[inline]
+    pub fn projected_schema(&self) -> &ProjectedSchemaRef {
+        &self.schema
+    }
 }
 
 /// Builder to create a new [ChunkReaderImpl] from scan request.
@@ -75,6 +104,10 @@ pub struct ChunkReaderBuilder {
     iter_ctx: IterContext,
     memtables: Vec<MemtableRef>,
     files_to_read: Vec<FileHandle>,
+    filter_deleted: bool,
+    ttl: Option<Duration>,
+    order: Option<TimeOrder>,
+    limit: Option<usize>,
 }
 
 impl ChunkReaderBuilder {
@@ -87,6 +120,10 @@ impl ChunkReaderBuilder {
             iter_ctx: IterContext::default(),
             memtables: Vec::new(),
             files_to_read: Vec::new(),
+            filter_deleted: true,
+            ttl: None,
+            order: None,
+            limit: None,
         }
     }
 
@@ -127,74 +164,445 @@ impl ChunkReaderBuilder {
         Ok(self)
     }
 
-    pub async fn build(mut self) -> Result<ChunkReaderImpl> {
-        let time_range_predicate = self.build_time_range_predicate();
+    /// Picks the given SST `files` to read.
+    pub fn pick_files(mut self, files: &[FileHandle]) -> Self {
+        self.files_to_read.extend_from_slice(files);
+        self
+    }
+
+    /// Whether to filter out rows marked as deleted, defaults to true.
+    ///
+    /// Compaction needs to keep the deletion markers since the merged file may not
+    /// contain all versions of a key.
+    pub fn filter_deleted(mut self, filter_deleted: bool) -> Self {
+        self.filter_deleted = filter_deleted;
+        self
+    }
+
+    /// Sets the TTL of the data to read, rows older than the TTL are filtered out.
+    pub fn ttl(mut self, ttl: Option<Duration>) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// Sets the order of the rows to read by timestamp, `None` to read rows in key order.
+    pub fn order(mut self, order: Option<TimeOrder>) -> Self {
+        self.order = order;
+        self
+    }
+
+    /// Sets max number of rows to read.
+    pub fn limit(mut self, limit: Option<usize>) -> Self {
+        self.limit = limit;
+        self
+    }
+
+    pub async fn build(self) -> Result<ChunkReaderImpl> {
+        let (order, limit) = (self.order, self.limit);
+        let (merge_ctx, memtables, sst_files) = self.prune_sources()?;
+        let schema = merge_ctx.schema.clone();
+
+        let reader: BoxedBatchReader = match order {
+            Some(order) => {
+                let windows = split_time_windows(memtables, &sst_files);
+                Box::new(merge_ctx.build_windowed_reader(windows, order))
+            }
+            None => merge_ctx.build_reader(&memtables, &sst_files, None).await?,
+        };
+
+        Ok(ChunkReaderImpl::new(
+            schema,
+            with_limit(reader, limit),
+            sst_files,
+        ))
+    }
+
+    /// Builds at most `num_partitions` readers that could be read in parallel.
+    ///
+    /// Memtables and SSTs are grouped into time windows that don't overlap, and each
+    /// partition reads some adjacent windows. If there are fewer windows than partitions,
+    /// windows are further split at the boundaries of their SSTs and memtables, and each
+    /// split window only reads rows in its own time range. Rows with the same key are
+    /// always in the same window, so they are still deduplicated. The order and limit
+    /// apply to each partition.
+    pub async fn build_partitions(self, num_partitions: usize) -> Result<Vec<ChunkReaderImpl>> {
+        let (order, limit) = (self.order, self.limit);
+        let (merge_ctx, memtables, sst_files) = self.prune_sources()?;
+        let schema = merge_ctx.schema.clone();
+
+        let windows = split_windows(split_time_windows(memtables, &sst_files), num_partitions);
+        if windows.is_empty() {
+            let reader = merge_ctx.build_reader(&[], &[], None).await?;
+            return Ok(vec![ChunkReaderImpl::new(schema, reader, Vec::new())]);
+        }
+        let num_partitions = num_partitions.clamp(1, windows.len());
+        let windows_per_partition = (windows.len() + num_partitions - 1) / num_partitions;
+
+        let mut readers = Vec::with_capacity(num_partitions);
+        for windows in windows.chunks(windows_per_partition) {
+            let memtables: Vec<_> = windows
+                .iter()
+                .flat_map(|w| w.memtables.iter().cloned())
+                .collect();
+            let files: Vec<_> = windows
+                .iter()
+                .flat_map(|w| w.files.iter().cloned())
+                .collect();
+            let reader: BoxedBatchReader = match order {
+                Some(order) => Box::new(
+                    merge_ctx
+                        .clone()
+                        .build_windowed_reader(windows.to_vec(), order),
+                ),
+                None => match windows {
+                    [window] => {
+                        merge_ctx
+                            .build_reader(&window.memtables, &window.files, window.time_range)
+                            .await?
+                    }
+                    // Windows are only split if each of them has its own partition, so
+                    // these windows are not split. They don't share sources and merging all
+                    // sources of the partition is the same as reading windows one by one.
+                    _ => merge_ctx.build_reader(&memtables, &files, None).await?,
+                },
+            };
+
+            readers.push(ChunkReaderImpl::new(
+                schema.clone(),
+                with_limit(reader, limit),
+                files,
+            ));
+        }
+
+        Ok(readers)
+    }
+
+    /// Prunes memtables and files by the time range to read, returns the context to
+    /// read them and the sources not pruned.
+    fn prune_sources(mut self) -> Result<(MergeContext, Vec<MemtableRef>, Vec<FileHandle>)> {
+        let expire_time = self.ttl.map(sst::expire_time);
+        let mut time_range_predicate = self.build_time_range_predicate();
+        if let Some(expire_time) = expire_time {
+            time_range_predicate =
+                time_range_predicate.and(&TimestampRange::from_start(expire_time));
+        }
         let schema = Arc::new(
             ProjectedSchema::new(self.schema, self.projection)
                 .context(error::InvalidProjectionSnafu)?,
         );
 
-        let num_sources = self.memtables.len() + self.files_to_read.len();
-        let mut reader_builder = MergeReaderBuilder::with_capacity(schema.clone(), num_sources)
-            .batch_size(self.iter_ctx.batch_size);
-
         self.iter_ctx.projected_schema = Some(schema.clone());
+        let mut memtables = Vec::with_capacity(self.memtables.len());
+        let mut num_pruned_memtables = 0;
         for mem in self.memtables {
-            let iter = mem.iter(&self.iter_ctx)?;
-            reader_builder = reader_builder.push_batch_iter(iter);
+            if !memtable_intersects(&mem, &time_range_predicate) {
+                debug!(
+                    "Skip memtable {}, predicate: {:?}",
+                    mem.id(),
+                    time_range_predicate
+                );
+                num_pruned_memtables += 1;
+                continue;
+            }
+            memtables.push(mem);
         }
 
-        let read_opts = ReadOptions {
-            batch_size: self.iter_ctx.batch_size,
-            projected_schema: schema.clone(),
-            predicate: Predicate::new(self.filters),
-        };
-        for file in &self.files_to_read {
-            if !Self::file_in_range(file, time_range_predicate) {
+        let mut sst_files = Vec::with_capacity(self.files_to_read.len());
+        let mut num_pruned_files = 0;
+        for file in self.files_to_read {
+            if !file.intersects(&time_range_predicate) {
                 debug!(
                     "Skip file {:?}, predicate: {:?}",
                     file, time_range_predicate
                 );
+                num_pruned_files += 1;
                 continue;
             }
+            sst_files.push(file);
+        }
+
+        counter!(METRIC_SCAN_SST_FILES_READ, sst_files.len() as u64);
+        counter!(METRIC_SCAN_SST_FILES_PRUNED, num_pruned_files);
+        counter!(METRIC_SCAN_MEMTABLES_PRUNED, num_pruned_memtables);
+
+        let read_opts = ReadOptions {
+            batch_size: self.iter_ctx.batch_size,
+            projected_schema: schema.clone(),
+            predicate: Predicate::new(self.filters),
+        };
+        let merge_ctx = MergeContext {
+            schema,
+            iter_ctx: self.iter_ctx,
+            sst_layer: self.sst_layer,
+            read_opts,
+            filter_deleted: self.filter_deleted,
+            expire_time,
+        };
+
+        Ok((merge_ctx, memtables, sst_files))
+    }
+
+    /// Build time range predicate from schema and filters.
+    pub fn build_time_range_predicate(&self) -> TimestampRange {
+        let Some(ts_col) = self.schema.user_schema().timestamp_column() else { return TimestampRange::min_to_max() };
+        TimeRangePredicateBuilder::new(&ts_col.name, &self.filters).build()
+    }
+}
+
+/// Returns true if the time range of `memtable` intersects with `range`.
+///
+/// Empty memtables never intersect with any range.
+fn memtable_intersects(memtable: &MemtableRef, range: &TimestampRange) -> bool {
+    let Some((start, end)) = memtable.time_range() else { return false };
+    if *range == TimestampRange::min_to_max() {
+        return true;
+    }
+    TimestampRange::new_inclusive(Some(start), Some(end)).intersects(range)
+}
+
+fn with_limit(reader: BoxedBatchReader, limit: Option<usize>) -> BoxedBatchReader {
+    match limit {
+        Some(limit) => Box::new(LimitReader::new(reader, limit)),
+        None => reader,
+    }
+}
+
+/// Context to build readers that merge and dedup rows from memtables and SSTs.
+#[derive(Clone)]
+struct MergeContext {
+    schema: ProjectedSchemaRef,
+    iter_ctx: IterContext,
+    sst_layer: AccessLayerRef,
+    read_opts: ReadOptions,
+    filter_deleted: bool,
+    expire_time: Option<Timestamp>,
+}
+
+impl MergeContext {
+    /// Returns a reader that reads rows from `memtables` and `files` in key order, only
+    /// rows in `time_range` are returned if it is not `None`.
+    async fn build_reader(
+        &self,
+        memtables: &[MemtableRef],
+        files: &[FileHandle],
+        time_range: Option<TimestampRange>,
+    ) -> Result<BoxedBatchReader> {
+        let num_sources = memtables.len() + files.len();
+        let mut reader_builder =
+            MergeReaderBuilder::with_capacity(self.schema.clone(), num_sources)
+                .batch_size(self.iter_ctx.batch_size);
+        for mem in memtables {
+            let iter = mem.iter(&self.iter_ctx)?;
+            reader_builder = reader_builder.push_batch_iter(iter);
+        }
+        for file in files {
             let reader = self
                 .sst_layer
-                .read_sst(file.file_name(), &read_opts)
+                .read_sst(file.file_name(), file.tier(), &self.read_opts)
                 .await?;
-
             reader_builder = reader_builder.push_batch_reader(reader);
         }
 
         let reader = reader_builder.build();
-        let reader = DedupReader::new(schema.clone(), reader);
+        let reader = DedupReader::new(self.schema.clone(), reader)
+            .filter_deleted(self.filter_deleted)
+            .expire_time(self.expire_time)
+            .time_range(time_range);
 
-        Ok(ChunkReaderImpl::new(schema, Box::new(reader)))
+        Ok(Box::new(reader))
     }
 
-    /// Build time range predicate from schema and filters.
-    pub fn build_time_range_predicate(&self) -> TimestampRange {
-        let Some(ts_col) = self.schema.user_schema().timestamp_column() else { return TimestampRange::min_to_max() };
-        TimeRangePredicateBuilder::new(&ts_col.name, &self.filters).build()
+    /// Returns a reader that reads rows from `windows` in timestamp `order`.
+    ///
+    /// `windows` must be sorted by time in ascending order and must not overlap, so rows
+    /// in a window only need to be sorted with other rows in the same window.
+    fn build_windowed_reader(
+        self,
+        mut windows: Vec<TimeWindow>,
+        order: TimeOrder,
+    ) -> WindowedReader {
+        if order == TimeOrder::Desc {
+            windows.reverse();
+        }
+        let schema = self.schema.clone();
+        let batch_size = self.iter_ctx.batch_size;
+        let windows = windows
+            .into_iter()
+            .map(|window| {
+                let ctx = self.clone();
+                async move {
+                    ctx.build_reader(&window.memtables, &window.files, window.time_range)
+                        .await
+                }
+                .boxed()
+            })
+            .collect();
+
+        WindowedReader::new(schema, windows, order, batch_size)
     }
+}
 
-    /// Check if SST file's time range matches predicate.
-    #[inline]
-    fn file_in_range(file: &FileHandle, predicate: TimestampRange) -> bool {
-        if predicate == TimestampRange::min_to_max() {
-            return true;
+/// Memtables and SST files whose time ranges overlap.
+#[derive(Clone, Default)]
+struct TimeWindow {
+    memtables: Vec<MemtableRef>,
+    files: Vec<FileHandle>,
+    /// Time range of rows to read from the sources, `None` to read all rows.
+    time_range: Option<TimestampRange>,
+}
+
+impl TimeWindow {
+    fn push(&mut self, source: WindowSource) {
+        match source {
+            WindowSource::Memtable(mem) => self.memtables.push(mem),
+            WindowSource::File(file) => self.files.push(file),
         }
-        // end_timestamp of sst file is inclusive.
-        let file_ts_range =
-            TimestampRange::new_inclusive(file.start_timestamp(), file.end_timestamp());
-        file_ts_range.intersects(&predicate)
     }
+
+    fn num_sources(&self) -> usize {
+        self.memtables.len() + self.files.len()
+    }
+
+    /// Returns time ranges of all sources, or `None` if any source has no time range.
+    fn source_ranges(&self) -> Option<Vec<TimestampRange>> {
+        self.memtables
+            .iter()
+            .map(|mem| mem.time_range())
+            .chain(self.files.iter().map(|file| file.time_range()))
+            .map(|range| {
+                range.map(|(start, end)| TimestampRange::new_inclusive(Some(start), Some(end)))
+            })
+            .collect()
+    }
+
+    /// Returns the time ranges before and after `point` in this window.
+    fn split_range(&self, point: Timestamp) -> (TimestampRange, TimestampRange) {
+        let range = self.time_range.unwrap_or_else(TimestampRange::min_to_max);
+        (
+            range.and(&TimestampRange::until_end(point, false)),
+            range.and(&TimestampRange::from_start(point)),
+        )
+    }
+
+    /// Returns the median of source boundaries that have sources on both sides, or
+    /// `None` if the window can't be split.
+    fn split_point(&self) -> Option<Timestamp> {
+        let ranges = self.source_ranges()?;
+        let mut points: Vec<_> = ranges
+            .iter()
+            .flat_map(|range| [*range.start(), *range.end()])
+            .flatten()
+            .filter(|point| {
+                let (before, after) = self.split_range(*point);
+                ranges.iter().any(|range| range.intersects(&before))
+                    && ranges.iter().any(|range| range.intersects(&after))
+            })
+            .collect();
+        points.sort_unstable();
+        points.dedup();
+
+        points.get(points.len() / 2).copied()
+    }
+
+    /// Splits the window at `point`, sources in both windows are read by both of them.
+    fn split_at(&self, point: Timestamp) -> (TimeWindow, TimeWindow) {
+        let (before, after) = self.split_range(point);
+        (self.sub_window(before), self.sub_window(after))
+    }
+
+    fn sub_window(&self, range: TimestampRange) -> TimeWindow {
+        TimeWindow {
+            memtables: self
+                .memtables
+                .iter()
+                .filter(|mem| memtable_intersects(mem, &range))
+                .cloned()
+                .collect(),
+            files: self
+                .files
+                .iter()
+                .filter(|file| file.intersects(&range))
+                .cloned()
+                .collect(),
+            time_range: Some(range),
+        }
+    }
+}
+
+enum WindowSource {
+    Memtable(MemtableRef),
+    File(FileHandle),
+}
+
+/// Groups `memtables` and `files` into time windows that don't overlap, windows are
+/// sorted by time in ascending order.
+fn split_time_windows(memtables: Vec<MemtableRef>, files: &[FileHandle]) -> Vec<TimeWindow> {
+    let sources: Vec<_> = memtables
+        .into_iter()
+        .map(|mem| (mem.time_range(), WindowSource::Memtable(mem)))
+        .chain(
+            files
+                .iter()
+                .map(|file| (file.time_range(), WindowSource::File(file.clone()))),
+        )
+        .collect();
+    if sources.iter().any(|(range, _)| range.is_none()) {
+        // A source without time range may overlap with any other source.
+        let mut window = TimeWindow::default();
+        for (_, source) in sources {
+            window.push(source);
+        }
+        return vec![window];
+    }
+
+    // Safety: All sources have time ranges.
+    let mut sources: Vec<_> = sources
+        .into_iter()
+        .map(|(range, source)| (range.unwrap(), source))
+        .collect();
+    sources.sort_by_key(|((start, _), _)| *start);
+
+    let mut windows: Vec<TimeWindow> = Vec::new();
+    let mut window_end = None;
+    for ((start, end), source) in sources {
+        match window_end {
+            Some(current_end) if start <= current_end => {
+                window_end = Some(cmp::max(current_end, end));
+            }
+            _ => {
+                windows.push(TimeWindow::default());
+                window_end = Some(end);
+            }
+        }
+        // Safety: A window is pushed for the first source.
+        windows.last_mut().unwrap().push(source);
+    }
+
+    windows
+}
+
+/// Splits the window with the most sources until there are `num_partitions` windows or
+/// no window could be split, so overlapping memtables and SSTs could still be read in
+/// parallel. Split windows are still sorted by time and don't overlap.
+fn split_windows(mut windows: Vec<TimeWindow>, num_partitions: usize) -> Vec<TimeWindow> {
+    while windows.len() < num_partitions {
+        let Some((index, point)) = windows
+            .iter()
+            .enumerate()
+            .filter_map(|(index, window)| window.split_point().map(|point| (index, window, point)))
+            .max_by_key(|(_, window, _)| window.num_sources())
+            .map(|(index, _, point)| (index, point)) else { break };
+        let (before, after) = windows[index].split_at(point);
+        windows.splice(index..=index, [before, after]);
+    }
+
+    windows
 }
 
 impl Visitor for ChunkReaderBuilder {
     fn visit(&mut self, _level: usize, files: &[FileHandle]) -> Result<()> {
-        // TODO(yingwen): Filter files by time range.
-
-        // Now we read all files, so just reserve enough space to hold all files.
+        // Files are filtered by time range in `build()`, so just reserve enough space
+        // to hold all files.
         self.files_to_read.reserve(files.len());
         for file in files {
             // We can't invoke async functions here, so we collects all files first, and
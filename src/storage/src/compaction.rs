@@ -0,0 +1,29 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Compaction of SST files.
+//!
+//! Flush always writes new SSTs to level 0. Once there are enough files in level 0, the
+//! [CompactionScheduler] runs a background job that groups the files by time window, then
+//! merges and dedups each group (together with level 1 files in the same window) into a
+//! new level 1 file.
+
+mod picker;
+mod scheduler;
+mod task;
+
+pub use picker::SimplePicker;
+pub use scheduler::{
+    CompactionRequestImpl, CompactionScheduler, CompactionSchedulerImpl, CompactionSchedulerRef,
+};
@@ -0,0 +1,347 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::{BTreeMap, HashSet};
+
+use common_time::range::TimestampRange;
+use common_time::timestamp::TimeUnit;
+use common_time::Timestamp;
+
+use crate::sst::{FileHandle, FileTier, LevelMetas};
+
+/// Default number of files in level 0 to trigger a compaction.
+const DEFAULT_MAX_FILES_IN_LEVEL0: usize = 8;
+
+/// Level of the output files of compaction.
+const OUTPUT_LEVEL: u8 = 1;
+
+/// Candidates of time window sizes (in seconds) to group files.
+const TIME_BUCKETS: [i64; 7] = [
+    60 * 60,                 // one hour
+    2 * 60 * 60,             // two hours
+    12 * 60 * 60,            // twelve hours
+    24 * 60 * 60,            // one day
+    7 * 24 * 60 * 60,        // one week
+    365 * 24 * 60 * 60,      // one year
+    10 * 365 * 24 * 60 * 60, // ten years
+];
+
+/// A group of files to merge into one file in `output_level`.
+#[derive(Debug)]
+pub struct CompactionOutput {
+    /// Level of the file to write.
+    pub output_level: u8,
+    /// Inclusive start of the time window (in seconds) of this output.
+    pub bucket_start: i64,
+    /// Size of the time window in seconds.
+    pub bucket: i64,
+    /// Files to merge.
+    pub inputs: Vec<FileHandle>,
+}
+
+/// Picks files to compact by time window.
+///
+/// Files in level 0 are assigned to time windows by their start timestamp, the window size
+/// is the smallest bucket in [TIME_BUCKETS] that could hold the file with the largest time
+/// span. Level 1 files overlapping a window are merged with the level 0 files of that window,
+/// so there is about one level 1 file in each window.
+#[derive(Debug)]
+pub struct SimplePicker {
+    /// Number of files in level 0 to trigger a compaction.
+    max_files_in_level0: usize,
+}
+
+impl Default for SimplePicker {
+    fn default() -> SimplePicker {
+        SimplePicker::new(DEFAULT_MAX_FILES_IN_LEVEL0)
+    }
+}
+
+impl SimplePicker {
+    pub fn new(max_files_in_level0: usize) -> SimplePicker {
+        SimplePicker {
+            max_files_in_level0,
+        }
+    }
+
+    /// Picks files to compact from `levels`, returns an empty vector if there is
+    /// nothing to compact.
+    ///
+    /// Files expired at `expire_time` are never picked as they would be removed directly.
+    /// Files in the cold tier are never picked either, so the merged output, which is
+    /// always written to the hot tier, won't move their rows back to the hot tier.
+    pub fn pick(
+        &self,
+        levels: &LevelMetas,
+        expire_time: Option<Timestamp>,
+    ) -> Vec<CompactionOutput> {
+        let is_candidate = |file: &FileHandle| {
+            file.tier() == FileTier::Hot && expire_time.map(|t| !file.expired(t)).unwrap_or(true)
+        };
+        let level0: Vec<_> = levels
+            .level(0)
+            .files()
+            .iter()
+            .filter(|file| is_candidate(file))
+            .cloned()
+            .collect();
+        if level0.len() < self.max_files_in_level0 {
+            return Vec::new();
+        }
+
+        let bucket = infer_time_bucket(&level0);
+        let mut windows: BTreeMap<i64, Vec<FileHandle>> = BTreeMap::new();
+        for file in level0 {
+            // Files without time range have no rows, we still compact them so they
+            // could be removed from level 0.
+            let start_sec = file
+                .start_timestamp()
+                .and_then(|ts| ts.convert_to(TimeUnit::Second))
+                .map(|ts| ts.value())
+                .unwrap_or(0);
+            let bucket_start = start_sec.div_euclid(bucket) * bucket;
+            windows.entry(bucket_start).or_default().push(file);
+        }
+
+        // A level 1 file may overlap with multiple windows, but it can only be merged
+        // into one output.
+        let mut picked = HashSet::new();
+        let level1 = levels.level(usize::from(OUTPUT_LEVEL));
+        windows
+            .into_iter()
+            .map(|(bucket_start, mut inputs)| {
+                let window = TimestampRange::with_unit(
+                    bucket_start,
+                    bucket_start.saturating_add(bucket),
+                    TimeUnit::Second,
+                )
+                .unwrap_or_else(TimestampRange::min_to_max);
+                for file in level1.files().iter().filter(|file| is_candidate(file)) {
+                    if file.intersects(&window) && picked.insert(file.file_name().to_string()) {
+                        inputs.push(file.clone());
+                    }
+                }
+
+                CompactionOutput {
+                    output_level: OUTPUT_LEVEL,
+                    bucket_start,
+                    bucket,
+                    inputs,
+                }
+            })
+            .collect()
+    }
+}
+
+/// Returns files in all levels whose rows are all older than `expire_time`.
+pub fn expired_ssts(levels: &LevelMetas, expire_time: Timestamp) -> Vec<FileHandle> {
+    levels
+        .levels()
+        .iter()
+        .flat_map(|level| level.files().iter())
+        .filter(|file| file.expired(expire_time))
+        .cloned()
+        .collect()
+}
+
+/// Returns hot files in all levels whose rows are all older than `cold_time`, files
+/// whose names are in `picked` are skipped.
+pub fn cold_ssts(
+    levels: &LevelMetas,
+    cold_time: Timestamp,
+    picked: &HashSet<&str>,
+) -> Vec<FileHandle> {
+    levels
+        .levels()
+        .iter()
+        .flat_map(|level| level.files().iter())
+        .filter(|file| {
+            file.tier() == FileTier::Hot
+                && file.expired(cold_time)
+                && !picked.contains(file.file_name())
+        })
+        .cloned()
+        .collect()
+}
+
+/// Infers the time window size from the largest time span of `files`.
+fn infer_time_bucket(files: &[FileHandle]) -> i64 {
+    let max_span = files
+        .iter()
+        .filter_map(|file| {
+            let (start, end) = file.time_range()?;
+            let start = start.convert_to(TimeUnit::Second)?.value();
+            let end = end.convert_to(TimeUnit::Second)?.value();
+            Some(end - start)
+        })
+        .max()
+        .unwrap_or(0);
+
+    fit_time_bucket(max_span)
+}
+
+/// Returns the smallest bucket that is not less than `span_sec`.
+fn fit_time_bucket(span_sec: i64) -> i64 {
+    TIME_BUCKETS
+        .iter()
+        .copied()
+        .find(|bucket| *bucket >= span_sec)
+        .unwrap_or(TIME_BUCKETS[TIME_BUCKETS.len() - 1])
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::sst::{FileMeta, FileTier};
+    use crate::test_util::access_layer_util::MockAccessLayer;
+
+    fn new_file_meta(name: &str, level: u8, start_sec: i64, end_sec: i64) -> FileMeta {
+        FileMeta {
+            file_name: name.to_string(),
+            start_timestamp: Some(Timestamp::new_millisecond(start_sec * 1000)),
+            end_timestamp: Some(Timestamp::new_millisecond(end_sec * 1000)),
+            level,
+            tier: FileTier::Hot,
+        }
+    }
+
+    fn new_level_metas(files: Vec<FileMeta>) -> LevelMetas {
+        LevelMetas::new(Arc::new(MockAccessLayer::default()))
+            .merge(files.into_iter(), std::iter::empty())
+    }
+
+    fn input_names(output: &CompactionOutput) -> Vec<&str> {
+        output.inputs.iter().map(|f| f.file_name()).collect()
+    }
+
+    #[test]
+    fn test_fit_time_bucket() {
+        assert_eq!(TIME_BUCKETS[0], fit_time_bucket(0));
+        assert_eq!(TIME_BUCKETS[0], fit_time_bucket(3600));
+        assert_eq!(TIME_BUCKETS[1], fit_time_bucket(3601));
+        assert_eq!(TIME_BUCKETS[3], fit_time_bucket(13 * 3600));
+        assert_eq!(TIME_BUCKETS[6], fit_time_bucket(i64::MAX));
+    }
+
+    #[test]
+    fn test_pick_not_enough_files() {
+        let levels = new_level_metas(vec![
+            new_file_meta("a", 0, 0, 10),
+            new_file_meta("b", 0, 0, 10),
+        ]);
+
+        let picker = SimplePicker::new(3);
+        assert!(picker.pick(&levels, None).is_empty());
+    }
+
+    #[test]
+    fn test_pick_by_time_window() {
+        let levels = new_level_metas(vec![
+            new_file_meta("a", 0, 0, 1000),
+            new_file_meta("b", 0, 100, 2000),
+            new_file_meta("c", 0, 3700, 4000),
+            new_file_meta("d", 1, 0, 3599),
+            new_file_meta("e", 1, 3000, 5000),
+            new_file_meta("f", 1, 7200, 8000),
+        ]);
+
+        let picker = SimplePicker::new(3);
+        let outputs = picker.pick(&levels, None);
+        assert_eq!(2, outputs.len());
+
+        assert_eq!(0, outputs[0].bucket_start);
+        assert_eq!(3600, outputs[0].bucket);
+        assert_eq!(1, outputs[0].output_level);
+        assert_eq!(vec!["a", "b", "d", "e"], input_names(&outputs[0]));
+
+        // File `e` has already been picked by the first output.
+        assert_eq!(3600, outputs[1].bucket_start);
+        assert_eq!(vec!["c"], input_names(&outputs[1]));
+    }
+
+    #[test]
+    fn test_pick_skip_expired() {
+        let levels = new_level_metas(vec![
+            new_file_meta("a", 0, 0, 1000),
+            new_file_meta("b", 0, 3600, 4000),
+            new_file_meta("c", 0, 3700, 4000),
+            new_file_meta("d", 1, 0, 1999),
+            new_file_meta("e", 1, 1000, 5000),
+        ]);
+        let expire_time = Timestamp::new_second(2000);
+
+        let expired: Vec<_> = expired_ssts(&levels, expire_time)
+            .iter()
+            .map(|f| f.file_name().to_string())
+            .collect();
+        assert_eq!(vec!["a", "d"], expired);
+
+        // Only 2 files in level 0 are alive.
+        let picker = SimplePicker::new(3);
+        assert!(picker.pick(&levels, Some(expire_time)).is_empty());
+
+        let picker = SimplePicker::new(2);
+        let outputs = picker.pick(&levels, Some(expire_time));
+        assert_eq!(1, outputs.len());
+        assert_eq!(3600, outputs[0].bucket_start);
+        assert_eq!(vec!["b", "c", "e"], input_names(&outputs[0]));
+    }
+
+    #[test]
+    fn test_pick_skip_cold() {
+        let mut cold_level0 = new_file_meta("b", 0, 100, 2000);
+        cold_level0.tier = FileTier::Cold;
+        let mut cold_level1 = new_file_meta("d", 1, 0, 3599);
+        cold_level1.tier = FileTier::Cold;
+        let levels = new_level_metas(vec![
+            new_file_meta("a", 0, 0, 1000),
+            cold_level0,
+            new_file_meta("c", 0, 200, 3000),
+            cold_level1,
+            new_file_meta("e", 1, 3000, 5000),
+        ]);
+
+        // Only 2 hot files in level 0.
+        let picker = SimplePicker::new(3);
+        assert!(picker.pick(&levels, None).is_empty());
+
+        let picker = SimplePicker::new(2);
+        let outputs = picker.pick(&levels, None);
+        assert_eq!(1, outputs.len());
+        assert_eq!(vec!["a", "c", "e"], input_names(&outputs[0]));
+    }
+
+    #[test]
+    fn test_cold_ssts() {
+        let mut cold_file = new_file_meta("c", 1, 0, 1000);
+        cold_file.tier = FileTier::Cold;
+        let levels = new_level_metas(vec![
+            new_file_meta("a", 0, 0, 1000),
+            new_file_meta("b", 0, 0, 3000),
+            cold_file,
+            new_file_meta("d", 1, 0, 1999),
+            new_file_meta("e", 1, 0, 1500),
+        ]);
+        let cold_time = Timestamp::new_second(2000);
+
+        let picked = HashSet::from(["e"]);
+        let cold: Vec<_> = cold_ssts(&levels, cold_time, &picked)
+            .iter()
+            .map(|f| f.file_name().to_string())
+            .collect();
+        assert_eq!(vec!["a", "d"], cold);
+    }
+}
@@ -0,0 +1,243 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use common_telemetry::logging;
+use store_api::logstore::LogStore;
+use store_api::storage::RegionId;
+use tokio::sync::watch;
+
+use crate::background::{Context, Job, JobHandle, JobPoolRef};
+use crate::compaction::picker::{self, SimplePicker};
+use crate::compaction::task::CompactionTask;
+use crate::error::Result;
+use crate::manifest::region::RegionManifest;
+use crate::region::{RegionWriterRef, SharedDataRef};
+use crate::sst::{self, AccessLayerRef};
+use crate::wal::Wal;
+
+/// Request to compact a region.
+pub struct CompactionRequestImpl<S: LogStore> {
+    /// Shared data of region to be compacted.
+    pub shared: SharedDataRef,
+    /// Sst access layer of the region.
+    pub sst_layer: AccessLayerRef,
+    /// Region writer, used to apply the edit to the region.
+    pub writer: RegionWriterRef,
+    /// Region write-ahead logging, used to persist the manifest version.
+    pub wal: Wal<S>,
+    /// Region manifest service, used to persist the edit.
+    pub manifest: RegionManifest,
+}
+
+impl<S: LogStore> CompactionRequestImpl<S> {
+    #[inline]
+    pub fn region_id(&self) -> RegionId {
+        self.shared.id()
+    }
+}
+
+#[async_trait]
+pub trait CompactionScheduler<S: LogStore>: Send + Sync + std::fmt::Debug {
+    /// Schedules a compaction for the region in `request`.
+    ///
+    /// Returns `None` if there is already a compaction job of the same region.
+    async fn schedule_compaction(
+        &self,
+        request: CompactionRequestImpl<S>,
+    ) -> Result<Option<JobHandle>>;
+
+    /// Cancels the compaction job of the region with `region_id` and waits until the
+    /// job exits. Does nothing if the region has no compaction job.
+    async fn stop_compaction(&self, region_id: RegionId) -> Result<()>;
+}
+
+pub type CompactionSchedulerRef<S> = Arc<dyn CompactionScheduler<S>>;
+
+/// A compaction job that is scheduled but not finished.
+#[derive(Debug)]
+struct RunningJob {
+    ctx: Context,
+    /// Its sender is held by the job, so the sender is dropped once the job exits.
+    exit_receiver: watch::Receiver<()>,
+}
+
+/// Compaction jobs of regions, each region has at most one job.
+type RunningRegions = Arc<Mutex<HashMap<RegionId, RunningJob>>>;
+
+#[derive(Debug)]
+pub struct CompactionSchedulerImpl {
+    job_pool: JobPoolRef,
+    picker: Arc<SimplePicker>,
+    running: RunningRegions,
+    /// Files whose rows are all older than this age are moved to the cold tier.
+    cold_sst_age: Option<Duration>,
+}
+
+impl CompactionSchedulerImpl {
+    pub fn new(job_pool: JobPoolRef, picker: SimplePicker) -> CompactionSchedulerImpl {
+        CompactionSchedulerImpl {
+            job_pool,
+            picker: Arc::new(picker),
+            running: Arc::new(Mutex::new(HashMap::new())),
+            cold_sst_age: None,
+        }
+    }
+
+    /// Moves files whose rows are all older than `cold_sst_age` to the cold tier, if
+    /// the region supports it. `None` disables the tiering.
+    pub fn with_cold_sst_age(mut self, cold_sst_age: Option<Duration>) -> Self {
+        self.cold_sst_age = cold_sst_age;
+        self
+    }
+}
+
+#[async_trait]
+impl<S: LogStore> CompactionScheduler<S> for CompactionSchedulerImpl {
+    async fn schedule_compaction(
+        &self,
+        request: CompactionRequestImpl<S>,
+    ) -> Result<Option<JobHandle>> {
+        let region_id = request.region_id();
+        let ctx = Context::default();
+        let (exit_sender, exit_receiver) = watch::channel(());
+        {
+            // Only allow one compaction job for each region, so jobs of the same region
+            // won't pick the same files.
+            let mut running = self.running.lock().unwrap();
+            // Check under the lock so no job starts after the region stops its compaction
+            // on dropping.
+            if request.shared.is_dropped() {
+                logging::debug!("Region {} is dropped, skip compaction", region_id);
+                return Ok(None);
+            }
+            if running.contains_key(&region_id) {
+                logging::debug!("Region {} is already under compaction", region_id);
+                return Ok(None);
+            }
+            running.insert(
+                region_id,
+                RunningJob {
+                    ctx: ctx.clone(),
+                    exit_receiver,
+                },
+            );
+        }
+
+        let job = CompactionJob {
+            request: Some(request),
+            picker: self.picker.clone(),
+            running: self.running.clone(),
+            cold_sst_age: self.cold_sst_age,
+            ctx,
+            _exit_sender: exit_sender,
+        };
+        match self.job_pool.submit(Box::new(job)).await {
+            Ok(handle) => Ok(Some(handle)),
+            Err(e) => {
+                self.running.lock().unwrap().remove(&region_id);
+                Err(e)
+            }
+        }
+    }
+
+    async fn stop_compaction(&self, region_id: RegionId) -> Result<()> {
+        let mut exit_receiver = {
+            let running = self.running.lock().unwrap();
+            let Some(job) = running.get(&region_id) else { return Ok(()) };
+            job.ctx.cancel();
+            job.exit_receiver.clone()
+        };
+
+        logging::info!("Wait for compaction job of region {} to exit", region_id);
+        // The job never sends anything, so `changed()` only returns an error once the
+        // sender is dropped.
+        while exit_receiver.changed().await.is_ok() {}
+
+        Ok(())
+    }
+}
+
+/// Background job that picks files of a region and compacts them.
+struct CompactionJob<S: LogStore> {
+    request: Option<CompactionRequestImpl<S>>,
+    picker: Arc<SimplePicker>,
+    running: RunningRegions,
+    cold_sst_age: Option<Duration>,
+    /// Context to cancel the job, shared with the scheduler.
+    ctx: Context,
+    _exit_sender: watch::Sender<()>,
+}
+
+impl<S: LogStore> CompactionJob<S> {
+    async fn compact(&self, ctx: &Context, request: CompactionRequestImpl<S>) -> Result<()> {
+        let version = request.shared.version_control.current();
+        let expire_time = request.shared.ttl().map(sst::expire_time);
+        let expired_ssts = expire_time
+            .map(|expire_time| picker::expired_ssts(version.ssts(), expire_time))
+            .unwrap_or_default();
+        let outputs = self.picker.pick(version.ssts(), expire_time);
+        let cold_ssts = match self.cold_sst_age {
+            Some(age) if request.sst_layer.has_cold_tier() => {
+                let picked: HashSet<_> = outputs
+                    .iter()
+                    .flat_map(|output| output.inputs.iter())
+                    .chain(expired_ssts.iter())
+                    .map(|file| file.file_name())
+                    .collect();
+                picker::cold_ssts(version.ssts(), sst::expire_time(age), &picked)
+            }
+            _ => Vec::new(),
+        };
+        if outputs.is_empty() && expired_ssts.is_empty() && cold_ssts.is_empty() {
+            logging::debug!("No files to compact in region {}", request.shared.name());
+            return Ok(());
+        }
+
+        let task = CompactionTask {
+            schema: version.schema().clone(),
+            outputs,
+            expired_ssts,
+            cold_ssts,
+            request,
+        };
+        task.run(ctx).await
+    }
+}
+
+#[async_trait]
+impl<S: LogStore> Job for CompactionJob<S> {
+    async fn run(&mut self, _ctx: &Context) -> Result<()> {
+        // The job runs only once, so it's safe to take the request.
+        let Some(request) = self.request.take() else { return Ok(()) };
+        let region_id = request.region_id();
+        let region_name = request.shared.name().to_string();
+
+        // Uses the context shared with the scheduler so the job could be stopped by
+        // the region.
+        let ctx = self.ctx.clone();
+        let result = self.compact(&ctx, request).await;
+        self.running.lock().unwrap().remove(&region_id);
+
+        if let Err(e) = &result {
+            logging::error!(e; "Failed to compact region: {}", region_name);
+        }
+
+        result
+    }
+}
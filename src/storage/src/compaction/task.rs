@@ -0,0 +1,174 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common_telemetry::logging;
+use store_api::logstore::LogStore;
+use store_api::storage::consts::WRITE_ROW_GROUP_SIZE;
+
+use crate::background::Context;
+use crate::chunk::ChunkReaderBuilder;
+use crate::compaction::picker::CompactionOutput;
+use crate::compaction::scheduler::CompactionRequestImpl;
+use crate::error::{CancelledSnafu, Result};
+use crate::manifest::action::RegionEdit;
+use crate::schema::RegionSchemaRef;
+use crate::sst::{self, FileHandle, FileMeta, FileTier, Source, SstInfo, WriteOptions};
+
+/// Task to merge the picked files and apply the result to the region.
+pub struct CompactionTask<S: LogStore> {
+    /// Schema of the output files.
+    pub schema: RegionSchemaRef,
+    /// Files to merge.
+    pub outputs: Vec<CompactionOutput>,
+    /// Files whose rows are all expired, they are removed without merging.
+    pub expired_ssts: Vec<FileHandle>,
+    /// Hot files to move to the cold tier.
+    pub cold_ssts: Vec<FileHandle>,
+    /// The region to compact.
+    pub request: CompactionRequestImpl<S>,
+}
+
+impl<S: LogStore> CompactionTask<S> {
+    /// Merges input files and writes the edit to the region.
+    pub async fn run(&self, ctx: &Context) -> Result<()> {
+        let mut files_to_add = self.merge_ssts(ctx).await?;
+        files_to_add.extend(self.move_ssts_to_cold(ctx).await?);
+        // Hot files moved to the cold tier are also removed, so they would be purged
+        // from the hot tier.
+        let files_to_remove: Vec<_> = self
+            .outputs
+            .iter()
+            .flat_map(|output| output.inputs.iter())
+            .chain(self.expired_ssts.iter())
+            .chain(self.cold_ssts.iter())
+            .map(|file| file.meta())
+            .collect();
+
+        // The region may be dropped during the compaction, the edit shouldn't be applied
+        // to a dropped region.
+        if ctx.is_cancelled() {
+            return CancelledSnafu {}.fail();
+        }
+
+        logging::info!(
+            "Compaction of region {} finished, files_to_add: {:?}, files_to_remove: {:?}",
+            self.request.shared.name(),
+            files_to_add,
+            files_to_remove
+        );
+
+        self.write_manifest_and_apply(files_to_add, files_to_remove)
+            .await
+    }
+
+    async fn merge_ssts(&self, ctx: &Context) -> Result<Vec<FileMeta>> {
+        if ctx.is_cancelled() {
+            return CancelledSnafu {}.fail();
+        }
+
+        let futures = self
+            .outputs
+            .iter()
+            .map(|output| output.build(&self.schema, &self.request));
+
+        futures_util::future::join_all(futures)
+            .await
+            .into_iter()
+            .collect()
+    }
+
+    /// Copies `cold_ssts` to the cold tier, returns metas of the copied files.
+    async fn move_ssts_to_cold(&self, ctx: &Context) -> Result<Vec<FileMeta>> {
+        if ctx.is_cancelled() {
+            return CancelledSnafu {}.fail();
+        }
+
+        let sst_layer = &self.request.sst_layer;
+        let futures = self.cold_ssts.iter().map(|file| async move {
+            sst_layer.copy_sst_to_cold(file.file_name()).await?;
+            Ok(FileMeta {
+                tier: FileTier::Cold,
+                ..file.meta()
+            })
+        });
+
+        futures_util::future::join_all(futures)
+            .await
+            .into_iter()
+            .collect()
+    }
+
+    async fn write_manifest_and_apply(
+        &self,
+        files_to_add: Vec<FileMeta>,
+        files_to_remove: Vec<FileMeta>,
+    ) -> Result<()> {
+        let request = &self.request;
+        let edit = RegionEdit {
+            region_version: request.shared.version_control.metadata().version(),
+            flushed_sequence: None,
+            files_to_add,
+            files_to_remove,
+        };
+
+        request
+            .writer
+            .write_edit_and_apply(&request.wal, &request.shared, &request.manifest, edit, None)
+            .await
+    }
+}
+
+impl CompactionOutput {
+    /// Merges and dedups the input files, then writes the rows into a new file.
+    async fn build<S: LogStore>(
+        &self,
+        schema: &RegionSchemaRef,
+        request: &CompactionRequestImpl<S>,
+    ) -> Result<FileMeta> {
+        let sst_layer = &request.sst_layer;
+        logging::debug!(
+            "Compact files {:?} in time window [{}, {}) to level {}",
+            self.inputs,
+            self.bucket_start,
+            self.bucket_start.saturating_add(self.bucket),
+            self.output_level
+        );
+
+        // Keep the deletion markers in the output as there may be older versions of the
+        // deleted keys in files not picked by this compaction. Expired rows are dropped.
+        let reader = ChunkReaderBuilder::new(schema.clone(), sst_layer.clone())
+            .batch_size(WRITE_ROW_GROUP_SIZE)
+            .pick_files(&self.inputs)
+            .filter_deleted(false)
+            .ttl(request.shared.ttl())
+            .build()
+            .await?;
+
+        let file_name = sst::generate_sst_file_name();
+        let SstInfo {
+            start_timestamp,
+            end_timestamp,
+        } = sst_layer
+            .write_sst(&file_name, Source::Reader(reader), &WriteOptions::default())
+            .await?;
+
+        Ok(FileMeta {
+            file_name,
+            start_timestamp,
+            end_timestamp,
+            level: self.output_level,
+            tier: FileTier::Hot,
+        })
+    }
+}
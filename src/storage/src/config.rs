@@ -14,5 +14,77 @@
 
 //! storage engine config
 
-#[derive(Debug, Default, Clone)]
-pub struct EngineConfig {}
+use std::time::Duration;
+
+use common_base::readable_size::ReadableSize;
+use serde::{Deserialize, Serialize};
+
+/// Default write buffer size of a region (32M).
+pub const DEFAULT_MAX_WRITE_BUFFER_SIZE: ReadableSize = ReadableSize::mb(32);
+/// Default max number of flush jobs running concurrently.
+pub const DEFAULT_MAX_FLUSH_TASKS: usize = 8;
+/// Default interval to flush regions automatically (1 hour).
+pub const DEFAULT_AUTO_FLUSH_INTERVAL: Duration = Duration::from_secs(60 * 60);
+/// Default interval to compact regions in background (10 minutes).
+pub const DEFAULT_COMPACTION_CHECK_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+/// Compression algorithm of WAL payloads.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WalCompression {
+    #[default]
+    None,
+    Zstd,
+    Lz4,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EngineConfig {
+    /// Max size of all memtables (mutable and immutable) of a region.
+    pub max_write_buffer_size: ReadableSize,
+    /// Size of the mutable memtable of a region that triggers a flush, defaults to
+    /// 7/8 of `max_write_buffer_size`.
+    pub mutable_memtable_size: Option<ReadableSize>,
+    /// Max size of memtables of all regions. Once exceeded, writes to a region trigger
+    /// a flush and stall until the previous flush of that region is done. `None` means
+    /// no limit.
+    pub global_write_buffer_size: Option<ReadableSize>,
+    /// Max number of flush jobs running concurrently.
+    pub max_flush_tasks: usize,
+    /// Regions that have not been flushed for this interval are flushed in background
+    /// if they have data in the mutable memtable. `None` disables the automatic flush.
+    #[serde(with = "humantime_serde")]
+    pub auto_flush_interval: Option<Duration>,
+    /// Interval to schedule compactions for regions in background, so SSTs expired by
+    /// the TTL of a region are removed and SSTs older than `cold_sst_age` are moved to
+    /// the cold storage even if the region has no new flush. `None` disables the
+    /// periodic compaction.
+    #[serde(with = "humantime_serde")]
+    pub compaction_check_interval: Option<Duration>,
+    /// SSTs whose rows are all older than this age are moved to the cold storage of the
+    /// engine. `None` disables the tiering, it is also disabled if there is no cold storage.
+    #[serde(with = "humantime_serde")]
+    pub cold_sst_age: Option<Duration>,
+    /// Compression of payloads written to the WAL.
+    pub wal_compression: WalCompression,
+    /// Whether to coalesce WAL entries of concurrent writes to all regions into one
+    /// append to the log store.
+    pub wal_group_commit: bool,
+}
+
+impl Default for EngineConfig {
+    fn default() -> Self {
+        Self {
+            max_write_buffer_size: DEFAULT_MAX_WRITE_BUFFER_SIZE,
+            mutable_memtable_size: None,
+            global_write_buffer_size: None,
+            max_flush_tasks: DEFAULT_MAX_FLUSH_TASKS,
+            auto_flush_interval: Some(DEFAULT_AUTO_FLUSH_INTERVAL),
+            compaction_check_interval: Some(DEFAULT_COMPACTION_CHECK_INTERVAL),
+            cold_sst_age: None,
+            wal_compression: WalCompression::None,
+            wal_group_commit: false,
+        }
+    }
+}
@@ -13,26 +13,33 @@
 // limitations under the License.
 
 use std::collections::HashMap;
-use std::sync::{Arc, RwLock};
+use std::sync::{Arc, RwLock, Weak};
+use std::time::Duration;
 
 use async_trait::async_trait;
-use common_telemetry::logging::info;
+use common_telemetry::logging::{self, info};
 use object_store::{util, ObjectStore};
-use snafu::ResultExt;
+use snafu::{OptionExt, ResultExt};
 use store_api::logstore::LogStore;
 use store_api::storage::{
-    CreateOptions, EngineContext, OpenOptions, RegionDescriptor, StorageEngine,
+    CreateOptions, EngineContext, MemtableType, OpenOptions, Region, RegionDescriptor, RegionId,
+    StorageEngine,
 };
 
 use crate::background::JobPoolImpl;
-use crate::config::EngineConfig;
+use crate::backup::{BackupLocation, RegionBackup};
+use crate::compaction::{CompactionSchedulerImpl, CompactionSchedulerRef, SimplePicker};
+use crate::config::{EngineConfig, WalCompression};
 use crate::error::{self, Error, Result};
 use crate::flush::{FlushSchedulerImpl, FlushSchedulerRef, FlushStrategyRef, SizeBasedStrategy};
 use crate::manifest::region::RegionManifest;
-use crate::memtable::{DefaultMemtableBuilder, MemtableBuilderRef};
+use crate::memtable::{
+    DefaultMemtableBuilder, MemtableBuilderRef, SeriesMemtableBuilder, WriteBufferManager,
+};
 use crate::metadata::RegionMetadata;
 use crate::region::{RegionImpl, StoreConfig};
 use crate::sst::FsAccessLayer;
+use crate::wal::{GroupCommitter, GroupCommitterRef};
 
 /// [StorageEngine] implementation.
 pub struct EngineImpl<S: LogStore> {
@@ -74,8 +81,8 @@ impl<S: LogStore> StorageEngine for EngineImpl<S> {
         self.inner.create_region(descriptor, opts).await
     }
 
-    async fn drop_region(&self, _ctx: &EngineContext, _region: Self::Region) -> Result<()> {
-        unimplemented!()
+    async fn drop_region(&self, _ctx: &EngineContext, region: Self::Region) -> Result<()> {
+        self.inner.drop_region(region).await
     }
 
     fn get_region(&self, _ctx: &EngineContext, name: &str) -> Result<Option<Self::Region>> {
@@ -85,10 +92,100 @@ impl<S: LogStore> StorageEngine for EngineImpl<S> {
 
 impl<S: LogStore> EngineImpl<S> {
     pub fn new(config: EngineConfig, log_store: Arc<S>, object_store: ObjectStore) -> Self {
-        Self {
-            inner: Arc::new(EngineInner::new(config, log_store, object_store)),
+        Self::with_cold_store(config, log_store, object_store, None)
+    }
+
+    /// Creates an engine that moves SSTs older than [EngineConfig::cold_sst_age] from
+    /// `object_store` to `cold_store`.
+    pub fn with_cold_store(
+        config: EngineConfig,
+        log_store: Arc<S>,
+        object_store: ObjectStore,
+        cold_store: Option<ObjectStore>,
+    ) -> Self {
+        let auto_flush_interval = config.auto_flush_interval;
+        let compaction_check_interval = config.compaction_check_interval;
+        let inner = Arc::new(EngineInner::new(
+            config,
+            log_store,
+            object_store,
+            cold_store,
+        ));
+        if let Some(interval) = auto_flush_interval {
+            start_auto_flush(Arc::downgrade(&inner), interval);
+        }
+        if let Some(interval) = compaction_check_interval {
+            start_periodic_compaction(Arc::downgrade(&inner), interval);
         }
+
+        Self { inner }
     }
+
+    /// Restores the region backup in `source` as a new region with `id` and `name`, then
+    /// opens the new region by `opts`.
+    ///
+    /// The backup is usually exported by [RegionImpl::export].
+    pub async fn restore_region(
+        &self,
+        _ctx: &EngineContext,
+        source: &BackupLocation,
+        id: RegionId,
+        name: &str,
+        opts: &OpenOptions,
+    ) -> Result<RegionImpl<S>> {
+        self.inner.restore_region(source, id, name, opts).await
+    }
+}
+
+/// Starts a background task that periodically flushes regions not flushed for `interval`.
+///
+/// The task exits once the engine is dropped.
+fn start_auto_flush<S: LogStore>(engine: Weak<EngineInner<S>>, interval: Duration) {
+    // Check regions more frequently than the flush interval so a region won't stay
+    // unflushed for much longer than `interval`.
+    let period = (interval / 2).max(Duration::from_secs(1));
+    let _handle = common_runtime::spawn_bg(async move {
+        let mut ticker = tokio::time::interval(period);
+        // The first tick completes immediately.
+        ticker.tick().await;
+        loop {
+            ticker.tick().await;
+
+            let Some(inner) = engine.upgrade() else {
+                info!("Storage engine dropped, stop auto flush");
+                return;
+            };
+            for region in inner.ready_regions() {
+                if let Err(e) = region.flush_if_idle(interval).await {
+                    logging::error!(e; "Failed to auto flush region {}", region.name());
+                }
+            }
+        }
+    });
+}
+
+/// Starts a background task that schedules compactions for regions every `interval`.
+///
+/// The task exits once the engine is dropped.
+fn start_periodic_compaction<S: LogStore>(engine: Weak<EngineInner<S>>, interval: Duration) {
+    let _handle = common_runtime::spawn_bg(async move {
+        let mut ticker = tokio::time::interval(interval);
+        // The first tick completes immediately.
+        ticker.tick().await;
+        loop {
+            ticker.tick().await;
+
+            let Some(inner) = engine.upgrade() else {
+                info!("Storage engine dropped, stop periodic compaction");
+                return;
+            };
+            for region in inner.ready_regions() {
+                if let Err(e) = region.compact_periodically().await {
+                    logging::error!(e; "Failed to schedule compaction for region {}", region.name());
+                }
+            }
+        }
+    });
 }
 
 /// Generate region sst path,
@@ -205,25 +302,58 @@ type RegionMap<S> = HashMap<String, RegionSlot<S>>;
 
 struct EngineInner<S: LogStore> {
     object_store: ObjectStore,
+    /// Object store of SSTs in the cold tier.
+    cold_store: Option<ObjectStore>,
     log_store: Arc<S>,
     regions: RwLock<RegionMap<S>>,
     memtable_builder: MemtableBuilderRef,
+    series_memtable_builder: MemtableBuilderRef,
     flush_scheduler: FlushSchedulerRef,
     flush_strategy: FlushStrategyRef,
+    compaction_scheduler: CompactionSchedulerRef<S>,
+    wal_compression: WalCompression,
+    wal_group_committer: Option<GroupCommitterRef<S>>,
 }
 
 impl<S: LogStore> EngineInner<S> {
-    pub fn new(_config: EngineConfig, log_store: Arc<S>, object_store: ObjectStore) -> Self {
+    pub fn new(
+        config: EngineConfig,
+        log_store: Arc<S>,
+        object_store: ObjectStore,
+        cold_store: Option<ObjectStore>,
+    ) -> Self {
         let job_pool = Arc::new(JobPoolImpl {});
-        let flush_scheduler = Arc::new(FlushSchedulerImpl::new(job_pool));
+        let flush_scheduler = Arc::new(FlushSchedulerImpl::new(
+            job_pool.clone(),
+            config.max_flush_tasks,
+        ));
+        let compaction_scheduler = Arc::new(
+            CompactionSchedulerImpl::new(job_pool, SimplePicker::default())
+                .with_cold_sst_age(cold_store.as_ref().and(config.cold_sst_age)),
+        );
+        let write_buffer_manager = Arc::new(WriteBufferManager::new(
+            config.global_write_buffer_size.map(|size| size.0 as usize),
+        ));
+        let wal_group_committer = config
+            .wal_group_commit
+            .then(|| Arc::new(GroupCommitter::new(log_store.clone())));
 
         Self {
             object_store,
+            cold_store,
             log_store,
             regions: RwLock::new(Default::default()),
-            memtable_builder: Arc::new(DefaultMemtableBuilder::default()),
+            memtable_builder: Arc::new(DefaultMemtableBuilder::with_write_buffer_manager(
+                write_buffer_manager.clone(),
+            )),
+            series_memtable_builder: Arc::new(SeriesMemtableBuilder::with_write_buffer_manager(
+                write_buffer_manager.clone(),
+            )),
             flush_scheduler,
-            flush_strategy: Arc::new(SizeBasedStrategy::default()),
+            flush_strategy: Arc::new(SizeBasedStrategy::new(&config, Some(write_buffer_manager))),
+            compaction_scheduler,
+            wal_compression: config.wal_compression,
+            wal_group_committer,
         }
     }
 
@@ -259,7 +389,8 @@ impl<S: LogStore> EngineInner<S> {
 
         let mut guard = SlotGuard::new(name, &self.regions);
 
-        let store_config = self.region_store_config(&opts.parent_dir, name);
+        let store_config =
+            self.region_store_config(&opts.parent_dir, opts.ttl, opts.memtable_type, name);
 
         let region = match RegionImpl::open(name.to_string(), store_config, opts).await? {
             None => return Ok(None),
@@ -289,7 +420,8 @@ impl<S: LogStore> EngineInner<S> {
                 .context(error::InvalidRegionDescSnafu {
                     region: &region_name,
                 })?;
-        let store_config = self.region_store_config(&opts.parent_dir, &region_name);
+        let store_config =
+            self.region_store_config(&opts.parent_dir, opts.ttl, opts.memtable_type, &region_name);
 
         let region = RegionImpl::create(metadata, store_config).await?;
 
@@ -300,26 +432,105 @@ impl<S: LogStore> EngineInner<S> {
         Ok(region)
     }
 
+    async fn restore_region(
+        &self,
+        source: &BackupLocation,
+        id: RegionId,
+        name: &str,
+        opts: &OpenOptions,
+    ) -> Result<RegionImpl<S>> {
+        if self.get_or_occupy_slot(name, RegionSlot::Opening).is_some() {
+            return error::RegionExistsSnafu { region: name }.fail();
+        }
+
+        let mut guard = SlotGuard::new(name, &self.regions);
+
+        let mut backup = RegionBackup::load(source)
+            .await?
+            .context(error::BackupNotFoundSnafu {
+                dir: source.sst_dir(),
+            })?;
+        let target = BackupLocation::new(self.object_store.clone(), &opts.parent_dir, name);
+        target.ensure_absent().await?;
+        backup.copy_files(source, &target).await?;
+        backup.rename(id, name);
+        backup.write_manifest(&target).await?;
+
+        let store_config =
+            self.region_store_config(&opts.parent_dir, opts.ttl, opts.memtable_type, name);
+        let region = RegionImpl::open(name.to_string(), store_config, opts)
+            .await?
+            .context(error::BackupNotFoundSnafu {
+                dir: target.sst_dir(),
+            })?;
+        guard.update(RegionSlot::Ready(region.clone()));
+
+        info!(
+            "Storage engine restore region {} from {:?}, flushed sequence: {}",
+            region.id(),
+            source,
+            backup.flushed_sequence
+        );
+
+        Ok(region)
+    }
+
+    async fn drop_region(&self, region: RegionImpl<S>) -> Result<()> {
+        region.drop_region().await?;
+
+        self.regions.write().unwrap().remove(region.name());
+
+        info!("Storage engine drop region {}", region.id());
+
+        Ok(())
+    }
+
     fn get_region(&self, name: &str) -> Option<RegionImpl<S>> {
         let slot = self.regions.read().unwrap().get(name).cloned()?;
         slot.get_ready_region()
     }
 
-    fn region_store_config(&self, parent_dir: &str, region_name: &str) -> StoreConfig<S> {
+    /// Returns all regions that are ready for access.
+    fn ready_regions(&self) -> Vec<RegionImpl<S>> {
+        let regions = self.regions.read().unwrap();
+        regions
+            .values()
+            .filter_map(|slot| slot.get_ready_region())
+            .collect()
+    }
+
+    fn region_store_config(
+        &self,
+        parent_dir: &str,
+        ttl: Option<Duration>,
+        memtable_type: MemtableType,
+        region_name: &str,
+    ) -> StoreConfig<S> {
         let parent_dir = util::normalize_dir(parent_dir);
 
         let sst_dir = &region_sst_dir(&parent_dir, region_name);
-        let sst_layer = Arc::new(FsAccessLayer::new(sst_dir, self.object_store.clone()));
+        let sst_layer = Arc::new(
+            FsAccessLayer::new(sst_dir, self.object_store.clone())
+                .with_cold_store(self.cold_store.clone()),
+        );
         let manifest_dir = region_manifest_dir(&parent_dir, region_name);
         let manifest = RegionManifest::new(&manifest_dir, self.object_store.clone());
+        let memtable_builder = match memtable_type {
+            MemtableType::BTree => self.memtable_builder.clone(),
+            MemtableType::Series => self.series_memtable_builder.clone(),
+        };
 
         StoreConfig {
             log_store: self.log_store.clone(),
             sst_layer,
             manifest,
-            memtable_builder: self.memtable_builder.clone(),
+            memtable_builder,
             flush_scheduler: self.flush_scheduler.clone(),
             flush_strategy: self.flush_strategy.clone(),
+            compaction_scheduler: self.compaction_scheduler.clone(),
+            ttl,
+            wal_compression: self.wal_compression,
+            wal_group_committer: self.wal_group_committer.clone(),
         }
     }
 }
@@ -329,7 +540,6 @@ mod tests {
     use datatypes::type_id::LogicalTypeId;
     use log_store::test_util::log_store_util;
     use object_store::backend::fs::Builder;
-    use store_api::storage::Region;
     use tempdir::TempDir;
 
     use super::*;
@@ -367,4 +577,51 @@ mod tests {
 
         assert!(engine.get_region(&ctx, "no such region").unwrap().is_none());
     }
+
+    #[tokio::test]
+    async fn test_drop_region() {
+        let (log_store, _tmp) =
+            log_store_util::create_tmp_local_file_log_store("test_engine_wal").await;
+        let dir = TempDir::new("test_drop_region").unwrap();
+        let store_dir = dir.path().to_string_lossy();
+
+        let accessor = Builder::default().root(&store_dir).build().unwrap();
+        let object_store = ObjectStore::new(accessor);
+
+        let engine = EngineImpl::new(
+            EngineConfig::default(),
+            Arc::new(log_store),
+            object_store.clone(),
+        );
+
+        let region_name = "region-0";
+        let desc = RegionDescBuilder::new(region_name)
+            .push_key_column(("k1", LogicalTypeId::Int32, false))
+            .push_value_column(("v1", LogicalTypeId::Float32, true))
+            .build();
+        let ctx = EngineContext::default();
+        let region = engine
+            .create_region(&ctx, desc, &CreateOptions::default())
+            .await
+            .unwrap();
+
+        let manifest_dir = region_manifest_dir("", region_name);
+        let manifest = object_store.object(&manifest_dir);
+        assert!(manifest.is_exist().await.unwrap());
+
+        engine.drop_region(&ctx, region).await.unwrap();
+
+        assert!(engine.get_region(&ctx, region_name).unwrap().is_none());
+        assert!(!manifest.is_exist().await.unwrap());
+
+        // The region directory is removed in background.
+        let region_dir = object_store.object(&region_sst_dir("", region_name));
+        for _ in 0..100 {
+            if !region_dir.is_exist().await.unwrap() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert!(!region_dir.is_exist().await.unwrap());
+    }
 }
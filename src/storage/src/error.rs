@@ -146,6 +146,12 @@ pub enum Error {
     #[snafu(display("Task already cancelled"))]
     Cancelled { backtrace: Backtrace },
 
+    #[snafu(display("Cold storage is not configured, failed to access SST {}", file_name))]
+    ColdStoreNotFound {
+        file_name: String,
+        backtrace: Backtrace,
+    },
+
     #[snafu(display(
         "Manifest protocol forbid to read, min_version: {}, supported_version: {}",
         min_version,
@@ -212,6 +218,34 @@ pub enum Error {
         backtrace: Backtrace,
     },
 
+    #[snafu(display(
+        "Failed to compress WAL payload, region_id: {}, source: {}",
+        region_id,
+        source
+    ))]
+    CompressWal {
+        region_id: RegionId,
+        source: std::io::Error,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display(
+        "Failed to decompress WAL payload, region_id: {}, message: {}",
+        region_id,
+        message
+    ))]
+    DecompressWal {
+        region_id: RegionId,
+        message: String,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Failed to commit WAL entries in group, message: {}", message))]
+    GroupCommitWal {
+        message: String,
+        backtrace: Backtrace,
+    },
+
     #[snafu(display(
         "Sequence of region should increase monotonically (should be {} < {})",
         prev,
@@ -412,6 +446,31 @@ pub enum Error {
 
     #[snafu(display("Failed to decode parquet file time range, msg: {}", msg))]
     DecodeParquetTimeRange { msg: String, backtrace: Backtrace },
+
+    #[snafu(display("Region already exists in dir: {}", dir))]
+    RegionExistsInDir { dir: String, backtrace: Backtrace },
+
+    #[snafu(display("Region backup not found in dir: {}", dir))]
+    BackupNotFound { dir: String, backtrace: Backtrace },
+
+    #[snafu(display("Missing value of key column {} in the get request", column))]
+    MissingKeyValue {
+        column: String,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Invalid value of key column {}, source: {}", column, source))]
+    InvalidKeyValue {
+        column: String,
+        #[snafu(backtrace)]
+        source: datatypes::error::Error,
+    },
+
+    #[snafu(display("Region {} already exists", region))]
+    RegionExists {
+        region: String,
+        backtrace: Backtrace,
+    },
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -432,16 +491,24 @@ impl ErrorExt for Error {
             | TypeMismatch { .. }
             | HasNull { .. }
             | UnequalLengths { .. }
-            | MoreColumnThanExpected { .. } => StatusCode::InvalidArguments,
+            | MoreColumnThanExpected { .. }
+            | RegionExistsInDir { .. }
+            | BackupNotFound { .. }
+            | RegionExists { .. }
+            | MissingKeyValue { .. }
+            | InvalidKeyValue { .. } => StatusCode::InvalidArguments,
 
             Utf8 { .. }
             | EncodeJson { .. }
             | DecodeJson { .. }
             | JoinTask { .. }
             | Cancelled { .. }
+            | ColdStoreNotFound { .. }
             | DecodeMetaActionList { .. }
             | Readline { .. }
             | WalDataCorrupted { .. }
+            | CompressWal { .. }
+            | DecompressWal { .. }
             | SequenceNotMonotonic { .. }
             | ConvertStoreSchema { .. }
             | InvalidRawRegion { .. }
@@ -463,6 +530,7 @@ impl ErrorExt for Error {
             | ListObjects { .. }
             | DeleteObject { .. }
             | WriteWal { .. }
+            | GroupCommitWal { .. }
             | DecodeWalHeader { .. }
             | EncodeWalHeader { .. }
             | ManifestProtocolForbidRead { .. }
@@ -19,20 +19,19 @@ use common_telemetry::logging;
 use store_api::logstore::LogStore;
 use store_api::storage::consts::WRITE_ROW_GROUP_SIZE;
 use store_api::storage::SequenceNumber;
-use uuid::Uuid;
+use tokio::sync::Semaphore;
 
 use crate::background::{Context, Job, JobHandle, JobPoolRef};
+use crate::compaction::{CompactionRequestImpl, CompactionSchedulerRef};
+use crate::config::EngineConfig;
 use crate::error::{CancelledSnafu, Result};
 use crate::manifest::action::*;
 use crate::manifest::region::RegionManifest;
-use crate::memtable::{IterContext, MemtableId, MemtableRef};
+use crate::memtable::{IterContext, MemtableId, MemtableRef, WriteBufferManagerRef};
 use crate::region::{RegionWriterRef, SharedDataRef};
-use crate::sst::{AccessLayerRef, FileMeta, SstInfo, WriteOptions};
+use crate::sst::{self, AccessLayerRef, FileMeta, FileTier, Source, SstInfo, WriteOptions};
 use crate::wal::Wal;
 
-/// Default write buffer size (32M).
-const DEFAULT_WRITE_BUFFER_SIZE: usize = 32 * 1024 * 1024;
-
 pub trait FlushStrategy: Send + Sync + std::fmt::Debug {
     fn should_flush(
         &self,
@@ -50,6 +49,8 @@ pub struct SizeBasedStrategy {
     max_write_buffer_size: usize,
     /// Mutable memtable memory size limitation
     mutable_limitation: usize,
+    /// Tracks memory of memtables of all regions.
+    write_buffer_manager: Option<WriteBufferManagerRef>,
 }
 
 #[inline]
@@ -59,16 +60,30 @@ fn get_mutable_limitation(max_write_buffer_size: usize) -> usize {
     max_write_buffer_size * 7 / 8
 }
 
-impl Default for SizeBasedStrategy {
-    fn default() -> Self {
-        let max_write_buffer_size = DEFAULT_WRITE_BUFFER_SIZE;
-        Self {
+impl SizeBasedStrategy {
+    pub fn new(
+        config: &EngineConfig,
+        write_buffer_manager: Option<WriteBufferManagerRef>,
+    ) -> SizeBasedStrategy {
+        let max_write_buffer_size = config.max_write_buffer_size.0 as usize;
+        let mutable_limitation = config
+            .mutable_memtable_size
+            .map(|size| size.0 as usize)
+            .unwrap_or_else(|| get_mutable_limitation(max_write_buffer_size));
+        SizeBasedStrategy {
             max_write_buffer_size,
-            mutable_limitation: get_mutable_limitation(max_write_buffer_size),
+            mutable_limitation,
+            write_buffer_manager,
         }
     }
 }
 
+impl Default for SizeBasedStrategy {
+    fn default() -> Self {
+        SizeBasedStrategy::new(&EngineConfig::default(), None)
+    }
+}
+
 impl FlushStrategy for SizeBasedStrategy {
     fn should_flush(
         &self,
@@ -93,6 +108,23 @@ impl FlushStrategy for SizeBasedStrategy {
             return true;
         }
 
+        if let Some(manager) = &self.write_buffer_manager {
+            // Memtables of all regions use too much memory, flush the region being written
+            // to release memory. The writer waits for the previous flush of the region before
+            // scheduling a new one, which applies back pressure to writes.
+            if bytes_mutable > 0 && manager.should_stall() {
+                logging::info!(
+                    "Region should flush as global write buffer is full, region: {}, \
+                     bytes_mutable: {}, global memory usage: {}",
+                    shared.name(),
+                    bytes_mutable,
+                    manager.memory_usage()
+                );
+
+                return true;
+            }
+        }
+
         let buffer_size = self.max_write_buffer_size;
 
         // If the memory exceeds the buffer size, we trigger more aggressive
@@ -124,19 +156,42 @@ pub trait FlushScheduler: Send + Sync + std::fmt::Debug {
 #[derive(Debug)]
 pub struct FlushSchedulerImpl {
     job_pool: JobPoolRef,
+    /// Limits the number of flush jobs running concurrently.
+    flush_permits: Arc<Semaphore>,
 }
 
 impl FlushSchedulerImpl {
-    pub fn new(job_pool: JobPoolRef) -> FlushSchedulerImpl {
-        FlushSchedulerImpl { job_pool }
+    pub fn new(job_pool: JobPoolRef, max_flush_tasks: usize) -> FlushSchedulerImpl {
+        FlushSchedulerImpl {
+            job_pool,
+            flush_permits: Arc::new(Semaphore::new(max_flush_tasks.max(1))),
+        }
     }
 }
 
 #[async_trait]
 impl FlushScheduler for FlushSchedulerImpl {
     async fn schedule_flush(&self, flush_job: Box<dyn Job>) -> Result<JobHandle> {
-        // TODO(yingwen): [flush] Implements flush schedule strategy, controls max background flushes.
-        self.job_pool.submit(flush_job).await
+        let job = PermitJob {
+            job: flush_job,
+            permits: self.flush_permits.clone(),
+        };
+        self.job_pool.submit(Box::new(job)).await
+    }
+}
+
+/// A job that runs after acquiring a permit from `permits`.
+struct PermitJob {
+    job: Box<dyn Job>,
+    permits: Arc<Semaphore>,
+}
+
+#[async_trait]
+impl Job for PermitJob {
+    async fn run(&mut self, ctx: &Context) -> Result<()> {
+        // The semaphore is never closed.
+        let _permit = self.permits.acquire().await.unwrap();
+        self.job.run(ctx).await
     }
 }
 
@@ -160,6 +215,8 @@ pub struct FlushJob<S: LogStore> {
     pub wal: Wal<S>,
     /// Region manifest service, used to persist metadata.
     pub manifest: RegionManifest,
+    /// Scheduler to schedule compaction of the region after flush.
+    pub compaction_scheduler: CompactionSchedulerRef<S>,
 }
 
 impl<S: LogStore> FlushJob<S> {
@@ -181,7 +238,7 @@ impl<S: LogStore> FlushJob<S> {
                 continue;
             }
 
-            let file_name = Self::generate_sst_file_name();
+            let file_name = sst::generate_sst_file_name();
             // TODO(hl): Check if random file name already exists in meta.
             let iter = m.iter(&iter_ctx)?;
             futures.push(async move {
@@ -190,7 +247,7 @@ impl<S: LogStore> FlushJob<S> {
                     end_timestamp,
                 } = self
                     .sst_layer
-                    .write_sst(&file_name, iter, &WriteOptions::default())
+                    .write_sst(&file_name, Source::Iter(iter), &WriteOptions::default())
                     .await?;
 
                 Ok(FileMeta {
@@ -198,6 +255,7 @@ impl<S: LogStore> FlushJob<S> {
                     start_timestamp,
                     end_timestamp,
                     level: 0,
+                    tier: FileTier::Hot,
                 })
             });
         }
@@ -216,7 +274,7 @@ impl<S: LogStore> FlushJob<S> {
     async fn write_manifest_and_apply(&self, file_metas: &[FileMeta]) -> Result<()> {
         let edit = RegionEdit {
             region_version: self.shared.version_control.metadata().version(),
-            flushed_sequence: self.flush_sequence,
+            flushed_sequence: Some(self.flush_sequence),
             files_to_add: file_metas.to_vec(),
             files_to_remove: Vec::default(),
         };
@@ -227,15 +285,26 @@ impl<S: LogStore> FlushJob<S> {
                 &self.shared,
                 &self.manifest,
                 edit,
-                self.max_memtable_id,
+                Some(self.max_memtable_id),
             )
             .await?;
         self.wal.obsolete(self.flush_sequence).await
     }
 
-    /// Generates random SST file name in format: `^[a-f\d]{8}(-[a-f\d]{4}){3}-[a-f\d]{12}.parquet$`
-    fn generate_sst_file_name() -> String {
-        format!("{}.parquet", Uuid::new_v4().hyphenated())
+    /// Schedules a compaction for the region as flush adds new files to level 0.
+    async fn schedule_compaction(&self) {
+        let request = CompactionRequestImpl {
+            shared: self.shared.clone(),
+            sst_layer: self.sst_layer.clone(),
+            writer: self.writer.clone(),
+            wal: self.wal.clone(),
+            manifest: self.manifest.clone(),
+        };
+
+        if let Err(e) = self.compaction_scheduler.schedule_compaction(request).await {
+            // Failing to schedule a compaction won't affect the flush result.
+            logging::error!(e; "Failed to schedule compaction, region: {}", self.shared.name());
+        }
     }
 }
 
@@ -245,14 +314,14 @@ impl<S: LogStore> Job for FlushJob<S> {
     async fn run(&mut self, ctx: &Context) -> Result<()> {
         let file_metas = self.write_memtables_to_layer(ctx).await?;
         self.write_manifest_and_apply(&file_metas).await?;
+        self.schedule_compaction().await;
         Ok(())
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use log_store::NoopLogStore;
-    use regex::Regex;
+    use common_base::readable_size::ReadableSize;
 
     use super::*;
 
@@ -264,12 +333,18 @@ mod tests {
     }
 
     #[test]
-    pub fn test_uuid_generate() {
-        let file_name = FlushJob::<NoopLogStore>::generate_sst_file_name();
-        let regex = Regex::new(r"^[a-f\d]{8}(-[a-f\d]{4}){3}-[a-f\d]{12}.parquet$").unwrap();
-        assert!(
-            regex.is_match(&file_name),
-            "Illegal sst file name: {file_name}",
-        );
+    fn test_new_size_based_strategy() {
+        let strategy = SizeBasedStrategy::default();
+        assert_eq!(32 * 1024 * 1024, strategy.max_write_buffer_size);
+        assert_eq!(28 * 1024 * 1024, strategy.mutable_limitation);
+
+        let config = EngineConfig {
+            max_write_buffer_size: ReadableSize::mb(64),
+            mutable_memtable_size: Some(ReadableSize::mb(16)),
+            ..Default::default()
+        };
+        let strategy = SizeBasedStrategy::new(&config, None);
+        assert_eq!(64 * 1024 * 1024, strategy.max_write_buffer_size);
+        assert_eq!(16 * 1024 * 1024, strategy.mutable_limitation);
     }
 }
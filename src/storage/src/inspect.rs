@@ -0,0 +1,160 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Utilities to inspect the WAL, manifest and SSTs of a region offline, which helps to
+//! diagnose regions that fail to open.
+
+use std::sync::Arc;
+
+use common_recordbatch::RecordBatch;
+use futures::TryStreamExt;
+use object_store::{util, ObjectStore};
+use snafu::ResultExt;
+use store_api::logstore::LogStore;
+use store_api::manifest::{
+    Manifest, ManifestVersion, MetaActionIterator, MAX_VERSION, MIN_VERSION,
+};
+use store_api::storage::{RegionId, SequenceNumber};
+use table::predicate::Predicate;
+
+use crate::engine;
+use crate::error::{self, Result};
+use crate::manifest::action::{RegionMetaAction, RegionMetaActionList};
+use crate::manifest::region::RegionManifest;
+use crate::metadata::RegionMetadata;
+use crate::proto::wal::WalHeader;
+use crate::read::BatchReader;
+use crate::schema::ProjectedSchema;
+pub use crate::sst::SstMeta;
+use crate::sst::{self, ParquetReader};
+use crate::wal::Wal;
+use crate::write_batch::Payload;
+
+/// A decoded WAL entry, contains the sequence, header and payload of the entry.
+pub type WalEntry = (SequenceNumber, WalHeader, Option<Payload>);
+
+/// Reads WAL entries of the region with `region_id` from `log_store`, starting from
+/// `start_seq`.
+pub async fn read_wal<S: LogStore>(
+    log_store: Arc<S>,
+    region_id: RegionId,
+    start_seq: SequenceNumber,
+) -> Result<Vec<WalEntry>> {
+    let wal = Wal::new(region_id, log_store);
+    let stream = wal.read_from_wal(start_seq).await?;
+    stream.try_collect().await
+}
+
+/// Inspects files of a region in the object store.
+pub struct RegionInspector {
+    region_name: String,
+    sst_dir: String,
+    object_store: ObjectStore,
+    manifest: RegionManifest,
+}
+
+impl RegionInspector {
+    /// Creates an inspector of the region `region_name` under `parent_dir`.
+    pub fn new(parent_dir: &str, region_name: &str, object_store: ObjectStore) -> Self {
+        let parent_dir = util::normalize_dir(parent_dir);
+        let manifest_dir = engine::region_manifest_dir(&parent_dir, region_name);
+
+        Self {
+            region_name: region_name.to_string(),
+            sst_dir: engine::region_sst_dir(&parent_dir, region_name),
+            manifest: RegionManifest::new(&manifest_dir, object_store.clone()),
+            object_store,
+        }
+    }
+
+    /// Returns actions in the manifest in the order the region replays them on open,
+    /// actions compacted into the last checkpoint come first.
+    pub async fn manifest_actions(&self) -> Result<Vec<(ManifestVersion, RegionMetaActionList)>> {
+        let mut start = MIN_VERSION;
+        let mut actions = Vec::new();
+        if let Some(checkpoint) = self.manifest.last_checkpoint().await? {
+            start = checkpoint.last_version + 1;
+            actions = checkpoint.into_actions();
+        }
+
+        let mut iter = self.manifest.scan(start, MAX_VERSION).await?;
+        while let Some(action) = iter.next_action().await? {
+            actions.push(action);
+        }
+
+        Ok(actions)
+    }
+
+    /// Returns the latest metadata of the region in the manifest, `None` if the manifest
+    /// has no metadata.
+    pub async fn metadata(&self) -> Result<Option<RegionMetadata>> {
+        let actions = self.manifest_actions().await?;
+        let last_change = actions
+            .into_iter()
+            .flat_map(|(_, action_list)| action_list.actions)
+            .filter_map(|action| match action {
+                RegionMetaAction::Change(change) => Some(change),
+                _ => None,
+            })
+            .last();
+        let Some(change) = last_change else {
+            return Ok(None);
+        };
+
+        let metadata = change
+            .metadata
+            .try_into()
+            .context(error::InvalidRawRegionSnafu {
+                region: &self.region_name,
+            })?;
+        Ok(Some(metadata))
+    }
+
+    /// Returns the metadata of the SST `file_name`.
+    pub async fn sst_meta(&self, file_name: &str) -> Result<SstMeta> {
+        let file_path = format!("{}{}", self.sst_dir, file_name);
+        sst::read_sst_meta(&file_path, &self.object_store).await
+    }
+
+    /// Reads all rows in the SST `file_name` by the schema in `metadata`, rows are not
+    /// deduplicated and deleted rows are also returned.
+    pub async fn read_sst(
+        &self,
+        file_name: &str,
+        metadata: &RegionMetadata,
+    ) -> Result<Vec<RecordBatch>> {
+        let file_path = format!("{}{}", self.sst_dir, file_name);
+        let projected_schema = Arc::new(ProjectedSchema::no_projection(metadata.schema().clone()));
+        let reader = ParquetReader::new(
+            &file_path,
+            self.object_store.clone(),
+            projected_schema.clone(),
+            Predicate::empty(),
+        );
+        let mut stream = reader.chunk_stream().await?;
+
+        let mut batches = Vec::new();
+        while let Some(batch) = stream.next_batch().await? {
+            let chunk = projected_schema.batch_to_chunk(&batch);
+            let batch = RecordBatch::new(
+                projected_schema.projected_user_schema().clone(),
+                chunk.columns,
+            )
+            .context(error::CreateRecordBatchSnafu)?;
+            batches.push(batch);
+        }
+
+        Ok(batches)
+    }
+}
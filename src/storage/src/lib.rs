@@ -15,15 +15,19 @@
 //! Storage engine implementation.
 
 mod background;
+pub mod backup;
 mod chunk;
 pub mod codec;
+mod compaction;
 pub mod config;
 mod engine;
 pub mod error;
 mod flush;
+pub mod inspect;
 pub mod manifest;
 pub mod memtable;
 pub mod metadata;
+mod metric;
 pub mod proto;
 pub mod read;
 pub mod region;
@@ -18,12 +18,12 @@ use serde::{Deserialize, Serialize};
 use serde_json as json;
 use snafu::{ensure, OptionExt, ResultExt};
 use store_api::manifest::action::{ProtocolAction, ProtocolVersion, VersionHeader};
-use store_api::manifest::{ManifestVersion, MetaAction};
+use store_api::manifest::{Checkpoint, ManifestVersion, MetaAction};
 use store_api::storage::{RegionId, SequenceNumber};
 
 use crate::error::{
-    self, DecodeJsonSnafu, DecodeMetaActionListSnafu, ManifestProtocolForbidReadSnafu,
-    ReadlineSnafu, Result,
+    self, DecodeJsonSnafu, DecodeMetaActionListSnafu, EncodeJsonSnafu,
+    ManifestProtocolForbidReadSnafu, ReadlineSnafu, Result,
 };
 use crate::manifest::helper;
 use crate::metadata::{ColumnFamilyMetadata, ColumnMetadata, VersionNumber};
@@ -73,7 +73,8 @@ pub struct RegionRemove {
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
 pub struct RegionEdit {
     pub region_version: VersionNumber,
-    pub flushed_sequence: SequenceNumber,
+    /// Flushed sequence of the region, `None` if the edit doesn't come from a flush, e.g. compaction.
+    pub flushed_sequence: Option<SequenceNumber>,
     pub files_to_add: Vec<FileMeta>,
     pub files_to_remove: Vec<FileMeta>,
 }
@@ -178,6 +179,137 @@ impl MetaAction for RegionMetaActionList {
     }
 }
 
+/// Checkpoint of the region manifest.
+///
+/// Only keeps the states required to recover the region: the metadata changes that might
+/// still be used while replaying the WAL, the flushed sequence and the SST files.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct RegionCheckpoint {
+    /// Version of the last action compacted into this checkpoint.
+    pub last_version: ManifestVersion,
+    /// The last protocol action.
+    pub protocol: ProtocolAction,
+    /// Metadata changes and their manifest versions, in the order they were written.
+    ///
+    /// The first change is the last one whose committed sequence is not greater than
+    /// the flushed sequence, older changes are useless after recovering.
+    pub changes: Vec<(ManifestVersion, RegionChange)>,
+    /// Manifest version of the last edit, `None` if there is no edit yet.
+    pub edit_version: Option<ManifestVersion>,
+    /// Flushed sequence of the region.
+    pub flushed_sequence: Option<SequenceNumber>,
+    /// SST files of the region.
+    pub files: Vec<FileMeta>,
+}
+
+impl RegionCheckpoint {
+    /// Converts the checkpoint into action lists whose effect is the same as the compacted
+    /// actions.
+    pub fn into_actions(self) -> Vec<(ManifestVersion, RegionMetaActionList)> {
+        let region_version = self
+            .changes
+            .last()
+            .map(|(_, c)| c.metadata.version)
+            .unwrap_or_default();
+        let mut actions: Vec<_> = self
+            .changes
+            .into_iter()
+            .map(|(version, change)| {
+                (
+                    version,
+                    RegionMetaActionList::with_action(RegionMetaAction::Change(change)),
+                )
+            })
+            .collect();
+
+        if let Some(version) = self.edit_version {
+            let edit = RegionEdit {
+                region_version,
+                flushed_sequence: self.flushed_sequence,
+                files_to_add: self.files,
+                files_to_remove: Vec::new(),
+            };
+            actions.push((
+                version,
+                RegionMetaActionList::with_action(RegionMetaAction::Edit(edit)),
+            ));
+        }
+
+        actions
+    }
+
+    fn apply_edit(&mut self, version: ManifestVersion, edit: RegionEdit) {
+        self.edit_version = Some(version);
+        if let Some(sequence) = edit.flushed_sequence {
+            self.flushed_sequence = Some(
+                self.flushed_sequence
+                    .map_or(sequence, |flushed| flushed.max(sequence)),
+            );
+        }
+        for file in edit.files_to_remove {
+            self.files.retain(|f| f.file_name != file.file_name);
+        }
+        self.files.extend(edit.files_to_add);
+    }
+
+    /// Removes changes that are no longer needed for recovering.
+    fn remove_obsolete_changes(&mut self) {
+        let flushed_sequence = self.flushed_sequence.unwrap_or_default();
+        let first_needed = self
+            .changes
+            .iter()
+            .rposition(|(_, c)| c.committed_sequence <= flushed_sequence)
+            .unwrap_or(0);
+        self.changes.drain(..first_needed);
+    }
+}
+
+impl Checkpoint for RegionCheckpoint {
+    type Error = error::Error;
+    type MetaAction = RegionMetaActionList;
+
+    fn last_version(&self) -> ManifestVersion {
+        self.last_version
+    }
+
+    fn protocol(&self) -> &ProtocolAction {
+        &self.protocol
+    }
+
+    fn apply(&mut self, version: ManifestVersion, action_list: RegionMetaActionList) -> Result<()> {
+        for action in action_list.actions {
+            match action {
+                RegionMetaAction::Protocol(p) => self.protocol = p,
+                RegionMetaAction::Change(c) => self.changes.push((version, c)),
+                RegionMetaAction::Edit(e) => self.apply_edit(version, e),
+                // Removing a region is not recovered from manifest now.
+                RegionMetaAction::Remove(_) => (),
+            }
+        }
+        self.remove_obsolete_changes();
+        self.last_version = version;
+
+        Ok(())
+    }
+
+    fn encode(&self) -> Result<Vec<u8>> {
+        json::to_vec(self).context(EncodeJsonSnafu)
+    }
+
+    fn decode(bs: &[u8], reader_version: ProtocolVersion) -> Result<Self> {
+        let checkpoint: RegionCheckpoint = json::from_slice(bs).context(DecodeJsonSnafu)?;
+        ensure!(
+            checkpoint.protocol.is_readable(reader_version),
+            ManifestProtocolForbidReadSnafu {
+                min_version: checkpoint.protocol.min_reader_version,
+                supported_version: reader_version,
+            }
+        );
+
+        Ok(checkpoint)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use common_telemetry::logging;
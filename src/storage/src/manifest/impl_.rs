@@ -23,19 +23,45 @@ use object_store::ObjectStore;
 use snafu::ensure;
 use store_api::manifest::action::{self, ProtocolAction, ProtocolVersion};
 use store_api::manifest::*;
+use tokio::sync::Mutex;
 
 use crate::error::{Error, ManifestProtocolForbidWriteSnafu, Result};
 use crate::manifest::storage::{ManifestObjectStore, ObjectStoreLogIterator};
 
+/// Default number of actions between two checkpoints.
+const DEFAULT_CHECKPOINT_ACTIONS_MARGIN: u64 = 10;
+
 #[derive(Clone, Debug)]
-pub struct ManifestImpl<M: MetaAction<Error = Error>> {
-    inner: Arc<ManifestImplInner<M>>,
+pub struct ManifestImpl<C: Checkpoint<Error = Error>, M: MetaAction<Error = Error>> {
+    inner: Arc<ManifestImplInner<C, M>>,
 }
 
-impl<M: MetaAction<Error = Error>> ManifestImpl<M> {
+impl<C, M> ManifestImpl<C, M>
+where
+    C: 'static + Checkpoint<Error = Error, MetaAction = M>,
+    M: 'static + MetaAction<Error = Error>,
+{
     pub fn new(manifest_dir: &str, object_store: ObjectStore) -> Self {
+        Self::with_checkpoint_margin(
+            manifest_dir,
+            object_store,
+            DEFAULT_CHECKPOINT_ACTIONS_MARGIN,
+        )
+    }
+
+    /// Create a manifest that makes a checkpoint in background every `checkpoint_margin`
+    /// actions, `0` disables the background checkpoint.
+    pub fn with_checkpoint_margin(
+        manifest_dir: &str,
+        object_store: ObjectStore,
+        checkpoint_margin: u64,
+    ) -> Self {
         ManifestImpl {
-            inner: Arc::new(ManifestImplInner::new(manifest_dir, object_store)),
+            inner: Arc::new(ManifestImplInner::new(
+                manifest_dir,
+                object_store,
+                checkpoint_margin,
+            )),
         }
     }
 
@@ -43,16 +69,49 @@ impl<M: MetaAction<Error = Error>> ManifestImpl<M> {
     pub fn update_state(&self, version: ManifestVersion, protocol: Option<ProtocolAction>) {
         self.inner.update_state(version, protocol);
     }
+
+    /// Deletes all files of this manifest.
+    pub async fn delete_all(&self) -> Result<()> {
+        // Avoid racing with a running checkpoint.
+        let _lock = self.inner.checkpoint_lock.lock().await;
+        self.inner.store.delete_all().await
+    }
+
+    /// Makes a checkpoint in background if there are enough actions since the last one.
+    fn maybe_checkpoint_in_background(&self, version: ManifestVersion) {
+        let margin = self.inner.checkpoint_margin;
+        let last_checkpoint_version = self.inner.last_checkpoint_version.load(Ordering::Relaxed);
+        if margin == 0 || version < last_checkpoint_version + margin {
+            return;
+        }
+
+        let inner = self.inner.clone();
+        common_runtime::spawn_bg(async move {
+            // Skip if another checkpoint is running.
+            let Ok(_lock) = inner.checkpoint_lock.try_lock() else { return };
+            if let Err(e) = inner.checkpoint().await {
+                logging::error!(e; "Failed to checkpoint manifest, path: {}", inner.store.path());
+            }
+        });
+    }
 }
 
 #[async_trait]
-impl<M: 'static + MetaAction<Error = Error>> Manifest for ManifestImpl<M> {
+impl<C, M> Manifest for ManifestImpl<C, M>
+where
+    C: 'static + Checkpoint<Error = Error, MetaAction = M>,
+    M: 'static + MetaAction<Error = Error>,
+{
     type Error = Error;
     type MetaAction = M;
     type MetaActionIterator = MetaActionIteratorImpl<M>;
+    type Checkpoint = C;
 
     async fn update(&self, action_list: M) -> Result<ManifestVersion> {
-        self.inner.save(action_list).await
+        let version = self.inner.save(action_list).await?;
+        self.maybe_checkpoint_in_background(version);
+
+        Ok(version)
     }
 
     async fn scan(
@@ -63,8 +122,13 @@ impl<M: 'static + MetaAction<Error = Error>> Manifest for ManifestImpl<M> {
         self.inner.scan(start, end).await
     }
 
-    async fn checkpoint(&self) -> Result<ManifestVersion> {
-        unimplemented!();
+    async fn checkpoint(&self) -> Result<Option<C>> {
+        let _lock = self.inner.checkpoint_lock.lock().await;
+        self.inner.checkpoint().await
+    }
+
+    async fn last_checkpoint(&self) -> Result<Option<C>> {
+        self.inner.last_checkpoint().await
     }
 
     fn last_version(&self) -> ManifestVersion {
@@ -73,7 +137,7 @@ impl<M: 'static + MetaAction<Error = Error>> Manifest for ManifestImpl<M> {
 }
 
 #[derive(Debug)]
-struct ManifestImplInner<M: MetaAction<Error = Error>> {
+struct ManifestImplInner<C: Checkpoint<Error = Error>, M: MetaAction<Error = Error>> {
     store: Arc<ManifestObjectStore>,
     version: AtomicU64,
     /// Current using protocol
@@ -81,7 +145,13 @@ struct ManifestImplInner<M: MetaAction<Error = Error>> {
     /// Current node supported protocols (reader_version, writer_version)
     supported_reader_version: ProtocolVersion,
     supported_writer_version: ProtocolVersion,
-    _phantom: PhantomData<M>,
+    /// Number of actions between two checkpoints, `0` means never checkpoint automatically.
+    checkpoint_margin: u64,
+    /// Version of the last action in the latest checkpoint.
+    last_checkpoint_version: AtomicU64,
+    /// Only allow one checkpoint at the same time.
+    checkpoint_lock: Mutex<()>,
+    _phantom: PhantomData<(C, M)>,
 }
 
 pub struct MetaActionIteratorImpl<M: MetaAction<Error = Error>> {
@@ -118,8 +188,12 @@ impl<M: MetaAction<Error = Error>> MetaActionIterator for MetaActionIteratorImpl
     }
 }
 
-impl<M: MetaAction<Error = Error>> ManifestImplInner<M> {
-    fn new(manifest_dir: &str, object_store: ObjectStore) -> Self {
+impl<C, M> ManifestImplInner<C, M>
+where
+    C: Checkpoint<Error = Error, MetaAction = M>,
+    M: MetaAction<Error = Error>,
+{
+    fn new(manifest_dir: &str, object_store: ObjectStore, checkpoint_margin: u64) -> Self {
         let (reader_version, writer_version) = action::supported_protocol_version();
 
         Self {
@@ -128,6 +202,9 @@ impl<M: MetaAction<Error = Error>> ManifestImplInner<M> {
             protocol: ArcSwap::new(Arc::new(ProtocolAction::new())),
             supported_reader_version: reader_version,
             supported_writer_version: writer_version,
+            checkpoint_margin,
+            last_checkpoint_version: AtomicU64::new(0),
+            checkpoint_lock: Mutex::new(()),
             _phantom: PhantomData,
         }
     }
@@ -195,4 +272,65 @@ impl<M: MetaAction<Error = Error>> ManifestImplInner<M> {
             _phantom: PhantomData,
         })
     }
+
+    async fn last_checkpoint(&self) -> Result<Option<C>> {
+        let Some((version, bytes)) = self.store.load_checkpoint().await? else { return Ok(None) };
+        let checkpoint = C::decode(&bytes, self.supported_reader_version)?;
+        self.last_checkpoint_version
+            .fetch_max(version, Ordering::Relaxed);
+
+        Ok(Some(checkpoint))
+    }
+
+    /// Compacts actions since the last checkpoint into a new checkpoint.
+    ///
+    /// Caller should hold the `checkpoint_lock`.
+    async fn checkpoint(&self) -> Result<Option<C>> {
+        let last_checkpoint = self.last_checkpoint().await?;
+        let prev_version = last_checkpoint.as_ref().map(|c| c.last_version());
+        let start = prev_version.map(|v| v + 1).unwrap_or(MIN_VERSION);
+        let end = self.last_version();
+        if start >= end {
+            return Ok(None);
+        }
+
+        let mut checkpoint = last_checkpoint.unwrap_or_default();
+        let mut iter = self.scan(start, end).await?;
+        let mut expect_version = start;
+        while let Some((version, action_list)) = iter.next_action().await? {
+            // Stop at the first missing version, the action of that version may be still
+            // being written.
+            if version != expect_version {
+                break;
+            }
+            checkpoint.apply(version, action_list)?;
+            expect_version += 1;
+        }
+
+        if expect_version == start {
+            return Ok(None);
+        }
+
+        let version = checkpoint.last_version();
+        self.store
+            .save_checkpoint(version, &checkpoint.encode()?)
+            .await?;
+        self.last_checkpoint_version
+            .fetch_max(version, Ordering::Relaxed);
+
+        // Actions in [start, version] are compacted into the checkpoint now.
+        self.store.delete(start, version + 1).await?;
+        if let Some(prev_version) = prev_version {
+            self.store.delete_checkpoint(prev_version).await?;
+        }
+
+        logging::info!(
+            "Manifest checkpoint done, path: {}, version: {}, compacted actions: {}",
+            self.store.path(),
+            version,
+            version + 1 - start
+        );
+
+        Ok(Some(checkpoint))
+    }
 }
@@ -16,7 +16,7 @@
 use crate::manifest::action::*;
 use crate::manifest::ManifestImpl;
 
-pub type RegionManifest = ManifestImpl<RegionMetaActionList>;
+pub type RegionManifest = ManifestImpl<RegionCheckpoint, RegionMetaActionList>;
 
 #[cfg(test)]
 mod tests {
@@ -130,4 +130,116 @@ mod tests {
         // Reach end
         assert!(iter.next_action().await.unwrap().is_none());
     }
+
+    fn file_names(checkpoint: &RegionCheckpoint) -> Vec<&str> {
+        checkpoint
+            .files
+            .iter()
+            .map(|f| f.file_name.as_str())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_region_manifest_checkpoint() {
+        common_telemetry::init_default_ut_logging();
+        let tmp_dir = TempDir::new("test_region_manifest_checkpoint").unwrap();
+        let object_store = ObjectStore::new(
+            fs::Builder::default()
+                .root(&tmp_dir.path().to_string_lossy())
+                .build()
+                .unwrap(),
+        );
+
+        // Disable background checkpoint.
+        let manifest = RegionManifest::with_checkpoint_margin("/manifest/", object_store, 0);
+        assert!(manifest.checkpoint().await.unwrap().is_none());
+
+        let region_meta = Arc::new(build_region_meta());
+        let change = RegionChange {
+            metadata: region_meta.as_ref().into(),
+            committed_sequence: 0,
+        };
+        manifest
+            .update(RegionMetaActionList::with_action(RegionMetaAction::Change(
+                change.clone(),
+            )))
+            .await
+            .unwrap();
+        manifest
+            .update(RegionMetaActionList::with_action(RegionMetaAction::Edit(
+                build_region_edit(1, &["f1", "f2"], &[]),
+            )))
+            .await
+            .unwrap();
+
+        let checkpoint = manifest.checkpoint().await.unwrap().unwrap();
+        assert_eq!(1, checkpoint.last_version);
+        assert_eq!(vec![(0, change.clone())], checkpoint.changes);
+        assert_eq!(Some(1), checkpoint.edit_version);
+        assert_eq!(Some(1), checkpoint.flushed_sequence);
+        assert_eq!(vec!["f1", "f2"], file_names(&checkpoint));
+        assert_eq!(
+            checkpoint,
+            manifest.last_checkpoint().await.unwrap().unwrap()
+        );
+        // Compacted actions are deleted.
+        let mut iter = manifest.scan(0, MAX_VERSION).await.unwrap();
+        assert!(iter.next_action().await.unwrap().is_none());
+        // No more actions to compact.
+        assert!(manifest.checkpoint().await.unwrap().is_none());
+
+        // Alter the region, but data after the alteration hasn't been flushed.
+        let mut altered = change.clone();
+        altered.committed_sequence = 5;
+        altered.metadata.version = 1;
+        manifest
+            .update(RegionMetaActionList::with_action(RegionMetaAction::Change(
+                altered.clone(),
+            )))
+            .await
+            .unwrap();
+        manifest
+            .update(RegionMetaActionList::with_action(RegionMetaAction::Edit(
+                build_region_edit(3, &["f3"], &["f1"]),
+            )))
+            .await
+            .unwrap();
+
+        let checkpoint = manifest.checkpoint().await.unwrap().unwrap();
+        assert_eq!(3, checkpoint.last_version);
+        // The first change is still needed to replay the WAL.
+        assert_eq!(
+            vec![(0, change.clone()), (2, altered.clone())],
+            checkpoint.changes
+        );
+        assert_eq!(Some(3), checkpoint.edit_version);
+        assert_eq!(Some(3), checkpoint.flushed_sequence);
+        assert_eq!(vec!["f2", "f3"], file_names(&checkpoint));
+
+        // Flush data after the alteration.
+        manifest
+            .update(RegionMetaActionList::with_action(RegionMetaAction::Edit(
+                build_region_edit(6, &[], &[]),
+            )))
+            .await
+            .unwrap();
+
+        let checkpoint = manifest.checkpoint().await.unwrap().unwrap();
+        assert_eq!(4, checkpoint.last_version);
+        assert_eq!(vec![(2, altered)], checkpoint.changes);
+        assert_eq!(Some(6), checkpoint.flushed_sequence);
+
+        let actions = checkpoint.into_actions();
+        assert_eq!(2, actions.len());
+        assert_eq!(2, actions[0].0);
+        assert!(matches!(
+            &actions[0].1.actions[0],
+            RegionMetaAction::Change(c) if c.committed_sequence == 5
+        ));
+        assert_eq!(4, actions[1].0);
+        assert!(matches!(
+            &actions[1].1.actions[0],
+            RegionMetaAction::Edit(e) if e.flushed_sequence == Some(6) && e.files_to_add.len() == 2
+        ));
+    }
 }
@@ -98,6 +98,12 @@ impl ManifestObjectStore {
         }
     }
 
+    /// Returns the path of the manifest directory.
+    #[inline]
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
     fn delta_file_path(&self, version: ManifestVersion) -> String {
         format!("{}{}", self.path, delta_file(version))
     }
@@ -105,6 +111,29 @@ impl ManifestObjectStore {
     fn checkpoint_file_path(&self, version: ManifestVersion) -> String {
         format!("{}{}", self.path, checkpoint_file(version))
     }
+
+    /// Deletes all files under the manifest directory.
+    pub async fn delete_all(&self) -> Result<()> {
+        let dir = self.object_store.object(&self.path);
+        let dir_exists = dir
+            .is_exist()
+            .await
+            .context(ReadObjectSnafu { path: &self.path })?;
+        if !dir_exists {
+            return Ok(());
+        }
+
+        self.object_store
+            .batch()
+            .remove_all(&self.path)
+            .await
+            .context(DeleteObjectSnafu { path: &self.path })?;
+        // Also remove the directory itself, in case the underlying storage has the
+        // concept of directory, e.g. the local file system.
+        dir.delete()
+            .await
+            .context(DeleteObjectSnafu { path: &self.path })
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -273,6 +302,15 @@ impl ManifestLogStorage for ManifestObjectStore {
             Ok(None)
         }
     }
+
+    async fn delete_checkpoint(&self, version: ManifestVersion) -> Result<()> {
+        let object = self
+            .object_store
+            .object(&self.checkpoint_file_path(version));
+        object.delete().await.context(DeleteObjectSnafu {
+            path: object.path(),
+        })
+    }
 }
 
 #[cfg(test)]
@@ -341,5 +379,47 @@ mod tests {
         let (v, checkpoint) = log_store.load_checkpoint().await.unwrap().unwrap();
         assert_eq!(checkpoint, "checkpoint".as_bytes());
         assert_eq!(3, v);
+
+        // A newer checkpoint
+        log_store
+            .save_checkpoint(4, "checkpoint4".as_bytes())
+            .await
+            .unwrap();
+        log_store.delete_checkpoint(3).await.unwrap();
+        let (v, checkpoint) = log_store.load_checkpoint().await.unwrap().unwrap();
+        assert_eq!(checkpoint, "checkpoint4".as_bytes());
+        assert_eq!(4, v);
+    }
+
+    #[tokio::test]
+    async fn test_manifest_delete_all() {
+        let tmp_dir = TempDir::new("test_manifest_delete_all").unwrap();
+        let object_store = ObjectStore::new(
+            fs::Builder::default()
+                .root(&tmp_dir.path().to_string_lossy())
+                .build()
+                .unwrap(),
+        );
+
+        let log_store = ManifestObjectStore::new("/manifest/", object_store);
+        // Delete an empty manifest.
+        log_store.delete_all().await.unwrap();
+
+        for v in 0..3 {
+            log_store
+                .save(v, format!("hello, {v}").as_bytes())
+                .await
+                .unwrap();
+        }
+        log_store
+            .save_checkpoint(2, "checkpoint".as_bytes())
+            .await
+            .unwrap();
+
+        log_store.delete_all().await.unwrap();
+
+        let mut it = log_store.scan(0, 11).await.unwrap();
+        assert!(it.next_log().await.unwrap().is_none());
+        assert!(log_store.load_checkpoint().await.unwrap().is_none());
     }
 }
@@ -17,7 +17,7 @@ use store_api::storage::SequenceNumber;
 
 use crate::manifest::action::*;
 use crate::metadata::RegionMetadata;
-use crate::sst::FileMeta;
+use crate::sst::{FileMeta, FileTier};
 use crate::test_util::descriptor_util::RegionDescBuilder;
 
 pub fn build_region_meta() -> RegionMetadata {
@@ -37,7 +37,7 @@ pub fn build_region_edit(
 ) -> RegionEdit {
     RegionEdit {
         region_version: 0,
-        flushed_sequence: sequence,
+        flushed_sequence: Some(sequence),
         files_to_add: files_to_add
             .iter()
             .map(|f| FileMeta {
@@ -45,6 +45,7 @@ pub fn build_region_edit(
                 start_timestamp: None,
                 end_timestamp: None,
                 level: 0,
+                tier: FileTier::Hot,
             })
             .collect(),
         files_to_remove: files_to_remove
@@ -54,6 +55,7 @@ pub fn build_region_edit(
                 start_timestamp: None,
                 end_timestamp: None,
                 level: 0,
+                tier: FileTier::Hot,
             })
             .collect(),
     }
@@ -14,20 +14,27 @@
 
 mod btree;
 mod inserter;
+mod series;
 #[cfg(test)]
 pub mod tests;
+mod time_range;
 mod version;
+mod write_buffer;
 
 use std::sync::atomic::{AtomicU32, Ordering};
 use std::sync::Arc;
 
+use common_time::Timestamp;
 use datatypes::vectors::VectorRef;
 use store_api::storage::{consts, OpType, SequenceNumber};
 
 use crate::error::Result;
 use crate::memtable::btree::BTreeMemtable;
 pub use crate::memtable::inserter::Inserter;
+use crate::memtable::series::SeriesMemtable;
+pub use crate::memtable::time_range::TimeRangeTracker;
 pub use crate::memtable::version::MemtableVersion;
+pub use crate::memtable::write_buffer::{AllocTracker, WriteBufferManager, WriteBufferManagerRef};
 use crate::read::Batch;
 use crate::schema::{ProjectedSchemaRef, RegionSchemaRef};
 
@@ -58,6 +65,10 @@ pub trait Memtable: Send + Sync + std::fmt::Debug {
 
     /// Return the number of rows contained in this memtable.
     fn num_rows(&self) -> usize;
+
+    /// Returns the min and max timestamp (both inclusive) of rows in this memtable,
+    /// `None` if the memtable is empty.
+    fn time_range(&self) -> Option<(Timestamp, Timestamp)>;
 }
 
 pub type MemtableRef = Arc<dyn Memtable>;
@@ -163,11 +174,52 @@ impl KeyValues {
 #[derive(Debug, Default)]
 pub struct DefaultMemtableBuilder {
     memtable_id: AtomicU32,
+    write_buffer_manager: Option<WriteBufferManagerRef>,
+}
+
+impl DefaultMemtableBuilder {
+    /// Returns a builder whose memtables account their memory in `write_buffer_manager`.
+    pub fn with_write_buffer_manager(
+        write_buffer_manager: WriteBufferManagerRef,
+    ) -> DefaultMemtableBuilder {
+        DefaultMemtableBuilder {
+            memtable_id: AtomicU32::new(0),
+            write_buffer_manager: Some(write_buffer_manager),
+        }
+    }
 }
 
 impl MemtableBuilder for DefaultMemtableBuilder {
     fn build(&self, schema: RegionSchemaRef) -> MemtableRef {
         let id = self.memtable_id.fetch_add(1, Ordering::Relaxed);
-        Arc::new(BTreeMemtable::new(id, schema))
+        let alloc_tracker = AllocTracker::new(self.write_buffer_manager.clone());
+        Arc::new(BTreeMemtable::new(id, schema, alloc_tracker))
+    }
+}
+
+/// Builds memtables that group rows by series.
+#[derive(Debug, Default)]
+pub struct SeriesMemtableBuilder {
+    memtable_id: AtomicU32,
+    write_buffer_manager: Option<WriteBufferManagerRef>,
+}
+
+impl SeriesMemtableBuilder {
+    /// Returns a builder whose memtables account their memory in `write_buffer_manager`.
+    pub fn with_write_buffer_manager(
+        write_buffer_manager: WriteBufferManagerRef,
+    ) -> SeriesMemtableBuilder {
+        SeriesMemtableBuilder {
+            memtable_id: AtomicU32::new(0),
+            write_buffer_manager: Some(write_buffer_manager),
+        }
+    }
+}
+
+impl MemtableBuilder for SeriesMemtableBuilder {
+    fn build(&self, schema: RegionSchemaRef) -> MemtableRef {
+        let id = self.memtable_id.fetch_add(1, Ordering::Relaxed);
+        let alloc_tracker = AllocTracker::new(self.write_buffer_manager.clone());
+        Arc::new(SeriesMemtable::new(id, schema, alloc_tracker))
     }
 }
@@ -15,9 +15,9 @@
 use std::cmp::Ordering;
 use std::collections::{btree_map, BTreeMap};
 use std::ops::Bound;
-use std::sync::atomic::{AtomicUsize, Ordering as AtomicOrdering};
 use std::sync::{Arc, RwLock};
 
+use common_time::Timestamp;
 use datatypes::data_type::DataType;
 use datatypes::prelude::*;
 use datatypes::value::Value;
@@ -26,7 +26,8 @@ use store_api::storage::{OpType, SequenceNumber};
 
 use crate::error::Result;
 use crate::memtable::{
-    BatchIterator, BoxedBatchIterator, IterContext, KeyValues, Memtable, MemtableId, RowOrdering,
+    AllocTracker, BatchIterator, BoxedBatchIterator, IterContext, KeyValues, Memtable, MemtableId,
+    RowOrdering, TimeRangeTracker,
 };
 use crate::read::Batch;
 use crate::schema::compat::ReadAdapter;
@@ -42,16 +43,22 @@ pub struct BTreeMemtable {
     id: MemtableId,
     schema: RegionSchemaRef,
     map: Arc<RwLockMap>,
-    estimated_bytes: AtomicUsize,
+    alloc_tracker: AllocTracker,
+    time_range_tracker: TimeRangeTracker,
 }
 
 impl BTreeMemtable {
-    pub fn new(id: MemtableId, schema: RegionSchemaRef) -> BTreeMemtable {
+    pub fn new(
+        id: MemtableId,
+        schema: RegionSchemaRef,
+        alloc_tracker: AllocTracker,
+    ) -> BTreeMemtable {
         BTreeMemtable {
             id,
             schema,
             map: Arc::new(RwLock::new(BTreeMap::new())),
-            estimated_bytes: AtomicUsize::new(0),
+            alloc_tracker,
+            time_range_tracker: TimeRangeTracker::default(),
         }
     }
 }
@@ -66,8 +73,9 @@ impl Memtable for BTreeMemtable {
     }
 
     fn write(&self, kvs: &KeyValues) -> Result<()> {
-        self.estimated_bytes
-            .fetch_add(kvs.estimated_memory_size(), AtomicOrdering::Relaxed);
+        self.alloc_tracker.on_allocate(kvs.estimated_memory_size());
+        self.time_range_tracker
+            .update(&kvs.keys[self.schema.timestamp_key_index()]);
 
         let mut map = self.map.write().unwrap();
         let iter_row = IterRow::new(kvs);
@@ -87,12 +95,16 @@ impl Memtable for BTreeMemtable {
     }
 
     fn bytes_allocated(&self) -> usize {
-        self.estimated_bytes.load(AtomicOrdering::Relaxed)
+        self.alloc_tracker.bytes_allocated()
     }
 
     fn num_rows(&self) -> usize {
         self.map.read().unwrap().len()
     }
+
+    fn time_range(&self) -> Option<(Timestamp, Timestamp)> {
+        self.time_range_tracker.time_range()
+    }
 }
 
 struct BTreeIterator {
@@ -0,0 +1,681 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::ops::Bound;
+use std::sync::atomic::{AtomicUsize, Ordering as AtomicOrdering};
+use std::sync::{Arc, Mutex, RwLock};
+
+use common_time::Timestamp;
+use datatypes::data_type::DataType;
+use datatypes::prelude::*;
+use datatypes::value::{Value, ValueRef};
+use datatypes::vectors::{UInt64VectorBuilder, UInt8VectorBuilder};
+use snafu::ResultExt;
+use store_api::storage::{OpType, SequenceNumber};
+
+use crate::error::{self, Result};
+use crate::memtable::{
+    AllocTracker, BatchIterator, BoxedBatchIterator, IterContext, KeyValues, Memtable, MemtableId,
+    RowOrdering, TimeRangeTracker,
+};
+use crate::metadata::ColumnMetadata;
+use crate::read::Batch;
+use crate::schema::compat::ReadAdapter;
+use crate::schema::{ProjectedSchema, ProjectedSchemaRef, RegionSchemaRef};
+
+/// Values of the key columns before the timestamp column.
+type SeriesKey = Vec<Value>;
+/// Series are only added under the write lock of the map, readers hold the read lock
+/// and lock each series to freeze rows written since the last read.
+type RwLockSeriesMap = RwLock<BTreeMap<SeriesKey, Mutex<Series>>>;
+
+/// A memtable that groups rows by series.
+///
+/// A series is identified by the key columns before the timestamp column. Rows of
+/// a series are appended to columnar buffers in write order and only sorted by the
+/// timestamp (and version) while reading, so writing time series data is cheaper
+/// than inserting each row into an ordered map.
+#[derive(Debug)]
+pub struct SeriesMemtable {
+    id: MemtableId,
+    schema: RegionSchemaRef,
+    series: Arc<RwLockSeriesMap>,
+    alloc_tracker: AllocTracker,
+    time_range_tracker: TimeRangeTracker,
+    num_rows: AtomicUsize,
+}
+
+impl SeriesMemtable {
+    pub fn new(
+        id: MemtableId,
+        schema: RegionSchemaRef,
+        alloc_tracker: AllocTracker,
+    ) -> SeriesMemtable {
+        SeriesMemtable {
+            id,
+            schema,
+            series: Arc::new(RwLock::new(BTreeMap::new())),
+            alloc_tracker,
+            time_range_tracker: TimeRangeTracker::default(),
+            num_rows: AtomicUsize::new(0),
+        }
+    }
+}
+
+impl Memtable for SeriesMemtable {
+    fn id(&self) -> MemtableId {
+        self.id
+    }
+
+    fn schema(&self) -> RegionSchemaRef {
+        self.schema.clone()
+    }
+
+    fn write(&self, kvs: &KeyValues) -> Result<()> {
+        self.alloc_tracker.on_allocate(kvs.estimated_memory_size());
+        self.time_range_tracker
+            .update(&kvs.keys[self.schema.timestamp_key_index()]);
+
+        let series_key_end = self.schema.timestamp_key_index();
+        let mut series_map = self.series.write().unwrap();
+        for row_idx in 0..kvs.len() {
+            let series_key: SeriesKey = kvs.keys[..series_key_end]
+                .iter()
+                .map(|vector| vector.get(row_idx))
+                .collect();
+            series_map
+                .entry(series_key)
+                .or_insert_with(|| Mutex::new(Series::new(&self.schema)))
+                .get_mut()
+                .unwrap()
+                .push(kvs, series_key_end, row_idx)?;
+        }
+        self.num_rows.fetch_add(kvs.len(), AtomicOrdering::Relaxed);
+
+        Ok(())
+    }
+
+    fn iter(&self, ctx: &IterContext) -> Result<BoxedBatchIterator> {
+        assert!(ctx.batch_size > 0);
+
+        let iter = SeriesIterator::new(ctx.clone(), self.schema.clone(), self.series.clone())?;
+
+        Ok(Box::new(iter))
+    }
+
+    fn bytes_allocated(&self) -> usize {
+        self.alloc_tracker.bytes_allocated()
+    }
+
+    fn num_rows(&self) -> usize {
+        self.num_rows.load(AtomicOrdering::Relaxed)
+    }
+
+    fn time_range(&self) -> Option<(Timestamp, Timestamp)> {
+        self.time_range_tracker.time_range()
+    }
+}
+
+/// A column of a series.
+///
+/// Rows are appended to a typed builder and frozen into a vector before reading, so
+/// each cell is stored as its native type instead of a [Value].
+struct SeriesColumn {
+    /// Rows frozen by previous reads, shared with readers until rows are written again.
+    frozen: VectorRef,
+    /// Rows written after the last freeze.
+    active: Box<dyn MutableVector>,
+}
+
+impl fmt::Debug for SeriesColumn {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SeriesColumn")
+            .field("frozen", &self.frozen)
+            .field("num_active_rows", &self.active.len())
+            .finish()
+    }
+}
+
+impl SeriesColumn {
+    fn new(data_type: &ConcreteDataType) -> SeriesColumn {
+        SeriesColumn {
+            frozen: data_type.create_mutable_vector(0).to_vector(),
+            active: data_type.create_mutable_vector(0),
+        }
+    }
+
+    fn push(&mut self, vector: &VectorRef, row_idx: usize) -> Result<()> {
+        self.active
+            .push_value_ref(vector.get_ref(row_idx))
+            .context(error::PushBatchSnafu)
+    }
+
+    /// Moves rows in the active builder to the frozen vector.
+    fn freeze(&mut self) -> Result<()> {
+        if self.active.is_empty() {
+            return Ok(());
+        }
+
+        let active = self.active.to_vector();
+        if self.frozen.is_empty() {
+            self.frozen = active;
+            return Ok(());
+        }
+        let mut builder = self
+            .frozen
+            .data_type()
+            .create_mutable_vector(self.frozen.len() + active.len());
+        builder
+            .extend_slice_of(&*self.frozen, 0, self.frozen.len())
+            .context(error::PushBatchSnafu)?;
+        builder
+            .extend_slice_of(&*active, 0, active.len())
+            .context(error::PushBatchSnafu)?;
+        self.frozen = builder.to_vector();
+        Ok(())
+    }
+
+    #[inline]
+    fn get_ref(&self, row_idx: usize) -> ValueRef {
+        self.frozen.get_ref(row_idx)
+    }
+}
+
+/// Rows of a series in columnar format, in write order.
+#[derive(Debug)]
+struct Series {
+    /// Row key columns after the series key, e.g. timestamp and version.
+    time_keys: Vec<SeriesColumn>,
+    values: Vec<SeriesColumn>,
+    sequences: Vec<SequenceNumber>,
+    index_in_batch: Vec<usize>,
+    op_types: Vec<OpType>,
+}
+
+impl Series {
+    fn new(schema: &RegionSchemaRef) -> Series {
+        let new_column =
+            |column_meta: &ColumnMetadata| SeriesColumn::new(&column_meta.desc.data_type);
+        Series {
+            time_keys: schema
+                .row_key_columns()
+                .skip(schema.timestamp_key_index())
+                .map(new_column)
+                .collect(),
+            values: schema.value_columns().map(new_column).collect(),
+            sequences: Vec::new(),
+            index_in_batch: Vec::new(),
+            op_types: Vec::new(),
+        }
+    }
+
+    fn push(&mut self, kvs: &KeyValues, series_key_end: usize, row_idx: usize) -> Result<()> {
+        for (column, vector) in self.time_keys.iter_mut().zip(&kvs.keys[series_key_end..]) {
+            column.push(vector, row_idx)?;
+        }
+        for (column, vector) in self.values.iter_mut().zip(&kvs.values) {
+            column.push(vector, row_idx)?;
+        }
+        self.sequences.push(kvs.sequence);
+        self.index_in_batch.push(kvs.start_index_in_batch + row_idx);
+        self.op_types.push(kvs.op_type);
+        Ok(())
+    }
+
+    fn len(&self) -> usize {
+        self.sequences.len()
+    }
+
+    /// Freezes all columns so rows written so far are readable. Frozen columns are
+    /// reused if no rows are written since the last freeze.
+    fn freeze(&mut self) -> Result<()> {
+        for column in self.time_keys.iter_mut().chain(self.values.iter_mut()) {
+            column.freeze()?;
+        }
+        Ok(())
+    }
+
+    /// Compares two rows by (time keys asc, sequence desc, index_in_batch desc, op_type desc),
+    /// the same as the order of the btree memtable.
+    fn compare_rows(&self, left: usize, right: usize) -> Ordering {
+        self.time_keys
+            .iter()
+            .map(|column| column.get_ref(left).cmp(&column.get_ref(right)))
+            .find(|ord| ord.is_ne())
+            .unwrap_or(Ordering::Equal)
+            .then_with(|| self.sequences[right].cmp(&self.sequences[left]))
+            .then_with(|| self.index_in_batch[right].cmp(&self.index_in_batch[left]))
+            .then_with(|| self.op_types[right].cmp(&self.op_types[left]))
+    }
+
+    fn is_time_key_equal(&self, left: usize, right: usize) -> bool {
+        self.time_keys
+            .iter()
+            .all(|column| column.get_ref(left) == column.get_ref(right))
+    }
+
+    /// Sorts rows of this series and returns the rows to read.
+    ///
+    /// Unless `ctx.for_flush` is set, invisible rows are skipped and only the latest
+    /// row of the same key is kept.
+    ///
+    /// The series must be frozen before sorting.
+    fn sorted_rows(&self, key: &SeriesKey, ctx: &IterContext) -> SeriesRows {
+        let mut indices: Vec<usize> = (0..self.len()).collect();
+        if !ctx.for_flush {
+            indices.retain(|idx| self.sequences[*idx] <= ctx.visible_sequence);
+        }
+        indices.sort_unstable_by(|left, right| self.compare_rows(*left, *right));
+        if !ctx.for_flush {
+            indices.dedup_by(|current, prev| self.is_time_key_equal(*prev, *current));
+        }
+
+        let column_of = |column: &SeriesColumn| column.frozen.clone();
+        SeriesRows {
+            key: key.clone(),
+            time_keys: self.time_keys.iter().map(column_of).collect(),
+            values: self.values.iter().map(column_of).collect(),
+            sequences: indices.iter().map(|idx| self.sequences[*idx]).collect(),
+            op_types: indices.iter().map(|idx| self.op_types[*idx]).collect(),
+            indices,
+            next: 0,
+        }
+    }
+}
+
+/// Sorted rows of a series to read.
+struct SeriesRows {
+    key: SeriesKey,
+    /// Frozen columns of the series, in write order.
+    time_keys: Vec<VectorRef>,
+    values: Vec<VectorRef>,
+    /// Indices of rows to read in the frozen columns, in sorted order.
+    indices: Vec<usize>,
+    sequences: Vec<SequenceNumber>,
+    op_types: Vec<OpType>,
+    /// Index of the next row to read.
+    next: usize,
+}
+
+impl SeriesRows {
+    fn num_remaining(&self) -> usize {
+        self.sequences.len() - self.next
+    }
+}
+
+struct SeriesIterator {
+    ctx: IterContext,
+    /// Schema of this memtable.
+    schema: RegionSchemaRef,
+    /// Projected schema that user expect to read.
+    projected_schema: ProjectedSchemaRef,
+    adapter: ReadAdapter,
+    series: Arc<RwLockSeriesMap>,
+    /// Key of the last series loaded.
+    last_series_key: Option<SeriesKey>,
+    /// Rows of the series being read.
+    current: Option<SeriesRows>,
+}
+
+impl BatchIterator for SeriesIterator {
+    fn schema(&self) -> ProjectedSchemaRef {
+        self.projected_schema.clone()
+    }
+
+    fn ordering(&self) -> RowOrdering {
+        RowOrdering::Key
+    }
+}
+
+impl Iterator for SeriesIterator {
+    type Item = Result<Batch>;
+
+    fn next(&mut self) -> Option<Result<Batch>> {
+        self.next_batch().transpose()
+    }
+}
+
+impl SeriesIterator {
+    fn new(
+        ctx: IterContext,
+        schema: RegionSchemaRef,
+        series: Arc<RwLockSeriesMap>,
+    ) -> Result<SeriesIterator> {
+        let projected_schema = ctx
+            .projected_schema
+            .clone()
+            .unwrap_or_else(|| Arc::new(ProjectedSchema::no_projection(schema.clone())));
+        let adapter = ReadAdapter::new(schema.store_schema().clone(), projected_schema.clone())?;
+
+        Ok(SeriesIterator {
+            ctx,
+            schema,
+            projected_schema,
+            adapter,
+            series,
+            last_series_key: None,
+            current: None,
+        })
+    }
+
+    fn next_batch(&mut self) -> Result<Option<Batch>> {
+        let batch_size = self.ctx.batch_size;
+        let mut builder = ColumnsBuilder::new(&self.schema, &self.adapter, batch_size);
+        while builder.num_rows < batch_size {
+            let exhausted = self
+                .current
+                .as_ref()
+                .map(|rows| rows.num_remaining() == 0)
+                .unwrap_or(true);
+            if exhausted {
+                match self.load_next_series()? {
+                    Some(rows) => self.current = Some(rows),
+                    None => break,
+                }
+            }
+
+            let rows = self.current.as_mut().unwrap();
+            let num_rows = rows.num_remaining().min(batch_size - builder.num_rows);
+            builder.push_rows(rows, num_rows)?;
+        }
+
+        if builder.num_rows == 0 {
+            return Ok(None);
+        }
+
+        let (key_columns, value_columns, sequences, op_types) = builder.finish();
+        let batch =
+            self.adapter
+                .batch_from_parts(key_columns, value_columns, sequences, op_types)?;
+
+        Ok(Some(batch))
+    }
+
+    /// Loads the next series that has rows to read.
+    fn load_next_series(&mut self) -> Result<Option<SeriesRows>> {
+        let series_map = self.series.read().unwrap();
+        let start = self.last_series_key.clone();
+        let range = match &start {
+            Some(key) => series_map.range::<SeriesKey, _>((Bound::Excluded(key), Bound::Unbounded)),
+            None => series_map.range::<SeriesKey, _>(..),
+        };
+
+        for (key, series) in range {
+            self.last_series_key = Some(key.clone());
+            // Rows written since the last read need to be frozen before reading.
+            let mut series = series.lock().unwrap();
+            series.freeze()?;
+            let rows = series.sorted_rows(key, &self.ctx);
+            if rows.num_remaining() > 0 {
+                return Ok(Some(rows));
+            }
+        }
+
+        Ok(None)
+    }
+}
+
+/// Builds columns of a batch from [SeriesRows], skips columns that are not needed.
+struct ColumnsBuilder {
+    keys: Vec<Option<Box<dyn MutableVector>>>,
+    values: Vec<Option<Box<dyn MutableVector>>>,
+    sequences: UInt64VectorBuilder,
+    op_types: UInt8VectorBuilder,
+    num_rows: usize,
+}
+
+impl ColumnsBuilder {
+    fn new(schema: &RegionSchemaRef, adapter: &ReadAdapter, capacity: usize) -> ColumnsBuilder {
+        let keys = schema
+            .row_key_columns()
+            .zip(adapter.source_key_needed())
+            .map(|(column_meta, needed)| {
+                needed.then(|| column_meta.desc.data_type.create_mutable_vector(capacity))
+            })
+            .collect();
+        let values = schema
+            .value_columns()
+            .zip(adapter.source_value_needed())
+            .map(|(column_meta, needed)| {
+                needed.then(|| column_meta.desc.data_type.create_mutable_vector(capacity))
+            })
+            .collect();
+
+        ColumnsBuilder {
+            keys,
+            values,
+            sequences: UInt64VectorBuilder::with_capacity(capacity),
+            op_types: UInt8VectorBuilder::with_capacity(capacity),
+            num_rows: 0,
+        }
+    }
+
+    /// Pushes next `num_rows` rows of `rows` to the builder.
+    fn push_rows(&mut self, rows: &mut SeriesRows, num_rows: usize) -> Result<()> {
+        let range = rows.next..rows.next + num_rows;
+        let indices = &rows.indices[range.clone()];
+        let series_key_end = rows.key.len();
+        for (key_idx, builder) in self.keys.iter_mut().enumerate() {
+            let Some(builder) = builder else { continue };
+            if key_idx < series_key_end {
+                // All rows of a series have the same series key.
+                let value = rows.key[key_idx].as_value_ref();
+                for _ in range.clone() {
+                    builder
+                        .push_value_ref(value)
+                        .context(error::PushBatchSnafu)?;
+                }
+            } else {
+                push_values(
+                    builder.as_mut(),
+                    &rows.time_keys[key_idx - series_key_end],
+                    indices,
+                )?;
+            }
+        }
+        for (value_idx, builder) in self.values.iter_mut().enumerate() {
+            let Some(builder) = builder else { continue };
+            push_values(builder.as_mut(), &rows.values[value_idx], indices)?;
+        }
+        for sequence in &rows.sequences[range.clone()] {
+            self.sequences.push(Some(*sequence));
+        }
+        for op_type in &rows.op_types[range] {
+            self.op_types.push(Some(op_type.as_u8()));
+        }
+
+        rows.next += num_rows;
+        self.num_rows += num_rows;
+        Ok(())
+    }
+
+    fn finish(self) -> (Vec<VectorRef>, Vec<VectorRef>, VectorRef, VectorRef) {
+        let to_vectors = |builders: Vec<Option<Box<dyn MutableVector>>>| -> Vec<VectorRef> {
+            builders
+                .into_iter()
+                .flatten()
+                .map(|mut builder| builder.to_vector())
+                .collect()
+        };
+        let (mut sequences, mut op_types) = (self.sequences, self.op_types);
+
+        (
+            to_vectors(self.keys),
+            to_vectors(self.values),
+            Arc::new(sequences.finish()),
+            Arc::new(op_types.finish()),
+        )
+    }
+}
+
+/// Pushes rows at `indices` of `vector` to the `builder`.
+fn push_values(
+    builder: &mut dyn MutableVector,
+    vector: &VectorRef,
+    indices: &[usize],
+) -> Result<()> {
+    for idx in indices {
+        builder
+            .push_value_ref(vector.get_ref(*idx))
+            .context(error::PushBatchSnafu)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use datatypes::type_id::LogicalTypeId;
+    use datatypes::vectors::{StringVector, TimestampMillisecondVector, UInt64Vector};
+
+    use super::*;
+    use crate::metadata::RegionMetadata;
+    use crate::test_util::descriptor_util::RegionDescBuilder;
+
+    #[test]
+    fn test_series_typed_columns() {
+        // Schema: (k0, timestamp) as key, v0 as value.
+        let desc = RegionDescBuilder::new("test")
+            .push_key_column(("k0", LogicalTypeId::String, false))
+            .push_value_column(("v0", LogicalTypeId::UInt64, true))
+            .build();
+        let metadata: RegionMetadata = desc.try_into().unwrap();
+        let memtable = SeriesMemtable::new(0, metadata.schema().clone(), AllocTracker::new(None));
+
+        let num_rows = 4096;
+        let kvs = KeyValues {
+            sequence: 10,
+            op_type: OpType::Put,
+            start_index_in_batch: 0,
+            keys: vec![
+                Arc::new(StringVector::from(vec!["host"; num_rows])) as _,
+                Arc::new(TimestampMillisecondVector::from_values(0..num_rows as i64)) as _,
+            ],
+            values: vec![Arc::new(UInt64Vector::from_values(0..num_rows as u64)) as _],
+        };
+        memtable.write(&kvs).unwrap();
+
+        // Reading freezes rows of the series.
+        let num_read: usize = memtable
+            .iter(&IterContext::default())
+            .unwrap()
+            .map(|batch| batch.unwrap().num_rows())
+            .sum();
+        assert_eq!(num_rows, num_read);
+
+        let series_map = memtable.series.read().unwrap();
+        assert_eq!(1, series_map.len());
+        let series = series_map.values().next().unwrap().lock().unwrap();
+        let columns: Vec<_> = series.time_keys.iter().chain(&series.values).collect();
+        assert!(columns.iter().all(|column| column.active.is_empty()));
+        assert!(columns.iter().all(|column| column.frozen.len() == num_rows));
+
+        // Typed columns take much less memory than storing each cell as a value.
+        let typed_size: usize = columns
+            .iter()
+            .map(|column| column.frozen.memory_size())
+            .sum();
+        let value_size = num_rows * columns.len() * std::mem::size_of::<Value>();
+        assert!(
+            typed_size * 2 < value_size,
+            "typed size: {typed_size}, value size: {value_size}"
+        );
+    }
+
+    #[test]
+    fn test_series_write_after_read() {
+        let desc = RegionDescBuilder::new("test")
+            .push_value_column(("v0", LogicalTypeId::UInt64, true))
+            .build();
+        let metadata: RegionMetadata = desc.try_into().unwrap();
+        let memtable = SeriesMemtable::new(0, metadata.schema().clone(), AllocTracker::new(None));
+        let write = |sequence, timestamps: &[i64], values: &[u64]| {
+            let kvs = KeyValues {
+                sequence,
+                op_type: OpType::Put,
+                start_index_in_batch: 0,
+                keys: vec![Arc::new(TimestampMillisecondVector::from_slice(timestamps)) as _],
+                values: vec![Arc::new(UInt64Vector::from_slice(values)) as _],
+            };
+            memtable.write(&kvs).unwrap();
+        };
+        let read = || {
+            let mut rows = Vec::new();
+            for batch in memtable.iter(&IterContext::default()).unwrap() {
+                let batch = batch.unwrap();
+                for i in 0..batch.num_rows() {
+                    rows.push((batch.column(0).get(i), batch.column(1).get(i)));
+                }
+            }
+            rows
+        };
+        let expect = |rows: &[(i64, u64)]| -> Vec<_> {
+            rows.iter()
+                .map(|(ts, v)| {
+                    (
+                        Value::from(common_time::Timestamp::new_millisecond(*ts)),
+                        Value::from(*v),
+                    )
+                })
+                .collect()
+        };
+
+        write(1, &[2000, 1000], &[2, 1]);
+        assert_eq!(expect(&[(1000, 1), (2000, 2)]), read());
+
+        // Rows written after the freeze are merged with frozen rows.
+        write(2, &[1000, 3000], &[10, 3]);
+        assert_eq!(expect(&[(1000, 10), (2000, 2), (3000, 3)]), read());
+    }
+
+    #[test]
+    fn test_series_read_shares_frozen() {
+        let desc = RegionDescBuilder::new("test")
+            .push_value_column(("v0", LogicalTypeId::UInt64, true))
+            .build();
+        let metadata: RegionMetadata = desc.try_into().unwrap();
+        let memtable = SeriesMemtable::new(0, metadata.schema().clone(), AllocTracker::new(None));
+        let kvs = KeyValues {
+            sequence: 1,
+            op_type: OpType::Put,
+            start_index_in_batch: 0,
+            keys: vec![Arc::new(TimestampMillisecondVector::from_slice([1000, 2000])) as _],
+            values: vec![Arc::new(UInt64Vector::from_slice([1, 2])) as _],
+        };
+        memtable.write(&kvs).unwrap();
+        let frozen_values = || {
+            let series_map = memtable.series.read().unwrap();
+            let series = series_map.values().next().unwrap().lock().unwrap();
+            series.values[0].frozen.clone()
+        };
+
+        let num_rows = |iter: BoxedBatchIterator| -> usize {
+            iter.map(|batch| batch.unwrap().num_rows()).sum()
+        };
+        assert_eq!(2, num_rows(memtable.iter(&IterContext::default()).unwrap()));
+        let first = frozen_values();
+
+        // Readers only hold the read lock of the map, and reuse the frozen vectors
+        // if nothing is written.
+        let _guard = memtable.series.read().unwrap();
+        std::thread::scope(|scope| {
+            scope.spawn(|| {
+                assert_eq!(2, num_rows(memtable.iter(&IterContext::default()).unwrap()));
+                assert!(Arc::ptr_eq(&first, &frozen_values()));
+            });
+        });
+    }
+}
@@ -16,7 +16,7 @@ use datatypes::prelude::*;
 use datatypes::timestamp::TimestampMillisecond;
 use datatypes::type_id::LogicalTypeId;
 use datatypes::vectors::{
-    TimestampMillisecondVector, TimestampMillisecondVectorBuilder, UInt64Vector,
+    StringVector, TimestampMillisecondVector, TimestampMillisecondVectorBuilder, UInt64Vector,
     UInt64VectorBuilder, UInt8Vector,
 };
 
@@ -171,7 +171,10 @@ impl Default for MemtableTester {
 impl MemtableTester {
     fn new() -> MemtableTester {
         let schema = schema_for_test();
-        let builders = vec![Arc::new(DefaultMemtableBuilder::default()) as _];
+        let builders = vec![
+            Arc::new(DefaultMemtableBuilder::default()) as _,
+            Arc::new(SeriesMemtableBuilder::default()) as _,
+        ];
 
         MemtableTester { schema, builders }
     }
@@ -592,3 +595,55 @@ fn test_memtable_projection() {
         assert_eq!(op_types, *batch.column(4));
     });
 }
+
+#[test]
+fn test_series_memtable_multiple_series() {
+    // Schema: (k0, timestamp) as key, v0 as value.
+    let desc = RegionDescBuilder::new("test")
+        .push_key_column(("k0", LogicalTypeId::String, false))
+        .push_value_column(("v0", LogicalTypeId::UInt64, true))
+        .build();
+    let metadata: RegionMetadata = desc.try_into().unwrap();
+    let memtable = SeriesMemtableBuilder::default().build(metadata.schema().clone());
+
+    let kvs = KeyValues {
+        sequence: 10,
+        op_type: OpType::Put,
+        start_index_in_batch: 0,
+        keys: vec![
+            Arc::new(StringVector::from(vec!["b", "a", "b", "a", "a"])) as _,
+            Arc::new(TimestampMillisecondVector::from_slice(&[
+                1002, 1001, 1000, 1000, 1001,
+            ])) as _,
+        ],
+        values: vec![Arc::new(UInt64Vector::from_slice(&[1, 2, 3, 4, 5])) as _],
+    };
+    memtable.write(&kvs).unwrap();
+    assert_eq!(5, memtable.num_rows());
+
+    for batch_size in [1, 2, 4, 8] {
+        let iter_ctx = IterContext {
+            batch_size,
+            ..Default::default()
+        };
+        let mut keys = Vec::new();
+        let mut values = Vec::new();
+        for batch in memtable.iter(&iter_ctx).unwrap() {
+            let batch = batch.unwrap();
+            assert!(batch.num_rows() <= batch_size);
+            for i in 0..batch.num_rows() {
+                keys.push((batch.column(0).get(i), batch.column(1).get(i)));
+                values.push(batch.column(2).get(i));
+            }
+        }
+
+        // Rows are sorted by series and timestamp, the later row of the duplicate key wins.
+        let expect_keys: Vec<_> = [("a", 1000), ("a", 1001), ("b", 1000), ("b", 1002)]
+            .into_iter()
+            .map(|(k0, ts)| (Value::from(k0), Value::from(TimestampMillisecond::from(ts))))
+            .collect();
+        assert_eq!(expect_keys, keys);
+        let expect_values: Vec<_> = [4, 5, 3, 1].into_iter().map(Value::from).collect();
+        assert_eq!(expect_values, values);
+    }
+}
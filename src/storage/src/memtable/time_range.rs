@@ -0,0 +1,92 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::RwLock;
+
+use common_time::Timestamp;
+use datatypes::value::Value;
+use datatypes::vectors::VectorRef;
+
+/// Tracks the time range of rows written to a memtable.
+#[derive(Debug, Default)]
+pub struct TimeRangeTracker {
+    /// Min and max timestamp (both inclusive) of written rows.
+    range: RwLock<Option<(Timestamp, Timestamp)>>,
+}
+
+impl TimeRangeTracker {
+    /// Extends the time range by `timestamps`.
+    pub fn update(&self, timestamps: &VectorRef) {
+        let mut batch_range: Option<(Timestamp, Timestamp)> = None;
+        for i in 0..timestamps.len() {
+            let Value::Timestamp(ts) = timestamps.get(i) else { continue };
+            batch_range = Some(match batch_range {
+                Some((min, max)) => (min.min(ts), max.max(ts)),
+                None => (ts, ts),
+            });
+        }
+        let Some((batch_min, batch_max)) = batch_range else { return };
+
+        let mut range = self.range.write().unwrap();
+        *range = Some(match *range {
+            Some((min, max)) => (min.min(batch_min), max.max(batch_max)),
+            None => (batch_min, batch_max),
+        });
+    }
+
+    /// Returns the min and max timestamp (both inclusive), `None` if no row is written.
+    pub fn time_range(&self) -> Option<(Timestamp, Timestamp)> {
+        *self.range.read().unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use datatypes::vectors::TimestampMillisecondVector;
+
+    use super::*;
+
+    #[test]
+    fn test_time_range_tracker() {
+        let tracker = TimeRangeTracker::default();
+        assert_eq!(None, tracker.time_range());
+
+        let timestamps: VectorRef = Arc::new(TimestampMillisecondVector::from_vec(vec![]));
+        tracker.update(&timestamps);
+        assert_eq!(None, tracker.time_range());
+
+        let timestamps: VectorRef =
+            Arc::new(TimestampMillisecondVector::from_vec(vec![3000, 1000, 2000]));
+        tracker.update(&timestamps);
+        assert_eq!(
+            Some((
+                Timestamp::new_millisecond(1000),
+                Timestamp::new_millisecond(3000)
+            )),
+            tracker.time_range()
+        );
+
+        let timestamps: VectorRef = Arc::new(TimestampMillisecondVector::from_vec(vec![500, 2500]));
+        tracker.update(&timestamps);
+        assert_eq!(
+            Some((
+                Timestamp::new_millisecond(500),
+                Timestamp::new_millisecond(3000)
+            )),
+            tracker.time_range()
+        );
+    }
+}
@@ -0,0 +1,123 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// Tracks memory used by memtables of all regions in the engine.
+#[derive(Debug, Default)]
+pub struct WriteBufferManager {
+    /// Max bytes of all memtables, `None` means no limit.
+    global_write_buffer_size: Option<usize>,
+    memory_used: AtomicUsize,
+}
+
+pub type WriteBufferManagerRef = Arc<WriteBufferManager>;
+
+impl WriteBufferManager {
+    pub fn new(global_write_buffer_size: Option<usize>) -> WriteBufferManager {
+        WriteBufferManager {
+            global_write_buffer_size,
+            memory_used: AtomicUsize::new(0),
+        }
+    }
+
+    /// Returns bytes used by all memtables.
+    pub fn memory_usage(&self) -> usize {
+        self.memory_used.load(Ordering::Relaxed)
+    }
+
+    /// Returns true if memtables use more memory than the global limit.
+    pub fn should_stall(&self) -> bool {
+        self.global_write_buffer_size
+            .map(|limit| self.memory_usage() >= limit)
+            .unwrap_or(false)
+    }
+
+    fn reserve_mem(&self, bytes: usize) {
+        self.memory_used.fetch_add(bytes, Ordering::Relaxed);
+    }
+
+    fn free_mem(&self, bytes: usize) {
+        self.memory_used.fetch_sub(bytes, Ordering::Relaxed);
+    }
+}
+
+/// Tracks memory allocated by a memtable.
+///
+/// The memory is also accounted in the [WriteBufferManager] (if any) and released
+/// from it once the tracker is dropped with its memtable.
+#[derive(Debug, Default)]
+pub struct AllocTracker {
+    write_buffer_manager: Option<WriteBufferManagerRef>,
+    bytes_allocated: AtomicUsize,
+}
+
+impl AllocTracker {
+    pub fn new(write_buffer_manager: Option<WriteBufferManagerRef>) -> AllocTracker {
+        AllocTracker {
+            write_buffer_manager,
+            bytes_allocated: AtomicUsize::new(0),
+        }
+    }
+
+    /// Tracks `bytes` newly allocated by the memtable.
+    pub fn on_allocate(&self, bytes: usize) {
+        self.bytes_allocated.fetch_add(bytes, Ordering::Relaxed);
+        if let Some(manager) = &self.write_buffer_manager {
+            manager.reserve_mem(bytes);
+        }
+    }
+
+    /// Returns bytes allocated by the memtable.
+    pub fn bytes_allocated(&self) -> usize {
+        self.bytes_allocated.load(Ordering::Relaxed)
+    }
+}
+
+impl Drop for AllocTracker {
+    fn drop(&mut self) {
+        if let Some(manager) = &self.write_buffer_manager {
+            manager.free_mem(self.bytes_allocated());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_alloc_tracker() {
+        let manager = Arc::new(WriteBufferManager::new(Some(100)));
+        let tracker = AllocTracker::new(Some(manager.clone()));
+        tracker.on_allocate(60);
+        assert_eq!(60, tracker.bytes_allocated());
+        assert!(!manager.should_stall());
+
+        let tracker2 = AllocTracker::new(Some(manager.clone()));
+        tracker2.on_allocate(40);
+        assert_eq!(100, manager.memory_usage());
+        assert!(manager.should_stall());
+
+        drop(tracker);
+        assert_eq!(40, manager.memory_usage());
+        assert!(!manager.should_stall());
+
+        // No limit.
+        let manager = WriteBufferManager::new(None);
+        manager.reserve_mem(usize::MAX / 2);
+        assert!(!manager.should_stall());
+    }
+}
@@ -0,0 +1,22 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! storage metrics
+
+/// Counter of SST files read by scans.
+pub const METRIC_SCAN_SST_FILES_READ: &str = "storage.scan.sst_files_read";
+/// Counter of SST files skipped by scans since their time ranges don't intersect the query.
+pub const METRIC_SCAN_SST_FILES_PRUNED: &str = "storage.scan.sst_files_pruned";
+/// Counter of memtables skipped by scans since their time ranges don't intersect the query.
+pub const METRIC_SCAN_MEMTABLES_PRUNED: &str = "storage.scan.memtables_pruned";
@@ -17,6 +17,7 @@ tonic::include_proto!("greptime.storage.wal.v1");
 
 use store_api::storage::OpType;
 
+use crate::config::WalCompression;
 use crate::write_batch::Payload;
 
 pub fn gen_mutation_types(payload: &Payload) -> Vec<i32> {
@@ -38,3 +39,13 @@ impl WalHeader {
         }
     }
 }
+
+impl From<WalCompression> for CompressionType {
+    fn from(compression: WalCompression) -> Self {
+        match compression {
+            WalCompression::None => CompressionType::None,
+            WalCompression::Zstd => CompressionType::Zstd,
+            WalCompression::Lz4 => CompressionType::Lz4,
+        }
+    }
+}
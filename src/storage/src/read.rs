@@ -16,17 +16,21 @@
 
 mod dedup;
 mod merge;
+mod windowed;
 
 use std::cmp::Ordering;
 
 use async_trait::async_trait;
 use common_base::BitVec;
+use common_time::range::TimestampRange;
+use common_time::Timestamp;
 use datatypes::data_type::DataType;
 use datatypes::prelude::ConcreteDataType;
 use datatypes::vectors::{BooleanVector, MutableVector, VectorRef};
 pub use dedup::DedupReader;
 pub use merge::{MergeReader, MergeReaderBuilder};
 use snafu::{ensure, ResultExt};
+pub use windowed::{WindowReaderFuture, WindowedReader};
 
 use crate::error::{self, Result};
 
@@ -143,6 +147,18 @@ pub trait BatchOp {
     /// - `batch` doesn't have a valid op type column.
     /// - `selected.len()` is less than the number of rows.
     fn unselect_deleted(&self, batch: &Batch, selected: &mut BitVec);
+
+    /// Unselect rows whose timestamps are less than `expire_time`.
+    ///
+    /// # Panics
+    /// Panics if `selected.len()` is less than the number of rows.
+    fn unselect_expired(&self, batch: &Batch, expire_time: Timestamp, selected: &mut BitVec);
+
+    /// Unselect rows whose timestamps are not in `range`.
+    ///
+    /// # Panics
+    /// Panics if `selected.len()` is less than the number of rows.
+    fn unselect_out_of_range(&self, batch: &Batch, range: &TimestampRange, selected: &mut BitVec);
 }
 
 /// Reusable [Batch] builder.
@@ -295,6 +311,42 @@ impl BatchReader for ConcatReader {
     }
 }
 
+/// Reader that returns at most `limit` rows from the inner reader.
+pub struct LimitReader {
+    reader: BoxedBatchReader,
+    /// Number of rows remaining to return.
+    remaining: usize,
+}
+
+impl LimitReader {
+    pub fn new(reader: BoxedBatchReader, limit: usize) -> LimitReader {
+        LimitReader {
+            reader,
+            remaining: limit,
+        }
+    }
+}
+
+#[async_trait]
+impl BatchReader for LimitReader {
+    async fn next_batch(&mut self) -> Result<Option<Batch>> {
+        if self.remaining == 0 {
+            // Stop reading the inner reader once we reach the limit.
+            return Ok(None);
+        }
+
+        let Some(batch) = self.reader.next_batch().await? else { return Ok(None) };
+        let batch = if batch.num_rows() > self.remaining {
+            batch.slice(0, self.remaining)
+        } else {
+            batch
+        };
+        self.remaining -= batch.num_rows();
+
+        Ok(Some(batch))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -332,6 +384,25 @@ mod tests {
         .await;
     }
 
+    #[tokio::test]
+    async fn test_limit_reader() {
+        let inner = read_util::build_boxed_reader(&[
+            &[(1, Some(1)), (2, Some(2))],
+            &[(3, None), (4, None)],
+        ]);
+        let mut reader = LimitReader::new(inner, 3);
+
+        read_util::check_reader_with_kv_batch(
+            &mut reader,
+            &[&[(1, Some(1)), (2, Some(2))], &[(3, None)]],
+        )
+        .await;
+
+        let inner = read_util::build_boxed_reader(&[&[(1, Some(1))]]);
+        let mut reader = LimitReader::new(inner, 0);
+        assert!(reader.next_batch().await.unwrap().is_none());
+    }
+
     #[tokio::test]
     async fn test_concat_reader_with_empty_reader() {
         let readers = vec![
@@ -14,6 +14,8 @@
 
 use async_trait::async_trait;
 use common_base::BitVec;
+use common_time::range::TimestampRange;
+use common_time::Timestamp;
 use datatypes::prelude::ScalarVector;
 use datatypes::vectors::BooleanVector;
 
@@ -31,6 +33,12 @@ pub struct DedupReader<R> {
     prev_batch: Option<Batch>,
     /// Reused bitmap buffer.
     selected: BitVec,
+    /// Whether to filter out rows marked as deleted.
+    filter_deleted: bool,
+    /// Rows whose timestamps are less than this time are filtered out.
+    expire_time: Option<Timestamp>,
+    /// Rows whose timestamps are not in this range are filtered out.
+    time_range: Option<TimestampRange>,
 }
 
 impl<R> DedupReader<R> {
@@ -40,9 +48,30 @@ impl<R> DedupReader<R> {
             reader,
             prev_batch: None,
             selected: BitVec::default(),
+            filter_deleted: true,
+            expire_time: None,
+            time_range: None,
         }
     }
 
+    /// Sets whether to filter out rows marked as deleted, defaults to true.
+    pub fn filter_deleted(mut self, filter_deleted: bool) -> Self {
+        self.filter_deleted = filter_deleted;
+        self
+    }
+
+    /// Sets the time before which rows are expired and filtered out, defaults to `None`.
+    pub fn expire_time(mut self, expire_time: Option<Timestamp>) -> Self {
+        self.expire_time = expire_time;
+        self
+    }
+
+    /// Sets the time range of rows to return, defaults to `None` (all rows).
+    pub fn time_range(mut self, time_range: Option<TimestampRange>) -> Self {
+        self.time_range = time_range;
+        self
+    }
+
     /// Take `batch` and then returns a new batch with no duplicated rows.
     ///
     /// This method may returns empty `Batch`.
@@ -66,8 +95,21 @@ impl<R> DedupReader<R> {
             .get_or_insert_with(Batch::default)
             .clone_from(&batch); // Use `clone_from` to reuse allocated memory if possible.
 
-        // Find all rows whose op_types are `OpType::Delete`, mark their `selected` to false.
-        self.schema.unselect_deleted(&batch, &mut self.selected);
+        if self.filter_deleted {
+            // Find all rows whose op_types are `OpType::Delete`, mark their `selected` to false.
+            self.schema.unselect_deleted(&batch, &mut self.selected);
+        }
+        if let Some(expire_time) = self.expire_time {
+            // All versions of a key have the same timestamp, so the key is either entirely
+            // expired or not.
+            self.schema
+                .unselect_expired(&batch, expire_time, &mut self.selected);
+        }
+        if let Some(time_range) = &self.time_range {
+            // All versions of a key have the same timestamp, so they are filtered together.
+            self.schema
+                .unselect_out_of_range(&batch, time_range, &mut self.selected);
+        }
 
         let filter = BooleanVector::from_iterator(self.selected.iter().by_vals());
         // Filter duplicate rows.
@@ -92,6 +134,7 @@ impl<R: BatchReader> BatchReader for DedupReader<R> {
 
 #[cfg(test)]
 mod tests {
+    use common_time::timestamp::TimeUnit;
     use store_api::storage::OpType;
 
     use super::*;
@@ -178,4 +221,66 @@ mod tests {
         let expect = [(100, Some(1)), (101, Some(1)), (102, Some(12))];
         assert_eq!(&expect, &result[..]);
     }
+
+    #[tokio::test]
+    async fn test_dedup_keep_deleted() {
+        let schema = read_util::new_projected_schema();
+        let reader = read_util::build_full_vec_reader(&[
+            // key, value, sequence, op_type
+            &[
+                (100, 1, 1000, OpType::Delete),
+                (100, 2, 999, OpType::Put),
+                (101, 1, 1000, OpType::Put),
+            ],
+            &[(102, 12, 1000, OpType::Delete)],
+        ]);
+        let mut reader = DedupReader::new(schema, reader).filter_deleted(false);
+
+        let result = read_util::collect_kv_batch(&mut reader).await;
+        let expect = [(100, Some(1)), (101, Some(1)), (102, Some(12))];
+        assert_eq!(&expect, &result[..]);
+    }
+
+    #[tokio::test]
+    async fn test_dedup_filter_expired() {
+        let schema = read_util::new_projected_schema();
+        let reader = read_util::build_full_vec_reader(&[
+            // key, value, sequence, op_type
+            &[
+                (100, 1, 1000, OpType::Put),
+                (100, 2, 999, OpType::Put),
+                (101, 1, 1000, OpType::Put),
+            ],
+            &[
+                (102, 12, 1000, OpType::Put),
+                (103, 13, 1000, OpType::Delete),
+            ],
+        ]);
+        let mut reader =
+            DedupReader::new(schema, reader).expire_time(Some(Timestamp::new_millisecond(101)));
+
+        let result = read_util::collect_kv_batch(&mut reader).await;
+        let expect = [(101, Some(1)), (102, Some(12))];
+        assert_eq!(&expect, &result[..]);
+    }
+
+    #[tokio::test]
+    async fn test_dedup_filter_time_range() {
+        let schema = read_util::new_projected_schema();
+        let reader = read_util::build_full_vec_reader(&[
+            // key, value, sequence, op_type
+            &[
+                (100, 1, 1000, OpType::Put),
+                (101, 1, 1000, OpType::Put),
+                (101, 2, 999, OpType::Put),
+            ],
+            &[(102, 12, 1000, OpType::Put), (103, 13, 1000, OpType::Put)],
+        ]);
+        let time_range = TimestampRange::with_unit(101, 103, TimeUnit::Millisecond);
+        let mut reader = DedupReader::new(schema, reader).time_range(time_range);
+
+        let result = read_util::collect_kv_batch(&mut reader).await;
+        let expect = [(101, Some(1)), (102, Some(12))];
+        assert_eq!(&expect, &result[..]);
+    }
 }
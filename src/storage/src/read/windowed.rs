@@ -0,0 +1,347 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Reader that returns rows ordered by timestamp.
+
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, VecDeque};
+
+use async_trait::async_trait;
+use datatypes::value::Value;
+use futures::future::BoxFuture;
+use store_api::storage::TimeOrder;
+
+use crate::error::Result;
+use crate::read::{Batch, BatchBuilder, BatchReader, BoxedBatchReader};
+use crate::schema::{ProjectedSchema, ProjectedSchemaRef};
+
+/// Future to build the reader of a time window.
+pub type WindowReaderFuture = BoxFuture<'static, Result<BoxedBatchReader>>;
+
+/// A reader that reads time windows one by one and merges rows in each window by
+/// timestamp.
+///
+/// Time ranges of the windows must not overlap and windows must be in the `order`, so the
+/// output rows are ordered by timestamp across windows. The reader of a window is built
+/// only when the window is read, so windows after the last batch the caller fetches are
+/// never read.
+pub struct WindowedReader {
+    /// Projected schema to read.
+    schema: ProjectedSchemaRef,
+    /// Windows to read.
+    windows: VecDeque<WindowReaderFuture>,
+    order: TimeOrder,
+    /// Suggested row number of each batch.
+    batch_size: usize,
+    /// Rows of current window to merge.
+    runs: SortedRuns,
+}
+
+impl WindowedReader {
+    pub fn new(
+        schema: ProjectedSchemaRef,
+        windows: Vec<WindowReaderFuture>,
+        order: TimeOrder,
+        batch_size: usize,
+    ) -> WindowedReader {
+        WindowedReader {
+            schema,
+            windows: windows.into(),
+            order,
+            batch_size,
+            runs: SortedRuns::default(),
+        }
+    }
+}
+
+#[async_trait]
+impl BatchReader for WindowedReader {
+    async fn next_batch(&mut self) -> Result<Option<Batch>> {
+        while self.runs.is_empty() {
+            let Some(window) = self.windows.pop_front() else { return Ok(None) };
+            self.runs = SortedRuns::read(&self.schema, self.order, window).await?;
+        }
+
+        self.runs
+            .next_batch(&self.schema, self.batch_size)
+            .map(Some)
+    }
+}
+
+/// Rows of a time window, split into runs sorted by timestamp.
+///
+/// The reader of a window returns rows in key order, so rows of each series are sorted
+/// by timestamp but rows of different series are interleaved by time. Rows of the window
+/// are kept in memory and runs are merged lazily, so only rows the caller fetches are
+/// merged and copied.
+#[derive(Default)]
+struct SortedRuns {
+    batches: Vec<Batch>,
+    /// Index of the timestamp column in the batches.
+    timestamp_index: Option<usize>,
+    /// Runs having rows to return, the run with the next row to return is on the top.
+    heap: BinaryHeap<RunCursor>,
+}
+
+impl SortedRuns {
+    /// Reads all rows of the `window` and splits them into sorted runs.
+    async fn read(
+        schema: &ProjectedSchema,
+        order: TimeOrder,
+        window: WindowReaderFuture,
+    ) -> Result<SortedRuns> {
+        let mut reader = window.await?;
+        let mut batches = Vec::new();
+        while let Some(batch) = reader.next_batch().await? {
+            if !batch.is_empty() {
+                batches.push(batch);
+            }
+        }
+
+        let timestamp_index = schema.schema_to_read().schema().timestamp_index();
+        let mut heap = BinaryHeap::new();
+        for (batch_index, batch) in batches.iter().enumerate() {
+            let num_rows = batch.num_rows();
+            let mut start = 0;
+            for i in 1..=num_rows {
+                // A run ends at the end of the batch or once the timestamp goes back.
+                let run_ends = i == num_rows
+                    || timestamp_index.map_or(false, |index| {
+                        let column = batch.column(index);
+                        column.get_ref(i) < column.get_ref(i - 1)
+                    });
+                if run_ends {
+                    let mut cursor = RunCursor {
+                        order,
+                        run: heap.len(),
+                        batch_index,
+                        start,
+                        end: i,
+                        timestamp: Value::Null,
+                    };
+                    cursor.update_timestamp(batch, timestamp_index);
+                    heap.push(cursor);
+                    start = i;
+                }
+            }
+        }
+
+        Ok(SortedRuns {
+            batches,
+            timestamp_index,
+            heap,
+        })
+    }
+
+    #[inline]
+    fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    /// Merges at most `batch_size` rows from the runs.
+    fn next_batch(&mut self, schema: &ProjectedSchema, batch_size: usize) -> Result<Batch> {
+        let schema = schema.schema_to_read().schema();
+        let mut builder = BatchBuilder::with_capacity(
+            schema.column_schemas().iter().map(|c| &c.data_type),
+            batch_size,
+        );
+        while builder.num_rows() < batch_size {
+            let Some(mut cursor) = self.heap.pop() else { break };
+            let batch = &self.batches[cursor.batch_index];
+            builder.push_row_of(batch, cursor.next_row())?;
+            if !cursor.is_exhausted() {
+                cursor.update_timestamp(batch, self.timestamp_index);
+                self.heap.push(cursor);
+            }
+        }
+
+        builder.build()
+    }
+}
+
+/// Cursor of a run of rows in a batch, rows of the run are sorted by timestamp in
+/// ascending order.
+struct RunCursor {
+    order: TimeOrder,
+    /// Index of the run, runs of a window are numbered in key order.
+    run: usize,
+    /// Index of the batch of the run.
+    batch_index: usize,
+    /// Rows in `[start, end)` of the batch are not returned yet.
+    start: usize,
+    end: usize,
+    /// Timestamp of the next row to return.
+    timestamp: Value,
+}
+
+impl RunCursor {
+    /// Returns index of the next row to return and advances the cursor.
+    ///
+    /// Rows are returned from the start of the run in ascending order and from the end in
+    /// descending order.
+    fn next_row(&mut self) -> usize {
+        match self.order {
+            TimeOrder::Asc => {
+                self.start += 1;
+                self.start - 1
+            }
+            TimeOrder::Desc => {
+                self.end -= 1;
+                self.end
+            }
+        }
+    }
+
+    #[inline]
+    fn is_exhausted(&self) -> bool {
+        self.start >= self.end
+    }
+
+    fn update_timestamp(&mut self, batch: &Batch, timestamp_index: Option<usize>) {
+        let Some(index) = timestamp_index else { return };
+        let row = match self.order {
+            TimeOrder::Asc => self.start,
+            TimeOrder::Desc => self.end - 1,
+        };
+        self.timestamp = batch.column(index).get(row);
+    }
+}
+
+impl Ord for RunCursor {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // `BinaryHeap` pops the greatest cursor first, so the cursor whose next row comes
+        // first in the `order` is the greatest. Rows with the same timestamp are returned
+        // in key order.
+        let by_time = match self.order {
+            TimeOrder::Asc => other.timestamp.cmp(&self.timestamp),
+            TimeOrder::Desc => self.timestamp.cmp(&other.timestamp),
+        };
+        by_time.then_with(|| other.run.cmp(&self.run))
+    }
+}
+
+impl PartialOrd for RunCursor {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for RunCursor {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for RunCursor {}
+
+#[cfg(test)]
+mod tests {
+    use futures::FutureExt;
+
+    use super::*;
+    use crate::test_util::read_util;
+
+    fn new_window(batches: &[&[(i64, Option<i64>)]]) -> WindowReaderFuture {
+        let reader = read_util::build_boxed_reader(batches);
+        async move { Ok(reader) }.boxed()
+    }
+
+    #[tokio::test]
+    async fn test_windowed_reader_asc() {
+        let windows = vec![
+            new_window(&[&[(1, Some(1)), (3, Some(3))], &[(2, Some(2))]]),
+            new_window(&[]),
+            new_window(&[&[(4, None)], &[(6, Some(6)), (5, Some(5))]]),
+        ];
+        let mut reader = WindowedReader::new(
+            read_util::new_projected_schema(),
+            windows,
+            TimeOrder::Asc,
+            2,
+        );
+
+        read_util::check_reader_with_kv_batch(
+            &mut reader,
+            &[
+                &[(1, Some(1)), (2, Some(2))],
+                &[(3, Some(3))],
+                &[(4, None), (5, Some(5))],
+                &[(6, Some(6))],
+            ],
+        )
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_windowed_reader_desc() {
+        let windows = vec![
+            new_window(&[&[(4, None)], &[(5, Some(5)), (6, Some(6))]]),
+            new_window(&[&[(1, Some(1)), (2, Some(2))], &[(3, Some(3))]]),
+        ];
+        let mut reader = WindowedReader::new(
+            read_util::new_projected_schema(),
+            windows,
+            TimeOrder::Desc,
+            10,
+        );
+
+        read_util::check_reader_with_kv_batch(
+            &mut reader,
+            &[
+                &[(6, Some(6)), (5, Some(5)), (4, None)],
+                &[(3, Some(3)), (2, Some(2)), (1, Some(1))],
+            ],
+        )
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_windowed_reader_merge_runs() {
+        // Rows of two series in key order, the second series starts at row 3.
+        let rows: &[&[(i64, Option<i64>)]] = &[
+            &[(1, Some(1)), (3, Some(3)), (5, Some(5)), (2, Some(2))],
+            &[(4, Some(4)), (6, Some(6))],
+        ];
+
+        let mut reader = WindowedReader::new(
+            read_util::new_projected_schema(),
+            vec![new_window(rows)],
+            TimeOrder::Asc,
+            4,
+        );
+        read_util::check_reader_with_kv_batch(
+            &mut reader,
+            &[
+                &[(1, Some(1)), (2, Some(2)), (3, Some(3)), (4, Some(4))],
+                &[(5, Some(5)), (6, Some(6))],
+            ],
+        )
+        .await;
+
+        let mut reader = WindowedReader::new(
+            read_util::new_projected_schema(),
+            vec![new_window(rows)],
+            TimeOrder::Desc,
+            4,
+        );
+        read_util::check_reader_with_kv_batch(
+            &mut reader,
+            &[
+                &[(6, Some(6)), (5, Some(5)), (4, Some(4)), (3, Some(3))],
+                &[(2, Some(2)), (1, Some(1))],
+            ],
+        )
+        .await;
+    }
+}
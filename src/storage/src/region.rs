@@ -16,18 +16,24 @@
 mod tests;
 mod writer;
 use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
 
 use async_trait::async_trait;
 use common_telemetry::logging;
+use object_store::ObjectStore;
 use snafu::ResultExt;
 use store_api::logstore::LogStore;
 use store_api::manifest::{self, Manifest, ManifestVersion, MetaActionIterator};
 use store_api::storage::{
-    AlterRequest, OpenOptions, ReadContext, Region, RegionId, SequenceNumber, WriteContext,
-    WriteResponse,
+    AlterRequest, FlushContext, OpenOptions, ReadContext, Region, RegionId, SequenceNumber,
+    WriteContext, WriteResponse,
 };
 
+use crate::backup::{BackupLocation, RegionBackup};
+use crate::compaction::{CompactionRequestImpl, CompactionSchedulerRef};
+use crate::config::WalCompression;
 use crate::error::{self, Error, Result};
 use crate::flush::{FlushSchedulerRef, FlushStrategyRef};
 use crate::manifest::action::{
@@ -39,11 +45,11 @@ use crate::metadata::{RegionMetaImpl, RegionMetadata, RegionMetadataRef};
 pub use crate::region::writer::{AlterContext, RegionWriter, RegionWriterRef, WriterContext};
 use crate::schema::compat::CompatWrite;
 use crate::snapshot::SnapshotImpl;
-use crate::sst::AccessLayerRef;
+use crate::sst::{AccessLayerRef, FileMeta, FileTier};
 use crate::version::{
     Version, VersionControl, VersionControlRef, VersionEdit, INIT_COMMITTED_SEQUENCE,
 };
-use crate::wal::Wal;
+use crate::wal::{GroupCommitterRef, Wal};
 use crate::write_batch::WriteBatch;
 
 /// [Region] implementation.
@@ -101,19 +107,31 @@ impl<S: LogStore> Region for RegionImpl<S> {
     async fn alter(&self, request: AlterRequest) -> Result<()> {
         self.inner.alter(request).await
     }
+
+    async fn flush(&self, ctx: &FlushContext) -> Result<()> {
+        self.inner.flush(ctx).await
+    }
 }
 
 /// Storage related config for region.
 ///
 /// Contains all necessary storage related components needed by the region, such as logstore,
 /// manifest, memtable builder.
-pub struct StoreConfig<S> {
+pub struct StoreConfig<S: LogStore> {
     pub log_store: Arc<S>,
     pub sst_layer: AccessLayerRef,
     pub manifest: RegionManifest,
     pub memtable_builder: MemtableBuilderRef,
     pub flush_scheduler: FlushSchedulerRef,
     pub flush_strategy: FlushStrategyRef,
+    pub compaction_scheduler: CompactionSchedulerRef<S>,
+    /// TTL of the region data, `None` means the data never expires.
+    pub ttl: Option<Duration>,
+    /// Compression of payloads written to the WAL.
+    pub wal_compression: WalCompression,
+    /// Committer to append WAL entries with other regions, `None` if the region appends
+    /// its entries alone.
+    pub wal_group_committer: Option<GroupCommitterRef<S>>,
 }
 
 pub type RecoverdMetadata = (SequenceNumber, (ManifestVersion, RawRegionMetadata));
@@ -143,7 +161,12 @@ impl<S: LogStore> RegionImpl<S> {
         let mutable_memtable = store_config
             .memtable_builder
             .build(metadata.schema().clone());
-        let version = Version::with_manifest_version(metadata, manifest_version, mutable_memtable);
+        let version = Version::with_manifest_version(
+            metadata,
+            manifest_version,
+            mutable_memtable,
+            store_config.sst_layer.clone(),
+        );
         let region = RegionImpl::new(version, store_config);
 
         Ok(region)
@@ -155,18 +178,23 @@ impl<S: LogStore> RegionImpl<S> {
         let id = metadata.id();
         let name = metadata.name().to_string();
         let version_control = VersionControl::with_version(version);
-        let wal = Wal::new(id, store_config.log_store);
+        let wal = Wal::new(id, store_config.log_store)
+            .with_compression(store_config.wal_compression)
+            .with_group_committer(store_config.wal_group_committer);
 
         let inner = Arc::new(RegionInner {
             shared: Arc::new(SharedData {
                 id,
                 name,
                 version_control: Arc::new(version_control),
+                ttl: store_config.ttl,
+                dropped: AtomicBool::new(false),
             }),
             writer: Arc::new(RegionWriter::new(store_config.memtable_builder)),
             wal,
             flush_strategy: store_config.flush_strategy,
             flush_scheduler: store_config.flush_scheduler,
+            compaction_scheduler: store_config.compaction_scheduler,
             sst_layer: store_config.sst_layer,
             manifest: store_config.manifest,
         });
@@ -186,6 +214,7 @@ impl<S: LogStore> RegionImpl<S> {
         let (version, mut recovered_metadata) = match Self::recover_from_manifest(
             &store_config.manifest,
             &store_config.memtable_builder,
+            &store_config.sst_layer,
         )
         .await?
         {
@@ -228,12 +257,16 @@ impl<S: LogStore> RegionImpl<S> {
             );
         }
 
-        let wal = Wal::new(metadata.id(), store_config.log_store);
+        let wal = Wal::new(metadata.id(), store_config.log_store)
+            .with_compression(store_config.wal_compression)
+            .with_group_committer(store_config.wal_group_committer);
         wal.obsolete(flushed_sequence).await?;
         let shared = Arc::new(SharedData {
             id: metadata.id(),
             name,
             version_control,
+            ttl: store_config.ttl,
+            dropped: AtomicBool::new(false),
         });
 
         let writer = Arc::new(RegionWriter::new(store_config.memtable_builder));
@@ -241,6 +274,7 @@ impl<S: LogStore> RegionImpl<S> {
             shared: &shared,
             flush_strategy: &store_config.flush_strategy,
             flush_scheduler: &store_config.flush_scheduler,
+            compaction_scheduler: &store_config.compaction_scheduler,
             sst_layer: &store_config.sst_layer,
             wal: &wal,
             writer: &writer,
@@ -257,6 +291,7 @@ impl<S: LogStore> RegionImpl<S> {
             wal,
             flush_strategy: store_config.flush_strategy,
             flush_scheduler: store_config.flush_scheduler,
+            compaction_scheduler: store_config.compaction_scheduler,
             sst_layer: store_config.sst_layer,
             manifest: store_config.manifest,
         });
@@ -269,20 +304,84 @@ impl<S: LogStore> RegionImpl<S> {
         self.inner.shared.id()
     }
 
+    /// Flush the region in background if it has not been flushed for `interval`.
+    pub(crate) async fn flush_if_idle(&self, interval: Duration) -> Result<()> {
+        let inner = &self.inner;
+        inner
+            .writer
+            .flush_if_idle(inner.writer_ctx(), interval)
+            .await
+    }
+
+    /// Schedules a compaction for the region in background if the region has a TTL or
+    /// a cold tier, so expired SSTs are removed and old SSTs are moved to the cold tier
+    /// without waiting for a flush.
+    pub(crate) async fn compact_periodically(&self) -> Result<()> {
+        if self.inner.shared.ttl().is_none() && !self.inner.sst_layer.has_cold_tier() {
+            return Ok(());
+        }
+        self.inner.schedule_compaction().await
+    }
+
+    /// Flushes the region and exports its SSTs and manifest to `parent_dir` of
+    /// `object_store`, returns the flushed sequence of the backup.
+    ///
+    /// Rows written during the export may be absent from the backup.
+    pub async fn export(
+        &self,
+        object_store: ObjectStore,
+        parent_dir: &str,
+    ) -> Result<SequenceNumber> {
+        let location = BackupLocation::new(object_store, parent_dir, self.name());
+        self.inner.export(&location).await
+    }
+
+    /// Drop the region.
+    ///
+    /// The compaction job of the region is stopped first. All SST files of the region
+    /// are marked as deleted and would be purged once no snapshot reads them, then the
+    /// directory of the region is removed. The manifest of the region is also removed.
+    pub async fn drop_region(&self) -> Result<()> {
+        self.inner.drop_region().await
+    }
+
     async fn recover_from_manifest(
         manifest: &RegionManifest,
         memtable_builder: &MemtableBuilderRef,
+        sst_layer: &AccessLayerRef,
     ) -> Result<(Option<Version>, RecoveredMetadataMap)> {
-        let (start, end) = Self::manifest_scan_range();
+        let (mut start, end) = Self::manifest_scan_range();
+        let mut last_manifest_version = manifest::MIN_VERSION;
+        let mut protocol = None;
+        // Recover from the latest checkpoint first, then replay actions after it.
+        let mut checkpoint_actions = Vec::new();
+        if let Some(checkpoint) = manifest.last_checkpoint().await? {
+            logging::debug!(
+                "Region recover from manifest checkpoint, last_version: {}",
+                checkpoint.last_version
+            );
+
+            start = checkpoint.last_version + 1;
+            last_manifest_version = checkpoint.last_version;
+            protocol = Some(checkpoint.protocol.clone());
+            checkpoint_actions = checkpoint.into_actions();
+        }
+        let mut checkpoint_actions = checkpoint_actions.into_iter();
         let mut iter = manifest.scan(start, end).await?;
 
         let mut version = None;
         let mut actions = Vec::new();
-        let mut last_manifest_version = manifest::MIN_VERSION;
         let mut recovered_metadata = BTreeMap::new();
 
-        while let Some((manifest_version, action_list)) = iter.next_action().await? {
-            last_manifest_version = manifest_version;
+        loop {
+            let (manifest_version, action_list) = match checkpoint_actions.next() {
+                Some(checkpoint_action) => checkpoint_action,
+                None => match iter.next_action().await? {
+                    Some(action) => action,
+                    None => break,
+                },
+            };
+            last_manifest_version = last_manifest_version.max(manifest_version);
 
             for action in action_list.actions {
                 match (action, version) {
@@ -297,8 +396,9 @@ impl<S: LogStore> RegionImpl<S> {
                         let memtable = memtable_builder.build(region_metadata.schema().clone());
                         version = Some(Version::with_manifest_version(
                             Arc::new(region_metadata),
-                            last_manifest_version,
+                            manifest_version,
                             memtable,
+                            sst_layer.clone(),
                         ));
                         for (manifest_version, action) in actions.drain(..) {
                             version = Self::replay_edit(manifest_version, action, version);
@@ -324,8 +424,10 @@ impl<S: LogStore> RegionImpl<S> {
 
         if version.is_some() {
             // update manifest state after recovering
-            let protocol = iter.last_protocol();
-            manifest.update_state(last_manifest_version + 1, protocol.clone());
+            if iter.last_protocol().is_some() {
+                protocol = iter.last_protocol().clone();
+            }
+            manifest.update_state(last_manifest_version + 1, protocol);
         }
 
         Ok((version, recovered_metadata))
@@ -344,7 +446,8 @@ impl<S: LogStore> RegionImpl<S> {
         if let RegionMetaAction::Edit(e) = action {
             let edit = VersionEdit {
                 files_to_add: e.files_to_add,
-                flushed_sequence: Some(e.flushed_sequence),
+                files_to_remove: e.files_to_remove,
+                flushed_sequence: e.flushed_sequence,
                 manifest_version,
                 max_memtable_id: None,
             };
@@ -382,15 +485,7 @@ impl<S: LogStore> RegionImpl<S> {
     // Replay metadata to inner.
     async fn replay_inner(&self, recovered_metadata: RecoveredMetadataMap) -> Result<()> {
         let inner = &self.inner;
-        let writer_ctx = WriterContext {
-            shared: &inner.shared,
-            flush_strategy: &inner.flush_strategy,
-            flush_scheduler: &inner.flush_scheduler,
-            sst_layer: &inner.sst_layer,
-            wal: &inner.wal,
-            writer: &inner.writer,
-            manifest: &inner.manifest,
-        };
+        let writer_ctx = inner.writer_ctx();
 
         inner.writer.replay(recovered_metadata, writer_ctx).await
     }
@@ -405,6 +500,11 @@ pub struct SharedData {
     name: String,
     // TODO(yingwen): Maybe no need to use Arc for version control.
     pub version_control: VersionControlRef,
+    /// TTL of the region data.
+    ttl: Option<Duration>,
+    /// Whether the region is dropped. A dropped region rejects writes, flushes and
+    /// compactions, so it won't add SSTs or manifest actions any more.
+    dropped: AtomicBool,
 }
 
 impl SharedData {
@@ -417,6 +517,21 @@ impl SharedData {
     pub fn name(&self) -> &str {
         &self.name
     }
+
+    #[inline]
+    pub fn ttl(&self) -> Option<Duration> {
+        self.ttl
+    }
+
+    #[inline]
+    pub fn is_dropped(&self) -> bool {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    #[inline]
+    fn mark_dropped(&self) {
+        self.dropped.store(true, Ordering::Relaxed);
+    }
 }
 
 pub type SharedDataRef = Arc<SharedData>;
@@ -428,6 +543,7 @@ struct RegionInner<S: LogStore> {
     wal: Wal<S>,
     flush_strategy: FlushStrategyRef,
     flush_scheduler: FlushSchedulerRef,
+    compaction_scheduler: CompactionSchedulerRef<S>,
     sst_layer: AccessLayerRef,
     manifest: RegionManifest,
 }
@@ -448,7 +564,7 @@ impl<S: LogStore> RegionInner<S> {
         let version = self.version_control().current();
         let sequence = self.version_control().committed_sequence();
 
-        SnapshotImpl::new(version, sequence, self.sst_layer.clone())
+        SnapshotImpl::new(version, sequence, self.sst_layer.clone(), self.shared.ttl())
     }
 
     fn compat_write_batch(&self, request: &mut WriteBatch) -> Result<()> {
@@ -460,17 +576,36 @@ impl<S: LogStore> RegionInner<S> {
         request.compat_write(schema.user_schema())
     }
 
-    /// Write to writer directly.
-    async fn write(&self, ctx: &WriteContext, request: WriteBatch) -> Result<WriteResponse> {
-        let writer_ctx = WriterContext {
+    fn writer_ctx(&self) -> WriterContext<'_, S> {
+        WriterContext {
             shared: &self.shared,
             flush_strategy: &self.flush_strategy,
             flush_scheduler: &self.flush_scheduler,
+            compaction_scheduler: &self.compaction_scheduler,
             sst_layer: &self.sst_layer,
             wal: &self.wal,
             writer: &self.writer,
             manifest: &self.manifest,
+        }
+    }
+
+    async fn schedule_compaction(&self) -> Result<()> {
+        let request = CompactionRequestImpl {
+            shared: self.shared.clone(),
+            sst_layer: self.sst_layer.clone(),
+            writer: self.writer.clone(),
+            wal: self.wal.clone(),
+            manifest: self.manifest.clone(),
         };
+        self.compaction_scheduler
+            .schedule_compaction(request)
+            .await
+            .map(|_| ())
+    }
+
+    /// Write to writer directly.
+    async fn write(&self, ctx: &WriteContext, request: WriteBatch) -> Result<WriteResponse> {
+        let writer_ctx = self.writer_ctx();
         // The writer would also try to compat the schema of write batch if it finds out the
         // schema version of request is less than current schema version.
         self.writer.write(ctx, request, writer_ctx).await
@@ -492,4 +627,82 @@ impl<S: LogStore> RegionInner<S> {
 
         self.writer.alter(alter_ctx, request).await
     }
+
+    async fn flush(&self, ctx: &FlushContext) -> Result<()> {
+        logging::info!(
+            "Flush region {}, name: {}, wait: {}",
+            self.shared.id,
+            self.shared.name,
+            ctx.wait
+        );
+
+        self.writer.flush(self.writer_ctx(), ctx.wait).await
+    }
+
+    async fn export(&self, location: &BackupLocation) -> Result<SequenceNumber> {
+        logging::info!(
+            "Export region {}, name: {} to {:?}",
+            self.shared.id,
+            self.shared.name,
+            location
+        );
+
+        location.ensure_absent().await?;
+        // Flush and wait, so all rows committed before the export are in SSTs.
+        self.writer.flush(self.writer_ctx(), true).await?;
+        // The snapshot holds SSTs of its version, so they won't be purged by the
+        // compaction during the copy.
+        let snapshot = self.create_snapshot();
+        let version = snapshot.version();
+        let sst_dir = location.sst_dir();
+        let mut files = Vec::new();
+        for level in version.ssts().levels() {
+            for file in level.files() {
+                self.sst_layer
+                    .export_sst(
+                        file.file_name(),
+                        file.tier(),
+                        &location.object_store,
+                        &sst_dir,
+                    )
+                    .await?;
+                files.push(FileMeta {
+                    tier: FileTier::Hot,
+                    ..file.meta()
+                });
+            }
+        }
+        let backup = RegionBackup {
+            metadata: version.metadata().as_ref().into(),
+            flushed_sequence: version.flushed_sequence(),
+            files,
+        };
+        // Writes the manifest last, so a backup is only visible after all its files are copied.
+        backup.write_manifest(location).await?;
+
+        Ok(backup.flushed_sequence)
+    }
+
+    async fn drop_region(&self) -> Result<()> {
+        logging::info!("Drop region {}, name: {}", self.shared.id, self.shared.name);
+
+        // Reject new flushes and compactions first, then wait until the running flush
+        // job finished, so no more SST would be added to the region. The flush job may
+        // schedule a compaction, so the compaction job is stopped after that.
+        self.shared.mark_dropped();
+        self.writer.wait_flush_done().await?;
+        self.compaction_scheduler
+            .stop_compaction(self.shared.id())
+            .await?;
+
+        let version = self.version_control().current();
+        version.ssts().mark_all_files_deleted();
+        // The region directory is removed once all SSTs are purged.
+        self.sst_layer.mark_region_dropped();
+
+        let committed_sequence = self.version_control().committed_sequence();
+        self.wal.obsolete(committed_sequence).await?;
+
+        self.manifest.delete_all().await
+    }
 }
@@ -15,23 +15,29 @@
 //! Region tests.
 
 mod alter;
+mod backup;
 mod basic;
+mod compact;
 mod flush;
+mod inspect;
 mod projection;
 
 use std::collections::HashMap;
 
 use common_telemetry::logging;
+use common_time::Timestamp;
 use datatypes::prelude::{ScalarVector, WrapperType};
 use datatypes::timestamp::TimestampMillisecond;
 use datatypes::type_id::LogicalTypeId;
+use datatypes::value::Value;
 use datatypes::vectors::{Int64Vector, TimestampMillisecondVector, VectorRef};
 use log_store::raft_engine::log_store::RaftEngineLogStore;
 use log_store::NoopLogStore;
 use object_store::backend::fs;
 use object_store::ObjectStore;
 use store_api::storage::{
-    consts, Chunk, ChunkReader, RegionMeta, ScanRequest, SequenceNumber, Snapshot, WriteRequest,
+    consts, Chunk, ChunkReader, GetRequest, RegionMeta, ScanRequest, SequenceNumber, Snapshot,
+    WriteRequest,
 };
 use tempdir::TempDir;
 
@@ -39,6 +45,7 @@ use super::*;
 use crate::manifest::action::{RegionChange, RegionMetaActionList};
 use crate::manifest::test_utils::*;
 use crate::memtable::DefaultMemtableBuilder;
+use crate::test_util::access_layer_util::MockAccessLayer;
 use crate::test_util::descriptor_util::RegionDescBuilder;
 use crate::test_util::{self, config_util, schema_util, write_batch_util};
 
@@ -106,12 +113,14 @@ impl<S: LogStore> TesterBase<S> {
     /// Scan all data.
     pub async fn full_scan(&self) -> Vec<(i64, Option<i64>)> {
         logging::info!("Full scan with ctx {:?}", self.read_ctx);
+        self.scan(ScanRequest::default()).await
+    }
+
+    /// Scan data by the `request`.
+    pub async fn scan(&self, request: ScanRequest) -> Vec<(i64, Option<i64>)> {
         let snapshot = self.region.snapshot(&self.read_ctx).unwrap();
 
-        let resp = snapshot
-            .scan(&self.read_ctx, ScanRequest::default())
-            .await
-            .unwrap();
+        let resp = snapshot.scan(&self.read_ctx, request).await.unwrap();
         let mut reader = resp.reader;
 
         let metadata = self.region.in_memory_metadata();
@@ -125,6 +134,55 @@ impl<S: LogStore> TesterBase<S> {
         dst
     }
 
+    /// Scan the region in at most `num_partitions` partitions, returns rows of each
+    /// partition.
+    pub async fn scan_partitions(
+        &self,
+        request: ScanRequest,
+        num_partitions: usize,
+    ) -> Vec<Vec<(i64, Option<i64>)>> {
+        let snapshot = self.region.snapshot(&self.read_ctx).unwrap();
+
+        let responses = snapshot
+            .scan_partitions(&self.read_ctx, request, num_partitions)
+            .await
+            .unwrap();
+        let mut partitions = Vec::with_capacity(responses.len());
+        for resp in responses {
+            let mut reader = resp.reader;
+            let mut dst = Vec::new();
+            while let Some(chunk) = reader.next_chunk().await.unwrap() {
+                append_chunk_to(&chunk, &mut dst);
+            }
+            partitions.push(dst);
+        }
+
+        partitions
+    }
+
+    /// Get the row with timestamp `ts`.
+    pub async fn get(&self, ts: i64) -> Option<(i64, Option<i64>)> {
+        let snapshot = self.region.snapshot(&self.read_ctx).unwrap();
+        let request = GetRequest {
+            primary_key: HashMap::new(),
+            timestamp: Timestamp::new_millisecond(ts),
+            sequence: None,
+            projection: None,
+        };
+        let row = snapshot.get(&self.read_ctx, request).await.unwrap().row?;
+
+        let ts = match &row[0] {
+            Value::Timestamp(ts) => ts.value(),
+            v => panic!("Unexpected timestamp {v:?}"),
+        };
+        let v0 = match &row[1] {
+            Value::Int64(v) => Some(*v),
+            Value::Null => None,
+            v => panic!("Unexpected value {v:?}"),
+        };
+        Some((ts, v0))
+    }
+
     pub fn committed_sequence(&self) -> SequenceNumber {
         self.region.committed_sequence()
     }
@@ -220,6 +278,32 @@ fn append_chunk_to(chunk: &Chunk, dst: &mut Vec<(i64, Option<i64>)>) {
     }
 }
 
+/// Returns the number of parquet files in `sst_dir`.
+fn parquet_file_num(sst_dir: &str) -> usize {
+    std::fs::read_dir(sst_dir)
+        .unwrap()
+        .filter(|entry| {
+            let path = entry.as_ref().unwrap().path();
+            path.extension()
+                .map(|ext| ext == "parquet")
+                .unwrap_or(false)
+        })
+        .count()
+}
+
+/// Waits until there are `expect` parquet files in `sst_dir`, as files removed
+/// from the region are purged in background.
+async fn wait_parquet_file_num(sst_dir: &str, expect: usize) {
+    for _ in 0..100 {
+        if parquet_file_num(sst_dir) == expect {
+            return;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+    }
+
+    assert_eq!(expect, parquet_file_num(sst_dir));
+}
+
 #[tokio::test]
 async fn test_new_region() {
     let region_name = "region-0";
@@ -242,7 +326,11 @@ async fn test_new_region() {
         .build(metadata.schema().clone());
 
     let region = RegionImpl::new(
-        Version::new(Arc::new(metadata), placeholder_memtable),
+        Version::new(
+            Arc::new(metadata),
+            placeholder_memtable,
+            store_config.sst_layer.clone(),
+        ),
         store_config,
     );
 
@@ -268,6 +356,7 @@ async fn test_new_region() {
 async fn test_recover_region_manifets() {
     let tmp_dir = TempDir::new("test_new_region").unwrap();
     let memtable_builder = Arc::new(DefaultMemtableBuilder::default()) as _;
+    let sst_layer = Arc::new(MockAccessLayer::default()) as _;
 
     let object_store = ObjectStore::new(
         fs::Builder::default()
@@ -280,13 +369,15 @@ async fn test_recover_region_manifets() {
     let region_meta = Arc::new(build_region_meta());
 
     // Recover from empty
-    assert!(
-        RegionImpl::<NoopLogStore>::recover_from_manifest(&manifest, &memtable_builder)
-            .await
-            .unwrap()
-            .0
-            .is_none()
-    );
+    assert!(RegionImpl::<NoopLogStore>::recover_from_manifest(
+        &manifest,
+        &memtable_builder,
+        &sst_layer,
+    )
+    .await
+    .unwrap()
+    .0
+    .is_none());
 
     {
         // save some actions into region_meta
@@ -321,7 +412,7 @@ async fn test_recover_region_manifets() {
 
     // try to recover
     let (version, recovered_metadata) =
-        RegionImpl::<NoopLogStore>::recover_from_manifest(&manifest, &memtable_builder)
+        RegionImpl::<NoopLogStore>::recover_from_manifest(&manifest, &memtable_builder, &sst_layer)
             .await
             .unwrap();
 
@@ -0,0 +1,101 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Region backup tests.
+
+use object_store::backend::fs::Builder;
+use object_store::ObjectStore;
+use store_api::storage::{EngineContext, OpenOptions, Region};
+use tempdir::TempDir;
+
+use crate::backup::BackupLocation;
+use crate::config::EngineConfig;
+use crate::engine::EngineImpl;
+use crate::error::Error;
+use crate::region::tests::{self, FileTesterBase};
+use crate::region::RegionImpl;
+use crate::test_util::config_util;
+
+const REGION_NAME: &str = "region-backup-0";
+const RESTORED_REGION_NAME: &str = "region-backup-1";
+
+fn new_object_store(dir: &str) -> ObjectStore {
+    ObjectStore::new(Builder::default().root(dir).build().unwrap())
+}
+
+#[tokio::test]
+async fn test_export_and_restore_region() {
+    common_telemetry::init_default_ut_logging();
+
+    let dir = TempDir::new("export-region").unwrap();
+    let store_dir = dir.path().to_str().unwrap();
+    let backup_dir = TempDir::new("export-region-backup").unwrap();
+    let backup_store = new_object_store(backup_dir.path().to_str().unwrap());
+
+    let metadata = tests::new_metadata(REGION_NAME, false);
+    let store_config = config_util::new_store_config(REGION_NAME, store_dir).await;
+    let log_store = store_config.log_store.clone();
+    let region = RegionImpl::create(metadata, store_config).await.unwrap();
+    let tester = FileTesterBase::with_region(region);
+
+    let data = [(1000, Some(100)), (2000, Some(200))];
+    tester.put(&data).await;
+    let flushed_sequence = tester
+        .region
+        .export(backup_store.clone(), "backup")
+        .await
+        .unwrap();
+    assert_eq!(tester.committed_sequence(), flushed_sequence);
+    // Rows written after the export are not in the backup.
+    tester.put(&[(3000, Some(300))]).await;
+
+    // Exports to the same location again.
+    let err = tester
+        .region
+        .export(backup_store.clone(), "backup")
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::RegionExistsInDir { .. }), "{err:?}");
+
+    let restore_dir = TempDir::new("restore-region").unwrap();
+    let engine = EngineImpl::new(
+        EngineConfig::default(),
+        log_store,
+        new_object_store(restore_dir.path().to_str().unwrap()),
+    );
+    let ctx = EngineContext::default();
+    let location = BackupLocation::new(backup_store, "backup", REGION_NAME);
+    let opts = OpenOptions::default();
+    let restored = engine
+        .restore_region(&ctx, &location, 1024, RESTORED_REGION_NAME, &opts)
+        .await
+        .unwrap();
+    assert_eq!(1024, restored.id());
+    assert_eq!(RESTORED_REGION_NAME, restored.name());
+
+    let restored = FileTesterBase::with_region(restored);
+    assert_eq!(data.to_vec(), restored.full_scan().await);
+    assert_eq!(flushed_sequence, restored.committed_sequence());
+
+    // The restored region is writable.
+    restored.put(&[(3000, Some(300))]).await;
+    assert_eq!(3, restored.full_scan().await.len());
+
+    // Restores to an existing region.
+    let err = engine
+        .restore_region(&ctx, &location, 1024, RESTORED_REGION_NAME, &opts)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::RegionExists { .. }), "{err:?}");
+}
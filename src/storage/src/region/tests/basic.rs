@@ -16,7 +16,7 @@
 
 use common_telemetry::info;
 use log_store::raft_engine::log_store::RaftEngineLogStore;
-use store_api::storage::{OpenOptions, SequenceNumber, WriteResponse};
+use store_api::storage::{FlushContext, OpenOptions, Region, SequenceNumber, WriteResponse};
 use tempdir::TempDir;
 
 use crate::error::Result;
@@ -109,6 +109,10 @@ impl Tester {
         self.base().full_scan().await
     }
 
+    async fn get(&self, ts: i64) -> Option<(i64, Option<i64>)> {
+        self.base().get(ts).await
+    }
+
     fn committed_sequence(&self) -> SequenceNumber {
         self.base().committed_sequence()
     }
@@ -274,3 +278,30 @@ async fn test_put_delete_absent_key() {
     let output = tester.full_scan().await;
     assert_eq!(expect, output);
 }
+
+#[tokio::test]
+async fn test_put_get() {
+    let dir = TempDir::new("put-get").unwrap();
+    let store_dir = dir.path().to_str().unwrap();
+    let tester = Tester::new(REGION_NAME, store_dir).await;
+
+    tester
+        .put(&[(1000, Some(100)), (1001, None), (1002, Some(102))])
+        .await;
+    assert_eq!(Some((1000, Some(100))), tester.get(1000).await);
+    assert_eq!(Some((1001, None)), tester.get(1001).await);
+    assert_eq!(None, tester.get(1003).await);
+
+    // Get rows from the SST and memtable.
+    tester
+        .base()
+        .region
+        .flush(&FlushContext::default())
+        .await
+        .unwrap();
+    tester.put(&[(1000, Some(200))]).await;
+    tester.delete(&[1002]).await;
+    assert_eq!(Some((1000, Some(200))), tester.get(1000).await);
+    assert_eq!(Some((1001, None)), tester.get(1001).await);
+    assert_eq!(None, tester.get(1002).await);
+}
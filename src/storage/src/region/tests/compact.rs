@@ -0,0 +1,380 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Region compaction tests.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use log_store::raft_engine::log_store::RaftEngineLogStore;
+use object_store::backend::fs::Builder;
+use object_store::ObjectStore;
+use store_api::storage::{FlushContext, OpenOptions, Region};
+use tempdir::TempDir;
+
+use crate::background::JobPoolImpl;
+use crate::compaction::{
+    CompactionRequestImpl, CompactionScheduler, CompactionSchedulerImpl, SimplePicker,
+};
+use crate::engine;
+use crate::region::tests::flush::FlushSwitch;
+use crate::region::tests::{self, FileTesterBase};
+use crate::region::{RegionImpl, StoreConfig};
+use crate::sst::{FileTier, FsAccessLayer};
+use crate::test_util::config_util;
+
+const REGION_NAME: &str = "region-compact-0";
+
+fn new_compaction_request(
+    region: &RegionImpl<RaftEngineLogStore>,
+) -> CompactionRequestImpl<RaftEngineLogStore> {
+    let inner = &region.inner;
+    CompactionRequestImpl {
+        shared: inner.shared.clone(),
+        sst_layer: inner.sst_layer.clone(),
+        writer: inner.writer.clone(),
+        wal: inner.wal.clone(),
+        manifest: inner.manifest.clone(),
+    }
+}
+
+fn file_tiers_in_level(region: &RegionImpl<RaftEngineLogStore>, level: usize) -> Vec<FileTier> {
+    let version = region.inner.version_control().current();
+    version
+        .ssts()
+        .level(level)
+        .files()
+        .iter()
+        .map(|file| file.tier())
+        .collect()
+}
+
+/// Returns a store config whose SST layer has a cold store under `cold_dir`.
+async fn new_tiered_store_config(
+    store_dir: &str,
+    cold_dir: &str,
+) -> StoreConfig<RaftEngineLogStore> {
+    let mut store_config = config_util::new_store_config(REGION_NAME, store_dir).await;
+    let sst_dir = engine::region_sst_dir("", REGION_NAME);
+    let object_store = ObjectStore::new(Builder::default().root(store_dir).build().unwrap());
+    let cold_store = ObjectStore::new(Builder::default().root(cold_dir).build().unwrap());
+    store_config.sst_layer =
+        Arc::new(FsAccessLayer::new(&sst_dir, object_store).with_cold_store(Some(cold_store)));
+    store_config
+}
+
+fn num_files_in_level(region: &RegionImpl<RaftEngineLogStore>, level: usize) -> usize {
+    let version = region.inner.version_control().current();
+    version.ssts().level(level).file_num()
+}
+
+#[tokio::test]
+async fn test_compact_level0_files() {
+    common_telemetry::init_default_ut_logging();
+
+    let dir = TempDir::new("compact-level0").unwrap();
+    let store_dir = dir.path().to_str().unwrap();
+
+    let flush_switch = Arc::new(FlushSwitch::default());
+    let metadata = tests::new_metadata(REGION_NAME, false);
+    let mut store_config = config_util::new_store_config(REGION_NAME, store_dir).await;
+    store_config.flush_strategy = flush_switch.clone();
+    let region = RegionImpl::create(metadata, store_config).await.unwrap();
+    let tester = FileTesterBase::with_region(region);
+
+    // Every write flushes rows of the previous write to a new SST.
+    flush_switch.set_should_flush(true);
+    tester.put(&[(1000, Some(100))]).await;
+    tester.put(&[(2000, Some(200))]).await;
+    tester.put(&[(1000, Some(101))]).await;
+    tester.delete(&[2000]).await;
+    tester.put(&[(3000, Some(300))]).await;
+    tester.region.wait_flush_done().await.unwrap();
+    flush_switch.set_should_flush(false);
+
+    assert_eq!(4, num_files_in_level(&tester.region, 0));
+    assert_eq!(0, num_files_in_level(&tester.region, 1));
+
+    let scheduler = CompactionSchedulerImpl::new(Arc::new(JobPoolImpl {}), SimplePicker::new(2));
+    let handle = scheduler
+        .schedule_compaction(new_compaction_request(&tester.region))
+        .await
+        .unwrap()
+        .unwrap();
+    handle.join().await.unwrap();
+
+    assert_eq!(0, num_files_in_level(&tester.region, 0));
+    assert_eq!(1, num_files_in_level(&tester.region, 1));
+
+    let expect = vec![(1000, Some(101)), (3000, Some(300))];
+    assert_eq!(expect, tester.full_scan().await);
+
+    // Input files of the compaction are purged.
+    let sst_dir = format!("{}/{}", store_dir, engine::region_sst_dir("", REGION_NAME));
+    tests::wait_parquet_file_num(&sst_dir, 1).await;
+
+    // Reopen the region and the compaction result should be recovered from the manifest.
+    tester.close().await;
+    let store_config = config_util::new_store_config(REGION_NAME, store_dir).await;
+    let region = RegionImpl::open(
+        REGION_NAME.to_string(),
+        store_config,
+        &OpenOptions::default(),
+    )
+    .await
+    .unwrap()
+    .unwrap();
+    let tester = FileTesterBase::with_region(region);
+
+    assert_eq!(0, num_files_in_level(&tester.region, 0));
+    assert_eq!(1, num_files_in_level(&tester.region, 1));
+    assert_eq!(expect, tester.full_scan().await);
+}
+
+#[tokio::test]
+async fn test_stop_compaction() {
+    common_telemetry::init_default_ut_logging();
+
+    let dir = TempDir::new("stop-compaction").unwrap();
+    let store_dir = dir.path().to_str().unwrap();
+
+    let flush_switch = Arc::new(FlushSwitch::default());
+    let metadata = tests::new_metadata(REGION_NAME, false);
+    let mut store_config = config_util::new_store_config(REGION_NAME, store_dir).await;
+    store_config.flush_strategy = flush_switch.clone();
+    let region = RegionImpl::create(metadata, store_config).await.unwrap();
+    let tester = FileTesterBase::with_region(region);
+
+    flush_switch.set_should_flush(true);
+    tester.put(&[(1000, Some(100))]).await;
+    tester.put(&[(2000, Some(200))]).await;
+    tester.put(&[(3000, Some(300))]).await;
+    tester.region.wait_flush_done().await.unwrap();
+    flush_switch.set_should_flush(false);
+    let expect = tester.full_scan().await;
+
+    let scheduler = CompactionSchedulerImpl::new(Arc::new(JobPoolImpl {}), SimplePicker::new(2));
+    let region_id = tester.region.id();
+    // Stopping a region without compaction job returns immediately.
+    CompactionScheduler::<RaftEngineLogStore>::stop_compaction(&scheduler, region_id)
+        .await
+        .unwrap();
+
+    let handle = scheduler
+        .schedule_compaction(new_compaction_request(&tester.region))
+        .await
+        .unwrap();
+    assert!(handle.is_some());
+    // Only one job for each region.
+    assert!(scheduler
+        .schedule_compaction(new_compaction_request(&tester.region))
+        .await
+        .unwrap()
+        .is_none());
+
+    // The job is either cancelled or finished after it is stopped.
+    CompactionScheduler::<RaftEngineLogStore>::stop_compaction(&scheduler, region_id)
+        .await
+        .unwrap();
+    assert_eq!(expect, tester.full_scan().await);
+    let handle = scheduler
+        .schedule_compaction(new_compaction_request(&tester.region))
+        .await
+        .unwrap();
+    assert!(handle.is_some());
+    let _ = handle.unwrap().join().await;
+}
+
+#[tokio::test]
+async fn test_compact_expired_files() {
+    common_telemetry::init_default_ut_logging();
+
+    let dir = TempDir::new("compact-expired").unwrap();
+    let store_dir = dir.path().to_str().unwrap();
+
+    let flush_switch = Arc::new(FlushSwitch::default());
+    let metadata = tests::new_metadata(REGION_NAME, false);
+    let mut store_config = config_util::new_store_config(REGION_NAME, store_dir).await;
+    store_config.flush_strategy = flush_switch.clone();
+    store_config.ttl = Some(Duration::from_secs(3600));
+    let region = RegionImpl::create(metadata, store_config).await.unwrap();
+    let tester = FileTesterBase::with_region(region);
+
+    let now = common_time::util::current_time_millis();
+    flush_switch.set_should_flush(true);
+    tester.put(&[(1000, Some(100))]).await;
+    tester.put(&[(2000, Some(200))]).await;
+    tester.put(&[(now, Some(300))]).await;
+    tester.put(&[(now + 1, Some(301))]).await;
+    tester.region.wait_flush_done().await.unwrap();
+    flush_switch.set_should_flush(false);
+
+    assert_eq!(3, num_files_in_level(&tester.region, 0));
+    // Expired rows are invisible.
+    let expect = vec![(now, Some(300)), (now + 1, Some(301))];
+    assert_eq!(expect, tester.full_scan().await);
+
+    // Not enough files to merge, but expired files are removed.
+    let scheduler = CompactionSchedulerImpl::new(Arc::new(JobPoolImpl {}), SimplePicker::new(2));
+    let handle = scheduler
+        .schedule_compaction(new_compaction_request(&tester.region))
+        .await
+        .unwrap()
+        .unwrap();
+    handle.join().await.unwrap();
+
+    assert_eq!(1, num_files_in_level(&tester.region, 0));
+    assert_eq!(0, num_files_in_level(&tester.region, 1));
+    assert_eq!(expect, tester.full_scan().await);
+
+    let sst_dir = format!("{}/{}", store_dir, engine::region_sst_dir("", REGION_NAME));
+    tests::wait_parquet_file_num(&sst_dir, 1).await;
+}
+
+#[tokio::test]
+async fn test_compact_expired_files_without_flush() {
+    common_telemetry::init_default_ut_logging();
+
+    let dir = TempDir::new("compact-expired-without-flush").unwrap();
+    let store_dir = dir.path().to_str().unwrap();
+
+    let metadata = tests::new_metadata(REGION_NAME, false);
+    let mut store_config = config_util::new_store_config(REGION_NAME, store_dir).await;
+    store_config.ttl = Some(Duration::from_secs(1));
+    let region = RegionImpl::create(metadata, store_config).await.unwrap();
+    let tester = FileTesterBase::with_region(region);
+
+    let now = common_time::util::current_time_millis();
+    tester.put(&[(now, Some(100)), (now + 1, Some(101))]).await;
+    tester.region.flush(&FlushContext::default()).await.unwrap();
+
+    // The compaction after the flush doesn't remove the file as its rows are alive.
+    assert_eq!(1, num_files_in_level(&tester.region, 0));
+    let sst_dir = format!("{}/{}", store_dir, engine::region_sst_dir("", REGION_NAME));
+    tests::wait_parquet_file_num(&sst_dir, 1).await;
+
+    // Rows expire but no more flush would trigger a compaction.
+    tokio::time::sleep(Duration::from_millis(1500)).await;
+    assert!(tester.full_scan().await.is_empty());
+    assert_eq!(1, num_files_in_level(&tester.region, 0));
+
+    tester.region.compact_periodically().await.unwrap();
+    tests::wait_parquet_file_num(&sst_dir, 0).await;
+    assert_eq!(0, num_files_in_level(&tester.region, 0));
+}
+
+#[tokio::test]
+async fn test_move_cold_files() {
+    common_telemetry::init_default_ut_logging();
+
+    let dir = TempDir::new("compact-cold").unwrap();
+    let store_dir = dir.path().to_str().unwrap();
+    let cold_dir = TempDir::new("compact-cold-remote").unwrap();
+    let cold_dir = cold_dir.path().to_str().unwrap();
+
+    let flush_switch = Arc::new(FlushSwitch::default());
+    let metadata = tests::new_metadata(REGION_NAME, false);
+    let mut store_config = new_tiered_store_config(store_dir, cold_dir).await;
+    store_config.flush_strategy = flush_switch.clone();
+    let region = RegionImpl::create(metadata, store_config).await.unwrap();
+    let tester = FileTesterBase::with_region(region);
+
+    let now = common_time::util::current_time_millis();
+    flush_switch.set_should_flush(true);
+    tester.put(&[(1000, Some(100))]).await;
+    tester.put(&[(now, Some(200))]).await;
+    tester.put(&[(now + 1, Some(201))]).await;
+    tester.region.wait_flush_done().await.unwrap();
+    flush_switch.set_should_flush(false);
+
+    assert_eq!(2, num_files_in_level(&tester.region, 0));
+
+    // Not enough files to merge, but the file older than 1 hour is moved to the cold store.
+    let scheduler = CompactionSchedulerImpl::new(Arc::new(JobPoolImpl {}), SimplePicker::new(4))
+        .with_cold_sst_age(Some(Duration::from_secs(3600)));
+    let handle = scheduler
+        .schedule_compaction(new_compaction_request(&tester.region))
+        .await
+        .unwrap()
+        .unwrap();
+    handle.join().await.unwrap();
+
+    let mut tiers = file_tiers_in_level(&tester.region, 0);
+    tiers.sort_by_key(|tier| *tier == FileTier::Cold);
+    assert_eq!(vec![FileTier::Hot, FileTier::Cold], tiers);
+
+    let expect = vec![(1000, Some(100)), (now, Some(200)), (now + 1, Some(201))];
+    assert_eq!(expect, tester.full_scan().await);
+
+    // The hot copy of the moved file is purged.
+    let sst_dir = engine::region_sst_dir("", REGION_NAME);
+    tests::wait_parquet_file_num(&format!("{store_dir}/{sst_dir}"), 1).await;
+    tests::wait_parquet_file_num(&format!("{cold_dir}/{sst_dir}"), 1).await;
+
+    // Reopen the region and the tiers of files are recovered from the manifest.
+    tester.close().await;
+    let store_config = new_tiered_store_config(store_dir, cold_dir).await;
+    let region = RegionImpl::open(
+        REGION_NAME.to_string(),
+        store_config,
+        &OpenOptions::default(),
+    )
+    .await
+    .unwrap()
+    .unwrap();
+    let tester = FileTesterBase::with_region(region);
+
+    let mut tiers = file_tiers_in_level(&tester.region, 0);
+    tiers.sort_by_key(|tier| *tier == FileTier::Cold);
+    assert_eq!(vec![FileTier::Hot, FileTier::Cold], tiers);
+    assert_eq!(expect, tester.full_scan().await);
+}
+
+#[tokio::test]
+async fn test_move_cold_files_without_flush() {
+    common_telemetry::init_default_ut_logging();
+
+    let dir = TempDir::new("move-cold-without-flush").unwrap();
+    let store_dir = dir.path().to_str().unwrap();
+    let cold_dir = TempDir::new("move-cold-without-flush-remote").unwrap();
+    let cold_dir = cold_dir.path().to_str().unwrap();
+
+    let metadata = tests::new_metadata(REGION_NAME, false);
+    let mut store_config = new_tiered_store_config(store_dir, cold_dir).await;
+    store_config.compaction_scheduler = Arc::new(
+        CompactionSchedulerImpl::new(Arc::new(JobPoolImpl {}), SimplePicker::new(4))
+            .with_cold_sst_age(Some(Duration::from_secs(1))),
+    );
+    let region = RegionImpl::create(metadata, store_config).await.unwrap();
+    let tester = FileTesterBase::with_region(region);
+
+    let now = common_time::util::current_time_millis();
+    tester.put(&[(now, Some(100)), (now + 1, Some(101))]).await;
+    tester.region.flush(&FlushContext::default()).await.unwrap();
+
+    // The compaction after the flush doesn't move the file as it is still new.
+    let sst_dir = engine::region_sst_dir("", REGION_NAME);
+    tests::wait_parquet_file_num(&format!("{store_dir}/{sst_dir}"), 1).await;
+    assert_eq!(vec![FileTier::Hot], file_tiers_in_level(&tester.region, 0));
+
+    // The file becomes old enough but no more flush would trigger a compaction.
+    tokio::time::sleep(Duration::from_millis(1500)).await;
+    tester.region.compact_periodically().await.unwrap();
+
+    tests::wait_parquet_file_num(&format!("{cold_dir}/{sst_dir}"), 1).await;
+    tests::wait_parquet_file_num(&format!("{store_dir}/{sst_dir}"), 0).await;
+    assert_eq!(vec![FileTier::Cold], file_tiers_in_level(&tester.region, 0));
+    let expect = vec![(now, Some(100)), (now + 1, Some(101))];
+    assert_eq!(expect, tester.full_scan().await);
+}
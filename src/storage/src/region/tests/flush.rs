@@ -14,18 +14,25 @@
 
 //! Region flush tests.
 
+use std::path::Path;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
 
+use common_query::logical_plan::Expr;
+use datafusion_expr::{col, lit};
 use log_store::raft_engine::log_store::RaftEngineLogStore;
-use store_api::storage::{OpenOptions, WriteResponse};
+use store_api::manifest::Manifest;
+use store_api::storage::{
+    FlushContext, OpenOptions, Region, ScanRequest, TimeOrder, WriteRequest, WriteResponse,
+};
 use tempdir::TempDir;
 
 use crate::engine;
 use crate::flush::{FlushStrategy, FlushStrategyRef};
 use crate::region::tests::{self, FileTesterBase};
 use crate::region::{RegionImpl, SharedDataRef};
-use crate::test_util::config_util;
+use crate::test_util::{self, config_util};
 
 const REGION_NAME: &str = "region-flush-0";
 
@@ -91,18 +98,31 @@ impl FlushTester {
         self.base().full_scan().await
     }
 
+    async fn scan(&self, filters: Vec<Expr>) -> Vec<(i64, Option<i64>)> {
+        let request = ScanRequest {
+            filters,
+            ..Default::default()
+        };
+        self.base().scan(request).await
+    }
+
     async fn wait_flush_done(&self) {
         self.base().region.wait_flush_done().await.unwrap();
     }
+
+    async fn flush(&self) {
+        let ctx = FlushContext::default();
+        self.base().region.flush(&ctx).await.unwrap();
+    }
 }
 
 #[derive(Debug, Default)]
-struct FlushSwitch {
+pub struct FlushSwitch {
     should_flush: AtomicBool,
 }
 
 impl FlushSwitch {
-    fn set_should_flush(&self, should_flush: bool) {
+    pub fn set_should_flush(&self, should_flush: bool) {
         self.should_flush.store(should_flush, Ordering::Relaxed);
     }
 }
@@ -269,3 +289,329 @@ async fn test_merge_read_after_flush() {
     let output = tester.full_scan().await;
     assert_eq!(expect, output);
 }
+
+#[tokio::test]
+async fn test_reopen_from_checkpoint() {
+    common_telemetry::init_default_ut_logging();
+
+    let dir = TempDir::new("reopen-checkpoint").unwrap();
+    let store_dir = dir.path().to_str().unwrap();
+
+    let flush_switch = Arc::new(FlushSwitch::default());
+    let mut tester = FlushTester::new(store_dir, flush_switch.clone()).await;
+
+    tester.put(&[(1000, Some(100))]).await;
+    // Put element to trigger flush.
+    flush_switch.set_should_flush(true);
+    tester.put(&[(2000, Some(200))]).await;
+    tester.wait_flush_done().await;
+    flush_switch.set_should_flush(false);
+
+    let manifest = &tester.base().region.inner.manifest;
+    let checkpoint = manifest.checkpoint().await.unwrap().unwrap();
+    assert_eq!(1, checkpoint.files.len());
+
+    // Data in memtable and actions after the checkpoint.
+    tester.put(&[(3000, Some(300))]).await;
+    flush_switch.set_should_flush(true);
+    tester.put(&[(4000, Some(400))]).await;
+    tester.wait_flush_done().await;
+    flush_switch.set_should_flush(false);
+
+    let expect = vec![
+        (1000, Some(100)),
+        (2000, Some(200)),
+        (3000, Some(300)),
+        (4000, Some(400)),
+    ];
+    assert_eq!(expect, tester.full_scan().await);
+
+    tester.reopen().await;
+    assert_eq!(expect, tester.full_scan().await);
+
+    // Put after reopen, the manifest version shouldn't conflict with the checkpoint.
+    flush_switch.set_should_flush(true);
+    tester.put(&[(5000, Some(500))]).await;
+    tester.wait_flush_done().await;
+
+    tester.reopen().await;
+    let mut expect = expect;
+    expect.push((5000, Some(500)));
+    assert_eq!(expect, tester.full_scan().await);
+}
+
+#[tokio::test]
+async fn test_drop_region() {
+    common_telemetry::init_default_ut_logging();
+
+    let dir = TempDir::new("drop-region").unwrap();
+    let store_dir = dir.path().to_str().unwrap();
+
+    let flush_switch = Arc::new(FlushSwitch::default());
+    let mut tester = FlushTester::new(store_dir, flush_switch.clone()).await;
+
+    tester.put(&[(1000, Some(100))]).await;
+    // Put element to trigger flush.
+    flush_switch.set_should_flush(true);
+    tester.put(&[(2000, Some(200))]).await;
+    tester.wait_flush_done().await;
+
+    let sst_dir = format!("{}/{}", store_dir, engine::region_sst_dir("", REGION_NAME));
+    assert!(has_parquet_file(&sst_dir));
+
+    tester.base().region.drop_region().await.unwrap();
+    // The dropped region rejects writes and flushes.
+    let base = tester.base();
+    let mut batch = tests::new_write_batch_for_test(false);
+    batch
+        .put(tests::new_put_data(&[(3000.into(), Some(300))]))
+        .unwrap();
+    assert!(base.region.write(&base.write_ctx, batch).await.is_err());
+    let ctx = FlushContext::default();
+    assert!(base.region.flush(&ctx).await.is_err());
+
+    let manifest_dir = format!(
+        "{}/{}",
+        store_dir,
+        engine::region_manifest_dir("", REGION_NAME)
+    );
+    assert!(!Path::new(&manifest_dir).exists());
+
+    // SSTs and the region directory are purged after the last handle to the region
+    // is dropped.
+    tester.base().close().await;
+    tester.base = None;
+    for _ in 0..100 {
+        if !Path::new(&sst_dir).exists() {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    assert!(!Path::new(&sst_dir).exists());
+}
+
+#[tokio::test]
+async fn test_manual_flush() {
+    common_telemetry::init_default_ut_logging();
+
+    let dir = TempDir::new("manual-flush").unwrap();
+    let store_dir = dir.path().to_str().unwrap();
+
+    let flush_switch = Arc::new(FlushSwitch::default());
+    let mut tester = FlushTester::new(store_dir, flush_switch.clone()).await;
+
+    // Flush an empty region does nothing.
+    tester.flush().await;
+    let sst_dir = format!("{}/{}", store_dir, engine::region_sst_dir("", REGION_NAME));
+    assert!(!has_parquet_file(&sst_dir));
+
+    tester.put(&[(1000, Some(100))]).await;
+    tester.flush().await;
+    assert!(has_parquet_file(&sst_dir));
+
+    let expect = vec![(1000, Some(100))];
+    assert_eq!(expect, tester.full_scan().await);
+
+    tester.reopen().await;
+    assert_eq!(expect, tester.full_scan().await);
+}
+
+#[tokio::test]
+async fn test_flush_if_idle() {
+    common_telemetry::init_default_ut_logging();
+
+    let dir = TempDir::new("flush-if-idle").unwrap();
+    let store_dir = dir.path().to_str().unwrap();
+
+    let flush_switch = Arc::new(FlushSwitch::default());
+    let tester = FlushTester::new(store_dir, flush_switch.clone()).await;
+    let region = &tester.base().region;
+    let sst_dir = format!("{}/{}", store_dir, engine::region_sst_dir("", REGION_NAME));
+
+    // Nothing to flush.
+    region.flush_if_idle(Duration::ZERO).await.unwrap();
+    tester.wait_flush_done().await;
+    assert!(!has_parquet_file(&sst_dir));
+
+    // The region has been idle for a while, but the data is just written.
+    tokio::time::sleep(Duration::from_secs(1)).await;
+    tester.put(&[(1000, Some(100))]).await;
+    region.flush_if_idle(Duration::from_secs(1)).await.unwrap();
+    tester.wait_flush_done().await;
+    assert!(!has_parquet_file(&sst_dir));
+
+    region.flush_if_idle(Duration::ZERO).await.unwrap();
+    tester.wait_flush_done().await;
+    assert!(has_parquet_file(&sst_dir));
+
+    assert_eq!(vec![(1000, Some(100))], tester.full_scan().await);
+}
+
+#[tokio::test]
+async fn test_scan_prune_by_time_range() {
+    common_telemetry::init_default_ut_logging();
+
+    let dir = TempDir::new("scan-prune").unwrap();
+    let store_dir = dir.path().to_str().unwrap();
+
+    let flush_switch = Arc::new(FlushSwitch::default());
+    let tester = FlushTester::new(store_dir, flush_switch.clone()).await;
+
+    // Rows in the SST.
+    tester.put(&[(1000, Some(100)), (2000, Some(200))]).await;
+    tester.flush().await;
+    // Rows in the memtable.
+    tester.put(&[(5000, Some(500)), (6000, Some(600))]).await;
+
+    // The scan doesn't filter rows, so all rows of the file or memtable are returned
+    // if it isn't pruned.
+    let filters = vec![Expr::from(
+        col(test_util::TIMESTAMP_NAME).gt_eq(lit(4000i64)),
+    )];
+    assert_eq!(
+        vec![(5000, Some(500)), (6000, Some(600))],
+        tester.scan(filters).await
+    );
+
+    let filters = vec![Expr::from(col(test_util::TIMESTAMP_NAME).lt(lit(3000i64)))];
+    assert_eq!(
+        vec![(1000, Some(100)), (2000, Some(200))],
+        tester.scan(filters).await
+    );
+
+    let filters = vec![Expr::from(col(test_util::TIMESTAMP_NAME).gt(lit(10000i64)))];
+    assert!(tester.scan(filters).await.is_empty());
+
+    assert_eq!(4, tester.full_scan().await.len());
+}
+
+#[tokio::test]
+async fn test_scan_with_order_and_limit() {
+    let dir = TempDir::new("scan-order").unwrap();
+    let store_dir = dir.path().to_str().unwrap();
+
+    let flush_switch = Arc::new(FlushSwitch::default());
+    let tester = FlushTester::new(store_dir, flush_switch.clone()).await;
+
+    tester.put(&[(1000, Some(100)), (2000, Some(200))]).await;
+    tester.flush().await;
+    tester.put(&[(5000, Some(500)), (4000, Some(400))]).await;
+    tester.flush().await;
+    // The memtable overlaps with the first SST.
+    tester.put(&[(2000, Some(201))]).await;
+
+    let scan = |order, limit| {
+        let request = ScanRequest {
+            order: Some(order),
+            limit,
+            ..Default::default()
+        };
+        tester.base().scan(request)
+    };
+    assert_eq!(
+        vec![
+            (1000, Some(100)),
+            (2000, Some(201)),
+            (4000, Some(400)),
+            (5000, Some(500))
+        ],
+        scan(TimeOrder::Asc, None).await
+    );
+    assert_eq!(
+        vec![(1000, Some(100)), (2000, Some(201))],
+        scan(TimeOrder::Asc, Some(2)).await
+    );
+    assert_eq!(
+        vec![(5000, Some(500)), (4000, Some(400)), (2000, Some(201))],
+        scan(TimeOrder::Desc, Some(3)).await
+    );
+
+    // Limit without order.
+    let request = ScanRequest {
+        limit: Some(1),
+        ..Default::default()
+    };
+    assert_eq!(vec![(1000, Some(100))], tester.base().scan(request).await);
+}
+
+#[tokio::test]
+async fn test_scan_partitions() {
+    let dir = TempDir::new("scan-partitions").unwrap();
+    let store_dir = dir.path().to_str().unwrap();
+
+    let flush_switch = Arc::new(FlushSwitch::default());
+    let tester = FlushTester::new(store_dir, flush_switch.clone()).await;
+
+    tester.put(&[(1000, Some(100)), (2000, Some(200))]).await;
+    tester.flush().await;
+    tester.put(&[(4000, Some(400)), (5000, Some(500))]).await;
+    tester.flush().await;
+    // The memtable overlaps with the first SST, the window is still split at the
+    // start of the memtable and the duplicate key is in the same partition.
+    tester.put(&[(2000, Some(201))]).await;
+
+    let partitions = tester
+        .base()
+        .scan_partitions(ScanRequest::default(), 4)
+        .await;
+    assert_eq!(
+        vec![
+            vec![(1000, Some(100))],
+            vec![(2000, Some(201))],
+            vec![(4000, Some(400)), (5000, Some(500))],
+        ],
+        partitions
+    );
+
+    let partitions = tester
+        .base()
+        .scan_partitions(ScanRequest::default(), 1)
+        .await;
+    assert_eq!(1, partitions.len());
+    assert_eq!(tester.full_scan().await, partitions[0]);
+}
+
+#[tokio::test]
+async fn test_scan_partitions_memtable_overlaps_ssts() {
+    let dir = TempDir::new("scan-partitions-overlap").unwrap();
+    let store_dir = dir.path().to_str().unwrap();
+
+    let flush_switch = Arc::new(FlushSwitch::default());
+    let tester = FlushTester::new(store_dir, flush_switch.clone()).await;
+
+    tester.put(&[(1000, Some(100)), (2000, Some(200))]).await;
+    tester.flush().await;
+    tester.put(&[(3000, Some(300)), (4000, Some(400))]).await;
+    tester.flush().await;
+    // The memtable overlaps with both SSTs.
+    tester
+        .put(&[(2000, Some(201)), (2500, Some(250)), (3500, Some(350))])
+        .await;
+
+    let partitions = tester
+        .base()
+        .scan_partitions(ScanRequest::default(), 4)
+        .await;
+    assert_eq!(
+        vec![
+            vec![(1000, Some(100)), (2000, Some(201))],
+            vec![(2500, Some(250))],
+            vec![(3000, Some(300)), (3500, Some(350))],
+            vec![(4000, Some(400))],
+        ],
+        partitions
+    );
+    assert_eq!(tester.full_scan().await, partitions.concat());
+
+    let partitions = tester
+        .base()
+        .scan_partitions(ScanRequest::default(), 2)
+        .await;
+    assert_eq!(
+        vec![
+            vec![(1000, Some(100)), (2000, Some(201)), (2500, Some(250))],
+            vec![(3000, Some(300)), (3500, Some(350)), (4000, Some(400))],
+        ],
+        partitions
+    );
+}
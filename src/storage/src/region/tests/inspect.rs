@@ -0,0 +1,69 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Region inspection tests.
+
+use object_store::backend::fs::Builder;
+use object_store::ObjectStore;
+use store_api::storage::{FlushContext, Region};
+use tempdir::TempDir;
+
+use crate::inspect::{self, RegionInspector};
+use crate::region::tests::{self, FileTesterBase};
+use crate::region::RegionImpl;
+use crate::test_util::config_util;
+
+const REGION_NAME: &str = "region-inspect-0";
+
+#[tokio::test]
+async fn test_inspect_region() {
+    common_telemetry::init_default_ut_logging();
+
+    let dir = TempDir::new("inspect-region").unwrap();
+    let store_dir = dir.path().to_str().unwrap();
+
+    let metadata = tests::new_metadata(REGION_NAME, false);
+    let region_id = metadata.id();
+    let store_config = config_util::new_store_config(REGION_NAME, store_dir).await;
+    let log_store = store_config.log_store.clone();
+    let region = RegionImpl::create(metadata, store_config).await.unwrap();
+    let tester = FileTesterBase::with_region(region);
+
+    tester.put(&[(1000, Some(100)), (2000, Some(200))]).await;
+    tester.region.flush(&FlushContext::default()).await.unwrap();
+    tester.put(&[(3000, Some(300))]).await;
+
+    let object_store = ObjectStore::new(Builder::default().root(store_dir).build().unwrap());
+    let inspector = RegionInspector::new("", REGION_NAME, object_store);
+
+    // Actions to create the region and to apply the flushed file.
+    let actions = inspector.manifest_actions().await.unwrap();
+    assert_eq!(2, actions.len());
+    let metadata = inspector.metadata().await.unwrap().unwrap();
+    assert_eq!(REGION_NAME, metadata.name());
+
+    let version = tester.region.inner.version_control().current();
+    let file_name = version.ssts().level(0).files()[0].file_name().to_string();
+    let sst_meta = inspector.sst_meta(&file_name).await.unwrap();
+    assert_eq!(2, sst_meta.num_rows);
+    let batches = inspector.read_sst(&file_name, &metadata).await.unwrap();
+    let num_rows: usize = batches.iter().map(|batch| batch.num_rows()).sum();
+    assert_eq!(2, num_rows);
+
+    // Flushed entries are obsolete, only the last put remains in the WAL.
+    let entries = inspect::read_wal(log_store, region_id, 0).await.unwrap();
+    let (_, _, payload) = entries.last().unwrap();
+    let payload = payload.as_ref().unwrap();
+    assert_eq!(1, payload.mutations[0].record_batch.num_rows());
+}
@@ -13,16 +13,18 @@
 // limitations under the License.
 
 use std::sync::Arc;
+use std::time::Duration;
 
 use common_telemetry::logging;
 use futures::TryStreamExt;
-use snafu::ResultExt;
+use snafu::{ensure, ResultExt};
 use store_api::logstore::LogStore;
 use store_api::manifest::{Manifest, ManifestVersion, MetaAction};
 use store_api::storage::{AlterRequest, SequenceNumber, WriteContext, WriteResponse};
 use tokio::sync::Mutex;
 
 use crate::background::JobHandle;
+use crate::compaction::CompactionSchedulerRef;
 use crate::error::{self, Result};
 use crate::flush::{FlushJob, FlushSchedulerRef, FlushStrategyRef};
 use crate::manifest::action::{
@@ -71,6 +73,7 @@ impl RegionWriter {
         writer_ctx: WriterContext<'_, S>,
     ) -> Result<WriteResponse> {
         let mut inner = self.inner.lock().await;
+        ensure_not_dropped(writer_ctx.shared)?;
         inner
             .write(&self.version_mutex, ctx, request, writer_ctx)
             .await
@@ -95,7 +98,7 @@ impl RegionWriter {
         shared: &SharedDataRef,
         manifest: &RegionManifest,
         edit: RegionEdit,
-        max_memtable_id: MemtableId,
+        max_memtable_id: Option<MemtableId>,
     ) -> Result<()> {
         let _lock = self.version_mutex.lock().await;
         // HACK: We won't acquire the write lock here because write stall would hold
@@ -112,6 +115,7 @@ impl RegionWriter {
         );
 
         let files_to_add = edit.files_to_add.clone();
+        let files_to_remove = edit.files_to_remove.clone();
         let flushed_sequence = edit.flushed_sequence;
 
         // Persist the meta action.
@@ -121,9 +125,10 @@ impl RegionWriter {
 
         let version_edit = VersionEdit {
             files_to_add,
-            flushed_sequence: Some(flushed_sequence),
+            files_to_remove,
+            flushed_sequence,
             manifest_version,
-            max_memtable_id: Some(max_memtable_id),
+            max_memtable_id,
         };
 
         // We could tolerate failure during persisting manifest version to the WAL, since it won't
@@ -149,6 +154,7 @@ impl RegionWriter {
         // Another potential benefit is that the write lock also protect against concurrent
         // alter request to the region.
         let inner = self.inner.lock().await;
+        ensure_not_dropped(alter_ctx.shared)?;
 
         let version_control = alter_ctx.version_control();
 
@@ -201,6 +207,71 @@ impl RegionWriter {
             .await
     }
 
+    /// Flush the mutable memtable of the region if it is not empty.
+    ///
+    /// Waits until the flush job is finished if `wait` is true.
+    pub async fn flush<S: LogStore>(
+        &self,
+        writer_ctx: WriterContext<'_, S>,
+        wait: bool,
+    ) -> Result<()> {
+        let mut inner = self.inner.lock().await;
+        ensure_not_dropped(writer_ctx.shared)?;
+        inner.flush(&writer_ctx).await?;
+
+        if wait {
+            if let Some(handle) = inner.flush_handle.take() {
+                handle.join().await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Flush the region in background if the oldest unflushed write in its memtables is
+    /// older than `interval`.
+    ///
+    /// Skips the region if the writer is busy, so the auto flush never blocks writes.
+    pub async fn flush_if_idle<S: LogStore>(
+        &self,
+        writer_ctx: WriterContext<'_, S>,
+        interval: Duration,
+    ) -> Result<()> {
+        let Ok(mut inner) = self.inner.try_lock() else {
+            return Ok(());
+        };
+        if writer_ctx.shared.is_dropped() {
+            return Ok(());
+        }
+
+        let Some(first_write_millis) = inner.mutable_first_write_millis else {
+            // Nothing written since the last flush.
+            return Ok(());
+        };
+        let elapsed = common_time::util::current_time_millis() - first_write_millis;
+        if elapsed < interval.as_millis() as i64 {
+            return Ok(());
+        }
+
+        logging::debug!(
+            "Auto flush region {}, oldest unflushed write is {}ms ago",
+            writer_ctx.shared.name(),
+            elapsed
+        );
+
+        inner.flush(&writer_ctx).await
+    }
+
+    /// Wait until the running flush job is finished.
+    pub async fn wait_flush_done(&self) -> Result<()> {
+        let mut inner = self.inner.lock().await;
+        if let Some(handle) = inner.flush_handle.take() {
+            handle.join().await?;
+        }
+
+        Ok(())
+    }
+
     /// Allocate a sequence and persist the manifest version using that sequence to the wal.
     ///
     /// This method should be protected by the `version_mutex`.
@@ -222,23 +293,11 @@ impl RegionWriter {
     }
 }
 
-// Private methods for tests.
-#[cfg(test)]
-impl RegionWriter {
-    pub async fn wait_flush_done(&self) -> Result<()> {
-        let mut inner = self.inner.lock().await;
-        if let Some(handle) = inner.flush_handle.take() {
-            handle.join().await?;
-        }
-
-        Ok(())
-    }
-}
-
 pub struct WriterContext<'a, S: LogStore> {
     pub shared: &'a SharedDataRef,
     pub flush_strategy: &'a FlushStrategyRef,
     pub flush_scheduler: &'a FlushSchedulerRef,
+    pub compaction_scheduler: &'a CompactionSchedulerRef<S>,
     pub sst_layer: &'a AccessLayerRef,
     pub wal: &'a Wal<S>,
     pub writer: &'a RegionWriterRef,
@@ -269,6 +328,9 @@ impl<'a, S: LogStore> AlterContext<'a, S> {
 struct WriterInner {
     memtable_builder: MemtableBuilderRef,
     flush_handle: Option<JobHandle>,
+    /// Timestamp in millis of the first write to the memtables since the last time we
+    /// triggered a flush, `None` if nothing is written since then.
+    mutable_first_write_millis: Option<i64>,
 }
 
 impl WriterInner {
@@ -276,6 +338,7 @@ impl WriterInner {
         WriterInner {
             memtable_builder,
             flush_handle: None,
+            mutable_first_write_millis: None,
         }
     }
 
@@ -316,6 +379,8 @@ impl WriterInner {
         // Insert batch into memtable.
         let mut inserter = Inserter::new(next_sequence);
         inserter.insert_memtable(request.payload(), version.mutable_memtable())?;
+        self.mutable_first_write_millis
+            .get_or_insert_with(common_time::util::current_time_millis);
 
         // Update committed_sequence to make current batch visible. The `&mut self` of WriterInner
         // guarantees the writer is exclusive.
@@ -394,6 +459,9 @@ impl WriterInner {
                     // out of memory during replay, but we need to do it carefully to avoid dead lock.
                     let mut inserter = Inserter::new(last_sequence);
                     inserter.insert_memtable(&payload, version.mutable_memtable())?;
+                    // The original write time is unknown, so replayed data is aged from now.
+                    self.mutable_first_write_millis
+                        .get_or_insert_with(common_time::util::current_time_millis);
                 }
             }
 
@@ -510,7 +578,28 @@ impl WriterInner {
         flush_strategy.should_flush(shared, mutable_bytes_allocated, total_bytes_allocated)
     }
 
+    /// Trigger a flush if the mutable memtable is not empty.
+    async fn flush<S: LogStore>(&mut self, ctx: &WriterContext<'_, S>) -> Result<()> {
+        let mutable_bytes_allocated = ctx
+            .version_control()
+            .current()
+            .memtables()
+            .mutable_bytes_allocated();
+        if mutable_bytes_allocated == 0 {
+            // Nothing to flush.
+            self.mutable_first_write_millis = None;
+            return Ok(());
+        }
+
+        self.trigger_flush(ctx).await
+    }
+
     async fn trigger_flush<S: LogStore>(&mut self, ctx: &WriterContext<'_, S>) -> Result<()> {
+        // The writer lock is held, so no flush job would be scheduled after the region
+        // is dropped and its running flush job is waited.
+        ensure_not_dropped(ctx.shared)?;
+        self.mutable_first_write_millis = None;
+
         let version_control = &ctx.shared.version_control;
         let new_mutable = self.alloc_memtable(version_control);
         // Freeze all mutable memtables so we can flush them later.
@@ -549,6 +638,7 @@ impl WriterInner {
             writer: ctx.writer.clone(),
             wal: ctx.wal.clone(),
             manifest: ctx.manifest.clone(),
+            compaction_scheduler: ctx.compaction_scheduler.clone(),
         };
 
         let flush_handle = ctx
@@ -560,3 +650,12 @@ impl WriterInner {
         Ok(())
     }
 }
+
+/// Returns an error if the region is dropped.
+fn ensure_not_dropped(shared: &SharedDataRef) -> Result<()> {
+    ensure!(
+        !shared.is_dropped(),
+        error::InvalidRegionStateSnafu { state: "dropped" }
+    );
+    Ok(())
+}
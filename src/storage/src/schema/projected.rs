@@ -18,8 +18,11 @@ use std::sync::Arc;
 
 use common_base::BitVec;
 use common_error::prelude::*;
+use common_time::range::TimestampRange;
+use common_time::Timestamp;
 use datatypes::prelude::ScalarVector;
 use datatypes::schema::{SchemaBuilder, SchemaRef};
+use datatypes::value::ValueRef;
 use datatypes::vectors::{BooleanVector, UInt8Vector};
 use store_api::storage::{Chunk, ColumnId, OpType};
 
@@ -356,10 +359,35 @@ impl BatchOp for ProjectedSchema {
             }
         }
     }
+
+    fn unselect_expired(&self, batch: &Batch, expire_time: Timestamp, selected: &mut BitVec) {
+        let Some(ts_index) = self.schema_to_read.schema().timestamp_index() else { return };
+        let timestamps = batch.column(ts_index);
+        for i in 0..timestamps.len() {
+            if let ValueRef::Timestamp(ts) = timestamps.get_ref(i) {
+                if ts < expire_time {
+                    selected.set(i, false);
+                }
+            }
+        }
+    }
+
+    fn unselect_out_of_range(&self, batch: &Batch, range: &TimestampRange, selected: &mut BitVec) {
+        let Some(ts_index) = self.schema_to_read.schema().timestamp_index() else { return };
+        let timestamps = batch.column(ts_index);
+        for i in 0..timestamps.len() {
+            if let ValueRef::Timestamp(ts) = timestamps.get_ref(i) {
+                if !range.contains(&ts) {
+                    selected.set(i, false);
+                }
+            }
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use common_time::timestamp::TimeUnit;
     use datatypes::prelude::ScalarVector;
     use datatypes::type_id::LogicalTypeId;
     use datatypes::vectors::{TimestampMillisecondVector, VectorRef};
@@ -571,4 +599,34 @@ mod tests {
             selected
         );
     }
+
+    #[test]
+    fn test_unselect_expired() {
+        let schema = read_util::new_projected_schema();
+        let batch = read_util::new_kv_batch(&[(100, Some(1)), (101, None), (102, Some(2))]);
+
+        let mut selected = BitVec::repeat(true, batch.num_rows());
+        schema.unselect_expired(&batch, Timestamp::new_millisecond(101), &mut selected);
+        assert_eq!(BitVec::from_iter([false, true, true]), selected);
+
+        // Expire time in a different unit.
+        let mut selected = BitVec::repeat(true, batch.num_rows());
+        schema.unselect_expired(&batch, Timestamp::new_second(1), &mut selected);
+        assert_eq!(BitVec::from_iter([false, false, false]), selected);
+    }
+
+    #[test]
+    fn test_unselect_out_of_range() {
+        let schema = read_util::new_projected_schema();
+        let batch = read_util::new_kv_batch(&[(100, Some(1)), (101, None), (102, Some(2))]);
+
+        let mut selected = BitVec::repeat(true, batch.num_rows());
+        let range = TimestampRange::with_unit(101, 102, TimeUnit::Millisecond).unwrap();
+        schema.unselect_out_of_range(&batch, &range, &mut selected);
+        assert_eq!(BitVec::from_iter([false, true, false]), selected);
+
+        let mut selected = BitVec::repeat(true, batch.num_rows());
+        schema.unselect_out_of_range(&batch, &TimestampRange::min_to_max(), &mut selected);
+        assert_eq!(BitVec::from_iter([true, true, true]), selected);
+    }
 }
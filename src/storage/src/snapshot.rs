@@ -13,15 +13,22 @@
 // limitations under the License.
 
 use std::cmp;
+use std::time::Duration;
 
 use async_trait::async_trait;
+use common_query::logical_plan::Expr;
+use datafusion_expr::{col, lit};
+use datatypes::prelude::ConcreteDataType;
+use datatypes::value::Value;
+use snafu::{OptionExt, ResultExt};
 use store_api::storage::{
-    GetRequest, GetResponse, ReadContext, ScanRequest, ScanResponse, SchemaRef, SequenceNumber,
-    Snapshot,
+    consts, Chunk, ChunkReader, GetRequest, GetResponse, ReadContext, ScanRequest, ScanResponse,
+    SchemaRef, SequenceNumber, Snapshot,
 };
 
 use crate::chunk::{ChunkReaderBuilder, ChunkReaderImpl};
-use crate::error::{Error, Result};
+use crate::error::{self, Error, Result};
+use crate::schema::ProjectedSchema;
 use crate::sst::AccessLayerRef;
 use crate::version::VersionRef;
 
@@ -31,6 +38,8 @@ pub struct SnapshotImpl {
     /// Max sequence number (inclusive) visible to user.
     visible_sequence: SequenceNumber,
     sst_layer: AccessLayerRef,
+    /// TTL of the region data, expired data is invisible to the snapshot.
+    ttl: Option<Duration>,
 }
 
 #[async_trait]
@@ -47,6 +56,93 @@ impl Snapshot for SnapshotImpl {
         ctx: &ReadContext,
         request: ScanRequest,
     ) -> Result<ScanResponse<ChunkReaderImpl>> {
+        let reader = self.reader_builder(ctx, request)?.build().await?;
+
+        Ok(ScanResponse { reader })
+    }
+
+    async fn scan_partitions(
+        &self,
+        ctx: &ReadContext,
+        request: ScanRequest,
+        num_partitions: usize,
+    ) -> Result<Vec<ScanResponse<ChunkReaderImpl>>> {
+        let readers = self
+            .reader_builder(ctx, request)?
+            .build_partitions(num_partitions)
+            .await?;
+
+        Ok(readers
+            .into_iter()
+            .map(|reader| ScanResponse { reader })
+            .collect())
+    }
+
+    async fn get(&self, ctx: &ReadContext, request: GetRequest) -> Result<GetResponse> {
+        let schema = self.version.schema();
+        // Validates the projection before reading.
+        ProjectedSchema::new(schema.clone(), request.projection.clone())
+            .context(error::InvalidProjectionSnafu)?;
+
+        let Some(key) = self.lookup_key(&request)? else {
+            return Ok(GetResponse { row: None });
+        };
+        // This isn't a point lookup. Filters by the key, so memtables and files that
+        // can't contain the timestamp are skipped and row groups are pruned by the key,
+        // but the remaining memtables and row groups are still scanned to find the row.
+        let filters = key.iter().map(|column| column.filter.clone()).collect();
+        let scan_request = ScanRequest {
+            sequence: request.sequence,
+            projection: None,
+            filters,
+            ..Default::default()
+        };
+        let mut reader = self.scan(ctx, scan_request).await?.reader;
+
+        // Rows are deduplicated by the reader, so at most one row matches the key. If the
+        // region has a version column, the row with the largest version is the last one.
+        let mut row = None;
+        while let Some(chunk) = reader.next_chunk().await? {
+            if let Some(values) = find_row(&chunk, &key) {
+                row = Some(values);
+            }
+        }
+
+        let row = row.map(|values| match &request.projection {
+            Some(projection) => projection.iter().map(|idx| values[*idx].clone()).collect(),
+            None => values,
+        });
+        Ok(GetResponse { row })
+    }
+}
+
+impl SnapshotImpl {
+    pub fn new(
+        version: VersionRef,
+        visible_sequence: SequenceNumber,
+        sst_layer: AccessLayerRef,
+        ttl: Option<Duration>,
+    ) -> SnapshotImpl {
+        SnapshotImpl {
+            version,
+            visible_sequence,
+            sst_layer,
+            ttl,
+        }
+    }
+
+    /// Returns the version read by the snapshot.
+    #[inline]
+    pub(crate) fn version(&self) -> &VersionRef {
+        &self.version
+    }
+
+    /// Returns a builder to read rows requested by `request`.
+    fn reader_builder(
+        &self,
+        ctx: &ReadContext,
+        request: ScanRequest,
+    ) -> Result<ChunkReaderBuilder> {
         let visible_sequence = self.sequence_to_read(request.sequence);
         let memtable_version = self.version.memtables();
 
@@ -60,33 +156,61 @@ impl Snapshot for SnapshotImpl {
                 .filters(request.filters)
                 .batch_size(ctx.batch_size)
                 .visible_sequence(visible_sequence)
+                .ttl(self.ttl)
+                .order(request.order)
+                .limit(request.limit)
                 .pick_memtables(mutables.clone());
 
         for memtable in immutables {
             builder = builder.pick_memtables(memtable.clone());
         }
 
-        let reader = builder.pick_ssts(self.version.ssts())?.build().await?;
-
-        Ok(ScanResponse { reader })
+        builder.pick_ssts(self.version.ssts())
     }
 
-    async fn get(&self, _ctx: &ReadContext, _request: GetRequest) -> Result<GetResponse> {
-        unimplemented!()
-    }
-}
+    /// Returns key columns to lookup, returns `None` if no row could have the key.
+    fn lookup_key(&self, request: &GetRequest) -> Result<Option<Vec<KeyColumn>>> {
+        let schema = self.version.schema();
+        let user_schema = schema.user_schema();
+        let timestamp_index = user_schema.timestamp_index();
+        let mut key = Vec::with_capacity(schema.num_row_key_columns());
+        // Row key columns are the first columns of the user schema.
+        for (idx, column) in schema.row_key_columns().enumerate() {
+            let name = column.name();
+            if name == consts::VERSION_COLUMN_NAME {
+                continue;
+            }
 
-impl SnapshotImpl {
-    pub fn new(
-        version: VersionRef,
-        visible_sequence: SequenceNumber,
-        sst_layer: AccessLayerRef,
-    ) -> SnapshotImpl {
-        SnapshotImpl {
-            version,
-            visible_sequence,
-            sst_layer,
+            let data_type = &user_schema.column_schemas()[idx].data_type;
+            let value = if Some(idx) == timestamp_index {
+                let timestamp = match data_type {
+                    ConcreteDataType::Timestamp(t) => request.timestamp.convert_to(t.unit()),
+                    _ => Some(request.timestamp),
+                };
+                match timestamp {
+                    Some(ts) if ts == request.timestamp => Value::Timestamp(ts),
+                    // The timestamp can't be represented in the unit of the column.
+                    _ => return Ok(None),
+                }
+            } else {
+                request
+                    .primary_key
+                    .get(name)
+                    .cloned()
+                    .context(error::MissingKeyValueSnafu { column: name })?
+            };
+            let scalar = value
+                .try_to_scalar_value(data_type)
+                .context(error::InvalidKeyValueSnafu { column: name })?;
+
+            key.push(KeyColumn {
+                index: idx,
+                value,
+                filter: Expr::from(col(name).eq(lit(scalar))),
+            });
         }
+
+        Ok(Some(key))
     }
 
     #[inline]
@@ -96,3 +220,24 @@ impl SnapshotImpl {
             .unwrap_or(self.visible_sequence)
     }
 }
+
+/// A key column to lookup by [Snapshot::get].
+struct KeyColumn {
+    /// Index of the column in the user schema.
+    index: usize,
+    value: Value,
+    /// Filter to prune files and row groups by the value.
+    filter: Expr,
+}
+
+/// Returns values of the last row in the `chunk` that matches the `key`.
+fn find_row(chunk: &Chunk, key: &[KeyColumn]) -> Option<Vec<Value>> {
+    let num_rows = chunk.columns.first().map(|v| v.len()).unwrap_or(0);
+    (0..num_rows)
+        .rev()
+        .find(|row| {
+            key.iter()
+                .all(|column| chunk.columns[column.index].get(*row) == column.value)
+        })
+        .map(|row| chunk.columns.iter().map(|v| v.get(row)).collect())
+}
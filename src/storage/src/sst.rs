@@ -12,24 +12,33 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod bloom_filter;
 mod parquet;
 
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
 
 use async_trait::async_trait;
+use common_telemetry::logging;
+use common_time::range::TimestampRange;
 use common_time::Timestamp;
 use object_store::{util, ObjectStore};
 use serde::{Deserialize, Serialize};
+use snafu::{OptionExt, ResultExt};
 use table::predicate::Predicate;
+use uuid::Uuid;
 
-use crate::error::Result;
+use crate::chunk::ChunkReaderImpl;
+use crate::error::{self, Result};
 use crate::memtable::BoxedBatchIterator;
-use crate::read::BoxedBatchReader;
+use crate::read::{Batch, BoxedBatchReader};
 use crate::schema::ProjectedSchemaRef;
-use crate::sst::parquet::{ParquetReader, ParquetWriter};
+use crate::sst::parquet::ParquetWriter;
+pub use crate::sst::parquet::{read_sst_meta, ParquetReader, SstMeta};
 
 /// Maximum level of SSTs.
-pub const MAX_LEVEL: usize = 1;
+pub const MAX_LEVEL: usize = 2;
 
 // We only has fixed number of level, so we array to hold elements. This implement
 // detail of LevelMetaVec should not be exposed to the user of [LevelMetas].
@@ -49,33 +58,68 @@ pub trait Visitor {
 #[derive(Debug, Clone)]
 pub struct LevelMetas {
     levels: LevelMetaVec,
+    /// Access layer of the SSTs, used to delete files no longer referenced.
+    sst_layer: AccessLayerRef,
 }
 
 impl LevelMetas {
     /// Create a new LevelMetas and initialized each level.
-    pub fn new() -> LevelMetas {
+    pub fn new(sst_layer: AccessLayerRef) -> LevelMetas {
         LevelMetas {
             levels: new_level_meta_vec(),
+            sst_layer,
         }
     }
 
     /// Merge `self` with files to add/remove to create a new [LevelMetas].
     ///
+    /// Removed files are marked as deleted, they will be purged from the object store
+    /// once all [FileHandle]s to them are dropped.
+    ///
     /// # Panics
-    /// Panics if level of [FileHandle] is greater than [MAX_LEVEL].
-    pub fn merge(&self, files_to_add: impl Iterator<Item = FileHandle>) -> LevelMetas {
+    /// Panics if level of [FileMeta] is greater than [MAX_LEVEL].
+    pub fn merge(
+        &self,
+        files_to_add: impl Iterator<Item = FileMeta>,
+        files_to_remove: impl Iterator<Item = FileMeta>,
+    ) -> LevelMetas {
         let mut merged = self.clone();
-        for file in files_to_add {
+        // Removes files first as a file moved to another tier is removed and added again
+        // with the same name.
+        for file in files_to_remove {
+            let level = usize::from(file.level);
+            merged.levels[level].remove_file(&file.file_name);
+        }
+
+        for meta in files_to_add {
+            let file = FileHandle::new(meta, self.sst_layer.clone());
             let level = file.level_index();
 
             merged.levels[level].add_file(file);
         }
 
-        // TODO(yingwen): Support file removal.
-
         merged
     }
 
+    /// Marks all files as deleted.
+    ///
+    /// Files will be purged once all [FileHandle]s to them are dropped.
+    pub fn mark_all_files_deleted(&self) {
+        for level in &self.levels {
+            for file in level.files() {
+                file.mark_deleted();
+            }
+        }
+    }
+
+    /// Returns the [LevelMeta] of given `level`.
+    ///
+    /// # Panics
+    /// Panics if `level >= MAX_LEVEL`.
+    pub fn level(&self, level: usize) -> &LevelMeta {
+        &self.levels[level]
+    }
+
     /// Visit all SST files.
     ///
     /// Stop visiting remaining files if the visitor returns `Err`, and the `Err`
@@ -88,18 +132,12 @@ impl LevelMetas {
         Ok(())
     }
 
-    #[cfg(test)]
+    /// Returns all levels.
     pub fn levels(&self) -> &[LevelMeta] {
         &self.levels
     }
 }
 
-impl Default for LevelMetas {
-    fn default() -> LevelMetas {
-        LevelMetas::new()
-    }
-}
-
 /// Metadata of files in same SST level.
 #[derive(Debug, Default, Clone)]
 pub struct LevelMeta {
@@ -115,14 +153,32 @@ impl LevelMeta {
         self.files.push(file);
     }
 
+    fn remove_file(&mut self, file_name: &str) {
+        self.files.retain(|file| {
+            if file.file_name() == file_name {
+                file.mark_deleted();
+                false
+            } else {
+                true
+            }
+        });
+    }
+
     fn visit_level<V: Visitor>(&self, visitor: &mut V) -> Result<()> {
         visitor.visit(self.level.into(), &self.files)
     }
 
-    #[cfg(test)]
+    /// Returns all files in this level.
+    #[inline]
     pub fn files(&self) -> &[FileHandle] {
         &self.files
     }
+
+    /// Returns the number of files in this level.
+    #[inline]
+    pub fn file_num(&self) -> usize {
+        self.files.len()
+    }
 }
 
 fn new_level_meta_vec() -> LevelMetaVec {
@@ -141,9 +197,9 @@ pub struct FileHandle {
 }
 
 impl FileHandle {
-    pub fn new(meta: FileMeta) -> FileHandle {
+    pub fn new(meta: FileMeta, sst_layer: AccessLayerRef) -> FileHandle {
         FileHandle {
-            inner: Arc::new(FileHandleInner::new(meta)),
+            inner: Arc::new(FileHandleInner::new(meta, sst_layer)),
         }
     }
 
@@ -158,6 +214,12 @@ impl FileHandle {
         &self.inner.meta.file_name
     }
 
+    /// Returns the storage tier of the file.
+    #[inline]
+    pub fn tier(&self) -> FileTier {
+        self.inner.meta.tier
+    }
+
     /// Return the start timestamp of current SST file.
     #[inline]
     pub fn start_timestamp(&self) -> Option<Timestamp> {
@@ -169,6 +231,57 @@ impl FileHandle {
     pub fn end_timestamp(&self) -> Option<Timestamp> {
         self.inner.meta.end_timestamp
     }
+
+    /// Returns the time range of current SST file, the end of the range is inclusive.
+    ///
+    /// Returns `None` if the file doesn't have a time range.
+    pub fn time_range(&self) -> Option<(Timestamp, Timestamp)> {
+        match (self.start_timestamp(), self.end_timestamp()) {
+            (Some(start), Some(end)) => Some((start, end)),
+            _ => None,
+        }
+    }
+
+    /// Returns true if the time range of current SST file intersects with `range`.
+    ///
+    /// Files without time range are always considered to be intersected.
+    pub fn intersects(&self, range: &TimestampRange) -> bool {
+        if *range == TimestampRange::min_to_max() {
+            return true;
+        }
+        // end_timestamp of sst file is inclusive.
+        let file_ts_range =
+            TimestampRange::new_inclusive(self.start_timestamp(), self.end_timestamp());
+        file_ts_range.intersects(range)
+    }
+
+    /// Returns true if all rows in current SST file are older than `expire_time`.
+    ///
+    /// Files without time range are never expired.
+    pub fn expired(&self, expire_time: Timestamp) -> bool {
+        self.end_timestamp()
+            .map(|end| end < expire_time)
+            .unwrap_or(false)
+    }
+
+    /// Returns the meta of the file.
+    #[inline]
+    pub fn meta(&self) -> FileMeta {
+        self.inner.meta.clone()
+    }
+
+    /// Marks the file as deleted, the file will be purged from the object store
+    /// after the last handle to it is dropped.
+    #[inline]
+    pub fn mark_deleted(&self) {
+        self.inner.deleted.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns true if the file is marked as deleted.
+    #[inline]
+    pub fn deleted(&self) -> bool {
+        self.inner.deleted.load(Ordering::Relaxed)
+    }
 }
 
 /// Actually data of [FileHandle].
@@ -177,14 +290,63 @@ impl FileHandle {
 #[derive(Debug)]
 struct FileHandleInner {
     meta: FileMeta,
+    /// Whether the file has been removed from the region.
+    deleted: AtomicBool,
+    sst_layer: AccessLayerRef,
 }
 
 impl FileHandleInner {
-    fn new(meta: FileMeta) -> FileHandleInner {
-        FileHandleInner { meta }
+    fn new(meta: FileMeta, sst_layer: AccessLayerRef) -> FileHandleInner {
+        FileHandleInner {
+            meta,
+            deleted: AtomicBool::new(false),
+            sst_layer,
+        }
     }
 }
 
+impl Drop for FileHandleInner {
+    fn drop(&mut self) {
+        if !self.deleted.load(Ordering::Relaxed) {
+            return;
+        }
+
+        // No version, snapshot or reader holds this file now, so it is safe to
+        // delete it in background.
+        let file_name = self.meta.file_name.clone();
+        let tier = self.meta.tier;
+        let sst_layer = self.sst_layer.clone();
+        common_runtime::spawn_bg(async move {
+            match sst_layer.delete_sst(&file_name, tier).await {
+                Ok(()) => logging::info!("Purged SST file {}", file_name),
+                Err(e) => logging::error!(e; "Failed to purge SST file {}", file_name),
+            }
+        });
+    }
+}
+
+/// Generates random SST file name in format: `^[a-f\d]{8}(-[a-f\d]{4}){3}-[a-f\d]{12}.parquet$`
+pub fn generate_sst_file_name() -> String {
+    format!("{}.parquet", Uuid::new_v4().hyphenated())
+}
+
+/// Returns the timestamp before which rows are expired according to the `ttl`.
+pub fn expire_time(ttl: Duration) -> Timestamp {
+    let now = common_time::util::current_time_millis();
+    let ttl = i64::try_from(ttl.as_millis()).unwrap_or(i64::MAX);
+    Timestamp::new_millisecond(now.saturating_sub(ttl))
+}
+
+/// Storage tier of a sst file.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FileTier {
+    /// Files in the object store of the engine, usually the local file system.
+    #[default]
+    Hot,
+    /// Files moved to the cold object store, usually a remote storage.
+    Cold,
+}
+
 /// Immutable metadata of a sst file.
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct FileMeta {
@@ -193,6 +355,9 @@ pub struct FileMeta {
     pub end_timestamp: Option<Timestamp>,
     /// SST level of the file.
     pub level: u8,
+    /// Storage tier of the file, files written before tiering is supported are hot.
+    #[serde(default)]
+    pub tier: FileTier,
 }
 
 #[derive(Debug, Default)]
@@ -200,6 +365,7 @@ pub struct WriteOptions {
     // TODO(yingwen): [flush] row group size.
 }
 
+#[derive(Clone)]
 pub struct ReadOptions {
     /// Suggested size of each batch.
     pub batch_size: usize,
@@ -210,6 +376,30 @@ pub struct ReadOptions {
     pub predicate: Predicate,
 }
 
+/// Source of the rows to write to a SST.
+pub enum Source {
+    /// Rows from a memtable.
+    Iter(BoxedBatchIterator),
+    /// Rows from a reader, e.g. merging existing SSTs during compaction.
+    Reader(ChunkReaderImpl),
+}
+
+impl Source {
+    async fn next_batch(&mut self) -> Result<Option<Batch>> {
+        match self {
+            Source::Iter(iter) => iter.next().transpose(),
+            Source::Reader(reader) => reader.next_batch().await,
+        }
+    }
+
+    fn projected_schema(&self) -> ProjectedSchemaRef {
+        match self {
+            Source::Iter(iter) => iter.schema(),
+            Source::Reader(reader) => reader.projected_schema().clone(),
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct SstInfo {
     pub start_timestamp: Option<Timestamp>,
@@ -223,12 +413,46 @@ pub trait AccessLayer: Send + Sync + std::fmt::Debug {
     async fn write_sst(
         &self,
         file_name: &str,
-        iter: BoxedBatchIterator,
+        source: Source,
         opts: &WriteOptions,
     ) -> Result<SstInfo>;
 
-    /// Read SST file with given `file_name` and schema.
-    async fn read_sst(&self, file_name: &str, opts: &ReadOptions) -> Result<BoxedBatchReader>;
+    /// Read SST file with given `file_name` in `tier` and schema.
+    async fn read_sst(
+        &self,
+        file_name: &str,
+        tier: FileTier,
+        opts: &ReadOptions,
+    ) -> Result<BoxedBatchReader>;
+
+    /// Deletes SST file with given `file_name` in `tier`.
+    async fn delete_sst(&self, file_name: &str, tier: FileTier) -> Result<()>;
+
+    /// Returns true if the layer is able to store files in the cold tier.
+    fn has_cold_tier(&self) -> bool;
+
+    /// Copies the hot SST file with given `file_name` to the cold tier.
+    ///
+    /// The hot file is kept and should be deleted by the caller once the file
+    /// in the cold tier is visible.
+    async fn copy_sst_to_cold(&self, file_name: &str) -> Result<()>;
+
+    /// Copies the SST file with given `file_name` in `tier` to `dir` of `object_store`,
+    /// the copied file has the same name.
+    async fn export_sst(
+        &self,
+        file_name: &str,
+        tier: FileTier,
+        object_store: &ObjectStore,
+        dir: &str,
+    ) -> Result<()>;
+
+    /// Marks the region of this layer as dropped, so the directory of the region is
+    /// removed once the layer is dropped.
+    ///
+    /// Every SST file holds the layer until it is purged, so the layer is only dropped
+    /// after all SSTs of the region are purged.
+    fn mark_region_dropped(&self);
 }
 
 pub type AccessLayerRef = Arc<dyn AccessLayer>;
@@ -238,6 +462,10 @@ pub type AccessLayerRef = Arc<dyn AccessLayer>;
 pub struct FsAccessLayer {
     sst_dir: String,
     object_store: ObjectStore,
+    /// Object store of cold files, under the same `sst_dir`.
+    cold_store: Option<ObjectStore>,
+    /// Whether the region of the layer is dropped.
+    region_dropped: AtomicBool,
 }
 
 impl FsAccessLayer {
@@ -245,13 +473,32 @@ impl FsAccessLayer {
         FsAccessLayer {
             sst_dir: util::normalize_dir(sst_dir),
             object_store,
+            cold_store: None,
+            region_dropped: AtomicBool::new(false),
         }
     }
 
+    /// Stores files in the cold tier to `cold_store`.
+    pub fn with_cold_store(mut self, cold_store: Option<ObjectStore>) -> FsAccessLayer {
+        self.cold_store = cold_store;
+        self
+    }
+
     #[inline]
     fn sst_file_path(&self, file_name: &str) -> String {
         format!("{}{}", self.sst_dir, file_name)
     }
+
+    /// Returns the object store of files in `tier`.
+    fn object_store(&self, file_name: &str, tier: FileTier) -> Result<&ObjectStore> {
+        match tier {
+            FileTier::Hot => Ok(&self.object_store),
+            FileTier::Cold => self
+                .cold_store
+                .as_ref()
+                .context(error::ColdStoreNotFoundSnafu { file_name }),
+        }
+    }
 }
 
 #[async_trait]
@@ -259,21 +506,26 @@ impl AccessLayer for FsAccessLayer {
     async fn write_sst(
         &self,
         file_name: &str,
-        iter: BoxedBatchIterator,
+        source: Source,
         opts: &WriteOptions,
     ) -> Result<SstInfo> {
         // Now we only supports parquet format. We may allow caller to specific SST format in
         // WriteOptions in the future.
         let file_path = self.sst_file_path(file_name);
-        let writer = ParquetWriter::new(&file_path, iter, self.object_store.clone());
+        let writer = ParquetWriter::new(&file_path, source, self.object_store.clone());
         writer.write_sst(opts).await
     }
 
-    async fn read_sst(&self, file_name: &str, opts: &ReadOptions) -> Result<BoxedBatchReader> {
+    async fn read_sst(
+        &self,
+        file_name: &str,
+        tier: FileTier,
+        opts: &ReadOptions,
+    ) -> Result<BoxedBatchReader> {
         let file_path = self.sst_file_path(file_name);
         let reader = ParquetReader::new(
             &file_path,
-            self.object_store.clone(),
+            self.object_store(file_name, tier)?.clone(),
             opts.projected_schema.clone(),
             opts.predicate.clone(),
         );
@@ -281,4 +533,270 @@ impl AccessLayer for FsAccessLayer {
         let stream = reader.chunk_stream().await?;
         Ok(Box::new(stream))
     }
+
+    async fn delete_sst(&self, file_name: &str, tier: FileTier) -> Result<()> {
+        let file_path = self.sst_file_path(file_name);
+        let object = self.object_store(file_name, tier)?.object(&file_path);
+        object
+            .delete()
+            .await
+            .context(error::DeleteObjectSnafu { path: file_path })
+    }
+
+    fn has_cold_tier(&self) -> bool {
+        self.cold_store.is_some()
+    }
+
+    async fn copy_sst_to_cold(&self, file_name: &str) -> Result<()> {
+        let file_path = self.sst_file_path(file_name);
+        let cold_store = self.object_store(file_name, FileTier::Cold)?;
+        copy_object(&self.object_store, &file_path, cold_store, &file_path).await
+    }
+
+    async fn export_sst(
+        &self,
+        file_name: &str,
+        tier: FileTier,
+        object_store: &ObjectStore,
+        dir: &str,
+    ) -> Result<()> {
+        let file_path = self.sst_file_path(file_name);
+        let target_path = format!("{}{}", util::normalize_dir(dir), file_name);
+        copy_object(
+            self.object_store(file_name, tier)?,
+            &file_path,
+            object_store,
+            &target_path,
+        )
+        .await
+    }
+
+    fn mark_region_dropped(&self) {
+        self.region_dropped.store(true, Ordering::Relaxed);
+    }
+}
+
+impl Drop for FsAccessLayer {
+    fn drop(&mut self) {
+        if !self.region_dropped.load(Ordering::Relaxed) {
+            return;
+        }
+
+        let sst_dir = self.sst_dir.clone();
+        let object_stores: Vec<_> = std::iter::once(self.object_store.clone())
+            .chain(self.cold_store.clone())
+            .collect();
+        common_runtime::spawn_bg(async move {
+            for object_store in object_stores {
+                match remove_dir(&object_store, &sst_dir).await {
+                    Ok(()) => logging::info!("Removed directory {} of dropped region", sst_dir),
+                    Err(e) => {
+                        logging::error!(e; "Failed to remove directory {} of dropped region", sst_dir)
+                    }
+                }
+            }
+        });
+    }
+}
+
+/// Removes the directory `dir` and all objects under it from `object_store`.
+async fn remove_dir(object_store: &ObjectStore, dir: &str) -> Result<()> {
+    let object = object_store.object(dir);
+    let exists = object
+        .is_exist()
+        .await
+        .context(error::ReadObjectSnafu { path: dir })?;
+    if !exists {
+        return Ok(());
+    }
+
+    object_store
+        .batch()
+        .remove_all(dir)
+        .await
+        .context(error::DeleteObjectSnafu { path: dir })?;
+    object
+        .delete()
+        .await
+        .context(error::DeleteObjectSnafu { path: dir })
+}
+
+/// Copies the object at `source_path` of `source` to `target_path` of `target`.
+///
+/// The object is streamed to the target so the whole object is never held in memory.
+pub(crate) async fn copy_object(
+    source: &ObjectStore,
+    source_path: &str,
+    target: &ObjectStore,
+    target_path: &str,
+) -> Result<()> {
+    let object = source.object(source_path);
+    let size = object
+        .metadata()
+        .await
+        .context(error::ReadObjectSnafu { path: source_path })?
+        .content_length();
+    let reader = object
+        .reader()
+        .await
+        .context(error::ReadObjectSnafu { path: source_path })?;
+    target
+        .object(target_path)
+        .write_from(size, reader)
+        .await
+        .context(error::WriteObjectSnafu { path: target_path })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use object_store::backend::fs::Builder;
+    use regex::Regex;
+    use tempdir::TempDir;
+
+    use super::*;
+    use crate::test_util::access_layer_util::MockAccessLayer;
+
+    #[test]
+    fn test_uuid_generate() {
+        let file_name = generate_sst_file_name();
+        let regex = Regex::new(r"^[a-f\d]{8}(-[a-f\d]{4}){3}-[a-f\d]{12}.parquet$").unwrap();
+        assert!(
+            regex.is_match(&file_name),
+            "Illegal sst file name: {file_name}",
+        );
+    }
+
+    fn new_file_meta(name: &str, level: u8) -> FileMeta {
+        FileMeta {
+            file_name: name.to_string(),
+            start_timestamp: None,
+            end_timestamp: None,
+            level,
+            tier: FileTier::Hot,
+        }
+    }
+
+    fn file_names_in_level(metas: &LevelMetas, level: usize) -> Vec<&str> {
+        metas
+            .level(level)
+            .files()
+            .iter()
+            .map(|f| f.file_name())
+            .collect()
+    }
+
+    #[test]
+    fn test_level_metas_add_and_remove() {
+        let metas = LevelMetas::new(Arc::new(MockAccessLayer::default()));
+        let merged = metas.merge(
+            vec![
+                new_file_meta("a", 0),
+                new_file_meta("b", 0),
+                new_file_meta("c", 1),
+            ]
+            .into_iter(),
+            std::iter::empty(),
+        );
+
+        assert_eq!(vec!["a", "b"], file_names_in_level(&merged, 0));
+        assert_eq!(vec!["c"], file_names_in_level(&merged, 1));
+
+        let merged = merged.merge(
+            vec![new_file_meta("d", 1)].into_iter(),
+            vec![new_file_meta("a", 0), new_file_meta("b", 0)].into_iter(),
+        );
+
+        assert!(file_names_in_level(&merged, 0).is_empty());
+        assert_eq!(vec!["c", "d"], file_names_in_level(&merged, 1));
+    }
+
+    async fn wait_files_deleted(sst_layer: &MockAccessLayer, expect: &[&str]) {
+        // Files are deleted in background.
+        for _ in 0..100 {
+            let mut deleted = sst_layer.deleted_files();
+            deleted.sort_unstable();
+            if deleted == expect {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+
+        panic!(
+            "Expect deleted files {:?}, actual {:?}",
+            expect,
+            sst_layer.deleted_files()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_purge_deleted_files() {
+        let sst_layer = Arc::new(MockAccessLayer::default());
+        let metas = LevelMetas::new(sst_layer.clone()).merge(
+            vec![new_file_meta("a", 0), new_file_meta("b", 0)].into_iter(),
+            std::iter::empty(),
+        );
+        // Holds file `a`, like a snapshot reading the old version.
+        let old_metas = metas.clone();
+
+        let metas = metas.merge(
+            std::iter::empty(),
+            vec![new_file_meta("a", 0), new_file_meta("b", 0)].into_iter(),
+        );
+        assert!(metas.level(0).files().is_empty());
+        assert!(old_metas.level(0).files().iter().all(|f| f.deleted()));
+
+        // Files are still referenced by the old metas.
+        assert!(sst_layer.deleted_files().is_empty());
+
+        drop(old_metas);
+        wait_files_deleted(&sst_layer, &["a", "b"]).await;
+    }
+
+    #[tokio::test]
+    async fn test_mark_all_files_deleted() {
+        let sst_layer = Arc::new(MockAccessLayer::default());
+        let metas = LevelMetas::new(sst_layer.clone()).merge(
+            vec![new_file_meta("a", 0), new_file_meta("b", 1)].into_iter(),
+            std::iter::empty(),
+        );
+        // File `c` is only added to the new metas, so it won't be deleted.
+        let merged = metas.merge(vec![new_file_meta("c", 1)].into_iter(), std::iter::empty());
+        metas.mark_all_files_deleted();
+        drop(metas);
+        drop(merged);
+
+        wait_files_deleted(&sst_layer, &["a", "b"]).await;
+    }
+
+    #[tokio::test]
+    async fn test_copy_object() {
+        let dir = TempDir::new("copy-object").unwrap();
+        let new_store = |name: &str| {
+            let root = dir.path().join(name);
+            ObjectStore::new(
+                Builder::default()
+                    .root(root.to_str().unwrap())
+                    .build()
+                    .unwrap(),
+            )
+        };
+        let source = new_store("source");
+        let target = new_store("target");
+
+        let data: Vec<u8> = (0..4096).map(|i| (i % 251) as u8).collect();
+        source
+            .object("a/b.parquet")
+            .write(data.clone())
+            .await
+            .unwrap();
+        copy_object(&source, "a/b.parquet", &target, "c/b.parquet")
+            .await
+            .unwrap();
+
+        assert_eq!(data, target.object("c/b.parquet").read().await.unwrap());
+        // The source object is kept.
+        assert_eq!(data, source.object("a/b.parquet").read().await.unwrap());
+    }
 }
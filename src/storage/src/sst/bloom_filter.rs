@@ -0,0 +1,380 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Bloom filters of primary key columns in SSTs.
+//!
+//! Each row group of a SST has a bloom filter for every primary key column (except the
+//! timestamp), so equality conditions on tags like `host = 'a'` could skip row groups (and
+//! files) that don't contain the value.
+
+use std::collections::{HashMap, HashSet};
+use std::f64::consts::LN_2;
+
+use common_telemetry::logging;
+use datatypes::prelude::ConcreteDataType;
+use datatypes::value::{Value, ValueRef};
+use serde::{Deserialize, Serialize};
+use store_api::storage::consts;
+use table::predicate::{ColumnValueFilter, Predicate};
+
+use crate::read::Batch;
+use crate::schema::StoreSchema;
+
+/// Key of the bloom filters in the key value metadata of a parquet file.
+pub const BLOOM_FILTER_KEY: &str = "greptime:bloom_filters";
+
+/// Expected false positive rate of the bloom filter.
+const FALSE_POSITIVE_RATE: f64 = 0.01;
+/// Max number of hash functions of a bloom filter.
+const MAX_NUM_HASHES: u32 = 16;
+
+const TAG_BOOLEAN: u8 = 0;
+const TAG_INT: u8 = 1;
+const TAG_UINT: u8 = 2;
+const TAG_STRING: u8 = 3;
+const TAG_BINARY: u8 = 4;
+
+/// A bloom filter of values in a column.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BloomFilter {
+    num_hashes: u32,
+    bits: Vec<u64>,
+}
+
+impl BloomFilter {
+    /// Creates a bloom filter that holds `num_items` distinct items.
+    pub fn with_capacity(num_items: usize) -> BloomFilter {
+        let num_items = num_items.max(1) as f64;
+        // m = -n * ln(p) / (ln(2) ^ 2)
+        let num_bits = (-num_items * FALSE_POSITIVE_RATE.ln() / (LN_2 * LN_2)).ceil() as usize;
+        let num_words = (num_bits + 63) / 64;
+        // k = m / n * ln(2)
+        let num_hashes = ((num_words * 64) as f64 / num_items * LN_2).round() as u32;
+
+        BloomFilter {
+            num_hashes: num_hashes.clamp(1, MAX_NUM_HASHES),
+            bits: vec![0; num_words],
+        }
+    }
+
+    /// Returns false if the `value` is definitely not in the filter.
+    ///
+    /// Always returns true for values of unsupported types.
+    pub fn may_contain(&self, value: ValueRef) -> bool {
+        hash_value(value)
+            .map(|hash| self.contains_hash(hash))
+            .unwrap_or(true)
+    }
+
+    fn insert_hash(&mut self, hash: u64) {
+        for bit in self.bit_indices(hash) {
+            self.bits[bit / 64] |= 1 << (bit % 64);
+        }
+    }
+
+    fn contains_hash(&self, hash: u64) -> bool {
+        self.bit_indices(hash)
+            .all(|bit| self.bits[bit / 64] & (1 << (bit % 64)) != 0)
+    }
+
+    /// Returns indices of bits for the `hash` by double hashing.
+    fn bit_indices(&self, hash: u64) -> impl Iterator<Item = usize> {
+        let num_bits = self.bits.len() as u64 * 64;
+        let (h1, h2) = (hash & u64::from(u32::MAX), hash >> 32);
+        (0..u64::from(self.num_hashes))
+            .map(move |i| (h1.wrapping_add(i.wrapping_mul(h2)) % num_bits) as usize)
+    }
+}
+
+/// Bloom filters of primary key columns in a row group.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RowGroupBloomFilters {
+    /// Column name to the bloom filter of that column.
+    columns: HashMap<String, BloomFilter>,
+}
+
+impl ColumnValueFilter for RowGroupBloomFilters {
+    fn may_contain(&self, column: &str, value: &Value) -> Option<bool> {
+        self.columns
+            .get(column)
+            .map(|filter| filter.may_contain(value.as_value_ref()))
+    }
+}
+
+/// Bloom filters of all row groups in a SST.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BloomFilters {
+    row_groups: Vec<RowGroupBloomFilters>,
+}
+
+impl BloomFilters {
+    /// Decodes bloom filters from the value in parquet's key value metadata.
+    pub fn decode(value: &str) -> Option<BloomFilters> {
+        serde_json::from_str(value)
+            .map_err(|e| logging::warn!("Failed to decode bloom filters, error: {}", e))
+            .ok()
+    }
+
+    /// Encodes bloom filters as a string that can be stored in parquet's key value metadata.
+    pub fn encode(&self) -> String {
+        // Serializing the filters should never fail.
+        serde_json::to_string(self).unwrap()
+    }
+
+    #[inline]
+    pub fn num_row_groups(&self) -> usize {
+        self.row_groups.len()
+    }
+
+    /// Marks row groups in `selection` that can't match the `predicate` as false.
+    ///
+    /// Does nothing if the number of row groups in the filters and the `selection`
+    /// are not the same.
+    pub fn prune_row_groups(&self, predicate: &Predicate, selection: &mut [bool]) {
+        if self.row_groups.len() != selection.len() {
+            return;
+        }
+
+        for (selected, filters) in selection.iter_mut().zip(&self.row_groups) {
+            if *selected && !predicate.may_match(filters) {
+                *selected = false;
+            }
+        }
+    }
+}
+
+/// Builds bloom filters for rows written to a SST.
+///
+/// Rows are split into row groups with `row_group_size` rows, the same as the parquet writer.
+pub struct BloomFilterBuilder {
+    /// Index and name of columns to build bloom filters.
+    columns: Vec<(usize, String)>,
+    row_group_size: usize,
+    /// Number of rows in current row group.
+    num_rows: usize,
+    /// Hashes of values of each column in current row group.
+    hashes: Vec<HashSet<u64>>,
+    filters: BloomFilters,
+}
+
+impl BloomFilterBuilder {
+    pub fn new(schema: &StoreSchema, row_group_size: usize) -> BloomFilterBuilder {
+        let timestamp_index = schema.schema().timestamp_index();
+        let columns: Vec<_> = schema
+            .row_key_indices()
+            .filter(|idx| Some(*idx) != timestamp_index)
+            .filter_map(|idx| {
+                let column = &schema.schema().column_schemas()[idx];
+                (column.name != consts::VERSION_COLUMN_NAME && is_supported_type(&column.data_type))
+                    .then(|| (idx, column.name.clone()))
+            })
+            .collect();
+        let hashes = vec![HashSet::new(); columns.len()];
+
+        BloomFilterBuilder {
+            columns,
+            row_group_size,
+            num_rows: 0,
+            hashes,
+            filters: BloomFilters::default(),
+        }
+    }
+
+    /// Adds values of rows in the `batch` to bloom filters.
+    pub fn update(&mut self, batch: &Batch) {
+        if self.columns.is_empty() {
+            return;
+        }
+
+        for row in 0..batch.num_rows() {
+            for ((idx, _), hashes) in self.columns.iter().zip(self.hashes.iter_mut()) {
+                if let Some(hash) = hash_value(batch.column(*idx).get_ref(row)) {
+                    hashes.insert(hash);
+                }
+            }
+
+            self.num_rows += 1;
+            if self.num_rows == self.row_group_size {
+                self.finish_row_group();
+            }
+        }
+    }
+
+    /// Finishes the builder and returns bloom filters of all row groups, returns `None`
+    /// if there is no column to build bloom filters.
+    pub fn finish(mut self) -> Option<BloomFilters> {
+        if self.columns.is_empty() {
+            return None;
+        }
+        if self.num_rows > 0 {
+            self.finish_row_group();
+        }
+
+        Some(self.filters)
+    }
+
+    fn finish_row_group(&mut self) {
+        let columns = self
+            .columns
+            .iter()
+            .zip(self.hashes.iter_mut())
+            .map(|((_, name), hashes)| {
+                let mut filter = BloomFilter::with_capacity(hashes.len());
+                for hash in hashes.drain() {
+                    filter.insert_hash(hash);
+                }
+                (name.clone(), filter)
+            })
+            .collect();
+
+        self.filters
+            .row_groups
+            .push(RowGroupBloomFilters { columns });
+        self.num_rows = 0;
+    }
+}
+
+fn is_supported_type(data_type: &ConcreteDataType) -> bool {
+    matches!(
+        data_type,
+        ConcreteDataType::Boolean(_)
+            | ConcreteDataType::Int8(_)
+            | ConcreteDataType::Int16(_)
+            | ConcreteDataType::Int32(_)
+            | ConcreteDataType::Int64(_)
+            | ConcreteDataType::UInt8(_)
+            | ConcreteDataType::UInt16(_)
+            | ConcreteDataType::UInt32(_)
+            | ConcreteDataType::UInt64(_)
+            | ConcreteDataType::String(_)
+            | ConcreteDataType::Binary(_)
+    )
+}
+
+/// Hashes the value, integers with the same value have the same hash regardless of their
+/// types. Returns `None` for null and values of unsupported types.
+fn hash_value(value: ValueRef) -> Option<u64> {
+    let hash = match value {
+        ValueRef::Boolean(v) => hash_bytes(TAG_BOOLEAN, &[u8::from(v)]),
+        ValueRef::Int8(v) => hash_i64(v.into()),
+        ValueRef::Int16(v) => hash_i64(v.into()),
+        ValueRef::Int32(v) => hash_i64(v.into()),
+        ValueRef::Int64(v) => hash_i64(v),
+        ValueRef::UInt8(v) => hash_i64(v.into()),
+        ValueRef::UInt16(v) => hash_i64(v.into()),
+        ValueRef::UInt32(v) => hash_i64(v.into()),
+        ValueRef::UInt64(v) => match i64::try_from(v) {
+            Ok(v) => hash_i64(v),
+            Err(_) => hash_bytes(TAG_UINT, &v.to_le_bytes()),
+        },
+        ValueRef::String(v) => hash_bytes(TAG_STRING, v.as_bytes()),
+        ValueRef::Binary(v) => hash_bytes(TAG_BINARY, v),
+        _ => return None,
+    };
+
+    Some(hash)
+}
+
+#[inline]
+fn hash_i64(v: i64) -> u64 {
+    hash_bytes(TAG_INT, &v.to_le_bytes())
+}
+
+/// Hashes the `tag` and `bytes` by FNV-1a.
+///
+/// The hash is persisted in SSTs so it must be stable, which means we can't use the hasher
+/// in std.
+fn hash_bytes(tag: u8, bytes: &[u8]) -> u64 {
+    const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+    const FNV_PRIME: u64 = 0x100000001b3;
+
+    let mut hash = FNV_OFFSET_BASIS;
+    for byte in std::iter::once(&tag).chain(bytes) {
+        hash ^= u64::from(*byte);
+        hash = hash.wrapping_mul(FNV_PRIME);
+    }
+
+    // Mixes the bits by the finalizer of splitmix64 since we take the high and low
+    // 32 bits as two hashes.
+    hash = (hash ^ (hash >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    hash = (hash ^ (hash >> 27)).wrapping_mul(0x94d049bb133111eb);
+    hash ^ (hash >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bloom_filter() {
+        let mut filter = BloomFilter::with_capacity(100);
+        for i in 0..100 {
+            filter.insert_hash(hash_value(ValueRef::Int64(i)).unwrap());
+        }
+        for i in 0..100 {
+            assert!(filter.may_contain(ValueRef::Int64(i)));
+            // Integers of different types have the same hash.
+            assert!(filter.may_contain(ValueRef::UInt32(i as u32)));
+        }
+
+        let false_positives = (100..10100)
+            .filter(|i| filter.may_contain(ValueRef::Int64(*i)))
+            .count();
+        // Expected false positive rate is 1%, leave some room for the test.
+        assert!(false_positives < 300, "false positives: {false_positives}");
+
+        // Unsupported values are always considered present.
+        assert!(filter.may_contain(ValueRef::Null));
+
+        let filter = BloomFilter::with_capacity(0);
+        assert!(!filter.may_contain(ValueRef::String("a")));
+    }
+
+    #[test]
+    fn test_hash_value() {
+        assert_eq!(
+            hash_value(ValueRef::Int8(-1)),
+            hash_value(ValueRef::Int64(-1))
+        );
+        assert_eq!(
+            hash_value(ValueRef::UInt8(1)),
+            hash_value(ValueRef::Int32(1))
+        );
+        assert_ne!(
+            hash_value(ValueRef::UInt64(u64::MAX)),
+            hash_value(ValueRef::Int64(-1))
+        );
+        assert_ne!(
+            hash_value(ValueRef::String("1")),
+            hash_value(ValueRef::Binary(b"1"))
+        );
+        assert_eq!(None, hash_value(ValueRef::Null));
+        assert_eq!(None, hash_value(ValueRef::Float64(1.0.into())));
+    }
+
+    #[test]
+    fn test_encode_bloom_filters() {
+        let mut filter = BloomFilter::with_capacity(1);
+        filter.insert_hash(hash_value(ValueRef::String("a")).unwrap());
+        let filters = BloomFilters {
+            row_groups: vec![RowGroupBloomFilters {
+                columns: HashMap::from([("k0".to_string(), filter)]),
+            }],
+        };
+
+        let decoded = BloomFilters::decode(&filters.encode()).unwrap();
+        assert_eq!(filters, decoded);
+        assert!(BloomFilters::decode("invalid").is_none());
+    }
+}
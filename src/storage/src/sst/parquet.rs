@@ -21,7 +21,7 @@ use std::sync::Arc;
 use async_compat::CompatExt;
 use async_stream::try_stream;
 use async_trait::async_trait;
-use common_telemetry::error;
+use common_telemetry::{debug, error, warn};
 use common_time::timestamp::TimeUnit;
 use common_time::Timestamp;
 use datatypes::arrow::record_batch::RecordBatch;
@@ -30,41 +30,42 @@ use futures_util::{Stream, StreamExt, TryStreamExt};
 use object_store::ObjectStore;
 use parquet::arrow::{ArrowWriter, ParquetRecordBatchStreamBuilder, ProjectionMask};
 use parquet::basic::{Compression, Encoding};
+use parquet::errors::ParquetError;
 use parquet::file::metadata::KeyValue;
 use parquet::file::properties::WriterProperties;
 use parquet::format::FileMetaData;
 use snafu::{OptionExt, ResultExt};
 use table::predicate::Predicate;
+use thrift::protocol::{TCompactOutputProtocol, TOutputProtocol, TSerializable};
 use tokio::io::BufReader;
 
 use crate::error::{
     self, DecodeParquetTimeRangeSnafu, NewRecordBatchSnafu, ReadObjectSnafu, ReadParquetSnafu,
     Result, WriteObjectSnafu, WriteParquetSnafu,
 };
-use crate::memtable::BoxedBatchIterator;
 use crate::read::{Batch, BatchReader};
 use crate::schema::compat::ReadAdapter;
 use crate::schema::{ProjectedSchemaRef, StoreSchema, StoreSchemaRef};
 use crate::sst;
-use crate::sst::SstInfo;
+use crate::sst::bloom_filter::{BloomFilterBuilder, BloomFilters, BLOOM_FILTER_KEY};
+use crate::sst::{Source, SstInfo};
+
+/// Magic bytes at the end of a parquet file.
+const PARQUET_MAGIC: &[u8; 4] = b"PAR1";
 
 /// Parquet sst writer.
 pub struct ParquetWriter<'a> {
     file_path: &'a str,
-    iter: BoxedBatchIterator,
+    source: Source,
     object_store: ObjectStore,
     max_row_group_size: usize,
 }
 
 impl<'a> ParquetWriter<'a> {
-    pub fn new(
-        file_path: &'a str,
-        iter: BoxedBatchIterator,
-        object_store: ObjectStore,
-    ) -> ParquetWriter {
+    pub fn new(file_path: &'a str, source: Source, object_store: ObjectStore) -> ParquetWriter {
         ParquetWriter {
             file_path,
-            iter,
+            source,
             object_store,
             max_row_group_size: 4096, // TODO(hl): make this configurable
         }
@@ -77,8 +78,8 @@ impl<'a> ParquetWriter<'a> {
     /// Iterates memtable and writes rows to Parquet file.
     /// A chunk of records yielded from each iteration with a size given
     /// in config will be written to a single row group.
-    async fn write_rows(self, extra_meta: Option<HashMap<String, String>>) -> Result<SstInfo> {
-        let projected_schema = self.iter.schema();
+    async fn write_rows(mut self, extra_meta: Option<HashMap<String, String>>) -> Result<SstInfo> {
+        let projected_schema = self.source.projected_schema();
         let store_schema = projected_schema.schema_to_read();
         let schema = store_schema.arrow_schema().clone();
         let object = self.object_store.object(self.file_path);
@@ -100,8 +101,10 @@ impl<'a> ParquetWriter<'a> {
         let mut buf = vec![];
         let mut arrow_writer = ArrowWriter::try_new(&mut buf, schema.clone(), Some(writer_props))
             .context(WriteParquetSnafu)?;
-        for batch in self.iter {
-            let batch = batch?;
+        let mut bloom_filter_builder =
+            BloomFilterBuilder::new(store_schema, self.max_row_group_size);
+        while let Some(batch) = self.source.next_batch().await? {
+            bloom_filter_builder.update(&batch);
             let arrow_batch = RecordBatch::try_new(
                 schema.clone(),
                 batch
@@ -116,7 +119,7 @@ impl<'a> ParquetWriter<'a> {
                 .context(WriteParquetSnafu)?;
         }
 
-        let file_meta = arrow_writer.close().context(WriteParquetSnafu)?;
+        let mut file_meta = arrow_writer.close().context(WriteParquetSnafu)?;
 
         let (start_timestamp, end_timestamp) =
             match decode_timestamp_range(&file_meta, store_schema) {
@@ -128,6 +131,20 @@ impl<'a> ParquetWriter<'a> {
                 }
             };
 
+        if let Some(bloom_filters) = bloom_filter_builder.finish() {
+            if bloom_filters.num_row_groups() == file_meta.row_groups.len() {
+                let key_value = KeyValue::new(BLOOM_FILTER_KEY.to_string(), bloom_filters.encode());
+                append_key_value_metadata(&mut buf, &mut file_meta, key_value)?;
+            } else {
+                warn!(
+                    "Skip writing bloom filters to {}, row groups in filters: {}, in file: {}",
+                    self.file_path,
+                    bloom_filters.num_row_groups(),
+                    file_meta.row_groups.len()
+                );
+            }
+        }
+
         object.write(buf).await.context(WriteObjectSnafu {
             path: object.path(),
         })?;
@@ -138,6 +155,49 @@ impl<'a> ParquetWriter<'a> {
     }
 }
 
+/// Appends `key_value` to the metadata of the parquet file in `buf`, `file_meta` is the
+/// metadata returned by the writer after writing the file.
+///
+/// The `ArrowWriter` requires all key value metadata before writing any row, but bloom
+/// filters are only available after all rows are written, so we rewrite the footer of the
+/// file, which is `| metadata | metadata length (4 bytes) | PAR1 |`.
+// TODO(yingwen): Use `ArrowWriter::append_key_value_metadata()` once we upgrade parquet.
+fn append_key_value_metadata(
+    buf: &mut Vec<u8>,
+    file_meta: &mut FileMetaData,
+    key_value: KeyValue,
+) -> Result<()> {
+    let footer_len = 4 + PARQUET_MAGIC.len();
+    let file_len = buf.len();
+    // Safety: The writer always writes the footer.
+    let metadata_len = u32::from_le_bytes(
+        buf[file_len - footer_len..file_len - PARQUET_MAGIC.len()]
+            .try_into()
+            .unwrap(),
+    ) as usize;
+    buf.truncate(file_len - footer_len - metadata_len);
+
+    file_meta
+        .key_value_metadata
+        .get_or_insert_with(Vec::new)
+        .push(key_value);
+
+    let metadata_start = buf.len();
+    {
+        let mut protocol = TCompactOutputProtocol::new(&mut *buf);
+        file_meta
+            .write_to_out_protocol(&mut protocol)
+            .and_then(|_| protocol.flush())
+            .map_err(|e| ParquetError::External(Box::new(e)))
+            .context(WriteParquetSnafu)?;
+    }
+    let metadata_len = (buf.len() - metadata_start) as u32;
+    buf.extend_from_slice(&metadata_len.to_le_bytes());
+    buf.extend_from_slice(PARQUET_MAGIC);
+
+    Ok(())
+}
+
 fn decode_timestamp_range(
     file_meta: &FileMetaData,
     store_schema: &StoreSchemaRef,
@@ -258,17 +318,28 @@ impl<'a> ParquetReader<'a> {
 
         let adapter = ReadAdapter::new(store_schema.clone(), self.projected_schema.clone())?;
 
-        let pruned_row_groups = self
-            .predicate
-            .prune_row_groups(
-                store_schema.schema().clone(),
-                builder.metadata().row_groups(),
-            )
+        let mut selection = self.predicate.prune_row_groups(
+            store_schema.schema().clone(),
+            builder.metadata().row_groups(),
+        );
+        if let Some(bloom_filters) = decode_bloom_filters(builder.metadata().file_metadata()) {
+            bloom_filters.prune_row_groups(&self.predicate, &mut selection);
+        }
+        let pruned_row_groups = selection
             .into_iter()
             .enumerate()
             .filter_map(|(idx, valid)| if valid { Some(idx) } else { None })
             .collect::<Vec<_>>();
 
+        if pruned_row_groups.is_empty() && builder.metadata().num_row_groups() > 0 {
+            // No row group matches the predicate, skip the whole file.
+            debug!(
+                "Skip file {}, no row group matches the predicate",
+                self.file_path
+            );
+            return ChunkStream::new(adapter, Box::pin(futures_util::stream::empty()));
+        }
+
         let projection = ProjectionMask::roots(
             builder.metadata().file_metadata().schema_descr(),
             adapter.fields_to_read(),
@@ -293,6 +364,66 @@ impl<'a> ParquetReader<'a> {
     }
 }
 
+/// Metadata of a parquet SST.
+#[derive(Debug)]
+pub struct SstMeta {
+    /// Schema of data stored in the file.
+    pub store_schema: StoreSchemaRef,
+    pub num_rows: i64,
+    /// Number of rows of each row group.
+    pub row_group_rows: Vec<i64>,
+    pub created_by: Option<String>,
+    /// Keys of the key value metadata.
+    pub metadata_keys: Vec<String>,
+    pub has_bloom_filters: bool,
+}
+
+/// Reads the metadata of the SST in `file_path` without reading rows.
+pub async fn read_sst_meta(file_path: &str, object_store: &ObjectStore) -> Result<SstMeta> {
+    let reader = object_store
+        .object(file_path)
+        .reader()
+        .await
+        .context(ReadObjectSnafu { path: file_path })?
+        .compat();
+    let builder = ParquetRecordBatchStreamBuilder::new(BufReader::new(reader))
+        .await
+        .context(ReadParquetSnafu { file: file_path })?;
+    let store_schema = Arc::new(
+        StoreSchema::try_from(builder.schema().clone())
+            .context(error::ConvertStoreSchemaSnafu { file: file_path })?,
+    );
+
+    let file_meta = builder.metadata().file_metadata();
+    let metadata_keys = file_meta
+        .key_value_metadata()
+        .map(|kvs| kvs.iter().map(|kv| kv.key.clone()).collect())
+        .unwrap_or_default();
+    Ok(SstMeta {
+        store_schema,
+        num_rows: file_meta.num_rows(),
+        row_group_rows: builder
+            .metadata()
+            .row_groups()
+            .iter()
+            .map(|rg| rg.num_rows())
+            .collect(),
+        created_by: file_meta.created_by().map(|s| s.to_string()),
+        metadata_keys,
+        has_bloom_filters: decode_bloom_filters(file_meta).is_some(),
+    })
+}
+
+fn decode_bloom_filters(file_meta: &parquet::file::metadata::FileMetaData) -> Option<BloomFilters> {
+    let value = file_meta
+        .key_value_metadata()?
+        .iter()
+        .find(|kv| kv.key == BLOOM_FILTER_KEY)?
+        .value
+        .as_ref()?;
+    BloomFilters::decode(value)
+}
+
 pub type SendableChunkStream = Pin<Box<dyn Stream<Item = Result<RecordBatch>> + Send>>;
 
 pub struct ChunkStream {
@@ -321,18 +452,23 @@ impl BatchReader for ChunkStream {
 mod tests {
     use std::sync::Arc;
 
+    use common_query::logical_plan::DfExpr;
+    use datafusion_expr::{col, lit};
     use datatypes::arrow::array::{Array, ArrayRef, UInt64Array, UInt8Array};
     use datatypes::prelude::Vector;
-    use datatypes::vectors::TimestampMillisecondVector;
+    use datatypes::type_id::LogicalTypeId;
+    use datatypes::vectors::{StringVector, TimestampMillisecondVector, UInt64Vector};
     use object_store::backend::fs::Builder;
     use store_api::storage::OpType;
     use tempdir::TempDir;
 
     use super::*;
     use crate::memtable::{
-        tests as memtable_tests, DefaultMemtableBuilder, IterContext, MemtableBuilder,
+        tests as memtable_tests, DefaultMemtableBuilder, IterContext, KeyValues, MemtableBuilder,
     };
+    use crate::metadata::RegionMetadata;
     use crate::schema::ProjectedSchema;
+    use crate::test_util::descriptor_util::RegionDescBuilder;
 
     #[tokio::test]
     async fn test_parquet_writer() {
@@ -367,7 +503,7 @@ mod tests {
         let object_store = ObjectStore::new(backend);
         let sst_file_name = "test-flush.parquet";
         let iter = memtable.iter(&IterContext::default()).unwrap();
-        let writer = ParquetWriter::new(sst_file_name, iter, object_store.clone());
+        let writer = ParquetWriter::new(sst_file_name, Source::Iter(iter), object_store.clone());
 
         writer
             .write_sst(&sst::WriteOptions::default())
@@ -465,7 +601,7 @@ mod tests {
         let object_store = ObjectStore::new(backend);
         let sst_file_name = "test-read-large.parquet";
         let iter = memtable.iter(&IterContext::default()).unwrap();
-        let writer = ParquetWriter::new(sst_file_name, iter, object_store.clone());
+        let writer = ParquetWriter::new(sst_file_name, Source::Iter(iter), object_store.clone());
 
         let SstInfo {
             start_timestamp,
@@ -538,7 +674,7 @@ mod tests {
         let object_store = ObjectStore::new(backend);
         let sst_file_name = "test-read.parquet";
         let iter = memtable.iter(&IterContext::default()).unwrap();
-        let writer = ParquetWriter::new(sst_file_name, iter, object_store.clone());
+        let writer = ParquetWriter::new(sst_file_name, Source::Iter(iter), object_store.clone());
 
         let SstInfo {
             start_timestamp,
@@ -577,5 +713,87 @@ mod tests {
                 .unwrap()
                 .num_rows()
         );
+
+        let meta = read_sst_meta("test-read.parquet", &object_store)
+            .await
+            .unwrap();
+        assert_eq!(6, meta.num_rows);
+        assert_eq!(vec![6], meta.row_group_rows);
+        assert!(meta.created_by.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_prune_by_bloom_filters() {
+        common_telemetry::init_default_ut_logging();
+        // Schema: (dc, host, timestamp) as key, v0 as value.
+        let desc = RegionDescBuilder::new("test")
+            .push_key_column(("dc", LogicalTypeId::String, false))
+            .push_key_column(("host", LogicalTypeId::String, false))
+            .push_value_column(("v0", LogicalTypeId::UInt64, true))
+            .build();
+        let metadata: RegionMetadata = desc.try_into().unwrap();
+        let schema = metadata.schema().clone();
+        let memtable = DefaultMemtableBuilder::default().build(schema.clone());
+
+        // Hosts in the first and the last row groups are in range [a, c], so min/max
+        // statistics can't prune them by `host = 'b'`.
+        let kvs = KeyValues {
+            sequence: 10,
+            op_type: OpType::Put,
+            start_index_in_batch: 0,
+            keys: vec![
+                Arc::new(StringVector::from(vec!["x", "x", "y", "y", "z", "z"])) as _,
+                Arc::new(StringVector::from(vec!["a", "c", "b", "b", "a", "c"])) as _,
+                Arc::new(TimestampMillisecondVector::from_slice(&[
+                    1000, 1000, 1000, 1001, 1000, 1000,
+                ])) as _,
+            ],
+            values: vec![Arc::new(UInt64Vector::from_slice(&[1, 2, 3, 4, 5, 6])) as _],
+        };
+        memtable.write(&kvs).unwrap();
+
+        let dir = TempDir::new("prune_bloom_filter").unwrap();
+        let path = dir.path().to_str().unwrap();
+        let backend = Builder::default().root(path).build().unwrap();
+        let object_store = ObjectStore::new(backend);
+        let sst_file_name = "test-bloom-filter.parquet";
+        let iter = memtable.iter(&IterContext::default()).unwrap();
+        let mut writer =
+            ParquetWriter::new(sst_file_name, Source::Iter(iter), object_store.clone());
+        // Each dc has its own row group.
+        writer.max_row_group_size = 2;
+        writer
+            .write_sst(&sst::WriteOptions::default())
+            .await
+            .unwrap();
+
+        let read_rows = |expr: DfExpr| {
+            let reader = ParquetReader::new(
+                sst_file_name,
+                object_store.clone(),
+                Arc::new(ProjectedSchema::new(schema.clone(), None).unwrap()),
+                Predicate::new(vec![expr.into()]),
+            );
+            async move {
+                let mut stream = reader.chunk_stream().await.unwrap();
+                let mut hosts = Vec::new();
+                while let Some(batch) = stream.next_batch().await.unwrap() {
+                    for i in 0..batch.num_rows() {
+                        hosts.push(batch.column(1).get(i).to_string());
+                    }
+                }
+                hosts
+            }
+        };
+
+        assert_eq!(vec!["b", "b"], read_rows(col("host").eq(lit("b"))).await);
+        assert_eq!(
+            vec!["a", "c", "a", "c"],
+            read_rows(col("host").in_list(vec![lit("a"), lit("c")], false)).await
+        );
+        // The whole file is skipped.
+        assert!(read_rows(col("host").eq(lit("bb"))).await.is_empty());
+        // Bloom filters don't prune rows by other conditions.
+        assert_eq!(6, read_rows(col("host").not_eq(lit("d"))).await.len());
     }
 }
@@ -0,0 +1,82 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+use object_store::ObjectStore;
+
+use crate::error::Result;
+use crate::read::BoxedBatchReader;
+use crate::sst::{AccessLayer, FileTier, ReadOptions, Source, SstInfo, WriteOptions};
+
+/// [AccessLayer] that only records deleted files, for tests that don't
+/// read or write SSTs.
+#[derive(Debug, Default)]
+pub struct MockAccessLayer {
+    deleted: Mutex<Vec<String>>,
+}
+
+impl MockAccessLayer {
+    /// Returns names of deleted files.
+    pub fn deleted_files(&self) -> Vec<String> {
+        self.deleted.lock().unwrap().clone()
+    }
+}
+
+#[async_trait]
+impl AccessLayer for MockAccessLayer {
+    async fn write_sst(
+        &self,
+        _file_name: &str,
+        _source: Source,
+        _opts: &WriteOptions,
+    ) -> Result<SstInfo> {
+        unimplemented!()
+    }
+
+    async fn read_sst(
+        &self,
+        _file_name: &str,
+        _tier: FileTier,
+        _opts: &ReadOptions,
+    ) -> Result<BoxedBatchReader> {
+        unimplemented!()
+    }
+
+    async fn delete_sst(&self, file_name: &str, _tier: FileTier) -> Result<()> {
+        self.deleted.lock().unwrap().push(file_name.to_string());
+        Ok(())
+    }
+
+    fn has_cold_tier(&self) -> bool {
+        false
+    }
+
+    async fn copy_sst_to_cold(&self, _file_name: &str) -> Result<()> {
+        unimplemented!()
+    }
+
+    async fn export_sst(
+        &self,
+        _file_name: &str,
+        _tier: FileTier,
+        _object_store: &ObjectStore,
+        _dir: &str,
+    ) -> Result<()> {
+        unimplemented!()
+    }
+
+    fn mark_region_dropped(&self) {}
+}
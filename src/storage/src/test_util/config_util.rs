@@ -20,6 +20,8 @@ use object_store::backend::fs::Builder;
 use object_store::ObjectStore;
 
 use crate::background::JobPoolImpl;
+use crate::compaction::{CompactionSchedulerImpl, SimplePicker};
+use crate::config::{WalCompression, DEFAULT_MAX_FLUSH_TASKS};
 use crate::engine;
 use crate::flush::{FlushSchedulerImpl, SizeBasedStrategy};
 use crate::manifest::region::RegionManifest;
@@ -45,7 +47,14 @@ pub async fn new_store_config(
     let sst_layer = Arc::new(FsAccessLayer::new(&sst_dir, object_store.clone()));
     let manifest = RegionManifest::new(&manifest_dir, object_store);
     let job_pool = Arc::new(JobPoolImpl {});
-    let flush_scheduler = Arc::new(FlushSchedulerImpl::new(job_pool));
+    let flush_scheduler = Arc::new(FlushSchedulerImpl::new(
+        job_pool.clone(),
+        DEFAULT_MAX_FLUSH_TASKS,
+    ));
+    let compaction_scheduler = Arc::new(CompactionSchedulerImpl::new(
+        job_pool,
+        SimplePicker::default(),
+    ));
     let log_config = LogConfig {
         log_file_dir: log_store_dir(store_dir),
         ..Default::default()
@@ -59,5 +68,9 @@ pub async fn new_store_config(
         memtable_builder: Arc::new(DefaultMemtableBuilder::default()),
         flush_scheduler,
         flush_strategy: Arc::new(SizeBasedStrategy::default()),
+        compaction_scheduler,
+        ttl: None,
+        wal_compression: WalCompression::None,
+        wal_group_committer: None,
     }
 }
@@ -30,7 +30,7 @@ use store_api::storage::{SchemaRef, SequenceNumber};
 use crate::memtable::{MemtableId, MemtableRef, MemtableVersion};
 use crate::metadata::RegionMetadataRef;
 use crate::schema::RegionSchemaRef;
-use crate::sst::{FileHandle, FileMeta, LevelMetas};
+use crate::sst::{AccessLayerRef, FileMeta, LevelMetas};
 use crate::sync::CowCell;
 
 pub const INIT_COMMITTED_SEQUENCE: u64 = 0;
@@ -129,6 +129,7 @@ impl VersionControl {
 #[derive(Debug)]
 pub struct VersionEdit {
     pub files_to_add: Vec<FileMeta>,
+    pub files_to_remove: Vec<FileMeta>,
     pub flushed_sequence: Option<SequenceNumber>,
     pub manifest_version: ManifestVersion,
     pub max_memtable_id: Option<MemtableId>,
@@ -164,8 +165,12 @@ pub struct Version {
 impl Version {
     /// Create a new `Version` with given `metadata`.
     #[cfg(test)]
-    pub fn new(metadata: RegionMetadataRef, memtable: MemtableRef) -> Version {
-        Version::with_manifest_version(metadata, 0, memtable)
+    pub fn new(
+        metadata: RegionMetadataRef,
+        memtable: MemtableRef,
+        sst_layer: AccessLayerRef,
+    ) -> Version {
+        Version::with_manifest_version(metadata, 0, memtable, sst_layer)
     }
 
     /// Create a new `Version` with given `metadata` and initial `manifest_version`.
@@ -173,11 +178,12 @@ impl Version {
         metadata: RegionMetadataRef,
         manifest_version: ManifestVersion,
         mutable_memtable: MemtableRef,
+        sst_layer: AccessLayerRef,
     ) -> Version {
         Version {
             metadata,
             memtables: Arc::new(MemtableVersion::new(mutable_memtable)),
-            ssts: Arc::new(LevelMetas::new()),
+            ssts: Arc::new(LevelMetas::new(sst_layer)),
             flushed_sequence: 0,
             manifest_version,
         }
@@ -234,8 +240,10 @@ impl Version {
             self.memtables = Arc::new(removed);
         }
 
-        let handles_to_add = edit.files_to_add.into_iter().map(FileHandle::new);
-        let merged_ssts = self.ssts.merge(handles_to_add);
+        let merged_ssts = self.ssts.merge(
+            edit.files_to_add.into_iter(),
+            edit.files_to_remove.into_iter(),
+        );
 
         self.ssts = Arc::new(merged_ssts);
     }
@@ -273,6 +281,7 @@ impl Version {
 mod tests {
     use super::*;
     use crate::memtable::{DefaultMemtableBuilder, MemtableBuilder};
+    use crate::test_util::access_layer_util::MockAccessLayer;
     use crate::test_util::descriptor_util::RegionDescBuilder;
 
     fn new_version_control() -> VersionControl {
@@ -282,7 +291,7 @@ mod tests {
         let metadata: RegionMetadataRef = Arc::new(desc.try_into().unwrap());
         let memtable = DefaultMemtableBuilder::default().build(metadata.schema().clone());
 
-        let version = Version::new(metadata, memtable);
+        let version = Version::new(metadata, memtable, Arc::new(MockAccessLayer::default()));
         VersionControl::with_version(version)
     }
 
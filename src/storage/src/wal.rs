@@ -12,6 +12,9 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod group_commit;
+
+use std::borrow::Cow;
 use std::pin::Pin;
 use std::sync::Arc;
 
@@ -23,12 +26,14 @@ use store_api::logstore::entry::{Entry, Id};
 use store_api::logstore::LogStore;
 use store_api::storage::{RegionId, SequenceNumber};
 
+pub use self::group_commit::{GroupCommitter, GroupCommitterRef};
 use crate::codec::{Decoder, Encoder};
+use crate::config::WalCompression;
 use crate::error::{
-    DecodeWalHeaderSnafu, EncodeWalHeaderSnafu, Error, MarkWalObsoleteSnafu, ReadWalSnafu, Result,
-    WalDataCorruptedSnafu, WriteWalSnafu,
+    CompressWalSnafu, DecodeWalHeaderSnafu, DecompressWalSnafu, EncodeWalHeaderSnafu, Error,
+    MarkWalObsoleteSnafu, ReadWalSnafu, Result, WalDataCorruptedSnafu, WriteWalSnafu,
 };
-use crate::proto::wal::{self, WalHeader};
+use crate::proto::wal::{self, CompressionType, WalHeader};
 use crate::write_batch::codec::{PayloadDecoder, PayloadEncoder};
 use crate::write_batch::Payload;
 
@@ -37,6 +42,8 @@ pub struct Wal<S: LogStore> {
     region_id: RegionId,
     namespace: S::Namespace,
     store: Arc<S>,
+    compression: WalCompression,
+    group_committer: Option<GroupCommitterRef<S>>,
 }
 
 pub type PayloadStream<'a> =
@@ -49,6 +56,8 @@ impl<S: LogStore> Clone for Wal<S> {
             region_id: self.region_id,
             namespace: self.namespace.clone(),
             store: self.store.clone(),
+            compression: self.compression,
+            group_committer: self.group_committer.clone(),
         }
     }
 }
@@ -60,9 +69,23 @@ impl<S: LogStore> Wal<S> {
             region_id,
             namespace,
             store,
+            compression: WalCompression::None,
+            group_committer: None,
         }
     }
 
+    /// Compresses payloads written to the WAL by `compression`.
+    pub fn with_compression(mut self, compression: WalCompression) -> Self {
+        self.compression = compression;
+        self
+    }
+
+    /// Appends entries via the `group_committer` instead of appending them alone.
+    pub fn with_group_committer(mut self, group_committer: Option<GroupCommitterRef<S>>) -> Self {
+        self.group_committer = group_committer;
+        self
+    }
+
     pub async fn obsolete(&self, seq: SequenceNumber) -> Result<()> {
         self.store
             .obsolete(self.namespace.clone(), seq)
@@ -108,6 +131,7 @@ impl<S: LogStore> Wal<S> {
     ) -> Result<Id> {
         if let Some(p) = payload {
             header.mutation_types = wal::gen_mutation_types(p);
+            header.set_compression(self.compression.into());
         }
 
         let mut buf = vec![];
@@ -119,13 +143,15 @@ impl<S: LogStore> Wal<S> {
         // Encode payload
         if let Some(p) = payload {
             let encoder = PayloadEncoder::new();
+            let mut payload_buf = vec![];
             // TODO(jiachun): provide some way to compute data size before encode, so we can preallocate an exactly sized buf.
             encoder
-                .encode(p, &mut buf)
+                .encode(p, &mut payload_buf)
                 .map_err(BoxedError::new)
                 .context(WriteWalSnafu {
                     region_id: self.region_id(),
                 })?;
+            self.compress(header.compression(), &payload_buf, &mut buf)?;
         }
 
         // write bytes to wal
@@ -159,6 +185,16 @@ impl<S: LogStore> Wal<S> {
     async fn write(&self, seq: SequenceNumber, bytes: &[u8]) -> Result<u64> {
         let e = self.store.entry(bytes, seq, self.namespace.clone());
 
+        if let Some(committer) = &self.group_committer {
+            return committer
+                .append(e)
+                .await
+                .map_err(BoxedError::new)
+                .context(WriteWalSnafu {
+                    region_id: self.region_id(),
+                });
+        }
+
         let response = self
             .store
             .append(e)
@@ -171,6 +207,46 @@ impl<S: LogStore> Wal<S> {
         Ok(response.entry_id)
     }
 
+    /// Compresses `data` by `compression` and appends it to `dst`.
+    fn compress(&self, compression: CompressionType, data: &[u8], dst: &mut Vec<u8>) -> Result<()> {
+        match compression {
+            CompressionType::None => dst.extend_from_slice(data),
+            CompressionType::Zstd => {
+                let compressed = zstd::bulk::compress(data, zstd::DEFAULT_COMPRESSION_LEVEL)
+                    .context(CompressWalSnafu {
+                        region_id: self.region_id(),
+                    })?;
+                dst.extend_from_slice(&compressed);
+            }
+            CompressionType::Lz4 => {
+                dst.extend_from_slice(&lz4_flex::compress_prepend_size(data));
+            }
+        }
+        Ok(())
+    }
+
+    /// Decompresses `data` compressed by `compression`.
+    fn decompress<'a>(
+        &self,
+        compression: CompressionType,
+        data: &'a [u8],
+    ) -> Result<Cow<'a, [u8]>> {
+        let decompressed = match compression {
+            CompressionType::None => return Ok(Cow::Borrowed(data)),
+            CompressionType::Zstd => zstd::stream::decode_all(data).map_err(|e| e.to_string()),
+            CompressionType::Lz4 => {
+                lz4_flex::decompress_size_prepended(data).map_err(|e| e.to_string())
+            }
+        };
+        decompressed.map(Cow::Owned).map_err(|message| {
+            DecompressWalSnafu {
+                region_id: self.region_id(),
+                message,
+            }
+            .build()
+        })
+    }
+
     fn decode_entry<E: Entry>(
         &self,
         entry: E,
@@ -197,9 +273,10 @@ impl<S: LogStore> Wal<S> {
             return Ok((seq_num, header, None));
         }
 
+        let data = self.decompress(header.compression(), &input[data_pos..])?;
         let decoder = PayloadDecoder::new(&header.mutation_types);
         let payload = decoder
-            .decode(&input[data_pos..])
+            .decode(&data)
             .map_err(BoxedError::new)
             .context(ReadWalSnafu {
                 region_id: self.region_id(),
@@ -244,9 +321,16 @@ impl Decoder for WalHeaderDecoder {
 
 #[cfg(test)]
 mod tests {
+    use std::collections::HashMap;
+
+    use datatypes::prelude::ScalarVector;
+    use datatypes::vectors::{BooleanVector, TimestampMillisecondVector, UInt64Vector, VectorRef};
+    use log_store::remote::{MemoryLogClient, RemoteLogConfig, RemoteLogStore};
     use log_store::test_util;
+    use store_api::storage::{consts, WriteRequest};
 
     use super::*;
+    use crate::write_batch::{self, WriteBatch};
 
     #[tokio::test]
     pub async fn test_write_wal() {
@@ -283,11 +367,117 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    pub async fn test_read_wal_from_another_node() -> Result<()> {
+        let client = MemoryLogClient::default();
+        let new_log_store =
+            || RemoteLogStore::new(RemoteLogConfig::default(), Arc::new(client.clone()));
+        let wal = Wal::new(0, Arc::new(new_log_store()));
+        for seq_num in 1..=3 {
+            let header = WalHeader::with_last_manifest_version(seq_num);
+            wal.write_to_wal(seq_num, header, None).await?;
+        }
+
+        // Replays the WAL by a log store connected to the same service.
+        let wal = Wal::new(0, Arc::new(new_log_store()));
+        let mut stream = wal.read_from_wal(2).await?;
+        let mut seq_nums = vec![];
+        while let Some((seq_num, header, _)) = stream.try_next().await? {
+            assert_eq!(seq_num, header.last_manifest_version);
+            seq_nums.push(seq_num);
+        }
+        assert_eq!(vec![2, 3], seq_nums);
+
+        Ok(())
+    }
+
+    fn new_test_batch() -> WriteBatch {
+        let mut batch = write_batch::new_test_batch();
+        for i in 0..10 {
+            let intv = Arc::new(UInt64Vector::from_slice(&[1, 2, 3])) as VectorRef;
+            let boolv =
+                Arc::new(BooleanVector::from(vec![Some(true), Some(false), None])) as VectorRef;
+            let tsv = Arc::new(TimestampMillisecondVector::from_vec(vec![i, i, i])) as VectorRef;
+
+            let mut put_data = HashMap::new();
+            put_data.insert("k1".to_string(), intv.clone());
+            put_data.insert(consts::VERSION_COLUMN_NAME.to_string(), intv);
+            put_data.insert("v1".to_string(), boolv);
+            put_data.insert("ts".to_string(), tsv);
+
+            batch.put(put_data).unwrap();
+        }
+
+        batch
+    }
+
+    #[tokio::test]
+    pub async fn test_read_wal_with_compression() -> Result<()> {
+        let (log_store, _tmp) =
+            test_util::log_store_util::create_tmp_local_file_log_store("wal_test").await;
+        let log_store = Arc::new(log_store);
+        let batch = new_test_batch();
+        let payload = batch.payload();
+        let compressions = [
+            WalCompression::None,
+            WalCompression::Zstd,
+            WalCompression::Lz4,
+        ];
+        for (seq_num, compression) in compressions.into_iter().enumerate() {
+            let wal = Wal::new(0, log_store.clone()).with_compression(compression);
+            let header = WalHeader::with_last_manifest_version(seq_num as u64);
+            wal.write_to_wal(seq_num as u64, header, Some(payload))
+                .await?;
+        }
+
+        // Entries written with any compression are readable.
+        let wal = Wal::new(0, log_store);
+        let mut stream = wal.read_from_wal(0).await?;
+        let mut compressions_read = vec![];
+        while let Some((_, header, data)) = stream.try_next().await? {
+            assert_eq!(Some(payload), data.as_ref());
+            compressions_read.push(header.compression());
+        }
+        assert_eq!(
+            vec![
+                CompressionType::None,
+                CompressionType::Zstd,
+                CompressionType::Lz4
+            ],
+            compressions_read
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    pub async fn test_write_wal_by_group_committer() -> Result<()> {
+        let (log_store, _tmp) =
+            test_util::log_store_util::create_tmp_local_file_log_store("wal_test").await;
+        let log_store = Arc::new(log_store);
+        let committer = Arc::new(GroupCommitter::new(log_store.clone()));
+        let wal = Wal::new(0, log_store).with_group_committer(Some(committer));
+        for seq_num in 0..3 {
+            let header = WalHeader::with_last_manifest_version(seq_num);
+            assert_eq!(seq_num, wal.write_to_wal(seq_num, header, None).await?);
+        }
+
+        let mut stream = wal.read_from_wal(0).await?;
+        let mut seq_nums = vec![];
+        while let Some((seq_num, _, _)) = stream.try_next().await? {
+            seq_nums.push(seq_num);
+        }
+        assert_eq!(vec![0, 1, 2], seq_nums);
+
+        Ok(())
+    }
+
     #[test]
     pub fn test_wal_header_codec() {
         let wal_header = WalHeader {
             last_manifest_version: 99999999,
             mutation_types: vec![],
+            ..Default::default()
         };
 
         let mut buf: Vec<u8> = vec![];
@@ -0,0 +1,163 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use common_telemetry::logging;
+use store_api::logstore::entry::Id;
+use store_api::logstore::LogStore;
+use tokio::sync::{mpsc, oneshot};
+
+use crate::error::{GroupCommitWalSnafu, Result};
+
+/// Max number of entries appended to the log store in one group.
+const MAX_GROUP_SIZE: usize = 256;
+
+struct AppendRequest<S: LogStore> {
+    entry: S::Entry,
+    sender: oneshot::Sender<Result<Id>>,
+}
+
+/// Coalesces WAL entries appended concurrently by all regions into one
+/// [LogStore::append_batch], so they share a single write (and fsync) of the log store.
+///
+/// Entries arriving while a group is being appended form the next group.
+pub struct GroupCommitter<S: LogStore> {
+    sender: mpsc::UnboundedSender<AppendRequest<S>>,
+}
+
+pub type GroupCommitterRef<S> = Arc<GroupCommitter<S>>;
+
+impl<S: LogStore> std::fmt::Debug for GroupCommitter<S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GroupCommitter").finish()
+    }
+}
+
+impl<S: LogStore> GroupCommitter<S> {
+    /// Creates a committer that appends entries to `store` in background, the background
+    /// task exits once the committer is dropped.
+    pub fn new(store: Arc<S>) -> GroupCommitter<S> {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        common_runtime::spawn_bg(commit_groups(store, receiver));
+
+        GroupCommitter { sender }
+    }
+
+    /// Appends the `entry` with entries of other writers and returns its id once the
+    /// group is persisted.
+    pub async fn append(&self, entry: S::Entry) -> Result<Id> {
+        let (sender, receiver) = oneshot::channel();
+        if self.sender.send(AppendRequest { entry, sender }).is_err() {
+            return GroupCommitWalSnafu {
+                message: "committer is stopped",
+            }
+            .fail();
+        }
+
+        match receiver.await {
+            Ok(result) => result,
+            Err(_) => GroupCommitWalSnafu {
+                message: "committer is stopped before the entry is persisted",
+            }
+            .fail(),
+        }
+    }
+}
+
+async fn commit_groups<S: LogStore>(
+    store: Arc<S>,
+    mut receiver: mpsc::UnboundedReceiver<AppendRequest<S>>,
+) {
+    while let Some(request) = receiver.recv().await {
+        let mut requests = vec![request];
+        while requests.len() < MAX_GROUP_SIZE {
+            match receiver.try_recv() {
+                Ok(request) => requests.push(request),
+                Err(_) => break,
+            }
+        }
+
+        let (entries, senders): (Vec<_>, Vec<_>) = requests
+            .into_iter()
+            .map(|request| (request.entry, request.sender))
+            .unzip();
+        match store.append_batch(entries).await {
+            Ok(ids) => {
+                for (sender, id) in senders.into_iter().zip(ids) {
+                    // The writer may be cancelled, so ignore the error.
+                    let _ = sender.send(Ok(id));
+                }
+            }
+            Err(e) => {
+                logging::error!(e; "Failed to append a group of {} WAL entries", senders.len());
+
+                let message = e.to_string();
+                for sender in senders {
+                    let _ = sender.send(
+                        GroupCommitWalSnafu {
+                            message: message.clone(),
+                        }
+                        .fail(),
+                    );
+                }
+            }
+        }
+    }
+
+    logging::info!("WAL group committer stopped");
+}
+
+#[cfg(test)]
+mod tests {
+    use futures_util::StreamExt;
+    use log_store::test_util;
+    use store_api::logstore::entry::Entry;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_group_commit() {
+        let (log_store, _tmp) =
+            test_util::log_store_util::create_tmp_local_file_log_store("group_commit").await;
+        let log_store = Arc::new(log_store);
+        let committer = Arc::new(GroupCommitter::new(log_store.clone()));
+
+        // Each region appends its entries in order, concurrently with other regions.
+        let handles: Vec<_> = (0..4)
+            .map(|ns| {
+                let committer = committer.clone();
+                let log_store = log_store.clone();
+                tokio::spawn(async move {
+                    for id in 0..16 {
+                        let entry = log_store.entry(b"test", id, log_store.namespace(ns));
+                        assert_eq!(id, committer.append(entry).await.unwrap());
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.await.unwrap();
+        }
+
+        for ns in 0..4 {
+            let mut stream = log_store.read(&log_store.namespace(ns), 0).await.unwrap();
+            let mut ids = vec![];
+            while let Some(entries) = stream.next().await {
+                ids.extend(entries.unwrap().iter().map(|e| e.id()));
+            }
+            assert_eq!((0..16).collect::<Vec<_>>(), ids);
+        }
+    }
+}
@@ -38,12 +38,9 @@ pub trait LogStore: Send + Sync + 'static + std::fmt::Debug {
     /// the entry id.
     async fn append(&self, mut e: Self::Entry) -> Result<AppendResponse, Self::Error>;
 
-    /// Append a batch of entries atomically and return the offset of first entry.
-    async fn append_batch(
-        &self,
-        ns: &Self::Namespace,
-        e: Vec<Self::Entry>,
-    ) -> Result<Vec<Id>, Self::Error>;
+    /// Append a batch of entries, which may belong to different namespaces, and return
+    /// their ids. Implementations should persist the batch in as few writes as possible.
+    async fn append_batch(&self, e: Vec<Self::Entry>) -> Result<Vec<Id>, Self::Error>;
 
     /// Create a new `EntryStream` to asynchronously generates `Entry` with ids
     /// starting from `id`.
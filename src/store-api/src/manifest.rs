@@ -48,6 +48,34 @@ pub trait MetaAction: Serialize + DeserializeOwned + Send + Sync + Clone + std::
     ) -> Result<(Self, Option<ProtocolAction>), Self::Error>;
 }
 
+/// Checkpoint of a manifest, compacts all actions in versions `[0, last_version]`.
+pub trait Checkpoint:
+    Serialize + DeserializeOwned + Default + Send + Sync + Clone + std::fmt::Debug
+{
+    type Error: ErrorExt + Send + Sync;
+    type MetaAction: MetaAction;
+
+    /// Returns the version of the last action compacted into this checkpoint.
+    fn last_version(&self) -> ManifestVersion;
+
+    /// Returns the last protocol action compacted into this checkpoint.
+    fn protocol(&self) -> &ProtocolAction;
+
+    /// Compacts the action list of `version` into this checkpoint.
+    fn apply(
+        &mut self,
+        version: ManifestVersion,
+        action_list: Self::MetaAction,
+    ) -> Result<(), Self::Error>;
+
+    /// Encode this checkpoint into a byte vector
+    fn encode(&self) -> Result<Vec<u8>, Self::Error>;
+
+    /// Decode checkpoint from byte slice with reader protocol version,
+    /// return error when reader version is not supported.
+    fn decode(bs: &[u8], reader_version: ProtocolVersion) -> Result<Self, Self::Error>;
+}
+
 #[async_trait]
 pub trait MetaActionIterator {
     type MetaAction: MetaAction;
@@ -64,6 +92,7 @@ pub trait Manifest: Send + Sync + Clone + 'static {
     type Error: ErrorExt + Send + Sync;
     type MetaAction: MetaAction;
     type MetaActionIterator: MetaActionIterator<Error = Self::Error, MetaAction = Self::MetaAction>;
+    type Checkpoint: Checkpoint<Error = Self::Error, MetaAction = Self::MetaAction>;
 
     /// Update metadata by the action
     async fn update(&self, action: Self::MetaAction) -> Result<ManifestVersion, Self::Error>;
@@ -75,7 +104,13 @@ pub trait Manifest: Send + Sync + Clone + 'static {
         end: ManifestVersion,
     ) -> Result<Self::MetaActionIterator, Self::Error>;
 
-    async fn checkpoint(&self) -> Result<ManifestVersion, Self::Error>;
+    /// Compacts actions into a new checkpoint and deletes the compacted actions.
+    ///
+    /// Returns `None` if there is no new action to compact.
+    async fn checkpoint(&self) -> Result<Option<Self::Checkpoint>, Self::Error>;
+
+    /// Returns the latest checkpoint.
+    async fn last_checkpoint(&self) -> Result<Option<Self::Checkpoint>, Self::Error>;
 
     fn last_version(&self) -> ManifestVersion;
 }
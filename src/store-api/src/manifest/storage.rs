@@ -52,4 +52,7 @@ pub trait ManifestLogStorage {
 
     /// Load the latest checkpoint
     async fn load_checkpoint(&self) -> Result<Option<(ManifestVersion, Vec<u8>)>, Self::Error>;
+
+    /// Delete the checkpoint of `version`
+    async fn delete_checkpoint(&self, version: ManifestVersion) -> Result<(), Self::Error>;
 }
@@ -32,11 +32,11 @@ pub use datatypes::schema::{
 
 pub use self::chunk::{Chunk, ChunkReader};
 pub use self::descriptors::*;
-pub use self::engine::{CreateOptions, EngineContext, OpenOptions, StorageEngine};
+pub use self::engine::{CreateOptions, EngineContext, MemtableType, OpenOptions, StorageEngine};
 pub use self::metadata::RegionMeta;
-pub use self::region::{Region, WriteContext};
+pub use self::region::{FlushContext, Region, WriteContext};
 pub use self::requests::{
-    AddColumn, AlterOperation, AlterRequest, GetRequest, ScanRequest, WriteRequest,
+    AddColumn, AlterOperation, AlterRequest, GetRequest, ScanRequest, TimeOrder, WriteRequest,
 };
 pub use self::responses::{GetResponse, ScanResponse, WriteResponse};
 pub use self::snapshot::{ReadContext, Snapshot};
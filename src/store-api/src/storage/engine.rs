@@ -18,6 +18,8 @@
 //! a [`StorageEngine`] instance manages a bunch of storage unit called [`Region`], which holds
 //! chunks of rows, support operations like PUT/DELETE/SCAN.
 
+use std::time::Duration;
+
 use async_trait::async_trait;
 use common_error::ext::ErrorExt;
 
@@ -82,6 +84,10 @@ pub struct EngineContext {}
 pub struct CreateOptions {
     /// Region parent directory
     pub parent_dir: String,
+    /// Region data TTL, data older than the TTL would be invisible and removed.
+    pub ttl: Option<Duration>,
+    /// Type of memtables of the region.
+    pub memtable_type: MemtableType,
 }
 
 /// Options to open a region.
@@ -89,4 +95,19 @@ pub struct CreateOptions {
 pub struct OpenOptions {
     /// Region parent directory
     pub parent_dir: String,
+    /// Region data TTL, data older than the TTL would be invisible and removed.
+    pub ttl: Option<Duration>,
+    /// Type of memtables of the region.
+    pub memtable_type: MemtableType,
+}
+
+/// Type of the memtable that buffers written rows of a region.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MemtableType {
+    /// Memtable that stores rows in an ordered map.
+    #[default]
+    BTree,
+    /// Memtable that groups rows by series (primary key) and sorts rows of each
+    /// series by timestamp while reading.
+    Series,
 }
@@ -72,6 +72,9 @@ pub trait Region: Send + Sync + Clone + std::fmt::Debug + 'static {
     fn write_request(&self) -> Self::WriteRequest;
 
     async fn alter(&self, request: AlterRequest) -> Result<(), Self::Error>;
+
+    /// Flush the mutable memtable of the region.
+    async fn flush(&self, ctx: &FlushContext) -> Result<(), Self::Error>;
 }
 
 /// Context for write operations.
@@ -83,3 +86,16 @@ impl From<&OpenOptions> for WriteContext {
         WriteContext::default()
     }
 }
+
+/// Context for flush operations.
+#[derive(Debug, Clone)]
+pub struct FlushContext {
+    /// Whether to wait until the flush is finished.
+    pub wait: bool,
+}
+
+impl Default for FlushContext {
+    fn default() -> FlushContext {
+        FlushContext { wait: true }
+    }
+}
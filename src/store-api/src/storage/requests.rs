@@ -16,6 +16,8 @@ use std::collections::{HashMap, HashSet};
 
 use common_error::ext::ErrorExt;
 use common_query::logical_plan::Expr;
+use common_time::Timestamp;
+use datatypes::value::Value;
 use datatypes::vectors::VectorRef;
 
 use crate::storage::{ColumnDescriptor, RegionDescriptor, SequenceNumber};
@@ -49,10 +51,32 @@ pub struct ScanRequest {
     pub projection: Option<Vec<usize>>,
     /// Filters pushed down
     pub filters: Vec<Expr>,
+    /// Order of the rows to return, `None` if the order doesn't matter.
+    pub order: Option<TimeOrder>,
+    /// Max number of rows to return, `None` to return all rows.
+    pub limit: Option<usize>,
 }
 
-#[derive(Debug)]
-pub struct GetRequest {}
+/// Order of rows by the timestamp column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeOrder {
+    Asc,
+    Desc,
+}
+
+/// Request to get the latest visible row with the given primary key and timestamp.
+#[derive(Debug, Clone)]
+pub struct GetRequest {
+    /// Values of all primary key columns (except the timestamp column), keyed by
+    /// column name. Values must have the same types as their columns.
+    pub primary_key: HashMap<String, Value>,
+    /// Value of the timestamp column.
+    pub timestamp: Timestamp,
+    /// Max sequence number to read, None for latest sequence.
+    pub sequence: Option<SequenceNumber>,
+    /// Indices of columns to return, `None` to return all columns.
+    pub projection: Option<Vec<usize>>,
+}
 
 /// Operation to add a column.
 #[derive(Debug)]
@@ -35,6 +35,19 @@ pub trait Snapshot: Send + Sync {
         request: ScanRequest,
     ) -> Result<ScanResponse<Self::Reader>, Self::Error>;
 
+    /// Scans the region in at most `num_partitions` partitions that could be read in
+    /// parallel. Rows with the same key are always in the same partition.
+    async fn scan_partitions(
+        &self,
+        ctx: &ReadContext,
+        request: ScanRequest,
+        num_partitions: usize,
+    ) -> Result<Vec<ScanResponse<Self::Reader>>, Self::Error>;
+
+    /// Reads the row with the key in `request`.
+    ///
+    /// This is not a point lookup by an index: implementations may scan the data that
+    /// could contain the key and filter out the row.
     async fn get(&self, ctx: &ReadContext, request: GetRequest)
         -> Result<GetResponse, Self::Error>;
 }
@@ -105,6 +105,16 @@ pub enum Error {
 
     #[snafu(display("Unsupported operation: {}", operation))]
     Unsupported { operation: String },
+
+    #[snafu(display("Invalid ttl option: {}, source: {}", value, source))]
+    InvalidTtl {
+        value: String,
+        source: humantime::DurationError,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Invalid memtable option: {}", value))]
+    InvalidMemtableType { value: String, backtrace: Backtrace },
 }
 
 impl ErrorExt for Error {
@@ -114,9 +124,10 @@ impl ErrorExt for Error {
             | Error::PollStream { .. }
             | Error::SchemaConversion { .. }
             | Error::TableProjection { .. } => StatusCode::EngineExecuteQuery,
-            Error::RemoveColumnInIndex { .. } | Error::BuildColumnDescriptor { .. } => {
-                StatusCode::InvalidArguments
-            }
+            Error::RemoveColumnInIndex { .. }
+            | Error::BuildColumnDescriptor { .. }
+            | Error::InvalidTtl { .. }
+            | Error::InvalidMemtableType { .. } => StatusCode::InvalidArguments,
             Error::TablesRecordBatch { .. } => StatusCode::Unexpected,
             Error::ColumnExists { .. } => StatusCode::TableColumnExists,
             Error::SchemaBuild { source, .. } => source.status_code(),
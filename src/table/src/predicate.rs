@@ -18,9 +18,10 @@ use common_time::range::TimestampRange;
 use common_time::Timestamp;
 use datafusion::parquet::file::metadata::RowGroupMetaData;
 use datafusion::physical_optimizer::pruning::PruningPredicate;
+use datafusion_common::ScalarValue;
 use datafusion_expr::{Between, BinaryExpr, Operator};
 use datatypes::schema::SchemaRef;
-use datatypes::value::scalar_value_to_timestamp;
+use datatypes::value::{scalar_value_to_timestamp, Value};
 
 use crate::predicate::stats::RowGroupPruningStatistics;
 
@@ -68,6 +69,59 @@ impl Predicate {
         }
         res
     }
+
+    /// Returns false if no row filtered by `filter` can match this predicate, e.g. the
+    /// value of an equality condition is absent from the bloom filters of a row group.
+    pub fn may_match(&self, filter: &dyn ColumnValueFilter) -> bool {
+        self.exprs
+            .iter()
+            .all(|expr| may_match_expr(expr.df_expr(), filter))
+    }
+}
+
+/// Tests whether values may exist in columns, e.g. with bloom filters.
+pub trait ColumnValueFilter {
+    /// Returns `Some(false)` if `value` definitely doesn't exist in `column`, or `None` if
+    /// the filter knows nothing about the `column`.
+    fn may_contain(&self, column: &str, value: &Value) -> Option<bool>;
+}
+
+/// Evaluates `=`, `IN` and `AND`/`OR` of them against the `filter`, other expressions
+/// are treated as matched.
+fn may_match_expr(expr: &DfExpr, filter: &dyn ColumnValueFilter) -> bool {
+    match expr {
+        DfExpr::BinaryExpr(BinaryExpr { left, op, right }) => match op {
+            Operator::And => may_match_expr(left, filter) && may_match_expr(right, filter),
+            Operator::Or => may_match_expr(left, filter) || may_match_expr(right, filter),
+            Operator::Eq => match (left.as_ref(), right.as_ref()) {
+                (DfExpr::Column(column), DfExpr::Literal(scalar))
+                | (DfExpr::Literal(scalar), DfExpr::Column(column)) => {
+                    column_may_contain(filter, &column.name, scalar)
+                }
+                _ => true,
+            },
+            _ => true,
+        },
+        DfExpr::InList {
+            expr,
+            list,
+            negated: false,
+        } => {
+            let DfExpr::Column(column) = expr.as_ref() else { return true; };
+            list.iter().any(|item| match item {
+                DfExpr::Literal(scalar) => column_may_contain(filter, &column.name, scalar),
+                _ => true,
+            })
+        }
+        _ => true,
+    }
+}
+
+fn column_may_contain(filter: &dyn ColumnValueFilter, column: &str, scalar: &ScalarValue) -> bool {
+    match Value::try_from(scalar.clone()) {
+        Ok(value) => filter.may_contain(column, &value).unwrap_or(true),
+        Err(_) => true,
+    }
 }
 
 // tests for `TimeRangePredicateBuilder` locates in src/query/tests/time_range_filter_test.rs
@@ -340,6 +394,73 @@ mod tests {
         )])
     }
 
+    /// Column `name` only contains values in the set.
+    struct NameFilter(Vec<&'static str>);
+
+    impl ColumnValueFilter for NameFilter {
+        fn may_contain(&self, column: &str, value: &Value) -> Option<bool> {
+            if column != "name" {
+                return None;
+            }
+            Some(self.0.iter().any(|name| Value::from(*name) == *value))
+        }
+    }
+
+    fn name_eq(name: &str) -> Expr {
+        Expr::BinaryExpr(BinaryExpr {
+            left: Box::new(Expr::Column(Column::from_name("name"))),
+            op: Operator::Eq,
+            right: Box::new(name.lit()),
+        })
+    }
+
+    #[test]
+    fn test_may_match() {
+        let filter = NameFilter(vec!["a", "b"]);
+        let predicate = |expr: Expr| Predicate::new(vec![expr.into()]);
+
+        assert!(Predicate::empty().may_match(&filter));
+        assert!(predicate(name_eq("a")).may_match(&filter));
+        assert!(!predicate(name_eq("c")).may_match(&filter));
+        // Literal on the left.
+        assert!(!predicate(Expr::BinaryExpr(BinaryExpr {
+            left: Box::new("c".lit()),
+            op: Operator::Eq,
+            right: Box::new(Expr::Column(Column::from_name("name"))),
+        }))
+        .may_match(&filter));
+
+        assert!(!predicate(name_eq("c").and(name_eq("a"))).may_match(&filter));
+        assert!(predicate(name_eq("c").or(name_eq("a"))).may_match(&filter));
+        assert!(!predicate(name_eq("c").or(name_eq("d"))).may_match(&filter));
+
+        let in_list = |names: &[&str], negated| Expr::InList {
+            expr: Box::new(Expr::Column(Column::from_name("name"))),
+            list: names.iter().map(|name| name.lit()).collect(),
+            negated,
+        };
+        assert!(predicate(in_list(&["c", "b"], false)).may_match(&filter));
+        assert!(!predicate(in_list(&["c", "d"], false)).may_match(&filter));
+        assert!(predicate(in_list(&["c", "d"], true)).may_match(&filter));
+
+        // Columns without filter and other operators.
+        assert!(predicate(Expr::BinaryExpr(BinaryExpr {
+            left: Box::new(Expr::Column(Column::from_name("cnt"))),
+            op: Operator::Eq,
+            right: Box::new(10.lit()),
+        }))
+        .may_match(&filter));
+        assert!(predicate(Expr::BinaryExpr(BinaryExpr {
+            left: Box::new(Expr::Column(Column::from_name("name"))),
+            op: Operator::NotEq,
+            right: Box::new("c".lit()),
+        }))
+        .may_match(&filter));
+        // All expressions must match.
+        let predicate = Predicate::new(vec![name_eq("a").into(), name_eq("c").into()]);
+        assert!(!predicate.may_match(&filter));
+    }
+
     #[tokio::test]
     async fn test_prune_empty() {
         assert_prune(3, Predicate::empty(), vec![true]).await;
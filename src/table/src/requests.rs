@@ -14,13 +14,46 @@
 
 //! Table and TableEngine requests
 use std::collections::HashMap;
+use std::time::Duration;
 
 use datatypes::prelude::VectorRef;
 use datatypes::schema::{ColumnSchema, SchemaRef};
-use store_api::storage::RegionNumber;
+use snafu::ResultExt;
+use store_api::storage::{MemtableType, RegionNumber};
 
+use crate::error::{self, Result};
 use crate::metadata::TableId;
 
+/// Key of the table option that sets the time-to-live of data, e.g. `WITH (ttl='30d')`.
+pub const TTL_KEY: &str = "ttl";
+
+/// Parses the ttl in table `options`, returns `None` if the table has no ttl.
+pub fn parse_ttl(options: &HashMap<String, String>) -> Result<Option<Duration>> {
+    options
+        .get(TTL_KEY)
+        .map(|value| humantime::parse_duration(value).context(error::InvalidTtlSnafu { value }))
+        .transpose()
+}
+
+/// Key of the table option that sets the memtable type, e.g. `WITH (memtable='series')`.
+pub const MEMTABLE_KEY: &str = "memtable";
+
+/// Parses the memtable type in table `options`, returns the default type if the table
+/// doesn't set it.
+pub fn parse_memtable_type(options: &HashMap<String, String>) -> Result<MemtableType> {
+    let Some(value) = options.get(MEMTABLE_KEY) else { return Ok(MemtableType::default()) };
+    match value.to_lowercase().as_str() {
+        "btree" => Ok(MemtableType::BTree),
+        "series" => Ok(MemtableType::Series),
+        _ => error::InvalidMemtableTypeSnafu { value }.fail(),
+    }
+}
+
+/// Returns true if `key` is a table option recognised by the table engine.
+pub fn valid_table_option(key: &str) -> bool {
+    matches!(key, TTL_KEY | MEMTABLE_KEY)
+}
+
 /// Insert request
 #[derive(Debug)]
 pub struct InsertRequest {
@@ -106,3 +139,52 @@ pub struct DeleteRequest {
     /// The key is the column name, and the value is the column value.
     pub key_column_values: HashMap<String, VectorRef>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_ttl() {
+        let mut options = HashMap::new();
+        assert_eq!(None, parse_ttl(&options).unwrap());
+
+        options.insert(TTL_KEY.to_string(), "30d".to_string());
+        assert_eq!(
+            Some(Duration::from_secs(30 * 24 * 60 * 60)),
+            parse_ttl(&options).unwrap()
+        );
+
+        options.insert(TTL_KEY.to_string(), "1h 30m".to_string());
+        assert_eq!(
+            Some(Duration::from_secs(90 * 60)),
+            parse_ttl(&options).unwrap()
+        );
+
+        options.insert(TTL_KEY.to_string(), "30".to_string());
+        assert!(parse_ttl(&options).is_err());
+    }
+
+    #[test]
+    fn test_parse_memtable_type() {
+        let mut options = HashMap::new();
+        assert_eq!(MemtableType::BTree, parse_memtable_type(&options).unwrap());
+
+        options.insert(MEMTABLE_KEY.to_string(), "Series".to_string());
+        assert_eq!(MemtableType::Series, parse_memtable_type(&options).unwrap());
+
+        options.insert(MEMTABLE_KEY.to_string(), "btree".to_string());
+        assert_eq!(MemtableType::BTree, parse_memtable_type(&options).unwrap());
+
+        options.insert(MEMTABLE_KEY.to_string(), "skiplist".to_string());
+        assert!(parse_memtable_type(&options).is_err());
+    }
+
+    #[test]
+    fn test_valid_table_option() {
+        assert!(valid_table_option(TTL_KEY));
+        assert!(valid_table_option(MEMTABLE_KEY));
+        assert!(!valid_table_option("regions"));
+        assert!(!valid_table_option("engine"));
+    }
+}
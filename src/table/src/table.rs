@@ -23,6 +23,7 @@ use async_trait::async_trait;
 use common_query::logical_plan::Expr;
 use common_query::physical_plan::PhysicalPlanRef;
 use datatypes::schema::SchemaRef;
+use store_api::storage::TimeOrder;
 
 use crate::error::{Result, UnsupportedSnafu};
 use crate::metadata::{FilterPushDownType, TableId, TableInfoRef, TableType};
@@ -70,6 +71,21 @@ pub trait Table: Send + Sync {
         limit: Option<usize>,
     ) -> Result<PhysicalPlanRef>;
 
+    /// Scan the table, rows of each partition are ordered by the timestamp column in
+    /// `order`, and each partition returns at most `limit` rows.
+    ///
+    /// Tables that can't return ordered rows scan all rows without the order, so the
+    /// caller still needs to sort and limit the rows.
+    async fn scan_ordered(
+        &self,
+        projection: Option<&Vec<usize>>,
+        filters: &[Expr],
+        _order: TimeOrder,
+        _limit: Option<usize>,
+    ) -> Result<PhysicalPlanRef> {
+        self.scan(projection, filters, None).await
+    }
+
     /// Tests whether the table provider can make use of a filter expression
     /// to optimise data retrieval.
     fn supports_filter_pushdown(&self, _filter: &Expr) -> Result<FilterPushDownType> {
@@ -93,6 +109,14 @@ pub trait Table: Send + Sync {
         }
         .fail()?
     }
+
+    /// Flush data in memory of the table to the underlying storage.
+    async fn flush(&self) -> Result<()> {
+        UnsupportedSnafu {
+            operation: "FLUSH TABLE",
+        }
+        .fail()?
+    }
 }
 
 pub type TableRef = Arc<dyn Table>;
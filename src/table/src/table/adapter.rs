@@ -28,6 +28,7 @@ use datafusion::prelude::SessionContext;
 use datafusion_expr::expr::Expr as DfExpr;
 use datatypes::schema::{SchemaRef as TableSchemaRef, SchemaRef};
 use snafu::prelude::*;
+use store_api::storage::TimeOrder;
 
 use crate::error::{self, Result};
 use crate::metadata::TableInfoRef;
@@ -36,16 +37,30 @@ use crate::table::{FilterPushDownType, Table, TableRef, TableType};
 /// Greptime Table ->  datafusion TableProvider
 pub struct DfTableProviderAdapter {
     table: TableRef,
+    /// Order of rows by the timestamp column the plan requires, `None` if the order
+    /// doesn't matter.
+    order: Option<TimeOrder>,
 }
 
 impl DfTableProviderAdapter {
     pub fn new(table: TableRef) -> Self {
-        Self { table }
+        Self { table, order: None }
+    }
+
+    /// Asks the table to return rows ordered by the timestamp column in `order`, so the
+    /// table could stop scanning once it returns enough rows.
+    pub fn with_order(mut self, order: Option<TimeOrder>) -> Self {
+        self.order = order;
+        self
     }
 
     pub fn table(&self) -> TableRef {
         self.table.clone()
     }
+
+    pub fn order(&self) -> Option<TimeOrder> {
+        self.order
+    }
 }
 
 #[async_trait::async_trait]
@@ -74,7 +89,14 @@ impl TableProvider for DfTableProviderAdapter {
         limit: Option<usize>,
     ) -> DfResult<Arc<dyn DfPhysicalPlan>> {
         let filters: Vec<Expr> = filters.iter().map(Clone::clone).map(Into::into).collect();
-        let inner = self.table.scan(projection, &filters, limit).await?;
+        let inner = match self.order {
+            Some(order) => {
+                self.table
+                    .scan_ordered(projection, &filters, order, limit)
+                    .await?
+            }
+            None => self.table.scan(projection, &filters, limit).await?,
+        };
         Ok(Arc::new(DfPhysicalPlanAdapter(inner)))
     }
 
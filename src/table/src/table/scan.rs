@@ -25,14 +25,15 @@ use datatypes::schema::SchemaRef;
 use snafu::OptionExt;
 
 pub struct SimpleTableScan {
-    stream: Mutex<Option<SendableRecordBatchStream>>,
+    /// Stream of each partition.
+    streams: Mutex<Vec<Option<SendableRecordBatchStream>>>,
     schema: SchemaRef,
 }
 
 impl Debug for SimpleTableScan {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("SimpleTableScan")
-            .field("stream", &"<SendableRecordBatchStream>")
+            .field("streams", &"<SendableRecordBatchStream>")
             .field("schema", &self.schema)
             .finish()
     }
@@ -42,10 +43,24 @@ impl SimpleTableScan {
     pub fn new(stream: SendableRecordBatchStream) -> Self {
         let schema = stream.schema();
         Self {
-            stream: Mutex::new(Some(stream)),
+            streams: Mutex::new(vec![Some(stream)]),
             schema,
         }
     }
+
+    /// Creates a scan whose `i-th` partition reads the `i-th` stream.
+    ///
+    /// Returns an error if `streams` is empty.
+    pub fn with_partitions(streams: Vec<SendableRecordBatchStream>) -> QueryResult<Self> {
+        let schema = streams
+            .first()
+            .context(query_error::EmptyPartitionsSnafu)?
+            .schema();
+        Ok(Self {
+            streams: Mutex::new(streams.into_iter().map(Some).collect()),
+            schema,
+        })
+    }
 }
 
 impl PhysicalPlan for SimpleTableScan {
@@ -58,7 +73,7 @@ impl PhysicalPlan for SimpleTableScan {
     }
 
     fn output_partitioning(&self) -> Partitioning {
-        Partitioning::UnknownPartitioning(1)
+        Partitioning::UnknownPartitioning(self.streams.lock().unwrap().len())
     }
 
     fn children(&self) -> Vec<PhysicalPlanRef> {
@@ -71,10 +86,17 @@ impl PhysicalPlan for SimpleTableScan {
 
     fn execute(
         &self,
-        _partition: usize,
+        partition: usize,
         _context: Arc<TaskContext>,
     ) -> QueryResult<SendableRecordBatchStream> {
-        let mut stream = self.stream.lock().unwrap();
+        let mut streams = self.streams.lock().unwrap();
+        let num_partitions = streams.len();
+        let stream = streams
+            .get_mut(partition)
+            .context(query_error::PartitionOutOfRangeSnafu {
+                partition,
+                num_partitions,
+            })?;
         stream.take().context(query_error::ExecuteRepeatedlySnafu)
     }
 }
@@ -131,4 +153,55 @@ mod test {
             _ => unreachable!(),
         }
     }
+
+    #[tokio::test]
+    async fn test_simple_table_scan_partitions() {
+        let ctx = SessionContext::new();
+        let schema = Arc::new(Schema::new(vec![ColumnSchema::new(
+            "a",
+            ConcreteDataType::int32_datatype(),
+            false,
+        )]));
+
+        let batch1 = RecordBatch::new(
+            schema.clone(),
+            vec![Arc::new(Int32Vector::from_slice(&[1, 2])) as _],
+        )
+        .unwrap();
+        let batch2 = RecordBatch::new(
+            schema.clone(),
+            vec![Arc::new(Int32Vector::from_slice(&[3, 4, 5])) as _],
+        )
+        .unwrap();
+
+        let streams = vec![
+            RecordBatches::try_new(schema.clone(), vec![batch1.clone()])
+                .unwrap()
+                .as_stream(),
+            RecordBatches::try_new(schema.clone(), vec![batch2.clone()])
+                .unwrap()
+                .as_stream(),
+        ];
+        let scan = SimpleTableScan::with_partitions(streams).unwrap();
+        assert!(matches!(
+            scan.output_partitioning(),
+            Partitioning::UnknownPartitioning(2)
+        ));
+
+        let stream = scan.execute(1, ctx.task_ctx()).unwrap();
+        assert_eq!(vec![batch2], util::collect(stream).await.unwrap());
+        let stream = scan.execute(0, ctx.task_ctx()).unwrap();
+        assert_eq!(vec![batch1], util::collect(stream).await.unwrap());
+
+        assert!(scan.execute(2, ctx.task_ctx()).is_err());
+    }
+
+    #[test]
+    fn test_simple_table_scan_empty_partitions() {
+        let result = SimpleTableScan::with_partitions(Vec::new());
+        assert!(matches!(
+            result,
+            Err(query_error::Error::EmptyPartitions { .. })
+        ));
+    }
 }
@@ -30,7 +30,7 @@ use datanode::sql::SqlHandler;
 use datatypes::data_type::ConcreteDataType;
 use datatypes::schema::{ColumnSchema, SchemaBuilder};
 use frontend::instance::Instance as FeInstance;
-use object_store::backend::s3;
+use object_store::backend::{azblob, s3};
 use object_store::test_util::TempFolder;
 use object_store::ObjectStore;
 use once_cell::sync::OnceCell;
@@ -56,6 +56,7 @@ fn get_port() -> usize {
 
 pub enum StorageType {
     S3,
+    Azblob,
     File,
 }
 
@@ -72,6 +73,13 @@ impl StorageType {
                     false
                 }
             }
+            StorageType::Azblob => {
+                if let Ok(c) = env::var("GT_AZBLOB_CONTAINER") {
+                    !c.is_empty()
+                } else {
+                    false
+                }
+            }
         }
     }
 }
@@ -104,11 +112,49 @@ fn get_test_store_config(
                 secret_access_key: secret_key,
                 endpoint: None,
                 region: None,
+                cache_path: None,
+                cache_capacity: None,
+            };
+
+            let store = ObjectStore::new(accessor);
+
+            (
+                config,
+                Some(TempDirGuard::Remote(TempFolder::new(&store, "/"))),
+            )
+        }
+        StorageType::Azblob => {
+            let root = uuid::Uuid::new_v4().to_string();
+            let container = env::var("GT_AZBLOB_CONTAINER").unwrap();
+            let endpoint = env::var("GT_AZBLOB_ENDPOINT").unwrap();
+            let account_name = env::var("GT_AZBLOB_ACCOUNT_NAME").unwrap();
+            let account_key = env::var("GT_AZBLOB_ACCOUNT_KEY").unwrap();
+
+            let accessor = azblob::Builder::default()
+                .root(&root)
+                .container(&container)
+                .endpoint(&endpoint)
+                .account_name(&account_name)
+                .account_key(&account_key)
+                .build()
+                .unwrap();
+
+            let config = ObjectStoreConfig::Azblob {
+                container,
+                root,
+                account_name,
+                account_key,
+                endpoint: Some(endpoint),
+                cache_path: None,
+                cache_capacity: None,
             };
 
             let store = ObjectStore::new(accessor);
 
-            (config, Some(TempDirGuard::S3(TempFolder::new(&store, "/"))))
+            (
+                config,
+                Some(TempDirGuard::Remote(TempFolder::new(&store, "/"))),
+            )
         }
         StorageType::File => {
             let data_tmp_dir = TempDir::new(&format!("gt_data_{name}")).unwrap();
@@ -125,7 +171,7 @@ fn get_test_store_config(
 
 enum TempDirGuard {
     File(TempDir),
-    S3(TempFolder),
+    Remote(TempFolder),
 }
 
 /// Create a tmp dir(will be deleted once it goes out of scope.) and a default `DatanodeOptions`,
@@ -137,7 +183,7 @@ pub struct TestGuard {
 
 impl TestGuard {
     pub async fn remove_all(&mut self) {
-        if let Some(TempDirGuard::S3(mut guard)) = self.data_tmp_dir.take() {
+        if let Some(TempDirGuard::Remote(mut guard)) = self.data_tmp_dir.take() {
             guard.remove_all().await.unwrap();
         }
     }
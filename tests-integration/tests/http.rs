@@ -17,6 +17,7 @@ use axum_test_helper::TestClient;
 use common_error::status_code::StatusCode as ErrorCode;
 use serde_json::json;
 use servers::http::handler::HealthResponse;
+use servers::http::promql::{Labels, PromJsonResponse, PromResponse};
 use servers::http::{JsonOutput, JsonResponse};
 use tests_integration::test_util::{setup_test_app, setup_test_app_with_frontend, StorageType};
 
@@ -50,6 +51,7 @@ macro_rules! http_tests {
                 $service,
 
                 test_sql_api,
+                test_prometheus_query_api,
                 test_metrics_api,
                 test_scripts_api,
                 test_health_api,
@@ -261,6 +263,91 @@ pub async fn test_sql_api(store_type: StorageType) {
     guard.remove_all().await;
 }
 
+pub async fn test_prometheus_query_api(store_type: StorageType) {
+    common_telemetry::init_default_ut_logging();
+    let (app, mut guard) = setup_test_app_with_frontend(store_type, "prometheus_query_api").await;
+    let client = TestClient::new(app);
+
+    let res = client
+        .get("/v1/sql?sql=insert into demo values('host1', 66.6, 1024, 0)")
+        .send()
+        .await;
+    assert_eq!(res.status(), StatusCode::OK);
+
+    // instant query
+    let res = client
+        .get("/v1/prometheus/api/v1/query?query=demo&time=1")
+        .send()
+        .await;
+    assert_eq!(res.status(), StatusCode::OK);
+    let body = serde_json::from_str::<PromJsonResponse>(&res.text().await).unwrap();
+    let Some(PromResponse::PromData(data)) = body.data else {
+        unreachable!()
+    };
+    assert_eq!(data.result_type, "vector");
+    let result = data
+        .result
+        .into_iter()
+        .map(|series| (series.metric, series.value.unwrap().1))
+        .collect::<Vec<_>>();
+    let labels = |field: &str| -> Labels {
+        [
+            ("__name__", "demo"),
+            ("host", "host1"),
+            ("__field__", field),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect()
+    };
+    assert_eq!(
+        result,
+        vec![
+            (labels("cpu"), "66.6".to_string()),
+            (labels("memory"), "1024".to_string())
+        ]
+    );
+
+    // range query with an invalid step
+    let res = client
+        .get("/v1/prometheus/api/v1/query_range?query=demo&start=0&end=10&step=1x")
+        .send()
+        .await;
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+    let body = serde_json::from_str::<PromJsonResponse>(&res.text().await).unwrap();
+    assert_eq!(body.status, "error");
+
+    // labels of all metrics
+    let res = client
+        .get("/v1/prometheus/api/v1/labels?start=0&end=10")
+        .send()
+        .await;
+    assert_eq!(res.status(), StatusCode::OK);
+    let body = serde_json::from_str::<PromJsonResponse>(&res.text().await).unwrap();
+    assert_eq!(
+        body.data,
+        Some(PromResponse::Labels(vec![
+            "__field__".to_string(),
+            "__name__".to_string(),
+            "host".to_string()
+        ]))
+    );
+
+    // label values
+    let res = client
+        .get("/v1/prometheus/api/v1/label/host/values?match[]=demo&start=0&end=10")
+        .send()
+        .await;
+    assert_eq!(res.status(), StatusCode::OK);
+    let body = serde_json::from_str::<PromJsonResponse>(&res.text().await).unwrap();
+    assert_eq!(
+        body.data,
+        Some(PromResponse::Labels(vec!["host1".to_string()]))
+    );
+
+    guard.remove_all().await;
+}
+
 pub async fn test_metrics_api(store_type: StorageType) {
     common_telemetry::init_default_ut_logging();
     common_telemetry::init_default_metrics_recorder();
@@ -16,6 +16,7 @@
 mod grpc;
 #[macro_use]
 mod http;
+mod wal;
 
-grpc_tests!(File, S3);
-http_tests!(File, S3);
+grpc_tests!(File, S3, Azblob);
+http_tests!(File, S3, Azblob);
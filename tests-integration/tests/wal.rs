@@ -0,0 +1,80 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::env;
+use std::sync::Arc;
+
+use common_query::Output;
+use common_recordbatch::util;
+use datanode::datanode::WalProvider;
+use datanode::instance::Instance;
+use session::context::QueryContext;
+use tests_integration::test_util::{create_tmp_dir_and_datanode_opts, StorageType};
+
+async fn execute_sql(instance: &Instance, sql: &str) -> Output {
+    instance
+        .execute_sql(sql, Arc::new(QueryContext::new()))
+        .await
+        .unwrap()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_kafka_wal_replay() {
+    common_telemetry::init_default_ut_logging();
+    let _ = dotenv::dotenv();
+    let endpoints = match env::var("GT_KAFKA_ENDPOINTS") {
+        Ok(endpoints) if !endpoints.is_empty() => endpoints,
+        _ => return,
+    };
+
+    let (mut opts, _guard) = create_tmp_dir_and_datanode_opts(StorageType::File, "kafka_wal");
+    opts.wal.provider = WalProvider::Kafka;
+    opts.wal.broker_endpoints = endpoints.split(',').map(|s| s.trim().to_string()).collect();
+    // Isolates topics of different test runs.
+    opts.wal.topic_prefix = format!("greptimedb_wal_test_{}_", uuid::Uuid::new_v4());
+
+    {
+        let instance = Instance::new(&opts).await.unwrap();
+        instance.start().await.unwrap();
+        let output = execute_sql(
+            &instance,
+            "create table demo(host string, cpu double, ts timestamp time index, primary key(host))",
+        )
+        .await;
+        assert!(matches!(output, Output::AffectedRows(0)));
+        let output = execute_sql(
+            &instance,
+            "insert into demo values('host1', 1.0, 1000), ('host2', 2.0, 2000)",
+        )
+        .await;
+        assert!(matches!(output, Output::AffectedRows(2)));
+    }
+
+    // The rows are not flushed, so the new instance must replay them from Kafka.
+    let instance = Instance::new(&opts).await.unwrap();
+    instance.start().await.unwrap();
+    let output = execute_sql(&instance, "select host, cpu from demo order by host").await;
+    let Output::Stream(stream) = output else {
+        unreachable!()
+    };
+    let batches = util::collect_batches(stream).await.unwrap();
+    let expected = "\
++-------+-----+
+| host  | cpu |
++-------+-----+
+| host1 | 1.0 |
+| host2 | 2.0 |
++-------+-----+";
+    assert_eq!(expected, batches.pretty_print().unwrap());
+}